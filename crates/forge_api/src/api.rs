@@ -1,8 +1,9 @@
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 use anyhow::Result;
 use forge_app::{AppConfig, InitAuth, User};
-use forge_stream::MpscStream;
+use forge_stream::{MpscStream, Subscription};
 
 use crate::*;
 
@@ -22,6 +23,10 @@ pub trait API: Sync + Send {
     /// Executes a chat request and returns a stream of responses
     async fn chat(&self, chat: ChatRequest) -> Result<MpscStream<Result<ChatResponse>>>;
 
+    /// Subscribes to the responses of every chat executed through this API,
+    /// allowing multiple consumers to observe turns concurrently
+    fn subscribe(&self) -> Subscription<TurnEvent>;
+
     /// Returns the current environment
     fn environment(&self) -> Environment;
 
@@ -62,6 +67,9 @@ pub trait API: Sync + Send {
     /// Returns the conversation with the given ID
     async fn conversation(&self, conversation_id: &ConversationId) -> Result<Option<Conversation>>;
 
+    /// Returns the IDs of the stored conversations
+    async fn conversations(&self) -> Result<Vec<ConversationId>>;
+
     /// Compacts the context of the main agent for the given conversation and
     /// persists it. Returns metrics about the compaction (original vs.
     /// compacted tokens and messages).
@@ -70,6 +78,88 @@ pub trait API: Sync + Send {
         conversation_id: &ConversationId,
     ) -> Result<CompactionResult>;
 
+    /// Returns the exact request that would be sent to the provider for the
+    /// given conversation along with a summary of its token usage
+    async fn inspect_context(
+        &self,
+        conversation_id: &ConversationId,
+    ) -> Result<Option<ContextInspection>>;
+
+    /// Restores the workspace to its state before the turn at index `turn` of
+    /// the conversation and drops that turn and the later ones from its
+    /// history
+    async fn restore_turn(
+        &self,
+        conversation_id: &ConversationId,
+        turn: usize,
+    ) -> Result<RestorePlan>;
+
+    /// Saves the context of the conversation and the content of the files it
+    /// touched as a named checkpoint
+    async fn save_checkpoint(
+        &self,
+        conversation_id: &ConversationId,
+        name: &str,
+    ) -> Result<Checkpoint>;
+
+    /// Brings the conversation and the files it touched back to a named
+    /// checkpoint
+    async fn restore_checkpoint(
+        &self,
+        conversation_id: &ConversationId,
+        name: &str,
+    ) -> Result<CheckpointRestore>;
+
+    /// Pins a message or a file so that it's sent with every request of the
+    /// conversation and never compacted. Returns `false` if it was already
+    /// pinned.
+    async fn pin(&self, conversation_id: &ConversationId, pin: Pin) -> Result<bool>;
+
+    /// Removes the pin at the given position of the conversation, starting at
+    /// 1
+    async fn unpin(&self, conversation_id: &ConversationId, position: usize) -> Result<Pin>;
+
+    /// Removes the messages or the attachments matching the target from the
+    /// context of the conversation, leaving an annotation of their removal
+    async fn drop_context(
+        &self,
+        conversation_id: &ConversationId,
+        target: &DropTarget,
+    ) -> Result<Vec<DroppedMessage>>;
+
+    /// Removes the file snapshots exceeding the retention limits and returns
+    /// the number of removed snapshots
+    async fn purge_snapshots(&self, retention: &SnapshotRetention) -> Result<usize>;
+
+    /// Cleans up after a conversation that has ended, removing the scratch
+    /// directory its tools used for intermediate artifacts and unsubscribing
+    /// from the MCP resources it followed
+    async fn end_conversation(&self, conversation_id: &ConversationId) -> Result<()>;
+
+    /// Reads the workspace journal entries written after `offset`. Passing the
+    /// offset of the returned page to the next call tails the journal.
+    async fn read_journal(&self, offset: u64) -> Result<JournalPage>;
+
+    /// Counts the entries of the workspace with a bounded scan. Large
+    /// workspaces are walked in sparse mode.
+    async fn workspace_size(&self) -> Result<WorkspaceSize>;
+
+    /// Returns the usage of the providers accounted on this machine, across
+    /// workspaces
+    async fn usage(&self) -> Result<UsageLedger>;
+
+    /// Returns the statistics of the tool calls recorded on this machine,
+    /// across workspaces
+    async fn tool_stats(&self) -> Result<ToolStatsLedger>;
+
+    /// Lists the commits of the workspace within the revision range, most
+    /// recent first
+    async fn git_log(&self, range: &str) -> Result<Vec<GitCommit>>;
+
+    /// Writes a file of the workspace, capturing a snapshot of its previous
+    /// content so that the change can be undone
+    async fn write_file(&self, path: &Path, content: String) -> Result<()>;
+
     /// Executes a shell command using the shell tool infrastructure
     async fn execute_shell_command(
         &self,
@@ -92,10 +182,40 @@ pub trait API: Sync + Send {
     /// project directory
     async fn write_mcp_config(&self, scope: &Scope, config: &McpConfig) -> Result<()>;
 
+    /// Prompts offered by the configured MCP servers, run as slash commands
+    async fn mcp_prompts(&self) -> Result<Vec<McpPrompt>>;
+
+    /// Resources offered by the configured MCP servers, attached to a message
+    /// as `@mcp:server/uri`
+    async fn mcp_resources(&self) -> Result<Vec<McpResource>>;
+
+    /// Text of the messages of the MCP prompt with its arguments filled in
+    async fn get_mcp_prompt(
+        &self,
+        name: &str,
+        arguments: BTreeMap<String, String>,
+    ) -> Result<String>;
+
     async fn init_login(&self) -> Result<InitAuth>;
     async fn login(&self, auth: &InitAuth) -> Result<()>;
     async fn logout(&self) -> anyhow::Result<()>;
     async fn provider(&self) -> anyhow::Result<Provider>;
     async fn app_config(&self) -> anyhow::Result<AppConfig>;
     async fn user_info(&self) -> anyhow::Result<Option<User>>;
+
+    /// Reads the settings remembered for the current workspace
+    async fn workspace_settings(&self) -> Result<WorkspaceSettings>;
+
+    /// Updates the settings remembered for the current workspace using the
+    /// provided closure and returns the updated settings
+    async fn update_workspace_settings<F>(&self, f: F) -> Result<WorkspaceSettings>
+    where
+        F: FnOnce(&mut WorkspaceSettings) + Send;
+
+    /// Reads the learnings remembered for the current workspace
+    async fn project_memory(&self) -> Result<ProjectMemory>;
+
+    /// Adds the learnings that aren't remembered yet to the memory of the
+    /// current workspace and returns the ones that were added
+    async fn remember(&self, learnings: ProjectMemory) -> Result<ProjectMemory>;
 }
@@ -0,0 +1,204 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::Result;
+use derive_setters::Setters;
+use forge_domain::*;
+use forge_infra::ForgeInfra;
+use forge_services::ForgeServices;
+use forge_stream::MpscStream;
+
+use crate::{ForgeAPI, API};
+
+/// Builder for [`ForgeClient`].
+///
+/// Every option is optional: by default the client is rooted at the process
+/// working directory, detects the provider from the app config or environment
+/// and exposes every tool.
+#[derive(Debug, Clone, Default, Setters)]
+#[setters(strip_option, into)]
+pub struct ForgeClientBuilder {
+    /// Directory the agent operates in. Defaults to the current directory.
+    workspace: Option<PathBuf>,
+
+    /// Run shell commands in restricted mode (rbash).
+    restricted: bool,
+
+    /// Pins the provider instead of detecting it.
+    provider: Option<Provider>,
+
+    /// Allow-list of tools the agents may use. All tools are available when
+    /// unset.
+    tools: Option<Vec<ToolName>>,
+}
+
+impl ForgeClientBuilder {
+    /// Builds the client, wiring up the infrastructure and services.
+    pub fn build(self) -> ForgeClient {
+        let infra = Arc::new(match &self.workspace {
+            Some(workspace) => ForgeInfra::with_cwd(self.restricted, workspace.clone()),
+            None => ForgeInfra::new(self.restricted),
+        });
+
+        let mut services = ForgeServices::new(infra.clone());
+        if let Some(provider) = self.provider {
+            services = services.provider(provider);
+        }
+
+        ForgeClient {
+            api: ForgeAPI::new(Arc::new(services), infra),
+            workspace: self.workspace,
+            tools: self.tools,
+        }
+    }
+}
+
+/// Entry point for embedding the forge agent engine in other Rust
+/// applications.
+///
+/// ```no_run
+/// # async fn run() -> anyhow::Result<()> {
+/// use forge_api::{Event, ForgeClient};
+///
+/// let client = ForgeClient::builder().workspace("/path/to/project").build();
+/// let conversation = client.create_conversation().await?;
+/// let stream = client
+///     .chat_stream(&conversation.id, Event::new("forge/user_task_init", Some("hello")))
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct ForgeClient {
+    api: ForgeAPI<ForgeServices<ForgeInfra>, ForgeInfra>,
+    workspace: Option<PathBuf>,
+    tools: Option<Vec<ToolName>>,
+}
+
+impl ForgeClient {
+    /// Creates a builder with default options.
+    pub fn builder() -> ForgeClientBuilder {
+        ForgeClientBuilder::default()
+    }
+
+    /// Sends an event to a conversation and returns the stream of responses.
+    pub async fn chat_stream(
+        &self,
+        conversation_id: &ConversationId,
+        event: Event,
+    ) -> Result<MpscStream<Result<ChatResponse>>> {
+        self.api
+            .chat(ChatRequest::new(event, conversation_id.clone()))
+            .await
+    }
+
+    /// Lists the models offered by the configured provider.
+    pub async fn list_models(&self) -> Result<Vec<Model>> {
+        self.api.models().await
+    }
+
+    /// Lists the tools available to the agents, honouring the tool filter.
+    pub async fn list_tools(&self) -> Result<Vec<ToolDefinition>> {
+        let tools = self.api.tools().await?;
+        Ok(match &self.tools {
+            Some(allowed) => tools
+                .into_iter()
+                .filter(|tool| allowed.contains(&tool.name))
+                .collect(),
+            None => tools,
+        })
+    }
+
+    /// Creates a new conversation from the workspace workflow.
+    pub async fn create_conversation(&self) -> Result<Conversation> {
+        let path = self
+            .workspace
+            .as_ref()
+            .map(|workspace| workspace.join("forge.yaml"));
+        let workflow = self.api.read_merged(path.as_deref()).await?;
+        self.create_conversation_with(workflow).await
+    }
+
+    /// Creates a new conversation from an explicit workflow.
+    pub async fn create_conversation_with(&self, workflow: Workflow) -> Result<Conversation> {
+        self.api
+            .init_conversation(filter_workflow(workflow, self.tools.as_deref()))
+            .await
+    }
+
+    /// Finds a conversation by its id.
+    pub async fn conversation(&self, id: &ConversationId) -> Result<Option<Conversation>> {
+        self.api.conversation(id).await
+    }
+
+    /// Creates or updates a conversation.
+    pub async fn upsert_conversation(&self, conversation: Conversation) -> Result<()> {
+        self.api.upsert_conversation(conversation).await
+    }
+
+    /// Compacts the context of a conversation.
+    pub async fn compact_conversation(&self, id: &ConversationId) -> Result<CompactionResult> {
+        self.api.compact_conversation(id).await
+    }
+
+    /// Access to the full [`API`] for operations not covered by the client.
+    pub fn api(&self) -> &impl API {
+        &self.api
+    }
+}
+
+/// Restricts the tools of every agent in the workflow to the allow-list.
+fn filter_workflow(mut workflow: Workflow, allowed: Option<&[ToolName]>) -> Workflow {
+    if let Some(allowed) = allowed {
+        for agent in workflow.agents.iter_mut() {
+            if let Some(tools) = agent.tools.as_mut() {
+                tools.retain(|tool| allowed.contains(tool));
+            }
+        }
+    }
+    workflow
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn workflow_fixture() -> Workflow {
+        let mut workflow = Workflow::new();
+        workflow.agents = vec![Agent::new(AgentId::new("forge")).tools(vec![
+            ToolName::new("forge_tool_fs_read"),
+            ToolName::new("forge_tool_fs_create"),
+            ToolName::new("forge_tool_process_shell"),
+        ])];
+        workflow
+    }
+
+    #[test]
+    fn test_filter_workflow_retains_allowed_tools() {
+        let fixture = workflow_fixture();
+        let allowed = vec![
+            ToolName::new("forge_tool_fs_read"),
+            ToolName::new("forge_tool_net_fetch"),
+        ];
+
+        let actual = filter_workflow(fixture, Some(&allowed)).agents[0]
+            .tools
+            .clone();
+
+        let expected = Some(vec![ToolName::new("forge_tool_fs_read")]);
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_filter_workflow_without_filter() {
+        let fixture = workflow_fixture();
+
+        let actual = filter_workflow(fixture.clone(), None).agents[0]
+            .tools
+            .clone();
+
+        let expected = fixture.agents[0].tools.clone();
+        assert_eq!(actual, expected);
+    }
+}
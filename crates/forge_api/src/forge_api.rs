@@ -1,27 +1,35 @@
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use anyhow::{Context, Result};
 use forge_app::{
     AppConfig, AppConfigService, AuthService, ConversationService, EnvironmentService,
-    FileDiscoveryService, ForgeApp, InitAuth, McpConfigManager, ProviderRegistry, ProviderService,
-    Services, User, Walker, WorkflowService,
+    FileDiscoveryService, ForgeApp, FsCreateService, FsSnapshotService, GitCommit, GitService,
+    InitAuth, JournalService, McpConfigManager, McpService, ProjectMemoryService, ProviderRegistry,
+    ProviderService, Services, ToolStatsService, User, Walker, WorkflowService,
+    WorkspaceSettingsService, WorkspaceSize,
 };
 use forge_domain::*;
 use forge_infra::ForgeInfra;
 use forge_services::{CommandInfra, ForgeServices};
-use forge_stream::MpscStream;
+use forge_stream::{EventBus, MpscStream, Subscription};
+use futures::StreamExt;
 
 use crate::API;
 
+/// Number of commits listed at most for a revision range
+const MAX_RELEASE_COMMITS: u64 = 1000;
+
 pub struct ForgeAPI<S, F> {
     services: Arc<S>,
     infra: Arc<F>,
+    events: EventBus<TurnEvent>,
 }
 
 impl<A, F> ForgeAPI<A, F> {
     pub fn new(services: Arc<A>, infra: Arc<F>) -> Self {
-        Self { services, infra }
+        Self { services, infra, events: EventBus::default() }
     }
 }
 
@@ -33,6 +41,17 @@ impl ForgeAPI<ForgeServices<ForgeInfra>, ForgeInfra> {
     }
 }
 
+impl<A: Services, F> ForgeAPI<A, F> {
+    /// Records a conversation event in the workspace journal. Failures are
+    /// only logged as the journal is meant for external tools.
+    async fn journal(&self, conversation_id: ConversationId, event: JournalEvent) {
+        let entry = JournalEntry::new(conversation_id, event);
+        if let Err(error) = self.services.record_journal(vec![entry]).await {
+            tracing::warn!(error = ?error, "Failed to record the journal");
+        }
+    }
+}
+
 #[async_trait::async_trait]
 impl<A: Services, F: CommandInfra> API for ForgeAPI<A, F> {
     async fn discover(&self) -> Result<Vec<File>> {
@@ -41,6 +60,10 @@ impl<A: Services, F: CommandInfra> API for ForgeAPI<A, F> {
         self.services.collect_files(config).await
     }
 
+    async fn workspace_size(&self) -> Result<WorkspaceSize> {
+        ForgeApp::new(self.services.clone()).workspace_size().await
+    }
+
     async fn tools(&self) -> anyhow::Result<Vec<ToolDefinition>> {
         let forge_app = ForgeApp::new(self.services.clone());
         forge_app.list_tools().await
@@ -58,15 +81,36 @@ impl<A: Services, F: CommandInfra> API for ForgeAPI<A, F> {
         chat: ChatRequest,
     ) -> anyhow::Result<MpscStream<Result<ChatResponse, anyhow::Error>>> {
         // Create a ForgeApp instance and delegate the chat logic to it
+        let conversation_id = chat.conversation_id;
         let forge_app = ForgeApp::new(self.services.clone());
-        forge_app.chat(chat).await
+        let mut stream = forge_app.chat(chat).await?;
+
+        // Forward the responses to the caller while publishing them on the bus
+        let events = self.events.clone();
+        Ok(MpscStream::spawn(move |tx| async move {
+            while let Some(message) = stream.next().await {
+                if let Ok(response) = &message {
+                    events.publish(TurnEvent::new(conversation_id, response.clone()));
+                }
+                if tx.send(message).await.is_err() {
+                    break;
+                }
+            }
+        }))
+    }
+
+    fn subscribe(&self) -> Subscription<TurnEvent> {
+        self.events.subscribe()
     }
 
     async fn init_conversation<W: Into<Workflow> + Send + Sync>(
         &self,
         workflow: W,
     ) -> anyhow::Result<Conversation> {
-        self.services.create_conversation(workflow.into()).await
+        let conversation = self.services.create_conversation(workflow.into()).await?;
+        self.journal(conversation.id, JournalEvent::ConversationStarted)
+            .await;
+        Ok(conversation)
     }
 
     async fn upsert_conversation(&self, conversation: Conversation) -> anyhow::Result<()> {
@@ -81,6 +125,109 @@ impl<A: Services, F: CommandInfra> API for ForgeAPI<A, F> {
         forge_app.compact_conversation(conversation_id).await
     }
 
+    async fn inspect_context(
+        &self,
+        conversation_id: &ConversationId,
+    ) -> anyhow::Result<Option<ContextInspection>> {
+        let forge_app = ForgeApp::new(self.services.clone());
+        forge_app.inspect_context(conversation_id).await
+    }
+
+    async fn restore_turn(
+        &self,
+        conversation_id: &ConversationId,
+        turn: usize,
+    ) -> anyhow::Result<RestorePlan> {
+        let forge_app = ForgeApp::new(self.services.clone());
+        forge_app.restore_turn(conversation_id, turn).await
+    }
+
+    async fn save_checkpoint(
+        &self,
+        conversation_id: &ConversationId,
+        name: &str,
+    ) -> anyhow::Result<Checkpoint> {
+        let forge_app = ForgeApp::new(self.services.clone());
+        forge_app.save_checkpoint(conversation_id, name).await
+    }
+
+    async fn restore_checkpoint(
+        &self,
+        conversation_id: &ConversationId,
+        name: &str,
+    ) -> anyhow::Result<CheckpointRestore> {
+        let forge_app = ForgeApp::new(self.services.clone());
+        forge_app.restore_checkpoint(conversation_id, name).await
+    }
+
+    async fn pin(&self, conversation_id: &ConversationId, pin: Pin) -> anyhow::Result<bool> {
+        let forge_app = ForgeApp::new(self.services.clone());
+        forge_app.pin(conversation_id, pin).await
+    }
+
+    async fn unpin(
+        &self,
+        conversation_id: &ConversationId,
+        position: usize,
+    ) -> anyhow::Result<Pin> {
+        let forge_app = ForgeApp::new(self.services.clone());
+        forge_app.unpin(conversation_id, position).await
+    }
+
+    async fn drop_context(
+        &self,
+        conversation_id: &ConversationId,
+        target: &DropTarget,
+    ) -> anyhow::Result<Vec<DroppedMessage>> {
+        let forge_app = ForgeApp::new(self.services.clone());
+        forge_app.drop_context(conversation_id, target).await
+    }
+
+    async fn purge_snapshots(&self, retention: &SnapshotRetention) -> anyhow::Result<usize> {
+        self.services.purge_snapshots(retention).await
+    }
+
+    async fn end_conversation(&self, conversation_id: &ConversationId) -> anyhow::Result<()> {
+        let path = self
+            .services
+            .get_environment()
+            .scratch_path(conversation_id);
+        if tokio::fs::try_exists(&path).await? {
+            tokio::fs::remove_dir_all(&path)
+                .await
+                .with_context(|| format!("Failed to remove {}", path.display()))?;
+        }
+        self.services.unsubscribe_resources(conversation_id).await?;
+        self.journal(*conversation_id, JournalEvent::ConversationEnded)
+            .await;
+        Ok(())
+    }
+
+    async fn read_journal(&self, offset: u64) -> anyhow::Result<JournalPage> {
+        self.services.read_journal(offset).await
+    }
+
+    async fn usage(&self) -> anyhow::Result<UsageLedger> {
+        self.services.usage().await
+    }
+
+    async fn tool_stats(&self) -> anyhow::Result<ToolStatsLedger> {
+        self.services.tool_stats().await
+    }
+
+    async fn git_log(&self, range: &str) -> anyhow::Result<Vec<GitCommit>> {
+        self.services
+            .log(None, Some(range.to_string()), Some(MAX_RELEASE_COMMITS))
+            .await
+    }
+
+    async fn write_file(&self, path: &Path, content: String) -> anyhow::Result<()> {
+        self.services
+            .create(path.display().to_string(), content, true, true, true)
+            .await?;
+        Ok(())
+    }
+
     fn environment(&self) -> Environment {
         self.services.get_environment().clone()
     }
@@ -111,13 +258,17 @@ impl<A: Services, F: CommandInfra> API for ForgeAPI<A, F> {
         self.services.find(conversation_id).await
     }
 
+    async fn conversations(&self) -> anyhow::Result<Vec<ConversationId>> {
+        self.services.list_conversations().await
+    }
+
     async fn execute_shell_command(
         &self,
         command: &str,
         working_dir: PathBuf,
     ) -> anyhow::Result<CommandOutput> {
         self.infra
-            .execute_command(command.to_string(), working_dir)
+            .execute_command(command.to_string(), working_dir, Default::default())
             .await
     }
     async fn read_mcp_config(&self) -> Result<McpConfig> {
@@ -134,6 +285,22 @@ impl<A: Services, F: CommandInfra> API for ForgeAPI<A, F> {
             .map_err(|e| anyhow::anyhow!(e))
     }
 
+    async fn mcp_prompts(&self) -> Result<Vec<McpPrompt>> {
+        self.services.list_prompts().await
+    }
+
+    async fn mcp_resources(&self) -> Result<Vec<McpResource>> {
+        self.services.list_resources().await
+    }
+
+    async fn get_mcp_prompt(
+        &self,
+        name: &str,
+        arguments: BTreeMap<String, String>,
+    ) -> Result<String> {
+        self.services.get_prompt(name, arguments).await
+    }
+
     async fn execute_shell_command_raw(
         &self,
         command: &str,
@@ -172,4 +339,31 @@ impl<A: Services, F: CommandInfra> API for ForgeAPI<A, F> {
         }
         Ok(None)
     }
+
+    async fn workspace_settings(&self) -> Result<WorkspaceSettings> {
+        self.services.read_workspace_settings().await
+    }
+
+    async fn update_workspace_settings<T>(&self, f: T) -> Result<WorkspaceSettings>
+    where
+        T: FnOnce(&mut WorkspaceSettings) + Send,
+    {
+        let mut settings = self.services.read_workspace_settings().await?;
+        f(&mut settings);
+        self.services.write_workspace_settings(&settings).await?;
+        Ok(settings)
+    }
+
+    async fn project_memory(&self) -> Result<ProjectMemory> {
+        self.services.read_project_memory().await
+    }
+
+    async fn remember(&self, learnings: ProjectMemory) -> Result<ProjectMemory> {
+        let mut memory = self.services.read_project_memory().await?;
+        let added = memory.merge(learnings);
+        if !added.is_empty() {
+            self.services.write_project_memory(&memory).await?;
+        }
+        Ok(added)
+    }
 }
@@ -1,7 +1,10 @@
 mod api;
+mod client;
 mod forge_api;
 
 pub use api::*;
+pub use client::*;
 pub use forge_api::*;
-pub use forge_app::{AppConfig, LoginInfo};
+pub use forge_app::{AppConfig, GitCommit, LoginInfo, WorkspaceSize};
 pub use forge_domain::*;
+pub use forge_stream::Subscription;
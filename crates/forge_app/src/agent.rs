@@ -1,14 +1,18 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use forge_domain::{
-    Agent, ChatCompletionMessage, Context, Conversation, ModelId, ResultStream, ToolCallContext,
-    ToolCallFull, ToolResult,
+    Agent, ChatCompletionMessage, CommandOutput, Context, Conversation, ConversationId,
+    JournalEntry, McpResourceUpdate, ModelId, ModerationRequest, ModerationVerdict, ResultStream,
+    Runnable, ToolCallContext, ToolCallFull, ToolCallSample, ToolResult,
 };
 
 use crate::tool_registry::ToolRegistry;
 use crate::{
-    AppConfigService, ConversationService, ProviderRegistry, ProviderService, Services,
-    TemplateService,
+    AppConfigService, ConversationService, JournalService, McpService, ModerationService,
+    ProviderRegistry, ProviderService, Services, ShellService, TaskDiscoveryService,
+    TemplateService, ToolStatsService,
 };
 
 /// Agent service trait that provides core chat and tool call functionality.
@@ -39,6 +43,38 @@ pub trait AgentService: Send + Sync + 'static {
 
     /// Synchronize the on-going conversation
     async fn update(&self, conversation: Conversation) -> anyhow::Result<()>;
+
+    /// Record workspace events in the journal
+    async fn journal(&self, entries: Vec<JournalEntry>) -> anyhow::Result<()>;
+
+    /// Add the outcome of a tool call to the statistics of the tools
+    async fn record_tool_stats(&self, sample: ToolCallSample) -> anyhow::Result<()>;
+
+    /// Ask a moderation service for a verdict on a response
+    async fn moderate(
+        &self,
+        endpoint: &str,
+        request: &ModerationRequest<'_>,
+    ) -> anyhow::Result<ModerationVerdict>;
+
+    /// List the targets of the build tools of the project in `path`
+    async fn discover_tasks(&self, path: PathBuf) -> anyhow::Result<Vec<Runnable>>;
+
+    /// Run a shell command outside of a tool call, e.g. the tests after an
+    /// edit
+    async fn run_command(
+        &self,
+        command: String,
+        cwd: PathBuf,
+        env: BTreeMap<String, String>,
+    ) -> anyhow::Result<CommandOutput>;
+
+    /// Contents of the MCP resources subscribed for the conversation that
+    /// changed since the last call
+    async fn take_resource_updates(
+        &self,
+        conversation_id: &ConversationId,
+    ) -> Vec<McpResourceUpdate>;
 }
 
 /// Blanket implementation of AgentService for any type that implements Services
@@ -75,4 +111,41 @@ impl<T: Services> AgentService for T {
     async fn update(&self, conversation: Conversation) -> anyhow::Result<()> {
         self.upsert(conversation).await
     }
+
+    async fn journal(&self, entries: Vec<JournalEntry>) -> anyhow::Result<()> {
+        self.record_journal(entries).await
+    }
+
+    async fn record_tool_stats(&self, sample: ToolCallSample) -> anyhow::Result<()> {
+        ToolStatsService::record_tool_stats(self, sample).await
+    }
+
+    async fn moderate(
+        &self,
+        endpoint: &str,
+        request: &ModerationRequest<'_>,
+    ) -> anyhow::Result<ModerationVerdict> {
+        ModerationService::moderate(self, endpoint, request).await
+    }
+
+    async fn discover_tasks(&self, path: PathBuf) -> anyhow::Result<Vec<Runnable>> {
+        TaskDiscoveryService::discover_tasks(self, path).await
+    }
+
+    async fn run_command(
+        &self,
+        command: String,
+        cwd: PathBuf,
+        env: BTreeMap<String, String>,
+    ) -> anyhow::Result<CommandOutput> {
+        let output = self.execute(command, cwd, false, env, None).await?;
+        Ok(output.output)
+    }
+
+    async fn take_resource_updates(
+        &self,
+        conversation_id: &ConversationId,
+    ) -> Vec<McpResourceUpdate> {
+        McpService::take_resource_updates(self, conversation_id).await
+    }
 }
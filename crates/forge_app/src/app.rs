@@ -1,21 +1,32 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::{Context, Result};
 use chrono::Local;
 use forge_domain::*;
 use forge_stream::MpscStream;
+use futures::StreamExt;
 
+use crate::agent::AgentService;
 use crate::authenticator::Authenticator;
-use crate::orch::Orchestrator;
+use crate::coalesce::TextCoalescer;
+use crate::orch::{Orchestrator, reasoning_support, request_transformers, tool_support};
 use crate::services::TemplateService;
 use crate::tool_registry::ToolRegistry;
 use crate::{
-    AppConfigService, AttachmentService, ConversationService, EnvironmentService,
-    FileDiscoveryService, InitAuth, ProviderRegistry, ProviderService, Services, Walker,
-    WorkflowService,
+    AppConfigService, AttachmentService, Content, ConversationService, EnvironmentService,
+    FileDiscoveryService, FsCheckpointService, FsMetaService, FsReadService, FsRemoveService,
+    FsSnapshotService, InitAuth, MAX_WORKSPACE_ENTRIES, ProjectMemoryService, ProviderRegistry,
+    ProviderService, ResourceGovernorService, Services, Walker, WorkflowService, WorkspaceSize,
 };
 
+/// System prompt of the requests answered without tools
+const ASK_SYSTEM_PROMPT: &str = "You are Forge, answering a quick question of a software engineer in \
+     their terminal. Answer directly and concisely in Markdown. You can't read files nor run \
+     commands here: when the question needs them, say so and suggest switching back to the agent \
+     mode.";
+
 /// ForgeApp handles the core chat functionality by orchestrating various
 /// services. It encapsulates the complex logic previously contained in the
 /// ForgeAPI chat method.
@@ -49,6 +60,10 @@ impl<S: Services> ForgeApp<S> {
             .await
             .unwrap_or_default()
             .expect("conversation for the request should've been created at this point.");
+        if chat.ask {
+            return self.ask(&conversation, &chat.event).await;
+        }
+        let pins = conversation.pins.clone();
 
         // Get tool definitions and models
         let tool_definitions = self.tool_registry.list().await?;
@@ -64,9 +79,20 @@ impl<S: Services> ForgeApp<S> {
         let max_depth = workflow.max_walker_depth;
         let environment = services.get_environment();
 
-        let mut walker = Walker::conservative().cwd(environment.cwd.clone());
+        // Gigantic workspaces, e.g. a home directory, are walked in sparse mode
+        let size = self.workspace_size().await?;
+        let mut walker = if size.is_large() {
+            tracing::warn!(
+                entries = size.entries,
+                "Walking a large workspace in sparse mode"
+            );
+            Walker::sparse()
+        } else {
+            Walker::conservative()
+        }
+        .cwd(environment.cwd.clone());
 
-        if let Some(depth) = max_depth {
+        if let Some(depth) = max_depth.filter(|_| !size.is_large()) {
             walker = walker.max_depth(depth);
         };
 
@@ -76,6 +102,7 @@ impl<S: Services> ForgeApp<S> {
             .into_iter()
             .map(|f| f.path)
             .collect::<Vec<_>>();
+        let files = services.rank_files(files).await?;
 
         // Register templates using workflow path or environment fallback
         let template_path = workflow
@@ -88,12 +115,14 @@ impl<S: Services> ForgeApp<S> {
 
         // Always try to get attachments and overwrite them
         if let Some(value) = chat.event.value.as_ref() {
-            let attachments = services.attachments(&value.to_string()).await?;
+            let attachments = services
+                .attachments(&chat.conversation_id, &value.to_string())
+                .await?;
             chat.event = chat.event.attachments(attachments);
         }
 
         // Create the orchestrator with all necessary dependencies
-        let orch = Orchestrator::new(
+        let mut orch = Orchestrator::new(
             services.clone(),
             environment.clone(),
             conversation,
@@ -101,7 +130,23 @@ impl<S: Services> ForgeApp<S> {
         )
         .tool_definitions(tool_definitions)
         .models(models)
-        .files(files);
+        .files(files)
+        .governor(services.governor());
+
+        if let Some(tool_choice) = chat.tool_choice.clone() {
+            orch = orch.tool_choice(tool_choice);
+        }
+
+        let pins = self.pinned_contents(&pins).await;
+        if !pins.is_empty() {
+            orch = orch.pins(pins);
+        }
+
+        // A memory that can't be read must not prevent the chat
+        let memory = services.read_project_memory().await.unwrap_or_default();
+        if !memory.is_empty() {
+            orch = orch.memory(memory);
+        }
 
         // Create and return the stream
         let stream = MpscStream::spawn(
@@ -131,6 +176,95 @@ impl<S: Services> ForgeApp<S> {
         Ok(stream)
     }
 
+    /// Answers the event with a single response of the model of the
+    /// conversation, streamed as it's generated. No tool is offered and the
+    /// conversation is left unchanged, which makes quick questions faster and
+    /// cheaper than a turn of the agent.
+    async fn ask(
+        &self,
+        conversation: &Conversation,
+        event: &Event,
+    ) -> Result<MpscStream<Result<ChatResponse, anyhow::Error>>> {
+        let services = self.services.clone();
+        let model = conversation.main_model()?;
+        let question = match event.value.as_ref() {
+            Some(serde_json::Value::String(question)) => question.clone(),
+            Some(value) => value.to_string(),
+            None => String::new(),
+        };
+        let context = Context::default()
+            .add_message(ContextMessage::system(ASK_SYSTEM_PROMPT))
+            .add_message(ContextMessage::user(question, Some(model.clone())));
+
+        let window = services
+            .get_environment()
+            .text_coalesce_ms
+            .map(Duration::from_millis);
+
+        let stream = MpscStream::spawn(
+            |tx: tokio::sync::mpsc::Sender<Result<ChatResponse, anyhow::Error>>| async move {
+                let result = async {
+                    let delta = |text| ChatResponse::Text { text, is_complete: false, is_md: true };
+                    let mut response = services.chat_agent(&model, context).await?;
+                    let mut deltas = TextCoalescer::new(window);
+                    let mut answer = String::new();
+                    let mut usage = None;
+                    loop {
+                        let message = match deltas.deadline() {
+                            Some(deadline) => tokio::select! {
+                                message = response.next() => message,
+                                _ = tokio::time::sleep_until(deadline) => {
+                                    if let Some(text) = deltas.flush() {
+                                        tx.send(Ok(delta(text))).await?;
+                                    }
+                                    continue;
+                                }
+                            },
+                            None => response.next().await,
+                        };
+                        let Some(message) = message else {
+                            break;
+                        };
+                        let message = message?;
+                        usage = message.usage.or(usage);
+                        let Some(content) = message.content else {
+                            continue;
+                        };
+                        if content.is_empty() {
+                            continue;
+                        }
+                        answer.push_str(content.as_str());
+                        if let Some(text) = deltas.push(content.as_str()) {
+                            tx.send(Ok(delta(text))).await?;
+                        }
+                    }
+                    if let Some(text) = deltas.flush() {
+                        tx.send(Ok(delta(text))).await?;
+                    }
+                    if let Some(usage) = usage {
+                        tx.send(Ok(ChatResponse::Usage(usage))).await?;
+                    }
+                    tx.send(Ok(ChatResponse::Text {
+                        text: answer,
+                        is_complete: true,
+                        is_md: true,
+                    }))
+                    .await?;
+                    anyhow::Ok(())
+                }
+                .await;
+
+                if let Err(error) = result
+                    && let Err(e) = tx.send(Err(error)).await
+                {
+                    tracing::error!("Failed to send error to stream: {}", e);
+                }
+            },
+        );
+
+        Ok(stream)
+    }
+
     /// Compacts the context of the main agent for the given conversation and
     /// persists it. Returns metrics about the compaction (original vs.
     /// compacted tokens and messages).
@@ -169,7 +303,8 @@ impl<S: Services> ForgeApp<S> {
             .clone();
 
         // Apply compaction using the Compactor
-        let compactor = Compactor::new(self.services.clone());
+        let compactor = Compactor::new(self.services.clone())
+            .response_language(conversation.response_language.clone());
 
         let compacted_context = compactor.compact(&agent, context, true).await?;
 
@@ -192,6 +327,293 @@ impl<S: Services> ForgeApp<S> {
         ))
     }
 
+    /// Builds the exact request that would be sent to the provider for the
+    /// current context of the conversation. Returns `None` if the conversation
+    /// has no context yet.
+    pub async fn inspect_context(
+        &self,
+        conversation_id: &ConversationId,
+    ) -> Result<Option<ContextInspection>> {
+        let conversation = self
+            .services
+            .find(conversation_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Conversation not found: {}", conversation_id))?;
+
+        let Some(context) = conversation.context.clone() else {
+            return Ok(None);
+        };
+
+        let model = conversation.main_model()?;
+        let config = self.services.read_app_config().await.unwrap_or_default();
+        let provider = self
+            .services
+            .get_provider(config)
+            .await
+            .context("Failed to get provider")?;
+        // The same transformations as the requests of the agent, so that the
+        // context shown is the one sent
+        let agent = conversation.get_agent(&AgentId::default())?;
+        let models = self
+            .services
+            .models(provider.clone())
+            .await
+            .unwrap_or_default();
+        let context = request_transformers(
+            tool_support(agent, &models)?,
+            reasoning_support(agent, &models)?,
+        )
+        .transform(context);
+        let payload = self
+            .services
+            .payload(&model, context.clone(), provider)
+            .await?;
+
+        Ok(Some(ContextInspection {
+            pins: conversation.pins.clone(),
+            ..ContextInspection::new(model, &context, payload)
+        }))
+    }
+
+    /// Restores the workspace to its state before the turn at index `turn` of
+    /// the conversation, using the snapshots linked to that turn and to the
+    /// later ones. The reverted turns are removed from the history.
+    pub async fn restore_turn(
+        &self,
+        conversation_id: &ConversationId,
+        turn: usize,
+    ) -> Result<RestorePlan> {
+        let mut conversation = self
+            .services
+            .find(conversation_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Conversation not found: {}", conversation_id))?;
+
+        if turn >= conversation.turns.len() {
+            anyhow::bail!("Turn {} not found in the history", turn + 1);
+        }
+
+        let turns = conversation.turns.split_off(turn);
+        let plan = RestorePlan::new(&turns);
+        self.apply_restore_plan(&plan).await?;
+
+        self.services.upsert(conversation).await?;
+
+        Ok(plan)
+    }
+
+    async fn apply_restore_plan(&self, plan: &RestorePlan) -> Result<()> {
+        for snapshot in plan.restore.iter() {
+            self.services
+                .restore_snapshot(snapshot)
+                .await
+                .with_context(|| format!("Failed to restore {}", snapshot.path))?;
+        }
+
+        for path in plan.remove.iter() {
+            if self.services.exists(Path::new(path)).await? {
+                self.services.remove(path.clone(), false).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Saves the context of the conversation and the content of the files it
+    /// touched under the given name, replacing the checkpoint with the same
+    /// name.
+    pub async fn save_checkpoint(
+        &self,
+        conversation_id: &ConversationId,
+        name: &str,
+    ) -> Result<Checkpoint> {
+        let mut conversation = self
+            .services
+            .find(conversation_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Conversation not found: {}", conversation_id))?;
+
+        let paths = conversation.touched_files().into_iter().collect::<Vec<_>>();
+        let checkpoint = Checkpoint {
+            name: name.to_string(),
+            created_at: chrono::Utc::now(),
+            context: conversation.context.clone(),
+            turns: conversation.turns.len(),
+            files: self.services.capture_files(&paths).await?,
+        };
+        conversation.save_checkpoint(checkpoint.clone());
+        self.services.upsert(conversation).await?;
+
+        Ok(checkpoint)
+    }
+
+    /// Brings the conversation and the workspace back to a checkpoint. The
+    /// files captured by the checkpoint are written back, and the files changed
+    /// by the later turns are restored from their snapshots. The later turns
+    /// are removed from the history.
+    pub async fn restore_checkpoint(
+        &self,
+        conversation_id: &ConversationId,
+        name: &str,
+    ) -> Result<CheckpointRestore> {
+        let mut conversation = self
+            .services
+            .find(conversation_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Conversation not found: {}", conversation_id))?;
+        let checkpoint = conversation
+            .checkpoint(name)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Checkpoint not found: {name}"))?;
+
+        // The files the checkpoint captured are written back below
+        let captured = |path: &String| checkpoint.files.iter().any(|file| &file.path == path);
+        let turns = conversation
+            .turns
+            .split_off(checkpoint.turns.min(conversation.turns.len()));
+        let mut plan = RestorePlan::new(&turns);
+        plan.restore.retain(|snapshot| !captured(&snapshot.path));
+        plan.remove.retain(|path| !captured(path));
+        plan.unrecoverable.retain(|path| !captured(path));
+        self.apply_restore_plan(&plan).await?;
+
+        self.services.restore_files(&checkpoint.files).await?;
+
+        conversation.context = checkpoint.context.clone();
+        self.services.upsert(conversation).await?;
+
+        let (restored, removed): (Vec<_>, Vec<_>) =
+            checkpoint.files.iter().partition(|file| file.existed());
+        Ok(CheckpointRestore {
+            restored: restored
+                .into_iter()
+                .map(|file| file.path.clone())
+                .chain(plan.restore.into_iter().map(|snapshot| snapshot.path))
+                .collect(),
+            removed: removed
+                .into_iter()
+                .map(|file| file.path.clone())
+                .chain(plan.remove)
+                .collect(),
+            unrecoverable: plan.unrecoverable,
+        })
+    }
+
+    /// Pins a context item of the conversation so that it's sent with every
+    /// request. A file is resolved against the working directory and must be
+    /// readable. Returns `false` if the item was already pinned.
+    pub async fn pin(&self, conversation_id: &ConversationId, pin: Pin) -> Result<bool> {
+        let mut conversation = self
+            .services
+            .find(conversation_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Conversation not found: {}", conversation_id))?;
+
+        let pin = match pin {
+            Pin::File { path } => {
+                let path = self.services.get_environment().cwd.join(path);
+                let path = path.display().to_string();
+                self.services
+                    .read(path.clone(), None, None)
+                    .await
+                    .with_context(|| format!("Failed to pin {path}"))?;
+                Pin::File { path }
+            }
+            pin => pin,
+        };
+
+        let pinned = conversation.pin(pin);
+        self.services.upsert(conversation).await?;
+
+        Ok(pinned)
+    }
+
+    /// Removes the pin at the given position of the conversation, starting at
+    /// 1
+    pub async fn unpin(&self, conversation_id: &ConversationId, position: usize) -> Result<Pin> {
+        let mut conversation = self
+            .services
+            .find(conversation_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Conversation not found: {}", conversation_id))?;
+
+        let pin = conversation
+            .unpin(position)
+            .ok_or_else(|| anyhow::anyhow!("No pin at position {position}"))?;
+        self.services.upsert(conversation).await?;
+
+        Ok(pin)
+    }
+
+    /// Replaces the messages of the context matching the target with an
+    /// annotation of their removal, e.g. a huge file attached by mistake
+    pub async fn drop_context(
+        &self,
+        conversation_id: &ConversationId,
+        target: &DropTarget,
+    ) -> Result<Vec<DroppedMessage>> {
+        let mut conversation = self
+            .services
+            .find(conversation_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Conversation not found: {}", conversation_id))?;
+
+        let context = conversation
+            .context
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("No context yet, send a message to build one"))?;
+        let cwd = self.services.get_environment().cwd;
+        let dropped = context.drop_messages(target, &cwd);
+        if dropped.is_empty() {
+            anyhow::bail!("Nothing to drop, use /drop to list the messages of the context");
+        }
+        self.services.upsert(conversation).await?;
+
+        Ok(dropped)
+    }
+
+    /// Content of the pins for the system prompt. The files are read for every
+    /// request so that the model sees their current content, up to the lines
+    /// a read returns, and the truncation is marked.
+    async fn pinned_contents(&self, pins: &[Pin]) -> Vec<PinnedContent> {
+        let mut contents = Vec::new();
+        for pin in pins {
+            let content = match pin {
+                Pin::Text { text } => text.clone(),
+                Pin::File { path } => match self.services.read(path.clone(), None, None).await {
+                    Ok(output) => {
+                        let Content::File(mut content) = output.content;
+                        if output.end_line < output.total_lines {
+                            content.push_str(&format!(
+                                "\n[Truncated at line {} of {}, read the rest of {path} with the read tool]",
+                                output.end_line, output.total_lines
+                            ));
+                        }
+                        content
+                    }
+                    // A pinned file that was removed must not prevent the chat
+                    Err(error) => {
+                        tracing::warn!(path, %error, "Failed to read pinned file");
+                        continue;
+                    }
+                },
+            };
+            contents.push(PinnedContent { source: pin.source().to_string(), content });
+        }
+        contents
+    }
+
+    /// Counts the entries of the workspace with a bounded scan, so that a
+    /// gigantic workspace is detected before it's walked
+    pub async fn workspace_size(&self) -> Result<WorkspaceSize> {
+        let cwd = self.services.get_environment().cwd;
+        let entries = self
+            .services
+            .count_entries(cwd, MAX_WORKSPACE_ENTRIES)
+            .await?;
+        Ok(WorkspaceSize { entries })
+    }
+
     pub async fn list_tools(&self) -> Result<Vec<ToolDefinition>> {
         self.tool_registry.list().await
     }
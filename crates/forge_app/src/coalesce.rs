@@ -0,0 +1,83 @@
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+/// Batches the text deltas of a streamed response, so that providers sending
+/// a token at a time don't redraw the terminal for each of them. The deltas
+/// received within the window are emitted together once it elapses.
+pub struct TextCoalescer {
+    window: Option<Duration>,
+    pending: String,
+    since: Option<Instant>,
+}
+
+impl TextCoalescer {
+    /// Coalesces the deltas received within the window, or emits each of
+    /// them as it arrives without one
+    pub fn new(window: Option<Duration>) -> Self {
+        Self { window, pending: String::new(), since: None }
+    }
+
+    /// Adds the delta, returning the text to emit when the window elapsed
+    pub fn push(&mut self, delta: &str) -> Option<String> {
+        let Some(window) = self.window else {
+            return Some(delta.to_string());
+        };
+        self.pending.push_str(delta);
+        let since = *self.since.get_or_insert_with(Instant::now);
+        if since.elapsed() >= window {
+            return self.flush();
+        }
+        None
+    }
+
+    /// When the held back text is due, `None` when there is none
+    pub fn deadline(&self) -> Option<Instant> {
+        Some(self.since? + self.window?)
+    }
+
+    /// Returns the held back text, to be emitted before the response ends
+    pub fn flush(&mut self) -> Option<String> {
+        self.since = None;
+        (!self.pending.is_empty()).then(|| std::mem::take(&mut self.pending))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_coalesce_deltas_within_window() {
+        let mut fixture = TextCoalescer::new(Some(Duration::from_millis(50)));
+
+        let mut actual = vec![fixture.push("Hel"), fixture.push("lo")];
+        tokio::time::advance(Duration::from_millis(50)).await;
+        actual.push(fixture.push(", world"));
+        actual.push(fixture.push("!"));
+        actual.push(fixture.flush());
+
+        let expected = vec![
+            None,
+            None,
+            Some("Hello, world".to_string()),
+            None,
+            Some("!".to_string()),
+        ];
+        assert_eq!(actual, expected);
+        assert_eq!(fixture.deadline(), None);
+    }
+
+    #[test]
+    fn test_emit_deltas_without_window() {
+        let mut fixture = TextCoalescer::new(None);
+
+        let actual = vec![fixture.push("Hel"), fixture.push("lo"), fixture.flush()];
+
+        let expected = vec![Some("Hel".to_string()), Some("lo".to_string()), None];
+        assert_eq!(actual, expected);
+        assert_eq!(fixture.deadline(), None);
+    }
+}
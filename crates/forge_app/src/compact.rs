@@ -12,11 +12,18 @@ use crate::agent::AgentService;
 /// A service dedicated to handling context compaction.
 pub struct Compactor<S> {
     services: Arc<S>,
+    /// Language the summaries are written in
+    response_language: Option<String>,
 }
 
 impl<S: AgentService> Compactor<S> {
     pub fn new(services: Arc<S>) -> Self {
-        Self { services }
+        Self { services, response_language: None }
+    }
+
+    pub fn response_language(mut self, language: Option<String>) -> Self {
+        self.response_language = language;
+        self
     }
 
     /// Apply compaction to the context if requested.
@@ -106,7 +113,8 @@ impl<S: AgentService> Compactor<S> {
         let summary_tag = compact.summary_tag.as_ref().cloned().unwrap_or_default();
         let ctx = serde_json::json!({
             "context": sequence_context.to_text(),
-            "summary_tag": summary_tag
+            "summary_tag": summary_tag,
+            "response_language": self.response_language
         });
 
         let prompt = self
@@ -1,4 +1,4 @@
-use forge_domain::{ToolCallArgumentError, ToolName};
+use forge_domain::{AgentId, ToolCallArgumentError, ToolName};
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
@@ -19,6 +19,44 @@ pub enum Error {
         supported_tools: String,
     },
 
+    #[error(
+        "Tool '{0}' is not available in this phase of the conversation. Please use one of the \
+         tools offered"
+    )]
+    Gated(ToolName),
+
+    #[error("Tool '{tool}' is not available, the MCP server {server} is disabled")]
+    DisabledMcpServer { tool: ToolName, server: String },
+
+    #[error(
+        "File {0} has not been read in this conversation. Read it before modifying it, so that \
+         the changes are based on its actual content"
+    )]
+    UnreadFile(String),
+
+    #[error("Agent '{agent}' is not permitted to {access} {path}. Allowed paths: [{allowed}]")]
+    PathNotPermitted {
+        agent: AgentId,
+        access: &'static str,
+        path: String,
+        allowed: String,
+    },
+
+    #[error("The user declined the removal of {0}. Do not try to remove it another way")]
+    RemovalRejected(String),
+
+    #[error("The user declined the completion the MCP server {0} asked for")]
+    SamplingDeclined(String),
+
+    #[error("Unknown template {name}. Available templates: {available}")]
+    UnknownScaffold { name: String, available: String },
+
+    #[error("{0} already exists, nothing was created. Choose another path or names")]
+    ScaffoldConflict(String),
+
+    #[error("Archives can only be extracted within a conversation, which has a scratch directory")]
+    NoScratchDir,
+
     #[error("Empty tool response")]
     EmptyToolResponse,
 
@@ -2,7 +2,7 @@ use std::convert::AsRef;
 use std::path::Path;
 
 use forge_display::TitleFormat;
-use forge_domain::{Environment, Tools};
+use forge_domain::{AttemptCompletion, Environment, Tools};
 
 use crate::fmt::content::{ContentFormat, FormatContent};
 use crate::utils::display_path;
@@ -41,6 +41,21 @@ impl FormatContent for Tools {
                 };
                 TitleFormat::debug(title).sub_title(display_path).into()
             }
+            Tools::ForgeToolFsWriteBegin(input) => {
+                let display_path = display_path_for(&input.path);
+                TitleFormat::debug("Write").sub_title(display_path).into()
+            }
+            Tools::ForgeToolFsWriteAppend(input) => {
+                let display_path = display_path_for(&input.path);
+                let lines = input.content.lines().count();
+                TitleFormat::debug(format!("Append {lines} lines"))
+                    .sub_title(display_path)
+                    .into()
+            }
+            Tools::ForgeToolFsWriteCommit(input) => {
+                let display_path = display_path_for(&input.path);
+                TitleFormat::debug("Commit").sub_title(display_path).into()
+            }
             Tools::ForgeToolFsSearch(input) => {
                 let formatted_dir = display_path_for(&input.path);
                 let title = match (&input.regex, &input.file_pattern) {
@@ -57,6 +72,12 @@ impl FormatContent for Tools {
                 let display_path = display_path_for(&input.path);
                 TitleFormat::debug("Remove").sub_title(display_path).into()
             }
+            Tools::ForgeToolFsRemoveDir(input) => {
+                let display_path = display_path_for(&input.path);
+                TitleFormat::debug("Remove directory")
+                    .sub_title(display_path)
+                    .into()
+            }
             Tools::ForgeToolFsPatch(input) => {
                 let display_path = display_path_for(&input.path);
                 TitleFormat::debug(input.operation.as_ref())
@@ -67,19 +88,89 @@ impl FormatContent for Tools {
                 let display_path = display_path_for(&input.path);
                 TitleFormat::debug("Undo").sub_title(display_path).into()
             }
+            Tools::ForgeToolFsWait(input) => {
+                let display_path = display_path_for(&input.path);
+                TitleFormat::debug("Wait for change")
+                    .sub_title(display_path)
+                    .into()
+            }
             Tools::ForgeToolProcessShell(input) => {
                 TitleFormat::debug(format!("Execute [{}]", env.shell))
                     .sub_title(&input.command)
                     .into()
             }
+            Tools::ForgeToolProcessEnv(input) => TitleFormat::debug("Env")
+                .sub_title(input.name.clone().unwrap_or_default())
+                .into(),
             Tools::ForgeToolNetFetch(input) => {
                 TitleFormat::debug("GET").sub_title(&input.url).into()
             }
+            Tools::ForgeToolGitLog(input) => {
+                let subtitle = [
+                    input.path.as_deref().map(display_path_for),
+                    input.range.clone(),
+                ];
+                let subtitle = subtitle.into_iter().flatten().collect::<Vec<_>>().join(" ");
+                TitleFormat::debug("Git Log").sub_title(subtitle).into()
+            }
+            Tools::ForgeToolGitBlame(input) => {
+                let display_path = display_path_for(&input.path);
+                TitleFormat::debug("Git Blame")
+                    .sub_title(display_path)
+                    .into()
+            }
+            Tools::ForgeToolGitCommitMessage(_) => TitleFormat::debug("Git Commit Message").into(),
+            Tools::ForgeToolScaffold(input) => TitleFormat::debug("Scaffold")
+                .sub_title(&input.template)
+                .into(),
+            Tools::ForgeToolLint(input) => {
+                let paths = input
+                    .paths
+                    .iter()
+                    .map(|path| display_path_for(path))
+                    .collect::<Vec<_>>();
+                let title = if input.fix { "Lint Fix" } else { "Lint" };
+                TitleFormat::debug(title).sub_title(paths.join(" ")).into()
+            }
+            Tools::ForgeToolDiscoverTasks(input) => {
+                let display_path = input.path.as_deref().map(display_path_for);
+                TitleFormat::debug("Discover Tasks")
+                    .sub_title(display_path.unwrap_or_default())
+                    .into()
+            }
+            Tools::ForgeToolToolchainInfo(_) => TitleFormat::debug("Toolchain Info").into(),
+            Tools::ForgeToolTimeGet(input) => TitleFormat::debug("Time")
+                .sub_title(input.timezone.as_deref().unwrap_or("local"))
+                .into(),
+            Tools::ForgeToolReminderSchedule(input) => TitleFormat::debug("Reminder")
+                .sub_title(&input.message)
+                .into(),
+            Tools::ForgeToolCalculate(input) => TitleFormat::debug("Calculate")
+                .sub_title(&input.expression)
+                .into(),
+            Tools::ForgeToolArchiveList(input) => {
+                let display_path = display_path_for(&input.path);
+                TitleFormat::debug("Archive List")
+                    .sub_title(display_path)
+                    .into()
+            }
+            Tools::ForgeToolImageDiff(input) => {
+                let display_path = display_path_for(&input.after);
+                TitleFormat::debug("Image Diff")
+                    .sub_title(display_path)
+                    .into()
+            }
+            Tools::ForgeToolArchiveExtract(input) => {
+                let display_path = display_path_for(&input.path);
+                TitleFormat::debug("Archive Extract")
+                    .sub_title(display_path)
+                    .into()
+            }
             Tools::ForgeToolFollowup(input) => TitleFormat::debug("Follow-up")
                 .sub_title(&input.question)
                 .into(),
             Tools::ForgeToolAttemptCompletion(input) => {
-                ContentFormat::Markdown(input.result.clone())
+                ContentFormat::Markdown(completion_markdown(input))
             }
             Tools::ForgeToolTaskListAppend(_) => {
                 TitleFormat::debug("Task +1 ADD".to_string()).into()
@@ -98,12 +189,36 @@ impl FormatContent for Tools {
     }
 }
 
+/// The result of a completion followed by the changes, the verification and
+/// the risks it reported
+fn completion_markdown(input: &AttemptCompletion) -> String {
+    let verification = input
+        .verification
+        .iter()
+        .map(|command| format!("`{command}`"))
+        .collect::<Vec<_>>();
+    let sections = [
+        ("Changes", input.changes.clone()),
+        ("Verification", verification),
+        ("Risks", input.risks.clone()),
+    ];
+
+    let mut markdown = input.result.clone();
+    for (title, items) in sections.iter().filter(|(_, items)| !items.is_empty()) {
+        markdown.push_str(&format!("\n\n**{title}**\n"));
+        for item in items {
+            markdown.push_str(&format!("\n- {item}"));
+        }
+    }
+    markdown
+}
+
 #[cfg(test)]
 mod tests {
     use std::path::PathBuf;
 
     use console::strip_ansi_codes;
-    use forge_domain::{Environment, FSRead, FSWrite, Shell, Tools};
+    use forge_domain::{AttemptCompletion, Environment, FSRead, FSWrite, Shell, Tools};
     use pretty_assertions::assert_eq;
     use url::Url;
 
@@ -142,6 +257,13 @@ mod tests {
             stdout_max_suffix_length: 10,
             http: Default::default(),
             max_file_size: 0,
+            max_write_size: 0,
+            external_paths: vec![],
+            resource_limits: Default::default(),
+            text_coalesce_ms: None,
+            response_cache: false,
+            quota: Default::default(),
+            storage: Default::default(),
             forge_api_url: Url::parse("http://forgecode.dev/api").unwrap(),
         }
     }
@@ -188,6 +310,7 @@ mod tests {
             path: "/home/user/project/new_file.txt".to_string(),
             content: "Hello world".to_string(),
             overwrite: false,
+            allow_large: false,
             explanation: None,
         });
         let env = fixture_environment();
@@ -206,6 +329,7 @@ mod tests {
             path: "/home/user/project/existing_file.txt".to_string(),
             content: "Updated content".to_string(),
             overwrite: true,
+            allow_large: false,
             explanation: None,
         });
         let env = fixture_environment();
@@ -259,4 +383,20 @@ mod tests {
         assert!(actual_with.contains("["));
         assert!(actual_with.contains(":"));
     }
+
+    #[test]
+    fn test_attempt_completion() {
+        let fixture = Tools::ForgeToolAttemptCompletion(AttemptCompletion {
+            result: "Fixed the parser".to_string(),
+            changes: vec!["Handled empty lines".to_string()],
+            verification: vec!["cargo test".to_string()],
+            risks: vec![],
+        });
+        let env = fixture_environment();
+
+        let actual = fixture.to_content(&env).unwrap().render(false);
+        let expected = "Fixed the parser\n\n**Changes**\n\n- Handled empty lines\n\n**Verification**\n\n- `cargo test`";
+
+        assert_eq!(actual, expected);
+    }
 }
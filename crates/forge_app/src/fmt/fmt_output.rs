@@ -1,4 +1,4 @@
-use forge_display::{DiffFormat, GrepFormat};
+use forge_display::{DiffFormat, GrepFormat, TitleFormat};
 use forge_domain::Environment;
 
 use crate::fmt::content::{ContentFormat, FormatContent};
@@ -10,7 +10,10 @@ impl FormatContent for Operation {
         match self {
             Operation::FsRead { input: _, output: _ } => None,
             Operation::FsCreate { input: _, output: _ } => None,
+            Operation::FsWriteBegin { input: _ } => None,
+            Operation::FsWriteAppend { input: _, output: _ } => None,
             Operation::FsRemove { input: _ } => None,
+            Operation::FsRemoveDir { input: _, output: _ } => None,
             Operation::FsSearch { input: _, output } => output.as_ref().map(|result| {
                 ContentFormat::PlainText(
                     GrepFormat::new(
@@ -27,10 +30,66 @@ impl FormatContent for Operation {
                 DiffFormat::format(&output.before, &output.after),
             )),
             Operation::FsUndo { input: _, output: _ } => None,
+            Operation::FsWait { input: _, output: _ } => None,
             Operation::NetFetch { input: _, output: _ } => None,
             Operation::Shell { output: _ } => None,
+            Operation::GitLog { input: _, output: _ } => None,
+            Operation::GitBlame { input: _, output: _ } => None,
+            Operation::GitCommitMessage { diff: _, recent_commits: _ } => None,
+            Operation::Scaffold { input: _, root: _, files: _ } => None,
+            Operation::Lint { input: _, output } => (!output.fixes.is_empty()).then(|| {
+                ContentFormat::PlainText(
+                    output
+                        .fixes
+                        .iter()
+                        .map(|fix| DiffFormat::format(&fix.before, &fix.after))
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                )
+            }),
+            Operation::ProcessEnv { input: _, output: _ } => None,
+            Operation::DiscoverTasks { input: _, root: _, output: _ } => None,
+            Operation::ToolchainInfo { input: _, output: _ } => None,
+            Operation::TimeGet { input: _, output: _ } => None,
+            Operation::ReminderSchedule { input: _, reminder: _ } => None,
+            Operation::Calculate { input: _, output: _ } => None,
+            Operation::ArchiveList { input: _, output: _ } => None,
+            Operation::ArchiveExtract { input: _, output: _ } => None,
+            Operation::ImageDiff { input: _, output: _ } => None,
             Operation::FollowUp { output: _ } => None,
-            Operation::AttemptCompletion => None,
+            Operation::AttemptCompletion { input: _, check, grounding } => {
+                let mut titles = Vec::new();
+                if !check.is_verified() {
+                    let reason = match check.unverified.is_empty() {
+                        true => "no command verified the modified files".to_string(),
+                        false => format!("not run: {}", check.unverified.join(", ")),
+                    };
+                    titles.push(TitleFormat::error("Completion not verified").sub_title(reason));
+                }
+                if !grounding.is_grounded() {
+                    let claims = grounding
+                        .unfounded
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    titles.push(
+                        TitleFormat::error("Answer not grounded")
+                            .sub_title(format!("not found in the workspace: {claims}")),
+                    );
+                }
+                match titles.len() {
+                    0 => None,
+                    1 => titles.pop().map(ContentFormat::from),
+                    _ => Some(ContentFormat::PlainText(
+                        titles
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>()
+                            .join("\n"),
+                    )),
+                }
+            }
             Operation::TaskListAppend { _input: _, before, after }
             | Operation::TaskListAppendMultiple { _input: _, before, after }
             | Operation::TaskListUpdate { _input: _, before, after }
@@ -47,7 +106,10 @@ mod tests {
     use std::path::PathBuf;
 
     use console::strip_ansi_codes;
-    use forge_domain::{Environment, PatchOperation};
+    use forge_display::TitleFormat;
+    use forge_domain::{
+        AttemptCompletion, Claim, Environment, GroundingCheck, PatchOperation, VerificationCheck,
+    };
     use insta::assert_snapshot;
     use pretty_assertions::assert_eq;
     use url::Url;
@@ -110,6 +172,13 @@ mod tests {
             stdout_max_suffix_length: 10,
             http: Default::default(),
             max_file_size: 0,
+            max_write_size: 0,
+            external_paths: vec![],
+            resource_limits: Default::default(),
+            text_coalesce_ms: None,
+            response_cache: false,
+            quota: Default::default(),
+            storage: Default::default(),
             forge_api_url: Url::parse("http://forgecode.dev/api").unwrap(),
         }
     }
@@ -128,6 +197,7 @@ mod tests {
                 start_line: 1,
                 end_line: 1,
                 total_lines: 5,
+                encoding: None,
             },
         };
         let env = fixture_environment();
@@ -152,6 +222,7 @@ mod tests {
                 start_line: 2,
                 end_line: 4,
                 total_lines: 10,
+                encoding: None,
             },
         };
         let env = fixture_environment();
@@ -169,6 +240,7 @@ mod tests {
                 path: "/home/user/project/new_file.txt".to_string(),
                 content: "New file content".to_string(),
                 overwrite: false,
+                allow_large: false,
                 explanation: Some("Create new file".to_string()),
             },
             output: FsCreateOutput {
@@ -192,6 +264,7 @@ mod tests {
                 path: "/home/user/project/existing_file.txt".to_string(),
                 content: "new content".to_string(),
                 overwrite: true,
+                allow_large: false,
                 explanation: Some("Overwrite existing file".to_string()),
             },
             output: FsCreateOutput {
@@ -215,6 +288,7 @@ mod tests {
                 path: "/home/user/project/file.txt".to_string(),
                 content: "File content".to_string(),
                 overwrite: false,
+                allow_large: false,
                 explanation: Some("Create file".to_string()),
             },
             output: FsCreateOutput {
@@ -236,6 +310,7 @@ mod tests {
         let fixture = Operation::FsRemove {
             input: forge_domain::FSRemove {
                 path: "/home/user/project/file.txt".to_string(),
+                permanent: false,
                 explanation: Some("Remove file".to_string()),
             },
         };
@@ -347,6 +422,7 @@ mod tests {
                 search: Some("Hello world".to_string()),
                 content: "Hello universe".to_string(),
                 operation: PatchOperation::Replace,
+                allow_large: false,
                 explanation: Some("Replace text".to_string()),
             },
             output: PatchOutput {
@@ -369,6 +445,7 @@ mod tests {
                 search: Some("line2".to_string()),
                 content: "new line\nline2".to_string(),
                 operation: PatchOperation::Replace,
+                allow_large: false,
                 explanation: Some("Add new line".to_string()),
             },
             output: PatchOutput {
@@ -465,6 +542,7 @@ mod tests {
                     exit_code: Some(0),
                 },
                 shell: "/bin/bash".to_string(),
+                attempts: 1,
             },
         };
         let env = fixture_environment();
@@ -486,6 +564,7 @@ mod tests {
                     exit_code: Some(0),
                 },
                 shell: "/bin/bash".to_string(),
+                attempts: 1,
             },
         };
         let env = fixture_environment();
@@ -507,6 +586,7 @@ mod tests {
                     exit_code: Some(127),
                 },
                 shell: "/bin/bash".to_string(),
+                attempts: 1,
             },
         };
         let env = fixture_environment();
@@ -542,7 +622,11 @@ mod tests {
 
     #[test]
     fn test_attempt_completion() {
-        let fixture = Operation::AttemptCompletion;
+        let fixture = Operation::AttemptCompletion {
+            input: AttemptCompletion::default(),
+            check: VerificationCheck::default(),
+            grounding: GroundingCheck::default(),
+        };
         let env = fixture_environment();
 
         let actual = fixture.to_content(&env);
@@ -550,4 +634,49 @@ mod tests {
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn test_attempt_completion_unverified() {
+        let fixture = Operation::AttemptCompletion {
+            input: AttemptCompletion::default(),
+            check: VerificationCheck {
+                verified: vec![],
+                unverified: vec!["cargo clippy".to_string()],
+                missing: false,
+            },
+            grounding: GroundingCheck::default(),
+        };
+        let env = fixture_environment();
+
+        let actual = fixture.to_content(&env);
+        let expected = Some(ContentFormat::Title(
+            TitleFormat::error("Completion not verified").sub_title("not run: cargo clippy"),
+        ));
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_attempt_completion_ungrounded() {
+        let fixture = Operation::AttemptCompletion {
+            input: AttemptCompletion::default(),
+            check: VerificationCheck::default(),
+            grounding: GroundingCheck {
+                grounded: vec![],
+                unfounded: vec![
+                    Claim::Symbol("parse_config".to_string()),
+                    Claim::ConfigKey("server.port".to_string()),
+                ],
+            },
+        };
+        let env = fixture_environment();
+
+        let actual = fixture.to_content(&env);
+        let expected = Some(ContentFormat::Title(
+            TitleFormat::error("Answer not grounded")
+                .sub_title("not found in the workspace: parse_config, server.port"),
+        ));
+
+        assert_eq!(actual, expected);
+    }
 }
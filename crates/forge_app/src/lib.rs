@@ -3,6 +3,7 @@ mod agent_executor;
 mod app;
 mod app_config;
 mod authenticator;
+mod coalesce;
 mod compact;
 mod error;
 pub mod fmt;
@@ -13,6 +14,7 @@ mod retry;
 mod services;
 mod tool_executor;
 mod tool_registry;
+mod triage;
 mod truncation;
 mod user;
 mod utils;
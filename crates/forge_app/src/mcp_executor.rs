@@ -1,21 +1,35 @@
 use std::sync::Arc;
 
 use forge_display::TitleFormat;
-use forge_domain::{ToolCallContext, ToolCallFull, ToolName, ToolOutput};
+use forge_domain::{
+    Agent, ChatCompletionMessageFull, McpSampling, McpSamplingRequest, McpSamplingResponse,
+    ResultStreamExt, ToolCallContext, ToolCallFull, ToolName, ToolOutput,
+};
+use tokio::sync::mpsc;
 
-use crate::McpService;
+use crate::error::Error;
+use crate::{
+    AppConfigService, FollowUpService, McpService, ProviderRegistry, ProviderService, Services,
+};
+
+const APPROVE_SAMPLING: &str = "Yes, answer it";
+const REJECT_SAMPLING: &str = "No, decline it";
 
 pub struct McpExecutor<S> {
     pub services: Arc<S>,
 }
 
-impl<S: McpService> McpExecutor<S> {
+impl<S: Services> McpExecutor<S> {
     pub fn new(services: Arc<S>) -> Self {
         Self { services }
     }
 
+    /// Calls the tool, answering the completions its server asks for while
+    /// the call runs, since servers sample to carry out a tool call. The ones
+    /// left unanswered are declined once the call ends.
     pub async fn execute(
         &self,
+        agent: &Agent,
         input: ToolCallFull,
         context: &mut ToolCallContext,
     ) -> anyhow::Result<ToolOutput> {
@@ -23,7 +37,62 @@ impl<S: McpService> McpExecutor<S> {
             .send_text(TitleFormat::info("MCP").sub_title(input.name.as_str()))
             .await?;
 
-        self.services.call(input).await
+        let (sampling, mut requests) = mpsc::unbounded_channel();
+        let call = self.services.call(input, sampling);
+        tokio::pin!(call);
+        loop {
+            tokio::select! {
+                output = &mut call => return output,
+                Some(McpSampling { request, reply }) = requests.recv() => {
+                    let response = self.sample(agent, &request, context).await;
+                    // The server may have given up on the request
+                    let _ = reply.send(response);
+                }
+            }
+        }
+    }
+
+    /// Asks the model of the agent for the completion once the user approves
+    /// it, since it's billed to the user
+    async fn sample(
+        &self,
+        agent: &Agent,
+        request: &McpSamplingRequest,
+        context: &mut ToolCallContext,
+    ) -> anyhow::Result<McpSamplingResponse> {
+        let model = agent
+            .model
+            .clone()
+            .ok_or(forge_domain::Error::MissingModel(agent.id.clone()))?;
+        let answer = self
+            .services
+            .follow_up(
+                format!(
+                    "Allow the MCP server {} to ask {model} for a completion of at most {} tokens \
+                     of these messages?\n\n{}",
+                    request.server,
+                    request.max_tokens,
+                    request.transcript()
+                ),
+                vec![APPROVE_SAMPLING.to_string(), REJECT_SAMPLING.to_string()],
+                None,
+            )
+            .await?;
+        if !answer.is_some_and(|answer| answer.ends_with(APPROVE_SAMPLING)) {
+            return Err(Error::SamplingDeclined(request.server.clone()).into());
+        }
+
+        context
+            .send_text(TitleFormat::info("MCP Sampling").sub_title(&request.server))
+            .await?;
+        let config = self.services.read_app_config().await.unwrap_or_default();
+        let provider = self.services.get_provider(config).await?;
+        let stream = self
+            .services
+            .chat(&model, request.context(&model), provider)
+            .await?;
+        let ChatCompletionMessageFull { content, .. } = stream.into_full(false).await?;
+        Ok(McpSamplingResponse { model: model.to_string(), text: content })
     }
 
     pub async fn contains_tool(&self, tool_name: &ToolName) -> anyhow::Result<bool> {
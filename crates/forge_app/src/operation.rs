@@ -5,8 +5,14 @@ use console::strip_ansi_codes;
 use derive_setters::Setters;
 use forge_display::DiffFormat;
 use forge_domain::{
-    Environment, FSPatch, FSRead, FSRemove, FSSearch, FSUndo, FSWrite, NetFetch, TaskList,
-    TaskListAppend, TaskListAppendMultiple, TaskListClear, TaskListList, TaskListUpdate,
+    ArchiveEntry, ArchiveEntryKind, ArchiveExtract, ArchiveExtraction, ArchiveFormat, ArchiveList,
+    AttemptCompletion, Calculate, Calculation, DiscoverTasks, EnvVar, Environment, FSPatch, FSRead,
+    FSRemove, FSRemoveDir, FSSearch, FSUndo, FSWait, FSWrite, FSWriteAppend, FSWriteBegin,
+    FileChange, FileChangeKind, FsChange, GitBlame, GitLog, GroundingCheck, ImageDiff,
+    ImageDiffOutput, Lint, NetFetch, ProcessEnv, Reminder, Runnable, Scaffold, ScaffoldFile,
+    ScheduleReminder, TOOLCHAIN_PROBES, TaskList, TaskListAppend, TaskListAppendMultiple,
+    TaskListClear, TaskListList, TaskListUpdate, TimeGet, TimeReport, Toolchain, ToolchainInfo,
+    VerificationCheck,
 };
 use forge_template::Element;
 
@@ -16,10 +22,15 @@ use crate::truncation::{
 };
 use crate::utils::display_path;
 use crate::{
-    Content, EnvironmentService, FsCreateOutput, FsCreateService, FsUndoOutput, HttpResponse,
-    PatchOutput, ReadOutput, ResponseContext, SearchResult, ShellOutput,
+    Content, EnvironmentService, FsCreateOutput, FsCreateService, FsRemoveDirOutput, FsUndoOutput,
+    FsWriteAppendOutput, GitBlameOutput, GitCommit, HttpResponse, LintOutput, PatchOutput,
+    ReadOutput, ResponseContext, SearchResult, ShellOutput,
 };
 
+/// Entries of an archive shown to the model, the listing of larger archives
+/// being cut
+const MAX_ARCHIVE_ENTRIES_SHOWN: usize = 500;
+
 #[derive(Debug, Default, Setters)]
 #[setters(into, strip_option)]
 pub struct TempContentFiles {
@@ -37,9 +48,20 @@ pub enum Operation {
         input: FSWrite,
         output: FsCreateOutput,
     },
+    FsWriteBegin {
+        input: FSWriteBegin,
+    },
+    FsWriteAppend {
+        input: FSWriteAppend,
+        output: FsWriteAppendOutput,
+    },
     FsRemove {
         input: FSRemove,
     },
+    FsRemoveDir {
+        input: FSRemoveDir,
+        output: FsRemoveDirOutput,
+    },
     FsSearch {
         input: FSSearch,
         output: Option<SearchResult>,
@@ -52,6 +74,11 @@ pub enum Operation {
         input: FSUndo,
         output: FsUndoOutput,
     },
+    /// Change of the watched path, `None` when the wait timed out
+    FsWait {
+        input: FSWait,
+        output: Option<FsChange>,
+    },
     NetFetch {
         input: NetFetch,
         output: HttpResponse,
@@ -59,10 +86,80 @@ pub enum Operation {
     Shell {
         output: ShellOutput,
     },
+    GitLog {
+        input: GitLog,
+        output: Vec<GitCommit>,
+    },
+    GitBlame {
+        input: GitBlame,
+        output: GitBlameOutput,
+    },
+    GitCommitMessage {
+        diff: String,
+        recent_commits: Vec<GitCommit>,
+    },
+    /// Files created from a template, with their absolute paths
+    Scaffold {
+        input: Scaffold,
+        root: PathBuf,
+        files: Vec<ScaffoldFile>,
+    },
+    Lint {
+        input: Lint,
+        output: LintOutput,
+    },
+    /// Redacted environment variables of the process
+    ProcessEnv {
+        input: ProcessEnv,
+        output: Vec<EnvVar>,
+    },
+    /// Targets defined by the build tools of the project in `root`
+    DiscoverTasks {
+        input: DiscoverTasks,
+        root: PathBuf,
+        output: Vec<Runnable>,
+    },
+    /// Toolchains found on the PATH with their versions
+    ToolchainInfo {
+        input: ToolchainInfo,
+        output: Vec<Toolchain>,
+    },
+    /// Time asked for, on the clocks of the timezone it's reported in
+    TimeGet {
+        input: TimeGet,
+        output: TimeReport,
+    },
+    /// Reminder added to the conversation
+    ReminderSchedule {
+        input: ScheduleReminder,
+        reminder: Reminder,
+    },
+    Calculate {
+        input: Calculate,
+        output: Calculation,
+    },
+    ArchiveList {
+        input: ArchiveList,
+        output: Vec<ArchiveEntry>,
+    },
+    ArchiveExtract {
+        input: ArchiveExtract,
+        output: ArchiveExtraction,
+    },
+    ImageDiff {
+        input: ImageDiff,
+        output: ImageDiffOutput,
+    },
     FollowUp {
         output: Option<String>,
     },
-    AttemptCompletion,
+    /// Completion of the task with its verification checked against the
+    /// journal
+    AttemptCompletion {
+        input: AttemptCompletion,
+        check: VerificationCheck,
+        grounding: GroundingCheck,
+    },
     TaskListAppend {
         _input: TaskListAppend,
         before: TaskList,
@@ -128,6 +225,91 @@ fn create_stream_element<T: StreamElement>(
     Some(elem)
 }
 impl Operation {
+    /// Describes how the operation changed the file system, if it did.
+    pub fn file_changes(&self) -> Vec<FileChange> {
+        let (path, kind, before, after) = match self {
+            Operation::FsCreate { input, output } => {
+                let kind = match output.before {
+                    Some(_) => FileChangeKind::Modified,
+                    None => FileChangeKind::Added,
+                };
+                (
+                    &input.path,
+                    kind,
+                    output.before.as_deref(),
+                    Some(&input.content[..]),
+                )
+            }
+            Operation::FsPatch { input, output } => (
+                &input.path,
+                FileChangeKind::Modified,
+                Some(&output.before[..]),
+                Some(&output.after[..]),
+            ),
+            Operation::FsRemove { input } => (&input.path, FileChangeKind::Deleted, None, None),
+            Operation::FsRemoveDir { input: _, output } => {
+                return output
+                    .snapshotted
+                    .iter()
+                    .map(|path| FileChange {
+                        path: path.clone(),
+                        kind: FileChangeKind::Deleted,
+                        insertions: 0,
+                        deletions: 0,
+                    })
+                    .collect();
+            }
+            Operation::Scaffold { files, .. } => {
+                return files
+                    .iter()
+                    .map(|file| {
+                        let (insertions, deletions) = DiffFormat::line_counts("", &file.content);
+                        FileChange {
+                            path: file.path.clone(),
+                            kind: FileChangeKind::Added,
+                            insertions,
+                            deletions,
+                        }
+                    })
+                    .collect();
+            }
+            Operation::Lint { input: _, output } => {
+                return output
+                    .fixes
+                    .iter()
+                    .map(|fix| {
+                        let (insertions, deletions) =
+                            DiffFormat::line_counts(&fix.before, &fix.after);
+                        FileChange {
+                            path: fix.path.clone(),
+                            kind: FileChangeKind::Modified,
+                            insertions,
+                            deletions,
+                        }
+                    })
+                    .collect();
+            }
+            Operation::FsUndo { input, output } => {
+                let kind = match (&output.before_undo, &output.after_undo) {
+                    (None, Some(_)) => FileChangeKind::Added,
+                    (Some(_), None) => FileChangeKind::Deleted,
+                    _ => FileChangeKind::Modified,
+                };
+                (
+                    &input.path,
+                    kind,
+                    output.before_undo.as_deref(),
+                    output.after_undo.as_deref(),
+                )
+            }
+            _ => return Vec::new(),
+        };
+
+        let (insertions, deletions) =
+            DiffFormat::line_counts(before.unwrap_or_default(), after.unwrap_or_default());
+        vec![FileChange { path: path.clone(), kind, insertions, deletions }]
+    }
+
     pub fn into_tool_output(
         self,
         content_files: TempContentFiles,
@@ -137,12 +319,13 @@ impl Operation {
             Operation::FsRead { input, output } => match &output.content {
                 Content::File(content) => {
                     let elm = Element::new("file_content")
-                        .attr("path", input.path)
+                        .attr("path", display_path(env, Path::new(&input.path)))
                         .attr(
                             "display_lines",
                             format!("{}-{}", output.start_line, output.end_line),
                         )
                         .attr("total_lines", content.lines().count())
+                        .attr_if_some("encoding", output.encoding)
                         .cdata(content);
 
                     forge_domain::ToolOutput::text(elm)
@@ -159,7 +342,7 @@ impl Operation {
                 };
 
                 elm = elm
-                    .attr("path", input.path)
+                    .attr("path", display_path(env, Path::new(&input.path)))
                     .attr("total_lines", input.content.lines().count());
 
                 if let Some(warning) = output.warning {
@@ -168,6 +351,32 @@ impl Operation {
 
                 forge_domain::ToolOutput::text(elm)
             }
+            Operation::FsWriteBegin { input } => {
+                let elem = Element::new("file_write_started")
+                    .attr("path", display_path(env, Path::new(&input.path)))
+                    .attr("overwrite", input.overwrite);
+
+                forge_domain::ToolOutput::text(elem)
+            }
+            Operation::FsWriteAppend { input, output } => {
+                let elem = Element::new("file_write_appended")
+                    .attr("path", display_path(env, Path::new(&input.path)))
+                    .attr("parts", output.parts)
+                    .attr("total_lines", output.total_lines);
+
+                forge_domain::ToolOutput::text(elem)
+            }
+            Operation::FsWait { input, output } => {
+                let elm = Element::new("file_change")
+                    .attr("path", display_path(env, Path::new(&input.path)));
+                let elm = match output {
+                    Some(change) => elm.attr("change", change),
+                    None => elm
+                        .attr("change", "none")
+                        .attr("timed_out_after_secs", input.timeout().as_secs()),
+                };
+                forge_domain::ToolOutput::text(elm)
+            }
             Operation::FsRemove { input } => {
                 let display_path = display_path(env, Path::new(&input.path));
                 let elem = Element::new("file_removed")
@@ -176,6 +385,19 @@ impl Operation {
 
                 forge_domain::ToolOutput::text(elem)
             }
+            Operation::FsRemoveDir { input, output } => {
+                let snapshots = output
+                    .snapshotted
+                    .iter()
+                    .map(|path| Element::new("snapshot").text(display_path(env, Path::new(path))));
+                let elem = Element::new("directory_removed")
+                    .attr("path", display_path(env, Path::new(&input.path)))
+                    .attr("status", "completed")
+                    .attr("trashed", output.trashed)
+                    .append(snapshots);
+
+                forge_domain::ToolOutput::text(elem)
+            }
             Operation::FsSearch { input, output } => match output {
                 Some(out) => {
                     let max_lines = min(
@@ -188,7 +410,7 @@ impl Operation {
                         truncate_search_output(&out.matches, start_index as u64, max_lines, env);
 
                     let mut elm = Element::new("search_results")
-                        .attr("path", &input.path)
+                        .attr("path", display_path(env, Path::new(&input.path)))
                         .attr("total_lines", truncated_output.total_lines)
                         .attr(
                             "display_lines",
@@ -206,7 +428,8 @@ impl Operation {
                     forge_domain::ToolOutput::text(elm)
                 }
                 None => {
-                    let mut elm = Element::new("search_results").attr("path", &input.path);
+                    let mut elm = Element::new("search_results")
+                        .attr("path", display_path(env, Path::new(&input.path)));
                     elm = elm.attr_if_some("regex", input.regex);
                     elm = elm.attr_if_some("file_pattern", input.file_pattern);
 
@@ -219,7 +442,7 @@ impl Operation {
                         .to_string();
 
                 let mut elm = Element::new("file_diff")
-                    .attr("path", &input.path)
+                    .attr("path", display_path(env, Path::new(&input.path)))
                     .attr("total_lines", output.after.lines().count())
                     .cdata(diff);
 
@@ -233,13 +456,13 @@ impl Operation {
                 match (&output.before_undo, &output.after_undo) {
                     (None, None) => {
                         let elm = Element::new("file_undo")
-                            .attr("path", input.path)
+                            .attr("path", display_path(env, Path::new(&input.path)))
                             .attr("status", "no_changes");
                         forge_domain::ToolOutput::text(elm)
                     }
                     (None, Some(after)) => {
                         let elm = Element::new("file_undo")
-                            .attr("path", input.path)
+                            .attr("path", display_path(env, Path::new(&input.path)))
                             .attr("status", "created")
                             .attr("total_lines", after.lines().count())
                             .cdata(after);
@@ -247,7 +470,7 @@ impl Operation {
                     }
                     (Some(before), None) => {
                         let elm = Element::new("file_undo")
-                            .attr("path", input.path)
+                            .attr("path", display_path(env, Path::new(&input.path)))
                             .attr("status", "removed")
                             .attr("total_lines", before.lines().count())
                             .cdata(before);
@@ -256,7 +479,7 @@ impl Operation {
                     (Some(after), Some(before)) => {
                         let diff = DiffFormat::format(before, after);
                         let elm = Element::new("file_undo")
-                            .attr("path", input.path)
+                            .attr("path", display_path(env, Path::new(&input.path)))
                             .attr("status", "restored")
                             .cdata(strip_ansi_codes(&diff));
 
@@ -302,6 +525,10 @@ impl Operation {
                     parent_elem = parent_elem.attr("exit_code", exit_code);
                 }
 
+                if output.attempts > 1 {
+                    parent_elem = parent_elem.attr("attempts", output.attempts);
+                }
+
                 let truncated_output = truncate_shell_output(
                     &output.output.stdout,
                     &output.output.stderr,
@@ -324,6 +551,220 @@ impl Operation {
 
                 forge_domain::ToolOutput::text(parent_elem)
             }
+            Operation::GitLog { input, output } => {
+                let elm = Element::new("git_log")
+                    .attr_if_some(
+                        "path",
+                        input.path.map(|path| display_path(env, Path::new(&path))),
+                    )
+                    .attr_if_some("range", input.range)
+                    .attr("total_commits", output.len())
+                    .append(output.into_iter().map(|commit| {
+                        Element::new("commit")
+                            .attr("hash", commit.hash)
+                            .attr("author", commit.author)
+                            .attr("date", commit.date)
+                            .text(commit.summary)
+                    }));
+                forge_domain::ToolOutput::text(elm)
+            }
+            Operation::GitBlame { input, output } => {
+                let elm = Element::new("git_blame")
+                    .attr("path", display_path(env, Path::new(&input.path)))
+                    .attr("total_lines", output.total_lines)
+                    .append(output.hunks.into_iter().map(|hunk| {
+                        Element::new("hunk")
+                            .attr(
+                                "display_lines",
+                                format!("{}-{}", hunk.start_line, hunk.end_line),
+                            )
+                            .attr("commit", hunk.commit.hash)
+                            .attr("author", hunk.commit.author)
+                            .attr("date", hunk.commit.date)
+                            .append(Element::new("summary").text(hunk.commit.summary))
+                            .append(Element::new("content").cdata(hunk.content))
+                    }));
+                forge_domain::ToolOutput::text(elm)
+            }
+            Operation::GitCommitMessage { diff, recent_commits } => {
+                let commits = recent_commits.into_iter().map(|commit| {
+                    Element::new("commit")
+                        .attr("hash", commit.hash)
+                        .text(commit.summary)
+                });
+                let elm = Element::new("staged_changes")
+                    .append(Element::new("recent_commits").append(commits))
+                    .append(Element::new("diff").cdata(diff));
+                forge_domain::ToolOutput::text(elm)
+            }
+            Operation::Scaffold { input, root, files } => {
+                let elm = Element::new("scaffold")
+                    .attr("template", input.template)
+                    .attr("path", display_path(env, &root))
+                    .attr("total_files", files.len())
+                    .append(files.into_iter().map(|file| {
+                        Element::new("file")
+                            .attr("path", display_path(env, Path::new(&file.path)))
+                            .attr("total_lines", file.content.lines().count())
+                    }));
+                forge_domain::ToolOutput::text(elm)
+            }
+            Operation::Lint { input: _, output } => {
+                let elm = Element::new("lint_diagnostics")
+                    .attr("total_diagnostics", output.diagnostics.len())
+                    .attr(
+                        "total_fixes",
+                        output.fixes.iter().map(|fix| fix.applied).sum::<usize>(),
+                    )
+                    .append(output.fixes.into_iter().map(|fix| {
+                        Element::new("fixed")
+                            .attr("path", display_path(env, Path::new(&fix.path)))
+                            .attr("total_fixes", fix.applied)
+                    }))
+                    .append(output.skipped.into_iter().map(|path| {
+                        Element::new("skipped").attr("path", display_path(env, Path::new(&path)))
+                    }))
+                    .append(output.diagnostics.into_iter().map(|diagnostic| {
+                        Element::new("diagnostic")
+                            .attr("path", display_path(env, Path::new(&diagnostic.path)))
+                            .attr("line", diagnostic.line)
+                            .attr("column", diagnostic.column)
+                            .attr("severity", diagnostic.severity)
+                            .attr_if_some("rule", diagnostic.rule)
+                            .text(diagnostic.message)
+                    }));
+                forge_domain::ToolOutput::text(elm)
+            }
+            Operation::ProcessEnv { input, output } => {
+                let elm = Element::new("environment_variables")
+                    .attr_if_some("name", input.name)
+                    .attr("total_variables", output.len())
+                    .append(output.into_iter().map(|var| {
+                        let elm = Element::new("variable").attr("name", var.name);
+                        match var.value {
+                            Some(value) => elm.text(value),
+                            None => elm.attr("redacted", true),
+                        }
+                    }));
+                forge_domain::ToolOutput::text(elm)
+            }
+            Operation::DiscoverTasks { input: _, root, output } => {
+                let elm = Element::new("runnable_targets")
+                    .attr("path", display_path(env, &root))
+                    .attr("total_targets", output.len())
+                    .append(output.into_iter().map(|runnable| {
+                        Element::new("target")
+                            .attr("tool", runnable.tool)
+                            .attr("name", runnable.name)
+                            .attr("command", runnable.command)
+                            .text(runnable.description.unwrap_or_default())
+                    }));
+                forge_domain::ToolOutput::text(elm)
+            }
+            Operation::ToolchainInfo { input: _, output } => {
+                let not_found = TOOLCHAIN_PROBES
+                    .iter()
+                    .map(|(name, ..)| *name)
+                    .filter(|name| !output.iter().any(|toolchain| toolchain.name == *name))
+                    .collect::<Vec<_>>();
+                let elm = Element::new("toolchains")
+                    .attr("total_found", output.len())
+                    .attr_if_some(
+                        "not_found",
+                        (!not_found.is_empty()).then(|| not_found.join(", ")),
+                    )
+                    .append(output.into_iter().map(|toolchain| {
+                        Element::new("toolchain")
+                            .attr("name", toolchain.name)
+                            .attr_if_some("version", toolchain.version)
+                            .text(toolchain.output)
+                    }));
+                forge_domain::ToolOutput::text(elm)
+            }
+            Operation::TimeGet { input: _, output } => {
+                let elm = Element::new("time")
+                    .attr("timezone", output.zone)
+                    .attr("rfc3339", output.time.to_rfc3339())
+                    .attr("date", output.time.format("%Y-%m-%d"))
+                    .attr("weekday", output.time.format("%A"))
+                    .attr("iso_week", output.iso_week())
+                    .attr("unix", output.time.timestamp());
+                forge_domain::ToolOutput::text(elm)
+            }
+            Operation::ReminderSchedule { input: _, reminder } => {
+                let elm = Element::new("reminder_scheduled")
+                    .attr("due", reminder.due.to_rfc3339())
+                    .text(reminder.message);
+                forge_domain::ToolOutput::text(elm)
+            }
+            Operation::Calculate { input, output } => {
+                let elm = Element::new("calculation")
+                    .attr("expression", input.expression)
+                    .attr("value", output.value)
+                    .attr_if_some("unit", output.unit)
+                    .attr_if_some("fraction", output.fraction);
+                forge_domain::ToolOutput::text(elm)
+            }
+            Operation::ArchiveList { input, output } => {
+                let entries = output
+                    .iter()
+                    .take(MAX_ARCHIVE_ENTRIES_SHOWN)
+                    .map(|entry| match entry.kind {
+                        ArchiveEntryKind::File => format!("{} ({} bytes)", entry.path, entry.size),
+                        ArchiveEntryKind::Directory => entry.path.clone(),
+                        ArchiveEntryKind::Link => format!("{} (link)", entry.path),
+                    })
+                    .collect::<Vec<_>>();
+                let elm = Element::new("archive")
+                    .attr("path", display_path(env, Path::new(&input.path)))
+                    .attr("entries", output.len())
+                    .attr_if_some(
+                        "shown",
+                        (output.len() > entries.len()).then_some(entries.len()),
+                    )
+                    .text(entries.join("\n"));
+                forge_domain::ToolOutput::text(elm)
+            }
+            Operation::ArchiveExtract { input, output } => {
+                let skipped = (!output.skipped.is_empty()).then(|| output.skipped.join(", "));
+                let elm = Element::new("archive_extracted")
+                    .attr("path", display_path(env, Path::new(&input.path)))
+                    .attr("format", output.format)
+                    .attr("destination", display_path(env, &output.destination))
+                    .attr("files", output.files)
+                    .attr("bytes", output.size)
+                    .attr_if_some("skipped_links", skipped);
+                forge_domain::ToolOutput::text(elm)
+            }
+            Operation::ImageDiff { input, output } => {
+                let comparison = output.comparison;
+                let (before, after) = (comparison.before, comparison.after);
+                let resized = (before != after)
+                    .then(|| format!("{}x{} to {}x{}", before.0, before.1, after.0, after.1));
+                let region = comparison.region.map(|region| {
+                    format!(
+                        "{}x{} at {},{}",
+                        region.width, region.height, region.x, region.y
+                    )
+                });
+                let elm = Element::new("image_diff")
+                    .attr("before", display_path(env, Path::new(&input.before)))
+                    .attr("after", display_path(env, Path::new(&input.after)))
+                    .attr("difference", format!("{:.2}%", comparison.percentage()))
+                    .attr("changed_pixels", comparison.changed_pixels)
+                    .attr("total_pixels", comparison.total_pixels)
+                    .attr_if_some("resized", resized)
+                    .attr_if_some("changed_region", region);
+                if comparison.changed_pixels == 0 {
+                    forge_domain::ToolOutput::text(elm.text("The images are identical"))
+                } else {
+                    let elm = elm.text(
+                        "The attached image is the second one faded to gray, with the changed pixels in red",
+                    );
+                    forge_domain::ToolOutput::text(elm)
+                        .combine(forge_domain::ToolOutput::image(output.diff))
+                }
+            }
             Operation::FollowUp { output } => match output {
                 None => {
                     let elm = Element::new("interrupted").text("No feedback provided");
@@ -334,10 +775,32 @@ impl Operation {
                     forge_domain::ToolOutput::text(elm)
                 }
             },
-            Operation::AttemptCompletion => forge_domain::ToolOutput::text(
-                Element::new("success")
-                    .text("[Task was completed successfully. Now wait for user feedback]"),
-            ),
+            Operation::AttemptCompletion { input: _, check, grounding } => {
+                let elm = Element::new("success")
+                    .text("[Task was completed successfully. Now wait for user feedback]");
+                let mut output = forge_domain::ToolOutput::text(elm);
+                if !check.is_verified() {
+                    let elm = Element::new("unverified_completion")
+                        .attr("modified_without_verification", check.missing)
+                        .append(
+                            check
+                                .unverified
+                                .into_iter()
+                                .map(|command| Element::new("command_not_run").text(command)),
+                        );
+                    output.combine_mut(forge_domain::ToolOutput::text(elm));
+                }
+                if !grounding.is_grounded() {
+                    let elm = Element::new("ungrounded_claims").append(
+                        grounding
+                            .unfounded
+                            .into_iter()
+                            .map(|claim| Element::new("not_found_in_workspace").text(claim)),
+                    );
+                    output.combine_mut(forge_domain::ToolOutput::text(elm));
+                }
+                output
+            }
             Operation::TaskListAppend { _input: _, before: _, after }
             | Operation::TaskListAppendMultiple { _input: _, before: _, after }
             | Operation::TaskListUpdate { _input: _, before: _, after }
@@ -364,6 +827,7 @@ impl Operation {
     pub async fn to_create_temp<S: EnvironmentService + FsCreateService>(
         &self,
         services: &S,
+        scratch_dir: Option<&Path>,
     ) -> anyhow::Result<TempContentFiles> {
         match self {
             Operation::NetFetch { input: _, output } => {
@@ -374,7 +838,14 @@ impl Operation {
 
                 if is_truncated {
                     files = files.stdout(
-                        create_temp_file(services, "forge_fetch_", ".txt", &output.content).await?,
+                        create_temp_file(
+                            services,
+                            scratch_dir,
+                            "forge_fetch_",
+                            ".txt",
+                            &output.content,
+                        )
+                        .await?,
                     );
                 }
 
@@ -395,6 +866,7 @@ impl Operation {
                     files = files.stdout(
                         create_temp_file(
                             services,
+                            scratch_dir,
                             "forge_shell_stdout_",
                             ".txt",
                             &output.output.stdout,
@@ -406,6 +878,7 @@ impl Operation {
                     files = files.stderr(
                         create_temp_file(
                             services,
+                            scratch_dir,
                             "forge_shell_stderr_",
                             ".txt",
                             &output.output.stderr,
@@ -426,11 +899,11 @@ mod tests {
     use std::fmt::Write;
     use std::path::PathBuf;
 
-    use forge_domain::{FSRead, ToolValue};
+    use forge_domain::{BuildTool, Claim, Diagnostic, FSRead, LintSeverity, ToolValue};
     use url::Url;
 
     use super::*;
-    use crate::{Match, MatchResult};
+    use crate::{BlameHunk, LintFix, Match, MatchResult};
 
     fn fixture_environment() -> Environment {
         Environment {
@@ -455,6 +928,13 @@ mod tests {
             stdout_max_suffix_length: 10,
             http: Default::default(),
             max_file_size: 256 << 10, // 256 KiB
+            max_write_size: 1 << 20,  // 1 MiB
+            external_paths: vec![],
+            resource_limits: Default::default(),
+            text_coalesce_ms: None,
+            response_cache: false,
+            quota: Default::default(),
+            storage: Default::default(),
             forge_api_url: Url::parse("http://forgecode.dev/api").unwrap(),
         }
     }
@@ -491,6 +971,7 @@ mod tests {
                 start_line: 1,
                 end_line: 2,
                 total_lines: 2,
+                encoding: None,
             },
         };
 
@@ -515,6 +996,7 @@ mod tests {
                 start_line: 1,
                 end_line: 1,
                 total_lines: 1,
+                encoding: None,
             },
         };
 
@@ -539,6 +1021,7 @@ mod tests {
                 start_line: 2,
                 end_line: 3,
                 total_lines: 5,
+                encoding: None,
             },
         };
 
@@ -563,6 +1046,7 @@ mod tests {
                 start_line: 1,
                 end_line: 100,
                 total_lines: 200,
+                encoding: None,
             },
         };
 
@@ -575,6 +1059,31 @@ mod tests {
         insta::assert_snapshot!(to_value(actual));
     }
 
+    #[test]
+    fn test_fs_read_with_encoding() {
+        let fixture = Operation::FsRead {
+            input: FSRead {
+                path: "/home/user/legacy.txt".to_string(),
+                start_line: None,
+                end_line: None,
+                explanation: Some("Test explanation".to_string()),
+            },
+            output: ReadOutput {
+                content: Content::File("Café crème".to_string()),
+                start_line: 1,
+                end_line: 1,
+                total_lines: 1,
+                encoding: Some("windows-1252".to_string()),
+            },
+        };
+
+        let env = fixture_environment();
+
+        let actual = fixture.into_tool_output(TempContentFiles::default(), &env);
+
+        insta::assert_snapshot!(to_value(actual));
+    }
+
     #[test]
     fn test_fs_create_basic() {
         let fixture = Operation::FsCreate {
@@ -582,6 +1091,7 @@ mod tests {
                 path: "/home/user/new_file.txt".to_string(),
                 content: "Hello, world!".to_string(),
                 overwrite: false,
+                allow_large: false,
                 explanation: Some("Creating a new file".to_string()),
             },
             output: FsCreateOutput {
@@ -605,6 +1115,7 @@ mod tests {
                 path: "/home/user/existing_file.txt".to_string(),
                 content: "New content for the file".to_string(),
                 overwrite: true,
+                allow_large: false,
                 explanation: Some("Overwriting existing file".to_string()),
             },
             output: FsCreateOutput {
@@ -631,6 +1142,28 @@ mod tests {
                     exit_code: Some(0),
                 },
                 shell: "/bin/bash".to_string(),
+                attempts: 1,
+            },
+        };
+
+        let env = fixture_environment();
+        let actual = fixture.into_tool_output(TempContentFiles::default(), &env);
+
+        insta::assert_snapshot!(to_value(actual));
+    }
+
+    #[test]
+    fn test_shell_output_with_attempts() {
+        let fixture = Operation::Shell {
+            output: ShellOutput {
+                output: forge_domain::CommandOutput {
+                    command: "npm install".to_string(),
+                    stdout: "added 1 package".to_string(),
+                    stderr: "".to_string(),
+                    exit_code: Some(0),
+                },
+                shell: "/bin/bash".to_string(),
+                attempts: 2,
             },
         };
 
@@ -658,6 +1191,7 @@ mod tests {
                     exit_code: Some(0),
                 },
                 shell: "/bin/bash".to_string(),
+                attempts: 1,
             },
         };
 
@@ -687,6 +1221,7 @@ mod tests {
                     exit_code: Some(1),
                 },
                 shell: "/bin/bash".to_string(),
+                attempts: 1,
             },
         };
 
@@ -722,6 +1257,7 @@ mod tests {
                     exit_code: Some(0),
                 },
                 shell: "/bin/bash".to_string(),
+                attempts: 1,
             },
         };
 
@@ -752,6 +1288,7 @@ mod tests {
                     exit_code: Some(0),
                 },
                 shell: "/bin/bash".to_string(),
+                attempts: 1,
             },
         };
 
@@ -772,6 +1309,7 @@ mod tests {
                     exit_code: Some(0),
                 },
                 shell: "/bin/bash".to_string(),
+                attempts: 1,
             },
         };
 
@@ -792,6 +1330,7 @@ mod tests {
                     exit_code: Some(0),
                 },
                 shell: "/bin/bash".to_string(),
+                attempts: 1,
             },
         };
 
@@ -825,6 +1364,7 @@ mod tests {
                     exit_code: Some(0),
                 },
                 shell: "/bin/bash".to_string(),
+                attempts: 1,
             },
         };
 
@@ -1111,6 +1651,7 @@ mod tests {
                 path: "/home/user/file_with_warning.txt".to_string(),
                 content: "Content with warning".to_string(),
                 overwrite: false,
+                allow_large: false,
                 explanation: Some("Creating file with warning".to_string()),
             },
             output: FsCreateOutput {
@@ -1127,11 +1668,54 @@ mod tests {
         insta::assert_snapshot!(to_value(actual));
     }
 
+    #[test]
+    fn test_fs_remove_dir_success() {
+        let fixture = Operation::FsRemoveDir {
+            input: forge_domain::FSRemoveDir {
+                path: "/home/user/project/build".to_string(),
+                permanent: false,
+                explanation: None,
+            },
+            output: FsRemoveDirOutput {
+                snapshotted: vec![
+                    "/home/user/project/build/notes.md".to_string(),
+                    "/home/user/project/build/report.txt".to_string(),
+                ],
+                trashed: true,
+            },
+        };
+
+        let env = fixture_environment();
+
+        let actual = fixture.into_tool_output(TempContentFiles::default(), &env);
+
+        insta::assert_snapshot!(to_value(actual));
+    }
+
+    #[test]
+    fn test_fs_write_append_success() {
+        let fixture = Operation::FsWriteAppend {
+            input: forge_domain::FSWriteAppend {
+                path: "/home/user/project/data/fixtures.json".to_string(),
+                content: "  {\"id\": 2}\n]\n".to_string(),
+                explanation: None,
+            },
+            output: FsWriteAppendOutput { parts: 2, total_lines: 4 },
+        };
+
+        let env = fixture_environment();
+
+        let actual = fixture.into_tool_output(TempContentFiles::default(), &env);
+
+        insta::assert_snapshot!(to_value(actual));
+    }
+
     #[test]
     fn test_fs_remove_success() {
         let fixture = Operation::FsRemove {
             input: forge_domain::FSRemove {
                 path: "/home/user/file_to_delete.txt".to_string(),
+                permanent: false,
                 explanation: Some("Removing unnecessary file".to_string()),
             },
         };
@@ -1210,6 +1794,7 @@ mod tests {
                 search: Some("world".to_string()),
                 operation: forge_domain::PatchOperation::Replace,
                 content: "universe".to_string(),
+                allow_large: false,
                 explanation: Some("Replacing world with universe".to_string()),
             },
             output: PatchOutput {
@@ -1234,6 +1819,7 @@ mod tests {
                 search: Some("line1".to_string()),
                 operation: forge_domain::PatchOperation::Append,
                 content: "\nnew line".to_string(),
+                allow_large: false,
                 explanation: Some("Adding new line after line1".to_string()),
             },
             output: PatchOutput {
@@ -1253,7 +1839,7 @@ mod tests {
     #[test]
     fn test_fs_undo_no_changes() {
         let fixture = Operation::FsUndo {
-            input: forge_domain::FSUndo {
+            input: FSUndo {
                 path: "/home/user/unchanged_file.txt".to_string(),
                 explanation: Some("Attempting to undo file with no changes".to_string()),
             },
@@ -1270,7 +1856,7 @@ mod tests {
     #[test]
     fn test_fs_undo_file_created() {
         let fixture = Operation::FsUndo {
-            input: forge_domain::FSUndo {
+            input: FSUndo {
                 path: "/home/user/new_file.txt".to_string(),
                 explanation: Some("Undoing operation resulted in file creation".to_string()),
             },
@@ -1290,7 +1876,7 @@ mod tests {
     #[test]
     fn test_fs_undo_file_removed() {
         let fixture = Operation::FsUndo {
-            input: forge_domain::FSUndo {
+            input: FSUndo {
                 path: "/home/user/deleted_file.txt".to_string(),
                 explanation: Some("Undoing operation resulted in file removal".to_string()),
             },
@@ -1309,10 +1895,46 @@ mod tests {
         insta::assert_snapshot!(to_value(actual));
     }
 
+    #[test]
+    fn test_fs_wait_changed() {
+        let fixture = Operation::FsWait {
+            input: FSWait {
+                path: "/home/user/project/target/build.log".to_string(),
+                timeout_secs: Some(120),
+                explanation: None,
+            },
+            output: Some(FsChange::Modified),
+        };
+
+        let env = fixture_environment();
+
+        let actual = fixture.into_tool_output(TempContentFiles::default(), &env);
+
+        insta::assert_snapshot!(to_value(actual));
+    }
+
+    #[test]
+    fn test_fs_wait_timed_out() {
+        let fixture = Operation::FsWait {
+            input: FSWait {
+                path: "/home/user/project/src/main.rs".to_string(),
+                timeout_secs: None,
+                explanation: None,
+            },
+            output: None,
+        };
+
+        let env = fixture_environment();
+
+        let actual = fixture.into_tool_output(TempContentFiles::default(), &env);
+
+        insta::assert_snapshot!(to_value(actual));
+    }
+
     #[test]
     fn test_fs_undo_file_restored() {
         let fixture = Operation::FsUndo {
-            input: forge_domain::FSUndo {
+            input: FSUndo {
                 path: "/home/user/restored_file.txt".to_string(),
                 explanation: Some("Reverting changes to restore previous state".to_string()),
             },
@@ -1332,7 +1954,7 @@ mod tests {
     #[test]
     fn test_fs_undo_success() {
         let fixture = Operation::FsUndo {
-            input: forge_domain::FSUndo {
+            input: FSUndo {
                 path: "/home/user/test.txt".to_string(),
                 explanation: Some("Reverting changes to test file".to_string()),
             },
@@ -1349,6 +1971,24 @@ mod tests {
         insta::assert_snapshot!(to_value(actual));
     }
 
+    #[test]
+    fn test_fs_undo_file_change() {
+        let fixture = Operation::FsUndo {
+            input: FSUndo { path: "/home/user/test.txt".to_string(), explanation: None },
+            output: FsUndoOutput { before_undo: Some("A\nB\n".to_string()), after_undo: None },
+        };
+
+        let actual = fixture.file_changes();
+
+        let expected = vec![FileChange {
+            path: "/home/user/test.txt".to_string(),
+            kind: FileChangeKind::Deleted,
+            insertions: 0,
+            deletions: 2,
+        }];
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn test_net_fetch_success() {
         let fixture = Operation::NetFetch {
@@ -1413,6 +2053,413 @@ mod tests {
         insta::assert_snapshot!(to_value(actual));
     }
 
+    #[test]
+    fn test_git_log() {
+        let fixture = Operation::GitLog {
+            input: GitLog {
+                path: Some("/home/user/project/src/cache.rs".to_string()),
+                range: Some("v1.0.0..HEAD".to_string()),
+                max_count: None,
+                explanation: None,
+            },
+            output: vec![GitCommit {
+                hash: "8f3c2a1".to_string(),
+                author: "Jane Doe".to_string(),
+                date: "2025-06-02T10:00:00+02:00".to_string(),
+                summary: "Make cache keys case-insensitive".to_string(),
+            }],
+        };
+
+        let env = fixture_environment();
+
+        let actual = fixture.into_tool_output(TempContentFiles::default(), &env);
+
+        insta::assert_snapshot!(to_value(actual));
+    }
+
+    #[test]
+    fn test_git_blame() {
+        let commit = GitCommit {
+            hash: "8f3c2a1".to_string(),
+            author: "Jane Doe".to_string(),
+            date: "2025-06-02T10:00:00+02:00".to_string(),
+            summary: "Make cache keys case-insensitive".to_string(),
+        };
+        let fixture = Operation::GitBlame {
+            input: GitBlame {
+                path: "/home/user/project/src/cache.rs".to_string(),
+                start_line: None,
+                end_line: None,
+                explanation: None,
+            },
+            output: GitBlameOutput {
+                hunks: vec![BlameHunk {
+                    commit,
+                    start_line: 4,
+                    end_line: 5,
+                    content: "let key = key.to_lowercase();\nself.entries.get(&key)".to_string(),
+                }],
+                total_lines: 12,
+            },
+        };
+
+        let env = fixture_environment();
+
+        let actual = fixture.into_tool_output(TempContentFiles::default(), &env);
+
+        insta::assert_snapshot!(to_value(actual));
+    }
+
+    #[test]
+    fn test_git_commit_message() {
+        let fixture = Operation::GitCommitMessage {
+            diff: concat!(
+                " src/cache.rs | 3 ++-\n",
+                " 1 file changed, 2 insertions(+), 1 deletion(-)\n\n",
+                "diff --git a/src/cache.rs b/src/cache.rs\n",
+                "@@ -10,3 +10,4 @@ impl Cache {\n",
+                "-        self.entries.get(key)\n",
+                "+        let key = key.to_lowercase();\n",
+                "+        self.entries.get(&key)\n",
+            )
+            .to_string(),
+            recent_commits: vec![GitCommit {
+                hash: "8f3c2a1".to_string(),
+                author: "Jane Doe".to_string(),
+                date: "2025-06-02T10:00:00+02:00".to_string(),
+                summary: "feat(cache): add a cache of the entries".to_string(),
+            }],
+        };
+
+        let env = fixture_environment();
+
+        let actual = fixture.into_tool_output(TempContentFiles::default(), &env);
+
+        insta::assert_snapshot!(to_value(actual));
+    }
+
+    fn fixture_scaffold() -> Operation {
+        Operation::Scaffold {
+            input: Scaffold {
+                template: "service".to_string(),
+                variables: [("name".to_string(), "billing".to_string())].into(),
+                path: None,
+                explanation: None,
+            },
+            root: PathBuf::from("/home/user/project"),
+            files: vec![
+                ScaffoldFile {
+                    path: "/home/user/project/billing/migrations/.gitkeep".to_string(),
+                    content: String::new(),
+                },
+                ScaffoldFile {
+                    path: "/home/user/project/billing/src/lib.rs".to_string(),
+                    content: "pub struct Billing;\n\nimpl Billing {}\n".to_string(),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_scaffold() {
+        let fixture = fixture_scaffold();
+
+        let env = fixture_environment();
+
+        let actual = fixture.into_tool_output(TempContentFiles::default(), &env);
+
+        insta::assert_snapshot!(to_value(actual));
+    }
+
+    #[test]
+    fn test_scaffold_file_changes() {
+        let fixture = fixture_scaffold();
+
+        let actual = fixture.file_changes();
+
+        let expected = vec![
+            FileChange {
+                path: "/home/user/project/billing/migrations/.gitkeep".to_string(),
+                kind: FileChangeKind::Added,
+                insertions: 0,
+                deletions: 0,
+            },
+            FileChange {
+                path: "/home/user/project/billing/src/lib.rs".to_string(),
+                kind: FileChangeKind::Added,
+                insertions: 3,
+                deletions: 0,
+            },
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    fn fixture_lint() -> Operation {
+        Operation::Lint {
+            input: Lint {
+                paths: vec![
+                    "/home/user/project/src/lib.rs".to_string(),
+                    "/home/user/project/README.md".to_string(),
+                ],
+                fix: true,
+                explanation: None,
+            },
+            output: LintOutput {
+                diagnostics: vec![Diagnostic {
+                    path: "/home/user/project/src/lib.rs".to_string(),
+                    line: 12,
+                    column: 9,
+                    rule: Some("clippy::len_zero".to_string()),
+                    severity: LintSeverity::Warning,
+                    message: "length comparison to zero".to_string(),
+                    fixes: vec![],
+                }],
+                fixes: vec![LintFix {
+                    path: "/home/user/project/src/lib.rs".to_string(),
+                    applied: 1,
+                    before: "fn one() -> u32 {\n    return 1;\n}\n".to_string(),
+                    after: "fn one() -> u32 {\n    1\n}\n".to_string(),
+                }],
+                skipped: vec!["/home/user/project/README.md".to_string()],
+            },
+        }
+    }
+
+    #[test]
+    fn test_lint() {
+        let fixture = fixture_lint();
+
+        let env = fixture_environment();
+
+        let actual = fixture.into_tool_output(TempContentFiles::default(), &env);
+
+        insta::assert_snapshot!(to_value(actual));
+    }
+
+    #[test]
+    fn test_lint_file_changes() {
+        let fixture = fixture_lint();
+
+        let actual = fixture.file_changes();
+
+        let expected = vec![FileChange {
+            path: "/home/user/project/src/lib.rs".to_string(),
+            kind: FileChangeKind::Modified,
+            insertions: 1,
+            deletions: 1,
+        }];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_process_env() {
+        let fixture = Operation::ProcessEnv {
+            input: ProcessEnv { name: None, explanation: None },
+            output: vec![
+                EnvVar { name: "AWS_SECRET_ACCESS_KEY".to_string(), value: None },
+                EnvVar {
+                    name: "PATH".to_string(),
+                    value: Some("/usr/local/bin:/usr/bin".to_string()),
+                },
+            ],
+        };
+
+        let env = fixture_environment();
+
+        let actual = fixture.into_tool_output(TempContentFiles::default(), &env);
+
+        insta::assert_snapshot!(to_value(actual));
+    }
+
+    #[test]
+    fn test_discover_tasks() {
+        let fixture = Operation::DiscoverTasks {
+            input: DiscoverTasks { path: None, explanation: None },
+            root: PathBuf::from("/home/user/project"),
+            output: vec![
+                Runnable {
+                    tool: BuildTool::Cargo,
+                    name: "test -p billing".to_string(),
+                    command: "cargo test -p billing".to_string(),
+                    description: Some("Run the tests of billing".to_string()),
+                },
+                Runnable {
+                    tool: BuildTool::Make,
+                    name: "release".to_string(),
+                    command: "make release".to_string(),
+                    description: None,
+                },
+            ],
+        };
+
+        let env = fixture_environment();
+
+        let actual = fixture.into_tool_output(TempContentFiles::default(), &env);
+
+        insta::assert_snapshot!(to_value(actual));
+    }
+
+    #[test]
+    fn test_toolchain_info() {
+        let fixture = Operation::ToolchainInfo {
+            input: ToolchainInfo { refresh: false, explanation: None },
+            output: vec![
+                Toolchain::parse("rustc", "rustc 1.80.0 (051478957 2024-07-21)"),
+                Toolchain::parse("cargo", "cargo 1.80.0 (376290515 2024-07-16)"),
+                Toolchain::parse("node", "v20.11.0"),
+            ],
+        };
+
+        let env = fixture_environment();
+
+        let actual = fixture.into_tool_output(TempContentFiles::default(), &env);
+
+        insta::assert_snapshot!(to_value(actual));
+    }
+
+    #[test]
+    fn test_time_get() {
+        let fixture = Operation::TimeGet {
+            input: TimeGet { timezone: Some("+05:30".to_string()), ..Default::default() },
+            output: TimeReport {
+                zone: "+05:30".parse().unwrap(),
+                time: "2024-05-01T14:30:00+05:30".parse().unwrap(),
+            },
+        };
+
+        let env = fixture_environment();
+
+        let actual = fixture.into_tool_output(TempContentFiles::default(), &env);
+
+        insta::assert_snapshot!(to_value(actual));
+    }
+
+    #[test]
+    fn test_reminder_schedule() {
+        let fixture = Operation::ReminderSchedule {
+            input: ScheduleReminder {
+                message: "Check the deployment".to_string(),
+                after: Some("2h".to_string()),
+                ..Default::default()
+            },
+            reminder: Reminder {
+                message: "Check the deployment".to_string(),
+                due: "2024-05-01T11:00:00Z".parse().unwrap(),
+                shown: false,
+            },
+        };
+
+        let env = fixture_environment();
+
+        let actual = fixture.into_tool_output(TempContentFiles::default(), &env);
+
+        insta::assert_snapshot!(to_value(actual));
+    }
+
+    #[test]
+    fn test_calculate() {
+        let fixture = Operation::Calculate {
+            input: Calculate {
+                expression: "(1 GB - 200 MB) / 3 in MB".to_string(),
+                ..Default::default()
+            },
+            output: Calculation {
+                value: "266.666666666667".to_string(),
+                fraction: Some("800/3".to_string()),
+                unit: Some("MB".to_string()),
+            },
+        };
+
+        let env = fixture_environment();
+
+        let actual = fixture.into_tool_output(TempContentFiles::default(), &env);
+
+        insta::assert_snapshot!(to_value(actual));
+    }
+
+    #[test]
+    fn test_archive_list() {
+        let fixture = Operation::ArchiveList {
+            input: ArchiveList {
+                path: "/home/user/project/downloads/release.tar.gz".to_string(),
+                ..Default::default()
+            },
+            output: vec![
+                ArchiveEntry {
+                    path: "forge/".to_string(),
+                    kind: ArchiveEntryKind::Directory,
+                    size: 0,
+                },
+                ArchiveEntry {
+                    path: "forge/bin/forge".to_string(),
+                    kind: ArchiveEntryKind::File,
+                    size: 4096,
+                },
+                ArchiveEntry {
+                    path: "forge/latest".to_string(),
+                    kind: ArchiveEntryKind::Link,
+                    size: 0,
+                },
+            ],
+        };
+
+        let env = fixture_environment();
+
+        let actual = fixture.into_tool_output(TempContentFiles::default(), &env);
+
+        insta::assert_snapshot!(to_value(actual));
+    }
+
+    #[test]
+    fn test_archive_extract() {
+        let fixture = Operation::ArchiveExtract {
+            input: ArchiveExtract {
+                path: "/home/user/project/downloads/release.tar.gz".to_string(),
+                ..Default::default()
+            },
+            output: ArchiveExtraction {
+                format: ArchiveFormat::TarGz,
+                destination: "/home/user/project/.forge/tmp/conversation/release".into(),
+                files: 1,
+                size: 4096,
+                skipped: vec!["forge/latest".to_string()],
+            },
+        };
+
+        let env = fixture_environment();
+
+        let actual = fixture.into_tool_output(TempContentFiles::default(), &env);
+
+        insta::assert_snapshot!(to_value(actual));
+    }
+
+    #[test]
+    fn test_image_diff() {
+        let fixture = Operation::ImageDiff {
+            input: ImageDiff {
+                before: "/home/user/project/shots/before.png".to_string(),
+                after: "/home/user/project/shots/after.png".to_string(),
+                ..Default::default()
+            },
+            output: ImageDiffOutput {
+                comparison: forge_domain::ImageComparison {
+                    before: (800, 600),
+                    after: (800, 640),
+                    changed_pixels: 40_000,
+                    total_pixels: 512_000,
+                    region: Some(forge_domain::DiffRegion { x: 0, y: 580, width: 800, height: 60 }),
+                },
+                diff: forge_domain::Image::new_bytes(vec![137, 80, 78, 71], "image/png"),
+            },
+        };
+
+        let env = fixture_environment();
+
+        let actual = fixture.into_tool_output(TempContentFiles::default(), &env);
+
+        insta::assert_snapshot!(to_value(actual));
+    }
+
     #[test]
     fn test_shell_success() {
         let fixture = Operation::Shell {
@@ -1424,6 +2471,7 @@ mod tests {
                     exit_code: Some(0),
                 },
                 shell: "/bin/bash".to_string(),
+                attempts: 1,
             },
         };
 
@@ -1436,7 +2484,55 @@ mod tests {
 
     #[test]
     fn test_attempt_completion() {
-        let fixture = Operation::AttemptCompletion;
+        let fixture = Operation::AttemptCompletion {
+            input: AttemptCompletion::default(),
+            check: VerificationCheck::default(),
+            grounding: GroundingCheck::default(),
+        };
+
+        let env = fixture_environment();
+
+        let actual = fixture.into_tool_output(TempContentFiles::default(), &env);
+
+        insta::assert_snapshot!(to_value(actual));
+    }
+
+    #[test]
+    fn test_attempt_completion_unverified() {
+        let fixture = Operation::AttemptCompletion {
+            input: AttemptCompletion {
+                result: "Fixed the parser".to_string(),
+                verification: vec!["cargo test".to_string(), "cargo clippy".to_string()],
+                ..Default::default()
+            },
+            check: VerificationCheck {
+                verified: vec!["cargo test".to_string()],
+                unverified: vec!["cargo clippy".to_string()],
+                missing: false,
+            },
+            grounding: GroundingCheck::default(),
+        };
+
+        let env = fixture_environment();
+
+        let actual = fixture.into_tool_output(TempContentFiles::default(), &env);
+
+        insta::assert_snapshot!(to_value(actual));
+    }
+
+    #[test]
+    fn test_attempt_completion_ungrounded() {
+        let fixture = Operation::AttemptCompletion {
+            input: AttemptCompletion {
+                result: "`parse_config` reads `server.port`".to_string(),
+                ..Default::default()
+            },
+            check: VerificationCheck::default(),
+            grounding: GroundingCheck {
+                grounded: vec![Claim::Symbol("parse_config".to_string())],
+                unfounded: vec![Claim::ConfigKey("server.port".to_string())],
+            },
+        };
 
         let env = fixture_environment();
 
@@ -1,11 +1,13 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Duration;
 
 use async_recursion::async_recursion;
 use derive_setters::Setters;
+use forge_display::TitleFormat;
 use forge_domain::*;
 use forge_template::Element;
+use futures::StreamExt;
 use serde_json::Value;
 use tracing::{debug, info, warn};
 
@@ -14,6 +16,17 @@ use crate::compact::Compactor;
 
 pub type ArcSender = Arc<tokio::sync::mpsc::Sender<anyhow::Result<ChatResponse>>>;
 
+/// Number of times a model may send malformed tool call arguments in a turn
+/// when `max_tool_failure_per_turn` isn't configured
+const MAX_MALFORMED_TOOL_CALL_ATTEMPTS: usize = 3;
+
+/// Time shown in the prompts of seeded runs, which don't depend on the clock
+const SEEDED_TIME: &str = "not shown in reproducible runs";
+
+/// Lines of the output of failing tests fed back to the agent, the last ones
+/// holding the summary of the failures
+const TEST_OUTPUT_LINES: usize = 100;
+
 #[derive(Clone, Setters)]
 #[setters(into, strip_option)]
 pub struct Orchestrator<S> {
@@ -24,7 +37,15 @@ pub struct Orchestrator<S> {
     tool_definitions: Vec<ToolDefinition>,
     models: Vec<Model>,
     files: Vec<String>,
+    /// Learnings about the project included in the system prompts
+    memory: Option<ProjectMemory>,
+    /// Content of the context items the user pinned, in the system prompts
+    pins: Vec<PinnedContent>,
     current_time: chrono::DateTime<chrono::Local>,
+    /// Overrides the tool choice of the agents for the request
+    tool_choice: Option<ToolChoice>,
+    /// Limits on the tools running at once across the conversations
+    governor: ResourceGovernor,
 }
 
 impl<S: AgentService> Orchestrator<S> {
@@ -42,7 +63,11 @@ impl<S: AgentService> Orchestrator<S> {
             tool_definitions: Default::default(),
             models: Default::default(),
             files: Default::default(),
+            memory: Default::default(),
+            pins: Default::default(),
             current_time,
+            tool_choice: Default::default(),
+            governor: Default::default(),
         }
     }
 
@@ -51,6 +76,17 @@ impl<S: AgentService> Orchestrator<S> {
         &self.conversation
     }
 
+    /// Time shown in the prompts, except in seeded runs
+    fn current_time(&self) -> String {
+        if self.conversation.seed.is_some() {
+            SEEDED_TIME.to_string()
+        } else {
+            self.current_time
+                .format("%Y-%m-%d %H:%M:%S %:z")
+                .to_string()
+        }
+    }
+
     // Helper function to get all tool results from a vector of tool calls
     #[async_recursion]
     async fn execute_tool_calls(
@@ -58,6 +94,7 @@ impl<S: AgentService> Orchestrator<S> {
         agent: &Agent,
         tool_calls: &[ToolCallFull],
         tool_context: &mut ToolCallContext,
+        turn: usize,
     ) -> anyhow::Result<Vec<(ToolCallFull, ToolResult)>> {
         // Always process tool calls sequentially
         let mut tool_call_records = Vec::with_capacity(tool_calls.len());
@@ -68,10 +105,33 @@ impl<S: AgentService> Orchestrator<S> {
                 .await?;
 
             // Execute the tool
+            let started_at = chrono::Utc::now();
             let tool_result = self
                 .services
                 .call(agent, tool_context, tool_call.clone())
                 .await;
+            tool_context.timeline.push(TimelineSpan::new(
+                turn,
+                agent.id.clone(),
+                SpanKind::ToolCall {
+                    name: tool_call.name.clone(),
+                    success: !tool_result.is_error(),
+                },
+                started_at,
+            ));
+
+            let sample = ToolCallSample {
+                tool: tool_call.name.clone(),
+                success: !tool_result.is_error(),
+                duration: (chrono::Utc::now() - started_at)
+                    .to_std()
+                    .unwrap_or_default(),
+                result_tokens: ContextMessage::tool_result(tool_result.clone()).token_count()
+                    as u64,
+            };
+            if let Err(error) = self.services.record_tool_stats(sample).await {
+                warn!(error = ?error, "Failed to record the tool statistics");
+            }
 
             if tool_result.is_error() {
                 warn!(
@@ -102,6 +162,69 @@ impl<S: AgentService> Orchestrator<S> {
         Ok(())
     }
 
+    /// Posts the reminders that came due into the context, so that the next
+    /// request answers them, and shows them to the user unless they were shown
+    /// while the user was idle
+    async fn post_due_reminders(
+        &mut self,
+        mut context: Context,
+        model_id: &ModelId,
+    ) -> anyhow::Result<Context> {
+        for reminder in Reminder::take_due(&mut self.conversation.reminders, chrono::Utc::now()) {
+            if !reminder.shown {
+                self.send(ChatResponse::Text {
+                    text: TitleFormat::action("Reminder")
+                        .sub_title(&reminder.message)
+                        .to_string(),
+                    is_complete: true,
+                    is_md: false,
+                })
+                .await?;
+            }
+            let elm = Element::new("reminder")
+                .attr("due", reminder.due.to_rfc3339())
+                .text(reminder.message);
+            context = context.add_message(ContextMessage::user(elm, model_id.clone().into()));
+        }
+        Ok(context)
+    }
+
+    /// Posts the contents of the subscribed MCP resources that changed into the
+    /// context, so that long sessions don't work with stale data
+    async fn post_resource_updates(
+        &self,
+        mut context: Context,
+        model_id: &ModelId,
+    ) -> anyhow::Result<Context> {
+        for update in self
+            .services
+            .take_resource_updates(&self.conversation.id)
+            .await
+        {
+            let reference = update.reference();
+            self.send(ChatResponse::Text {
+                text: TitleFormat::action("Resource Updated")
+                    .sub_title(&reference)
+                    .to_string(),
+                is_complete: true,
+                is_md: false,
+            })
+            .await?;
+            for content in update.contents {
+                context = context.add_message(match content {
+                    AttachmentContent::Image(image) => ContextMessage::Image(image),
+                    AttachmentContent::FileContent(content) => {
+                        let elm = Element::new("resource_updated")
+                            .attr("reference", &reference)
+                            .cdata(content);
+                        ContextMessage::user(elm, model_id.clone().into())
+                    }
+                });
+            }
+        }
+        Ok(context)
+    }
+
     /// Get the allowed tools for an agent
     fn get_allowed_tools(&mut self, agent: &Agent) -> anyhow::Result<Vec<ToolDefinition>> {
         let completion = ToolsDiscriminants::ForgeToolAttemptCompletion;
@@ -113,6 +236,7 @@ impl<S: AgentService> Orchestrator<S> {
                     .iter()
                     .filter(|tool| tool.name != completion.name())
                     .filter(|tool| allowed.contains(&tool.name))
+                    .filter(|tool| self.conversation.is_tool_enabled(&tool.name))
                     .cloned(),
             );
         }
@@ -122,6 +246,121 @@ impl<S: AgentService> Orchestrator<S> {
         Ok(tools)
     }
 
+    /// Records workspace events in the journal. Failures are only logged as
+    /// the journal is meant for external tools.
+    async fn journal(&self, entries: Vec<JournalEntry>) {
+        if let Err(error) = self.services.journal(entries).await {
+            warn!(error = ?error, "Failed to record the journal");
+        }
+    }
+
+    /// Checks a response with the moderation of the conversation and returns
+    /// the reason when it's blocked. The response is blocked when the
+    /// moderation service can't be reached.
+    async fn moderate(
+        &self,
+        agent: &Agent,
+        content: &str,
+        tool_calls: &[ToolCallFull],
+    ) -> Option<String> {
+        let moderation = self.conversation.moderation.as_ref()?;
+        let request = ModerationRequest { content, tool_calls };
+
+        let local = moderation.check(&request);
+        let remote = match (&local, &moderation.endpoint) {
+            (ModerationVerdict::Block { .. }, _) | (_, None) => ModerationVerdict::Allow,
+            (_, Some(endpoint)) => self
+                .services
+                .moderate(endpoint, &request)
+                .await
+                .unwrap_or_else(|error| ModerationVerdict::Block {
+                    reason: format!("Moderation service unavailable: {error}"),
+                }),
+        };
+
+        let mut blocked = None;
+        for verdict in [local, remote] {
+            match verdict {
+                ModerationVerdict::Allow => {}
+                ModerationVerdict::Flag { reason } => {
+                    warn!(agent_id = %agent.id, reason = %reason, "Response flagged by moderation");
+                }
+                ModerationVerdict::Block { reason } => {
+                    warn!(agent_id = %agent.id, reason = %reason, "Response blocked by moderation");
+                    blocked.get_or_insert(reason);
+                }
+            }
+        }
+
+        blocked
+    }
+
+    /// Files that were read or changed in the conversation, including the
+    /// changes of the current turn
+    fn touched_files(&self, changes: &ChangeSummary) -> BTreeSet<String> {
+        let mut files = self.conversation.touched_files();
+        files.extend(changes.files.iter().map(|file| file.path.clone()));
+        files
+    }
+
+    /// Runs the tests when the files changed since they last ran match the
+    /// test after edit policy. Nothing is run once the failures were fed back
+    /// `max_attempts` times, so that the turn can complete.
+    async fn test_after_edit(
+        &self,
+        untested: &mut ChangeSummary,
+        failures: usize,
+    ) -> anyhow::Result<Option<CommandOutput>> {
+        let Some(policy) = self.conversation.test_after_edit.as_ref() else {
+            return Ok(None);
+        };
+        let cwd = self.environment.cwd.clone();
+        if !policy.is_triggered(untested, &cwd) {
+            return Ok(None);
+        }
+        *untested = ChangeSummary::default();
+        if failures >= policy.max_attempts() {
+            warn!(failures, "Tests still failing after the edits");
+            return Ok(None);
+        }
+
+        let runnables = match policy.command {
+            Some(_) => Vec::new(),
+            None => self
+                .services
+                .discover_tasks(cwd.clone())
+                .await
+                .unwrap_or_else(|error| {
+                    warn!(error = ?error, "Failed to discover the test command");
+                    Vec::new()
+                }),
+        };
+        let Some(command) = policy.test_command(&runnables) else {
+            warn!("No test command to run after the edits");
+            return Ok(None);
+        };
+
+        self.send(ChatResponse::Text {
+            text: TitleFormat::action("Test after edit")
+                .sub_title(&command)
+                .to_string(),
+            is_complete: true,
+            is_md: false,
+        })
+        .await?;
+        match self
+            .services
+            .run_command(command, cwd, self.conversation.env.clone())
+            .await
+        {
+            Ok(output) => Ok(Some(output)),
+            Err(error) => {
+                warn!(error = ?error, "Failed to run the tests after the edits");
+                Ok(None)
+            }
+        }
+    }
+
     /// Checks if parallel tool calls is supported by agent
     fn is_parallel_tool_call_supported(&self, agent: &Agent) -> bool {
         agent
@@ -134,27 +373,10 @@ impl<S: AgentService> Orchestrator<S> {
 
     // Returns if agent supports tool or not.
     fn is_tool_supported(&self, agent: &Agent) -> anyhow::Result<bool> {
-        let model_id = agent
-            .model
-            .as_ref()
-            .ok_or(Error::MissingModel(agent.id.clone()))?;
-
-        // Check if at agent level tool support is defined
-        let tool_supported = match agent.tool_supported {
-            Some(tool_supported) => tool_supported,
-            None => {
-                // If not defined at agent level, check model level
-
-                let model = self.models.iter().find(|model| &model.id == model_id);
-                model
-                    .and_then(|model| model.tools_supported)
-                    .unwrap_or_default()
-            }
-        };
-
+        let tool_supported = tool_support(agent, &self.models)?;
         debug!(
             agent_id = %agent.id,
-            model_id = %model_id,
+            model_id = ?agent.model,
             tool_supported,
             "Tool support check"
         );
@@ -162,19 +384,10 @@ impl<S: AgentService> Orchestrator<S> {
     }
 
     fn is_reasoning_supported(&self, agent: &Agent) -> anyhow::Result<bool> {
-        let model_id = agent
-            .model
-            .as_ref()
-            .ok_or(Error::MissingModel(agent.id.clone()))?;
-
-        let model = self.models.iter().find(|model| &model.id == model_id);
-        let reasoning_supported = model
-            .and_then(|model| model.supports_reasoning)
-            .unwrap_or_default();
-
+        let reasoning_supported = reasoning_support(agent, &self.models)?;
         debug!(
             agent_id = %agent.id,
-            model_id = %model_id,
+            model_id = ?agent.model,
             reasoning_supported,
             "Reasoning support check"
         );
@@ -189,19 +402,22 @@ impl<S: AgentService> Orchestrator<S> {
     ) -> anyhow::Result<Context> {
         Ok(if let Some(system_prompt) = &agent.system_prompt {
             let env = self.environment.clone();
-            let mut files = self.files.clone();
-            files.sort();
+            let files = self.files.clone();
 
-            let current_time = self
-                .current_time
-                .format("%Y-%m-%d %H:%M:%S %:z")
-                .to_string();
+            let current_time = self.current_time();
 
             let tool_supported = self.is_tool_supported(agent)?;
             let supports_parallel_tool_calls = self.is_parallel_tool_call_supported(agent);
             let tool_information = match tool_supported {
                 true => None,
-                false => Some(ToolUsagePrompt::from(&self.get_allowed_tools(agent)?).to_string()),
+                false => {
+                    let tools = ToolGate::new(&self.conversation.tool_gates).filter(
+                        self.get_allowed_tools(agent)?,
+                        &agent.id,
+                        &self.touched_files(&ChangeSummary::default()),
+                    );
+                    Some(ToolUsagePrompt::from(&tools).to_string())
+                }
             };
 
             let ctx = SystemContext {
@@ -211,6 +427,13 @@ impl<S: AgentService> Orchestrator<S> {
                 tool_supported,
                 files,
                 custom_rules: agent.custom_rules.as_ref().cloned().unwrap_or_default(),
+                memory: self
+                    .memory
+                    .as_ref()
+                    .map(ToString::to_string)
+                    .unwrap_or_default(),
+                pins: self.pins.clone(),
+                response_language: self.conversation.response_language.clone(),
                 variables: variables.clone(),
                 supports_parallel_tool_calls,
             };
@@ -237,9 +460,21 @@ impl<S: AgentService> Orchestrator<S> {
             self.conversation.dispatch_event(event.clone())
         };
 
+        // Snapshot the state so that a failed turn can be rolled back
+        let checkpoint = Checkpoint::new(&self.conversation);
+
         // Execute all agent initialization with the event
         for agent_id in &target_agents {
-            self.init_agent(agent_id, &event).await?;
+            if let Err(error) = self.init_agent(agent_id, &event).await {
+                warn!(
+                    conversation_id = %self.conversation.id,
+                    agent = %agent_id,
+                    error = %error,
+                    "Turn failed, rolling back the context"
+                );
+                checkpoint.restore(&mut self.conversation, &error);
+                return Err(error);
+            }
         }
 
         Ok(())
@@ -252,16 +487,43 @@ impl<S: AgentService> Orchestrator<S> {
         tool_supported: bool,
         reasoning_supported: bool,
     ) -> anyhow::Result<ChatCompletionMessageFull> {
-        let mut transformers = TransformToolCalls::new()
-            .when(|_| !tool_supported)
-            .pipe(ImageHandling::new())
-            .pipe(DropReasoningDetails.when(|_| !reasoning_supported))
-            .pipe(ReasoningNormalizer.when(|_| reasoning_supported));
+        let mut transformers = request_transformers(tool_supported, reasoning_supported);
+        let offered = context
+            .tools
+            .iter()
+            .map(|tool| tool.name.clone())
+            .collect::<Vec<_>>();
         let response = self
             .services
             .chat_agent(model_id, transformers.transform(context))
             .await?;
-        response.into_full(!tool_supported).await
+        // The user is told when the provider stalls, while the response is
+        // still awaited
+        let sender = self.sender.clone();
+        let response: BoxStream<ChatCompletionMessage, anyhow::Error> =
+            Box::pin(response.filter(move |message| {
+                let stalled = match message {
+                    Ok(ChatCompletionMessage { stalled: Some(seconds), .. }) => Some(*seconds),
+                    _ => None,
+                };
+                if let (Some(seconds), Some(sender)) = (stalled, &sender) {
+                    let _ = sender.try_send(Ok(ChatResponse::Text {
+                        text: TitleFormat::info("Waiting for the provider")
+                            .sub_title(format!("nothing was sent for {seconds} seconds"))
+                            .to_string(),
+                        is_complete: true,
+                        is_md: false,
+                    }));
+                }
+                futures::future::ready(stalled.is_none())
+            }));
+        let mut message = response.into_full(!tool_supported).await?;
+        // Models without tool support may write the calls in fenced JSON blocks
+        // instead of `forge_tool_call` ones
+        if !tool_supported && message.tool_calls.is_empty() {
+            message.tool_calls = ToolCallFull::try_from_fenced(&message.content, &offered);
+        }
+        Ok(message)
     }
     /// Checks if compaction is needed and performs it if necessary
     async fn check_and_compact(
@@ -274,6 +536,7 @@ impl<S: AgentService> Orchestrator<S> {
         if agent.should_compact(context, estimated_tokens) {
             info!(agent_id = %agent.id, "Compaction needed");
             Compactor::new(self.services.clone())
+                .response_language(self.conversation.response_language.clone())
                 .compact(agent, context.clone(), false)
                 .await
                 .map(Some)
@@ -286,6 +549,17 @@ impl<S: AgentService> Orchestrator<S> {
     // Create a helper method with the core functionality
     async fn init_agent(&mut self, agent_id: &AgentId, event: &Event) -> anyhow::Result<()> {
         let mut tool_failure_attempts = HashMap::new();
+        let mut changes = ChangeSummary::default();
+        // Changes made since the tests last ran after an edit
+        let mut untested = ChangeSummary::default();
+        let mut test_failures = 0;
+        let mut snapshots = Vec::new();
+        let started_at = chrono::Utc::now();
+        let turn = self
+            .conversation
+            .timeline
+            .last()
+            .map_or(0, |span| span.turn + 1);
         let variables = self.conversation.variables.clone();
         debug!(
             conversation_id = %self.conversation.id,
@@ -307,10 +581,13 @@ impl<S: AgentService> Orchestrator<S> {
         context = context.conversation_id(self.conversation.id);
 
         // Reset all the available tools
-        context = context.tools(self.get_allowed_tools(&agent)?);
+        let allowed_tools = self.get_allowed_tools(&agent)?;
+        context = context.tools(allowed_tools.clone());
+        context.tool_choice = self.tool_choice.clone().or(agent.tool_choice.clone());
 
         // Render the system prompts with the variables
         context = self.set_system_prompt(context, &agent, &variables).await?;
+        let message_index = context.messages.len();
 
         // Render user prompts
         context = self
@@ -333,6 +610,13 @@ impl<S: AgentService> Orchestrator<S> {
             context = context.max_tokens(max_tokens.value() as usize);
         }
 
+        // Seeded runs sample greedily so that they can be reproduced
+        if let Some(seed) = self.conversation.seed {
+            context = context
+                .seed(seed)
+                .temperature(Temperature::new_unchecked(0.0));
+        }
+
         if reasoning_supported {
             // Add reasoning specific params to context only if reasoning is supported
             // by underlying model
@@ -371,12 +655,26 @@ impl<S: AgentService> Orchestrator<S> {
         let mut empty_tool_call_count = 0;
         let mut request_count = 0;
 
+        // Budget and progress of the turn when the agent runs without approval
+        let autonomy = self.conversation.autonomy.clone();
+        let mut tool_call_stats = ToolCallStats::default();
+
         // Retrieve the number of requests allowed per tick.
         let max_requests_per_turn = self.conversation.max_requests_per_turn;
 
         while !is_complete {
+            // Offer the tools that are allowed in the current phase of the conversation
+            context.tools = ToolGate::new(&self.conversation.tool_gates).filter(
+                allowed_tools.clone(),
+                &agent.id,
+                &self.touched_files(&changes),
+            );
+            context = self.post_due_reminders(context, &model_id).await?;
+            context = self.post_resource_updates(context, &model_id).await?;
+
             // Set context for the current loop iteration
             self.conversation.context = Some(context.clone());
+            self.conversation.record_request(&context);
             self.services.update(self.conversation.clone()).await?;
 
             // Run the main chat request and compaction check in parallel
@@ -398,19 +696,21 @@ impl<S: AgentService> Orchestrator<S> {
                 }),
             );
 
+            // Tool calls with malformed arguments are sent back to the model to be fixed
+            let main_request = async { recover_malformed_tool_call(main_request.await) };
+
             // Prepare compaction task that runs in parallel
 
             // Execute both operations in parallel
-            let (
-                ChatCompletionMessageFull {
-                    tool_calls,
-                    content,
-                    mut usage,
-                    reasoning,
-                    reasoning_details,
-                },
-                compaction_result,
-            ) = tokio::try_join!(main_request, self.check_and_compact(&agent, &context))?;
+            let generation_started_at = chrono::Utc::now();
+            let (message, compaction_result) =
+                tokio::try_join!(main_request, self.check_and_compact(&agent, &context))?;
+            self.conversation.timeline.push(TimelineSpan::new(
+                turn,
+                agent.id.clone(),
+                SpanKind::Generation { model: model_id.clone() },
+                generation_started_at,
+            ));
 
             // Apply compaction result if it completed successfully
             match compaction_result {
@@ -423,6 +723,51 @@ impl<S: AgentService> Orchestrator<S> {
                 }
             }
 
+            let ChatCompletionMessageFull {
+                tool_calls,
+                content,
+                mut usage,
+                reasoning,
+                reasoning_details,
+            } = match message {
+                Ok(message) => message,
+                Err(call) => {
+                    let allowed_max_attempts = self
+                        .conversation
+                        .max_tool_failure_per_turn
+                        .unwrap_or(MAX_MALFORMED_TOOL_CALL_ATTEMPTS);
+                    let current_attempts = tool_failure_attempts
+                        .entry(call.name.clone())
+                        .and_modify(|count| *count += 1)
+                        .or_insert(1);
+
+                    warn!(
+                        agent_id = %agent.id,
+                        model_id = %model_id,
+                        tool = %call.name,
+                        error = %call.error,
+                        attempts = *current_attempts,
+                        "Tool call has malformed arguments"
+                    );
+
+                    if *current_attempts > allowed_max_attempts {
+                        return Err(Error::MalformedToolCall(call).into());
+                    }
+
+                    let attempts_left = allowed_max_attempts.saturating_sub(*current_attempts);
+                    let message = Element::new("tool_call_error")
+                        .attr("tool", &call.name)
+                        .append(Element::new("error").text(&call.error))
+                        .append(Element::new("arguments").cdata(&call.arguments))
+                        .append(Element::new("retry").text(format!(
+                            "The arguments of this tool call are not valid JSON. You have {attempts_left} attempt(s) remaining out of a maximum of {allowed_max_attempts}. Fix the arguments and call the tool again."
+                        )));
+                    context =
+                        context.add_message(ContextMessage::user(message, model_id.clone().into()));
+                    continue;
+                }
+            };
+
             // Set estimated tokens
             usage.estimated_tokens = context.token_count();
 
@@ -435,13 +780,47 @@ impl<S: AgentService> Orchestrator<S> {
             // Send the usage information if available
             self.send(ChatResponse::Usage(usage.clone())).await?;
 
+            // A blocked response ends the turn without being shown nor having its tool
+            // calls executed
+            if let Some(reason) = self.moderate(&agent, &content, &tool_calls).await {
+                self.send(ChatResponse::Blocked { reason }).await?;
+                break;
+            }
+
+            // An autonomous agent stops where the user has to decide, the question is kept
+            // in the context so that the next prompt can answer it
+            if autonomy.is_some()
+                && let Some(question) = Autonomy::decision_required(&tool_calls)
+            {
+                context = context.append_message(
+                    format!("{content}\n\n{question}").trim(),
+                    reasoning_details,
+                    vec![],
+                );
+                self.conversation.context = Some(context.clone());
+                self.services.update(self.conversation.clone()).await?;
+                self.send(ChatResponse::Interrupt {
+                    reason: InterruptionReason::DecisionRequired { question },
+                })
+                .await?;
+                break;
+            }
+
             let has_no_tool_calls = tool_calls.is_empty();
 
             debug!(agent_id = %agent.id, tool_call_count = tool_calls.len(), "Tool call count");
 
-            is_complete = tool_calls.iter().any(|call| Tools::is_complete(&call.name));
+            // Without tools the response itself answers the request
+            let tools_disabled = context.tool_choice == Some(ToolChoice::None);
+            is_complete =
+                tools_disabled || tool_calls.iter().any(|call| Tools::is_complete(&call.name));
+
+            // A specific tool is only forced on the first request of the turn
+            if matches!(context.tool_choice, Some(ToolChoice::Call(_))) {
+                context.tool_choice = Some(ToolChoice::Auto);
+            }
 
-            if !is_complete && !has_no_tool_calls {
+            if tools_disabled || (!is_complete && !has_no_tool_calls) {
                 // If task is completed we would have already displayed a message so we can
                 // ignore the content that's collected from the stream
                 // NOTE: Important to send the content messages before the tool call happens
@@ -456,7 +835,7 @@ impl<S: AgentService> Orchestrator<S> {
             }
 
             if let Some(reasoning) = reasoning.as_ref()
-                && !is_complete
+                && (!is_complete || tools_disabled)
                 && reasoning_supported
             {
                 // If reasoning is present, send it as a separate message
@@ -464,8 +843,22 @@ impl<S: AgentService> Orchestrator<S> {
                     .await?;
             }
 
-            let mut tool_context =
-                ToolCallContext::new(self.conversation.tasks.clone()).sender(self.sender.clone());
+            let mut tool_context = ToolCallContext::new(self.conversation.tasks.clone())
+                .sender(self.sender.clone())
+                .conversation_id(Some(self.conversation.id))
+                .turn_started_at(Some(started_at))
+                .read_before_write(self.conversation.read_before_write)
+                .read_files(self.conversation.read_files.clone())
+                .tool_gates(self.conversation.tool_gates.clone())
+                .touched_files(self.touched_files(&changes))
+                .disabled_mcp_servers(self.conversation.disabled_mcp_servers.clone())
+                .verify_answers(self.conversation.verify_answers)
+                .scratch_dir(Some(self.environment.scratch_path(&self.conversation.id)))
+                .env(self.conversation.env.clone())
+                .shell_retry(self.conversation.shell_retry.clone())
+                .scaffolds(self.conversation.scaffolds.clone())
+                .reminders(self.conversation.reminders.clone())
+                .governor(self.governor.clone());
 
             // Check if tool calls are within allowed limits if max_tool_failure_per_turn is
             // configured
@@ -474,8 +867,23 @@ impl<S: AgentService> Orchestrator<S> {
 
             // Process tool calls and update context
             let mut tool_call_records = self
-                .execute_tool_calls(&agent, &tool_calls, &mut tool_context)
+                .execute_tool_calls(&agent, &tool_calls, &mut tool_context, turn)
                 .await?;
+            for (_, result) in &tool_call_records {
+                tool_call_stats.record(result.is_error());
+            }
+            let step_changes = std::mem::take(&mut tool_context.changes);
+            self.journal(JournalEntry::from_changes(
+                self.conversation.id,
+                &step_changes,
+            ))
+            .await;
+            untested.merge(step_changes.clone());
+            changes.merge(step_changes);
+            snapshots.append(&mut tool_context.snapshots);
+            self.conversation
+                .timeline
+                .append(&mut tool_context.timeline);
 
             // Update the tool call attempts, if the tool call is an error
             // we increment the attempts, otherwise we remove it from the attempts map
@@ -503,7 +911,7 @@ impl<S: AgentService> Orchestrator<S> {
 
             context = context.append_message(content.clone(), reasoning_details, tool_call_records);
 
-            if has_no_tool_calls {
+            if has_no_tool_calls && !tools_disabled {
                 // No tool calls present, which doesn't mean task is complete so reprompt the
                 // agent to ensure the task complete.
                 let content = self
@@ -538,6 +946,39 @@ impl<S: AgentService> Orchestrator<S> {
                 empty_tool_call_count = 0;
             }
 
+            // The tests run once the agent considers its edits done, so that it fixes the
+            // failures before the turn completes
+            if is_complete
+                && let Some(output) = self.test_after_edit(&mut untested, test_failures).await?
+            {
+                let command = output.command.clone();
+                self.journal(vec![JournalEntry::new(
+                    self.conversation.id,
+                    JournalEvent::CommandExecuted { command: command.clone() },
+                )])
+                .await;
+                changes.record_command(&command);
+
+                if output.exit_code != Some(0) {
+                    test_failures += 1;
+                    let lines = |text: &str| {
+                        let lines = text.lines().collect::<Vec<_>>();
+                        lines[lines.len().saturating_sub(TEST_OUTPUT_LINES)..].join("\n")
+                    };
+                    let message = Element::new("test_failure")
+                        .attr("command", &command)
+                        .attr_if_some("exit_code", output.exit_code)
+                        .append(Element::new("stdout").cdata(lines(&output.stdout)))
+                        .append(Element::new("stderr").cdata(lines(&output.stderr)))
+                        .append(Element::new("note").text(
+                            "The tests failed after your changes. Fix the failures, then complete the task again.",
+                        ));
+                    context =
+                        context.add_message(ContextMessage::user(message, model_id.clone().into()));
+                    is_complete = false;
+                }
+            }
+
             if allowed_limits_exceeded {
                 // Tool call retry limit exceeded, force completion
                 warn!(
@@ -563,6 +1004,8 @@ impl<S: AgentService> Orchestrator<S> {
             // Update context in the conversation
             context = SetModel::new(model_id.clone()).transform(context);
             self.conversation.tasks = tool_context.tasks;
+            self.conversation.read_files = tool_context.read_files;
+            self.conversation.reminders = tool_context.reminders;
             self.conversation.context = Some(context.clone());
             self.services.update(self.conversation.clone()).await?;
             request_count += 1;
@@ -588,6 +1031,77 @@ impl<S: AgentService> Orchestrator<S> {
                     is_complete = true;
                 }
             }
+
+            if !is_complete && let Some(autonomy) = autonomy.as_ref() {
+                let elapsed = (chrono::Utc::now() - started_at)
+                    .to_std()
+                    .unwrap_or_default();
+                if autonomy.is_exhausted(elapsed, request_count) {
+                    warn!(
+                        agent_id = %agent.id,
+                        request_count,
+                        elapsed = ?elapsed,
+                        "Autonomous agent has spent its budget"
+                    );
+                    self.send(ChatResponse::Interrupt {
+                        reason: InterruptionReason::AutonomyBudgetExhausted {
+                            elapsed,
+                            requests: request_count as u64,
+                        },
+                    })
+                    .await?;
+                    is_complete = true;
+                } else if autonomy.is_report_due(request_count) {
+                    self.send(ChatResponse::ProgressReport(ProgressReport::new(
+                        &self.conversation.tasks,
+                        &changes,
+                        std::mem::take(&mut tool_call_stats),
+                        elapsed,
+                        request_count,
+                    )))
+                    .await?;
+                }
+            }
+        }
+
+        // An autonomous agent always reports where it stopped
+        if autonomy.is_some() {
+            let elapsed = (chrono::Utc::now() - started_at)
+                .to_std()
+                .unwrap_or_default();
+            self.send(ChatResponse::ProgressReport(ProgressReport::new(
+                &self.conversation.tasks,
+                &changes,
+                tool_call_stats,
+                elapsed,
+                request_count,
+            )))
+            .await?;
+        }
+
+        // Summarize what the turn changed on disk and record it in the transcript
+        if changes.has_file_changes() {
+            self.conversation.insert_event(Event::new(
+                CHANGE_SUMMARY_EVENT,
+                serde_json::to_value(&changes).ok(),
+            ));
+            // Link the turn to the snapshots it created so that it can be reverted
+            let prompt = event
+                .value
+                .as_ref()
+                .and_then(|value| value.as_str())
+                .and_then(|value| value.lines().next())
+                .unwrap_or(&event.name)
+                .to_string();
+            self.conversation.turns.push(TurnRecord {
+                message_index,
+                started_at,
+                prompt,
+                changes: changes.clone(),
+                snapshots,
+            });
+            self.services.update(self.conversation.clone()).await?;
+            self.send(ChatResponse::ChangeSummary(changes)).await?;
         }
 
         Ok(())
@@ -620,11 +1134,7 @@ impl<S: AgentService> Orchestrator<S> {
         {
             let event_context = EventContext::new(event.clone())
                 .variables(variables.clone())
-                .current_time(
-                    self.current_time
-                        .format("%Y-%m-%d %H:%M:%S %:z")
-                        .to_string(),
-                );
+                .current_time(self.current_time());
             debug!(event_context = ?event_context, "Event context");
             Some(
                 self.services
@@ -643,3 +1153,177 @@ impl<S: AgentService> Orchestrator<S> {
         Ok(context)
     }
 }
+
+/// State of a conversation before a turn. If the turn fails halfway the
+/// context is restored from it, so that a retry starts from a clean state.
+struct Checkpoint {
+    context: Option<Context>,
+    tasks: TaskList,
+    read_files: BTreeSet<String>,
+    reminders: Vec<Reminder>,
+}
+
+impl Checkpoint {
+    fn new(conversation: &Conversation) -> Self {
+        Self {
+            context: conversation.context.clone(),
+            tasks: conversation.tasks.clone(),
+            read_files: conversation.read_files.clone(),
+            reminders: conversation.reminders.clone(),
+        }
+    }
+
+    /// Rolls the conversation back and leaves a note for the model explaining
+    /// that the side effects of the tools that already ran were not undone.
+    fn restore(self, conversation: &mut Conversation, error: &anyhow::Error) {
+        let checkpoint_len = self
+            .context
+            .as_ref()
+            .map_or(0, |context| context.messages.len());
+        let executed_tools = conversation
+            .context
+            .iter()
+            .flat_map(|context| context.messages.iter().skip(checkpoint_len))
+            .filter_map(|message| match message {
+                ContextMessage::Tool(result) => {
+                    Some(Element::new("tool").text(result.name.as_str()))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let mut note = Element::new("turn_rollback")
+            .append(Element::new("error").cdata(format!("{error:#}")))
+            .append(Element::new("note").text(
+                "The previous attempt at this request failed and its messages were discarded. \
+                 Changes made by the tools that already ran (edited files, executed commands) \
+                 were not undone, verify the current state before repeating them.",
+            ));
+        if !executed_tools.is_empty() {
+            note = note.append(Element::new("executed_tools").append(executed_tools));
+        }
+
+        let context = self
+            .context
+            .unwrap_or_default()
+            .add_message(ContextMessage::user(note, None));
+        conversation.context = Some(context);
+        conversation.tasks = self.tasks;
+        conversation.read_files = self.read_files;
+        conversation.reminders = self.reminders;
+    }
+}
+
+/// Separates tool calls with malformed arguments from other failures of a chat
+/// turn, so that the model can be asked to fix the call.
+fn recover_malformed_tool_call(
+    result: anyhow::Result<ChatCompletionMessageFull>,
+) -> anyhow::Result<std::result::Result<ChatCompletionMessageFull, MalformedToolCall>> {
+    match result {
+        Ok(message) => Ok(Ok(message)),
+        Err(error) => match error.downcast_ref::<Error>() {
+            Some(Error::MalformedToolCall(call)) => Ok(Err(call.clone())),
+            _ => Err(error),
+        },
+    }
+}
+
+/// Whether the agent gets the tools natively rather than described in its
+/// system prompt, as set on the agent or else on its model
+pub(crate) fn tool_support(agent: &Agent, models: &[Model]) -> anyhow::Result<bool> {
+    let model_id = agent
+        .model
+        .as_ref()
+        .ok_or(Error::MissingModel(agent.id.clone()))?;
+    Ok(agent.tool_supported.unwrap_or_else(|| {
+        models
+            .iter()
+            .find(|model| &model.id == model_id)
+            .and_then(|model| model.tools_supported)
+            .unwrap_or_default()
+    }))
+}
+
+/// Whether the model of the agent supports reasoning
+pub(crate) fn reasoning_support(agent: &Agent, models: &[Model]) -> anyhow::Result<bool> {
+    let model_id = agent
+        .model
+        .as_ref()
+        .ok_or(Error::MissingModel(agent.id.clone()))?;
+    Ok(models
+        .iter()
+        .find(|model| &model.id == model_id)
+        .and_then(|model| model.supports_reasoning)
+        .unwrap_or_default())
+}
+
+/// Transformations applied to the context of every request sent to the
+/// provider
+pub(crate) fn request_transformers(
+    tool_supported: bool,
+    reasoning_supported: bool,
+) -> impl Transformer<Value = Context> {
+    TransformToolCalls::new()
+        .when(move |_| !tool_supported)
+        .pipe(SlimToolSchemas)
+        .pipe(ImageHandling::new())
+        .pipe(DropReasoningDetails.when(move |_| !reasoning_supported))
+        .pipe(ReasoningNormalizer.when(move |_| reasoning_supported))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_checkpoint_restore() {
+        let before = Context::default().add_message(ContextMessage::user("Fix the build", None));
+        let mut fixture = Conversation::new(ConversationId::generate(), Workflow::new(), vec![]);
+        fixture.context = Some(before.clone());
+        let checkpoint = Checkpoint::new(&fixture);
+        fixture.context = Some(
+            before
+                .clone()
+                .add_message(ContextMessage::assistant("Patching", None, None))
+                .add_tool_results(vec![
+                    ToolResult::new(ToolName::new("forge_tool_fs_patch")).success("patched"),
+                ]),
+        );
+
+        checkpoint.restore(&mut fixture, &anyhow::anyhow!("Provider unavailable"));
+
+        let actual = fixture.context.unwrap().messages;
+        assert_eq!(actual.len(), 2);
+        assert_eq!(actual[0], before.messages[0]);
+        let note = actual[1].to_text();
+        assert!(note.contains("Provider unavailable"));
+        assert!(note.contains("forge_tool_fs_patch"));
+    }
+
+    #[test]
+    fn test_recover_malformed_tool_call() {
+        let call = MalformedToolCall {
+            name: ToolName::new("forge_tool_fs_read"),
+            call_id: None,
+            arguments: "{\"path\": ".to_string(),
+            error: "EOF while parsing a value".to_string(),
+        };
+        let fixture = Err(Error::MalformedToolCall(call.clone()).into());
+
+        let actual = recover_malformed_tool_call(fixture).unwrap().unwrap_err();
+
+        let expected = call;
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_recover_malformed_tool_call_keeps_other_errors() {
+        let fixture = Err(anyhow::anyhow!("Provider unavailable"));
+
+        let actual = recover_malformed_tool_call(fixture);
+
+        assert!(actual.is_err());
+    }
+}
@@ -1,11 +1,19 @@
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use forge_domain::{
-    Attachment, ChatCompletionMessage, CommandOutput, Context, Conversation, ConversationId,
-    Environment, File, McpConfig, Model, ModelId, PatchOperation, Provider, ResultStream, Scope,
-    ToolCallFull, ToolDefinition, ToolOutput, Workflow,
+    ArchiveEntry, ArchiveExtraction, Attachment, AttachmentContent, ChatCompletionMessage,
+    CommandOutput, Context, Conversation, ConversationId, Diagnostic, EnvVar, Environment, File,
+    FileState, FsChange, ImageDiffOutput, JournalEntry, JournalPage, McpConfig, McpPrompt,
+    McpResource, McpResourceUpdate, McpSampling, Model, ModelId, ModerationRequest,
+    ModerationVerdict, OutputRing, PatchOperation, ProjectMemory, Provider, ResourceGovernor,
+    ResultStream, Runnable, Scope, SnapshotRef, SnapshotRetention, ToolCallFull, ToolCallSample,
+    ToolDefinition, ToolOutput, ToolStatsLedger, Toolchain, UsageLedger, Workflow,
+    WorkspaceSettings,
 };
 use merge::Merge;
+use tokio::sync::mpsc;
 
 use crate::user::User;
 use crate::{AppConfig, InitAuth, LoginInfo, Walker};
@@ -14,6 +22,9 @@ use crate::{AppConfig, InitAuth, LoginInfo, Walker};
 pub struct ShellOutput {
     pub output: CommandOutput,
     pub shell: String,
+    /// Number of times the command was run, more than once when it failed
+    /// transiently
+    pub attempts: usize,
 }
 
 #[derive(Debug)]
@@ -29,6 +40,8 @@ pub struct ReadOutput {
     pub start_line: u64,
     pub end_line: u64,
     pub total_lines: u64,
+    /// Encoding the content was transcoded from, when it isn't UTF-8
+    pub encoding: Option<String>,
 }
 
 #[derive(Debug)]
@@ -78,12 +91,85 @@ pub struct FsCreateOutput {
 #[derive(Debug)]
 pub struct FsRemoveOutput {}
 
+#[derive(Debug)]
+pub struct FsRemoveDirOutput {
+    /// Files that were snapshotted before the removal and can be undone
+    pub snapshotted: Vec<String>,
+    /// Whether the directory was moved to the trash rather than deleted
+    pub trashed: bool,
+}
+
+/// A file being written in several parts
+#[derive(Default, Debug, Clone)]
+pub struct FsWriteDraft {
+    /// The parts appended so far, joined
+    pub content: String,
+    pub overwrite: bool,
+    pub allow_large: bool,
+    /// Number of parts appended so far
+    pub parts: usize,
+}
+
+#[derive(Debug)]
+pub struct FsWriteAppendOutput {
+    /// Number of parts appended so far
+    pub parts: usize,
+    /// Number of lines of the content assembled so far
+    pub total_lines: usize,
+}
+
 #[derive(Default, Debug, derive_more::From)]
 pub struct FsUndoOutput {
     pub before_undo: Option<String>,
     pub after_undo: Option<String>,
 }
 
+/// A commit of the git history
+#[derive(Debug, Clone, PartialEq)]
+pub struct GitCommit {
+    pub hash: String,
+    pub author: String,
+    /// Date the commit was authored, in RFC 3339
+    pub date: String,
+    /// First line of the commit message
+    pub summary: String,
+}
+
+/// Lines of a file last changed by the same commit
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlameHunk {
+    pub commit: GitCommit,
+    pub start_line: u64,
+    pub end_line: u64,
+    pub content: String,
+}
+
+#[derive(Debug)]
+pub struct GitBlameOutput {
+    pub hunks: Vec<BlameHunk>,
+    pub total_lines: u64,
+}
+
+/// A file whose fixes were applied by the linters
+#[derive(Debug, Clone, PartialEq)]
+pub struct LintFix {
+    pub path: String,
+    /// Number of fixes applied
+    pub applied: usize,
+    pub before: String,
+    pub after: String,
+}
+
+#[derive(Debug)]
+pub struct LintOutput {
+    /// Diagnostics of the files, the ones remaining after the fixes when
+    /// some were applied
+    pub diagnostics: Vec<Diagnostic>,
+    pub fixes: Vec<LintFix>,
+    /// Paths of the files no configured linter checks
+    pub skipped: Vec<String>,
+}
+
 #[async_trait::async_trait]
 pub trait ProviderService: Send + Sync {
     async fn chat(
@@ -93,6 +179,16 @@ pub trait ProviderService: Send + Sync {
         provider: Provider,
     ) -> ResultStream<ChatCompletionMessage, anyhow::Error>;
     async fn models(&self, provider: Provider) -> anyhow::Result<Vec<Model>>;
+    /// Returns the exact request body that would be sent to the provider for
+    /// the given context without sending it
+    async fn payload(
+        &self,
+        id: &ModelId,
+        context: Context,
+        provider: Provider,
+    ) -> anyhow::Result<serde_json::Value>;
+    /// Returns the usage of the providers accounted on this machine
+    async fn usage(&self) -> anyhow::Result<UsageLedger>;
 }
 
 #[async_trait::async_trait]
@@ -107,7 +203,47 @@ pub trait McpConfigManager: Send + Sync {
 #[async_trait::async_trait]
 pub trait McpService: Send + Sync {
     async fn list(&self) -> anyhow::Result<Vec<ToolDefinition>>;
-    async fn call(&self, call: ToolCallFull) -> anyhow::Result<ToolOutput>;
+    /// Calls the tool, sending the completions its server asks for while the
+    /// call runs to `sampling`. The ones a server asks for outside of a call of
+    /// its tools are declined.
+    async fn call(
+        &self,
+        call: ToolCallFull,
+        sampling: mpsc::UnboundedSender<McpSampling>,
+    ) -> anyhow::Result<ToolOutput>;
+    /// Prompts of the connected servers, named after their server
+    async fn list_prompts(&self) -> anyhow::Result<Vec<McpPrompt>>;
+    /// Text of the messages of the prompt with its arguments filled in
+    async fn get_prompt(
+        &self,
+        name: &str,
+        arguments: BTreeMap<String, String>,
+    ) -> anyhow::Result<String>;
+    /// Resources of the connected servers
+    async fn list_resources(&self) -> anyhow::Result<Vec<McpResource>>;
+    /// Contents of a resource of the server
+    async fn read_resource(
+        &self,
+        server: &str,
+        uri: &str,
+    ) -> anyhow::Result<Vec<AttachmentContent>>;
+    /// Reads the resource of the server again whenever it changes, until the
+    /// resources of the conversation are unsubscribed
+    async fn subscribe_resource(
+        &self,
+        conversation_id: &ConversationId,
+        server: &str,
+        uri: &str,
+    ) -> anyhow::Result<()>;
+    /// Stops following the changes of the resources subscribed for the
+    /// conversation
+    async fn unsubscribe_resources(&self, conversation_id: &ConversationId) -> anyhow::Result<()>;
+    /// Contents of the resources subscribed for the conversation that changed
+    /// since the last call
+    async fn take_resource_updates(
+        &self,
+        conversation_id: &ConversationId,
+    ) -> Vec<McpResourceUpdate>;
 }
 
 #[async_trait::async_trait]
@@ -118,6 +254,9 @@ pub trait ConversationService: Send + Sync {
 
     async fn create_conversation(&self, workflow: Workflow) -> anyhow::Result<Conversation>;
 
+    /// IDs of the conversations kept in the storage
+    async fn list_conversations(&self) -> anyhow::Result<Vec<ConversationId>>;
+
     /// This is useful when you want to perform several operations on a
     /// conversation atomically.
     async fn update<F, T>(&self, id: &ConversationId, f: F) -> anyhow::Result<T>
@@ -137,7 +276,13 @@ pub trait TemplateService: Send + Sync {
 
 #[async_trait::async_trait]
 pub trait AttachmentService {
-    async fn attachments(&self, url: &str) -> anyhow::Result<Vec<Attachment>>;
+    /// Attachments of the message, following the changes of the MCP resources
+    /// it attaches for the conversation
+    async fn attachments(
+        &self,
+        conversation_id: &ConversationId,
+        url: &str,
+    ) -> anyhow::Result<Vec<Attachment>>;
 }
 
 pub trait EnvironmentService: Send + Sync {
@@ -185,6 +330,15 @@ pub trait WorkflowService {
 #[async_trait::async_trait]
 pub trait FileDiscoveryService: Send + Sync {
     async fn collect_files(&self, config: Walker) -> anyhow::Result<Vec<File>>;
+
+    /// Counts the entries of the directory, ignored files excluded, stopping
+    /// at `limit`. The count is kept for the rest of the session.
+    async fn count_entries(&self, cwd: PathBuf, limit: usize) -> anyhow::Result<usize>;
+
+    /// Orders the files, given relative to the working directory, from the
+    /// most to the least structurally important by ranking them over the
+    /// graph of their imports.
+    async fn rank_files(&self, files: Vec<String>) -> anyhow::Result<Vec<String>>;
 }
 
 #[async_trait::async_trait]
@@ -195,10 +349,37 @@ pub trait FsCreateService: Send + Sync {
         path: String,
         content: String,
         overwrite: bool,
+        allow_large: bool,
         capture_snapshot: bool,
     ) -> anyhow::Result<FsCreateOutput>;
 }
 
+#[async_trait::async_trait]
+pub trait FsWriteDraftService: Send + Sync {
+    /// Starts a draft of the file at the specified path, discarding any earlier
+    /// draft of it.
+    async fn begin_draft(
+        &self,
+        path: String,
+        overwrite: bool,
+        allow_large: bool,
+    ) -> anyhow::Result<()>;
+
+    /// Appends a part to the draft of the file at the specified path.
+    async fn append_draft(
+        &self,
+        path: String,
+        content: String,
+    ) -> anyhow::Result<FsWriteAppendOutput>;
+
+    /// Returns the draft of the file at the specified path.
+    async fn get_draft(&self, path: String) -> anyhow::Result<FsWriteDraft>;
+
+    /// Discards the draft of the file at the specified path, once it has been
+    /// written.
+    async fn discard_draft(&self, path: String) -> anyhow::Result<()>;
+}
+
 #[async_trait::async_trait]
 pub trait FsPatchService: Send + Sync {
     /// Patches a file at the specified path with the given content.
@@ -208,6 +389,7 @@ pub trait FsPatchService: Send + Sync {
         search: Option<String>,
         operation: PatchOperation,
         content: String,
+        allow_large: bool,
     ) -> anyhow::Result<PatchOutput>;
 }
 
@@ -224,8 +406,13 @@ pub trait FsReadService: Send + Sync {
 
 #[async_trait::async_trait]
 pub trait FsRemoveService: Send + Sync {
-    /// Removes a file at the specified path.
-    async fn remove(&self, path: String) -> anyhow::Result<FsRemoveOutput>;
+    /// Removes a file at the specified path, moving it to the trash unless
+    /// `permanent` is set.
+    async fn remove(&self, path: String, permanent: bool) -> anyhow::Result<FsRemoveOutput>;
+
+    /// Recursively removes a directory at the specified path, moving it to the
+    /// trash unless `permanent` is set.
+    async fn remove_dir(&self, path: String, permanent: bool) -> anyhow::Result<FsRemoveDirOutput>;
 }
 
 #[async_trait::async_trait]
@@ -237,6 +424,14 @@ pub trait FsSearchService: Send + Sync {
         regex: Option<String>,
         file_pattern: Option<String>,
     ) -> anyhow::Result<Option<SearchResult>>;
+
+    /// Searches the files the walker lists for the regex, so that a search of
+    /// a whole workspace stays within the limits of the walker
+    async fn search_walk(
+        &self,
+        walker: Walker,
+        regex: String,
+    ) -> anyhow::Result<Option<SearchResult>>;
 }
 
 #[async_trait::async_trait]
@@ -259,20 +454,162 @@ pub trait FsUndoService: Send + Sync {
     async fn undo(&self, path: String) -> anyhow::Result<FsUndoOutput>;
 }
 
+#[async_trait::async_trait]
+pub trait FsMetaService: Send + Sync {
+    /// Checks whether anything exists at the specified path.
+    async fn exists(&self, path: &Path) -> anyhow::Result<bool>;
+    /// Checks whether the specified path is a regular file.
+    async fn is_file(&self, path: &Path) -> anyhow::Result<bool>;
+    /// Waits until the path is created, modified or removed, checking it at a
+    /// bounded rate. Returns `None` when the timeout elapses first.
+    async fn wait_for_change(
+        &self,
+        path: &Path,
+        timeout: Duration,
+    ) -> anyhow::Result<Option<FsChange>>;
+}
+
+#[async_trait::async_trait]
+pub trait PathResolverService: Send + Sync {
+    /// Absolute path of a path supplied to a tool. Relative paths are resolved
+    /// against the workspace, `.` and `..` are removed. Fails when the path is
+    /// outside of the workspace and of the external paths once its symbolic
+    /// links are followed.
+    async fn resolve_path(&self, path: &str) -> anyhow::Result<PathBuf>;
+    /// Existing paths whose name is close to the one of the missing `path`,
+    /// e.g. with a typo or another case, the closest first.
+    async fn similar_paths(&self, path: &Path) -> anyhow::Result<Vec<PathBuf>>;
+}
+
+#[async_trait::async_trait]
+pub trait FsSnapshotService: Send + Sync {
+    /// Lists the snapshots captured for the specified file, oldest first.
+    async fn list_snapshots(&self, path: &Path) -> anyhow::Result<Vec<SnapshotRef>>;
+    /// Restores a file to the content captured by the snapshot. The snapshot
+    /// and the newer snapshots of the same file are discarded.
+    async fn restore_snapshot(&self, snapshot: &SnapshotRef) -> anyhow::Result<()>;
+    /// Removes the snapshots exceeding the retention limits and returns their
+    /// number.
+    async fn purge_snapshots(&self, retention: &SnapshotRetention) -> anyhow::Result<usize>;
+}
+
+#[async_trait::async_trait]
+pub trait FsCheckpointService: Send + Sync {
+    /// Captures the specified files as snapshots, binary files included. The
+    /// files that don't exist are captured as such.
+    async fn capture_files(&self, paths: &[String]) -> anyhow::Result<Vec<FileState>>;
+    /// Writes the captured snapshots back to the files and removes the files
+    /// that didn't exist. The files are snapshotted first so that the
+    /// restoration can be undone.
+    async fn restore_files(&self, files: &[FileState]) -> anyhow::Result<()>;
+}
+
 #[async_trait::async_trait]
 pub trait NetFetchService: Send + Sync {
     /// Fetches content from a URL and returns it as a string.
     async fn fetch(&self, url: String, raw: Option<bool>) -> anyhow::Result<HttpResponse>;
 }
 
+#[async_trait::async_trait]
+pub trait GitService: Send + Sync {
+    /// Lists the commits of the history, most recent first, touching `path`
+    /// when provided and within the revision `range` when provided.
+    async fn log(
+        &self,
+        path: Option<String>,
+        range: Option<String>,
+        max_count: Option<u64>,
+    ) -> anyhow::Result<Vec<GitCommit>>;
+
+    /// Finds the commit that last changed each line of `path` within the
+    /// range.
+    async fn blame(
+        &self,
+        path: String,
+        start_line: Option<u64>,
+        end_line: Option<u64>,
+    ) -> anyhow::Result<GitBlameOutput>;
+
+    /// Returns the changes staged for the next commit as a unified diff,
+    /// preceded by the number of lines changed in each file.
+    async fn staged_diff(&self) -> anyhow::Result<String>;
+}
+
+#[async_trait::async_trait]
+pub trait LintService: Send + Sync {
+    /// Runs the linters configured for the files and returns their
+    /// diagnostics, after applying the fixes they mark as safe when `fix` is
+    /// set.
+    async fn lint(&self, paths: Vec<String>, fix: bool) -> anyhow::Result<LintOutput>;
+}
+
+#[async_trait::async_trait]
+pub trait ProcessEnvService: Send + Sync {
+    /// Redacted view of the environment variables of the process sorted by
+    /// name, or of the variable `name` only.
+    async fn process_env(&self, name: Option<String>) -> anyhow::Result<Vec<EnvVar>>;
+}
+
+#[async_trait::async_trait]
+pub trait ToolchainService: Send + Sync {
+    /// Toolchains found on the PATH with their versions, read once and reused
+    /// for the rest of the session unless `refresh` is set.
+    async fn toolchains(&self, refresh: bool) -> anyhow::Result<Vec<Toolchain>>;
+}
+
+#[async_trait::async_trait]
+pub trait ArchiveService: Send + Sync {
+    /// Entries of the zip, tar or tar.gz archive at `path`, without extracting
+    /// it.
+    async fn list_archive(&self, path: &Path) -> anyhow::Result<Vec<ArchiveEntry>>;
+
+    /// Extracts the archive at `path` into `destination`. Nothing is written
+    /// when an entry would land outside of `destination` or when the archive
+    /// is too large once extracted.
+    async fn extract_archive(
+        &self,
+        path: &Path,
+        destination: &Path,
+    ) -> anyhow::Result<ArchiveExtraction>;
+}
+
+#[async_trait::async_trait]
+pub trait ImageDiffService: Send + Sync {
+    /// Compares the images at `before` and `after`, ignoring the differences
+    /// of a color channel up to `tolerance`.
+    async fn diff_images(
+        &self,
+        before: &Path,
+        after: &Path,
+        tolerance: u8,
+    ) -> anyhow::Result<ImageDiffOutput>;
+}
+
+pub trait ResourceGovernorService: Send + Sync {
+    /// Governor shared by the conversations of the process, bounding the tools
+    /// running at once in each resource category.
+    fn governor(&self) -> ResourceGovernor;
+}
+
+#[async_trait::async_trait]
+pub trait TaskDiscoveryService: Send + Sync {
+    /// Lists the targets defined by the build tools of the project in `path`,
+    /// a directory.
+    async fn discover_tasks(&self, path: PathBuf) -> anyhow::Result<Vec<Runnable>>;
+}
+
 #[async_trait::async_trait]
 pub trait ShellService: Send + Sync {
-    /// Executes a shell command and returns the output.
+    /// Executes a shell command with the given environment variables set on
+    /// top of the inherited ones and returns the output. The output is pushed
+    /// to the ring, when given, as it's produced.
     async fn execute(
         &self,
         command: String,
         cwd: PathBuf,
         keep_ansi: bool,
+        env: BTreeMap<String, String>,
+        progress: Option<OutputRing>,
     ) -> anyhow::Result<ShellOutput>;
 }
 
@@ -282,6 +619,51 @@ pub trait AppConfigService: Send + Sync {
     async fn write_app_config(&self, config: &AppConfig) -> anyhow::Result<()>;
 }
 
+#[async_trait::async_trait]
+pub trait WorkspaceSettingsService: Send + Sync {
+    /// Reads the settings remembered for the current workspace, returning
+    /// defaults if none have been stored yet.
+    async fn read_workspace_settings(&self) -> anyhow::Result<WorkspaceSettings>;
+    async fn write_workspace_settings(&self, settings: &WorkspaceSettings) -> anyhow::Result<()>;
+}
+
+#[async_trait::async_trait]
+pub trait ProjectMemoryService: Send + Sync {
+    /// Reads the learnings remembered for the current workspace, returning
+    /// an empty memory if none have been stored yet.
+    async fn read_project_memory(&self) -> anyhow::Result<ProjectMemory>;
+    async fn write_project_memory(&self, memory: &ProjectMemory) -> anyhow::Result<()>;
+}
+
+#[async_trait::async_trait]
+pub trait JournalService: Send + Sync {
+    /// Appends entries to the journal of the workspace
+    async fn record_journal(&self, entries: Vec<JournalEntry>) -> anyhow::Result<()>;
+
+    /// Reads the entries written after `offset`, a position returned by an
+    /// earlier read. Reading from 0 returns the whole journal.
+    async fn read_journal(&self, offset: u64) -> anyhow::Result<JournalPage>;
+}
+
+#[async_trait::async_trait]
+pub trait ToolStatsService: Send + Sync {
+    /// Adds the outcome of a tool call to the statistics kept across sessions
+    async fn record_tool_stats(&self, sample: ToolCallSample) -> anyhow::Result<()>;
+
+    /// Reads the statistics of the tool calls
+    async fn tool_stats(&self) -> anyhow::Result<ToolStatsLedger>;
+}
+
+#[async_trait::async_trait]
+pub trait ModerationService: Send + Sync {
+    /// Asks the moderation service at `endpoint` for a verdict on a response
+    async fn moderate(
+        &self,
+        endpoint: &str,
+        request: &ModerationRequest<'_>,
+    ) -> anyhow::Result<ModerationVerdict>;
+}
+
 #[async_trait::async_trait]
 pub trait AuthService: Send + Sync {
     async fn init_auth(&self) -> anyhow::Result<InitAuth>;
@@ -306,18 +688,36 @@ pub trait Services: Send + Sync + 'static + Clone {
     type FileDiscoveryService: FileDiscoveryService;
     type McpConfigManager: McpConfigManager;
     type FsCreateService: FsCreateService;
+    type FsWriteDraftService: FsWriteDraftService;
     type FsPatchService: FsPatchService;
     type FsReadService: FsReadService;
     type FsRemoveService: FsRemoveService;
     type FsSearchService: FsSearchService;
     type FollowUpService: FollowUpService;
     type FsUndoService: FsUndoService;
+    type FsMetaService: FsMetaService;
+    type PathResolverService: PathResolverService;
+    type FsSnapshotService: FsSnapshotService;
+    type FsCheckpointService: FsCheckpointService;
     type NetFetchService: NetFetchService;
     type ShellService: ShellService;
+    type GitService: GitService;
+    type LintService: LintService;
+    type TaskDiscoveryService: TaskDiscoveryService;
+    type ProcessEnvService: ProcessEnvService;
+    type ToolchainService: ToolchainService;
+    type ArchiveService: ArchiveService;
+    type ImageDiffService: ImageDiffService;
+    type ResourceGovernorService: ResourceGovernorService;
     type McpService: McpService;
     type AuthService: AuthService;
     type AppConfigService: AppConfigService;
     type ProviderRegistry: ProviderRegistry;
+    type WorkspaceSettingsService: WorkspaceSettingsService;
+    type ProjectMemoryService: ProjectMemoryService;
+    type JournalService: JournalService;
+    type ToolStatsService: ToolStatsService;
+    type ModerationService: ModerationService;
 
     fn provider_service(&self) -> &Self::ProviderService;
     fn conversation_service(&self) -> &Self::ConversationService;
@@ -327,19 +727,37 @@ pub trait Services: Send + Sync + 'static + Clone {
     fn file_discovery_service(&self) -> &Self::FileDiscoveryService;
     fn mcp_config_manager(&self) -> &Self::McpConfigManager;
     fn fs_create_service(&self) -> &Self::FsCreateService;
+    fn fs_write_draft_service(&self) -> &Self::FsWriteDraftService;
     fn fs_patch_service(&self) -> &Self::FsPatchService;
     fn fs_read_service(&self) -> &Self::FsReadService;
     fn fs_remove_service(&self) -> &Self::FsRemoveService;
     fn fs_search_service(&self) -> &Self::FsSearchService;
     fn follow_up_service(&self) -> &Self::FollowUpService;
     fn fs_undo_service(&self) -> &Self::FsUndoService;
+    fn fs_meta_service(&self) -> &Self::FsMetaService;
+    fn path_resolver_service(&self) -> &Self::PathResolverService;
+    fn fs_snapshot_service(&self) -> &Self::FsSnapshotService;
+    fn fs_checkpoint_service(&self) -> &Self::FsCheckpointService;
     fn net_fetch_service(&self) -> &Self::NetFetchService;
     fn shell_service(&self) -> &Self::ShellService;
+    fn git_service(&self) -> &Self::GitService;
+    fn lint_service(&self) -> &Self::LintService;
+    fn task_discovery_service(&self) -> &Self::TaskDiscoveryService;
+    fn process_env_service(&self) -> &Self::ProcessEnvService;
+    fn toolchain_service(&self) -> &Self::ToolchainService;
+    fn archive_service(&self) -> &Self::ArchiveService;
+    fn image_diff_service(&self) -> &Self::ImageDiffService;
+    fn resource_governor_service(&self) -> &Self::ResourceGovernorService;
     fn mcp_service(&self) -> &Self::McpService;
     fn environment_service(&self) -> &Self::EnvironmentService;
     fn auth_service(&self) -> &Self::AuthService;
     fn app_config_service(&self) -> &Self::AppConfigService;
     fn provider_registry(&self) -> &Self::ProviderRegistry;
+    fn workspace_settings_service(&self) -> &Self::WorkspaceSettingsService;
+    fn project_memory_service(&self) -> &Self::ProjectMemoryService;
+    fn journal_service(&self) -> &Self::JournalService;
+    fn tool_stats_service(&self) -> &Self::ToolStatsService;
+    fn moderation_service(&self) -> &Self::ModerationService;
 }
 
 #[async_trait::async_trait]
@@ -358,6 +776,10 @@ impl<I: Services> ConversationService for I {
             .await
     }
 
+    async fn list_conversations(&self) -> anyhow::Result<Vec<ConversationId>> {
+        self.conversation_service().list_conversations().await
+    }
+
     async fn update<F, T>(&self, id: &ConversationId, f: F) -> anyhow::Result<T>
     where
         F: FnOnce(&mut Conversation) -> T + Send,
@@ -379,6 +801,19 @@ impl<I: Services> ProviderService for I {
     async fn models(&self, provider: Provider) -> anyhow::Result<Vec<Model>> {
         self.provider_service().models(provider).await
     }
+
+    async fn payload(
+        &self,
+        id: &ModelId,
+        context: Context,
+        provider: Provider,
+    ) -> anyhow::Result<serde_json::Value> {
+        self.provider_service().payload(id, context, provider).await
+    }
+
+    async fn usage(&self) -> anyhow::Result<UsageLedger> {
+        self.provider_service().usage().await
+    }
 }
 
 #[async_trait::async_trait]
@@ -400,8 +835,62 @@ impl<I: Services> McpService for I {
         self.mcp_service().list().await
     }
 
-    async fn call(&self, call: ToolCallFull) -> anyhow::Result<ToolOutput> {
-        self.mcp_service().call(call).await
+    async fn call(
+        &self,
+        call: ToolCallFull,
+        sampling: mpsc::UnboundedSender<McpSampling>,
+    ) -> anyhow::Result<ToolOutput> {
+        self.mcp_service().call(call, sampling).await
+    }
+
+    async fn list_prompts(&self) -> anyhow::Result<Vec<McpPrompt>> {
+        self.mcp_service().list_prompts().await
+    }
+
+    async fn get_prompt(
+        &self,
+        name: &str,
+        arguments: BTreeMap<String, String>,
+    ) -> anyhow::Result<String> {
+        self.mcp_service().get_prompt(name, arguments).await
+    }
+
+    async fn list_resources(&self) -> anyhow::Result<Vec<McpResource>> {
+        self.mcp_service().list_resources().await
+    }
+
+    async fn read_resource(
+        &self,
+        server: &str,
+        uri: &str,
+    ) -> anyhow::Result<Vec<AttachmentContent>> {
+        self.mcp_service().read_resource(server, uri).await
+    }
+
+    async fn subscribe_resource(
+        &self,
+        conversation_id: &ConversationId,
+        server: &str,
+        uri: &str,
+    ) -> anyhow::Result<()> {
+        self.mcp_service()
+            .subscribe_resource(conversation_id, server, uri)
+            .await
+    }
+
+    async fn unsubscribe_resources(&self, conversation_id: &ConversationId) -> anyhow::Result<()> {
+        self.mcp_service()
+            .unsubscribe_resources(conversation_id)
+            .await
+    }
+
+    async fn take_resource_updates(
+        &self,
+        conversation_id: &ConversationId,
+    ) -> Vec<McpResourceUpdate> {
+        self.mcp_service()
+            .take_resource_updates(conversation_id)
+            .await
     }
 }
 
@@ -424,8 +913,14 @@ impl<I: Services> TemplateService for I {
 
 #[async_trait::async_trait]
 impl<I: Services> AttachmentService for I {
-    async fn attachments(&self, url: &str) -> anyhow::Result<Vec<Attachment>> {
-        self.attachment_service().attachments(url).await
+    async fn attachments(
+        &self,
+        conversation_id: &ConversationId,
+        url: &str,
+    ) -> anyhow::Result<Vec<Attachment>> {
+        self.attachment_service()
+            .attachments(conversation_id, url)
+            .await
     }
 }
 
@@ -456,6 +951,16 @@ impl<I: Services> FileDiscoveryService for I {
     async fn collect_files(&self, config: Walker) -> anyhow::Result<Vec<File>> {
         self.file_discovery_service().collect_files(config).await
     }
+
+    async fn count_entries(&self, cwd: PathBuf, limit: usize) -> anyhow::Result<usize> {
+        self.file_discovery_service()
+            .count_entries(cwd, limit)
+            .await
+    }
+
+    async fn rank_files(&self, files: Vec<String>) -> anyhow::Result<Vec<String>> {
+        self.file_discovery_service().rank_files(files).await
+    }
 }
 
 #[async_trait::async_trait]
@@ -465,10 +970,11 @@ impl<I: Services> FsCreateService for I {
         path: String,
         content: String,
         overwrite: bool,
+        allow_large: bool,
         capture_snapshot: bool,
     ) -> anyhow::Result<FsCreateOutput> {
         self.fs_create_service()
-            .create(path, content, overwrite, capture_snapshot)
+            .create(path, content, overwrite, allow_large, capture_snapshot)
             .await
     }
 }
@@ -481,9 +987,10 @@ impl<I: Services> FsPatchService for I {
         search: Option<String>,
         operation: PatchOperation,
         content: String,
+        allow_large: bool,
     ) -> anyhow::Result<PatchOutput> {
         self.fs_patch_service()
-            .patch(path, search, operation, content)
+            .patch(path, search, operation, content, allow_large)
             .await
     }
 }
@@ -502,10 +1009,46 @@ impl<I: Services> FsReadService for I {
     }
 }
 
+#[async_trait::async_trait]
+impl<I: Services> FsWriteDraftService for I {
+    async fn begin_draft(
+        &self,
+        path: String,
+        overwrite: bool,
+        allow_large: bool,
+    ) -> anyhow::Result<()> {
+        self.fs_write_draft_service()
+            .begin_draft(path, overwrite, allow_large)
+            .await
+    }
+
+    async fn append_draft(
+        &self,
+        path: String,
+        content: String,
+    ) -> anyhow::Result<FsWriteAppendOutput> {
+        self.fs_write_draft_service()
+            .append_draft(path, content)
+            .await
+    }
+
+    async fn get_draft(&self, path: String) -> anyhow::Result<FsWriteDraft> {
+        self.fs_write_draft_service().get_draft(path).await
+    }
+
+    async fn discard_draft(&self, path: String) -> anyhow::Result<()> {
+        self.fs_write_draft_service().discard_draft(path).await
+    }
+}
+
 #[async_trait::async_trait]
 impl<I: Services> FsRemoveService for I {
-    async fn remove(&self, path: String) -> anyhow::Result<FsRemoveOutput> {
-        self.fs_remove_service().remove(path).await
+    async fn remove(&self, path: String, permanent: bool) -> anyhow::Result<FsRemoveOutput> {
+        self.fs_remove_service().remove(path, permanent).await
+    }
+
+    async fn remove_dir(&self, path: String, permanent: bool) -> anyhow::Result<FsRemoveDirOutput> {
+        self.fs_remove_service().remove_dir(path, permanent).await
     }
 }
 
@@ -521,6 +1064,14 @@ impl<I: Services> FsSearchService for I {
             .search(path, regex, file_pattern)
             .await
     }
+
+    async fn search_walk(
+        &self,
+        walker: Walker,
+        regex: String,
+    ) -> anyhow::Result<Option<SearchResult>> {
+        self.fs_search_service().search_walk(walker, regex).await
+    }
 }
 
 #[async_trait::async_trait]
@@ -544,6 +1095,62 @@ impl<I: Services> FsUndoService for I {
     }
 }
 
+#[async_trait::async_trait]
+impl<I: Services> FsMetaService for I {
+    async fn exists(&self, path: &Path) -> anyhow::Result<bool> {
+        self.fs_meta_service().exists(path).await
+    }
+
+    async fn is_file(&self, path: &Path) -> anyhow::Result<bool> {
+        self.fs_meta_service().is_file(path).await
+    }
+
+    async fn wait_for_change(
+        &self,
+        path: &Path,
+        timeout: Duration,
+    ) -> anyhow::Result<Option<FsChange>> {
+        self.fs_meta_service().wait_for_change(path, timeout).await
+    }
+}
+
+#[async_trait::async_trait]
+impl<I: Services> PathResolverService for I {
+    async fn resolve_path(&self, path: &str) -> anyhow::Result<PathBuf> {
+        self.path_resolver_service().resolve_path(path).await
+    }
+
+    async fn similar_paths(&self, path: &Path) -> anyhow::Result<Vec<PathBuf>> {
+        self.path_resolver_service().similar_paths(path).await
+    }
+}
+
+#[async_trait::async_trait]
+impl<I: Services> FsSnapshotService for I {
+    async fn list_snapshots(&self, path: &Path) -> anyhow::Result<Vec<SnapshotRef>> {
+        self.fs_snapshot_service().list_snapshots(path).await
+    }
+
+    async fn restore_snapshot(&self, snapshot: &SnapshotRef) -> anyhow::Result<()> {
+        self.fs_snapshot_service().restore_snapshot(snapshot).await
+    }
+
+    async fn purge_snapshots(&self, retention: &SnapshotRetention) -> anyhow::Result<usize> {
+        self.fs_snapshot_service().purge_snapshots(retention).await
+    }
+}
+
+#[async_trait::async_trait]
+impl<I: Services> FsCheckpointService for I {
+    async fn capture_files(&self, paths: &[String]) -> anyhow::Result<Vec<FileState>> {
+        self.fs_checkpoint_service().capture_files(paths).await
+    }
+
+    async fn restore_files(&self, files: &[FileState]) -> anyhow::Result<()> {
+        self.fs_checkpoint_service().restore_files(files).await
+    }
+}
+
 #[async_trait::async_trait]
 impl<I: Services> NetFetchService for I {
     async fn fetch(&self, url: String, raw: Option<bool>) -> anyhow::Result<HttpResponse> {
@@ -558,8 +1165,102 @@ impl<I: Services> ShellService for I {
         command: String,
         cwd: PathBuf,
         keep_ansi: bool,
+        env: BTreeMap<String, String>,
+        progress: Option<OutputRing>,
     ) -> anyhow::Result<ShellOutput> {
-        self.shell_service().execute(command, cwd, keep_ansi).await
+        self.shell_service()
+            .execute(command, cwd, keep_ansi, env, progress)
+            .await
+    }
+}
+
+#[async_trait::async_trait]
+impl<I: Services> GitService for I {
+    async fn log(
+        &self,
+        path: Option<String>,
+        range: Option<String>,
+        max_count: Option<u64>,
+    ) -> anyhow::Result<Vec<GitCommit>> {
+        self.git_service().log(path, range, max_count).await
+    }
+
+    async fn blame(
+        &self,
+        path: String,
+        start_line: Option<u64>,
+        end_line: Option<u64>,
+    ) -> anyhow::Result<GitBlameOutput> {
+        self.git_service().blame(path, start_line, end_line).await
+    }
+
+    async fn staged_diff(&self) -> anyhow::Result<String> {
+        self.git_service().staged_diff().await
+    }
+}
+
+#[async_trait::async_trait]
+impl<I: Services> LintService for I {
+    async fn lint(&self, paths: Vec<String>, fix: bool) -> anyhow::Result<LintOutput> {
+        self.lint_service().lint(paths, fix).await
+    }
+}
+
+#[async_trait::async_trait]
+impl<I: Services> ProcessEnvService for I {
+    async fn process_env(&self, name: Option<String>) -> anyhow::Result<Vec<EnvVar>> {
+        self.process_env_service().process_env(name).await
+    }
+}
+
+#[async_trait::async_trait]
+impl<I: Services> ToolchainService for I {
+    async fn toolchains(&self, refresh: bool) -> anyhow::Result<Vec<Toolchain>> {
+        self.toolchain_service().toolchains(refresh).await
+    }
+}
+
+#[async_trait::async_trait]
+impl<I: Services> ArchiveService for I {
+    async fn list_archive(&self, path: &Path) -> anyhow::Result<Vec<ArchiveEntry>> {
+        self.archive_service().list_archive(path).await
+    }
+
+    async fn extract_archive(
+        &self,
+        path: &Path,
+        destination: &Path,
+    ) -> anyhow::Result<ArchiveExtraction> {
+        self.archive_service()
+            .extract_archive(path, destination)
+            .await
+    }
+}
+
+#[async_trait::async_trait]
+impl<I: Services> ImageDiffService for I {
+    async fn diff_images(
+        &self,
+        before: &Path,
+        after: &Path,
+        tolerance: u8,
+    ) -> anyhow::Result<ImageDiffOutput> {
+        self.image_diff_service()
+            .diff_images(before, after, tolerance)
+            .await
+    }
+}
+
+impl<I: Services> ResourceGovernorService for I {
+    fn governor(&self) -> ResourceGovernor {
+        self.resource_governor_service().governor()
+    }
+}
+
+#[async_trait::async_trait]
+impl<I: Services> TaskDiscoveryService for I {
+    async fn discover_tasks(&self, path: PathBuf) -> anyhow::Result<Vec<Runnable>> {
+        self.task_discovery_service().discover_tasks(path).await
     }
 }
 
@@ -587,6 +1288,67 @@ impl<I: Services> AppConfigService for I {
     }
 }
 
+#[async_trait::async_trait]
+impl<I: Services> WorkspaceSettingsService for I {
+    async fn read_workspace_settings(&self) -> anyhow::Result<WorkspaceSettings> {
+        self.workspace_settings_service()
+            .read_workspace_settings()
+            .await
+    }
+
+    async fn write_workspace_settings(&self, settings: &WorkspaceSettings) -> anyhow::Result<()> {
+        self.workspace_settings_service()
+            .write_workspace_settings(settings)
+            .await
+    }
+}
+
+#[async_trait::async_trait]
+impl<I: Services> ProjectMemoryService for I {
+    async fn read_project_memory(&self) -> anyhow::Result<ProjectMemory> {
+        self.project_memory_service().read_project_memory().await
+    }
+
+    async fn write_project_memory(&self, memory: &ProjectMemory) -> anyhow::Result<()> {
+        self.project_memory_service()
+            .write_project_memory(memory)
+            .await
+    }
+}
+
+#[async_trait::async_trait]
+impl<I: Services> JournalService for I {
+    async fn record_journal(&self, entries: Vec<JournalEntry>) -> anyhow::Result<()> {
+        self.journal_service().record_journal(entries).await
+    }
+
+    async fn read_journal(&self, offset: u64) -> anyhow::Result<JournalPage> {
+        self.journal_service().read_journal(offset).await
+    }
+}
+
+#[async_trait::async_trait]
+impl<I: Services> ToolStatsService for I {
+    async fn record_tool_stats(&self, sample: ToolCallSample) -> anyhow::Result<()> {
+        self.tool_stats_service().record_tool_stats(sample).await
+    }
+
+    async fn tool_stats(&self) -> anyhow::Result<ToolStatsLedger> {
+        self.tool_stats_service().tool_stats().await
+    }
+}
+
+#[async_trait::async_trait]
+impl<I: Services> ModerationService for I {
+    async fn moderate(
+        &self,
+        endpoint: &str,
+        request: &ModerationRequest<'_>,
+    ) -> anyhow::Result<ModerationVerdict> {
+        self.moderation_service().moderate(endpoint, request).await
+    }
+}
+
 #[async_trait::async_trait]
 impl<I: Services> AuthService for I {
     async fn init_auth(&self) -> anyhow::Result<InitAuth> {
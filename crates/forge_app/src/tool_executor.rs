@@ -1,40 +1,96 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::Context;
-use forge_domain::{TaskList, ToolCallContext, ToolCallFull, ToolOutput, Tools};
+use chrono::{DateTime, Utc};
+use forge_domain::{
+    Agent, ChangeSummary, ChatResponse, Claim, ConversationId, DEFAULT_IMAGE_DIFF_TOLERANCE,
+    FSWrite, GroundingCheck, JournalEntry, OutputRing, ScaffoldFile, ScaffoldTemplate, Shell,
+    SnapshotRef, ToolCallContext, ToolCallFull, ToolOutput, ToolResult, Tools, ToolsDiscriminants,
+    VerificationCheck,
+};
 
 use crate::error::Error;
 use crate::fmt::content::FormatContent;
 use crate::operation::Operation;
-use crate::services::ShellService;
+use crate::services::{MatchResult, ShellOutput, ShellService};
+use crate::triage::Triage;
+use crate::truncation::prepare_scratch_dir;
 use crate::{
-    ConversationService, EnvironmentService, FollowUpService, FsCreateService, FsPatchService,
-    FsReadService, FsRemoveService, FsSearchService, FsUndoService, NetFetchService,
+    ArchiveService, ConversationService, EnvironmentService, FollowUpService, FsCreateService,
+    FsMetaService, FsPatchService, FsReadService, FsRemoveService, FsSearchService,
+    FsSnapshotService, FsUndoService, FsWriteDraftService, GitService, ImageDiffService,
+    JournalService, LintService, NetFetchService, PathResolverService, ProcessEnvService,
+    TaskDiscoveryService, ToolchainService, Walker, WorkspaceSettingsService,
 };
 
+/// Options offered to the user before removing a directory recursively
+const APPROVE_REMOVAL: &str = "Yes, remove it";
+const ALWAYS_APPROVE_REMOVAL: &str = "Yes, and don't ask again for it in this workspace";
+const REJECT_REMOVAL: &str = "No, keep it";
+
+/// Number of recent commits whose style the commit messages follow
+const RECENT_COMMITS: u64 = 10;
+
+/// Number of lines of output of a running command kept for the UI, the older
+/// ones being skipped when the UI falls behind
+const PROGRESS_LINES: usize = 200;
+
+/// Interval at which the output of a running command is sent to the UI
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Number of files of the workspace searched for the claims of an answer, so
+/// that checking an answer stays quick in a large workspace
+const GROUNDING_MAX_FILES: usize = 5_000;
+
+/// Size of the files searched for the claims of an answer, larger ones being
+/// generated or data rather than code
+const GROUNDING_MAX_FILE_SIZE: u64 = 1024 * 1024;
+
 pub struct ToolExecutor<S> {
     services: Arc<S>,
+    triage: Triage<S>,
 }
 
 impl<
     S: FsReadService
         + FsCreateService
+        + FsWriteDraftService
         + FsSearchService
         + NetFetchService
         + FsRemoveService
         + FsPatchService
         + FsUndoService
         + ShellService
+        + GitService
+        + LintService
+        + TaskDiscoveryService
+        + ProcessEnvService
+        + ToolchainService
+        + ArchiveService
+        + ImageDiffService
         + FollowUpService
         + ConversationService
-        + EnvironmentService,
+        + EnvironmentService
+        + FsMetaService
+        + FsSnapshotService
+        + PathResolverService
+        + JournalService
+        + WorkspaceSettingsService,
 > ToolExecutor<S>
 {
     pub fn new(services: Arc<S>) -> Self {
-        Self { services }
+        Self { triage: Triage::new(services.clone()), services }
     }
 
-    async fn call_internal(&self, input: Tools, tasks: &mut TaskList) -> anyhow::Result<Operation> {
+    async fn call_internal(
+        &self,
+        input: Tools,
+        context: &mut ToolCallContext,
+    ) -> anyhow::Result<Operation> {
+        let tasks = &mut context.tasks;
         Ok(match input {
             Tools::ForgeToolFsRead(input) => {
                 let output = self
@@ -54,11 +110,48 @@ impl<
                         input.path.clone(),
                         input.content.clone(),
                         input.overwrite,
+                        input.allow_large,
                         true,
                     )
                     .await?;
                 (input, output).into()
             }
+            Tools::ForgeToolFsWriteBegin(input) => {
+                self.services
+                    .begin_draft(input.path.clone(), input.overwrite, input.allow_large)
+                    .await?;
+                input.into()
+            }
+            Tools::ForgeToolFsWriteAppend(input) => {
+                let output = self
+                    .services
+                    .append_draft(input.path.clone(), input.content.clone())
+                    .await?;
+                (input, output).into()
+            }
+            Tools::ForgeToolFsWriteCommit(input) => {
+                let draft = self.services.get_draft(input.path.clone()).await?;
+                let input = FSWrite {
+                    path: input.path,
+                    content: draft.content,
+                    overwrite: draft.overwrite,
+                    allow_large: draft.allow_large,
+                    explanation: input.explanation,
+                };
+                let output = self
+                    .services
+                    .create(
+                        input.path.clone(),
+                        input.content.clone(),
+                        input.overwrite,
+                        input.allow_large,
+                        true,
+                    )
+                    .await?;
+                // Kept on failure so that the commit can be retried
+                self.services.discard_draft(input.path.clone()).await?;
+                (input, output).into()
+            }
             Tools::ForgeToolFsSearch(input) => {
                 let output = self
                     .services
@@ -71,9 +164,20 @@ impl<
                 (input, output).into()
             }
             Tools::ForgeToolFsRemove(input) => {
-                let _output = self.services.remove(input.path.clone()).await?;
+                let _output = self
+                    .services
+                    .remove(input.path.clone(), input.permanent)
+                    .await?;
                 input.into()
             }
+            Tools::ForgeToolFsRemoveDir(input) => {
+                self.approve_removal(&input.path).await?;
+                let output = self
+                    .services
+                    .remove_dir(input.path.clone(), input.permanent)
+                    .await?;
+                (input, output).into()
+            }
             Tools::ForgeToolFsPatch(input) => {
                 let output = self
                     .services
@@ -82,6 +186,7 @@ impl<
                         input.search.clone(),
                         input.operation.clone(),
                         input.content.clone(),
+                        input.allow_large,
                     )
                     .await?;
                 (input, output).into()
@@ -90,17 +195,142 @@ impl<
                 let output = self.services.undo(input.path.clone()).await?;
                 (input, output).into()
             }
-            Tools::ForgeToolProcessShell(input) => {
+            Tools::ForgeToolFsWait(input) => {
                 let output = self
                     .services
-                    .execute(input.command.clone(), input.cwd.clone(), input.keep_ansi)
+                    .wait_for_change(Path::new(&input.path), input.timeout())
                     .await?;
+                Operation::FsWait { input, output }
+            }
+            Tools::ForgeToolProcessShell(input) => {
+                let output = self.execute_shell(&input, context).await?;
                 output.into()
             }
             Tools::ForgeToolNetFetch(input) => {
                 let output = self.services.fetch(input.url.clone(), input.raw).await?;
                 (input, output).into()
             }
+            Tools::ForgeToolGitLog(input) => {
+                let output = self
+                    .services
+                    .log(input.path.clone(), input.range.clone(), input.max_count)
+                    .await?;
+                (input, output).into()
+            }
+            Tools::ForgeToolGitBlame(input) => {
+                let output = self
+                    .services
+                    .blame(
+                        input.path.clone(),
+                        input.start_line.map(|i| i as u64),
+                        input.end_line.map(|i| i as u64),
+                    )
+                    .await?;
+                (input, output).into()
+            }
+            Tools::ForgeToolGitCommitMessage(_) => {
+                let diff = self.services.staged_diff().await?;
+                // A repository without commits has no history to follow
+                let recent_commits = self
+                    .services
+                    .log(None, None, Some(RECENT_COMMITS))
+                    .await
+                    .unwrap_or_default();
+                Operation::GitCommitMessage { diff, recent_commits }
+            }
+            Tools::ForgeToolScaffold(input) => {
+                let template = context
+                    .scaffolds
+                    .iter()
+                    .find(|template| template.name == input.template)
+                    .ok_or_else(|| Error::UnknownScaffold {
+                        name: input.template.clone(),
+                        available: list_scaffolds(&context.scaffolds),
+                    })?;
+                let root = match &input.path {
+                    Some(path) => PathBuf::from(path),
+                    None => self.services.get_environment().cwd,
+                };
+                let files = template
+                    .render(&input.variables)?
+                    .into_iter()
+                    .map(|file| ScaffoldFile {
+                        path: root.join(&file.path).display().to_string(),
+                        content: file.content,
+                    })
+                    .collect::<Vec<_>>();
+
+                // Nothing is created when a file of the template is in the way
+                for file in &files {
+                    if self.services.exists(Path::new(&file.path)).await? {
+                        return Err(Error::ScaffoldConflict(file.path.clone()).into());
+                    }
+                }
+                for file in &files {
+                    self.services
+                        .create(file.path.clone(), file.content.clone(), false, false, true)
+                        .await?;
+                }
+                Operation::Scaffold { input, root, files }
+            }
+            Tools::ForgeToolLint(input) => {
+                let output = self.services.lint(input.paths.clone(), input.fix).await?;
+                Operation::Lint { input, output }
+            }
+            Tools::ForgeToolProcessEnv(input) => {
+                let output = self.services.process_env(input.name.clone()).await?;
+                Operation::ProcessEnv { input, output }
+            }
+            Tools::ForgeToolDiscoverTasks(input) => {
+                let root = match &input.path {
+                    Some(path) => PathBuf::from(path),
+                    None => self.services.get_environment().cwd,
+                };
+                let output = self.services.discover_tasks(root.clone()).await?;
+                Operation::DiscoverTasks { input, root, output }
+            }
+            Tools::ForgeToolToolchainInfo(input) => {
+                let output = self.services.toolchains(input.refresh).await?;
+                Operation::ToolchainInfo { input, output }
+            }
+            Tools::ForgeToolTimeGet(input) => {
+                let output = input.report(Utc::now())?;
+                Operation::TimeGet { input, output }
+            }
+            Tools::ForgeToolReminderSchedule(input) => {
+                let reminder = input.reminder(Utc::now())?;
+                context.reminders.push(reminder.clone());
+                Operation::ReminderSchedule { input, reminder }
+            }
+            Tools::ForgeToolCalculate(input) => {
+                let output = input.evaluate()?;
+                Operation::Calculate { input, output }
+            }
+            Tools::ForgeToolArchiveList(input) => {
+                let output = self.services.list_archive(Path::new(&input.path)).await?;
+                Operation::ArchiveList { input, output }
+            }
+            Tools::ForgeToolImageDiff(input) => {
+                let output = self
+                    .services
+                    .diff_images(
+                        Path::new(&input.before),
+                        Path::new(&input.after),
+                        input.tolerance.unwrap_or(DEFAULT_IMAGE_DIFF_TOLERANCE),
+                    )
+                    .await?;
+                Operation::ImageDiff { input, output }
+            }
+            Tools::ForgeToolArchiveExtract(input) => {
+                let scratch_dir = context.scratch_dir.clone().ok_or(Error::NoScratchDir)?;
+                prepare_scratch_dir(&scratch_dir).await?;
+                let destination = input.destination(&scratch_dir)?;
+                let output = self
+                    .services
+                    .extract_archive(Path::new(&input.path), &destination)
+                    .await?;
+                Operation::ArchiveExtract { input, output }
+            }
             Tools::ForgeToolFollowup(input) => {
                 let output = self
                     .services
@@ -120,8 +350,20 @@ impl<
                     .await?;
                 output.into()
             }
-            Tools::ForgeToolAttemptCompletion(_input) => {
-                crate::operation::Operation::AttemptCompletion
+            Tools::ForgeToolAttemptCompletion(input) => {
+                let check = self
+                    .check_verification(
+                        &input.verification,
+                        context.conversation_id,
+                        context.turn_started_at,
+                        &context.changes,
+                    )
+                    .await;
+                let grounding = match context.verify_answers {
+                    true => self.check_grounding(&input.result).await,
+                    false => GroundingCheck::default(),
+                };
+                Operation::AttemptCompletion { input, check, grounding }
             }
             Tools::ForgeToolTaskListAppend(input) => {
                 let before = tasks.clone();
@@ -153,27 +395,159 @@ impl<
         })
     }
 
+    /// Sends the output of a running command to the UI
+    async fn send_progress(
+        output: Option<String>,
+        context: &ToolCallContext,
+    ) -> anyhow::Result<()> {
+        let Some(output) = output else {
+            return Ok(());
+        };
+        context
+            .send(ChatResponse::ToolProgress {
+                name: ToolsDiscriminants::ForgeToolProcessShell.name(),
+                output,
+            })
+            .await
+    }
+
+    /// Runs a shell command, running it again while its output shows a
+    /// transient failure, up to the retries of the conversation
+    async fn execute_shell(
+        &self,
+        input: &Shell,
+        context: &ToolCallContext,
+    ) -> anyhow::Result<ShellOutput> {
+        let retry = &context.shell_retry;
+        let mut attempts = 1;
+        loop {
+            let progress = OutputRing::new(PROGRESS_LINES);
+            let execution = self.services.execute(
+                input.command.clone(),
+                input.cwd.clone(),
+                input.keep_ansi,
+                context.env.clone(),
+                Some(progress.clone()),
+            );
+            tokio::pin!(execution);
+
+            // Stream the output to the UI while the command runs
+            let mut interval = tokio::time::interval(PROGRESS_INTERVAL);
+            let result = loop {
+                tokio::select! {
+                    result = &mut execution => break result,
+                    _ = interval.tick() => {
+                        Self::send_progress(progress.drain(), context).await?;
+                    }
+                }
+            };
+            Self::send_progress(progress.flush(), context).await?;
+            let mut output = result?;
+
+            if attempts > retry.max_retries() || !retry.is_transient(&output.output) {
+                output.attempts = attempts;
+                return Ok(output);
+            }
+
+            let delay = retry.delay(attempts);
+            tracing::warn!(
+                command = %input.command,
+                attempts,
+                delay = ?delay,
+                "Retrying command after a transient failure"
+            );
+            tokio::time::sleep(delay).await;
+            attempts += 1;
+        }
+    }
+
     pub async fn execute(
         &self,
+        agent: &Agent,
         input: ToolCallFull,
         context: &mut ToolCallContext,
     ) -> anyhow::Result<ToolOutput> {
-        let tool_input = Tools::try_from(input).map_err(Error::CallArgument)?;
+        let mut tool_input = Tools::try_from(input).map_err(Error::CallArgument)?;
         let env = self.services.get_environment();
         if let Some(content) = tool_input.to_content(&env) {
             context.send(content).await?;
         }
 
+        if let Err(error) = self.resolve_paths(&mut tool_input).await {
+            return Ok(self.failure(&tool_input, error, context).await);
+        }
+
         // Send tool call information
 
-        let execution_result = self
-            .call_internal(tool_input.clone(), &mut context.tasks)
-            .await;
-        if let Err(ref error) = execution_result {
-            tracing::error!(error = ?error, "Tool execution failed");
+        let started_at = Utc::now();
+        let execution_result = match self.authorize(agent, &tool_input, context).await {
+            Ok(()) => {
+                // Held until the tool completes, waiting for the tools of the
+                // other conversations using the same resource
+                let _permit = match tool_input.resource() {
+                    Some(resource) => Some(context.acquire(resource).await),
+                    None => None,
+                };
+                self.call_internal(tool_input.clone(), context).await
+            }
+            Err(error) => Err(error),
+        };
+        let execution_result = match execution_result {
+            Ok(execution_result) => execution_result,
+            Err(error) => {
+                tracing::error!(error = ?error, "Tool execution failed");
+                return Ok(self.failure(&tool_input, error, context).await);
+            }
+        };
+
+        // Remember the files whose content the agent has seen
+        match &tool_input {
+            Tools::ForgeToolFsRead(input) => {
+                context.read_files.insert(input.path.clone());
+            }
+            Tools::ForgeToolFsCreate(input) => {
+                context.read_files.insert(input.path.clone());
+            }
+            Tools::ForgeToolFsWriteCommit(input) => {
+                context.read_files.insert(input.path.clone());
+            }
+            Tools::ForgeToolFsPatch(input) => {
+                context.read_files.insert(input.path.clone());
+            }
+            Tools::ForgeToolFsRemove(input) => {
+                context.read_files.remove(&input.path);
+            }
+            Tools::ForgeToolFsRemoveDir(input) => {
+                let dir = Path::new(&input.path);
+                context
+                    .read_files
+                    .retain(|path| !Path::new(path).starts_with(dir));
+            }
+            Tools::ForgeToolScaffold(_) => {
+                if let Operation::Scaffold { files, .. } = &execution_result {
+                    context
+                        .read_files
+                        .extend(files.iter().map(|file| file.path.clone()));
+                }
+            }
+            _ => {}
         }
 
-        let execution_result = execution_result?;
+        // Track what the tool changed so that it can be summarized after the turn
+        for change in execution_result.file_changes() {
+            let snapshots = self.snapshots_since(&change.path, started_at).await;
+            context.snapshots.extend(snapshots);
+            context.changes.record_file(change);
+        }
+
+        if let Operation::Shell { output } = &execution_result {
+            context.changes.record_command(&output.output.command);
+
+            // Remember failing commands so that later failures can be explained
+            if output.output.exit_code.is_some_and(|code| code != 0) {
+                context.last_failed_command = Some(output.output.clone());
+            }
+        }
 
         // Send formatted output message
         if let Some(output) = execution_result.to_content(&env) {
@@ -181,9 +555,385 @@ impl<
         }
 
         let truncation_path = execution_result
-            .to_create_temp(self.services.as_ref())
+            .to_create_temp(self.services.as_ref(), context.scratch_dir.as_deref())
             .await?;
 
         Ok(execution_result.into_tool_output(truncation_path, &env))
     }
+
+    /// Snapshots of `path` captured by the tool call that started at
+    /// `started_at`.
+    async fn snapshots_since(&self, path: &str, started_at: DateTime<Utc>) -> Vec<SnapshotRef> {
+        match self.services.list_snapshots(Path::new(path)).await {
+            Ok(snapshots) => snapshots
+                .into_iter()
+                .filter(|snapshot| snapshot.created_at >= started_at)
+                .collect(),
+            Err(error) => {
+                tracing::warn!(path, error = ?error, "Failed to list snapshots");
+                Vec::new()
+            }
+        }
+    }
+
+    /// Checks the verification commands reported by a completion against the
+    /// commands the journal recorded for the conversation since the turn
+    /// started, and the ones run earlier in the step that aren't journaled
+    /// yet.
+    async fn check_verification(
+        &self,
+        reported: &[String],
+        conversation_id: Option<ConversationId>,
+        turn_started_at: Option<DateTime<Utc>>,
+        changes: &ChangeSummary,
+    ) -> VerificationCheck {
+        let Some(conversation_id) = conversation_id else {
+            return VerificationCheck::new(reported, &[]);
+        };
+        let mut entries = match self.services.read_journal(0).await {
+            Ok(page) => page
+                .entries
+                .into_iter()
+                .filter(|entry| entry.conversation_id == conversation_id)
+                .filter(|entry| turn_started_at.is_none_or(|started| entry.timestamp >= started))
+                .collect(),
+            Err(error) => {
+                tracing::warn!(error = ?error, "Failed to read the journal");
+                Vec::new()
+            }
+        };
+        entries.extend(JournalEntry::from_changes(conversation_id, changes));
+        VerificationCheck::new(reported, &entries)
+    }
+
+    /// Spot-checks the symbols and configuration keys the answer mentions
+    /// with a single bounded search of the workspace, a claim being grounded
+    /// when a line of the workspace contains it as a word.
+    async fn check_grounding(&self, answer: &str) -> GroundingCheck {
+        let claims = Claim::extract(answer);
+        if claims.is_empty() {
+            return GroundingCheck::default();
+        }
+
+        let terms = claims
+            .iter()
+            .map(|claim| regex::escape(claim.search_term()))
+            .collect::<Vec<_>>();
+        let cwd = self.services.get_environment().cwd;
+        let regex = format!(r"\b(?:{})\b", terms.join("|"));
+        let walker = Walker::unlimited()
+            .cwd(cwd)
+            .max_files(GROUNDING_MAX_FILES)
+            .max_file_size(GROUNDING_MAX_FILE_SIZE)
+            .skip_binary(true);
+        let lines = match self.services.search_walk(walker, regex).await {
+            Ok(result) => result
+                .map(|result| result.matches)
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|match_| match match_.result {
+                    Some(MatchResult::Found { line, .. }) => Some(line),
+                    _ => None,
+                })
+                .collect::<Vec<_>>(),
+            Err(error) => {
+                tracing::warn!(error = ?error, "Failed to search the workspace for the claims");
+                return GroundingCheck::default();
+            }
+        };
+
+        GroundingCheck::new(claims, &lines)
+    }
+
+    /// Checks that the agent may run the tool before executing it.
+    async fn authorize(
+        &self,
+        agent: &Agent,
+        input: &Tools,
+        context: &ToolCallContext,
+    ) -> anyhow::Result<()> {
+        self.check_permissions(agent, input)?;
+        self.ensure_read(input, context).await
+    }
+
+    /// Rejects tool calls operating on paths outside of the globs the agent is
+    /// restricted to.
+    fn check_permissions(&self, agent: &Agent, input: &Tools) -> Result<(), Error> {
+        if agent.permissions.is_none() {
+            return Ok(());
+        }
+
+        let cwd = self.services.get_environment().cwd;
+        let path = match input {
+            Tools::ForgeToolFsRead(input) => Path::new(&input.path),
+            Tools::ForgeToolFsSearch(input) => Path::new(&input.path),
+            Tools::ForgeToolFsCreate(input) => Path::new(&input.path),
+            Tools::ForgeToolFsWriteBegin(input) => Path::new(&input.path),
+            Tools::ForgeToolFsWriteAppend(input) => Path::new(&input.path),
+            Tools::ForgeToolFsWriteCommit(input) => Path::new(&input.path),
+            Tools::ForgeToolFsPatch(input) => Path::new(&input.path),
+            Tools::ForgeToolFsRemove(input) => Path::new(&input.path),
+            Tools::ForgeToolFsRemoveDir(input) => Path::new(&input.path),
+            Tools::ForgeToolFsUndo(input) => Path::new(&input.path),
+            Tools::ForgeToolFsWait(input) => Path::new(&input.path),
+            Tools::ForgeToolArchiveList(input) => Path::new(&input.path),
+            Tools::ForgeToolArchiveExtract(input) => Path::new(&input.path),
+            Tools::ForgeToolProcessShell(input) => input.cwd.as_path(),
+            Tools::ForgeToolGitLog(input) => match &input.path {
+                Some(path) => Path::new(path),
+                None => return Ok(()),
+            },
+            Tools::ForgeToolGitBlame(input) => Path::new(&input.path),
+            Tools::ForgeToolScaffold(input) => {
+                input.path.as_deref().map_or(cwd.as_path(), Path::new)
+            }
+            Tools::ForgeToolDiscoverTasks(input) => {
+                input.path.as_deref().map_or(cwd.as_path(), Path::new)
+            }
+            Tools::ForgeToolLint(lint) => {
+                return lint
+                    .paths
+                    .iter()
+                    .try_for_each(|path| check_path(agent, input, Path::new(path), &cwd));
+            }
+            Tools::ForgeToolImageDiff(diff) => {
+                return [&diff.before, &diff.after]
+                    .into_iter()
+                    .try_for_each(|path| check_path(agent, input, Path::new(path), &cwd));
+            }
+            _ => return Ok(()),
+        };
+
+        check_path(agent, input, path, &cwd)
+    }
+
+    /// Asks the user to approve the recursive removal of `path`, unless the
+    /// removal was approved for good in this workspace before.
+    async fn approve_removal(&self, path: &str) -> anyhow::Result<()> {
+        let grant = format!("remove {path}");
+        let mut settings = self.services.read_workspace_settings().await?;
+        if settings.is_approved(&grant) {
+            return Ok(());
+        }
+
+        let answer = self
+            .services
+            .follow_up(
+                format!("Remove the directory {path} and everything in it?"),
+                vec![
+                    APPROVE_REMOVAL.to_string(),
+                    ALWAYS_APPROVE_REMOVAL.to_string(),
+                    REJECT_REMOVAL.to_string(),
+                ],
+                None,
+            )
+            .await?;
+
+        match answer {
+            Some(answer) if answer.ends_with(ALWAYS_APPROVE_REMOVAL) => {
+                settings.approve(grant);
+                self.services.write_workspace_settings(&settings).await
+            }
+            Some(answer) if answer.ends_with(APPROVE_REMOVAL) => Ok(()),
+            _ => Err(Error::RemovalRejected(path.to_string()).into()),
+        }
+    }
+
+    /// Makes the paths of the call absolute, so that the permissions, the
+    /// files read in the conversation and the services see a single form of
+    /// each path. Paths outside of the workspace and of the external paths
+    /// are rejected, as are commands run from them.
+    async fn resolve_paths(&self, input: &mut Tools) -> anyhow::Result<()> {
+        for path in input.paths_mut() {
+            let resolved = self.services.resolve_path(path).await?;
+            *path = resolved.display().to_string();
+        }
+        if let Tools::ForgeToolProcessShell(input) = input {
+            input.cwd = self
+                .services
+                .resolve_path(&input.cwd.to_string_lossy())
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Rejects modifications of existing files that haven't been read in the
+    /// conversation when the read-before-write policy is enabled.
+    async fn ensure_read(&self, input: &Tools, context: &ToolCallContext) -> anyhow::Result<()> {
+        if !context.read_before_write {
+            return Ok(());
+        }
+
+        let Some(path) = written_path(input) else {
+            return Ok(());
+        };
+        let exists = self.services.exists(Path::new(path)).await?;
+        require_read(path, &context.read_files, exists)
+    }
+
+    /// Converts a failed execution into an error output enriched with the
+    /// diagnostics gathered for it.
+    async fn failure(
+        &self,
+        input: &Tools,
+        error: anyhow::Error,
+        context: &ToolCallContext,
+    ) -> ToolOutput {
+        let name = ToolsDiscriminants::from(input).name();
+        let mut output = ToolResult::new(name).failure(error).output;
+        if let Some(diagnostics) = self
+            .triage
+            .diagnose(input, context.last_failed_command.as_ref())
+            .await
+        {
+            output.combine_mut(ToolOutput::text(diagnostics));
+        }
+        output
+    }
+}
+
+/// Rejects the access of the tool call to `path` when it's outside of the
+/// globs the agent is restricted to.
+fn check_path(agent: &Agent, input: &Tools, path: &Path, cwd: &Path) -> Result<(), Error> {
+    let Some(permissions) = agent.permissions.as_ref() else {
+        return Ok(());
+    };
+
+    let (access, globs, allowed) = match input {
+        Tools::ForgeToolFsRead(_)
+        | Tools::ForgeToolFsSearch(_)
+        | Tools::ForgeToolGitLog(_)
+        | Tools::ForgeToolGitBlame(_)
+        | Tools::ForgeToolDiscoverTasks(_)
+        | Tools::ForgeToolArchiveList(_)
+        | Tools::ForgeToolArchiveExtract(_)
+        | Tools::ForgeToolImageDiff(_) => {
+            ("read", &permissions.read, permissions.can_read(path, cwd))
+        }
+        Tools::ForgeToolLint(lint) if !lint.fix => {
+            ("read", &permissions.read, permissions.can_read(path, cwd))
+        }
+        Tools::ForgeToolProcessShell(_) => (
+            "run commands in",
+            &permissions.write,
+            permissions.can_write(path, cwd),
+        ),
+        _ => (
+            "modify",
+            &permissions.write,
+            permissions.can_write(path, cwd),
+        ),
+    };
+    if allowed {
+        return Ok(());
+    }
+
+    Err(Error::PathNotPermitted {
+        agent: agent.id.clone(),
+        access,
+        path: path.display().to_string(),
+        allowed: globs
+            .iter()
+            .flatten()
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(", "),
+    })
+}
+
+/// Lines listing the templates with their variables and descriptions
+fn list_scaffolds(templates: &[ScaffoldTemplate]) -> String {
+    if templates.is_empty() {
+        return "none, no project templates are defined in forge.yaml".to_string();
+    }
+    templates
+        .iter()
+        .map(|template| match &template.description {
+            Some(description) => format!("\n- {}: {description}", template.signature()),
+            None => format!("\n- {}", template.signature()),
+        })
+        .collect()
+}
+
+/// Path of the file modified by `input`, `None` for the tools the
+/// read-before-write policy doesn't apply to
+fn written_path(input: &Tools) -> Option<&String> {
+    match input {
+        Tools::ForgeToolFsCreate(input) => Some(&input.path),
+        Tools::ForgeToolFsWriteBegin(input) => Some(&input.path),
+        Tools::ForgeToolFsPatch(input) => Some(&input.path),
+        _ => None,
+    }
+}
+
+/// Fails when the existing file at `path` hasn't been read. New files have no
+/// content that could be overlooked.
+fn require_read(path: &str, read_files: &BTreeSet<String>, exists: bool) -> anyhow::Result<()> {
+    if !exists || read_files.contains(path) {
+        return Ok(());
+    }
+    Err(Error::UnreadFile(path.to_string()).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use forge_domain::{FSRead, FSWriteBegin};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_require_read_rejects_unread_file() {
+        let fixture = BTreeSet::new();
+
+        let actual = require_read("/project/src/main.rs", &fixture, true)
+            .unwrap_err()
+            .to_string();
+
+        let expected = Error::UnreadFile("/project/src/main.rs".to_string()).to_string();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_require_read_accepts_file_read_earlier() {
+        let fixture = BTreeSet::from(["/project/src/main.rs".to_string()]);
+
+        let actual = require_read("/project/src/main.rs", &fixture, true).is_ok();
+
+        let expected = true;
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_require_read_accepts_new_file() {
+        let fixture = BTreeSet::new();
+
+        let actual = require_read("/project/src/lib.rs", &fixture, false).is_ok();
+
+        let expected = true;
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_written_path() {
+        let fixture = [
+            Tools::ForgeToolFsWriteBegin(FSWriteBegin {
+                path: "/project/a.rs".to_string(),
+                overwrite: true,
+                allow_large: false,
+                explanation: None,
+            }),
+            Tools::ForgeToolFsRead(FSRead {
+                path: "/project/b.rs".to_string(),
+                start_line: None,
+                end_line: None,
+                explanation: None,
+            }),
+        ];
+
+        let actual = fixture.iter().map(written_path).collect::<Vec<_>>();
+
+        let expected = vec![Some(&"/project/a.rs".to_string()), None];
+        assert_eq!(actual, expected);
+    }
 }
@@ -3,8 +3,8 @@ use std::time::Duration;
 
 use anyhow::Context;
 use forge_domain::{
-    Agent, AgentInput, ToolCallContext, ToolCallFull, ToolDefinition, ToolName, ToolOutput,
-    ToolResult, Tools, ToolsDiscriminants,
+    Agent, AgentInput, ToolCallContext, ToolCallFull, ToolDefinition, ToolGate, ToolName,
+    ToolOutput, ToolResult, Tools, ToolsDiscriminants, mcp_server_of,
 };
 use strum::IntoEnumIterator;
 use tokio::time::timeout;
@@ -56,14 +56,18 @@ impl<S: Services> ToolRegistry<S> {
         context: &mut ToolCallContext,
     ) -> anyhow::Result<ToolOutput> {
         Self::validate_tool_call(agent, &input.name)?;
+        Self::validate_gate(agent, &input.name, context)?;
+        Self::validate_mcp_server(&input.name, context)?;
 
         tracing::info!(tool_name = %input.name, arguments = %input.arguments, "Executing tool call");
         let tool_name = input.name.clone();
 
         // First, try to call a Forge tool
         if Tools::contains(&input.name) {
-            self.call_with_timeout(&tool_name, || self.tool_executor.execute(input, context))
-                .await
+            self.call_with_timeout(&tool_name, || {
+                self.tool_executor.execute(agent, input, context)
+            })
+            .await
         } else if self.agent_executor.contains_tool(&input.name).await? {
             // Handle agent delegation tool calls
             let agent_input = AgentInput::try_from(&input)?;
@@ -72,8 +76,10 @@ impl<S: Services> ToolRegistry<S> {
                 .execute(input.name.to_string(), agent_input.task, context)
                 .await
         } else if self.mcp_executor.contains_tool(&input.name).await? {
-            self.call_with_timeout(&tool_name, || self.mcp_executor.execute(input, context))
-                .await
+            self.call_with_timeout(&tool_name, || {
+                self.mcp_executor.execute(agent, input, context)
+            })
+            .await
         } else {
             Err(Error::NotFound(input.name).into())
         }
@@ -131,13 +137,46 @@ impl<S> ToolRegistry<S> {
         }
         Ok(())
     }
+
+    /// Verifies the tool gates of the workflow allow the tool, the model may
+    /// call a tool that wasn't offered to it
+    fn validate_gate(
+        agent: &Agent,
+        tool_name: &ToolName,
+        context: &ToolCallContext,
+    ) -> Result<(), Error> {
+        let mut touched = context.touched_files.clone();
+        touched.extend(context.changes.files.iter().map(|file| file.path.clone()));
+        if ToolGate::new(&context.tool_gates).allows(tool_name, &agent.id, &touched) {
+            return Ok(());
+        }
+        Err(Error::Gated(tool_name.clone()))
+    }
+
+    /// Verifies the MCP server of the tool isn't disabled in the conversation,
+    /// the model may call the tools it wasn't offered
+    fn validate_mcp_server(tool_name: &ToolName, context: &ToolCallContext) -> Result<(), Error> {
+        match mcp_server_of(tool_name) {
+            Some(server) if context.disabled_mcp_servers.contains(server) => {
+                Err(Error::DisabledMcpServer {
+                    tool: tool_name.clone(),
+                    server: server.to_string(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use forge_domain::{Agent, AgentId, ToolName, Tools, ToolsDiscriminants};
+    use forge_domain::{
+        Agent, AgentId, TaskList, ToolCallContext, ToolGateRule, ToolName, Tools,
+        ToolsDiscriminants,
+    };
     use pretty_assertions::assert_eq;
 
+    use crate::error::Error;
     use crate::tool_registry::ToolRegistry;
 
     fn agent() -> Agent {
@@ -171,6 +210,46 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_gated_tool_call() {
+        let fixture = ToolCallContext::new(TaskList::new()).tool_gates(vec![
+            ToolGateRule::default()
+                .tools(vec!["forge_tool_fs_read".to_string()])
+                .hide_for(vec![AgentId::new("test_agent")]),
+        ]);
+
+        let actual = ToolRegistry::<()>::validate_gate(
+            &agent(),
+            &ToolName::new("forge_tool_fs_read"),
+            &fixture,
+        )
+        .unwrap_err()
+        .to_string();
+
+        let expected = Error::Gated(ToolName::new("forge_tool_fs_read")).to_string();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_disabled_mcp_server_tool_call() {
+        let fixture = ToolCallContext::new(TaskList::new())
+            .disabled_mcp_servers(["github".to_string()].into());
+
+        let actual = ToolRegistry::<()>::validate_mcp_server(
+            &ToolName::new("mcp_github_tool_create_issue"),
+            &fixture,
+        )
+        .unwrap_err()
+        .to_string();
+
+        let expected = Error::DisabledMcpServer {
+            tool: ToolName::new("mcp_github_tool_create_issue"),
+            server: "github".to_string(),
+        }
+        .to_string();
+        assert_eq!(actual, expected);
+    }
+
     #[tokio::test]
     async fn test_completion_tool_call() {
         let result = ToolRegistry::<()>::validate_tool_call(
@@ -0,0 +1,193 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use forge_domain::{CommandOutput, Tools};
+use forge_template::Element;
+
+use crate::{FsMetaService, PathResolverService};
+
+/// Number of trailing stderr lines attached to a failed tool call.
+const MAX_STDERR_LINES: usize = 20;
+
+/// Gathers diagnostics correlated with a failed tool call so that the model
+/// gets actionable context instead of a bare error message.
+pub struct Triage<S> {
+    services: Arc<S>,
+}
+
+impl<S: FsMetaService + PathResolverService> Triage<S> {
+    pub fn new(services: Arc<S>) -> Self {
+        Self { services }
+    }
+
+    /// Returns the diagnostics for a failed call of `input`, or `None` if
+    /// nothing relevant could be found. `last_failed_command` is the most
+    /// recent shell command that exited with a non-zero status, its stderr
+    /// is only attached to the failures of other shell commands.
+    pub async fn diagnose(
+        &self,
+        input: &Tools,
+        last_failed_command: Option<&CommandOutput>,
+    ) -> Option<Element> {
+        let mut diagnostics = Vec::new();
+
+        if let Some(path) = target_path(input) {
+            diagnostics.push(self.path_status(&path).await);
+        }
+
+        if let Some(output) = related_failure(input, last_failed_command) {
+            diagnostics.push(recent_failure(output));
+        }
+
+        if diagnostics.is_empty() {
+            None
+        } else {
+            Some(Element::new("diagnostics").append(diagnostics))
+        }
+    }
+
+    async fn path_status(&self, path: &Path) -> Element {
+        let exists = self.services.exists(path).await.unwrap_or_default();
+        let mut element = Element::new("path")
+            .attr("exists", exists)
+            .text(path.display());
+
+        if exists {
+            let is_file = self.services.is_file(path).await.unwrap_or_default();
+            element = element.attr("kind", if is_file { "file" } else { "directory" });
+        } else {
+            if let Some(parent) = path.parent() {
+                let parent_exists = self.services.exists(parent).await.unwrap_or_default();
+                element = element.attr("parent_exists", parent_exists);
+            }
+            let similar = self.services.similar_paths(path).await.unwrap_or_default();
+            element = element.append(
+                similar
+                    .into_iter()
+                    .map(|path| Element::new("did_you_mean").text(path.display())),
+            );
+        }
+
+        element
+    }
+}
+
+/// The path a tool operates on, if any.
+fn target_path(input: &Tools) -> Option<PathBuf> {
+    match input {
+        Tools::ForgeToolFsRead(input) => Some(PathBuf::from(&input.path)),
+        Tools::ForgeToolFsCreate(input) => Some(PathBuf::from(&input.path)),
+        Tools::ForgeToolFsWriteBegin(input) => Some(PathBuf::from(&input.path)),
+        Tools::ForgeToolFsWriteAppend(input) => Some(PathBuf::from(&input.path)),
+        Tools::ForgeToolFsWriteCommit(input) => Some(PathBuf::from(&input.path)),
+        Tools::ForgeToolFsSearch(input) => Some(PathBuf::from(&input.path)),
+        Tools::ForgeToolFsRemove(input) => Some(PathBuf::from(&input.path)),
+        Tools::ForgeToolFsRemoveDir(input) => Some(PathBuf::from(&input.path)),
+        Tools::ForgeToolFsPatch(input) => Some(PathBuf::from(&input.path)),
+        Tools::ForgeToolFsUndo(input) => Some(PathBuf::from(&input.path)),
+        Tools::ForgeToolProcessShell(input) => Some(input.cwd.clone()),
+        Tools::ForgeToolGitLog(input) => input.path.as_ref().map(PathBuf::from),
+        Tools::ForgeToolGitBlame(input) => Some(PathBuf::from(&input.path)),
+        Tools::ForgeToolDiscoverTasks(input) => input.path.as_ref().map(PathBuf::from),
+        _ => None,
+    }
+}
+
+/// The failed shell command whose output explains the failure of `input`.
+/// Only shell commands are related, the errors of other tools don't depend on
+/// a previous command.
+fn related_failure<'a>(
+    input: &Tools,
+    last_failed_command: Option<&'a CommandOutput>,
+) -> Option<&'a CommandOutput> {
+    last_failed_command.filter(|_| matches!(input, Tools::ForgeToolProcessShell(_)))
+}
+
+fn recent_failure(output: &CommandOutput) -> Element {
+    let lines = output.stderr.lines().collect::<Vec<_>>();
+    let start = lines.len().saturating_sub(MAX_STDERR_LINES);
+
+    Element::new("recent_shell_failure")
+        .attr("command", &output.command)
+        .attr_if_some("exit_code", output.exit_code)
+        .append(Element::new("stderr").cdata(lines[start..].join("\n")))
+}
+
+#[cfg(test)]
+mod tests {
+    use forge_domain::{FSRead, Shell};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_target_path() {
+        let fixture = Tools::ForgeToolFsRead(FSRead {
+            path: "/project/src/main.rs".to_string(),
+            start_line: None,
+            end_line: None,
+            explanation: None,
+        });
+        let actual = target_path(&fixture);
+        let expected = Some(PathBuf::from("/project/src/main.rs"));
+        assert_eq!(actual, expected);
+    }
+
+    fn failed_command() -> CommandOutput {
+        CommandOutput {
+            command: "cargo test".to_string(),
+            stdout: String::new(),
+            stderr: "error: test failed".to_string(),
+            exit_code: Some(101),
+        }
+    }
+
+    #[test]
+    fn test_related_failure_of_shell() {
+        let failure = failed_command();
+        let fixture = Tools::ForgeToolProcessShell(Shell {
+            command: "cargo test".to_string(),
+            cwd: PathBuf::from("/project"),
+            keep_ansi: false,
+            explanation: None,
+        });
+        let actual =
+            related_failure(&fixture, Some(&failure)).map(|output| output.command.as_str());
+        let expected = Some("cargo test");
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_related_failure_ignores_unrelated_tool() {
+        let failure = failed_command();
+        let fixture = Tools::ForgeToolFsRead(FSRead {
+            path: "/project/package.json".to_string(),
+            start_line: None,
+            end_line: None,
+            explanation: None,
+        });
+        let actual =
+            related_failure(&fixture, Some(&failure)).map(|output| output.command.as_str());
+        let expected = None;
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_recent_failure_keeps_stderr_tail() {
+        let fixture = CommandOutput {
+            command: "cargo build".to_string(),
+            stdout: String::new(),
+            stderr: (0..MAX_STDERR_LINES + 5)
+                .map(|i| format!("error {i}"))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            exit_code: Some(101),
+        };
+
+        let actual = recent_failure(&fixture).render();
+
+        assert!(actual.contains(r#"exit_code="101""#));
+        assert!(actual.contains("error 5\n"));
+        assert!(!actual.contains("error 4\n"));
+    }
+}
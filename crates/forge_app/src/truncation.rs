@@ -1,34 +1,52 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use forge_domain::Environment;
 
 use crate::utils::format_match;
 use crate::{FsCreateService, Match};
 
+/// Writes `content` to a new file in `scratch_dir`, or in the system
+/// temporary directory when there is none.
 pub async fn create_temp_file<S: FsCreateService>(
     services: &S,
+    scratch_dir: Option<&Path>,
     prefix: &str,
     ext: &str,
     content: &str,
 ) -> anyhow::Result<PathBuf> {
-    let path = tempfile::Builder::new()
-        .disable_cleanup(true)
-        .prefix(prefix)
-        .suffix(ext)
-        .tempfile()?
-        .into_temp_path()
-        .to_path_buf();
+    let mut builder = tempfile::Builder::new();
+    builder.disable_cleanup(true).prefix(prefix).suffix(ext);
+    let file = match scratch_dir {
+        Some(dir) => {
+            prepare_scratch_dir(dir).await?;
+            builder.tempfile_in(dir)?
+        }
+        None => builder.tempfile()?,
+    };
+    let path = file.into_temp_path().to_path_buf();
     services
         .create(
             path.to_string_lossy().to_string(),
             content.to_string(),
             true,
+            true,
             false,
         )
         .await?;
     Ok(path)
 }
 
+/// Creates the scratch directory with a `.gitignore` that keeps its content
+/// out of version control.
+pub(crate) async fn prepare_scratch_dir(dir: &Path) -> anyhow::Result<()> {
+    let gitignore = dir.join(".gitignore");
+    if !tokio::fs::try_exists(&gitignore).await? {
+        tokio::fs::create_dir_all(dir).await?;
+        tokio::fs::write(gitignore, "*\n").await?;
+    }
+    Ok(())
+}
+
 /// Clips text content based on line count
 fn clip_by_lines(
     content: &str,
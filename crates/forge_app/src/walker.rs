@@ -2,6 +2,10 @@ use std::path::PathBuf;
 
 use derive_setters::Setters;
 
+/// Number of entries from which a workspace is walked in sparse mode, e.g. a
+/// home directory forge was started in by accident
+pub const MAX_WORKSPACE_ENTRIES: usize = 20_000;
+
 /// Configuration for filesystem walking operations
 #[derive(Debug, Clone, Setters)]
 #[setters(strip_option, into)]
@@ -36,6 +40,12 @@ impl Walker {
         }
     }
 
+    /// Creates a new WalkerConfig for sparse mode, where only the top level
+    /// of a workspace too large to be walked deeper is listed
+    pub fn sparse() -> Self {
+        Self { max_depth: Some(1), ..Self::conservative() }
+    }
+
     /// Creates a new WalkerConfig with no limits (use with caution)
     pub fn unlimited() -> Self {
         Self {
@@ -56,6 +66,21 @@ impl Default for Walker {
     }
 }
 
+/// Number of entries of a workspace, counted before walking it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WorkspaceSize {
+    /// Entries counted, at most `MAX_WORKSPACE_ENTRIES`
+    pub entries: usize,
+}
+
+impl WorkspaceSize {
+    /// Whether the workspace is too large to be walked in full and is walked
+    /// in sparse mode instead
+    pub fn is_large(&self) -> bool {
+        self.entries >= MAX_WORKSPACE_ENTRIES
+    }
+}
+
 /// Represents a file or directory found during filesystem traversal
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct WalkedFile {
@@ -0,0 +1,9 @@
+mod report;
+mod runner;
+mod sandbox;
+mod task;
+
+pub use report::*;
+pub use runner::*;
+pub use sandbox::*;
+pub use task::*;
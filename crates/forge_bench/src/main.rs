@@ -0,0 +1,72 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Parser;
+use forge_api::{AgentId, ModelId};
+use forge_bench::{load_suite, run_task, Report};
+
+/// Runs a suite of scripted coding tasks with each model and agent, and
+/// compares how many they solve, the size of their changes and their cost
+#[derive(Parser)]
+#[command(name = "forge-bench")]
+struct Cli {
+    /// YAML fixture of a task, or directory of fixtures
+    suite: PathBuf,
+
+    /// Model the tasks are run with. Repeat to compare models.
+    #[arg(long = "model", required = true)]
+    models: Vec<String>,
+
+    /// Agent the tasks are sent to. Repeat to compare agents.
+    #[arg(long = "agent", default_value = "forge")]
+    agents: Vec<String>,
+
+    /// File the report is written to, as JSON when its extension is `json`
+    /// and as Markdown otherwise. Printed when unset.
+    #[arg(long, short)]
+    output: Option<PathBuf>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let tasks = load_suite(&cli.suite)?;
+
+    let runs = tasks.len() * cli.models.len() * cli.agents.len();
+    let mut report = Report::default();
+    for model in cli.models.iter().map(ModelId::new) {
+        for agent in cli.agents.iter().map(AgentId::new) {
+            for task in &tasks {
+                let outcome = run_task(task, &model, &agent).await;
+                let result = match (&outcome.error, outcome.passed) {
+                    (_, true) => "passed",
+                    (Some(_), false) => "error",
+                    (None, false) => "failed",
+                };
+                eprintln!(
+                    "[{}/{runs}] {} with {model} ({agent}): {result}",
+                    report.outcomes.len() + 1,
+                    task.name
+                );
+                report.outcomes.push(outcome);
+            }
+        }
+    }
+
+    match cli.output {
+        Some(path)
+            if path
+                .extension()
+                .is_some_and(|extension| extension == "json") =>
+        {
+            let json = serde_json::json!({
+                "summaries": report.summaries(),
+                "outcomes": report.outcomes,
+            });
+            std::fs::write(path, serde_json::to_string_pretty(&json)?)?;
+        }
+        Some(path) => std::fs::write(path, report.to_markdown())?,
+        None => println!("{}", report.to_markdown()),
+    }
+    Ok(())
+}
@@ -0,0 +1,200 @@
+use forge_api::{AgentId, ModelId};
+use serde::Serialize;
+
+/// Score of a task run with a model and an agent
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Outcome {
+    pub task: String,
+    pub model: ModelId,
+    pub agent: AgentId,
+    /// Whether the test command of the task succeeded after the agent was
+    /// done
+    pub passed: bool,
+    /// Lines added and removed by the agent
+    pub diff_lines: usize,
+    /// Dollars spent, when the provider reports the cost of its requests
+    pub cost: Option<f64>,
+    /// Requests made to the model
+    pub turns: usize,
+    pub tool_calls: usize,
+    pub duration_ms: u128,
+    /// Why the run stopped before the agent was done, e.g. a spent budget
+    pub error: Option<String>,
+}
+
+impl Outcome {
+    pub fn new(task: impl ToString, model: ModelId, agent: AgentId) -> Self {
+        Self {
+            task: task.to_string(),
+            model,
+            agent,
+            passed: false,
+            diff_lines: 0,
+            cost: None,
+            turns: 0,
+            tool_calls: 0,
+            duration_ms: 0,
+            error: None,
+        }
+    }
+}
+
+/// Aggregated scores of a model and an agent over the suite
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Summary {
+    pub model: ModelId,
+    pub agent: AgentId,
+    pub tasks: usize,
+    pub passed: usize,
+    pub cost: Option<f64>,
+    pub mean_diff_lines: f64,
+    pub mean_turns: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Report {
+    pub outcomes: Vec<Outcome>,
+}
+
+impl Report {
+    /// Summaries of each model and agent, in the order they were run
+    pub fn summaries(&self) -> Vec<Summary> {
+        let mut candidates = Vec::<(&ModelId, &AgentId)>::new();
+        for outcome in &self.outcomes {
+            if !candidates.contains(&(&outcome.model, &outcome.agent)) {
+                candidates.push((&outcome.model, &outcome.agent));
+            }
+        }
+
+        candidates
+            .into_iter()
+            .map(|(model, agent)| {
+                let outcomes = self
+                    .outcomes
+                    .iter()
+                    .filter(|outcome| &outcome.model == model && &outcome.agent == agent)
+                    .collect::<Vec<_>>();
+                let tasks = outcomes.len();
+                let mean = |value: fn(&Outcome) -> usize| {
+                    outcomes.iter().map(|outcome| value(outcome)).sum::<usize>() as f64
+                        / tasks as f64
+                };
+                Summary {
+                    model: model.clone(),
+                    agent: agent.clone(),
+                    tasks,
+                    passed: outcomes.iter().filter(|outcome| outcome.passed).count(),
+                    cost: outcomes
+                        .iter()
+                        .filter_map(|outcome| outcome.cost)
+                        .reduce(|total, cost| total + cost),
+                    mean_diff_lines: mean(|outcome| outcome.diff_lines),
+                    mean_turns: mean(|outcome| outcome.turns),
+                }
+            })
+            .collect()
+    }
+
+    /// Comparison of the models and agents followed by the outcome of each
+    /// task
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::from("## Summary\n\n");
+        markdown.push_str("| Model | Agent | Passed | Cost | Mean diff lines | Mean turns |\n");
+        markdown.push_str("| --- | --- | --- | --- | --- | --- |\n");
+        for summary in self.summaries() {
+            markdown.push_str(&format!(
+                "| {} | {} | {}/{} | {} | {:.1} | {:.1} |\n",
+                summary.model,
+                summary.agent,
+                summary.passed,
+                summary.tasks,
+                format_cost(summary.cost),
+                summary.mean_diff_lines,
+                summary.mean_turns
+            ));
+        }
+
+        markdown.push_str("\n## Tasks\n\n");
+        markdown.push_str(
+            "| Task | Model | Agent | Result | Diff lines | Cost | Turns | Tool calls | Duration |\n",
+        );
+        markdown.push_str("| --- | --- | --- | --- | --- | --- | --- | --- | --- |\n");
+        for outcome in &self.outcomes {
+            let result = match (&outcome.error, outcome.passed) {
+                (Some(error), _) => {
+                    format!("error: {}", error.replace('\n', " ").replace('|', "\\|"))
+                }
+                (None, true) => "passed".to_string(),
+                (None, false) => "failed".to_string(),
+            };
+            markdown.push_str(&format!(
+                "| {} | {} | {} | {result} | {} | {} | {} | {} | {}s |\n",
+                outcome.task,
+                outcome.model,
+                outcome.agent,
+                outcome.diff_lines,
+                format_cost(outcome.cost),
+                outcome.turns,
+                outcome.tool_calls,
+                outcome.duration_ms / 1000
+            ));
+        }
+        markdown
+    }
+}
+
+fn format_cost(cost: Option<f64>) -> String {
+    cost.map(|cost| format!("${cost:.4}"))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn outcome(task: &str, model: &str, passed: bool, diff_lines: usize, turns: usize) -> Outcome {
+        Outcome {
+            passed,
+            diff_lines,
+            cost: Some(0.01),
+            turns,
+            tool_calls: turns * 2,
+            duration_ms: 12_000,
+            ..Outcome::new(task, ModelId::new(model), AgentId::FORGE)
+        }
+    }
+
+    #[test]
+    fn test_to_markdown() {
+        let fixture = Report {
+            outcomes: vec![
+                outcome("greet", "gpt-4.1", true, 4, 3),
+                outcome("sum", "gpt-4.1", false, 9, 6),
+                Outcome {
+                    error: Some("Budget spent after 20 request(s) in 61s".to_string()),
+                    cost: None,
+                    ..outcome("greet", "o3", false, 0, 20)
+                },
+            ],
+        };
+
+        let actual = fixture.to_markdown();
+
+        let expected = concat!(
+            "## Summary\n\n",
+            "| Model | Agent | Passed | Cost | Mean diff lines | Mean turns |\n",
+            "| --- | --- | --- | --- | --- | --- |\n",
+            "| gpt-4.1 | forge | 1/2 | $0.0200 | 6.5 | 4.5 |\n",
+            "| o3 | forge | 0/1 | - | 0.0 | 20.0 |\n",
+            "\n## Tasks\n\n",
+            "| Task | Model | Agent | Result | Diff lines | Cost | Turns | Tool calls | Duration |\n",
+            "| --- | --- | --- | --- | --- | --- | --- | --- | --- |\n",
+            "| greet | gpt-4.1 | forge | passed | 4 | $0.0100 | 3 | 6 | 12s |\n",
+            "| sum | gpt-4.1 | forge | failed | 9 | $0.0100 | 6 | 12 | 12s |\n",
+            "| greet | o3 | forge | error: Budget spent after 20 request(s) in 61s | 0 | - | 20 | 40 | 12s |\n",
+        );
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,103 @@
+use std::time::Instant;
+
+use anyhow::Result;
+use forge_api::{AgentId, ChatResponse, Event, ForgeClient, InterruptionReason, ModelId, API};
+use tokio_stream::StreamExt;
+
+use crate::{BenchTask, Outcome, Sandbox};
+
+/// Event starting a task, as sent by the CLI for a new prompt
+const EVENT_USER_TASK_INIT: &str = "user_task_init";
+
+/// Runs the task with the model and the agent in a new sandbox, then scores
+/// the changes of the agent with the test command of the task
+pub async fn run_task(task: &BenchTask, model: &ModelId, agent: &AgentId) -> Outcome {
+    let started_at = Instant::now();
+    let mut outcome = Outcome::new(&task.name, model.clone(), agent.clone());
+    if let Err(error) = run(task, &mut outcome).await {
+        outcome.error = Some(format!("{error:#}"));
+    }
+    outcome.duration_ms = started_at.elapsed().as_millis();
+    outcome
+}
+
+async fn run(task: &BenchTask, outcome: &mut Outcome) -> Result<()> {
+    let sandbox = Sandbox::create(&task.files).await?;
+    if let Some(setup) = &task.setup {
+        anyhow::ensure!(
+            sandbox.run(setup).await?,
+            "The setup command failed: {setup}"
+        );
+    }
+    sandbox.commit().await?;
+
+    chat(task, &sandbox, outcome).await?;
+
+    outcome.diff_lines = sandbox.diff_lines().await?;
+    outcome.passed = sandbox.run(&task.test).await?;
+    Ok(())
+}
+
+async fn chat(task: &BenchTask, sandbox: &Sandbox, outcome: &mut Outcome) -> Result<()> {
+    let client = ForgeClient::builder().workspace(sandbox.path()).build();
+
+    let path = sandbox.path().join("forge.yaml");
+    let mut workflow = client.api().read_merged(Some(&path)).await?;
+    workflow.model = Some(outcome.model.clone());
+    for agent in workflow.agents.iter_mut() {
+        agent.model = Some(outcome.model.clone());
+    }
+
+    // Nobody is there to approve the tools of a benchmark
+    let autonomy = task
+        .budget
+        .clone()
+        .or_else(|| workflow.autonomy.clone())
+        .unwrap_or_default();
+    let mut conversation = client.create_conversation_with(workflow).await?;
+    conversation.autonomy = Some(autonomy);
+    let conversation_id = conversation.id;
+    client.upsert_conversation(conversation).await?;
+
+    let event = Event::new(
+        format!("{}/{EVENT_USER_TASK_INIT}", outcome.agent),
+        Some(task.prompt.clone()),
+    );
+    let mut stream = client.chat_stream(&conversation_id, event).await?;
+    while let Some(message) = stream.next().await {
+        match message? {
+            ChatResponse::Usage(usage) => {
+                outcome.turns += 1;
+                if let Some(cost) = usage.cost {
+                    outcome.cost = Some(outcome.cost.unwrap_or_default() + cost);
+                }
+            }
+            ChatResponse::ToolCallStart(_) => outcome.tool_calls += 1,
+            ChatResponse::Interrupt { reason } => outcome.error = Some(describe(reason)),
+            ChatResponse::Blocked { reason } => {
+                outcome.error = Some(format!("Response blocked: {reason}"));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn describe(reason: InterruptionReason) -> String {
+    match reason {
+        InterruptionReason::MaxToolFailurePerTurnLimitReached { limit } => {
+            format!("Maximum tool failure limit ({limit}) reached")
+        }
+        InterruptionReason::MaxRequestPerTurnLimitReached { limit } => {
+            format!("Maximum request ({limit}) per turn achieved")
+        }
+        InterruptionReason::AutonomyBudgetExhausted { elapsed, requests } => format!(
+            "Budget spent after {requests} request(s) in {}s",
+            elapsed.as_secs()
+        ),
+        InterruptionReason::DecisionRequired { question } => {
+            format!("Decision required: {question}")
+        }
+    }
+}
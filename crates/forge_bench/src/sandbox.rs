@@ -0,0 +1,110 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use tempfile::TempDir;
+use tokio::process::Command;
+
+/// Temporary git repository a task is run in, removed when dropped. The files
+/// of the task are committed so that the changes of the agent can be measured.
+pub struct Sandbox {
+    dir: TempDir,
+}
+
+impl Sandbox {
+    pub async fn create(files: &BTreeMap<String, String>) -> Result<Self> {
+        let dir = tempfile::Builder::new().prefix("forge-bench-").tempdir()?;
+        for (path, content) in files {
+            let path = dir.path().join(path);
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            tokio::fs::write(&path, content)
+                .await
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+
+        let sandbox = Self { dir };
+        sandbox.git(&["init", "--quiet"]).await?;
+        Ok(sandbox)
+    }
+
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// Runs a shell command from the root of the sandbox, returning whether
+    /// it succeeded
+    pub async fn run(&self, command: &str) -> Result<bool> {
+        let output = Command::new("sh")
+            .args(["-c", command])
+            .current_dir(self.path())
+            .output()
+            .await
+            .with_context(|| format!("Failed to run {command}"))?;
+        Ok(output.status.success())
+    }
+
+    /// Commits the content of the sandbox as the baseline of the diff
+    pub async fn commit(&self) -> Result<()> {
+        self.git(&["add", "--all"]).await?;
+        self.git(&["commit", "--quiet", "--allow-empty", "-m", "baseline"])
+            .await?;
+        Ok(())
+    }
+
+    /// Lines added and removed since the baseline, new files included
+    pub async fn diff_lines(&self) -> Result<usize> {
+        self.git(&["add", "--all"]).await?;
+        let numstat = self.git(&["diff", "--cached", "--numstat"]).await?;
+        Ok(count_lines(&numstat))
+    }
+
+    async fn git(&self, args: &[&str]) -> Result<String> {
+        let output = Command::new("git")
+            .args([
+                "-c",
+                "user.name=forge-bench",
+                "-c",
+                "user.email=forge-bench@localhost",
+            ])
+            .args(args)
+            .current_dir(self.path())
+            .output()
+            .await?;
+        anyhow::ensure!(
+            output.status.success(),
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+/// Sums the added and removed lines of `git diff --numstat`, binary files
+/// counting as no line
+fn count_lines(numstat: &str) -> usize {
+    numstat
+        .lines()
+        .flat_map(|line| line.split('\t').take(2))
+        .filter_map(|count| count.parse::<usize>().ok())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_count_lines() {
+        let fixture = "3\t1\tsrc/lib.rs\n-\t-\tlogo.png\n12\t0\tsrc/new.rs\n";
+
+        let actual = count_lines(fixture);
+
+        let expected = 16;
+        assert_eq!(actual, expected);
+    }
+}
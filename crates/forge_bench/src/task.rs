@@ -0,0 +1,132 @@
+use std::collections::BTreeMap;
+use std::path::{Component, Path};
+
+use anyhow::{Context, Result};
+use forge_api::Autonomy;
+use serde::Deserialize;
+
+/// A scripted coding task, read from a YAML fixture:
+///
+/// ```yaml
+/// name: fix-off-by-one
+/// prompt: The tests of `sum` fail, fix the function
+/// files:
+///   Cargo.toml: |
+///     [package]
+///     name = "sum"
+/// test: cargo test --quiet
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BenchTask {
+    /// Name of the task in the report
+    pub name: String,
+    /// Prompt sent to the agent
+    pub prompt: String,
+    /// Content of the workspace the agent starts in, by path relative to
+    /// its root
+    #[serde(default)]
+    pub files: BTreeMap<String, String>,
+    /// Command preparing the workspace before the agent starts, e.g.
+    /// installing dependencies. Its changes aren't counted in the diff.
+    #[serde(default)]
+    pub setup: Option<String>,
+    /// Command deciding whether the task was solved, passing when it exits
+    /// successfully
+    pub test: String,
+    /// Budget of the agent. Defaults to the `autonomy` setting of the
+    /// workflow.
+    #[serde(default)]
+    pub budget: Option<Autonomy>,
+}
+
+impl BenchTask {
+    pub fn parse(content: &str) -> Result<Self> {
+        let task: Self = serde_yml::from_str(content)?;
+        anyhow::ensure!(!task.name.trim().is_empty(), "The task has no name");
+        for path in task.files.keys() {
+            let is_relative = Path::new(path)
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)));
+            anyhow::ensure!(
+                is_relative,
+                "The file {path} of the task {} is outside of its workspace",
+                task.name
+            );
+        }
+        Ok(task)
+    }
+}
+
+/// Reads the task of a fixture, or the tasks of every YAML fixture in a
+/// directory sorted by file name
+pub fn load_suite(path: &Path) -> Result<Vec<BenchTask>> {
+    let paths = if path.is_dir() {
+        let mut paths = std::fs::read_dir(path)
+            .with_context(|| format!("Failed to read the suite {}", path.display()))?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        paths.retain(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "yaml" || extension == "yml")
+        });
+        paths.sort();
+        paths
+    } else {
+        vec![path.to_path_buf()]
+    };
+
+    let tasks = paths
+        .iter()
+        .map(|path| {
+            std::fs::read_to_string(path)
+                .map_err(anyhow::Error::from)
+                .and_then(|content| BenchTask::parse(&content))
+                .with_context(|| format!("Failed to read the task {}", path.display()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    anyhow::ensure!(!tasks.is_empty(), "No task found in {}", path.display());
+    Ok(tasks)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let fixture = r#"
+name: greet
+prompt: Make `greet` return a greeting
+files:
+  src/greet.sh: "echo"
+test: sh src/greet.sh | grep -q hello
+budget:
+  max_requests: 20
+"#;
+
+        let actual = BenchTask::parse(fixture).unwrap();
+
+        let expected = BenchTask {
+            name: "greet".to_string(),
+            prompt: "Make `greet` return a greeting".to_string(),
+            files: BTreeMap::from([("src/greet.sh".to_string(), "echo".to_string())]),
+            setup: None,
+            test: "sh src/greet.sh | grep -q hello".to_string(),
+            budget: Some(Autonomy { max_requests: Some(20), ..Default::default() }),
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_file_outside_of_workspace() {
+        let fixture = "name: escape\nprompt: p\nfiles:\n  ../outside: x\ntest: 'true'\n";
+
+        let actual = BenchTask::parse(fixture).unwrap_err().to_string();
+
+        let expected = "The file ../outside of the task escape is outside of its workspace";
+        assert_eq!(actual, expected);
+    }
+}
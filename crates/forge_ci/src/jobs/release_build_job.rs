@@ -82,11 +82,21 @@ impl From<ReleaseBuilderJob> for Job {
                 .add_step(Step::run(
                     "cp ${{ matrix.binary_path }} ${{ matrix.binary_name }}",
                 ))
+                // Checksum verified by `forge update` before replacing the executable
+                .add_step(
+                    Step::run(
+                        "shasum -a 256 ${{ matrix.binary_name }} > ${{ matrix.binary_name }}.sha256",
+                    )
+                    .shell("bash"),
+                )
                 // Upload to the generated github release id
                 .add_step(
                     Step::uses("xresloader", "upload-to-github-release", "v1")
                         .add_with(("release_id", release_id))
-                        .add_with(("file", "${{ matrix.binary_name }}"))
+                        .add_with((
+                            "file",
+                            "${{ matrix.binary_name }};${{ matrix.binary_name }}.sha256",
+                        ))
                         .add_with(("overwrite", "true")),
                 );
         }
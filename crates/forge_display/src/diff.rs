@@ -1,8 +1,10 @@
 use std::fmt;
 
-use console::{style, Style};
+use colored::Colorize;
 use similar::{ChangeTag, TextDiff};
 
+use crate::theme;
+
 struct Line(Option<usize>);
 
 impl fmt::Display for Line {
@@ -17,37 +19,55 @@ impl fmt::Display for Line {
 pub struct DiffFormat;
 
 impl DiffFormat {
+    /// Counts the lines inserted and deleted between `old` and `new`.
+    pub fn line_counts(old: &str, new: &str) -> (usize, usize) {
+        TextDiff::from_lines(old, new).iter_all_changes().fold(
+            (0, 0),
+            |(insertions, deletions), change| match change.tag() {
+                ChangeTag::Insert => (insertions + 1, deletions),
+                ChangeTag::Delete => (insertions, deletions + 1),
+                ChangeTag::Equal => (insertions, deletions),
+            },
+        )
+    }
+
     pub fn format(old: &str, new: &str) -> String {
         let diff = TextDiff::from_lines(old, new);
         let ops = diff.grouped_ops(3);
         let mut output = String::new();
+        let theme = theme();
 
         if ops.is_empty() {
-            output.push_str(&format!("{}\n", style("No changes applied").dim()));
+            output.push_str(&format!("{}\n", "No changes applied".dimmed()));
             return output;
         }
 
         for (idx, group) in ops.iter().enumerate() {
             if idx > 0 {
-                output.push_str(&format!("{}\n", style("...").dim()));
+                output.push_str(&format!("{}\n", "...".dimmed()));
             }
             for op in group {
                 for change in diff.iter_inline_changes(op) {
-                    let (sign, s) = match change.tag() {
-                        ChangeTag::Delete => ("-", Style::new().blue()),
-                        ChangeTag::Insert => ("+", Style::new().yellow()),
-                        ChangeTag::Equal => (" ", Style::new().dim()),
+                    let paint = |text: &str| match change.tag() {
+                        ChangeTag::Delete => text.color(theme.delete),
+                        ChangeTag::Insert => text.color(theme.insert),
+                        ChangeTag::Equal => text.dimmed(),
+                    };
+                    let sign = match change.tag() {
+                        ChangeTag::Delete => "-",
+                        ChangeTag::Insert => "+",
+                        ChangeTag::Equal => " ",
                     };
 
                     output.push_str(&format!(
                         "{}{} |{}",
-                        style(Line(change.old_index())).dim(),
-                        style(Line(change.new_index())).dim(),
-                        s.apply_to(sign),
+                        Line(change.old_index()).to_string().dimmed(),
+                        Line(change.new_index()).to_string().dimmed(),
+                        paint(sign),
                     ));
 
                     for (_, value) in change.iter_strings_lossy() {
-                        output.push_str(&format!("{}", s.apply_to(value)));
+                        output.push_str(&format!("{}", paint(&value)));
                     }
                     if change.missing_newline() {
                         output.push('\n');
@@ -63,6 +83,7 @@ impl DiffFormat {
 mod tests {
     use console::strip_ansi_codes;
     use insta::assert_snapshot;
+    use pretty_assertions::assert_eq;
 
     use super::*;
 
@@ -98,4 +119,13 @@ mod tests {
         let clean_diff = strip_ansi_codes(&diff);
         assert_snapshot!(clean_diff);
     }
+
+    #[test]
+    fn test_line_counts() {
+        let old = "line 1\nline 2\nline 3\n";
+        let new = "line 1\nmodified line\nline 3\nline 4\n";
+        let actual = DiffFormat::line_counts(old, new);
+        let expected = (2, 1);
+        assert_eq!(actual, expected);
+    }
 }
@@ -1,9 +1,11 @@
 use std::collections::BTreeMap;
 
-use console::style;
+use colored::Colorize;
 use derive_setters::Setters;
 use regex::Regex;
 
+use crate::theme;
+
 /// RipGrepFormatter formats search results in ripgrep-like style.
 #[derive(Clone, Setters)]
 #[setters(into, strip_option)]
@@ -81,7 +83,7 @@ impl GrepFormat {
 
     /// Format a single line with colorization and consistent padding
     fn format_line(&self, num: &str, content: &str, padding: usize) -> String {
-        let num = style(format!("{num:>padding$}: ")).dim();
+        let num = format!("{num:>padding$}: ").dimmed();
 
         // Format the content with highlighting if regex is available
         let line = match self.regex {
@@ -91,7 +93,7 @@ impl GrepFormat {
                     format!(
                         "{}{}{}",
                         &content[..mat.start()],
-                        style(&content[mat.start()..mat.end()]).yellow().bold(),
+                        content[mat.start()..mat.end()].color(theme().accent).bold(),
                         &content[mat.end()..]
                     )
                 },
@@ -109,7 +111,7 @@ impl GrepFormat {
         group: Vec<(&str, &str)>,
         max_num_width: usize,
     ) -> String {
-        let file_header = style(path).cyan();
+        let file_header = path.color(theme().highlight);
         let formatted_lines = group
             .into_iter()
             .map(|(num, content)| self.format_line(num, content, max_num_width))
@@ -123,7 +125,7 @@ impl GrepFormat {
         let formatted_paths: Vec<_> = self
             .lines
             .iter()
-            .map(|line| format!("{}", style(line).cyan()))
+            .map(|line| format!("{}", line.color(theme().highlight)))
             .collect();
 
         // Join with newlines
@@ -0,0 +1,20 @@
+/// Wraps the text in an OSC 8 escape sequence, which terminals supporting it
+/// render as a link opening the URL. Other terminals show the text alone.
+pub fn hyperlink(url: &str, text: &str) -> String {
+    format!("\x1b]8;;{url}\x1b\\{text}\x1b]8;;\x1b\\")
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_hyperlink() {
+        let actual = hyperlink("file:///project/src/lib.rs#L3", "src/lib.rs:3");
+
+        let expected = "\x1b]8;;file:///project/src/lib.rs#L3\x1b\\src/lib.rs:3\x1b]8;;\x1b\\";
+        assert_eq!(actual, expected);
+    }
+}
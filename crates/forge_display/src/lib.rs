@@ -1,9 +1,13 @@
 pub mod diff;
 pub mod grep;
+pub mod hyperlink;
 pub mod markdown;
+pub mod theme;
 pub mod title;
 
 pub use diff::DiffFormat;
 pub use grep::GrepFormat;
+pub use hyperlink::hyperlink;
 pub use markdown::MarkdownFormat;
+pub use theme::*;
 pub use title::*;
@@ -3,6 +3,8 @@ use regex::Regex;
 use termimad::crossterm::style::{Attribute, Color};
 use termimad::{CompoundStyle, LineStyle, MadSkin};
 
+use crate::theme::{colors_enabled, theme};
+
 /// MarkdownFormat provides functionality for formatting markdown text for
 /// terminal display.
 #[derive(Clone, Setters, Default)]
@@ -13,10 +15,24 @@ pub struct MarkdownFormat {
 }
 
 impl MarkdownFormat {
-    /// Create a new MarkdownFormat with the default skin
+    /// Create a new MarkdownFormat with a skin following the current theme,
+    /// without styles when the output isn't colored
     pub fn new() -> Self {
-        let mut skin = MadSkin::default();
-        let compound_style = CompoundStyle::new(Some(Color::Cyan), None, Attribute::Bold.into());
+        if !colors_enabled() {
+            return Self { skin: MadSkin::no_style(), max_consecutive_newlines: 2 };
+        }
+
+        let theme = theme();
+        let mut skin = if theme.light {
+            MadSkin::default_light()
+        } else {
+            MadSkin::default()
+        };
+        let compound_style = CompoundStyle::new(
+            Some(to_crossterm(theme.highlight)),
+            None,
+            Attribute::Bold.into(),
+        );
         skin.inline_code = compound_style.clone();
 
         let codeblock_style = CompoundStyle::new(None, None, Default::default());
@@ -64,6 +80,30 @@ impl MarkdownFormat {
     }
 }
 
+/// Converts a color of the theme to its terminal equivalent
+fn to_crossterm(color: colored::Color) -> Color {
+    match color {
+        colored::Color::Black => Color::Black,
+        colored::Color::Red => Color::DarkRed,
+        colored::Color::Green => Color::DarkGreen,
+        colored::Color::Yellow => Color::DarkYellow,
+        colored::Color::Blue => Color::DarkBlue,
+        colored::Color::Magenta => Color::DarkMagenta,
+        colored::Color::Cyan => Color::DarkCyan,
+        colored::Color::White => Color::Grey,
+        colored::Color::BrightBlack => Color::DarkGrey,
+        colored::Color::BrightRed => Color::Red,
+        colored::Color::BrightGreen => Color::Green,
+        colored::Color::BrightYellow => Color::Yellow,
+        colored::Color::BrightBlue => Color::Blue,
+        colored::Color::BrightMagenta => Color::Magenta,
+        colored::Color::BrightCyan => Color::Cyan,
+        colored::Color::BrightWhite => Color::White,
+        colored::Color::AnsiColor(value) => Color::AnsiValue(value),
+        colored::Color::TrueColor { r, g, b } => Color::Rgb { r, g, b },
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
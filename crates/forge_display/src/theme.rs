@@ -0,0 +1,232 @@
+use std::io::IsTerminal;
+use std::sync::RwLock;
+
+use colored::Color;
+
+static THEME: RwLock<Option<Theme>> = RwLock::new(None);
+
+/// Colors of the terminal output by role
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    /// Whether the colors are meant for a light background
+    pub light: bool,
+    /// Regular text, e.g. the titles
+    pub text: Color,
+    /// Icons of the actions and completions
+    pub accent: Color,
+    /// Keys of the info sections, inline code and the current directory
+    pub highlight: Color,
+    /// Git branch and prompt indicator
+    pub success: Color,
+    pub error: Color,
+    /// Lines inserted in diffs
+    pub insert: Color,
+    /// Lines deleted in diffs
+    pub delete: Color,
+    /// Secondary text, e.g. line numbers and the status line
+    pub muted: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            light: false,
+            text: Color::White,
+            accent: Color::Yellow,
+            highlight: Color::BrightCyan,
+            success: Color::BrightGreen,
+            error: Color::Red,
+            insert: Color::Yellow,
+            delete: Color::Blue,
+            muted: Color::BrightBlack,
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            light: true,
+            text: Color::Black,
+            accent: Color::Magenta,
+            highlight: Color::Blue,
+            success: Color::Green,
+            error: Color::Red,
+            insert: Color::Green,
+            delete: Color::Red,
+            muted: Color::BrightBlack,
+        }
+    }
+
+    /// Palette of Solarized, readable on its dark and light backgrounds
+    pub fn solarized() -> Self {
+        let rgb = |r, g, b| Color::TrueColor { r, g, b };
+        Self {
+            light: false,
+            text: rgb(0x83, 0x94, 0x96),
+            accent: rgb(0xb5, 0x89, 0x00),
+            highlight: rgb(0x2a, 0xa1, 0x98),
+            success: rgb(0x85, 0x99, 0x00),
+            error: rgb(0xdc, 0x32, 0x2f),
+            insert: rgb(0x85, 0x99, 0x00),
+            delete: rgb(0xdc, 0x32, 0x2f),
+            muted: rgb(0x58, 0x6e, 0x75),
+        }
+    }
+
+    /// Replaces the color of a role, e.g. `insert`. Fails when the role or
+    /// the color is unknown.
+    pub fn set_color(&mut self, role: &str, value: &str) -> Result<(), String> {
+        let color = parse_color(value).ok_or_else(|| format!("Unknown color `{value}`"))?;
+        let slot = match role {
+            "text" => &mut self.text,
+            "accent" => &mut self.accent,
+            "highlight" => &mut self.highlight,
+            "success" => &mut self.success,
+            "error" => &mut self.error,
+            "insert" => &mut self.insert,
+            "delete" => &mut self.delete,
+            "muted" => &mut self.muted,
+            _ => return Err(format!("Unknown color role `{role}`")),
+        };
+        *slot = color;
+        Ok(())
+    }
+}
+
+/// Sets the theme of the following output
+pub fn set_theme(theme: Theme) {
+    *THEME.write().unwrap_or_else(|error| error.into_inner()) = Some(theme);
+}
+
+/// Theme of the output, the dark one unless another one was set
+pub fn theme() -> Theme {
+    THEME
+        .read()
+        .unwrap_or_else(|error| error.into_inner())
+        .clone()
+        .unwrap_or_default()
+}
+
+/// Parses a color name such as `cyan` or `bright blue`, or a hex code such as
+/// `#268bd2`
+pub fn parse_color(value: &str) -> Option<Color> {
+    let value = value.trim();
+    if let Some(hex) = value.strip_prefix('#') {
+        if hex.len() != 6 {
+            return None;
+        }
+        let channel = |index: usize| u8::from_str_radix(hex.get(index..index + 2)?, 16).ok();
+        return Some(Color::TrueColor { r: channel(0)?, g: channel(2)?, b: channel(4)? });
+    }
+
+    value.replace('_', " ").parse().ok()
+}
+
+/// Whether the output should be colored following the `NO_COLOR`,
+/// `CLICOLOR_FORCE` and `CLICOLOR` conventions, in that order of precedence
+pub fn should_colorize(var: impl Fn(&str) -> Option<String>, is_terminal: bool) -> bool {
+    let is_set = |name: &str| var(name).is_some_and(|value| !value.is_empty());
+    let is_enabled = |name: &str| var(name).is_some_and(|value| value != "0");
+    if is_set("NO_COLOR") {
+        false
+    } else if is_enabled("CLICOLOR_FORCE") {
+        true
+    } else if var("CLICOLOR").is_some_and(|value| value == "0") {
+        false
+    } else {
+        is_terminal
+    }
+}
+
+/// Enables or disables the colors of all the output according to the
+/// environment
+pub fn init_colors() {
+    let enabled = should_colorize(
+        |name| std::env::var(name).ok(),
+        std::io::stdout().is_terminal(),
+    );
+    colored::control::set_override(enabled);
+    console::set_colors_enabled(enabled);
+    console::set_colors_enabled_stderr(enabled);
+}
+
+/// Whether the output is colored
+pub fn colors_enabled() -> bool {
+    colored::control::SHOULD_COLORIZE.should_colorize()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars = pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect::<HashMap<_, _>>();
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn test_should_colorize() {
+        let actual = [
+            should_colorize(vars(&[]), true),
+            should_colorize(vars(&[]), false),
+            should_colorize(vars(&[("NO_COLOR", "1")]), true),
+            should_colorize(vars(&[("NO_COLOR", "")]), true),
+            should_colorize(vars(&[("CLICOLOR_FORCE", "1")]), false),
+            should_colorize(vars(&[("NO_COLOR", "1"), ("CLICOLOR_FORCE", "1")]), true),
+            should_colorize(vars(&[("CLICOLOR", "0")]), true),
+        ];
+
+        let expected = [true, false, false, true, true, false, false];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_color() {
+        let actual = [
+            parse_color("cyan"),
+            parse_color("bright_blue"),
+            parse_color("#268bd2"),
+            parse_color("#26"),
+            parse_color("mauve"),
+        ];
+
+        let expected = [
+            Some(Color::Cyan),
+            Some(Color::BrightBlue),
+            Some(Color::TrueColor { r: 0x26, g: 0x8b, b: 0xd2 }),
+            None,
+            None,
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_set_color() {
+        let mut fixture = Theme::light();
+
+        let actual = (
+            fixture.set_color("insert", "#859900"),
+            fixture.set_color("border", "red"),
+            fixture.insert,
+        );
+
+        let expected = (
+            Ok(()),
+            Err("Unknown color role `border`".to_string()),
+            Color::TrueColor { r: 0x85, g: 0x99, b: 0x00 },
+        );
+        assert_eq!(actual, expected);
+    }
+}
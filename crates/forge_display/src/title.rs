@@ -3,6 +3,8 @@ use std::fmt::{self, Display, Formatter};
 use colored::Colorize;
 use derive_setters::Setters;
 
+use crate::theme;
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum Category {
     Action,
@@ -82,13 +84,14 @@ impl TitleFormat {
 
     fn format(&self, with_timestamp: bool) -> String {
         let mut buf = String::new();
+        let theme = theme();
 
         let icon = match self.category {
-            Category::Action => "⏺".yellow(),
-            Category::Info => "⏺".white(),
-            Category::Debug => "⏺".cyan(),
-            Category::Error => "⏺".red(),
-            Category::Completion => "⏺".yellow(),
+            Category::Action => "⏺".color(theme.accent),
+            Category::Info => "⏺".color(theme.text),
+            Category::Debug => "⏺".color(theme.highlight),
+            Category::Error => "⏺".color(theme.error),
+            Category::Completion => "⏺".color(theme.accent),
         };
 
         buf.push_str(format!("{icon} ").as_str());
@@ -106,11 +109,11 @@ impl TitleFormat {
         }
 
         let title = match self.category {
-            Category::Action => self.title.white(),
-            Category::Info => self.title.white(),
+            Category::Action => self.title.color(theme.text),
+            Category::Info => self.title.color(theme.text),
             Category::Debug => self.title.dimmed(),
-            Category::Error => format!("{} {}", "ERROR:".bold(), self.title).red(),
-            Category::Completion => self.title.white().bold(),
+            Category::Error => format!("{} {}", "ERROR:".bold(), self.title).color(theme.error),
+            Category::Completion => self.title.color(theme.text).bold(),
         };
 
         buf.push_str(title.to_string().as_str());
@@ -11,8 +11,8 @@ use crate::merge::Key;
 use crate::temperature::Temperature;
 use crate::template::Template;
 use crate::{
-    Context, Error, EventContext, MaxTokens, ModelId, Result, SystemContext, ToolDefinition,
-    ToolName, TopK, TopP,
+    Context, Error, EventContext, MaxTokens, ModelId, PathPermissions, Result, SystemContext,
+    ToolChoice, ToolDefinition, ToolName, TopK, TopP,
 };
 
 // Unique identifier for an agent
@@ -32,6 +32,7 @@ impl AgentId {
 
     pub const FORGE: AgentId = AgentId(Cow::Borrowed("forge"));
     pub const MUSE: AgentId = AgentId(Cow::Borrowed("muse"));
+    pub const REVIEWER: AgentId = AgentId(Cow::Borrowed("reviewer"));
 }
 
 impl Default for AgentId {
@@ -83,6 +84,12 @@ pub struct Agent {
     #[merge(strategy = merge_opt_vec)]
     pub tools: Option<Vec<ToolName>>,
 
+    /// Whether and which tools the model may call. Use `none` for plain
+    /// question answering or `call: <tool>` to force a specific tool.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub tool_choice: Option<ToolChoice>,
+
     // The transforms feature has been removed
     /// Used to specify the events the agent is interested in    
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -168,6 +175,11 @@ pub struct Agent {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[merge(strategy = crate::merge::option)]
     pub reasoning: Option<ReasoningConfig>,
+
+    /// Path globs the agent's tools are restricted to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub permissions: Option<PathPermissions>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Merge, Setters, JsonSchema, PartialEq)]
@@ -234,6 +246,8 @@ impl Agent {
             top_k: Default::default(),
             max_tokens: Default::default(),
             reasoning: Default::default(),
+            permissions: Default::default(),
+            tool_choice: Default::default(),
         }
     }
 
@@ -0,0 +1,210 @@
+use std::path::{Component, Path, PathBuf};
+
+use strum_macros::Display;
+
+use crate::{ArchiveExtract, Error, Result};
+
+/// Largest archive the archive tools read
+pub const MAX_ARCHIVE_SIZE: u64 = 256 * 1024 * 1024;
+
+/// Most bytes extracted from an archive, so that an archive decompressing to
+/// far more than its size can't fill the disk
+pub const MAX_EXTRACTED_SIZE: u64 = 512 * 1024 * 1024;
+
+/// Most entries read from an archive
+pub const MAX_ARCHIVE_ENTRIES: usize = 10_000;
+
+/// Format of an archive, recognized from its first bytes rather than its
+/// name, since downloaded artifacts are often renamed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum ArchiveFormat {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+impl ArchiveFormat {
+    /// Format of the archive starting with the bytes, `None` for the other
+    /// files
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(b"PK\x03\x04") || bytes.starts_with(b"PK\x05\x06") {
+            Some(ArchiveFormat::Zip)
+        } else if bytes.starts_with(&[0x1f, 0x8b]) {
+            Some(ArchiveFormat::TarGz)
+        } else if bytes.get(257..262) == Some(b"ustar") {
+            Some(ArchiveFormat::Tar)
+        } else {
+            None
+        }
+    }
+}
+
+/// Kind of an entry of an archive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum ArchiveEntryKind {
+    File,
+    Directory,
+    /// A symbolic or hard link, listed but never extracted since it could
+    /// point outside of the destination
+    Link,
+}
+
+/// Entry of an archive, as stored in it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveEntry {
+    pub path: String,
+    pub kind: ArchiveEntryKind,
+    /// Size of the entry once extracted
+    pub size: u64,
+}
+
+/// Outcome of the extraction of an archive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveExtraction {
+    pub format: ArchiveFormat,
+    /// Directory the archive was extracted into
+    pub destination: PathBuf,
+    /// Number of files written
+    pub files: usize,
+    /// Bytes written
+    pub size: u64,
+    /// Entries that weren't extracted, like links
+    pub skipped: Vec<String>,
+}
+
+/// Path of an entry relative to the directory the archive is extracted into,
+/// `None` for the entries that would be written outside of it like
+/// `../../.bashrc` or `/etc/passwd`
+pub fn archive_entry_path(name: &str) -> Option<PathBuf> {
+    // Zip archives created on Windows may separate the components with \
+    let name = name.replace('\\', "/");
+    let mut path = PathBuf::new();
+    for component in Path::new(&name).components() {
+        match component {
+            Component::Normal(part) => path.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    (!path.as_os_str().is_empty()).then_some(path)
+}
+
+impl ArchiveExtract {
+    /// Directory of the scratch directory the archive is extracted into: the
+    /// one asked for or the name of the archive without its extensions
+    pub fn destination(&self, scratch_dir: &Path) -> Result<PathBuf> {
+        let name = match &self.destination {
+            Some(destination) => destination.clone(),
+            None => {
+                let file_name = Path::new(&self.path)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                let lowercase = file_name.to_lowercase();
+                let extension = [".tar.gz", ".tgz", ".tar", ".zip"]
+                    .into_iter()
+                    .find(|extension| lowercase.ends_with(extension))
+                    .map_or(0, str::len);
+                file_name[..file_name.len() - extension].to_string()
+            }
+        };
+        archive_entry_path(&name)
+            .map(|relative| scratch_dir.join(relative))
+            .ok_or(Error::InvalidArchiveDestination(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_detect_format() {
+        let mut tar = vec![0; 512];
+        tar[257..262].copy_from_slice(b"ustar");
+        let fixture = [
+            b"PK\x03\x04rest".to_vec(),
+            vec![0x1f, 0x8b, 0x08],
+            tar,
+            b"plain text".to_vec(),
+        ];
+
+        let actual = fixture
+            .iter()
+            .map(|bytes| ArchiveFormat::detect(bytes))
+            .collect::<Vec<_>>();
+
+        let expected = vec![
+            Some(ArchiveFormat::Zip),
+            Some(ArchiveFormat::TarGz),
+            Some(ArchiveFormat::Tar),
+            None,
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_entry_paths_stay_in_destination() {
+        let fixture = [
+            "bin/forge",
+            "./docs/README.md",
+            "../../.bashrc",
+            "/etc/passwd",
+            "lib/../../escape",
+            "..\\windows\\escape",
+            "./",
+        ];
+
+        let actual = fixture
+            .into_iter()
+            .map(archive_entry_path)
+            .collect::<Vec<_>>();
+
+        let expected = vec![
+            Some(PathBuf::from("bin/forge")),
+            Some(PathBuf::from("docs/README.md")),
+            None,
+            None,
+            None,
+            None,
+            None,
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_extract_destination() {
+        let scratch = Path::new("/project/.forge/tmp/conversation");
+        let fixture = [
+            ("downloads/forge-v1.2.0-x86_64.tar.gz", None),
+            ("vendor/BUNDLE.ZIP", None),
+            ("release.tgz", Some("release/latest")),
+            ("release.tgz", Some("../outside")),
+        ];
+
+        let actual = fixture
+            .into_iter()
+            .map(|(path, destination)| {
+                ArchiveExtract {
+                    path: path.to_string(),
+                    destination: destination.map(str::to_string),
+                    ..Default::default()
+                }
+                .destination(scratch)
+                .ok()
+            })
+            .collect::<Vec<_>>();
+
+        let expected = vec![
+            Some(scratch.join("forge-v1.2.0-x86_64")),
+            Some(scratch.join("BUNDLE")),
+            Some(scratch.join("release/latest")),
+            None,
+        ];
+        assert_eq!(actual, expected);
+    }
+}
@@ -22,7 +22,9 @@ impl Attachment {
     /// Parses a string and extracts all file paths in the format
     /// @[path/to/file]. File paths can contain spaces and are considered to
     /// extend until the closing bracket. If the closing bracket is missing,
-    /// consider everything until the end of the string as the path.
+    /// consider everything until the end of the string as the path. Resources
+    /// of MCP servers can be referred to without brackets too, as
+    /// @mcp:server/uri.
     pub fn parse_all<T: ToString>(text: T) -> HashSet<String> {
         let input = text.to_string();
         let mut remaining = input.as_str();
@@ -40,6 +42,14 @@ impl Attachment {
                 }
             }
         }
+        paths.extend(
+            input
+                .split_whitespace()
+                .filter_map(|word| word.strip_prefix("@mcp:"))
+                // The punctuation ending a sentence isn't part of the URI
+                .map(|reference| reference.trim_end_matches(['.', ',', ';', '!', '?', ')']))
+                .map(|reference| format!("mcp:{reference}")),
+        );
 
         paths
     }
@@ -114,6 +124,22 @@ mod tests {
         assert_eq!(paths.len(), 0);
     }
 
+    #[test]
+    fn test_attachment_parse_all_mcp_resources() {
+        let text = String::from(
+            "Compare @mcp:github/repo://forge/README.md, @[mcp:fs/file:///notes/todo.md] and \
+             @mcp:db/schema://users.",
+        );
+        let actual = Attachment::parse_all(text);
+
+        let expected = HashSet::from([
+            "mcp:github/repo://forge/README.md".to_string(),
+            "mcp:fs/file:///notes/todo.md".to_string(),
+            "mcp:db/schema://users".to_string(),
+        ]);
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn test_attachment_parse_all_with_multibyte_chars() {
         let text = String::from(
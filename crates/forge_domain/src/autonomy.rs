@@ -0,0 +1,229 @@
+use std::time::Duration;
+
+use merge::Merge;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{ChangeSummary, TaskList, ToolCallFull, Tools};
+
+/// Minutes an autonomous agent may work on a prompt when not configured
+const DEFAULT_MAX_MINUTES: u64 = 60;
+
+/// Requests an autonomous agent may make for a prompt when not configured
+const DEFAULT_MAX_REQUESTS: usize = 200;
+
+/// Requests between two progress reports when not configured
+const DEFAULT_REPORT_EVERY: usize = 10;
+
+/// Budget of an agent running without interactive approval, e.g. for an
+/// overnight batch refactor. The agent stops once the budget is spent or when
+/// it needs a decision from the user.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Merge, JsonSchema)]
+pub struct Autonomy {
+    /// Minutes the agent may work on a prompt. Defaults to 60.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub max_minutes: Option<u64>,
+    /// Requests to the model the agent may make for a prompt. Defaults to
+    /// 200.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub max_requests: Option<usize>,
+    /// Requests to the model between two progress reports. Defaults to 10.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub report_every: Option<usize>,
+}
+
+impl Autonomy {
+    pub fn max_duration(&self) -> Duration {
+        Duration::from_secs(self.max_minutes.unwrap_or(DEFAULT_MAX_MINUTES) * 60)
+    }
+
+    pub fn max_requests(&self) -> usize {
+        self.max_requests.unwrap_or(DEFAULT_MAX_REQUESTS)
+    }
+
+    /// Whether a progress report is due after the given number of requests
+    pub fn is_report_due(&self, requests: usize) -> bool {
+        let every = self.report_every.unwrap_or(DEFAULT_REPORT_EVERY).max(1);
+        requests > 0 && requests % every == 0
+    }
+
+    /// Whether the agent has spent its budget
+    pub fn is_exhausted(&self, elapsed: Duration, requests: usize) -> bool {
+        elapsed >= self.max_duration() || requests >= self.max_requests()
+    }
+
+    /// Question the user has to answer before the tool calls can be executed.
+    /// An autonomous agent stops there instead of waiting for the answer.
+    pub fn decision_required(tool_calls: &[ToolCallFull]) -> Option<String> {
+        tool_calls
+            .iter()
+            .filter_map(|call| Tools::try_from(call.clone()).ok())
+            .find_map(|tool| match tool {
+                Tools::ForgeToolFollowup(input) => Some(input.question),
+                Tools::ForgeToolFsRemoveDir(input) => Some(format!(
+                    "Remove the directory {} and everything in it?",
+                    input.path
+                )),
+                _ => None,
+            })
+    }
+}
+
+/// Progress of an agent running autonomously, sent periodically and when it
+/// stops
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProgressReport {
+    /// Tasks completed so far, or the files changed when the agent doesn't
+    /// track tasks
+    pub done: Vec<String>,
+    /// Tasks that are in progress or pending
+    pub next: Vec<String>,
+    /// Share of the tool calls since the previous report that succeeded,
+    /// from 0 to 1
+    pub confidence: f64,
+    /// Time spent on the prompt so far
+    pub elapsed: Duration,
+    /// Requests made to the model for the prompt so far
+    pub requests: usize,
+}
+
+impl ProgressReport {
+    pub fn new(
+        tasks: &TaskList,
+        changes: &ChangeSummary,
+        tool_calls: ToolCallStats,
+        elapsed: Duration,
+        requests: usize,
+    ) -> Self {
+        let mut done = tasks
+            .tasks()
+            .iter()
+            .filter(|task| task.is_done())
+            .map(|task| task.task.clone())
+            .collect::<Vec<_>>();
+        if done.is_empty() {
+            done = changes
+                .files
+                .iter()
+                .map(|file| format!("Changed {}", file.path))
+                .collect();
+        }
+
+        let next = tasks
+            .tasks()
+            .iter()
+            .filter(|task| task.is_in_progress())
+            .chain(tasks.tasks().iter().filter(|task| task.is_pending()))
+            .map(|task| task.task.clone())
+            .collect();
+
+        Self {
+            done,
+            next,
+            confidence: tool_calls.success_rate(),
+            elapsed,
+            requests,
+        }
+    }
+}
+
+/// Outcomes of the tool calls executed since the previous progress report
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ToolCallStats {
+    pub succeeded: usize,
+    pub failed: usize,
+}
+
+impl ToolCallStats {
+    pub fn record(&mut self, is_error: bool) {
+        if is_error {
+            self.failed += 1;
+        } else {
+            self.succeeded += 1;
+        }
+    }
+
+    /// Share of the tool calls that succeeded, 1 when none were executed
+    pub fn success_rate(&self) -> f64 {
+        let total = self.succeeded + self.failed;
+        if total == 0 {
+            1.0
+        } else {
+            self.succeeded as f64 / total as f64
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+    use crate::ToolName;
+
+    #[test]
+    fn test_budget() {
+        let fixture = Autonomy {
+            max_minutes: Some(30),
+            max_requests: Some(50),
+            report_every: None,
+        };
+
+        let actual = [
+            fixture.is_exhausted(Duration::from_secs(60), 10),
+            fixture.is_exhausted(Duration::from_secs(30 * 60), 10),
+            fixture.is_exhausted(Duration::from_secs(60), 50),
+            fixture.is_report_due(0),
+            fixture.is_report_due(10),
+            fixture.is_report_due(15),
+        ];
+
+        let expected = [false, true, true, false, true, false];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_decision_required() {
+        let fixture = vec![
+            ToolCallFull::new(ToolName::new("forge_tool_fs_read"))
+                .arguments(json!({"path": "/project/src/lib.rs"})),
+            ToolCallFull::new(ToolName::new("forge_tool_followup"))
+                .arguments(json!({"question": "Keep the old API?"})),
+        ];
+
+        let actual = Autonomy::decision_required(&fixture);
+
+        let expected = Some("Keep the old API?".to_string());
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_progress_report() {
+        let mut tasks = TaskList::new();
+        tasks.append("Rename the module");
+        tasks.append("Update the imports");
+        tasks.get_task_mut(0).unwrap().mark_done();
+        let stats = ToolCallStats { succeeded: 3, failed: 1 };
+
+        let actual = ProgressReport::new(
+            &tasks,
+            &ChangeSummary::default(),
+            stats,
+            Duration::from_secs(90),
+            4,
+        );
+
+        let expected = ProgressReport {
+            done: vec!["Rename the module".to_string()],
+            next: vec!["Update the imports".to_string()],
+            confidence: 0.75,
+            elapsed: Duration::from_secs(90),
+            requests: 4,
+        };
+        assert_eq!(actual, expected);
+    }
+}
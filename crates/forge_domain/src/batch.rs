@@ -0,0 +1,280 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{AgentId, Autonomy};
+
+/// Prompts run by `forge batch`, read from a YAML file such as:
+///
+/// ```yaml
+/// tasks:
+///   - name: rename-config
+///     prompt: Rename `Config` to `Settings` across the crate
+///     budget:
+///       max_minutes: 30
+///   - prompt: Add the missing doc comments in src/api
+///     agent: muse
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Batch {
+    pub tasks: Vec<BatchTask>,
+}
+
+impl Batch {
+    /// Parses a batch, rejecting the tasks whose names map to the same slug
+    /// since they would share a branch
+    pub fn parse(content: &str) -> anyhow::Result<Self> {
+        let batch: Self = serde_yml::from_str(content)?;
+        anyhow::ensure!(!batch.tasks.is_empty(), "The batch has no tasks");
+
+        let mut slugs = HashSet::new();
+        for (index, task) in batch.tasks.iter().enumerate() {
+            let slug = task.slug(index);
+            anyhow::ensure!(
+                slugs.insert(slug.clone()),
+                "Several tasks are named {slug}, give them distinct names"
+            );
+        }
+        Ok(batch)
+    }
+}
+
+/// A prompt of a batch, run autonomously by an agent
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct BatchTask {
+    /// Name of the task in the report, also used for its branch when the
+    /// tasks run in parallel. Defaults to the position of the task.
+    #[serde(default)]
+    pub name: Option<String>,
+    pub prompt: String,
+    /// Agent the prompt is sent to. Defaults to the forge agent.
+    #[serde(default)]
+    pub agent: Option<AgentId>,
+    /// Budget of the task. Defaults to the `autonomy` setting of the
+    /// workflow.
+    #[serde(default)]
+    pub budget: Option<Autonomy>,
+}
+
+impl BatchTask {
+    /// Name of the task made of lowercase words joined by dashes, such as
+    /// `rename-config`, or `task-2` for an unnamed second task
+    pub fn slug(&self, index: usize) -> String {
+        let slug = self
+            .name
+            .iter()
+            .flat_map(|name| name.split(|c: char| !c.is_ascii_alphanumeric()))
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .collect::<Vec<_>>()
+            .join("-");
+
+        if slug.is_empty() {
+            format!("task-{}", index + 1)
+        } else {
+            slug
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchStatus {
+    /// The agent completed the task
+    Succeeded,
+    /// The agent stopped before completing the task, e.g. because it spent
+    /// its budget or needs a decision
+    Interrupted,
+    /// The task couldn't be run or the agent failed
+    Failed,
+}
+
+impl BatchStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BatchStatus::Succeeded => "succeeded",
+            BatchStatus::Interrupted => "interrupted",
+            BatchStatus::Failed => "failed",
+        }
+    }
+}
+
+/// Outcome of a task of a batch
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BatchTaskResult {
+    pub name: String,
+    pub status: BatchStatus,
+    /// Final answer of the agent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub answer: Option<String>,
+    /// Why the task was interrupted or failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Files changed by the agent
+    pub changed_files: Vec<String>,
+    /// Worktree the task ran in, when the tasks ran in parallel
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub worktree: Option<PathBuf>,
+    /// Branch the changes of the task were committed to, when the tasks ran
+    /// in parallel
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    pub duration_ms: u128,
+}
+
+/// Results of the tasks of a batch, in the order of the batch
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct BatchReport {
+    pub tasks: Vec<BatchTaskResult>,
+}
+
+impl BatchReport {
+    /// Whether every task was completed
+    pub fn is_success(&self) -> bool {
+        self.tasks
+            .iter()
+            .all(|task| task.status == BatchStatus::Succeeded)
+    }
+
+    /// Number of tasks with the given status
+    pub fn count(&self, status: BatchStatus) -> usize {
+        self.tasks
+            .iter()
+            .filter(|task| task.status == status)
+            .count()
+    }
+
+    /// Summary of the tasks as a Markdown table
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::from("| Task | Status | Files | Duration | Reason |\n");
+        markdown.push_str("| --- | --- | --- | --- | --- |\n");
+        for task in &self.tasks {
+            let reason = task
+                .reason
+                .as_deref()
+                .unwrap_or_default()
+                .replace('\n', " ")
+                .replace('|', "\\|");
+            markdown.push_str(&format!(
+                "| {} | {} | {} | {}s | {reason} |\n",
+                task.name,
+                task.status.as_str(),
+                task.changed_files.len(),
+                task.duration_ms / 1000
+            ));
+        }
+        markdown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn result(name: &str, status: BatchStatus, reason: Option<&str>) -> BatchTaskResult {
+        BatchTaskResult {
+            name: name.to_string(),
+            status,
+            answer: None,
+            reason: reason.map(str::to_string),
+            changed_files: vec!["src/lib.rs".to_string()],
+            worktree: None,
+            branch: None,
+            duration_ms: 61_000,
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let fixture = concat!(
+            "tasks:\n",
+            "  - name: Rename config\n",
+            "    prompt: Rename Config to Settings\n",
+            "    budget:\n",
+            "      max_minutes: 30\n",
+            "  - prompt: Add the missing doc comments\n",
+            "    agent: muse\n",
+        );
+
+        let actual = Batch::parse(fixture).unwrap();
+
+        let expected = Batch {
+            tasks: vec![
+                BatchTask {
+                    name: Some("Rename config".to_string()),
+                    prompt: "Rename Config to Settings".to_string(),
+                    agent: None,
+                    budget: Some(Autonomy { max_minutes: Some(30), ..Default::default() }),
+                },
+                BatchTask {
+                    name: None,
+                    prompt: "Add the missing doc comments".to_string(),
+                    agent: Some(AgentId::new("muse")),
+                    budget: None,
+                },
+            ],
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_empty_batch() {
+        let actual = Batch::parse("tasks: []");
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn test_parse_duplicate_names() {
+        let fixture = "tasks:\n  - name: Rename config\n    prompt: a\n  - name: rename-config\n    prompt: b";
+
+        let actual = Batch::parse(fixture).unwrap_err().to_string();
+
+        let expected = "Several tasks are named rename-config, give them distinct names";
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_slug() {
+        let named = BatchTask {
+            name: Some("Rename `Config`, again!".to_string()),
+            prompt: "Rename".to_string(),
+            agent: None,
+            budget: None,
+        };
+        let unnamed = BatchTask { name: None, ..named.clone() };
+
+        let actual = [named.slug(0), unnamed.slug(1)];
+
+        let expected = ["rename-config-again".to_string(), "task-2".to_string()];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_report() {
+        let fixture = BatchReport {
+            tasks: vec![
+                result("rename", BatchStatus::Succeeded, None),
+                result(
+                    "docs",
+                    BatchStatus::Interrupted,
+                    Some("Decision required: a|b"),
+                ),
+            ],
+        };
+
+        let actual = fixture.to_markdown();
+
+        let expected = concat!(
+            "| Task | Status | Files | Duration | Reason |\n",
+            "| --- | --- | --- | --- | --- |\n",
+            "| rename | succeeded | 1 | 61s |  |\n",
+            "| docs | interrupted | 1 | 61s | Decision required: a\\|b |\n",
+        );
+        assert_eq!(actual, expected);
+        assert!(!fixture.is_success());
+        assert_eq!(fixture.count(BatchStatus::Interrupted), 1);
+    }
+}
@@ -0,0 +1,681 @@
+use crate::{Calculate, Error, Result};
+
+/// Decimals shown of the values that aren't whole numbers
+const DECIMALS: u32 = 12;
+
+/// Units of the quantities, with their size in the base unit of their
+/// dimension: bytes or seconds
+const UNITS: [(&str, Dimension, i128, i128); 16] = [
+    ("B", Dimension::Bytes, 1, 1),
+    ("KB", Dimension::Bytes, 1_000, 1),
+    ("kB", Dimension::Bytes, 1_000, 1),
+    ("MB", Dimension::Bytes, 1_000_000, 1),
+    ("GB", Dimension::Bytes, 1_000_000_000, 1),
+    ("TB", Dimension::Bytes, 1_000_000_000_000, 1),
+    ("KiB", Dimension::Bytes, 1 << 10, 1),
+    ("MiB", Dimension::Bytes, 1 << 20, 1),
+    ("GiB", Dimension::Bytes, 1 << 30, 1),
+    ("TiB", Dimension::Bytes, 1 << 40, 1),
+    ("ms", Dimension::Time, 1, 1_000),
+    ("s", Dimension::Time, 1, 1),
+    ("min", Dimension::Time, 60, 1),
+    ("h", Dimension::Time, 3_600, 1),
+    ("d", Dimension::Time, 86_400, 1),
+    ("w", Dimension::Time, 604_800, 1),
+];
+
+/// Result of an expression evaluated with the calculator tool
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Calculation {
+    /// The value, rounded to 12 decimals when it has more
+    pub value: String,
+    /// The exact value as a fraction, when the decimal value is rounded
+    pub fraction: Option<String>,
+    /// Unit of the value when it's a size or a duration
+    pub unit: Option<String>,
+}
+
+impl Calculate {
+    /// Evaluates the expression with exact rational arithmetic, so that
+    /// decimals like 0.1 + 0.2 add up exactly
+    pub fn evaluate(&self) -> Result<Calculation> {
+        let (quantity, unit) = Parser::new(&self.expression)?.parse()?;
+        let (value, unit) = match unit {
+            Some((name, dimension, size)) => {
+                if quantity.dimension != Some(dimension) {
+                    return Err(invalid(format!("can't convert {quantity} to {name}")));
+                }
+                (quantity.value.div(size)?, Some(name.to_string()))
+            }
+            None => (
+                quantity.value,
+                quantity.dimension.map(|d| d.base().to_string()),
+            ),
+        };
+        let fraction = (!value.is_decimal(DECIMALS)).then(|| value.to_string());
+        Ok(Calculation { value: value.to_decimal(DECIMALS), fraction, unit })
+    }
+}
+
+fn invalid(reason: impl ToString) -> Error {
+    Error::InvalidExpression(reason.to_string())
+}
+
+/// Exact rational number, reduced and with a positive denominator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Ratio {
+    num: i128,
+    den: i128,
+}
+
+fn gcd(a: i128, b: i128) -> u128 {
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+impl Ratio {
+    fn new(num: i128, den: i128) -> Result<Self> {
+        if den == 0 {
+            return Err(invalid("division by zero"));
+        }
+        // The divisor is positive as the denominator isn't zero
+        let divisor = Self::checked(i128::try_from(gcd(num, den)).ok())? * den.signum();
+        Ok(Self {
+            num: Self::checked(num.checked_div(divisor))?,
+            den: Self::checked(den.checked_div(divisor))?,
+        })
+    }
+
+    fn integer(num: i128) -> Self {
+        Self { num, den: 1 }
+    }
+
+    fn checked(value: Option<i128>) -> Result<i128> {
+        value.ok_or_else(|| invalid("the value is too large"))
+    }
+
+    fn add(self, other: Ratio) -> Result<Self> {
+        let num = Self::checked(
+            self.num
+                .checked_mul(other.den)
+                .zip(other.num.checked_mul(self.den))
+                .and_then(|(a, b)| a.checked_add(b)),
+        )?;
+        Self::new(num, Self::checked(self.den.checked_mul(other.den))?)
+    }
+
+    fn neg(self) -> Result<Self> {
+        Ok(Self { num: Self::checked(self.num.checked_neg())?, den: self.den })
+    }
+
+    fn mul(self, other: Ratio) -> Result<Self> {
+        // Reduced crosswise first, so that the products stay small
+        let a = Self::checked(i128::try_from(gcd(self.num, other.den)).ok())?.max(1);
+        let b = Self::checked(i128::try_from(gcd(other.num, self.den)).ok())?.max(1);
+        let num = Self::checked((self.num / a).checked_mul(other.num / b))?;
+        let den = Self::checked((self.den / b).checked_mul(other.den / a))?;
+        Self::new(num, den)
+    }
+
+    fn div(self, other: Ratio) -> Result<Self> {
+        if other.num == 0 {
+            return Err(invalid("division by zero"));
+        }
+        self.mul(Self::new(other.den, other.num)?)
+    }
+
+    fn floor(self) -> Self {
+        Self::integer(self.num.div_euclid(self.den))
+    }
+
+    fn rem(self, other: Ratio) -> Result<Self> {
+        let quotient = self.div(other)?.floor();
+        self.add(quotient.mul(other)?.neg()?)
+    }
+
+    fn pow(self, exponent: Ratio) -> Result<Self> {
+        if exponent.den != 1 {
+            return Err(invalid("exponents must be whole numbers"));
+        }
+        let power = u32::try_from(exponent.num.unsigned_abs())
+            .map_err(|_| invalid("the exponent is too large"))?;
+        let num = Self::checked(self.num.checked_pow(power))?;
+        let den = Self::checked(self.den.checked_pow(power))?;
+        match exponent.num < 0 {
+            true => Self::new(den, num),
+            false => Self::new(num, den),
+        }
+    }
+
+    /// Whether the value has at most the number of decimals
+    fn is_decimal(self, decimals: u32) -> bool {
+        self.num
+            .checked_mul(10i128.pow(decimals))
+            .is_some_and(|scaled| scaled % self.den == 0)
+    }
+
+    /// The value rounded half away from zero to the number of decimals,
+    /// without trailing zeros
+    fn to_decimal(self, decimals: u32) -> String {
+        let scale = 10i128.pow(decimals);
+        let Some(scaled) = self.num.checked_mul(scale) else {
+            return (self.num as f64 / self.den as f64).to_string();
+        };
+        let mut rounded = scaled / self.den;
+        if (scaled % self.den).unsigned_abs() * 2 >= self.den.unsigned_abs() {
+            rounded += scaled.signum();
+        }
+        let sign = if rounded < 0 { "-" } else { "" };
+        let rounded = rounded.unsigned_abs();
+        let scale = scale.unsigned_abs();
+        let fraction = format!("{:0width$}", rounded % scale, width = decimals as usize);
+        match fraction.trim_end_matches('0') {
+            "" => format!("{sign}{}", rounded / scale),
+            fraction => format!("{sign}{}.{fraction}", rounded / scale),
+        }
+    }
+}
+
+impl std::fmt::Display for Ratio {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.den {
+            1 => write!(f, "{}", self.num),
+            den => write!(f, "{}/{den}", self.num),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dimension {
+    Bytes,
+    Time,
+}
+
+impl Dimension {
+    fn base(self) -> &'static str {
+        match self {
+            Dimension::Bytes => "B",
+            Dimension::Time => "s",
+        }
+    }
+}
+
+/// A number, with the dimension of its unit when it's a size or a duration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Quantity {
+    value: Ratio,
+    dimension: Option<Dimension>,
+}
+
+impl Quantity {
+    fn number(value: Ratio) -> Self {
+        Self { value, dimension: None }
+    }
+
+    fn same_dimension(self, other: Quantity, operation: &str) -> Result<Option<Dimension>> {
+        match self.dimension == other.dimension {
+            true => Ok(self.dimension),
+            false => Err(invalid(format!("can't {operation} {self} and {other}"))),
+        }
+    }
+
+    fn add(self, other: Quantity) -> Result<Self> {
+        let dimension = self.same_dimension(other, "add")?;
+        Ok(Self { value: self.value.add(other.value)?, dimension })
+    }
+
+    fn sub(self, other: Quantity) -> Result<Self> {
+        let dimension = self.same_dimension(other, "subtract")?;
+        Ok(Self { value: self.value.add(other.value.neg()?)?, dimension })
+    }
+
+    fn mul(self, other: Quantity) -> Result<Self> {
+        let dimension = match (self.dimension, other.dimension) {
+            (Some(_), Some(_)) => return Err(invalid(format!("can't multiply {self} by {other}"))),
+            (dimension, None) | (None, dimension) => dimension,
+        };
+        Ok(Self { value: self.value.mul(other.value)?, dimension })
+    }
+
+    fn div(self, other: Quantity) -> Result<Self> {
+        let dimension = match (self.dimension, other.dimension) {
+            (dimension, None) => dimension,
+            // The ratio of two quantities of the same dimension is a number
+            (Some(a), Some(b)) if a == b => None,
+            _ => return Err(invalid(format!("can't divide {self} by {other}"))),
+        };
+        Ok(Self { value: self.value.div(other.value)?, dimension })
+    }
+
+    fn rem(self, other: Quantity) -> Result<Self> {
+        let dimension = self.same_dimension(other, "take the remainder of")?;
+        Ok(Self { value: self.value.rem(other.value)?, dimension })
+    }
+}
+
+impl std::fmt::Display for Quantity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = self.value.to_decimal(DECIMALS);
+        match self.dimension {
+            Some(dimension) => write!(f, "{value} {}", dimension.base()),
+            None => write!(f, "{value}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Number(Ratio),
+    Name(String),
+    Symbol(char),
+}
+
+fn tokenize(expression: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut rest = expression.trim_start();
+    while let Some(c) = rest.chars().next() {
+        let length = match c {
+            '0'..='9' | '.' => {
+                let (value, length) = number(rest)?;
+                tokens.push(Token::Number(value));
+                length
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let length = rest
+                    .find(|c: char| !c.is_alphanumeric() && c != '_')
+                    .unwrap_or(rest.len());
+                tokens.push(Token::Name(rest[..length].to_string()));
+                length
+            }
+            '+' | '-' | '*' | '/' | '%' | '^' | '(' | ')' | ',' => {
+                tokens.push(Token::Symbol(c));
+                1
+            }
+            c => return Err(invalid(format!("unexpected character {c}"))),
+        };
+        rest = rest[length..].trim_start();
+    }
+    Ok(tokens)
+}
+
+/// The number the text starts with and its length. Numbers are written in
+/// decimal, possibly with an exponent like 1.5e3, or in hexadecimal, octal or
+/// binary like 0x1F, and their digits may be separated with _.
+fn number(text: &str) -> Result<(Ratio, usize)> {
+    let radix = match text.get(..2) {
+        Some("0x") => Some(16),
+        Some("0o") => Some(8),
+        Some("0b") => Some(2),
+        _ => None,
+    };
+    if let Some(radix) = radix {
+        let length = text[2..]
+            .find(|c: char| !c.is_digit(radix) && c != '_')
+            .map_or(text.len(), |end| end + 2);
+        let value = i128::from_str_radix(&text[2..length].replace('_', ""), radix)
+            .map_err(|_| invalid(format!("invalid number {}", &text[..length])))?;
+        return Ok((Ratio::integer(value), length));
+    }
+
+    let mut length = text
+        .find(|c: char| !c.is_ascii_digit() && c != '.' && c != '_')
+        .unwrap_or(text.len());
+    let mantissa = text[..length].replace('_', "");
+    let mut exponent = 0;
+    if let Some(rest) = text[length..].strip_prefix(['e', 'E']) {
+        let digits = rest.strip_prefix(['-', '+']).unwrap_or(rest);
+        let end = digits
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(digits.len());
+        // Otherwise the e starts the name following the number
+        if end > 0 {
+            let written = &rest[..rest.len() - digits.len() + end];
+            exponent = written.parse::<i32>().unwrap_or(i32::MAX);
+            length += 1 + written.len();
+        }
+    }
+
+    let bad_number = || invalid(format!("invalid number {}", &text[..length]));
+    let (whole, decimals) = mantissa.split_once('.').unwrap_or((&mantissa, ""));
+    let digits = format!("{whole}{decimals}");
+    if digits.is_empty() || decimals.contains('.') {
+        return Err(bad_number());
+    }
+    let num = digits.parse::<i128>().map_err(|_| bad_number())?;
+    let exponent = exponent.saturating_sub(decimals.len() as i32);
+    let scale = Ratio::integer(10).pow(Ratio::integer(exponent.into()))?;
+    Ok((Ratio::integer(num).mul(scale)?, length))
+}
+
+/// Name of a unit, its dimension and its size in the base unit
+type Unit = (&'static str, Dimension, Ratio);
+
+fn unit(name: &str) -> Option<Unit> {
+    UNITS
+        .iter()
+        .find(|(unit, ..)| *unit == name)
+        .map(|(unit, dimension, num, den)| (*unit, *dimension, Ratio { num: *num, den: *den }))
+}
+
+/// Recursive descent parser evaluating the expression as it reads it
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn new(expression: &str) -> Result<Self> {
+        Ok(Self { tokens: tokenize(expression)?, position: 0 })
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn eat(&mut self, symbol: char) -> bool {
+        let found = self.peek() == Some(&Token::Symbol(symbol));
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, symbol: char) -> Result<()> {
+        match self.eat(symbol) {
+            true => Ok(()),
+            false => Err(invalid(format!("expected {symbol}"))),
+        }
+    }
+
+    /// The value of the expression and the unit it's converted to with
+    /// `in <unit>` or `to <unit>`
+    fn parse(mut self) -> Result<(Quantity, Option<Unit>)> {
+        let quantity = self.expression()?;
+        let target = match self.next() {
+            None => return Ok((quantity, None)),
+            Some(Token::Name(word)) if word == "in" || word == "to" => match self.next() {
+                Some(Token::Name(name)) => {
+                    unit(&name).ok_or_else(|| invalid(format!("unknown unit {name}")))?
+                }
+                _ => return Err(invalid(format!("expected a unit after {word}"))),
+            },
+            Some(token) => return Err(invalid(format!("unexpected {}", describe(&token)))),
+        };
+        match self.next() {
+            None => Ok((quantity, Some(target))),
+            Some(token) => Err(invalid(format!("unexpected {}", describe(&token)))),
+        }
+    }
+
+    fn expression(&mut self) -> Result<Quantity> {
+        let mut value = self.term()?;
+        loop {
+            if self.eat('+') {
+                value = value.add(self.term()?)?;
+            } else if self.eat('-') {
+                value = value.sub(self.term()?)?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn term(&mut self) -> Result<Quantity> {
+        let mut value = self.unary()?;
+        loop {
+            if self.eat('*') {
+                value = value.mul(self.unary()?)?;
+            } else if self.eat('/') {
+                value = value.div(self.unary()?)?;
+            } else if self.eat('%') {
+                value = value.rem(self.unary()?)?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn unary(&mut self) -> Result<Quantity> {
+        if self.eat('-') {
+            let value = self.unary()?;
+            return Ok(Quantity { value: value.value.neg()?, ..value });
+        }
+        if self.eat('+') {
+            return self.unary();
+        }
+        self.power()
+    }
+
+    fn power(&mut self) -> Result<Quantity> {
+        let base = self.quantity()?;
+        if !self.eat('^') {
+            return Ok(base);
+        }
+        // Right associative, 2^3^2 is 2^9, and -2^2 is -4
+        let exponent = self.unary()?;
+        if base.dimension.is_some() || exponent.dimension.is_some() {
+            return Err(invalid("units can't be raised to a power"));
+        }
+        Ok(Quantity::number(base.value.pow(exponent.value)?))
+    }
+
+    /// A value, possibly followed by its unit, e.g. 512 MiB
+    fn quantity(&mut self) -> Result<Quantity> {
+        let value = self.primary()?;
+        let unit = match self.peek() {
+            Some(Token::Name(name)) => unit(name),
+            _ => None,
+        };
+        let Some((name, dimension, size)) = unit else {
+            return Ok(value);
+        };
+        if value.dimension.is_some() {
+            return Err(invalid(format!(
+                "{value} already has a unit, can't add {name}"
+            )));
+        }
+        self.position += 1;
+        Ok(Quantity { value: value.value.mul(size)?, dimension: Some(dimension) })
+    }
+
+    fn primary(&mut self) -> Result<Quantity> {
+        match self.next() {
+            Some(Token::Number(value)) => Ok(Quantity::number(value)),
+            Some(Token::Symbol('(')) => {
+                let value = self.expression()?;
+                self.expect(')')?;
+                Ok(value)
+            }
+            Some(Token::Name(name)) if self.eat('(') => {
+                let mut arguments = vec![self.expression()?];
+                while self.eat(',') {
+                    arguments.push(self.expression()?);
+                }
+                self.expect(')')?;
+                function(&name, arguments)
+            }
+            Some(token) => Err(invalid(format!("unexpected {}", describe(&token)))),
+            None => Err(invalid("unexpected end of the expression")),
+        }
+    }
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Number(value) => value.to_string(),
+        Token::Name(name) => name.clone(),
+        Token::Symbol(symbol) => symbol.to_string(),
+    }
+}
+
+fn function(name: &str, arguments: Vec<Quantity>) -> Result<Quantity> {
+    let single = |arguments: &[Quantity]| match arguments {
+        [value] => Ok(*value),
+        _ => Err(invalid(format!("{name} takes a single argument"))),
+    };
+    let value = match name {
+        "abs" => {
+            let value = single(&arguments)?;
+            match value.value.num < 0 {
+                true => Quantity { value: value.value.neg()?, ..value },
+                false => value,
+            }
+        }
+        "floor" => {
+            let value = single(&arguments)?;
+            Quantity { value: value.value.floor(), ..value }
+        }
+        "ceil" => {
+            let value = single(&arguments)?;
+            Quantity { value: value.value.neg()?.floor().neg()?, ..value }
+        }
+        "round" => {
+            let value = single(&arguments)?;
+            let half = Ratio { num: value.value.num.signum(), den: 2 };
+            let rounded = value.value.add(half)?;
+            // Half away from zero
+            let rounded = match rounded.num < 0 {
+                true => rounded.neg()?.floor().neg()?,
+                false => rounded.floor(),
+            };
+            Quantity { value: rounded, ..value }
+        }
+        "min" | "max" => {
+            let mut values = arguments.into_iter();
+            let first = values
+                .next()
+                .ok_or_else(|| invalid(format!("{name} needs values")))?;
+            values.try_fold(first, |best, value| {
+                best.same_dimension(value, "compare")?;
+                let greater = value.value.add(best.value.neg()?)?.num > 0;
+                Ok(match (name == "max") == greater {
+                    true => value,
+                    false => best,
+                })
+            })?
+        }
+        _ => return Err(invalid(format!("unknown function {name}"))),
+    };
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn evaluate(expression: &str) -> Result<Calculation> {
+        Calculate { expression: expression.to_string(), ..Default::default() }.evaluate()
+    }
+
+    fn calculation(value: &str, fraction: Option<&str>, unit: Option<&str>) -> Calculation {
+        Calculation {
+            value: value.to_string(),
+            fraction: fraction.map(str::to_string),
+            unit: unit.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_exact_arithmetic() {
+        let fixture = [
+            "0.1 + 0.2",
+            "2 + 3 * 4 - 10 / 4",
+            "-(2 ^ 3 ^ 2) % 7",
+            "-2 ^ 2",
+            "1 / 3",
+            "0x1F + 0b101 + 1_000",
+            "1.5e3 + 2e-3",
+            "max(3, round(2.5), ceil(-1.5)) + abs(-4) + floor(-0.5)",
+            "99999999999999999999 * 10",
+        ];
+
+        let actual = fixture
+            .into_iter()
+            .map(|expression| evaluate(expression).unwrap())
+            .collect::<Vec<_>>();
+
+        let expected = vec![
+            calculation("0.3", None, None),
+            calculation("11.5", None, None),
+            calculation("6", None, None),
+            calculation("-4", None, None),
+            calculation("0.333333333333", Some("1/3"), None),
+            calculation("1036", None, None),
+            calculation("1500.002", None, None),
+            calculation("6", None, None),
+            calculation("999999999999999999990", None, None),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_unit_conversions() {
+        let fixture = [
+            "3.5 GiB in MB",
+            "2h + 30min to s",
+            "10MiB / 256KiB",
+            "1500 ms * 4",
+            "(1 GB - 200 MB) / 3 in MB",
+        ];
+
+        let actual = fixture
+            .into_iter()
+            .map(|expression| evaluate(expression).unwrap())
+            .collect::<Vec<_>>();
+
+        let expected = vec![
+            calculation("3758.096384", None, Some("MB")),
+            calculation("9000", None, Some("s")),
+            calculation("40", None, None),
+            calculation("6", None, Some("s")),
+            calculation("266.666666666667", Some("800/3"), Some("MB")),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_invalid_expressions() {
+        let fixture = [
+            "1 / 0",
+            "2 MB + 3 s",
+            "4 MB in h",
+            "2 ^ 0.5",
+            "(1 + 2",
+            "pow(2)",
+            "1 $ 2",
+            "-(-(2 ^ 126) * 2)",
+            "abs(-(2 ^ 126) * 2)",
+            "1 / (-(2 ^ 126) * 2)",
+        ];
+
+        let actual = fixture
+            .into_iter()
+            .map(|expression| evaluate(expression).unwrap_err().to_string())
+            .collect::<Vec<_>>();
+
+        let expected = vec![
+            "Invalid expression: division by zero",
+            "Invalid expression: can't add 2000000 B and 3 s",
+            "Invalid expression: can't convert 4000000 B to h",
+            "Invalid expression: exponents must be whole numbers",
+            "Invalid expression: expected )",
+            "Invalid expression: unknown function pow",
+            "Invalid expression: unexpected character $",
+            "Invalid expression: the value is too large",
+            "Invalid expression: the value is too large",
+            "Invalid expression: the value is too large",
+        ];
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,139 @@
+use serde::{Deserialize, Serialize};
+
+/// Name of the conversation event recording the changes made in a turn.
+pub const CHANGE_SUMMARY_EVENT: &str = "change_summary";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileChangeKind {
+    Added,
+    Modified,
+    Deleted,
+}
+
+/// A file touched during a turn along with its line counts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileChange {
+    pub path: String,
+    pub kind: FileChangeKind,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+/// What the agent actually did during a turn: the files it changed and the
+/// commands it ran.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangeSummary {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<FileChange>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub commands: Vec<String>,
+}
+
+impl ChangeSummary {
+    /// Records a change to a file, folding it into any earlier change of the
+    /// same file in the turn.
+    pub fn record_file(&mut self, change: FileChange) {
+        let Some(index) = self.files.iter().position(|file| file.path == change.path) else {
+            self.files.push(change);
+            return;
+        };
+
+        let existing = &mut self.files[index];
+        let kind = match (existing.kind, change.kind) {
+            // A file created and removed within the same turn left no trace
+            (FileChangeKind::Added, FileChangeKind::Deleted) => {
+                self.files.remove(index);
+                return;
+            }
+            (FileChangeKind::Added, _) => FileChangeKind::Added,
+            (FileChangeKind::Deleted, FileChangeKind::Added) => FileChangeKind::Modified,
+            (_, kind) => kind,
+        };
+        existing.kind = kind;
+        existing.insertions += change.insertions;
+        existing.deletions += change.deletions;
+    }
+
+    pub fn record_command(&mut self, command: impl ToString) {
+        self.commands.push(command.to_string());
+    }
+
+    pub fn merge(&mut self, other: ChangeSummary) {
+        other
+            .files
+            .into_iter()
+            .for_each(|change| self.record_file(change));
+        self.commands.extend(other.commands);
+    }
+
+    /// Whether the turn modified the file system.
+    pub fn has_file_changes(&self) -> bool {
+        !self.files.is_empty()
+    }
+
+    pub fn insertions(&self) -> usize {
+        self.files.iter().map(|file| file.insertions).sum()
+    }
+
+    pub fn deletions(&self) -> usize {
+        self.files.iter().map(|file| file.deletions).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn change(path: &str, kind: FileChangeKind, insertions: usize, deletions: usize) -> FileChange {
+        FileChange { path: path.to_string(), kind, insertions, deletions }
+    }
+
+    #[test]
+    fn test_record_file_folds_changes_to_same_path() {
+        let mut fixture = ChangeSummary::default();
+        fixture.record_file(change("/a.rs", FileChangeKind::Added, 10, 0));
+        fixture.record_file(change("/a.rs", FileChangeKind::Modified, 2, 1));
+        fixture.record_file(change("/b.rs", FileChangeKind::Modified, 1, 1));
+
+        let actual = fixture.files;
+
+        let expected = vec![
+            change("/a.rs", FileChangeKind::Added, 12, 1),
+            change("/b.rs", FileChangeKind::Modified, 1, 1),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_record_file_added_then_deleted() {
+        let mut fixture = ChangeSummary::default();
+        fixture.record_file(change("/tmp.rs", FileChangeKind::Added, 3, 0));
+        fixture.record_file(change("/tmp.rs", FileChangeKind::Deleted, 0, 0));
+
+        let actual = fixture.has_file_changes();
+
+        assert!(!actual);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut fixture = ChangeSummary::default();
+        fixture.record_command("cargo test");
+        let mut other = ChangeSummary::default();
+        other.record_file(change("/a.rs", FileChangeKind::Modified, 4, 2));
+        other.record_command("cargo fmt");
+
+        fixture.merge(other);
+
+        let actual = (fixture.insertions(), fixture.deletions(), fixture.commands);
+        let expected = (
+            4,
+            2,
+            vec!["cargo test".to_string(), "cargo fmt".to_string()],
+        );
+        assert_eq!(actual, expected);
+    }
+}
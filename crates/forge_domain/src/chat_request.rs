@@ -1,17 +1,29 @@
 use derive_setters::Setters;
 use serde::{Deserialize, Serialize};
 
-use crate::{ConversationId, Event};
+use crate::{ConversationId, Event, ToolChoice};
 
 #[derive(Debug, Serialize, Deserialize, Clone, Setters)]
 #[setters(into, strip_option)]
 pub struct ChatRequest {
     pub event: Event,
     pub conversation_id: ConversationId,
+    /// Overrides the tool choice of the agent for this request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<ToolChoice>,
+    /// Answers with a single response of the model, without tools nor agent
+    /// loop, for quick questions
+    #[serde(default)]
+    pub ask: bool,
 }
 
 impl ChatRequest {
     pub fn new(content: Event, conversation_id: ConversationId) -> Self {
-        Self { event: content, conversation_id }
+        Self {
+            event: content,
+            conversation_id,
+            tool_choice: None,
+            ask: false,
+        }
     }
 }
@@ -1,6 +1,8 @@
 use std::time::Duration;
 
-use crate::{ToolCallFull, ToolResult, Usage};
+use crate::{
+    ChangeSummary, ConversationId, ProgressReport, ToolCallFull, ToolName, ToolResult, Usage,
+};
 
 /// Events that are emitted by the agent for external consumption. This includes
 /// events for all internal state changes.
@@ -15,6 +17,12 @@ pub enum ChatResponse {
         content: String,
     },
     ToolCallStart(ToolCallFull),
+    /// Output of a tool call that's still running, e.g. a long build, sent
+    /// as it's produced. The model only gets the result once the call ends.
+    ToolProgress {
+        name: ToolName,
+        output: String,
+    },
     ToolCallEnd(ToolResult),
     Usage(Usage),
     RetryAttempt {
@@ -27,12 +35,50 @@ pub enum ChatResponse {
     Reasoning {
         content: String,
     },
+    /// Files changed and commands run during a turn that modified the file
+    /// system, sent once the turn completes.
+    ChangeSummary(ChangeSummary),
+    /// A response was blocked by the moderation of the workflow, its tool
+    /// calls weren't executed.
+    Blocked {
+        reason: String,
+    },
+    /// Progress of an agent running autonomously, sent periodically and when
+    /// it stops.
+    ProgressReport(ProgressReport),
+}
+
+/// A [`ChatResponse`] tagged with the conversation it belongs to, as
+/// broadcast to event subscribers.
+#[derive(Debug, Clone)]
+pub struct TurnEvent {
+    pub conversation_id: ConversationId,
+    pub response: ChatResponse,
+}
+
+impl TurnEvent {
+    pub fn new(conversation_id: ConversationId, response: ChatResponse) -> Self {
+        Self { conversation_id, response }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub enum InterruptionReason {
-    MaxToolFailurePerTurnLimitReached { limit: u64 },
-    MaxRequestPerTurnLimitReached { limit: u64 },
+    MaxToolFailurePerTurnLimitReached {
+        limit: u64,
+    },
+    MaxRequestPerTurnLimitReached {
+        limit: u64,
+    },
+    /// The autonomous agent spent the time or the requests it was given
+    AutonomyBudgetExhausted {
+        elapsed: Duration,
+        requests: u64,
+    },
+    /// The autonomous agent needs the user to answer a question to continue
+    DecisionRequired {
+        question: String,
+    },
 }
 
 #[derive(Clone)]
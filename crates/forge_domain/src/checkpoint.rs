@@ -0,0 +1,50 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{Context, SnapshotRef};
+
+/// State of a file when a checkpoint was saved
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileState {
+    pub path: String,
+    /// Snapshot holding the content of the file, `None` when the file didn't
+    /// exist
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<SnapshotRef>,
+    /// Content of the file in the checkpoints saved before they referred to
+    /// snapshots
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+}
+
+impl FileState {
+    /// Whether the file existed when the checkpoint was saved
+    pub fn existed(&self) -> bool {
+        self.snapshot.is_some() || self.content.is_some()
+    }
+}
+
+/// A named state of the conversation and of the files it touched, to go back
+/// to after an experiment that didn't work out.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub context: Option<Context>,
+    /// Number of turns in the history of the conversation, the later turns
+    /// happened after the checkpoint
+    pub turns: usize,
+    pub files: Vec<FileState>,
+}
+
+/// Files brought back to their state at a checkpoint
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CheckpointRestore {
+    /// Files written back with their content at the checkpoint
+    pub restored: Vec<String>,
+    /// Files that didn't exist at the checkpoint
+    pub removed: Vec<String>,
+    /// Files changed after the checkpoint without a snapshot to restore them
+    /// from
+    pub unrecoverable: Vec<String>,
+}
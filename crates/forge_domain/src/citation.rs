@@ -0,0 +1,208 @@
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::{ContextMessage, Conversation, Role};
+
+lazy_static::lazy_static! {
+    /// References to lines of a file, e.g. `src/main.rs:10`,
+    /// `src/main.rs:10-20` or `src/main.rs#L10-L20`
+    static ref CITATION: Regex = Regex::new(
+        r"(?P<path>/?(?:[\w.\-]+/)*[\w\-]+(?:\.[\w\-]+)*\.[A-Za-z][A-Za-z0-9]*)(?::(?P<line>\d+)(?:-(?P<end>\d+))?|#L(?P<anchor>\d+)(?:-L?(?P<anchor_end>\d+))?)"
+    )
+    .unwrap();
+}
+
+/// Lines of a file the model refers to in an answer
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Citation {
+    /// Path as written by the model, absolute or relative to the working
+    /// directory
+    pub path: String,
+    /// First cited line, starting at 1
+    pub line: usize,
+    /// Last cited line, when a range is cited
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<usize>,
+}
+
+/// Whether a citation designates lines that exist
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CitationStatus {
+    Valid,
+    /// The file doesn't exist
+    MissingFile,
+    /// The file is shorter than the cited lines
+    MissingLines {
+        lines: usize,
+    },
+}
+
+/// A citation checked against the workspace
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckedCitation {
+    #[serde(flatten)]
+    pub citation: Citation,
+    pub status: CitationStatus,
+}
+
+impl Citation {
+    /// Citations of the text with their position in it. Paths that are part
+    /// of a URL or of a longer word aren't citations.
+    pub fn extract(text: &str) -> Vec<(Range<usize>, Citation)> {
+        CITATION
+            .captures_iter(text)
+            .filter_map(|captures| {
+                let span = captures.get(0)?.range();
+                let preceding = text[..span.start].chars().next_back();
+                if preceding.is_some_and(|c| c.is_alphanumeric() || "/.:_-".contains(c)) {
+                    return None;
+                }
+
+                let number = |name: &str| captures.name(name)?.as_str().parse::<usize>().ok();
+                let line = number("line").or_else(|| number("anchor"))?;
+                let end_line = number("end").or_else(|| number("anchor_end"));
+                if line == 0 || end_line.is_some_and(|end| end < line) {
+                    return None;
+                }
+
+                let path = captures["path"].to_string();
+                Some((span, Citation { path, line, end_line }))
+            })
+            .collect()
+    }
+
+    /// Path of the cited file
+    pub fn resolve(&self, cwd: &Path) -> PathBuf {
+        cwd.join(&self.path)
+    }
+
+    /// Link opening the cited file at the first cited line
+    pub fn url(&self, cwd: &Path) -> String {
+        let path = self.resolve(cwd);
+        let path = path.to_string_lossy().replace(' ', "%20");
+        let path = if path.starts_with('/') {
+            path
+        } else {
+            format!("/{}", path.replace('\\', "/"))
+        };
+        match self.end_line {
+            Some(end_line) => format!("file://{path}#L{}-L{end_line}", self.line),
+            None => format!("file://{path}#L{}", self.line),
+        }
+    }
+
+    /// Checks the citation against the number of lines of the cited file,
+    /// `None` when it doesn't exist
+    pub fn check(&self, lines: Option<usize>) -> CitationStatus {
+        match lines {
+            None => CitationStatus::MissingFile,
+            Some(lines) if self.end_line.unwrap_or(self.line) > lines => {
+                CitationStatus::MissingLines { lines }
+            }
+            Some(_) => CitationStatus::Valid,
+        }
+    }
+}
+
+impl std::fmt::Display for Citation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.end_line {
+            Some(end_line) => write!(f, "{}:{}-{end_line}", self.path, self.line),
+            None => write!(f, "{}:{}", self.path, self.line),
+        }
+    }
+}
+
+impl Conversation {
+    /// Citations of the answers of the conversation, each one once in the
+    /// order they were first made
+    pub fn citations(&self) -> Vec<Citation> {
+        let mut citations = Vec::new();
+        let messages = self
+            .context
+            .iter()
+            .flat_map(|context| context.messages.iter());
+        for message in messages {
+            let ContextMessage::Text(message) = message else {
+                continue;
+            };
+            if message.role != Role::Assistant {
+                continue;
+            }
+            for (_, citation) in Citation::extract(&message.content) {
+                if !citations.contains(&citation) {
+                    citations.push(citation);
+                }
+            }
+        }
+        citations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn citation(path: &str, line: usize, end_line: Option<usize>) -> Citation {
+        Citation { path: path.to_string(), line, end_line }
+    }
+
+    #[test]
+    fn test_extract_citations() {
+        let fixture = "The parser lives in `src/parser.rs:42`, see also src/lib.rs#L3-L9 and \
+                       main.rs:7-12. The server listens on https://example.com/app.rs:80 and \
+                       the ratio is 1.5:1.";
+
+        let actual = Citation::extract(fixture)
+            .into_iter()
+            .map(|(span, citation)| (&fixture[span], citation))
+            .collect::<Vec<_>>();
+
+        let expected = vec![
+            ("src/parser.rs:42", citation("src/parser.rs", 42, None)),
+            ("src/lib.rs#L3-L9", citation("src/lib.rs", 3, Some(9))),
+            ("main.rs:7-12", citation("main.rs", 7, Some(12))),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_check_citation() {
+        let fixture = [
+            (citation("src/lib.rs", 10, None), Some(20)),
+            (citation("src/lib.rs", 10, Some(30)), Some(20)),
+            (citation("src/missing.rs", 1, None), None),
+        ];
+
+        let actual = fixture
+            .iter()
+            .map(|(citation, lines)| citation.check(*lines))
+            .collect::<Vec<_>>();
+
+        let expected = vec![
+            CitationStatus::Valid,
+            CitationStatus::MissingLines { lines: 20 },
+            CitationStatus::MissingFile,
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_citation_url() {
+        let fixture = citation("src/my lib.rs", 3, Some(9));
+
+        let actual = (fixture.url(Path::new("/project")), fixture.to_string());
+
+        let expected = (
+            "file:///project/src/my%20lib.rs#L3-L9".to_string(),
+            "src/my lib.rs:3-9".to_string(),
+        );
+        assert_eq!(actual, expected);
+    }
+}
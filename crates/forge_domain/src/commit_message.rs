@@ -0,0 +1,150 @@
+use std::fmt::Display;
+
+use crate::{extract_tag_content, Error};
+
+/// Tag the model wraps the commit message it writes in
+pub const COMMIT_MESSAGE_TAG: &str = "commit_message";
+
+/// Types of change a conventional commit can have
+const COMMIT_TYPES: [&str; 11] = [
+    "feat", "fix", "docs", "style", "refactor", "perf", "test", "build", "ci", "chore", "revert",
+];
+
+/// A commit message following the conventional commits specification, such
+/// as `fix(cache): ignore the case of the keys`, with the details of the
+/// change as bullets in its body
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommitMessage {
+    /// Type of the change, such as `feat` or `fix`
+    pub kind: String,
+    pub scope: Option<String>,
+    /// Whether the change breaks the compatibility, marked with a `!`
+    pub breaking: bool,
+    pub subject: String,
+    pub bullets: Vec<String>,
+}
+
+impl CommitMessage {
+    /// Reads the commit message from the answer of a model, within the commit
+    /// message tag if there's one
+    pub fn parse(answer: &str) -> Result<Self, Error> {
+        let message = extract_tag_content(answer, COMMIT_MESSAGE_TAG).unwrap_or(answer.trim());
+        let mut lines = message.lines();
+        let header = lines.next().unwrap_or_default().trim();
+        let invalid = |reason: &str| Error::InvalidCommitMessage(format!("{reason}: {header}"));
+
+        let (prefix, subject) = header
+            .split_once(": ")
+            .ok_or_else(|| invalid("The header has no type"))?;
+        let (prefix, breaking) = match prefix.strip_suffix('!') {
+            Some(prefix) => (prefix, true),
+            None => (prefix, false),
+        };
+        let (kind, scope) = match prefix.split_once('(') {
+            Some((kind, scope)) => {
+                let scope = scope
+                    .strip_suffix(')')
+                    .filter(|scope| !scope.is_empty())
+                    .ok_or_else(|| invalid("The scope is malformed"))?;
+                (kind, Some(scope.to_string()))
+            }
+            None => (prefix, None),
+        };
+        if !COMMIT_TYPES.contains(&kind) {
+            return Err(invalid("The type is unknown"));
+        }
+        if subject.trim().is_empty() {
+            return Err(invalid("The subject is empty"));
+        }
+
+        let bullets = lines
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                line.strip_prefix("- ")
+                    .or_else(|| line.strip_prefix("* "))
+                    .unwrap_or(line)
+                    .to_string()
+            })
+            .collect();
+
+        Ok(Self {
+            kind: kind.to_string(),
+            scope,
+            breaking,
+            subject: subject.trim().to_string(),
+            bullets,
+        })
+    }
+}
+
+impl Display for CommitMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.kind)?;
+        if let Some(scope) = &self.scope {
+            write!(f, "({scope})")?;
+        }
+        if self.breaking {
+            write!(f, "!")?;
+        }
+        write!(f, ": {}", self.subject)?;
+        if !self.bullets.is_empty() {
+            writeln!(f)?;
+        }
+        for bullet in &self.bullets {
+            write!(f, "\n- {bullet}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_commit_message() {
+        let fixture = r#"Here is the message:
+<commit_message>
+fix(cache)!: ignore the case of the keys
+
+- Lowercase the keys on insertion and lookup
+* Drop the case-sensitive constructor
+</commit_message>"#;
+
+        let actual = CommitMessage::parse(fixture).unwrap();
+
+        let expected = CommitMessage {
+            kind: "fix".to_string(),
+            scope: Some("cache".to_string()),
+            breaking: true,
+            subject: "ignore the case of the keys".to_string(),
+            bullets: vec![
+                "Lowercase the keys on insertion and lookup".to_string(),
+                "Drop the case-sensitive constructor".to_string(),
+            ],
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_commit_message_display() {
+        let fixture = CommitMessage::parse("feat: add /commit\n\n- Reads the staged diff").unwrap();
+
+        let actual = fixture.to_string();
+
+        let expected = "feat: add /commit\n\n- Reads the staged diff";
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_rejects_header_without_type() {
+        let actual = CommitMessage::parse("Update the cache").map_err(|error| error.to_string());
+
+        let expected =
+            Err("Invalid commit message: The header has no type: Update the cache".to_string());
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,196 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{estimate_token_count, Context, ContextMessage, ContextUsage, ToolName};
+
+/// Number of characters of a message shown in a context diff
+const PREVIEW_CHARS: usize = 80;
+
+/// A message of a context, as shown in a context diff
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageSummary {
+    /// Role of the message, or `tool` and `image` for tool results and images
+    pub role: String,
+    /// First line of the message, truncated
+    pub preview: String,
+    pub tokens: usize,
+}
+
+impl From<&ContextMessage> for MessageSummary {
+    fn from(message: &ContextMessage) -> Self {
+        let (role, content) = match message {
+            ContextMessage::Text(message) => (
+                message.role.to_string().to_lowercase(),
+                message.content.clone(),
+            ),
+            ContextMessage::Tool(result) => ("tool".to_string(), result.name.to_string()),
+            ContextMessage::Image(_) => ("image".to_string(), String::new()),
+        };
+        let line = content.lines().next().unwrap_or_default();
+        let mut preview = line.chars().take(PREVIEW_CHARS).collect::<String>();
+        if preview.len() < content.trim_end().len() {
+            preview.push('…');
+        }
+
+        Self {
+            role,
+            preview,
+            tokens: estimate_token_count(message.to_text().chars().count()),
+        }
+    }
+}
+
+/// What changed in the context between two requests to the provider, to find
+/// what makes the context grow.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContextDiff {
+    /// Messages of the latest request that weren't in the previous one
+    pub added_messages: Vec<MessageSummary>,
+    /// Messages of the previous request that aren't in the latest one, e.g.
+    /// after a compaction
+    pub removed_messages: Vec<MessageSummary>,
+    pub added_tools: Vec<ToolName>,
+    pub removed_tools: Vec<ToolName>,
+    pub before: ContextUsage,
+    pub after: ContextUsage,
+}
+
+impl ContextDiff {
+    /// Compares the messages shared by both contexts at their start and end,
+    /// so that the messages in between are the ones added or removed
+    pub fn new(before: &Context, after: &Context) -> Self {
+        let prefix = before
+            .messages
+            .iter()
+            .zip(after.messages.iter())
+            .take_while(|(before, after)| before == after)
+            .count();
+        let suffix = before.messages[prefix..]
+            .iter()
+            .rev()
+            .zip(after.messages[prefix..].iter().rev())
+            .take_while(|(before, after)| before == after)
+            .count();
+
+        let summarize = |messages: &[ContextMessage]| {
+            messages
+                .iter()
+                .map(MessageSummary::from)
+                .collect::<Vec<_>>()
+        };
+        let tool_names = |context: &Context| {
+            context
+                .tools
+                .iter()
+                .map(|tool| tool.name.clone())
+                .collect::<Vec<_>>()
+        };
+        let before_tools = tool_names(before);
+        let after_tools = tool_names(after);
+
+        Self {
+            added_messages: summarize(&after.messages[prefix..after.messages.len() - suffix]),
+            removed_messages: summarize(&before.messages[prefix..before.messages.len() - suffix]),
+            added_tools: after_tools
+                .iter()
+                .filter(|tool| !before_tools.contains(tool))
+                .cloned()
+                .collect(),
+            removed_tools: before_tools
+                .iter()
+                .filter(|tool| !after_tools.contains(tool))
+                .cloned()
+                .collect(),
+            before: ContextUsage::from(before),
+            after: ContextUsage::from(after),
+        }
+    }
+
+    /// Change in the estimated tokens of the context, negative when it shrank
+    pub fn token_delta(&self) -> i64 {
+        self.after.total_tokens() as i64 - self.before.total_tokens() as i64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{ToolCallId, ToolDefinition, ToolResult};
+
+    #[test]
+    fn test_context_diff() {
+        let before = Context::default()
+            .add_message(ContextMessage::system("You are Forge"))
+            .add_message(ContextMessage::user("Read the config", None))
+            .add_tool(ToolDefinition::new("forge_tool_fs_read"))
+            .add_tool(ToolDefinition::new("forge_tool_fs_create"));
+        let mut after = before
+            .clone()
+            .add_message(ContextMessage::assistant("Reading it\nnow", None, None))
+            .add_message(ContextMessage::tool_result(
+                ToolResult::new(ToolName::new("forge_tool_fs_read"))
+                    .call_id(ToolCallId::new("call_1"))
+                    .success("a".repeat(400)),
+            ));
+        after.tools.remove(1);
+
+        let actual = ContextDiff::new(&before, &after);
+
+        let expected_added = vec![
+            ("assistant".to_string(), "Reading it…".to_string()),
+            ("tool".to_string(), "forge_tool_fs_read".to_string()),
+        ];
+        assert_eq!(
+            actual
+                .added_messages
+                .iter()
+                .map(|message| (message.role.clone(), message.preview.clone()))
+                .collect::<Vec<_>>(),
+            expected_added
+        );
+        assert!(actual.removed_messages.is_empty());
+        assert!(actual.added_tools.is_empty());
+        assert_eq!(
+            actual.removed_tools,
+            vec![ToolName::new("forge_tool_fs_create")]
+        );
+        assert_eq!(
+            actual.token_delta(),
+            actual.after.total_tokens() as i64 - actual.before.total_tokens() as i64
+        );
+    }
+
+    #[test]
+    fn test_context_diff_compaction() {
+        let before = Context::default()
+            .add_message(ContextMessage::system("You are Forge"))
+            .add_message(ContextMessage::user("First", None))
+            .add_message(ContextMessage::assistant("Second", None, None))
+            .add_message(ContextMessage::user("Third", None));
+        let after = Context::default()
+            .add_message(ContextMessage::system("You are Forge"))
+            .add_message(ContextMessage::assistant("Summary", None, None))
+            .add_message(ContextMessage::user("Third", None));
+
+        let actual = ContextDiff::new(&before, &after);
+
+        let expected = (
+            vec!["Summary".to_string()],
+            vec!["First".to_string(), "Second".to_string()],
+        );
+        let previews = |messages: &[MessageSummary]| {
+            messages
+                .iter()
+                .map(|message| message.preview.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            (
+                previews(&actual.added_messages),
+                previews(&actual.removed_messages)
+            ),
+            expected
+        );
+    }
+}
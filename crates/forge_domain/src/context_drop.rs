@@ -0,0 +1,201 @@
+use std::path::{Component, Path, PathBuf};
+
+use forge_template::Element;
+use serde::{Deserialize, Serialize};
+
+use crate::{Context, ContextMessage, MessageSummary, Role, ToolOutput, ToolValue};
+
+/// Name of the element a dropped message is replaced with
+const DROPPED_TAG: &str = "dropped_by_user";
+
+/// Messages of a context the user drops with `/drop`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DropTarget {
+    /// Message at the position in the context, starting at 1
+    Message(usize),
+    /// Attachments and file reads of the path, absolute or relative to the
+    /// working directory
+    Path(String),
+}
+
+impl DropTarget {
+    /// A number is the position of a message, anything else a path
+    pub fn parse(value: &str) -> Self {
+        match value.parse::<usize>() {
+            Ok(position) => DropTarget::Message(position),
+            Err(_) => DropTarget::Path(value.to_string()),
+        }
+    }
+}
+
+/// A message replaced by the annotation of its removal
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DroppedMessage {
+    /// Position of the message in the context, starting at 1
+    pub position: usize,
+    /// The message as it was before it was dropped
+    pub summary: MessageSummary,
+}
+
+impl Context {
+    /// Replaces the messages matching the target with an annotation recording
+    /// that the user removed them. The messages stay in place so that tool
+    /// calls remain paired with their results. The system prompt is never
+    /// dropped. The relative paths are resolved against `cwd`.
+    pub fn drop_messages(&mut self, target: &DropTarget, cwd: &Path) -> Vec<DroppedMessage> {
+        let mut dropped = Vec::new();
+        for (index, message) in self.messages.iter_mut().enumerate() {
+            let position = index + 1;
+            let is_target = match target {
+                DropTarget::Message(target) => *target == position,
+                DropTarget::Path(path) => {
+                    file_content_path(message).is_some_and(|file| is_same_file(file, path, cwd))
+                }
+            };
+            if !is_target || is_system(message) || is_dropped(message) {
+                continue;
+            }
+
+            let summary = MessageSummary::from(&*message);
+            let description = file_content_path(message)
+                .map(ToString::to_string)
+                .unwrap_or_else(|| format!("{} message", summary.role));
+            let annotation = Element::new(DROPPED_TAG)
+                .attr("content", description)
+                .attr("tokens", summary.tokens)
+                .render();
+
+            match message {
+                ContextMessage::Text(text) => text.content = annotation,
+                ContextMessage::Tool(result) => result.output = ToolOutput::text(annotation),
+                image @ ContextMessage::Image(_) => *image = ContextMessage::user(annotation, None),
+            }
+            dropped.push(DroppedMessage { position, summary });
+        }
+        dropped
+    }
+}
+
+fn is_system(message: &ContextMessage) -> bool {
+    matches!(message, ContextMessage::Text(message) if message.role == Role::System)
+}
+
+fn is_dropped(message: &ContextMessage) -> bool {
+    text_content(message).is_some_and(|content| content.starts_with(&format!("<{DROPPED_TAG}")))
+}
+
+fn text_content(message: &ContextMessage) -> Option<&str> {
+    match message {
+        ContextMessage::Text(message) => Some(&message.content),
+        ContextMessage::Tool(result) => result.output.values.iter().find_map(|value| match value {
+            ToolValue::Text(text) => Some(text.as_str()),
+            _ => None,
+        }),
+        ContextMessage::Image(_) => None,
+    }
+}
+
+/// Path of the file an attachment or a read of a file contains
+fn file_content_path(message: &ContextMessage) -> Option<&str> {
+    text_content(message)?
+        .trim_start()
+        .strip_prefix("<file_content")?
+        .trim_start()
+        .strip_prefix("path=\"")?
+        .split('"')
+        .next()
+}
+
+/// Whether the paths designate the same file once resolved against the
+/// working directory
+fn is_same_file(file: &str, path: &str, cwd: &Path) -> bool {
+    normalize(&cwd.join(file)) == normalize(&cwd.join(path))
+}
+
+/// Removes the `.` and `..` components without touching the file system
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{ToolCallId, ToolName, ToolResult};
+
+    fn attachment(path: &str, content: &str) -> ContextMessage {
+        let elm = Element::new("file_content")
+            .attr("path", path)
+            .cdata(content);
+        ContextMessage::user(elm, None)
+    }
+
+    fn fixture() -> Context {
+        Context::default()
+            .add_message(ContextMessage::system("You are Forge"))
+            .add_message(ContextMessage::user("Explain the parser", None))
+            .add_message(attachment("/project/assets/dump.sql", &"a".repeat(400)))
+            .add_message(ContextMessage::tool_result(
+                ToolResult::new(ToolName::new("forge_tool_fs_read"))
+                    .call_id(ToolCallId::new("call_1"))
+                    .success(
+                        Element::new("file_content")
+                            .attr("path", "assets/dump.sql")
+                            .cdata("b".repeat(40))
+                            .render(),
+                    ),
+            ))
+    }
+
+    #[test]
+    fn test_drop_messages_by_path() {
+        let mut context = fixture();
+
+        let cwd = Path::new("/project");
+
+        let other = context.drop_messages(&DropTarget::parse("dump.sql"), cwd);
+        let dropped = context.drop_messages(&DropTarget::parse("./assets/dump.sql"), cwd);
+        let again = context.drop_messages(&DropTarget::parse("assets/dump.sql"), cwd);
+
+        let actual = (
+            other.len(),
+            dropped
+                .iter()
+                .map(|dropped| dropped.position)
+                .collect::<Vec<_>>(),
+            again.len(),
+            file_content_path(&context.messages[2]),
+            is_dropped(&context.messages[3]),
+        );
+        let expected = (0, vec![3, 4], 0, None, true);
+        assert_eq!(actual, expected);
+        assert!(context.token_count() < fixture().token_count());
+    }
+
+    #[test]
+    fn test_drop_messages_by_position() {
+        let mut context = fixture();
+        let cwd = Path::new("/project");
+
+        let actual = (
+            context.drop_messages(&DropTarget::Message(1), cwd).len(),
+            context.drop_messages(&DropTarget::Message(2), cwd).len(),
+            context.drop_messages(&DropTarget::Message(9), cwd).len(),
+        );
+
+        let expected = (0, 1, 0);
+        assert_eq!(actual, expected);
+        assert_eq!(context.messages[0], ContextMessage::system("You are Forge"));
+    }
+}
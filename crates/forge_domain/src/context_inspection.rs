@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{estimate_token_count, Context, ContextMessage, ModelId, Pin, Role};
+
+/// Breakdown of the estimated tokens of a context by where they are spent.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContextUsage {
+    /// Number of tokens used by the system prompt
+    pub system_tokens: usize,
+    /// Number of tokens used by the user, assistant and tool messages
+    pub message_tokens: usize,
+    /// Number of tokens used by the tool schemas
+    pub tool_tokens: usize,
+    /// Number of messages in the context, including the system prompt
+    pub messages: usize,
+    /// Number of tools exposed to the model
+    pub tools: usize,
+}
+
+impl ContextUsage {
+    pub fn total_tokens(&self) -> usize {
+        self.system_tokens + self.message_tokens + self.tool_tokens
+    }
+}
+
+impl From<&Context> for ContextUsage {
+    fn from(context: &Context) -> Self {
+        let system_chars = context
+            .messages
+            .iter()
+            .filter_map(|message| match message {
+                ContextMessage::Text(message) if message.role == Role::System => {
+                    Some(message.content.chars().count())
+                }
+                _ => None,
+            })
+            .sum();
+        let tool_chars = context
+            .tools
+            .iter()
+            .map(|tool| serde_json::to_string(tool).map_or(0, |tool| tool.chars().count()))
+            .sum();
+
+        Self {
+            system_tokens: estimate_token_count(system_chars),
+            message_tokens: context.token_count(),
+            tool_tokens: estimate_token_count(tool_chars),
+            messages: context.messages.len(),
+            tools: context.tools.len(),
+        }
+    }
+}
+
+/// The exact request that would be sent to the provider for a conversation,
+/// along with a summary of its size.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContextInspection {
+    /// Model the request is addressed to
+    pub model: ModelId,
+    /// Token breakdown of the context the request was built from
+    pub usage: ContextUsage,
+    /// Request body in the provider's wire format
+    pub payload: Value,
+    /// Context items the user pinned, which compaction never removes
+    #[serde(default)]
+    pub pins: Vec<Pin>,
+}
+
+impl ContextInspection {
+    pub fn new(model: ModelId, context: &Context, payload: Value) -> Self {
+        Self {
+            model,
+            usage: ContextUsage::from(context),
+            payload,
+            pins: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+    use crate::ToolDefinition;
+
+    #[test]
+    fn test_context_usage() {
+        let fixture = Context::default()
+            .add_message(ContextMessage::system("a".repeat(40)))
+            .add_message(ContextMessage::user("b".repeat(20), None))
+            .add_tool(ToolDefinition::new("forge_tool_fs_read"));
+
+        let actual = ContextUsage::from(&fixture);
+
+        let tool_chars = serde_json::to_string(&fixture.tools[0]).unwrap().len();
+        let expected = ContextUsage {
+            system_tokens: 10,
+            message_tokens: 5,
+            tool_tokens: tool_chars / 4,
+            messages: 2,
+            tools: 1,
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_context_inspection_total_tokens() {
+        let fixture = ContextInspection::new(
+            ModelId::new("anthropic/claude-3.5-sonnet"),
+            &Context::default().add_message(ContextMessage::user("b".repeat(20), None)),
+            json!({"messages": []}),
+        );
+
+        let actual = fixture.usage.total_tokens();
+
+        let expected = 5;
+        assert_eq!(actual, expected);
+    }
+}
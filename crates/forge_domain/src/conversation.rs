@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 use derive_more::derive::Display;
 use derive_setters::Setters;
@@ -8,7 +8,12 @@ use serde_json::Value;
 use uuid::Uuid;
 
 use crate::task::TaskList;
-use crate::{Agent, AgentId, Compact, Context, Error, Event, ModelId, Result, ToolName, Workflow};
+use crate::{
+    is_mcp_tool_of, Agent, AgentId, Autonomy, CheckedCitation, Checkpoint, Compact, Context,
+    ContextDiff, Error, Event, Fingerprint, ModelId, Moderation, Pin, Reminder, Result,
+    ScaffoldTemplate, ShellRetry, TestAfterEdit, Timeline, TimelineSpan, ToolGateRule, ToolName,
+    Tools, TurnRecord, Workflow,
+};
 
 #[derive(Debug, Default, Display, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 #[serde(transparent)]
@@ -43,6 +48,74 @@ pub struct Conversation {
     pub tasks: TaskList,
     pub max_tool_failure_per_turn: Option<usize>,
     pub max_requests_per_turn: Option<usize>,
+    /// Whether files must be read before the agent may modify them
+    #[serde(default)]
+    pub read_before_write: bool,
+    /// Files whose content the agent has seen in this conversation
+    #[serde(default)]
+    pub read_files: BTreeSet<String>,
+    /// Turns that changed the workspace, oldest first
+    #[serde(default)]
+    pub turns: Vec<TurnRecord>,
+    /// Rules deciding in which phase of the conversation tools are offered
+    #[serde(default)]
+    pub tool_gates: Vec<ToolGateRule>,
+    /// Environment variables set for the shell commands of this conversation
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Retries of the shell commands that fail transiently
+    #[serde(default)]
+    pub shell_retry: ShellRetry,
+    /// Time spent generating and running tools in each turn, oldest first
+    #[serde(default)]
+    pub timeline: Vec<TimelineSpan>,
+    /// Checks of the responses before their tool calls are executed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub moderation: Option<Moderation>,
+    /// Budget of the agents when they run without interactive approval. The
+    /// agents ask for approval when not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub autonomy: Option<Autonomy>,
+    /// Seed of a reproducible run. It's sent to the providers supporting it,
+    /// the temperature is 0 and the prompts don't mention the time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u32>,
+    /// Environment the seeded run was started in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<Fingerprint>,
+    /// Contexts of the last two requests sent to the provider, oldest first,
+    /// to show how the context changes between requests
+    #[serde(skip)]
+    pub requests: Vec<Context>,
+    /// States saved with `/checkpoint save`, oldest first
+    #[serde(default)]
+    pub checkpoints: Vec<Checkpoint>,
+    /// Project templates the scaffold tool creates
+    #[serde(default)]
+    pub scaffolds: Vec<ScaffoldTemplate>,
+    /// Tests run once a turn modified some files
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub test_after_edit: Option<TestAfterEdit>,
+    /// Context items sent with every request, in the order they were pinned
+    #[serde(default)]
+    pub pins: Vec<Pin>,
+    /// Language the agents answer in, set with `/lang`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_language: Option<String>,
+    /// MCP servers whose tools aren't offered in this conversation
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub disabled_mcp_servers: BTreeSet<String>,
+    /// Whether the claims of the final answers are spot-checked against the
+    /// workspace
+    #[serde(default)]
+    pub verify_answers: bool,
+    /// Reminders scheduled by the agent and not due yet
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reminders: Vec<Reminder>,
+    /// Whether the conversation is only kept in memory and never stored, e.g.
+    /// a fork distilling the learnings of another one
+    #[serde(skip)]
+    pub ephemeral: bool,
 }
 
 impl Conversation {
@@ -59,6 +132,94 @@ impl Conversation {
             .ok_or(Error::NoModelDefined(agent.id.clone()))
     }
 
+    /// Records the context of a request sent to the provider, keeping only the
+    /// last two
+    pub fn record_request(&mut self, context: &Context) {
+        self.requests.push(context.clone());
+        if self.requests.len() > 2 {
+            self.requests.remove(0);
+        }
+    }
+
+    /// What changed in the context between the last two requests sent to the
+    /// provider. Returns `None` until two requests were sent.
+    pub fn context_diff(&self) -> Option<ContextDiff> {
+        match self.requests.as_slice() {
+            [before, after] => Some(ContextDiff::new(before, after)),
+            _ => None,
+        }
+    }
+
+    /// Files the agents read or changed in this conversation
+    pub fn touched_files(&self) -> BTreeSet<String> {
+        let changed = self
+            .turns
+            .iter()
+            .flat_map(|turn| turn.changes.files.iter().map(|file| file.path.clone()));
+
+        self.read_files.iter().cloned().chain(changed).collect()
+    }
+
+    /// Saves a checkpoint, replacing the one with the same name
+    pub fn save_checkpoint(&mut self, checkpoint: Checkpoint) {
+        self.checkpoints
+            .retain(|saved| saved.name != checkpoint.name);
+        self.checkpoints.push(checkpoint);
+    }
+
+    pub fn checkpoint(&self, name: &str) -> Option<&Checkpoint> {
+        self.checkpoints
+            .iter()
+            .find(|checkpoint| checkpoint.name == name)
+    }
+
+    /// Pins a context item. Returns `false` if it was already pinned.
+    pub fn pin(&mut self, pin: Pin) -> bool {
+        if self.pins.contains(&pin) {
+            return false;
+        }
+        self.pins.push(pin);
+        true
+    }
+
+    /// Removes the pin at the given position, starting at 1
+    pub fn unpin(&mut self, position: usize) -> Option<Pin> {
+        let index = position
+            .checked_sub(1)
+            .filter(|index| *index < self.pins.len())?;
+        Some(self.pins.remove(index))
+    }
+
+    /// Offers or stops offering the tools of an MCP server in this
+    /// conversation. Returns `false` if the server already was in that state.
+    pub fn set_mcp_server_enabled(&mut self, server: &str, enabled: bool) -> bool {
+        if enabled {
+            self.disabled_mcp_servers.remove(server)
+        } else {
+            self.disabled_mcp_servers.insert(server.to_string())
+        }
+    }
+
+    /// Whether the tool is offered, i.e. it's not a tool of a disabled MCP
+    /// server
+    pub fn is_tool_enabled(&self, tool: &ToolName) -> bool {
+        !self
+            .disabled_mcp_servers
+            .iter()
+            .any(|server| is_mcp_tool_of(tool, server))
+    }
+
+    /// The last prompt the user sent in this conversation
+    pub fn last_prompt(&self) -> Option<String> {
+        self.events
+            .iter()
+            .rev()
+            .filter(|event| {
+                event.name.ends_with("user_task_init") || event.name.ends_with("user_task_update")
+            })
+            .find_map(|event| event.value.as_ref()?.as_str().map(ToString::to_string))
+    }
+
     /// Sets the model for all agents in the conversation
     pub fn set_model(&mut self, model: &ModelId) -> Result<()> {
         for agent in self.agents.iter_mut() {
@@ -71,6 +232,32 @@ impl Conversation {
         Ok(())
     }
 
+    /// Copy of the conversation answering with the given model, whose agents
+    /// can only use the tools that don't change the workspace. Used to compare
+    /// the answers of models to the same prompt.
+    pub fn fork(&self, model: &ModelId) -> Result<Self> {
+        let mut fork = self.clone();
+        fork.id = ConversationId::generate();
+        fork.set_model(model)?;
+        for agent in fork.agents.iter_mut() {
+            if let Some(tools) = agent.tools.as_mut() {
+                tools.retain(Tools::is_read_only);
+            }
+        }
+
+        Ok(fork)
+    }
+
+    /// Continues the conversation from the answer of one of its forks
+    pub fn adopt(&mut self, fork: &Conversation) -> Result<()> {
+        self.set_model(&fork.main_model()?)?;
+        self.context = fork.context.clone();
+        self.read_files.extend(fork.read_files.iter().cloned());
+        self.timeline.extend(fork.timeline.iter().cloned());
+
+        Ok(())
+    }
+
     pub fn new(id: ConversationId, workflow: Workflow, additional_tools: Vec<ToolName>) -> Self {
         // Merge the workflow with the default workflow
         let mut base_workflow = Workflow::default();
@@ -139,6 +326,10 @@ impl Conversation {
                 }
             }
 
+            if agent.permissions.is_none() {
+                agent.permissions = workflow.permissions.clone();
+            }
+
             // Subscribe the main agent to all commands
             if agent.id == AgentId::default() {
                 let commands = workflow
@@ -180,6 +371,27 @@ impl Conversation {
             tasks: TaskList::new(),
             max_tool_failure_per_turn: workflow.max_tool_failure_per_turn,
             max_requests_per_turn: workflow.max_requests_per_turn,
+            read_before_write: workflow.read_before_write.unwrap_or_default(),
+            read_files: Default::default(),
+            turns: Default::default(),
+            tool_gates: workflow.tool_gates.clone().unwrap_or_default(),
+            env: workflow.env.clone().unwrap_or_default(),
+            shell_retry: workflow.shell_retry.clone().unwrap_or_default(),
+            timeline: Vec::new(),
+            moderation: workflow.moderation.clone(),
+            autonomy: None,
+            seed: None,
+            fingerprint: None,
+            requests: Vec::new(),
+            checkpoints: Vec::new(),
+            scaffolds: workflow.scaffolds.clone().unwrap_or_default(),
+            test_after_edit: workflow.test_after_edit.clone(),
+            pins: Vec::new(),
+            response_language: workflow.response_language.clone(),
+            disabled_mcp_servers: BTreeSet::new(),
+            verify_answers: workflow.verify_answers.unwrap_or_default(),
+            reminders: Vec::new(),
+            ephemeral: false,
         }
     }
 
@@ -238,15 +450,22 @@ impl Conversation {
     /// Generates an HTML representation of the conversation
     ///
     /// This method uses Handlebars to render the conversation as HTML
-    /// from the template file, including all agents, events, and variables.
+    /// from the template file, including all agents, events, and variables,
+    /// along with the citations of the answers as checked against the
+    /// workspace.
     ///
     /// # Errors
     /// - If the template file cannot be found or read
     /// - If the Handlebars template registration fails
     /// - If the template rendering fails
-    pub fn to_html(&self) -> String {
+    pub fn to_html(&self, citations: &[CheckedCitation]) -> String {
         // Instead of using Handlebars, we now use our Element DSL
-        crate::conversation_html::render_conversation_html(self)
+        crate::conversation_html::render_conversation_html(self, citations)
+    }
+
+    /// Timeline of the turns of the conversation, with the idle waits
+    pub fn timeline(&self) -> Timeline {
+        Timeline::new(self.id, &self.timeline)
     }
 
     /// Add an event to the conversation
@@ -283,7 +502,8 @@ mod tests {
     use serde_json::json;
 
     use crate::{
-        Agent, AgentId, Command, Compact, Error, MaxTokens, ModelId, Temperature, Workflow,
+        Agent, AgentId, Checkpoint, Command, Compact, Context, ContextDiff, ContextMessage, Error,
+        Event, MaxTokens, ModelId, PathPermissions, Pin, Temperature, ToolName, Workflow,
     };
 
     #[test]
@@ -323,6 +543,143 @@ mod tests {
         assert_eq!(conversation.variables, variables);
     }
 
+    #[test]
+    fn test_context_diff_keeps_last_two_requests() {
+        // Arrange
+        let id = super::ConversationId::generate();
+        let mut conversation = super::Conversation::new_inner(id, Workflow::new(), vec![]);
+        let first = Context::default().add_message(ContextMessage::user("First", None));
+        let second = first
+            .clone()
+            .add_message(ContextMessage::assistant("Second", None, None));
+        let third = second
+            .clone()
+            .add_message(ContextMessage::user("Third", None));
+
+        // Act
+        conversation.record_request(&first);
+        let single = conversation.context_diff();
+        conversation.record_request(&second);
+        conversation.record_request(&third);
+
+        // Assert
+        assert_eq!(single, None);
+        assert_eq!(conversation.requests, vec![second.clone(), third.clone()]);
+        assert_eq!(
+            conversation.context_diff(),
+            Some(ContextDiff::new(&second, &third))
+        );
+    }
+
+    #[test]
+    fn test_save_checkpoint_replaces_same_name() {
+        // Arrange
+        let id = super::ConversationId::generate();
+        let mut conversation = super::Conversation::new_inner(id, Workflow::new(), vec![]);
+        let checkpoint = |name: &str, turns: usize| Checkpoint {
+            name: name.to_string(),
+            created_at: chrono::Utc::now(),
+            context: None,
+            turns,
+            files: vec![],
+        };
+
+        // Act
+        conversation.save_checkpoint(checkpoint("before-refactor", 1));
+        conversation.save_checkpoint(checkpoint("green", 2));
+        conversation.save_checkpoint(checkpoint("before-refactor", 3));
+
+        // Assert
+        let actual = conversation
+            .checkpoints
+            .iter()
+            .map(|checkpoint| (checkpoint.name.as_str(), checkpoint.turns))
+            .collect::<Vec<_>>();
+        let expected = vec![("green", 2), ("before-refactor", 3)];
+        assert_eq!(actual, expected);
+        assert_eq!(conversation.checkpoint("green").map(|c| c.turns), Some(2));
+        assert_eq!(conversation.checkpoint("missing"), None);
+    }
+
+    #[test]
+    fn test_pin_and_unpin() {
+        // Arrange
+        let id = super::ConversationId::generate();
+        let mut conversation = super::Conversation::new_inner(id, Workflow::new(), vec![]);
+        let requirement = Pin::Text { text: "Keep the API stable".to_string() };
+        let file = Pin::File { path: "/project/src/api.rs".to_string() };
+
+        // Act
+        let added = (
+            conversation.pin(requirement.clone()),
+            conversation.pin(file.clone()),
+            conversation.pin(requirement.clone()),
+        );
+        let removed = (
+            conversation.unpin(0),
+            conversation.unpin(3),
+            conversation.unpin(1),
+        );
+
+        // Assert
+        assert_eq!(added, (true, true, false));
+        assert_eq!(removed, (None, None, Some(requirement)));
+        assert_eq!(conversation.pins, vec![file]);
+    }
+
+    #[test]
+    fn test_set_mcp_server_enabled() {
+        // Arrange
+        let id = super::ConversationId::generate();
+        let mut conversation = super::Conversation::new_inner(id, Workflow::new(), vec![]);
+
+        // Act
+        let changed = (
+            conversation.set_mcp_server_enabled("github", false),
+            conversation.set_mcp_server_enabled("github", false),
+            conversation.set_mcp_server_enabled("slack", true),
+        );
+        let enabled = (
+            conversation.is_tool_enabled(&ToolName::new("mcp_github_tool_create_issue")),
+            conversation.is_tool_enabled(&ToolName::new("mcp_slack_tool_post")),
+            conversation.is_tool_enabled(&ToolName::new("forge_tool_fs_read")),
+        );
+
+        // Assert
+        assert_eq!(changed, (true, false, false));
+        assert_eq!(enabled, (false, true, true));
+    }
+
+    #[test]
+    fn test_last_prompt() {
+        // Arrange
+        let id = super::ConversationId::generate();
+        let mut conversation = super::Conversation::new_inner(id, Workflow::new(), vec![]);
+        conversation.insert_event(Event::new("forge/user_task_init", Some("Add a parser")));
+        conversation.insert_event(Event::new("forge/user_task_update", Some("Keep it lazy")));
+        conversation.insert_event(Event::new("forge/attempt_completion", None::<String>));
+
+        // Act
+        let actual = conversation.last_prompt();
+
+        // Assert
+        assert_eq!(actual, Some("Keep it lazy".to_string()));
+    }
+
+    #[test]
+    fn test_conversation_new_with_read_before_write() {
+        // Arrange
+        let id = super::ConversationId::generate();
+        let workflow = Workflow::new().read_before_write(true);
+
+        // Act
+        let conversation = super::Conversation::new_inner(id, workflow, vec![]);
+
+        // Assert
+        assert!(conversation.read_before_write);
+        assert!(conversation.read_files.is_empty());
+    }
+
     #[test]
     fn test_conversation_new_applies_workflow_settings_to_agents() {
         // Arrange
@@ -509,6 +866,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_conversation_new_applies_workflow_permissions_to_agents() {
+        // Arrange
+        let id = super::ConversationId::generate();
+        let docs = PathPermissions { read: None, write: Some(vec!["docs/**".to_string()]) };
+        let src = PathPermissions { read: None, write: Some(vec!["src/**".to_string()]) };
+        let agent1 = Agent::new("agent1");
+        let agent2 = Agent::new("agent2").permissions(docs.clone());
+
+        let workflow = Workflow::new()
+            .agents(vec![agent1, agent2])
+            .permissions(src.clone());
+
+        // Act
+        let conversation = super::Conversation::new_inner(id, workflow, vec![]);
+
+        // Assert
+        let actual = conversation
+            .agents
+            .into_iter()
+            .map(|agent| agent.permissions)
+            .collect::<Vec<_>>();
+        let expected = vec![Some(src), Some(docs)];
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn test_conversation_new_merges_workflow_compact_with_agent_compact() {
         // Arrange
@@ -1089,6 +1472,45 @@ mod tests {
         assert_eq!(actual[0].subscribe, original_agent.subscribe);
     }
 
+    #[test]
+    fn test_fork_is_read_only() {
+        let workflow = Workflow::new().agents(vec![Agent::new(AgentId::default()).tools(vec![
+            ToolName::new("forge_tool_fs_read"),
+            ToolName::new("forge_tool_fs_patch"),
+            ToolName::new("forge_tool_process_shell"),
+            ToolName::new("forge_tool_attempt_completion"),
+        ])]);
+        let conversation =
+            super::Conversation::new_inner(super::ConversationId::generate(), workflow, vec![]);
+
+        let actual = conversation.fork(&ModelId::new("gpt-4.1")).unwrap();
+
+        let agent = actual.get_agent(&AgentId::default()).unwrap();
+        let expected = vec![
+            ToolName::new("forge_tool_fs_read"),
+            ToolName::new("forge_tool_attempt_completion"),
+        ];
+        assert_ne!(actual.id, conversation.id);
+        assert_eq!(agent.tools, Some(expected));
+        assert_eq!(actual.main_model().unwrap(), ModelId::new("gpt-4.1"));
+    }
+
+    #[test]
+    fn test_adopt_fork() {
+        let workflow = Workflow::new().agents(vec![
+            Agent::new(AgentId::default()).model(ModelId::new("sonnet-4"))
+        ]);
+        let mut fixture =
+            super::Conversation::new_inner(super::ConversationId::generate(), workflow, vec![]);
+        let mut fork = fixture.fork(&ModelId::new("gpt-4.1")).unwrap();
+        fork.context = Some(Context::default().add_message(ContextMessage::user("Hi", None)));
+
+        fixture.adopt(&fork).unwrap();
+
+        assert_eq!(fixture.context, fork.context);
+        assert_eq!(fixture.main_model().unwrap(), ModelId::new("gpt-4.1"));
+    }
+
     #[test]
     fn test_set_model() {
         let workflow = Workflow::new().agents(vec![
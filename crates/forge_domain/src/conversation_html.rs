@@ -3,8 +3,12 @@ use serde_json::to_string_pretty;
 
 use crate::context::ContextMessage;
 use crate::conversation::Conversation;
+use crate::{CheckedCitation, CitationStatus};
 
-pub fn render_conversation_html(conversation: &Conversation) -> String {
+pub fn render_conversation_html(
+    conversation: &Conversation,
+    citations: &[CheckedCitation],
+) -> String {
     let html = Element::new("html")
         .attr("lang", "en")
         .append(
@@ -34,6 +38,8 @@ pub fn render_conversation_html(conversation: &Conversation) -> String {
                 .append(create_variables_section(conversation))
                 // Agent States Section
                 .append(create_conversation_context_section(conversation))
+                // Citations Section
+                .append(create_citations_section(citations))
                 // Agents Section
                 .append(create_agents_section(conversation))
                 // All Subscriptions Section
@@ -189,6 +195,42 @@ fn create_all_subscriptions_section(conversation: &Conversation) -> Element {
     section.append(table)
 }
 
+fn create_citations_section(citations: &[CheckedCitation]) -> Element {
+    let section = Element::new("div.section").append(Element::new("h2").text("Citations"));
+
+    if citations.is_empty() {
+        return section.append(Element::new("p").text("No citations found."));
+    }
+
+    let table = Element::new("table")
+        .append(
+            Element::new("tr")
+                .append(Element::new("th").text("Citation"))
+                .append(Element::new("th").text("Status")),
+        )
+        .append(citations.iter().map(|checked| {
+            let citation = &checked.citation;
+            let (status, description) = match checked.status {
+                CitationStatus::Valid => ("valid", "Valid".to_string()),
+                CitationStatus::MissingFile => ("missing_file", "File not found".to_string()),
+                CitationStatus::MissingLines { lines } => (
+                    "missing_lines",
+                    format!("Beyond the end of the file ({lines} lines)"),
+                ),
+            };
+
+            Element::new("tr")
+                .attr("data-path", &citation.path)
+                .attr("data-line", citation.line)
+                .attr_if_some("data-end-line", citation.end_line)
+                .attr("data-status", status)
+                .append(Element::new("td").append(Element::new("code").text(citation)))
+                .append(Element::new("td").text(description))
+        }));
+
+    section.append(table)
+}
+
 fn create_events_section(conversation: &Conversation) -> Element {
     let section = Element::new("div.section").append(Element::new("h2").text("Events"));
 
@@ -380,6 +422,7 @@ fn create_conversation_context_section(conversation: &Conversation) -> Element {
 mod tests {
     use super::*;
     use crate::conversation::Conversation;
+    use crate::Citation;
 
     #[test]
     fn test_render_empty_conversation() {
@@ -388,7 +431,7 @@ mod tests {
         let workflow = crate::Workflow::new();
 
         let fixture = Conversation::new(id, workflow, Default::default());
-        let actual = render_conversation_html(&fixture);
+        let actual = render_conversation_html(&fixture, &[]);
 
         // We're verifying that the function runs without errors
         // and returns a non-empty string for an empty conversation
@@ -400,5 +443,30 @@ mod tests {
         assert!(actual.contains("Agents"));
         assert!(actual.contains("Events"));
         assert!(actual.contains("Conversation Context"));
+        assert!(actual.contains("No citations found."));
+    }
+
+    #[test]
+    fn test_render_citations() {
+        let id = crate::conversation::ConversationId::generate();
+        let fixture = Conversation::new(id, crate::Workflow::new(), Default::default());
+        let citations = vec![
+            CheckedCitation {
+                citation: Citation { path: "src/lib.rs".to_string(), line: 3, end_line: Some(9) },
+                status: CitationStatus::Valid,
+            },
+            CheckedCitation {
+                citation: Citation { path: "src/gone.rs".to_string(), line: 1, end_line: None },
+                status: CitationStatus::MissingFile,
+            },
+        ];
+
+        let actual = render_conversation_html(&fixture, &citations);
+
+        assert!(actual.contains(r#"data-end-line="9""#));
+        assert!(actual.contains(r#"data-status="valid""#));
+        assert!(actual.contains("<code>src/lib.rs:3-9</code>"));
+        assert!(actual.contains(r#"data-status="missing_file""#));
+        assert!(actual.contains("File not found"));
     }
 }
@@ -4,7 +4,7 @@ use derive_setters::Setters;
 use serde::{Deserialize, Serialize};
 use url::Url;
 
-use crate::{HttpConfig, RetryConfig};
+use crate::{ConversationId, HttpConfig, ResourceLimits, RetryConfig, Storage, UsageQuota};
 
 const VERSION: &str = match option_env!("APP_VERSION") {
     Some(val) => val,
@@ -45,6 +45,31 @@ pub struct Environment {
     pub http: HttpConfig,
     /// Maximum file size in bytes for operations
     pub max_file_size: u64,
+    /// Maximum size in bytes of the content written by FSWrite and added by
+    /// FSPatch, unless the tool call explicitly allows large content
+    pub max_write_size: u64,
+    /// Whether provider responses are cached and replayed for identical
+    /// requests
+    pub response_cache: bool,
+    /// Daily limits on the usage of the providers
+    pub quota: UsageQuota,
+    /// Where the conversations and the snapshots are kept
+    pub storage: Storage,
+    /// Directories outside of the workspace the tools may access, e.g. the
+    /// sources of the dependencies. Set with `FORGE_EXTERNAL_PATHS`, separated
+    /// like the PATH.
+    #[serde(default)]
+    pub external_paths: Vec<PathBuf>,
+    /// Maximum number of tools of each resource category running at once. Set
+    /// with `FORGE_TOOL_LIMIT_CPU`, `FORGE_TOOL_LIMIT_IO`,
+    /// `FORGE_TOOL_LIMIT_NETWORK` and `FORGE_TOOL_LIMIT_PROBE`.
+    #[serde(default)]
+    pub resource_limits: ResourceLimits,
+    /// Window in milliseconds within which the streamed text deltas are
+    /// batched into a single event, each delta is emitted as it arrives when
+    /// unset. Set with `FORGE_TEXT_COALESCE_MS`.
+    #[serde(default)]
+    pub text_coalesce_ms: Option<u64>,
 }
 
 impl Environment {
@@ -56,12 +81,32 @@ impl Environment {
         self.base_path.join("logs")
     }
 
+    /// Prompts and commands entered in the workspace
     pub fn history_path(&self) -> PathBuf {
+        self.cwd.join(".forge").join("history")
+    }
+    /// History shared by all workspaces before it was kept per workspace
+    pub fn legacy_history_path(&self) -> PathBuf {
         self.base_path.join(".forge_history")
     }
+    /// Long texts pasted in the prompts of the workspace
+    pub fn pastes_path(&self) -> PathBuf {
+        self.cwd.join(".forge").join("pastes")
+    }
     pub fn snapshot_path(&self) -> PathBuf {
         self.base_path.join("snapshots")
     }
+    pub fn response_cache_path(&self) -> PathBuf {
+        self.base_path.join("cache").join("responses")
+    }
+    /// Statistics of the tool calls, shared by all workspaces
+    pub fn tool_stats_path(&self) -> PathBuf {
+        self.base_path.join("tool_stats.json")
+    }
+    /// Ledger of the usage of the providers, shared by all workspaces
+    pub fn usage_path(&self) -> PathBuf {
+        self.base_path.join("usage.json")
+    }
     pub fn mcp_user_config(&self) -> PathBuf {
         self.base_path.join(".mcp.json")
     }
@@ -73,6 +118,31 @@ impl Environment {
     pub fn mcp_local_config(&self) -> PathBuf {
         self.cwd.join(".mcp.json")
     }
+    pub fn workspace_settings(&self) -> PathBuf {
+        self.cwd.join(".forge").join("state.json")
+    }
+
+    /// Learnings about the workspace loaded into the system prompts
+    pub fn project_memory(&self) -> PathBuf {
+        self.cwd.join(".forge").join("memory.md")
+    }
+
+    /// Journal of the events of the workspace, one JSON entry per line
+    pub fn journal_path(&self) -> PathBuf {
+        self.cwd.join(".forge").join("journal").join("events.jsonl")
+    }
+
+    /// Directory holding the scratch directories of all conversations
+    pub fn scratch_root(&self) -> PathBuf {
+        self.cwd.join(".forge").join("tmp")
+    }
+
+    /// Directory where tools keep the intermediate artifacts of a
+    /// conversation, like full command logs. It's removed when the
+    /// conversation ends.
+    pub fn scratch_path(&self, conversation_id: &ConversationId) -> PathBuf {
+        self.scratch_root().join(conversation_id.to_string())
+    }
     pub fn version(&self) -> String {
         VERSION.to_string()
     }
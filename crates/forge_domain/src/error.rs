@@ -3,7 +3,7 @@ use std::pin::Pin;
 use derive_more::From;
 use thiserror::Error;
 
-use crate::{AgentId, ConversationId};
+use crate::{AgentId, ConversationId, MalformedToolCall};
 
 // NOTE: Deriving From for error is a really bad idea. This is because you end
 // up converting errors incorrectly without much context. For eg: You don't want
@@ -20,6 +20,10 @@ pub enum Error {
     #[error("JSON serialization/deserialization error: {0}")]
     ToolCallArgument(serde_json::Error),
 
+    #[error("Malformed arguments for tool {}: {}", .0.name, .0.error)]
+    #[from(skip)]
+    MalformedToolCall(MalformedToolCall),
+
     #[error("Invalid tool call XML: {0}")]
     #[from(skip)]
     ToolCallParse(String),
@@ -55,6 +59,114 @@ pub enum Error {
     #[from(skip)]
     NoModelDefined(AgentId),
 
+    #[error("Invalid snapshot retention: {0} must be greater than zero")]
+    #[from(skip)]
+    InvalidSnapshotRetention(&'static str),
+
+    #[error("Daily quota of {limit} exceeded, {used} were used today")]
+    #[from(skip)]
+    QuotaExceeded { limit: String, used: String },
+
+    #[error("Invalid commit message: {0}")]
+    #[from(skip)]
+    InvalidCommitMessage(String),
+
+    #[error("Template {template} needs a value for the variable {variable}")]
+    #[from(skip)]
+    MissingScaffoldVariable { template: String, variable: String },
+
+    #[error("Unknown template variable: {0}")]
+    #[from(skip)]
+    UnknownScaffoldVariable(String),
+
+    #[error("Unknown template filter: {0}")]
+    #[from(skip)]
+    UnknownScaffoldFilter(String),
+
+    #[error("Invalid template path {0}, it must be relative and stay in the target directory")]
+    #[from(skip)]
+    InvalidScaffoldPath(String),
+
+    #[error(
+        "Unsupported storage {0}, use s3://<bucket>/<prefix>, gs://<bucket>/<prefix> or postgres://<host>/<database>"
+    )]
+    #[from(skip)]
+    UnsupportedStorage(String),
+
+    #[error("Invalid {0} MCP configuration: {1}")]
+    #[from(skip)]
+    InvalidMcpImport(String, String),
+
+    #[error("Missing argument {argument}, usage: {usage}")]
+    #[from(skip)]
+    MissingMcpPromptArgument { argument: String, usage: String },
+
+    #[error("Invalid tool glob {glob} of the MCP server {server}: {reason}")]
+    #[from(skip)]
+    InvalidMcpToolGlob {
+        server: String,
+        glob: String,
+        reason: String,
+    },
+
+    #[error(
+        "Unknown timezone {0}, use local, UTC, an IANA name like Europe/Paris or an offset like +05:30"
+    )]
+    #[from(skip)]
+    UnknownTimezone(String),
+
+    #[error("Invalid time {0}, use RFC 3339 like 2024-05-01T09:00:00Z or YYYY-MM-DD HH:MM")]
+    #[from(skip)]
+    InvalidTime(String),
+
+    #[error("Invalid duration {0}, use amounts of w, d, h, m and s like 1d12h or -90m")]
+    #[from(skip)]
+    InvalidDuration(String),
+
+    #[error("Invalid reminder: {0}")]
+    #[from(skip)]
+    InvalidReminder(String),
+
+    #[error("Invalid expression: {0}")]
+    #[from(skip)]
+    InvalidExpression(String),
+
+    #[error("{0} is not a zip, tar or tar.gz archive")]
+    #[from(skip)]
+    UnsupportedArchive(String),
+
+    #[error("Archive entry {0} would be extracted outside of the destination")]
+    #[from(skip)]
+    UnsafeArchiveEntry(String),
+
+    #[error("Archive entry {0} is both a file and a directory")]
+    #[from(skip)]
+    ConflictingArchiveEntry(String),
+
+    #[error("Archive is too large: {0}")]
+    #[from(skip)]
+    ArchiveTooLarge(String),
+
+    #[error("Invalid destination {0}, use a directory name inside the scratch directory")]
+    #[from(skip)]
+    InvalidArchiveDestination(String),
+
+    #[error("{0} is not a PNG, JPEG or WebP image")]
+    #[from(skip)]
+    UnsupportedImage(String),
+
+    #[error("Image {0} is too large to be compared")]
+    #[from(skip)]
+    ImageTooLarge(String),
+
+    #[error("The review has no comments within <{tag}> tags")]
+    #[from(skip)]
+    MissingReviewComments { tag: &'static str },
+
+    #[error("Invalid review comments: {0}")]
+    #[from(skip)]
+    InvalidReviewComments(serde_json::Error),
+
     #[error(transparent)]
     Retryable(anyhow::Error),
 }
@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+
+/// What a seeded run depends on besides its prompts, recorded in the
+/// conversation so that a replay can report why its results differ
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fingerprint {
+    /// Version of forge
+    pub version: String,
+    /// Operating system and architecture, e.g. `linux-x86_64`
+    pub os: String,
+    pub model: String,
+    /// Hash of the workflow, covering the agents, their prompts and tools
+    pub workflow: String,
+    /// Commit checked out in the working directory, when it's a git
+    /// repository
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+}
+
+impl Fingerprint {
+    /// Fields whose value differs in the current fingerprint, as the name of
+    /// the field with the recorded and the current values
+    pub fn divergence(&self, current: &Self) -> Vec<(&'static str, String, String)> {
+        let commit = |commit: &Option<String>| commit.clone().unwrap_or_else(|| "none".to_string());
+        [
+            ("version", self.version.clone(), current.version.clone()),
+            ("os", self.os.clone(), current.os.clone()),
+            ("model", self.model.clone(), current.model.clone()),
+            ("workflow", self.workflow.clone(), current.workflow.clone()),
+            ("commit", commit(&self.commit), commit(&current.commit)),
+        ]
+        .into_iter()
+        .filter(|(_, recorded, current)| recorded != current)
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_divergence() {
+        let fixture = Fingerprint {
+            version: "0.99.0".to_string(),
+            os: "linux-x86_64".to_string(),
+            model: "gpt-4.1".to_string(),
+            workflow: "a1b2".to_string(),
+            commit: Some("4f2c9e1".to_string()),
+        };
+        let current = Fingerprint {
+            version: "0.100.0".to_string(),
+            commit: None,
+            ..fixture.clone()
+        };
+
+        let actual = (fixture.divergence(&fixture), fixture.divergence(&current));
+
+        let expected = (
+            vec![],
+            vec![
+                ("version", "0.99.0".to_string(), "0.100.0".to_string()),
+                ("commit", "4f2c9e1".to_string(), "none".to_string()),
+            ],
+        );
+        assert_eq!(actual, expected);
+    }
+}
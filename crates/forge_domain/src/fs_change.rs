@@ -0,0 +1,122 @@
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use strum_macros::Display;
+
+use crate::FSWait;
+
+/// Time waited for a change when the model doesn't set a timeout
+const DEFAULT_WAIT: Duration = Duration::from_secs(60);
+
+/// Longest wait, so that a forgotten change doesn't hold the agent for long
+const MAX_WAIT: Duration = Duration::from_secs(600);
+
+/// Interval between two checks of the watched path, limiting the load of
+/// waiting on the file system
+pub const FS_WAIT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// State of an existing path, compared between two checks to detect its
+/// changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileStamp {
+    pub modified: SystemTime,
+    pub size: u64,
+    /// Hash of the entries nested in a directory with their own state, as
+    /// the changes of a nested file don't change the directory itself. 0 for
+    /// a file.
+    pub listing: u64,
+}
+
+/// How a watched path changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Display)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum FsChange {
+    Created,
+    Modified,
+    Removed,
+}
+
+impl FsChange {
+    /// Change of the path between two checks, `None` standing for a path
+    /// where nothing exists
+    pub fn between(before: Option<FileStamp>, after: Option<FileStamp>) -> Option<FsChange> {
+        match (before, after) {
+            (None, Some(_)) => Some(FsChange::Created),
+            (Some(_), None) => Some(FsChange::Removed),
+            (Some(before), Some(after)) if before != after => Some(FsChange::Modified),
+            _ => None,
+        }
+    }
+}
+
+impl FSWait {
+    /// Time to wait for a change, bounded so that the agent isn't held
+    /// indefinitely
+    pub fn timeout(&self) -> Duration {
+        self.timeout_secs
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_WAIT)
+            .clamp(FS_WAIT_POLL_INTERVAL, MAX_WAIT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn stamp(secs: u64, size: u64) -> Option<FileStamp> {
+        Some(FileStamp {
+            modified: SystemTime::UNIX_EPOCH + Duration::from_secs(secs),
+            size,
+            listing: 0,
+        })
+    }
+
+    #[test]
+    fn test_change_between_checks() {
+        let fixture = [
+            (None, stamp(1, 10)),
+            (stamp(1, 10), None),
+            (stamp(1, 10), stamp(2, 10)),
+            (stamp(1, 10), stamp(1, 12)),
+            (stamp(1, 10), stamp(1, 10)),
+            (None, None),
+        ];
+
+        let actual = fixture
+            .into_iter()
+            .map(|(before, after)| FsChange::between(before, after))
+            .collect::<Vec<_>>();
+
+        let expected = vec![
+            Some(FsChange::Created),
+            Some(FsChange::Removed),
+            Some(FsChange::Modified),
+            Some(FsChange::Modified),
+            None,
+            None,
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_wait_timeout_is_bounded() {
+        let fixture = [None, Some(0), Some(30), Some(3_600)];
+
+        let actual = fixture
+            .into_iter()
+            .map(|timeout_secs| FSWait { timeout_secs, ..Default::default() }.timeout())
+            .collect::<Vec<_>>();
+
+        let expected = vec![
+            Duration::from_secs(60),
+            Duration::from_millis(500),
+            Duration::from_secs(30),
+            Duration::from_secs(600),
+        ];
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,216 @@
+use std::fmt;
+
+/// Number of claims of an answer checked, so that the verification stays
+/// cheap on long answers
+const MAX_CLAIMS: usize = 20;
+
+/// Extensions of the file names quoted in answers, which aren't configuration
+/// keys
+const FILE_EXTENSIONS: [&str; 16] = [
+    "rs", "toml", "yaml", "yml", "json", "md", "ts", "tsx", "js", "py", "go", "java", "txt",
+    "lock", "sh", "html",
+];
+
+/// Words quoted in answers that aren't about the codebase
+const COMMON_WORDS: [&str; 12] = [
+    "true", "false", "null", "None", "Some", "Ok", "Err", "self", "Self", "async", "await", "main",
+];
+
+/// Something an answer states exists in the codebase, quoted as inline code
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Claim {
+    /// A function, type or constant, e.g. `parse_config` or `Config::load()`
+    Symbol(String),
+    /// A key of a configuration, e.g. `server.port`
+    ConfigKey(String),
+}
+
+impl Claim {
+    /// Claims of the answer, each one once and in the order they are made.
+    /// Commands, paths and literals quoted in the answer aren't claims.
+    pub fn extract(answer: &str) -> Vec<Claim> {
+        let mut claims = Vec::new();
+        let mut fenced = false;
+        let lines = answer.lines().filter(|line| {
+            if line.trim_start().starts_with("```") {
+                fenced = !fenced;
+                return false;
+            }
+            !fenced
+        });
+        let spans = lines.flat_map(|line| line.split('`').skip(1).step_by(2));
+        for span in spans {
+            let Some(claim) = Claim::parse(span.trim()) else {
+                continue;
+            };
+            if !claims.contains(&claim) {
+                claims.push(claim);
+            }
+            if claims.len() == MAX_CLAIMS {
+                break;
+            }
+        }
+        claims
+    }
+
+    fn parse(span: &str) -> Option<Claim> {
+        let span = span.strip_suffix("()").unwrap_or(span);
+        if span.len() < 3 || COMMON_WORDS.contains(&span) {
+            return None;
+        }
+
+        let is_symbol = span.split("::").all(is_identifier)
+            // A plain word, e.g. `cargo` or `Error`, says little about the
+            // codebase
+            && (span.contains("::")
+                || span.contains('_')
+                || span.chars().skip(1).any(|c| c.is_ascii_uppercase()));
+        if is_symbol {
+            return Some(Claim::Symbol(span.to_string()));
+        }
+        let keys = span.split('.').collect::<Vec<_>>();
+        let is_file = keys
+            .last()
+            .is_some_and(|extension| FILE_EXTENSIONS.contains(extension));
+        if keys.len() > 1 && !is_file && keys.iter().all(|key| is_key(key)) {
+            return Some(Claim::ConfigKey(span.to_string()));
+        }
+        None
+    }
+
+    /// Term searched in the workspace to find the claim, the qualified name
+    /// of a symbol and the last segment of a key as a configuration nests its
+    /// keys
+    pub fn search_term(&self) -> &str {
+        match self {
+            Claim::Symbol(symbol) => symbol,
+            Claim::ConfigKey(key) => key.rsplit('.').next().unwrap_or(key),
+        }
+    }
+}
+
+impl fmt::Display for Claim {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Claim::Symbol(symbol) => write!(f, "{symbol}"),
+            Claim::ConfigKey(key) => write!(f, "{key}"),
+        }
+    }
+}
+
+fn is_identifier(word: &str) -> bool {
+    let mut chars = word.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn is_key(key: &str) -> bool {
+    key.chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_lowercase() || c == '_')
+        && key
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+}
+
+fn contains_word(line: &str, word: &str) -> bool {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    line.match_indices(word).any(|(start, _)| {
+        let before = line[..start].chars().next_back();
+        let after = line[start + word.len()..].chars().next();
+        !before.is_some_and(is_word) && !after.is_some_and(is_word)
+    })
+}
+
+/// Claims of an answer checked against the workspace
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GroundingCheck {
+    /// Claims found in the workspace
+    pub grounded: Vec<Claim>,
+    /// Claims nothing in the workspace matches, possibly hallucinated
+    pub unfounded: Vec<Claim>,
+}
+
+impl GroundingCheck {
+    /// A claim is grounded when one of the lines found searching the
+    /// workspace contains its search term as a whole word, with the same case
+    pub fn new(claims: Vec<Claim>, lines: &[String]) -> Self {
+        let (grounded, unfounded) = claims.into_iter().partition(|claim| {
+            lines
+                .iter()
+                .any(|line| contains_word(line, claim.search_term()))
+        });
+        Self { grounded, unfounded }
+    }
+
+    /// Whether every claim of the answer was found in the workspace
+    pub fn is_grounded(&self) -> bool {
+        self.unfounded.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_extract_claims() {
+        let fixture = "`Config::load()` reads `forge.yaml` and falls back to \
+                       `server.port`, see `parse_config` and `cargo test`. It returns `true` \
+                       through `Result`, and `parse_config` again.\n\n\
+                       ```rust\nlet `a_b` = 1;\n```";
+
+        let actual = Claim::extract(fixture);
+
+        let expected = vec![
+            Claim::Symbol("Config::load".to_string()),
+            Claim::ConfigKey("server.port".to_string()),
+            Claim::Symbol("parse_config".to_string()),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_grounding_check() {
+        let fixture = vec![
+            Claim::Symbol("parse_config".to_string()),
+            Claim::ConfigKey("server.port".to_string()),
+            Claim::Symbol("ForgeApp".to_string()),
+        ];
+        let lines = vec![
+            "pub fn parse_config(path: &Path) -> Result<Config> {".to_string(),
+            "    ports: 8080".to_string(),
+            "struct FORGEAPP;".to_string(),
+        ];
+
+        let actual = GroundingCheck::new(fixture, &lines);
+
+        let expected = GroundingCheck {
+            grounded: vec![Claim::Symbol("parse_config".to_string())],
+            unfounded: vec![
+                Claim::ConfigKey("server.port".to_string()),
+                Claim::Symbol("ForgeApp".to_string()),
+            ],
+        };
+        assert_eq!(actual, expected);
+        assert!(!actual.is_grounded());
+    }
+
+    #[test]
+    fn test_search_term() {
+        let fixture = [
+            Claim::Symbol("Config::load".to_string()),
+            Claim::ConfigKey("server.port".to_string()),
+            Claim::Symbol("ForgeApp".to_string()),
+        ];
+
+        let actual = fixture.iter().map(Claim::search_term).collect::<Vec<_>>();
+
+        let expected = vec!["Config::load", "port", "ForgeApp"];
+        assert_eq!(actual, expected);
+    }
+}
@@ -1,5 +1,10 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
 
+use crate::Provider;
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct HttpConfig {
@@ -8,6 +13,25 @@ pub struct HttpConfig {
     pub pool_idle_timeout: u64,
     pub pool_max_idle_per_host: usize,
     pub max_redirects: usize,
+    /// Seconds between the TCP keep-alive probes of the pooled connections,
+    /// so that the ones idle between turns aren't dropped by proxies. Zero
+    /// turns them off.
+    pub tcp_keepalive: u64,
+    /// Negotiates HTTP/2 with the servers supporting it, multiplexing the
+    /// requests over a single connection
+    pub http2: bool,
+    /// Seconds between the pings of idle HTTP/2 connections, keeping them
+    /// alive between turns. Zero turns them off.
+    pub http2_keep_alive_interval: u64,
+    /// Compresses the chat requests with gzip, for the providers and
+    /// gateways accepting compressed requests. Brotli is only negotiated for
+    /// the responses, as gzip is the encoding the gateways commonly accept.
+    pub compress_requests: bool,
+    /// Stall detection of the responses of the providers without one of their
+    /// own
+    pub stream_stall: StreamStall,
+    /// Stall detection of the responses by provider, see [`Provider::id`]
+    pub provider_stream_stall: BTreeMap<String, StreamStall>,
 }
 
 impl Default for HttpConfig {
@@ -18,6 +42,111 @@ impl Default for HttpConfig {
             pool_idle_timeout: 90,
             pool_max_idle_per_host: 5,
             max_redirects: 10,
+            tcp_keepalive: 60,
+            http2: true,
+            http2_keep_alive_interval: 30,
+            compress_requests: false,
+            stream_stall: StreamStall::default(),
+            provider_stream_stall: BTreeMap::new(),
         }
     }
 }
+
+impl HttpConfig {
+    /// Stall detection of the responses of the provider
+    pub fn stream_stall(&self, provider: &Provider) -> StreamStall {
+        self.provider_stream_stall
+            .get(provider.id())
+            .copied()
+            .unwrap_or(self.stream_stall)
+    }
+}
+
+/// How long a response may go without a chunk, in seconds. Servers keeping
+/// the connection alive with comments while the model hangs would otherwise
+/// leave the session waiting until the connection dies. Zero turns a limit
+/// off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamStall {
+    /// Warns that the response stalled
+    pub warn_after: u64,
+    /// Aborts the response so that the request is retried
+    pub abort_after: u64,
+}
+
+impl Default for StreamStall {
+    fn default() -> Self {
+        // Reasoning models may think for minutes before their first chunk
+        Self { warn_after: 60, abort_after: 60 * 5 }
+    }
+}
+
+impl StreamStall {
+    /// Wait before warning, `None` when it's off or the response is aborted
+    /// first
+    pub fn warn_after(&self) -> Option<Duration> {
+        let aborted_first = self.abort_after > 0 && self.warn_after >= self.abort_after;
+        (self.warn_after > 0 && !aborted_first).then(|| Duration::from_secs(self.warn_after))
+    }
+
+    /// Wait before aborting, `None` when it's off
+    pub fn abort_after(&self) -> Option<Duration> {
+        (self.abort_after > 0).then(|| Duration::from_secs(self.abort_after))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_stream_stall_of_provider() {
+        let fixture = HttpConfig {
+            provider_stream_stall: BTreeMap::from([(
+                "anthropic".to_string(),
+                StreamStall { warn_after: 20, abort_after: 90 },
+            )]),
+            ..Default::default()
+        };
+
+        let actual = [Provider::anthropic("key"), Provider::open_router("key")]
+            .iter()
+            .map(|provider| fixture.stream_stall(provider))
+            .collect::<Vec<_>>();
+
+        let expected = vec![
+            StreamStall { warn_after: 20, abort_after: 90 },
+            StreamStall::default(),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_stream_stall_limits() {
+        let fixture = [
+            StreamStall { warn_after: 30, abort_after: 120 },
+            StreamStall { warn_after: 120, abort_after: 30 },
+            StreamStall { warn_after: 30, abort_after: 0 },
+            StreamStall { warn_after: 0, abort_after: 0 },
+        ];
+
+        let actual = fixture
+            .iter()
+            .map(|stall| {
+                let seconds = |wait: Option<Duration>| wait.map(|wait| wait.as_secs());
+                (seconds(stall.warn_after()), seconds(stall.abort_after()))
+            })
+            .collect::<Vec<_>>();
+
+        let expected = vec![
+            (Some(30), Some(120)),
+            (None, Some(30)),
+            (Some(30), None),
+            (None, None),
+        ];
+        assert_eq!(actual, expected);
+    }
+}
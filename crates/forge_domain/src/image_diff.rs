@@ -0,0 +1,199 @@
+use crate::Image;
+
+/// Tolerance of the image diff when the call doesn't set one, high enough to
+/// ignore anti-aliasing and compression noise
+pub const DEFAULT_IMAGE_DIFF_TOLERANCE: u8 = 16;
+
+/// Largest image file compared
+pub const MAX_IMAGE_DIFF_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Largest image compared, in pixels, e.g. an 8K screenshot
+pub const MAX_IMAGE_DIFF_PIXELS: u64 = 7680 * 4320;
+
+/// Color of the changed pixels in the highlighted image
+const HIGHLIGHT: [u8; 4] = [255, 0, 0, 255];
+
+/// Pixels of a decoded image, as RGBA bytes row after row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bitmap {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl Bitmap {
+    /// Pixel at the position, `None` outside of the image
+    fn pixel(&self, x: u32, y: u32) -> Option<&[u8]> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let start = (y as usize * self.width as usize + x as usize) * 4;
+        self.pixels.get(start..start + 4)
+    }
+}
+
+/// Smallest rectangle holding all the changed pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiffRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// How much two images differ
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageComparison {
+    /// Width and height of the first image
+    pub before: (u32, u32),
+    /// Width and height of the second image
+    pub after: (u32, u32),
+    /// Pixels differing by more than the tolerance, or covered by only one of
+    /// the images when their sizes differ
+    pub changed_pixels: u64,
+    /// Pixels compared, those of the largest width and height of the images
+    pub total_pixels: u64,
+    pub region: Option<DiffRegion>,
+}
+
+impl ImageComparison {
+    /// Share of the pixels that changed, from 0 to 100
+    pub fn percentage(&self) -> f64 {
+        if self.total_pixels == 0 {
+            return 0.0;
+        }
+        self.changed_pixels as f64 * 100.0 / self.total_pixels as f64
+    }
+}
+
+/// Outcome of the image diff tool: the comparison and the second image with
+/// the changed pixels highlighted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageDiffOutput {
+    pub comparison: ImageComparison,
+    pub diff: Image,
+}
+
+/// Compares the images pixel by pixel. A pixel changed when a channel differs
+/// by more than the tolerance. The returned bitmap is the second image faded
+/// with the changed pixels painted red, so that the changes stand out.
+pub fn diff_images(before: &Bitmap, after: &Bitmap, tolerance: u8) -> (ImageComparison, Bitmap) {
+    let width = before.width.max(after.width);
+    let height = before.height.max(after.height);
+    let mut highlighted = Bitmap {
+        width,
+        height,
+        pixels: Vec::with_capacity(width as usize * height as usize * 4),
+    };
+    let mut changed_pixels = 0;
+    let mut region: Option<(u32, u32, u32, u32)> = None;
+
+    for y in 0..height {
+        for x in 0..width {
+            let unchanged = match (before.pixel(x, y), after.pixel(x, y)) {
+                (Some(a), Some(b)) if a.iter().zip(b).all(|(a, b)| a.abs_diff(*b) <= tolerance) => {
+                    Some(b)
+                }
+                _ => None,
+            };
+            match unchanged {
+                Some(pixel) => highlighted.pixels.extend_from_slice(&faded(pixel)),
+                None => {
+                    changed_pixels += 1;
+                    region = Some(match region {
+                        Some((left, top, right, bottom)) => {
+                            (left.min(x), top.min(y), right.max(x), bottom.max(y))
+                        }
+                        None => (x, y, x, y),
+                    });
+                    highlighted.pixels.extend_from_slice(&HIGHLIGHT);
+                }
+            }
+        }
+    }
+
+    let comparison = ImageComparison {
+        before: (before.width, before.height),
+        after: (after.width, after.height),
+        changed_pixels,
+        total_pixels: width as u64 * height as u64,
+        region: region.map(|(left, top, right, bottom)| DiffRegion {
+            x: left,
+            y: top,
+            width: right - left + 1,
+            height: bottom - top + 1,
+        }),
+    };
+    (comparison, highlighted)
+}
+
+/// Light gray of the pixel, keeping the layout visible under the highlights
+fn faded(pixel: &[u8]) -> [u8; 4] {
+    let gray = (pixel[0] as u32 * 299 + pixel[1] as u32 * 587 + pixel[2] as u32 * 114) / 1000;
+    let light = (255 - (255 - gray) / 3) as u8;
+    [light, light, light, 255]
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn bitmap(width: u32, height: u32, pixels: &[[u8; 4]]) -> Bitmap {
+        Bitmap { width, height, pixels: pixels.concat() }
+    }
+
+    const WHITE: [u8; 4] = [255, 255, 255, 255];
+    const BLACK: [u8; 4] = [0, 0, 0, 255];
+    const NEAR_WHITE: [u8; 4] = [250, 250, 250, 255];
+
+    #[test]
+    fn test_diff_images() {
+        let before = bitmap(3, 2, &[WHITE, WHITE, WHITE, WHITE, WHITE, WHITE]);
+        let after = bitmap(3, 2, &[WHITE, BLACK, WHITE, NEAR_WHITE, WHITE, BLACK]);
+
+        let (actual, highlighted) = diff_images(&before, &after, DEFAULT_IMAGE_DIFF_TOLERANCE);
+
+        let expected = ImageComparison {
+            before: (3, 2),
+            after: (3, 2),
+            changed_pixels: 2,
+            total_pixels: 6,
+            region: Some(DiffRegion { x: 1, y: 0, width: 2, height: 2 }),
+        };
+        assert_eq!(actual, expected);
+        assert_eq!(highlighted.pixel(1, 0), Some(&HIGHLIGHT[..]));
+        assert_eq!(highlighted.pixel(0, 1), Some(&faded(&NEAR_WHITE)[..]));
+    }
+
+    #[test]
+    fn test_diff_identical_images() {
+        let fixture = bitmap(2, 1, &[BLACK, WHITE]);
+
+        let (actual, _) = diff_images(&fixture, &fixture, 0);
+
+        assert_eq!(actual.changed_pixels, 0);
+        assert_eq!(actual.region, None);
+        assert_eq!(actual.percentage(), 0.0);
+    }
+
+    #[test]
+    fn test_diff_images_of_different_sizes() {
+        let before = bitmap(2, 1, &[WHITE, WHITE]);
+        let after = bitmap(2, 2, &[WHITE, WHITE, WHITE, WHITE]);
+
+        let (actual, highlighted) = diff_images(&before, &after, 0);
+
+        let expected = ImageComparison {
+            before: (2, 1),
+            after: (2, 2),
+            changed_pixels: 2,
+            total_pixels: 4,
+            region: Some(DiffRegion { x: 0, y: 1, width: 2, height: 1 }),
+        };
+        assert_eq!(actual, expected);
+        assert_eq!(actual.percentage(), 50.0);
+        assert_eq!(highlighted.pixels.len(), 16);
+    }
+}
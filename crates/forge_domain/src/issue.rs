@@ -0,0 +1,224 @@
+use std::fmt::Display;
+use std::path::{Component, Path};
+
+use serde::{Deserialize, Serialize};
+
+/// Number of words of the title kept in the name of a branch
+const BRANCH_TITLE_WORDS: usize = 6;
+
+/// An issue of the tracker of the repository a task is started from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Issue {
+    pub number: u64,
+    pub title: String,
+    #[serde(default)]
+    pub body: String,
+    pub url: String,
+}
+
+/// Issue a branch of the workspace was started from, remembered to link the
+/// pull request of the branch to it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IssueLink {
+    pub number: u64,
+    pub title: String,
+    pub url: String,
+}
+
+/// A file, and optionally lines of it, an issue refers to
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodeReference {
+    /// Path of the file relative to the root of the repository
+    pub path: String,
+    pub start_line: Option<u64>,
+    pub end_line: Option<u64>,
+}
+
+impl Issue {
+    /// Name of the branch of the task, such as `42-ignore-the-case-of-keys`
+    pub fn branch_name(&self) -> String {
+        let title = self
+            .title
+            .to_lowercase()
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .take(BRANCH_TITLE_WORDS)
+            .collect::<Vec<_>>()
+            .join("-");
+
+        if title.is_empty() {
+            format!("issue-{}", self.number)
+        } else {
+            format!("{}-{title}", self.number)
+        }
+    }
+
+    /// Files the body of the issue refers to, through links to files of a
+    /// GitHub repository first and then through paths within inline code.
+    /// Only relative paths that stay within the repository are kept, as the
+    /// body is written by anyone who can open an issue.
+    pub fn code_references(&self) -> Vec<CodeReference> {
+        let links = self
+            .body
+            .split_whitespace()
+            .filter_map(|word| word.split_once("/blob/"))
+            .filter_map(|(_, path)| {
+                // Drops the ref the link points to
+                let (_, path) = path.split_once('/')?;
+                let path = path.trim_end_matches(|c: char| ")>],.".contains(c));
+                Some(match path.split_once("#L") {
+                    Some((path, lines)) => {
+                        let (start, end) = lines.split_once("-L").unwrap_or((lines, lines));
+                        CodeReference::new(path, start.parse().ok(), end.parse().ok())
+                    }
+                    None => CodeReference::new(path, None, None),
+                })
+            });
+        let spans = self
+            .body
+            .split('`')
+            .skip(1)
+            .step_by(2)
+            .filter_map(CodeReference::parse);
+
+        let mut references: Vec<CodeReference> = Vec::new();
+        for reference in links.chain(spans) {
+            if reference.is_within_repository() && !references.contains(&reference) {
+                references.push(reference);
+            }
+        }
+        references
+    }
+}
+
+impl From<&Issue> for IssueLink {
+    fn from(issue: &Issue) -> Self {
+        Self {
+            number: issue.number,
+            title: issue.title.clone(),
+            url: issue.url.clone(),
+        }
+    }
+}
+
+impl CodeReference {
+    fn new(path: &str, start_line: Option<u64>, end_line: Option<u64>) -> Self {
+        Self { path: path.to_string(), start_line, end_line }
+    }
+
+    /// Whether the path is relative and made of plain names only, without
+    /// `..` or a root that would lead out of the repository
+    fn is_within_repository(&self) -> bool {
+        let path = Path::new(&self.path);
+        path.components().next().is_some()
+            && path
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+    }
+
+    /// Reads a reference written as `path`, `path:line` or `path:start-end`.
+    /// Only paths within a directory and with an extension are considered, to
+    /// tell them apart from other code.
+    fn parse(text: &str) -> Option<Self> {
+        let (path, lines) = match text.split_once(':') {
+            Some((path, lines)) => (path, Some(lines)),
+            None => (text, None),
+        };
+        let (directory, file) = path.rsplit_once('/')?;
+        let is_path = !directory.is_empty()
+            && file.contains('.')
+            && !file.ends_with('.')
+            && path
+                .chars()
+                .all(|c| c.is_alphanumeric() || "/._-".contains(c));
+        if !is_path {
+            return None;
+        }
+
+        let (start, end) = match lines {
+            Some(lines) => {
+                let (start, end) = lines.split_once('-').unwrap_or((lines, lines));
+                (Some(start.parse().ok()?), Some(end.parse().ok()?))
+            }
+            None => (None, None),
+        };
+        Some(Self::new(path.trim_start_matches("./"), start, end))
+    }
+}
+
+impl Display for CodeReference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.path)?;
+        match (self.start_line, self.end_line) {
+            (Some(start), Some(end)) if start != end => write!(f, ":{start}-{end}"),
+            (Some(start), _) => write!(f, ":{start}"),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn fixture(title: &str, body: &str) -> Issue {
+        Issue {
+            number: 42,
+            title: title.to_string(),
+            body: body.to_string(),
+            url: "https://github.com/acme/cache/issues/42".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_branch_name() {
+        let actual = [
+            fixture(
+                "Cache keys aren't case-insensitive (regression in v2.1)",
+                "",
+            ),
+            fixture("🐛", ""),
+        ]
+        .map(|issue| issue.branch_name());
+
+        let expected = [
+            "42-cache-keys-aren-t-case-insensitive".to_string(),
+            "issue-42".to_string(),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_code_references() {
+        let fixture = fixture(
+            "Cache keys aren't case-insensitive",
+            "The lookup in `src/cache.rs:12-14` uses the key as is, unlike \
+             https://github.com/acme/cache/blob/main/src/store.rs#L30-L32. \
+             Calling `cache.get(key)` or `Cache::new` doesn't help, see `docs/cache.md`.",
+        );
+
+        let actual = fixture.code_references();
+
+        let expected = vec![
+            CodeReference::new("src/store.rs", Some(30), Some(32)),
+            CodeReference::new("src/cache.rs", Some(12), Some(14)),
+            CodeReference::new("docs/cache.md", None, None),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_code_references_outside_of_repository() {
+        let fixture = fixture(
+            "Leaks",
+            "See `/etc/ssl/openssl.cnf`, `../../.ssh/id_rsa`, `src/../../secret.rs` and `./src/lib.rs`.",
+        );
+
+        let actual = fixture.code_references();
+
+        let expected: Vec<CodeReference> = vec![];
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,102 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{ChangeSummary, ConversationId, FileChangeKind};
+
+/// A line of the workspace journal, which records what agents do in the
+/// workspace so that editors and dashboards can follow along.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub timestamp: DateTime<Utc>,
+    pub conversation_id: ConversationId,
+    #[serde(flatten)]
+    pub event: JournalEvent,
+}
+
+impl JournalEntry {
+    pub fn new(conversation_id: ConversationId, event: JournalEvent) -> Self {
+        Self { timestamp: Utc::now(), conversation_id, event }
+    }
+
+    /// Entries for the files changed and the commands run by an agent
+    pub fn from_changes(conversation_id: ConversationId, changes: &ChangeSummary) -> Vec<Self> {
+        let files = changes
+            .files
+            .iter()
+            .map(|file| JournalEvent::FileModified { path: file.path.clone(), kind: file.kind });
+        let commands = changes
+            .commands
+            .iter()
+            .map(|command| JournalEvent::CommandExecuted { command: command.clone() });
+
+        files
+            .chain(commands)
+            .map(|event| Self::new(conversation_id, event))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum JournalEvent {
+    ConversationStarted,
+    ConversationEnded,
+    FileModified { path: String, kind: FileChangeKind },
+    CommandExecuted { command: String },
+}
+
+/// Entries read from the journal, along with the offset to continue reading
+/// from once more entries are written.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct JournalPage {
+    pub entries: Vec<JournalEntry>,
+    pub offset: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::FileChange;
+
+    #[test]
+    fn test_entries_from_changes() {
+        let conversation_id = ConversationId::generate();
+        let mut changes = ChangeSummary::default();
+        changes.record_file(FileChange {
+            path: "/project/main.rs".to_string(),
+            kind: FileChangeKind::Modified,
+            insertions: 1,
+            deletions: 0,
+        });
+        changes.record_command("cargo test");
+
+        let actual = JournalEntry::from_changes(conversation_id, &changes)
+            .into_iter()
+            .map(|entry| entry.event)
+            .collect::<Vec<_>>();
+
+        let expected = vec![
+            JournalEvent::FileModified {
+                path: "/project/main.rs".to_string(),
+                kind: FileChangeKind::Modified,
+            },
+            JournalEvent::CommandExecuted { command: "cargo test".to_string() },
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_entry_json_line() {
+        let fixture = JournalEntry::new(
+            ConversationId::generate(),
+            JournalEvent::CommandExecuted { command: "ls".to_string() },
+        );
+
+        let actual = serde_json::to_value(&fixture).unwrap();
+
+        assert_eq!(actual["event"], "command_executed");
+        assert_eq!(actual["command"], "ls");
+    }
+}
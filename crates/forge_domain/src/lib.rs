@@ -1,94 +1,193 @@
 mod agent;
+mod archive;
 mod attachment;
+mod autonomy;
+mod batch;
+mod calculator;
+mod change_summary;
 mod chat_request;
 mod chat_response;
+mod checkpoint;
+mod citation;
+mod commit_message;
 mod compact;
 mod compaction_result;
 mod compaction_strategy;
 mod context;
+mod context_diff;
+mod context_drop;
+mod context_inspection;
 mod conversation;
 mod conversation_html;
+mod edit_mode;
 mod env;
+mod env_var;
 mod error;
 mod event;
 mod file;
+mod fingerprint;
+mod fs_change;
+mod grounding;
 mod http_config;
 mod image;
+mod image_diff;
+mod issue;
+mod journal;
+mod lint;
+mod logging;
 mod max_tokens;
 mod mcp;
+mod mcp_import;
 mod merge;
 mod message;
 mod model;
+mod moderation;
+mod notifications;
+mod output_ring;
+mod permissions;
+mod pin;
 mod point;
+mod project_memory;
 mod provider;
 mod reasoning;
+mod release_notes;
+mod resource_governor;
 mod result_stream_ext;
 mod retry_config;
+mod review;
+mod runnable;
+mod scaffold;
+mod share;
 mod shell;
+mod shell_retry;
+mod snapshot_retention;
+mod storage;
 mod suggestion;
 mod system_context;
 mod task;
 mod temperature;
 mod template;
+mod test_after_edit;
+mod theme_config;
+mod time;
+mod timeline;
+mod timeline_html;
 mod tool_call;
 mod tool_call_context;
 mod tool_call_parser;
 mod tool_choice;
 mod tool_definition;
+mod tool_gate;
 mod tool_name;
 mod tool_result;
+mod tool_stats;
 mod tool_usage;
+mod toolchain;
 mod tools;
 mod top_k;
 mod top_p;
 mod transformer;
+mod turn_history;
 mod update;
+mod usage_quota;
+mod verification;
 mod workflow;
+mod workspace_settings;
+mod worktree;
 mod xml;
 
 pub use agent::*;
+pub use archive::*;
 pub use attachment::*;
+pub use autonomy::*;
+pub use batch::*;
+pub use calculator::*;
+pub use change_summary::*;
 pub use chat_request::*;
 pub use chat_response::*;
+pub use checkpoint::*;
+pub use citation::*;
+pub use commit_message::*;
 pub use compact::*;
 pub use compaction_result::*;
 pub use compaction_strategy::*;
 pub use context::*;
+pub use context_diff::*;
+pub use context_drop::*;
+pub use context_inspection::*;
 pub use conversation::*;
 pub use conversation_html::*;
+pub use edit_mode::*;
 pub use env::*;
+pub use env_var::*;
 pub use error::*;
 pub use event::*;
 pub use file::*;
+pub use fingerprint::*;
+pub use fs_change::*;
+pub use grounding::*;
 pub use http_config::*;
 pub use image::*;
+pub use image_diff::*;
+pub use issue::*;
+pub use journal::*;
+pub use lint::*;
+pub use logging::*;
 pub use max_tokens::*;
 pub use mcp::*;
+pub use mcp_import::*;
 pub use message::*;
 pub use model::*;
+pub use moderation::*;
+pub use notifications::*;
+pub use output_ring::*;
+pub use permissions::*;
+pub use pin::*;
 pub use point::*;
+pub use project_memory::*;
 pub use provider::*;
 pub use reasoning::*;
+pub use release_notes::*;
+pub use resource_governor::*;
 pub use result_stream_ext::*;
 pub use retry_config::*;
+pub use review::*;
+pub use runnable::*;
+pub use scaffold::*;
+pub use share::*;
 pub use shell::*;
+pub use shell_retry::*;
+pub use snapshot_retention::*;
+pub use storage::*;
 pub use suggestion::*;
 pub use system_context::*;
 pub use task::*;
 pub use temperature::*;
 pub use template::*;
+pub use test_after_edit::*;
+pub use theme_config::*;
+pub use time::*;
+pub use timeline::*;
 pub use tool_call::*;
 pub use tool_call_context::*;
 pub use tool_call_parser::*;
 pub use tool_choice::*;
 pub use tool_definition::*;
+pub use tool_gate::*;
 pub use tool_name::*;
 pub use tool_result::*;
+pub use tool_stats::*;
 pub use tool_usage::*;
+pub use toolchain::*;
 pub use tools::*;
 pub use top_k::*;
 pub use top_p::*;
 pub use transformer::*;
+pub use turn_history::*;
 pub use update::*;
+pub use usage_quota::*;
+pub use verification::*;
 pub use workflow::*;
+pub use workspace_settings::*;
+pub use worktree::*;
 pub use xml::*;
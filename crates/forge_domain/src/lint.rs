@@ -0,0 +1,343 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use strum_macros::Display;
+
+/// How serious a diagnostic is, as reported by the linter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum LintSeverity {
+    Error,
+    Warning,
+    Info,
+}
+
+/// Unit of the offsets of a text edit. ESLint counts UTF-16 code units, as
+/// JavaScript strings do, while the compiler counts bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OffsetUnit {
+    Byte,
+    Utf16,
+}
+
+/// Replacement of the content between two offsets of a file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextEdit {
+    pub start: usize,
+    pub end: usize,
+    pub replacement: String,
+    pub unit: OffsetUnit,
+}
+
+/// A problem reported by a linter in a file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    /// Absolute path of the file
+    pub path: String,
+    /// Line of the problem (1-based)
+    pub line: u64,
+    /// Column of the problem (1-based)
+    pub column: u64,
+    /// Name of the rule, e.g. `clippy::needless_return` or `no-unused-vars`,
+    /// when the problem isn't a plain compiler error
+    pub rule: Option<String>,
+    pub severity: LintSeverity,
+    pub message: String,
+    /// Edits fixing the problem that are safe to apply without review
+    pub fixes: Vec<TextEdit>,
+}
+
+/// Linters whose JSON output the lint tool understands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum Linter {
+    Clippy,
+    Eslint,
+}
+
+impl Linter {
+    /// Linter checking the file, based on its extension
+    pub fn for_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "rs" => Some(Self::Clippy),
+            "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" | "mts" | "cts" => Some(Self::Eslint),
+            _ => None,
+        }
+    }
+
+    /// Files marking the root of a project configured for the linter. The
+    /// linter is only run when one of them is found in a parent directory.
+    pub fn markers(&self) -> &'static [&'static str] {
+        match self {
+            Self::Clippy => &["Cargo.toml"],
+            Self::Eslint => &[
+                "eslint.config.js",
+                "eslint.config.mjs",
+                "eslint.config.cjs",
+                "eslint.config.ts",
+                ".eslintrc.js",
+                ".eslintrc.cjs",
+                ".eslintrc.json",
+                ".eslintrc.yml",
+                ".eslintrc.yaml",
+                ".eslintrc",
+            ],
+        }
+    }
+
+    /// Program and arguments of the command linting the files from the root
+    /// of the project. Clippy always checks the whole package, its
+    /// diagnostics are filtered afterwards.
+    pub fn command(&self, paths: &[String]) -> Vec<String> {
+        match self {
+            Self::Clippy => ["cargo", "clippy", "--message-format=json", "--quiet"]
+                .map(String::from)
+                .to_vec(),
+            Self::Eslint => ["npx", "--no-install", "eslint", "--format", "json"]
+                .into_iter()
+                .map(String::from)
+                .chain(paths.iter().cloned())
+                .collect(),
+        }
+    }
+
+    /// Parses the JSON output of the linter run from the root into the
+    /// diagnostics of the files
+    pub fn parse(&self, output: &str, root: &Path) -> Vec<Diagnostic> {
+        match self {
+            Self::Clippy => output
+                .lines()
+                .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+                .filter(|value| value["reason"] == "compiler-message")
+                .filter_map(|value| parse_compiler_message(&value["message"], root))
+                .collect(),
+            Self::Eslint => serde_json::from_str::<Vec<Value>>(output)
+                .unwrap_or_default()
+                .iter()
+                .flat_map(parse_eslint_file)
+                .collect(),
+        }
+    }
+}
+
+fn parse_compiler_message(message: &Value, root: &Path) -> Option<Diagnostic> {
+    let span = message["spans"]
+        .as_array()?
+        .iter()
+        .find(|span| span["is_primary"] == true)?;
+    let severity = match message["level"].as_str()? {
+        "error" => LintSeverity::Error,
+        "warning" => LintSeverity::Warning,
+        _ => LintSeverity::Info,
+    };
+    let spans = std::iter::once(message)
+        .chain(message["children"].as_array().into_iter().flatten())
+        .flat_map(|message| message["spans"].as_array().into_iter().flatten());
+    // The suggestions may change other files, e.g. the definition of a
+    // function, while the fixes are applied to the file of the diagnostic
+    let fixes = spans
+        .filter(|candidate| candidate["file_name"] == span["file_name"])
+        .filter(|span| span["suggestion_applicability"] == "MachineApplicable")
+        .filter_map(|span| {
+            Some(TextEdit {
+                start: span["byte_start"].as_u64()? as usize,
+                end: span["byte_end"].as_u64()? as usize,
+                replacement: span["suggested_replacement"].as_str()?.to_string(),
+                unit: OffsetUnit::Byte,
+            })
+        })
+        .collect();
+
+    Some(Diagnostic {
+        path: root.join(span["file_name"].as_str()?).display().to_string(),
+        line: span["line_start"].as_u64()?,
+        column: span["column_start"].as_u64()?,
+        rule: message["code"]["code"].as_str().map(String::from),
+        severity,
+        message: message["message"].as_str()?.to_string(),
+        fixes,
+    })
+}
+
+fn parse_eslint_file(file: &Value) -> Vec<Diagnostic> {
+    let Some(path) = file["filePath"].as_str() else {
+        return Vec::new();
+    };
+    file["messages"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|message| {
+            let severity = match message["severity"].as_u64()? {
+                2 => LintSeverity::Error,
+                1 => LintSeverity::Warning,
+                _ => LintSeverity::Info,
+            };
+            let fix = &message["fix"];
+            let fixes = fix["range"]
+                .as_array()
+                .and_then(|range| {
+                    Some(TextEdit {
+                        start: range.first()?.as_u64()? as usize,
+                        end: range.get(1)?.as_u64()? as usize,
+                        replacement: fix["text"].as_str()?.to_string(),
+                        unit: OffsetUnit::Utf16,
+                    })
+                })
+                .into_iter()
+                .collect();
+
+            Some(Diagnostic {
+                path: path.to_string(),
+                line: message["line"].as_u64().unwrap_or(1),
+                column: message["column"].as_u64().unwrap_or(1),
+                rule: message["ruleId"].as_str().map(String::from),
+                severity,
+                message: message["message"].as_str()?.to_string(),
+                fixes,
+            })
+        })
+        .collect()
+}
+
+/// Applies the edits to the content, from the last one to the first so that
+/// their offsets stay valid. An edit overlapping one that was applied, or
+/// with offsets outside of the content, is skipped. Returns the new content
+/// with the number of edits applied.
+pub fn apply_edits(content: &str, edits: &[TextEdit]) -> (String, usize) {
+    let mut ranges = edits
+        .iter()
+        .filter_map(|edit| {
+            let start = byte_offset(content, edit.start, edit.unit)?;
+            let end = byte_offset(content, edit.end, edit.unit)?;
+            (start <= end).then_some((start, end, edit.replacement.as_str()))
+        })
+        .collect::<Vec<_>>();
+    ranges.sort_by(|a, b| b.cmp(a));
+    ranges.dedup();
+
+    let mut content = content.to_string();
+    let mut applied = 0;
+    let mut limit = usize::MAX;
+    for (start, end, replacement) in ranges {
+        if end > limit {
+            continue;
+        }
+        content.replace_range(start..end, replacement);
+        limit = start;
+        applied += 1;
+    }
+    (content, applied)
+}
+
+/// Converts the offset to an offset in bytes, `None` when it's outside of
+/// the content or not on a character boundary
+fn byte_offset(content: &str, offset: usize, unit: OffsetUnit) -> Option<usize> {
+    match unit {
+        OffsetUnit::Byte => content.is_char_boundary(offset).then_some(offset),
+        OffsetUnit::Utf16 => {
+            let mut units = 0;
+            for (index, char) in content.char_indices() {
+                if units == offset {
+                    return Some(index);
+                }
+                units += char.len_utf16();
+            }
+            (units == offset).then_some(content.len())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_clippy() {
+        let fixture = [
+            r#"{"reason":"compiler-artifact","target":{"name":"app"}}"#,
+            r#"{"reason":"compiler-message","message":{"message":"unneeded `return` statement","code":{"code":"clippy::needless_return","explanation":null},"level":"warning","spans":[{"file_name":"src/lib.rs","byte_start":25,"byte_end":34,"line_start":2,"column_start":5,"is_primary":true,"suggested_replacement":null,"suggestion_applicability":null}],"children":[{"message":"remove `return`","code":null,"level":"help","spans":[{"file_name":"src/lib.rs","byte_start":25,"byte_end":34,"line_start":2,"column_start":5,"is_primary":true,"suggested_replacement":"1","suggestion_applicability":"MachineApplicable"},{"file_name":"src/other.rs","byte_start":3,"byte_end":5,"line_start":1,"column_start":4,"is_primary":false,"suggested_replacement":"x","suggestion_applicability":"MachineApplicable"}],"children":[]}]}}"#,
+            r#"{"reason":"compiler-message","message":{"message":"aborting due to 1 previous error","code":null,"level":"error","spans":[],"children":[]}}"#,
+        ]
+        .join("\n");
+
+        let actual = Linter::Clippy.parse(&fixture, Path::new("/project"));
+
+        let expected = vec![Diagnostic {
+            path: "/project/src/lib.rs".to_string(),
+            line: 2,
+            column: 5,
+            rule: Some("clippy::needless_return".to_string()),
+            severity: LintSeverity::Warning,
+            message: "unneeded `return` statement".to_string(),
+            fixes: vec![TextEdit {
+                start: 25,
+                end: 34,
+                replacement: "1".to_string(),
+                unit: OffsetUnit::Byte,
+            }],
+        }];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_eslint() {
+        let fixture = r#"[{"filePath":"/project/src/index.js","messages":[{"ruleId":"no-unused-vars","severity":2,"message":"'a' is defined but never used.","line":1,"column":5},{"ruleId":"semi","severity":1,"message":"Missing semicolon.","line":2,"column":10,"fix":{"range":[21,21],"text":";"}}]}]"#;
+
+        let actual = Linter::Eslint.parse(fixture, Path::new("/project"));
+
+        let expected = vec![
+            Diagnostic {
+                path: "/project/src/index.js".to_string(),
+                line: 1,
+                column: 5,
+                rule: Some("no-unused-vars".to_string()),
+                severity: LintSeverity::Error,
+                message: "'a' is defined but never used.".to_string(),
+                fixes: vec![],
+            },
+            Diagnostic {
+                path: "/project/src/index.js".to_string(),
+                line: 2,
+                column: 10,
+                rule: Some("semi".to_string()),
+                severity: LintSeverity::Warning,
+                message: "Missing semicolon.".to_string(),
+                fixes: vec![TextEdit {
+                    start: 21,
+                    end: 21,
+                    replacement: ";".to_string(),
+                    unit: OffsetUnit::Utf16,
+                }],
+            },
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_apply_edits() {
+        let edit = |start, end, replacement: &str, unit| TextEdit {
+            start,
+            end,
+            replacement: replacement.to_string(),
+            unit,
+        };
+        let fixture = [
+            edit(0, 3, "let", OffsetUnit::Byte),
+            edit(0, 2, "overlapping", OffsetUnit::Byte),
+            // At the end, "é" being one UTF-16 unit but two bytes
+            edit(11, 11, ";", OffsetUnit::Utf16),
+            edit(100, 100, "outside", OffsetUnit::Byte),
+        ];
+
+        let actual = apply_edits("var a = \"é\"", &fixture);
+
+        let expected = ("let a = \"é\";".to_string(), 2);
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,62 @@
+use merge::Merge;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Output format of the log records
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LogFormat {
+    #[default]
+    Json,
+    Pretty,
+}
+
+/// How often a new log file is started
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LogRotation {
+    Hourly,
+    #[default]
+    Daily,
+    Never,
+}
+
+/// Configuration of the logs written under `base_path/logs`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Merge, JsonSchema)]
+pub struct Logging {
+    /// Log filter with optional per-module levels, e.g.
+    /// `forge=debug,forge_provider=trace`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<String>,
+    /// Format of the log records
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<LogFormat>,
+    /// How often log files are rotated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rotation: Option<LogRotation>,
+    /// Maximum number of rotated log files to keep
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_files: Option<usize>,
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_logging_from_yaml() {
+        let fixture = "level: forge=debug,forge_provider=trace\nformat: pretty\nrotation: hourly\nmax_files: 5\n";
+
+        let actual: Logging = serde_yml::from_str(fixture).unwrap();
+
+        let expected = Logging {
+            level: Some("forge=debug,forge_provider=trace".to_string()),
+            format: Some(LogFormat::Pretty),
+            rotation: Some(LogRotation::Hourly),
+            max_files: Some(5),
+        };
+        assert_eq!(actual, expected);
+    }
+}
@@ -6,8 +6,14 @@ use std::fmt::{Display, Formatter};
 use std::ops::Deref;
 
 use derive_setters::Setters;
+use glob::Pattern;
 use merge::Merge;
 use serde::{Deserialize, Serialize};
+use tokio::sync::oneshot;
+
+use crate::{
+    AttachmentContent, Context, ContextMessage, Error, ModelId, Result, Role, Temperature, ToolName,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Scope {
@@ -29,12 +35,39 @@ impl McpServerConfig {
         args: Vec<String>,
         env: Option<BTreeMap<String, String>>,
     ) -> Self {
-        Self::Stdio(McpStdioServer { command: command.into(), args, env: env.unwrap_or_default() })
+        Self::Stdio(McpStdioServer {
+            command: command.into(),
+            args,
+            env: env.unwrap_or_default(),
+            tools: Default::default(),
+        })
     }
 
     /// Create a new SSE-based MCP server
     pub fn new_sse(url: impl Into<String>) -> Self {
-        Self::Sse(McpSseServer { url: url.into() })
+        Self::Sse(McpSseServer {
+            url: url.into(),
+            transport: McpHttpTransport::Sse,
+            tools: Default::default(),
+        })
+    }
+
+    /// Create a new MCP server reached over HTTP, with the transport it
+    /// supports
+    pub fn new_http(url: impl Into<String>) -> Self {
+        Self::Sse(McpSseServer {
+            url: url.into(),
+            transport: McpHttpTransport::Auto,
+            tools: Default::default(),
+        })
+    }
+
+    /// Tools of the server offered to the model
+    pub fn tool_filter(&self) -> &McpToolFilter {
+        match self {
+            McpServerConfig::Stdio(stdio) => &stdio.tools,
+            McpServerConfig::Sse(sse) => &sse.tools,
+        }
     }
 }
 
@@ -52,13 +85,92 @@ pub struct McpStdioServer {
     /// Environment variables to pass to the command
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub env: BTreeMap<String, String>,
+
+    #[serde(flatten)]
+    pub tools: McpToolFilter,
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq, Hash)]
 pub struct McpSseServer {
-    /// Url of the MCP server
+    /// Url of the MCP server, `ws://` and `wss://` URLs being reached over a
+    /// WebSocket
     #[serde(skip_serializing_if = "String::is_empty")]
     pub url: String,
+
+    /// Transport used to exchange with the server
+    #[serde(default, skip_serializing_if = "McpHttpTransport::is_sse")]
+    pub transport: McpHttpTransport,
+
+    #[serde(flatten)]
+    pub tools: McpToolFilter,
+}
+
+/// Tools of a server offered to the model, by name as the server lists them.
+/// Globs are supported, e.g. `browser_*`.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq, Hash)]
+pub struct McpToolFilter {
+    /// Only the tools matching one of these are offered, all of them when
+    /// unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub include_tools: Option<Vec<String>>,
+
+    /// Tools never offered, even when included
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude_tools: Vec<String>,
+}
+
+impl McpToolFilter {
+    /// Whether the tool of the server is offered to the model
+    pub fn allows(&self, tool: &str) -> bool {
+        let matches = |globs: &[String]| {
+            globs
+                .iter()
+                .filter_map(|glob| Pattern::new(glob).ok())
+                .any(|pattern| pattern.matches(tool))
+        };
+        self.include_tools.as_deref().is_none_or(matches) && !matches(&self.exclude_tools)
+    }
+
+    /// Checks that the globs of the server parse, as the ones that don't
+    /// would otherwise never match
+    pub fn validate(&self, server: &str) -> Result<()> {
+        self.include_tools
+            .iter()
+            .flatten()
+            .chain(&self.exclude_tools)
+            .try_for_each(|glob| {
+                Pattern::new(glob)
+                    .map(|_| ())
+                    .map_err(|error| Error::InvalidMcpToolGlob {
+                        server: server.to_string(),
+                        glob: glob.clone(),
+                        reason: error.msg.to_string(),
+                    })
+            })
+    }
+}
+
+/// Transport of an MCP server reached over HTTP
+#[derive(Default, Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum McpHttpTransport {
+    /// Streamable HTTP, falling back to SSE for the servers that don't
+    /// support it yet
+    Auto,
+    /// Messages posted to the single endpoint of the server, which answers
+    /// with JSON or with a stream of events
+    StreamableHttp,
+    /// Legacy transport of the servers streaming their messages from an SSE
+    /// endpoint, kept for the configurations written before the transport
+    /// could be chosen
+    #[default]
+    Sse,
+}
+
+impl McpHttpTransport {
+    fn is_sse(&self) -> bool {
+        *self == McpHttpTransport::Sse
+    }
 }
 
 impl Display for McpServerConfig {
@@ -91,6 +203,15 @@ pub struct McpConfig {
     pub mcp_servers: BTreeMap<String, McpServerConfig>,
 }
 
+impl McpConfig {
+    /// Checks the tool globs of every server
+    pub fn validate(&self) -> Result<()> {
+        self.mcp_servers
+            .iter()
+            .try_for_each(|(name, server)| server.tool_filter().validate(name))
+    }
+}
+
 impl Deref for McpConfig {
     type Target = BTreeMap<String, McpServerConfig>;
 
@@ -99,8 +220,450 @@ impl Deref for McpConfig {
     }
 }
 
+/// Event an MCP server notifies on its own initiative, outside of the
+/// responses to the requests of the client
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum McpNotification {
+    /// The tools of the server changed and should be listed again
+    ToolListChanged,
+    /// Resources were added to or removed from the server
+    ResourceListChanged,
+    /// The content of a resource the client subscribed to changed
+    ResourceUpdated { uri: String },
+    /// The prompts of the server changed
+    PromptListChanged,
+}
+
+/// Completion an MCP server asks the client to generate with its model, so
+/// that the server doesn't need credentials of its own
+#[derive(Debug, Clone, PartialEq)]
+pub struct McpSamplingRequest {
+    /// Name of the server asking, set once the request reaches the service
+    pub server: String,
+    pub messages: Vec<McpSamplingMessage>,
+    pub system_prompt: Option<String>,
+    pub max_tokens: usize,
+    pub temperature: Option<f32>,
+}
+
+/// Message of the conversation an MCP server asks a completion of, only text
+/// being supported
+#[derive(Debug, Clone, PartialEq)]
+pub struct McpSamplingMessage {
+    pub role: Role,
+    pub text: String,
+}
+
+/// Completion generated for an MCP server
+#[derive(Debug, Clone, PartialEq)]
+pub struct McpSamplingResponse {
+    /// Model that generated the completion
+    pub model: String,
+    pub text: String,
+}
+
+/// Sampling request waiting for its response. The server waits until the reply
+/// is sent, or fails the request once it's dropped.
+#[derive(Debug)]
+pub struct McpSampling {
+    pub request: McpSamplingRequest,
+    pub reply: oneshot::Sender<anyhow::Result<McpSamplingResponse>>,
+}
+
+impl McpSamplingRequest {
+    /// Messages the completion is asked for, one per paragraph after its role,
+    /// shown to the user before they approve the request
+    pub fn transcript(&self) -> String {
+        let system = self
+            .system_prompt
+            .iter()
+            .map(|prompt| format!("{}: {prompt}", Role::System));
+        let messages = self
+            .messages
+            .iter()
+            .map(|message| format!("{}: {}", message.role, message.text));
+        system.chain(messages).collect::<Vec<_>>().join("\n\n")
+    }
+
+    /// Context asking the model of the agent for the completion, with the
+    /// system prompt of the server rather than the one of the agent
+    pub fn context(&self, model: &ModelId) -> Context {
+        let system = self.system_prompt.iter().map(ContextMessage::system);
+        let messages = self.messages.iter().map(|message| match message.role {
+            Role::Assistant => ContextMessage::assistant(&message.text, None, None),
+            _ => ContextMessage::user(&message.text, Some(model.clone())),
+        });
+        let mut context = Context::default().max_tokens(self.max_tokens);
+        for message in system.chain(messages) {
+            context = context.add_message(message);
+        }
+        match self.temperature.and_then(|t| Temperature::new(t).ok()) {
+            Some(temperature) => context.temperature(temperature),
+            None => context,
+        }
+    }
+}
+
+/// Name of a tool of an MCP server, prefixed with the server so that the tools
+/// of different servers don't collide
+pub fn mcp_tool_name(server: &str, tool: &str) -> ToolName {
+    ToolName::new(format!("mcp_{server}_tool_{tool}"))
+}
+
+/// Name of the MCP server offering the tool, `None` when it isn't an MCP tool
+pub fn mcp_server_of(tool: &ToolName) -> Option<&str> {
+    let rest = tool.as_str().strip_prefix("mcp_")?;
+    rest.find("_tool_").map(|end| &rest[..end])
+}
+
+/// Whether the tool is one of the tools of the MCP server
+pub fn is_mcp_tool_of(tool: &ToolName, server: &str) -> bool {
+    mcp_server_of(tool) == Some(server)
+}
+
+/// Prompt template an MCP server offers, run by the user as a slash command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct McpPrompt {
+    pub name: String,
+    pub description: Option<String>,
+    pub arguments: Vec<McpPromptArgument>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct McpPromptArgument {
+    pub name: String,
+    pub description: Option<String>,
+    pub required: bool,
+}
+
+/// Name of a prompt of an MCP server, prefixed with the server so that the
+/// prompts of different servers don't collide
+pub fn mcp_prompt_name(server: &str, prompt: &str) -> String {
+    format!("{server}:{prompt}")
+}
+
+/// Resource an MCP server offers, e.g. a file, a schema or an issue, attached
+/// to a message as `@mcp:server/uri`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct McpResource {
+    /// Name of the server offering it, set once listed by the service
+    pub server: String,
+    pub uri: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub mime_type: Option<String>,
+}
+
+impl McpResource {
+    /// Reference attaching the resource to a message, e.g.
+    /// `mcp:github/repo://antinomyhq/forge/README.md`
+    pub fn reference(&self) -> String {
+        mcp_resource_reference(&self.server, &self.uri)
+    }
+}
+
+/// Reference attaching the resource of the server to a message
+pub fn mcp_resource_reference(server: &str, uri: &str) -> String {
+    format!("mcp:{server}/{uri}")
+}
+
+/// Contents of a subscribed resource, read again after its server notified
+/// that it changed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct McpResourceUpdate {
+    pub server: String,
+    pub uri: String,
+    pub contents: Vec<AttachmentContent>,
+}
+
+impl McpResourceUpdate {
+    pub fn reference(&self) -> String {
+        mcp_resource_reference(&self.server, &self.uri)
+    }
+}
+
+/// Server and URI of the resource an attachment refers to as
+/// `mcp:server/uri`, `None` for the attachments of files
+pub fn parse_mcp_resource_reference(path: &str) -> Option<(&str, &str)> {
+    let (server, uri) = path.strip_prefix("mcp:")?.split_once('/')?;
+    (!server.is_empty() && !uri.is_empty()).then_some((server, uri))
+}
+
+impl McpPrompt {
+    /// Slash command running the prompt
+    pub fn command(&self) -> String {
+        format!("/{}", self.name)
+    }
+
+    /// Usage of the command, e.g. `/github:review <pr> [<focus>]`
+    pub fn usage(&self) -> String {
+        let arguments = self
+            .arguments
+            .iter()
+            .map(|argument| match argument.required {
+                true => format!(" <{}>", argument.name),
+                false => format!(" [<{}>]", argument.name),
+            });
+        std::iter::once(self.command()).chain(arguments).collect()
+    }
+
+    /// Values of the arguments given to the command, as `name=value` or in
+    /// the order the prompt declares them. The words left over go to the last
+    /// argument, so that it can hold a sentence.
+    pub fn parse_arguments(&self, words: &[&str]) -> Result<BTreeMap<String, String>> {
+        let mut values = BTreeMap::new();
+        let mut positional = Vec::new();
+        for word in words {
+            match word.split_once('=') {
+                Some((name, value)) if self.arguments.iter().any(|a| a.name == name) => {
+                    values.insert(name.to_string(), value.to_string());
+                }
+                _ => positional.push(*word),
+            }
+        }
+
+        let mut unset = self
+            .arguments
+            .iter()
+            .filter(|argument| !values.contains_key(&argument.name))
+            .peekable();
+        let mut words = positional.into_iter();
+        while let Some(argument) = unset.next() {
+            let value = match unset.peek() {
+                Some(_) => words.next().map(str::to_string),
+                None => {
+                    Some(words.by_ref().collect::<Vec<_>>().join(" ")).filter(|v| !v.is_empty())
+                }
+            };
+            match value {
+                Some(value) => {
+                    values.insert(argument.name.clone(), value);
+                }
+                None if argument.required => {
+                    return Err(Error::MissingMcpPromptArgument {
+                        argument: argument.name.clone(),
+                        usage: self.usage(),
+                    })
+                }
+                None => {}
+            }
+        }
+        Ok(values)
+    }
+}
+
 impl From<BTreeMap<String, McpServerConfig>> for McpConfig {
     fn from(mcp_servers: BTreeMap<String, McpServerConfig>) -> Self {
         Self { mcp_servers }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_is_mcp_tool_of() {
+        let fixture = ToolName::new("mcp_github_tool_create_issue");
+
+        let actual = [
+            is_mcp_tool_of(&fixture, "github"),
+            is_mcp_tool_of(&fixture, "git"),
+            is_mcp_tool_of(&ToolName::new("forge_tool_fs_read"), "github"),
+        ];
+
+        let expected = [true, false, false];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_http_transport_selection() {
+        let fixture = r#"{"mcpServers": {
+            "legacy": {"url": "https://legacy.example.com/sse"},
+            "remote": {"url": "https://mcp.example.com/mcp", "transport": "auto"}
+        }}"#;
+
+        let actual = serde_json::from_str::<McpConfig>(fixture).unwrap();
+
+        let expected = McpConfig::from(BTreeMap::from([
+            (
+                "legacy".to_string(),
+                McpServerConfig::new_sse("https://legacy.example.com/sse"),
+            ),
+            (
+                "remote".to_string(),
+                McpServerConfig::new_http("https://mcp.example.com/mcp"),
+            ),
+        ]));
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_tool_filter() {
+        let config = r#"{
+            "command": "npx",
+            "include_tools": ["browser_*", "read_file"],
+            "exclude_tools": ["browser_install"]
+        }"#;
+        let config = serde_json::from_str::<McpServerConfig>(config).unwrap();
+        let fixture = [
+            "browser_click",
+            "browser_install",
+            "read_file",
+            "write_file",
+        ];
+
+        let actual = fixture
+            .into_iter()
+            .filter(|tool| config.tool_filter().allows(tool))
+            .collect::<Vec<_>>();
+
+        let expected = vec!["browser_click", "read_file"];
+        assert_eq!(actual, expected);
+        assert!(McpToolFilter::default().allows("write_file"));
+    }
+
+    #[test]
+    fn test_tool_filter_invalid_glob() {
+        let fixture = McpConfig::from(BTreeMap::from([(
+            "browser".to_string(),
+            McpServerConfig::Stdio(McpStdioServer::default().tools(McpToolFilter {
+                include_tools: Some(vec!["browser_*".to_string()]),
+                exclude_tools: vec!["browser_[install".to_string()],
+            })),
+        )]));
+
+        let actual = fixture.validate().unwrap_err().to_string();
+
+        let expected =
+            "Invalid tool glob browser_[install of the MCP server browser: invalid range pattern";
+        assert_eq!(actual, expected);
+        assert!(McpConfig::default().validate().is_ok());
+    }
+
+    fn prompt() -> McpPrompt {
+        let argument = |name: &str, required| McpPromptArgument {
+            name: name.to_string(),
+            description: None,
+            required,
+        };
+        McpPrompt {
+            name: mcp_prompt_name("github", "review"),
+            description: Some("Reviews a pull request".to_string()),
+            arguments: vec![argument("pr", true), argument("focus", false)],
+        }
+    }
+
+    #[test]
+    fn test_prompt_arguments() {
+        let fixture = prompt();
+
+        let actual = [
+            fixture
+                .parse_arguments(&["42", "error", "handling"])
+                .unwrap(),
+            fixture.parse_arguments(&["focus=tests", "42"]).unwrap(),
+            fixture.parse_arguments(&["42"]).unwrap(),
+        ];
+
+        let values = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect::<BTreeMap<_, _>>()
+        };
+        let expected = [
+            values(&[("pr", "42"), ("focus", "error handling")]),
+            values(&[("pr", "42"), ("focus", "tests")]),
+            values(&[("pr", "42")]),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_prompt_missing_argument() {
+        let fixture = prompt();
+
+        let actual = fixture
+            .parse_arguments(&["focus=tests"])
+            .unwrap_err()
+            .to_string();
+
+        let expected = "Missing argument pr, usage: /github:review <pr> [<focus>]";
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_mcp_resource_reference() {
+        let fixture = [
+            "mcp:github/repo://antinomyhq/forge/README.md",
+            "mcp:fs/file:///var/log/app.log",
+            "mcp:github",
+            "mcp:/file:///etc/hosts",
+            "src/main.rs",
+        ];
+
+        let actual = fixture
+            .into_iter()
+            .map(parse_mcp_resource_reference)
+            .collect::<Vec<_>>();
+
+        let expected = vec![
+            Some(("github", "repo://antinomyhq/forge/README.md")),
+            Some(("fs", "file:///var/log/app.log")),
+            None,
+            None,
+            None,
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_sampling_context() {
+        let fixture = McpSamplingRequest {
+            server: "github".to_string(),
+            messages: vec![
+                McpSamplingMessage { role: Role::User, text: "Summarize #42".to_string() },
+                McpSamplingMessage { role: Role::Assistant, text: "Which repo?".to_string() },
+                McpSamplingMessage { role: Role::User, text: "forge".to_string() },
+            ],
+            system_prompt: Some("You summarize issues".to_string()),
+            max_tokens: 200,
+            temperature: Some(0.2),
+        };
+        let model = ModelId::new("anthropic/claude-3.5-sonnet");
+
+        let actual = fixture.context(&model);
+
+        let expected = Context::default()
+            .max_tokens(200usize)
+            .temperature(Temperature::new(0.2).unwrap())
+            .add_message(ContextMessage::system("You summarize issues"))
+            .add_message(ContextMessage::user("Summarize #42", Some(model.clone())))
+            .add_message(ContextMessage::assistant("Which repo?", None, None))
+            .add_message(ContextMessage::user("forge", Some(model)));
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_sampling_transcript() {
+        let fixture = McpSamplingRequest {
+            server: "github".to_string(),
+            messages: vec![
+                McpSamplingMessage { role: Role::User, text: "Summarize #42".to_string() },
+                McpSamplingMessage { role: Role::Assistant, text: "Which repo?".to_string() },
+            ],
+            system_prompt: Some("You summarize issues".to_string()),
+            max_tokens: 200,
+            temperature: None,
+        };
+
+        let actual = fixture.transcript();
+
+        let expected =
+            "System: You summarize issues\n\nUser: Summarize #42\n\nAssistant: Which repo?";
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,353 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+use serde_json::{Map, Value};
+use strum_macros::Display;
+
+use crate::{Environment, Error, McpConfig, McpServerConfig, Result};
+
+/// Options of a server that are converted, the others being reported
+const SUPPORTED_OPTIONS: [&str; 6] = ["command", "args", "env", "url", "type", "disabled"];
+
+/// Tool whose MCP configuration can be imported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+pub enum McpSource {
+    #[strum(to_string = "Claude Desktop")]
+    ClaudeDesktop,
+    Cursor,
+    #[strum(to_string = "VS Code")]
+    VsCode,
+}
+
+impl McpSource {
+    /// Files the tool reads its MCP servers from, the ones of the project
+    /// first
+    pub fn config_paths(&self, env: &Environment) -> Vec<PathBuf> {
+        let home = env.home.clone().unwrap_or_default();
+        let config_dir = match env.os.as_str() {
+            "macos" => home.join("Library/Application Support"),
+            "windows" => home.join("AppData/Roaming"),
+            _ => home.join(".config"),
+        };
+        match self {
+            McpSource::ClaudeDesktop => {
+                vec![config_dir.join("Claude/claude_desktop_config.json")]
+            }
+            McpSource::Cursor => vec![
+                env.cwd.join(".cursor/mcp.json"),
+                home.join(".cursor/mcp.json"),
+            ],
+            McpSource::VsCode => vec![
+                env.cwd.join(".vscode/mcp.json"),
+                config_dir.join("Code/User/mcp.json"),
+            ],
+        }
+    }
+
+    /// Key of the object listing the servers in the configuration
+    fn servers_key(&self) -> &'static str {
+        match self {
+            McpSource::ClaudeDesktop | McpSource::Cursor => "mcpServers",
+            McpSource::VsCode => "servers",
+        }
+    }
+}
+
+/// Servers converted from the MCP configuration of another tool
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct McpImport {
+    pub config: McpConfig,
+    /// Servers and options that couldn't be converted and were left out
+    pub unsupported: Vec<String>,
+}
+
+impl McpImport {
+    /// Converts the servers of the JSON configuration of the tool. Servers
+    /// run with a command become stdio servers, the ones with a URL SSE
+    /// servers. The `${...}` variables are replaced with the values
+    /// `resolve` returns for their names, the others are reported.
+    pub fn parse(
+        source: McpSource,
+        content: &str,
+        resolve: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self> {
+        let invalid = |reason: String| Error::InvalidMcpImport(source.to_string(), reason);
+        let value =
+            serde_json::from_str::<Value>(content).map_err(|error| invalid(error.to_string()))?;
+        let key = source.servers_key();
+        let servers = value
+            .get(key)
+            .and_then(Value::as_object)
+            .ok_or_else(|| invalid(format!("no {key} object")))?;
+
+        let mut import = Self::default();
+        for (name, server) in servers {
+            let Some(server) = server.as_object() else {
+                import
+                    .unsupported
+                    .push(format!("{name}: not an object, skipped"));
+                continue;
+            };
+            if server.get("disabled").and_then(Value::as_bool) == Some(true) {
+                import
+                    .unsupported
+                    .push(format!("{name}: disabled, skipped"));
+                continue;
+            }
+
+            let mut unresolved = BTreeSet::new();
+            let server = server
+                .iter()
+                .map(|(key, value)| (key.clone(), expand_value(value, &resolve, &mut unresolved)))
+                .collect::<Map<_, _>>();
+            let Some(config) = convert(&server) else {
+                import
+                    .unsupported
+                    .push(format!("{name}: no command or url, skipped"));
+                continue;
+            };
+            import.unsupported.extend(
+                server
+                    .keys()
+                    .filter(|key| !SUPPORTED_OPTIONS.contains(&key.as_str()))
+                    .map(|key| format!("{name}: {key} is not supported")),
+            );
+            if unresolved
+                .iter()
+                .any(|variable| variable.starts_with("input:"))
+            {
+                import
+                    .unsupported
+                    .push(format!("{name}: ${{input:…}} variables are not supported"));
+            }
+            import.unsupported.extend(
+                unresolved
+                    .iter()
+                    .filter(|variable| !variable.starts_with("input:"))
+                    .map(|variable| format!("{name}: ${{{variable}}} can't be resolved")),
+            );
+            import.config.mcp_servers.insert(name.clone(), config);
+        }
+        Ok(import)
+    }
+}
+
+/// Value of a `${...}` variable of the configurations of the editors: the
+/// workspace folder, the home directory or an environment variable read with
+/// `var`
+pub fn resolve_variable(
+    name: &str,
+    env: &Environment,
+    var: impl Fn(&str) -> Option<String>,
+) -> Option<String> {
+    match name {
+        "workspaceFolder" => Some(env.cwd.display().to_string()),
+        "userHome" => env.home.as_ref().map(|home| home.display().to_string()),
+        _ => var(name.strip_prefix("env:")?),
+    }
+}
+
+/// The value with the variables of its strings replaced, the names of the
+/// ones that couldn't be resolved being added to `unresolved`
+fn expand_value(
+    value: &Value,
+    resolve: &impl Fn(&str) -> Option<String>,
+    unresolved: &mut BTreeSet<String>,
+) -> Value {
+    match value {
+        Value::String(text) => Value::String(expand(text, resolve, unresolved)),
+        Value::Array(values) => Value::Array(
+            values
+                .iter()
+                .map(|value| expand_value(value, resolve, unresolved))
+                .collect(),
+        ),
+        Value::Object(values) => Value::Object(
+            values
+                .iter()
+                .map(|(key, value)| (key.clone(), expand_value(value, resolve, unresolved)))
+                .collect(),
+        ),
+        value => value.clone(),
+    }
+}
+
+/// Replaces the `${name}` variables of the text, leaving the ones that can't
+/// be resolved as they are
+fn expand(
+    text: &str,
+    resolve: &impl Fn(&str) -> Option<String>,
+    unresolved: &mut BTreeSet<String>,
+) -> String {
+    let mut expanded = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        expanded.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+            expanded.push_str(&rest[start..]);
+            return expanded;
+        };
+        let name = &rest[start + 2..end];
+        match resolve(name) {
+            Some(value) => expanded.push_str(&value),
+            None => {
+                unresolved.insert(name.to_string());
+                expanded.push_str(&rest[start..=end]);
+            }
+        }
+        rest = &rest[end + 1..];
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+fn convert(server: &Map<String, Value>) -> Option<McpServerConfig> {
+    if let Some(command) = server.get("command").and_then(Value::as_str) {
+        let args = server
+            .get("args")
+            .and_then(Value::as_array)
+            .map(|args| {
+                args.iter()
+                    .filter_map(|arg| arg.as_str().map(ToString::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let env = server.get("env").and_then(Value::as_object).map(|env| {
+            env.iter()
+                .filter_map(|(key, value)| Some((key.clone(), value.as_str()?.to_string())))
+                .collect::<BTreeMap<_, _>>()
+        });
+        return Some(McpServerConfig::new_stdio(command, args, env));
+    }
+
+    let url = server.get("url").and_then(Value::as_str)?;
+    match server.get("type").and_then(Value::as_str) {
+        Some("sse") => Some(McpServerConfig::new_sse(url)),
+        _ => Some(McpServerConfig::new_http(url)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_claude_desktop() {
+        let fixture = r#"{
+            "mcpServers": {
+                "filesystem": {
+                    "command": "npx",
+                    "args": ["-y", "@modelcontextprotocol/server-filesystem", "/tmp"],
+                    "env": {"DEBUG": "1"}
+                },
+                "old": {"command": "old-server", "disabled": true},
+                "remote": {"url": "https://mcp.example.com/sse", "headers": {"X-Key": "k"}}
+            }
+        }"#;
+
+        let actual = McpImport::parse(McpSource::ClaudeDesktop, fixture, |_| None).unwrap();
+
+        let expected = McpImport {
+            config: McpConfig {
+                mcp_servers: BTreeMap::from([
+                    (
+                        "filesystem".to_string(),
+                        McpServerConfig::new_stdio(
+                            "npx",
+                            vec![
+                                "-y".to_string(),
+                                "@modelcontextprotocol/server-filesystem".to_string(),
+                                "/tmp".to_string(),
+                            ],
+                            Some(BTreeMap::from([("DEBUG".to_string(), "1".to_string())])),
+                        ),
+                    ),
+                    (
+                        "remote".to_string(),
+                        McpServerConfig::new_http("https://mcp.example.com/sse"),
+                    ),
+                ]),
+            },
+            unsupported: vec![
+                "old: disabled, skipped".to_string(),
+                "remote: headers is not supported".to_string(),
+            ],
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_vscode() {
+        let fixture = r#"{
+            "inputs": [{"type": "promptString", "id": "token"}],
+            "servers": {
+                "github": {
+                    "type": "http",
+                    "url": "https://api.githubcopilot.com/mcp/",
+                    "env": {"TOKEN": "${input:token}"}
+                }
+            }
+        }"#;
+
+        let actual = McpImport::parse(McpSource::VsCode, fixture, |_| None).unwrap();
+
+        let expected = vec!["github: ${input:…} variables are not supported".to_string()];
+        assert_eq!(actual.unsupported, expected);
+        assert_eq!(
+            actual.config.mcp_servers.get("github"),
+            Some(&McpServerConfig::new_http(
+                "https://api.githubcopilot.com/mcp/"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_variables() {
+        let fixture = r#"{
+            "servers": {
+                "files": {
+                    "command": "mcp-files",
+                    "args": ["${workspaceFolder}/docs", "${unknown}"],
+                    "env": {"TOKEN": "${env:TOKEN}"}
+                }
+            }
+        }"#;
+        let resolve = |name: &str| match name {
+            "workspaceFolder" => Some("/project".to_string()),
+            "env:TOKEN" => Some("secret".to_string()),
+            _ => None,
+        };
+
+        let actual = McpImport::parse(McpSource::VsCode, fixture, resolve).unwrap();
+
+        let expected = McpImport {
+            config: McpConfig {
+                mcp_servers: BTreeMap::from([(
+                    "files".to_string(),
+                    McpServerConfig::new_stdio(
+                        "mcp-files",
+                        vec!["/project/docs".to_string(), "${unknown}".to_string()],
+                        Some(BTreeMap::from([(
+                            "TOKEN".to_string(),
+                            "secret".to_string(),
+                        )])),
+                    ),
+                )]),
+            },
+            unsupported: vec!["files: ${unknown} can't be resolved".to_string()],
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        let actual = McpImport::parse(McpSource::Cursor, r#"{"servers": {}}"#, |_| None)
+            .unwrap_err()
+            .to_string();
+
+        let expected = "Invalid Cursor MCP configuration: no mcpServers object";
+        assert_eq!(actual, expected);
+    }
+}
@@ -6,7 +6,7 @@ use strum_macros::EnumString;
 use super::{ToolCall, ToolCallFull};
 use crate::reasoning::{Reasoning, ReasoningFull};
 
-#[derive(Default, Clone, Debug, Serialize, PartialEq)]
+#[derive(Default, Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct Usage {
     pub prompt_tokens: usize,
     pub completion_tokens: usize,
@@ -19,7 +19,7 @@ pub struct Usage {
 /// Represents a message that was received from the LLM provider
 /// NOTE: Tool call messages are part of the larger Response object and not part
 /// of the message.
-#[derive(Default, Clone, Debug, Setters, PartialEq)]
+#[derive(Default, Clone, Debug, Setters, PartialEq, Serialize, Deserialize)]
 #[setters(into, strip_option)]
 pub struct ChatCompletionMessage {
     pub content: Option<Content>,
@@ -28,10 +28,14 @@ pub struct ChatCompletionMessage {
     pub tool_calls: Vec<ToolCall>,
     pub finish_reason: Option<FinishReason>,
     pub usage: Option<Usage>,
+    /// Seconds the provider has sent nothing for, set on the notice a response
+    /// yields once it stalls rather than on a chunk of the response
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stalled: Option<u64>,
 }
 
 /// Represents partial or full content of a message
-#[derive(Clone, Debug, PartialEq, Eq, From)]
+#[derive(Clone, Debug, PartialEq, Eq, From, Serialize, Deserialize)]
 pub enum Content {
     Part(ContentPart),
     Full(ContentFull),
@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+use merge::Merge;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Duration of a turn after which its completion is notified when not
+/// configured
+const DEFAULT_MIN_TURN_SECS: u64 = 30;
+
+/// Desktop notifications sent while the agents work, so that the terminal can
+/// be left in the background during long tasks
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Merge, JsonSchema)]
+pub struct Notifications {
+    /// Minimum duration in seconds of a turn for its completion to be
+    /// notified. Defaults to 30.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub min_turn_secs: Option<u64>,
+    /// Whether to notify when an agent waits for an answer or an approval.
+    /// Defaults to true.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub on_input: Option<bool>,
+    /// Whether to notify when an autonomous agent spent its budget. Defaults
+    /// to true.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub on_budget: Option<bool>,
+}
+
+impl Notifications {
+    /// Whether the completion of a turn that took `elapsed` is notified
+    pub fn is_turn_notified(&self, elapsed: Duration) -> bool {
+        elapsed >= Duration::from_secs(self.min_turn_secs.unwrap_or(DEFAULT_MIN_TURN_SECS))
+    }
+
+    pub fn is_input_notified(&self) -> bool {
+        self.on_input.unwrap_or(true)
+    }
+
+    pub fn is_budget_notified(&self) -> bool {
+        self.on_budget.unwrap_or(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_defaults() {
+        let fixture = Notifications::default();
+
+        let actual = [
+            fixture.is_turn_notified(Duration::from_secs(10)),
+            fixture.is_turn_notified(Duration::from_secs(30)),
+            fixture.is_input_notified(),
+            fixture.is_budget_notified(),
+        ];
+
+        let expected = [false, true, true, true];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_configured() {
+        let fixture: Notifications =
+            serde_yml::from_str("min_turn_secs: 120\non_input: false").unwrap();
+
+        let actual = [
+            fixture.is_turn_notified(Duration::from_secs(60)),
+            fixture.is_input_notified(),
+            fixture.is_budget_notified(),
+        ];
+
+        let expected = [false, false, true];
+        assert_eq!(actual, expected);
+    }
+}
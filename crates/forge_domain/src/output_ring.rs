@@ -0,0 +1,110 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+/// Output of a running tool waiting to be shown, shared between the tool that
+/// produces it and the task streaming it to the UI. Only the last lines are
+/// kept, so that a slow UI neither holds a command back nor grows the memory.
+#[derive(Debug, Clone)]
+pub struct OutputRing {
+    state: Arc<Mutex<RingState>>,
+}
+
+#[derive(Debug)]
+struct RingState {
+    lines: VecDeque<String>,
+    /// Output after the last line break
+    partial: String,
+    capacity: usize,
+    /// Lines dropped since the last drain
+    skipped: usize,
+}
+
+impl OutputRing {
+    /// Creates a ring keeping at most `capacity` lines
+    pub fn new(capacity: usize) -> Self {
+        let state = RingState {
+            lines: VecDeque::new(),
+            partial: String::new(),
+            capacity: capacity.max(1),
+            skipped: 0,
+        };
+        Self { state: Arc::new(Mutex::new(state)) }
+    }
+
+    /// Appends output, its complete lines becoming available to `drain`
+    pub fn push(&self, chunk: &str) {
+        let mut state = self.lock();
+        state.partial.push_str(chunk);
+        while let Some(end) = state.partial.find('\n') {
+            let line = state.partial.drain(..=end).collect::<String>();
+            if state.lines.len() == state.capacity {
+                state.lines.pop_front();
+                state.skipped += 1;
+            }
+            state.lines.push_back(line);
+        }
+    }
+
+    /// Takes the complete lines pushed since the last drain
+    pub fn drain(&self) -> Option<String> {
+        self.take(false)
+    }
+
+    /// Takes the remaining output, including an unterminated last line
+    pub fn flush(&self) -> Option<String> {
+        self.take(true)
+    }
+
+    fn take(&self, partial: bool) -> Option<String> {
+        let mut state = self.lock();
+        let mut output = String::new();
+        if state.skipped > 0 {
+            output.push_str(&format!("… {} line(s) skipped\n", state.skipped));
+            state.skipped = 0;
+        }
+        output.extend(state.lines.drain(..));
+        if partial {
+            output.push_str(&std::mem::take(&mut state.partial));
+        }
+        (!output.is_empty()).then_some(output)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, RingState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_drain_keeps_partial_line() {
+        let fixture = OutputRing::new(10);
+        fixture.push("Compiling forge_domain\nCompil");
+
+        let actual = (fixture.drain(), fixture.drain(), fixture.flush());
+
+        let expected = (
+            Some("Compiling forge_domain\n".to_string()),
+            None,
+            Some("Compil".to_string()),
+        );
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_push_drops_oldest_lines() {
+        let fixture = OutputRing::new(2);
+        for line in ["one\n", "two\n", "three\n", "four\n"] {
+            fixture.push(line);
+        }
+
+        let actual = fixture.drain();
+
+        let expected = Some("… 2 line(s) skipped\nthree\nfour\n".to_string());
+        assert_eq!(actual, expected);
+    }
+}
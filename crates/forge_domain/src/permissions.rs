@@ -0,0 +1,162 @@
+use std::path::{Path, PathBuf};
+
+use glob::{MatchOptions, Pattern};
+use merge::Merge;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// Restricts the paths an agent's tools may operate on. Relative globs are
+/// resolved against the current working directory, e.g. `docs/**`. A path
+/// is allowed when both the path and the one its symbolic links resolve to
+/// match the globs.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Merge, JsonSchema)]
+pub struct PathPermissions {
+    /// Globs of the paths that may be read or searched. All paths are
+    /// readable when not specified.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub read: Option<Vec<String>>,
+    /// Globs of the paths that may be created, patched, removed or restored,
+    /// and of the directories shell commands may run in. All paths are
+    /// writable when not specified. The directory isn't a boundary for the
+    /// commands, which can access any path: leave the shell tool out of the
+    /// agent's tools to enforce the globs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub write: Option<Vec<String>>,
+}
+
+impl PathPermissions {
+    pub fn can_read(&self, path: &Path, cwd: &Path) -> bool {
+        allows(self.read.as_deref(), path, cwd)
+    }
+
+    pub fn can_write(&self, path: &Path, cwd: &Path) -> bool {
+        allows(self.write.as_deref(), path, cwd)
+    }
+}
+
+fn allows(globs: Option<&[String]>, path: &Path, cwd: &Path) -> bool {
+    let Some(globs) = globs else {
+        return true;
+    };
+    let patterns = globs
+        .iter()
+        .filter_map(|glob| Pattern::new(glob).ok())
+        .collect::<Vec<_>>();
+
+    // A symbolic link among the allowed paths may point anywhere else
+    let real = match (real_path(path), real_path(cwd)) {
+        (Some(path), Some(cwd)) => matches(&patterns, &path, &cwd),
+        _ => true,
+    };
+    real && matches(&patterns, path, cwd)
+}
+
+fn matches(patterns: &[Pattern], path: &Path, cwd: &Path) -> bool {
+    let absolute = path.display().to_string();
+    let relative = path
+        .strip_prefix(cwd)
+        .ok()
+        .map(|path| path.display().to_string());
+    // A trailing separator lets `dir/**` match the directory itself
+    let candidates = [Some(absolute), relative]
+        .into_iter()
+        .flatten()
+        .flat_map(|path| [format!("{path}/"), path])
+        .collect::<Vec<_>>();
+
+    patterns.iter().any(|pattern| {
+        candidates
+            .iter()
+            .any(|candidate| pattern.matches_with(candidate, MATCH_OPTIONS))
+    })
+}
+
+/// The path with its symbolic links resolved. The components that don't
+/// exist yet, e.g. of a file being created, are appended to their closest
+/// existing ancestor once resolved.
+fn real_path(path: &Path) -> Option<PathBuf> {
+    let mut missing = vec![];
+    let mut existing = path;
+    loop {
+        if let Ok(real) = existing.canonicalize() {
+            return Some(
+                missing
+                    .into_iter()
+                    .rev()
+                    .fold(real, |path, name| path.join(name)),
+            );
+        }
+        missing.push(existing.file_name()?);
+        existing = existing.parent()?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn fixture() -> PathPermissions {
+        PathPermissions {
+            read: None,
+            write: Some(vec!["docs/**".to_string(), "/tmp/*.md".to_string()]),
+        }
+    }
+
+    #[test]
+    fn test_unrestricted_access() {
+        let fixture = fixture();
+        let actual = fixture.can_read(Path::new("/project/src/main.rs"), Path::new("/project"));
+        assert!(actual);
+    }
+
+    #[test]
+    fn test_write_globs() {
+        let fixture = fixture();
+        let cwd = Path::new("/project");
+
+        let actual = [
+            "/project/docs/guide.md",
+            "/project/docs/api/index.md",
+            "/project/docs",
+            "/project/src/main.rs",
+            "/tmp/notes.md",
+            "/tmp/nested/notes.md",
+        ]
+        .map(|path| fixture.can_write(Path::new(path), cwd));
+
+        let expected = [true, true, true, false, true, false];
+        assert_eq!(actual, expected);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_globs_through_symlink() {
+        let workspace = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        let cwd = workspace.path().canonicalize().unwrap();
+        std::fs::create_dir(cwd.join("docs")).unwrap();
+        std::os::unix::fs::symlink(outside.path(), cwd.join("docs/link")).unwrap();
+        let fixture = PathPermissions { read: None, write: Some(vec!["docs/**".to_string()]) };
+
+        let actual = [
+            cwd.join("docs/guide.md"),
+            cwd.join("docs/new/guide.md"),
+            cwd.join("docs/link/secret.md"),
+            cwd.join("docs/link"),
+        ]
+        .map(|path| fixture.can_write(&path, &cwd));
+
+        let expected = [true, true, false, false];
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,78 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Number of characters of a pinned message shown when listing the pins
+const PREVIEW_CHARS: usize = 60;
+
+/// Context item the user pinned. Pins are part of the system prompt of every
+/// request, so compaction never removes or summarizes them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Pin {
+    /// A message, e.g. a key requirement of the task
+    Text { text: String },
+    /// A file whose current content is sent, e.g. a critical interface
+    File { path: String },
+}
+
+impl Pin {
+    /// Where the content of the pin comes from
+    pub fn source(&self) -> &str {
+        match self {
+            Pin::Text { .. } => "message",
+            Pin::File { path } => path,
+        }
+    }
+}
+
+impl fmt::Display for Pin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Pin::Text { text } => {
+                let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                if text.chars().count() > PREVIEW_CHARS {
+                    let preview = text.chars().take(PREVIEW_CHARS).collect::<String>();
+                    write!(f, "\"{}…\"", preview.trim_end())
+                } else {
+                    write!(f, "\"{text}\"")
+                }
+            }
+            Pin::File { path } => write!(f, "{path}"),
+        }
+    }
+}
+
+/// Content of a pin as rendered in the system prompt
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PinnedContent {
+    pub source: String,
+    pub content: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_pin_display() {
+        let fixture = [
+            Pin::Text {
+                text: "Keep the public API\n  backward compatible".to_string(),
+            },
+            Pin::Text { text: "a".repeat(80) },
+            Pin::File { path: "/project/src/api.rs".to_string() },
+        ];
+
+        let actual = fixture.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+        let expected = vec![
+            "\"Keep the public API backward compatible\"".to_string(),
+            format!("\"{}…\"", "a".repeat(60)),
+            "/project/src/api.rs".to_string(),
+        ];
+        assert_eq!(actual, expected);
+    }
+}
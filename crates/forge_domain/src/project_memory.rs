@@ -0,0 +1,189 @@
+use std::fmt::{Display, Formatter};
+
+/// Tag wrapping the learnings the agent distills from a conversation
+pub const PROJECT_MEMORY_TAG: &str = "project_memory";
+
+/// Durable learnings about a project, distilled from its conversations and
+/// loaded into the system prompt of the next ones. Stored as Markdown in
+/// `.forge/memory.md` so that it can be reviewed and edited by hand, only the
+/// items of its sections are kept.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProjectMemory {
+    /// Commands building, testing or running the project
+    pub commands: Vec<String>,
+    /// Conventions the code of the project follows
+    pub conventions: Vec<String>,
+    /// Pitfalls that were run into and how to avoid them
+    pub gotchas: Vec<String>,
+}
+
+impl ProjectMemory {
+    /// Parses the items of the `## Commands`, `## Conventions` and
+    /// `## Gotchas` sections. An indented line continues the item above it.
+    pub fn parse(markdown: &str) -> Self {
+        let mut memory = Self::default();
+        let mut section = None;
+        for line in markdown.lines() {
+            if let Some(heading) = line.strip_prefix("## ") {
+                let heading = heading.trim();
+                section = SECTIONS
+                    .iter()
+                    .position(|section| section.eq_ignore_ascii_case(heading));
+                continue;
+            }
+            let Some(items) =
+                section.and_then(|index| memory.sections_mut().into_iter().nth(index))
+            else {
+                continue;
+            };
+
+            let trimmed = line.trim_start();
+            if let Some(item) = trimmed
+                .strip_prefix("- ")
+                .or_else(|| trimmed.strip_prefix("* "))
+            {
+                items.push(item.trim().to_string());
+            } else if line.starts_with(char::is_whitespace) && !trimmed.is_empty() {
+                if let Some(last) = items.last_mut() {
+                    last.push(' ');
+                    last.push_str(trimmed.trim_end());
+                }
+            }
+        }
+        memory
+    }
+
+    /// Number of items of all sections
+    pub fn len(&self) -> usize {
+        self.sections().iter().map(|items| items.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty() && self.conventions.is_empty() && self.gotchas.is_empty()
+    }
+
+    /// Adds the items that aren't remembered yet, ignoring case and
+    /// surrounding spaces. Returns the items that were added.
+    pub fn merge(&mut self, other: Self) -> Self {
+        let mut added = Self::default();
+        let others = [other.commands, other.conventions, other.gotchas];
+        for ((items, added), others) in self
+            .sections_mut()
+            .into_iter()
+            .zip(added.sections_mut())
+            .zip(others)
+        {
+            for item in others {
+                let key = item.trim().to_lowercase();
+                if !key.is_empty() && !items.iter().any(|item| item.trim().to_lowercase() == key) {
+                    items.push(item.trim().to_string());
+                    added.push(item.trim().to_string());
+                }
+            }
+        }
+        added
+    }
+
+    fn sections(&self) -> [&Vec<String>; 3] {
+        [&self.commands, &self.conventions, &self.gotchas]
+    }
+
+    fn sections_mut(&mut self) -> [&mut Vec<String>; 3] {
+        [&mut self.commands, &mut self.conventions, &mut self.gotchas]
+    }
+}
+
+/// Headings of the sections, in the order of the fields
+const SECTIONS: [&str; 3] = ["Commands", "Conventions", "Gotchas"];
+
+impl Display for ProjectMemory {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "# Project memory")?;
+        for (heading, items) in SECTIONS.iter().zip(self.sections()) {
+            if items.is_empty() {
+                continue;
+            }
+            writeln!(f, "\n## {heading}\n")?;
+            for item in items {
+                writeln!(f, "- {item}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let fixture = [
+            "# Project memory",
+            "",
+            "## Commands",
+            "",
+            "- `cargo insta test --accept` updates the snapshots",
+            "* `just ci` runs the checks of the CI,",
+            "  formatting included",
+            "",
+            "## Notes",
+            "- Not a section of the memory",
+            "",
+            "## gotchas",
+            "- The tests of forge_inte call real providers",
+        ]
+        .join("\n");
+
+        let actual = ProjectMemory::parse(&fixture);
+
+        let expected = ProjectMemory {
+            commands: vec![
+                "`cargo insta test --accept` updates the snapshots".to_string(),
+                "`just ci` runs the checks of the CI, formatting included".to_string(),
+            ],
+            conventions: vec![],
+            gotchas: vec!["The tests of forge_inte call real providers".to_string()],
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut fixture = ProjectMemory {
+            commands: vec!["`cargo test` runs the tests".to_string()],
+            ..Default::default()
+        };
+        let learnings = ProjectMemory {
+            commands: vec![" `CARGO TEST` runs the tests".to_string()],
+            conventions: vec!["Tests use pretty_assertions".to_string()],
+            gotchas: vec![],
+        };
+
+        let added = fixture.merge(learnings);
+
+        let actual = (fixture.to_string(), added);
+        let expected = (
+            [
+                "# Project memory",
+                "",
+                "## Commands",
+                "",
+                "- `cargo test` runs the tests",
+                "",
+                "## Conventions",
+                "",
+                "- Tests use pretty_assertions",
+                "",
+            ]
+            .join("\n"),
+            ProjectMemory {
+                conventions: vec!["Tests use pretty_assertions".to_string()],
+                ..Default::default()
+            },
+        );
+        assert_eq!(actual, expected);
+    }
+}
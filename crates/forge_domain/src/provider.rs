@@ -123,6 +123,26 @@ impl Provider {
         }
     }
 
+    /// Short name of the provider its settings are keyed by, `custom` for the
+    /// ones at other URLs
+    pub fn id(&self) -> &'static str {
+        if self.is_forge() {
+            "forge"
+        } else if self.is_open_router() {
+            "open_router"
+        } else if self.is_requesty() {
+            "requesty"
+        } else if self.is_xai() {
+            "xai"
+        } else if self.is_open_ai() {
+            "openai"
+        } else if self.is_anthropic() {
+            "anthropic"
+        } else {
+            "custom"
+        }
+    }
+
     pub fn is_forge(&self) -> bool {
         match self {
             Provider::OpenAI { url, .. } => url.as_str().starts_with(Self::FORGE_URL),
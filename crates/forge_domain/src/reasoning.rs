@@ -14,7 +14,7 @@ pub struct ReasoningFull {
     pub signature: Option<String>,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Reasoning {
     Part(Vec<ReasoningPart>),
     Full(Vec<ReasoningFull>),
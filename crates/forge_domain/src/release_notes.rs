@@ -0,0 +1,226 @@
+use crate::CommitMessage;
+
+/// Tag the model wraps the release notes it drafts in
+pub const RELEASE_NOTES_TAG: &str = "release_notes";
+
+/// Title of the changelog created when the workspace has none
+const CHANGELOG_TITLE: &str = "# Changelog";
+
+/// Sections of the release notes, in order, with the commit types they gather
+const SECTIONS: [(&str, &[&str]); 9] = [
+    ("Features", &["feat"]),
+    ("Bug Fixes", &["fix"]),
+    ("Performance", &["perf"]),
+    ("Refactoring", &["refactor"]),
+    ("Documentation", &["docs"]),
+    ("Tests", &["test"]),
+    ("Build and CI", &["build", "ci"]),
+    ("Chores", &["chore", "style"]),
+    ("Reverts", &["revert"]),
+];
+
+/// A change of a release, read from the subject of its commit
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReleaseChange {
+    /// Abbreviated hash of the commit
+    pub hash: String,
+    /// Type of the conventional commit, `None` for other commits
+    pub kind: Option<String>,
+    pub scope: Option<String>,
+    pub breaking: bool,
+    pub summary: String,
+    /// Number of the pull request the commit was merged with, if mentioned
+    pub pull_request: Option<u64>,
+}
+
+impl ReleaseChange {
+    pub fn new(hash: &str, subject: &str) -> Self {
+        let pull_request = subject
+            .trim_end()
+            .strip_suffix(')')
+            .and_then(|subject| subject.rsplit_once("(#"))
+            .and_then(|(_, number)| number.parse().ok());
+        let hash = hash.chars().take(7).collect();
+
+        match CommitMessage::parse(subject) {
+            Ok(message) => Self {
+                hash,
+                kind: Some(message.kind),
+                scope: message.scope,
+                breaking: message.breaking,
+                summary: message.subject,
+                pull_request,
+            },
+            Err(_) => Self {
+                hash,
+                kind: None,
+                scope: None,
+                breaking: false,
+                summary: subject.trim().to_string(),
+                pull_request,
+            },
+        }
+    }
+
+    fn to_markdown(&self) -> String {
+        let mut entry = String::from("- ");
+        if let Some(scope) = &self.scope {
+            entry.push_str(&format!("**{scope}:** "));
+        }
+        entry.push_str(&self.summary);
+        if self.pull_request.is_none() {
+            entry.push_str(&format!(" ({})", self.hash));
+        }
+        entry
+    }
+}
+
+/// Changes of a release grouped into sections by type
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReleaseNotes {
+    pub sections: Vec<(String, Vec<ReleaseChange>)>,
+}
+
+impl ReleaseNotes {
+    /// Groups the changes by type, breaking changes first and the commits that
+    /// don't follow the conventional commits specification last. Changes are
+    /// kept in the given order within a section.
+    pub fn new(changes: Vec<ReleaseChange>) -> Self {
+        let (breaking, mut changes): (Vec<_>, Vec<_>) =
+            changes.into_iter().partition(|change| change.breaking);
+        let mut sections = vec![("Breaking Changes".to_string(), breaking)];
+        for (title, kinds) in SECTIONS {
+            let (section, rest) = changes.into_iter().partition(|change| {
+                change
+                    .kind
+                    .as_deref()
+                    .is_some_and(|kind| kinds.contains(&kind))
+            });
+            sections.push((title.to_string(), section));
+            changes = rest;
+        }
+        sections.push(("Other Changes".to_string(), changes));
+
+        sections.retain(|(_, changes)| !changes.is_empty());
+        Self { sections }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sections.is_empty()
+    }
+
+    /// Renders the changes as Markdown, a `###` heading per section
+    pub fn to_markdown(&self) -> String {
+        self.sections
+            .iter()
+            .map(|(title, changes)| {
+                let entries = changes
+                    .iter()
+                    .map(ReleaseChange::to_markdown)
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                format!("### {title}\n\n{entries}")
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+/// Adds the notes of a release above the previous ones, right after the title
+/// of the changelog. A changelog is started when `changelog` is empty.
+pub fn prepend_release_notes(changelog: &str, notes: &str) -> String {
+    let changelog = changelog.trim_start();
+    let notes = notes.trim();
+    let (title, rest) = match changelog.split_once('\n') {
+        Some((title, rest)) if title.starts_with("# ") => (title, rest.trim_start()),
+        None if changelog.starts_with("# ") => (changelog, ""),
+        _ => (CHANGELOG_TITLE, changelog),
+    };
+
+    if rest.is_empty() {
+        format!("{title}\n\n{notes}\n")
+    } else {
+        format!("{title}\n\n{notes}\n\n{rest}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn fixture() -> ReleaseNotes {
+        ReleaseNotes::new(vec![
+            ReleaseChange::new(
+                "1111111aaa",
+                "fix(cache): ignore the case of the keys (#42)",
+            ),
+            ReleaseChange::new("2222222bbb", "Bump the version"),
+            ReleaseChange::new("3333333ccc", "feat!: drop the v1 API"),
+            ReleaseChange::new("4444444ddd", "feat(cli): add /commit"),
+        ])
+    }
+
+    #[test]
+    fn test_release_change() {
+        let actual = ReleaseChange::new(
+            "1111111aaa",
+            "fix(cache): ignore the case of the keys (#42)",
+        );
+
+        let expected = ReleaseChange {
+            hash: "1111111".to_string(),
+            kind: Some("fix".to_string()),
+            scope: Some("cache".to_string()),
+            breaking: false,
+            summary: "ignore the case of the keys (#42)".to_string(),
+            pull_request: Some(42),
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_release_notes_to_markdown() {
+        let actual = fixture().to_markdown();
+
+        let expected = [
+            "### Breaking Changes",
+            "",
+            "- drop the v1 API (3333333)",
+            "",
+            "### Features",
+            "",
+            "- **cli:** add /commit (4444444)",
+            "",
+            "### Bug Fixes",
+            "",
+            "- **cache:** ignore the case of the keys (#42)",
+            "",
+            "### Other Changes",
+            "",
+            "- Bump the version (2222222)",
+        ]
+        .join("\n");
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_prepend_release_notes() {
+        let fixture = "# Changelog\n\n## 1.0.0\n\n- First release\n";
+
+        let actual = prepend_release_notes(fixture, "## 1.1.0\n\n- Second release\n");
+
+        let expected =
+            "# Changelog\n\n## 1.1.0\n\n- Second release\n\n## 1.0.0\n\n- First release\n";
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_prepend_release_notes_starts_a_changelog() {
+        let actual = prepend_release_notes("", "## 1.0.0");
+
+        let expected = "# Changelog\n\n## 1.0.0\n";
+        assert_eq!(actual, expected);
+    }
+}
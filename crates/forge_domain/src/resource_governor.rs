@@ -0,0 +1,111 @@
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Resource a tool mostly consumes while it runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceCategory {
+    /// Commands, builds and linters
+    Cpu,
+    /// Reads, writes and searches of the file system
+    Io,
+    /// Requests to remote hosts
+    Network,
+    /// Short runs of the tools of the project reporting on it, like the
+    /// linters and the version commands, which shouldn't wait for the builds
+    Probe,
+}
+
+/// Maximum number of tools of each category running at once across the
+/// conversations of the process
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ResourceLimits {
+    pub cpu: usize,
+    pub io: usize,
+    pub network: usize,
+    pub probe: usize,
+}
+
+impl Default for ResourceLimits {
+    fn default() -> Self {
+        Self { cpu: 2, io: 8, network: 4, probe: 4 }
+    }
+}
+
+/// Bounds the tools running at once in each resource category, so that
+/// parallel agents don't saturate the CPU, the disk or the network. Clones
+/// share the same limits.
+#[derive(Debug, Clone)]
+pub struct ResourceGovernor {
+    cpu: Arc<Semaphore>,
+    io: Arc<Semaphore>,
+    network: Arc<Semaphore>,
+    probe: Arc<Semaphore>,
+}
+
+impl ResourceGovernor {
+    pub fn new(limits: &ResourceLimits) -> Self {
+        // A limit of zero would block the tools forever
+        let semaphore = |limit: usize| Arc::new(Semaphore::new(limit.max(1)));
+        Self {
+            cpu: semaphore(limits.cpu),
+            io: semaphore(limits.io),
+            network: semaphore(limits.network),
+            probe: semaphore(limits.probe),
+        }
+    }
+
+    /// Waits until a tool of the category may run. The resources are released
+    /// when the permit is dropped.
+    pub async fn acquire(&self, category: ResourceCategory) -> OwnedSemaphorePermit {
+        let semaphore = match category {
+            ResourceCategory::Cpu => &self.cpu,
+            ResourceCategory::Io => &self.io,
+            ResourceCategory::Network => &self.network,
+            ResourceCategory::Probe => &self.probe,
+        };
+        semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("the semaphores of the governor are never closed")
+    }
+}
+
+impl Default for ResourceGovernor {
+    fn default() -> Self {
+        Self::new(&ResourceLimits::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_acquire() {
+        let fixture =
+            ResourceGovernor::new(&ResourceLimits { cpu: 1, io: 0, network: 2, probe: 3 });
+
+        let cpu = fixture.acquire(ResourceCategory::Cpu).await;
+        let _io = fixture.acquire(ResourceCategory::Io).await;
+        let _network = fixture.acquire(ResourceCategory::Network).await;
+        let _probe = fixture.acquire(ResourceCategory::Probe).await;
+
+        let actual = (
+            fixture.cpu.available_permits(),
+            fixture.io.available_permits(),
+            fixture.network.available_permits(),
+            fixture.probe.available_permits(),
+        );
+        let expected = (0, 0, 1, 2);
+        assert_eq!(actual, expected);
+
+        drop(cpu);
+        assert_eq!(fixture.cpu.available_permits(), 1);
+    }
+}
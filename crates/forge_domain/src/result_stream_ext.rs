@@ -32,12 +32,15 @@ impl ResultStreamExt<anyhow::Error> for crate::BoxStream<ChatCompletionMessage,
         let mut messages = Vec::new();
         let mut usage: Usage = Default::default();
         let mut content = String::new();
-        let mut xml_tool_calls = None;
         let mut tool_interrupted = false;
 
         while let Some(message) = self.next().await {
             let message =
                 anyhow::Ok(message?).with_context(|| "Failed to process message stream")?;
+            // Stall notices aren't part of the response
+            if message.stalled.is_some() {
+                continue;
+            }
             messages.push(message.clone());
 
             // Process usage information
@@ -47,22 +50,12 @@ impl ResultStreamExt<anyhow::Error> for crate::BoxStream<ChatCompletionMessage,
             if let Some(content_part) = message.content.as_ref() {
                 content.push_str(content_part.as_str());
 
-                // Check for XML tool calls in the content, but only interrupt if flag is set
-                if should_interrupt_for_xml {
-                    // Use match instead of ? to avoid propagating errors
-                    if let Some(tool_call) = ToolCallFull::try_from_xml(&content)
-                        .ok()
-                        .into_iter()
-                        .flatten()
-                        .next()
-                    {
-                        xml_tool_calls = Some(tool_call);
-                        tool_interrupted = true;
-
-                        // Break the loop since we found an XML tool call and interruption is
-                        // enabled
-                        break;
-                    }
+                // Check for XML tool calls in the content, but only interrupt if flag is set.
+                // Several tool calls can follow each other, so the stream is only interrupted
+                // once the model writes something else after them.
+                if should_interrupt_for_xml && is_past_xml_tool_calls(&content) {
+                    tool_interrupted = true;
+                    break;
                 }
             }
         }
@@ -92,12 +85,19 @@ impl ResultStreamExt<anyhow::Error> for crate::BoxStream<ChatCompletionMessage,
                 content.push('\n');
                 content.push_str("<forge_feedback>");
                 content.push_str(
-                    "Response interrupted by tool result. Use only one tool at the end of the message",
+                    "Response interrupted by tool result. Use tools only at the end of the message",
                 );
                 content.push_str("</forge_feedback>");
             }
         }
 
+        // Parse the tool calls the model wrote in the content, in order
+        let xml_tool_calls = if should_interrupt_for_xml {
+            ToolCallFull::try_from_text(&content)
+        } else {
+            Vec::new()
+        };
+
         // Extract all tool calls in a fully declarative way with combined sources
         // Start with complete tool calls (for non-streaming mode)
         let initial_tool_calls: Vec<ToolCallFull> = messages
@@ -114,11 +114,9 @@ impl ResultStreamExt<anyhow::Error> for crate::BoxStream<ChatCompletionMessage,
             .collect();
 
         // Process partial tool calls
-        // Convert parse failures to retryable errors so they can be retried by asking
-        // LLM to try again
-        let partial_tool_calls = ToolCallFull::try_from_parts(&tool_call_parts)
-            .with_context(|| format!("Failed to parse tool call: {tool_call_parts:?}"))
-            .map_err(crate::Error::Retryable)?;
+        // Malformed arguments are returned as is so that the orchestrator can ask the
+        // LLM to fix the tool call
+        let partial_tool_calls = ToolCallFull::try_from_parts(&tool_call_parts)?;
 
         // Combine all sources of tool calls
         let tool_calls: Vec<ToolCallFull> = initial_tool_calls
@@ -155,6 +153,20 @@ impl ResultStreamExt<anyhow::Error> for crate::BoxStream<ChatCompletionMessage,
     }
 }
 
+/// Checks whether the content continues past its last complete XML tool call
+/// with something other than another tool call, e.g. a hallucinated tool
+/// result.
+fn is_past_xml_tool_calls(content: &str) -> bool {
+    const OPENING_TAG: &str = "<forge_tool_call>";
+    const CLOSING_TAG: &str = "</forge_tool_call>";
+
+    let Some((i, _)) = content.rmatch_indices(CLOSING_TAG).next() else {
+        return false;
+    };
+    let rest = content[i + CLOSING_TAG.len()..].trim_start();
+    !rest.is_empty() && !rest.starts_with(OPENING_TAG) && !OPENING_TAG.starts_with(rest)
+}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
@@ -246,7 +258,7 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_into_full_with_tool_call_parse_failure_creates_retryable_error() {
+    async fn test_into_full_with_tool_call_parse_failure_creates_malformed_tool_call_error() {
         use crate::{Error, ToolCallId, ToolCallPart, ToolName};
 
         // Fixture: Create a stream with invalid tool call JSON
@@ -266,12 +278,57 @@ mod tests {
         // Actual: Convert stream to full message
         let actual = result_stream.into_full(false).await;
 
-        // Expected: Should return a retryable error
+        // Expected: Should return a malformed tool call error
         assert!(actual.is_err());
         let error = actual.unwrap_err();
         let domain_error = error.downcast_ref::<Error>();
         assert!(domain_error.is_some());
-        assert!(matches!(domain_error.unwrap(), Error::Retryable(_)));
+        assert!(matches!(
+            domain_error.unwrap(),
+            Error::MalformedToolCall(call) if call.arguments == "invalid json {"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_into_full_with_multiple_xml_tool_calls() {
+        // Fixture: Two XML tool calls followed by a hallucinated tool result
+        let parts = [
+            "Reading both files.\n<forge_tool_call>\n",
+            r#"{"name": "forge_tool_fs_read", "arguments": {"path": "/a.rs"}}"#,
+            "\n</forge_tool_call>\n<forge_tool_",
+            "call>\n",
+            r#"{"name": "forge_tool_fs_read", "arguments": {"path": "/b.rs"}}"#,
+            "\n</forge_tool_call>\n",
+            "<forge_tool_result>fn main() {}</forge_tool_result>",
+            "This is never read",
+        ];
+        let messages = parts
+            .into_iter()
+            .map(|part| Ok(ChatCompletionMessage::default().content(Content::part(part))))
+            .collect::<Vec<_>>();
+
+        let result_stream: BoxStream<ChatCompletionMessage, anyhow::Error> =
+            Box::pin(tokio_stream::iter(messages));
+
+        // Actual: Convert stream to full message
+        let actual = result_stream.into_full(true).await.unwrap();
+
+        // Expected: Both calls in order and the content truncated after the last one
+        let arguments = actual
+            .tool_calls
+            .iter()
+            .map(|call| call.arguments.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            arguments,
+            vec![
+                serde_json::json!({"path": "/a.rs"}),
+                serde_json::json!({"path": "/b.rs"}),
+            ]
+        );
+        assert!(actual.content.starts_with("Reading both files."));
+        assert!(!actual.content.contains("forge_tool_result"));
+        assert!(actual.content.ends_with("</forge_feedback>"));
     }
 
     #[tokio::test]
@@ -0,0 +1,390 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{extract_tag_content, Error, Result};
+
+/// Tag the reviewer wraps its comments in
+pub const REVIEW_COMMENTS_TAG: &str = "review_comments";
+
+/// Changes made to a file by a unified diff
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileDiff {
+    /// Path of the file after the change
+    pub path: String,
+    pub hunks: Vec<DiffHunk>,
+}
+
+/// Lines changed together in a file
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffHunk {
+    /// The `@@ -a,b +c,d @@` line introducing the hunk
+    pub header: String,
+    /// Number of the first line of the hunk in the new version of the file
+    pub start_line: u64,
+    /// Lines of the hunk, each starting with ` `, `+` or `-`
+    pub lines: Vec<String>,
+}
+
+impl FileDiff {
+    /// Splits a unified diff, as printed by `git diff`, into the hunks of each
+    /// file. Removed and binary files are left out since there is nothing to
+    /// comment on.
+    pub fn parse_all(diff: &str) -> Vec<FileDiff> {
+        let mut files: Vec<FileDiff> = Vec::new();
+        // Lines left in the current hunk, in the old and the new version
+        let mut remaining = (0, 0);
+
+        for line in diff.lines() {
+            if remaining.0 > 0 || remaining.1 > 0 {
+                match line.chars().next() {
+                    Some('-') => remaining.0 -= 1,
+                    Some('+') => remaining.1 -= 1,
+                    Some('\\') => {}
+                    _ => remaining = (remaining.0.saturating_sub(1), remaining.1.saturating_sub(1)),
+                }
+                if let Some(hunk) = files.last_mut().and_then(|file| file.hunks.last_mut()) {
+                    hunk.lines.push(line.to_string());
+                }
+            } else if line.starts_with("diff --git ") {
+                files.push(FileDiff { path: String::new(), hunks: Vec::new() });
+            } else if let Some(path) = line.strip_prefix("+++ ") {
+                if let Some(file) = files.last_mut() {
+                    file.path = path.strip_prefix("b/").unwrap_or(path).to_string();
+                }
+            } else if let Some((old, new)) = parse_hunk_header(line) {
+                if let Some(file) = files.last_mut() {
+                    file.hunks.push(DiffHunk {
+                        header: line.to_string(),
+                        start_line: new.0,
+                        lines: Vec::new(),
+                    });
+                    remaining = (old.1, new.1);
+                }
+            }
+        }
+
+        files.retain(|file| file.path != "/dev/null" && !file.hunks.is_empty());
+        files
+    }
+
+    /// Whether `line` of the new version of the file is part of the diff, and
+    /// can therefore be commented on in a pull request
+    pub fn contains(&self, line: u64) -> bool {
+        self.hunks.iter().any(|hunk| hunk.contains(line))
+    }
+}
+
+impl DiffHunk {
+    /// Number of lines of the hunk in the new version of the file
+    fn new_lines(&self) -> u64 {
+        self.lines
+            .iter()
+            .filter(|line| !line.starts_with('-') && !line.starts_with('\\'))
+            .count() as u64
+    }
+
+    pub fn contains(&self, line: u64) -> bool {
+        (self.start_line..self.start_line + self.new_lines()).contains(&line)
+    }
+
+    /// The hunk with each line prefixed by its number in the new version of
+    /// the file, so that comments can refer to it. Removed lines have no
+    /// number.
+    pub fn numbered(&self) -> String {
+        let width = (self.start_line + self.new_lines()).to_string().len();
+        let mut number = self.start_line;
+        let lines = self.lines.iter().map(|line| {
+            if line.starts_with('-') || line.starts_with('\\') {
+                format!("{:width$} {line}", "")
+            } else {
+                number += 1;
+                format!("{:width$} {line}", number - 1)
+            }
+        });
+
+        std::iter::once(self.header.clone())
+            .chain(lines)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Parses the old and the new ranges of a `@@ -a,b +c,d @@` line, each as
+/// its first line and its number of lines
+fn parse_hunk_header(line: &str) -> Option<((u64, u64), (u64, u64))> {
+    let mut ranges = line.strip_prefix("@@ ")?.split(' ');
+    let old = parse_range(ranges.next()?.strip_prefix('-')?)?;
+    let new = parse_range(ranges.next()?.strip_prefix('+')?)?;
+    Some((old, new))
+}
+
+fn parse_range(range: &str) -> Option<(u64, u64)> {
+    match range.split_once(',') {
+        Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+        None => Some((range.parse().ok()?, 1)),
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewSeverity {
+    /// Style or readability remarks that can be ignored
+    #[default]
+    Info,
+    /// Likely problems that deserve a look before merging
+    Warning,
+    /// Bugs, security issues or data loss that block the merge
+    Error,
+}
+
+impl ReviewSeverity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReviewSeverity::Info => "info",
+            ReviewSeverity::Warning => "warning",
+            ReviewSeverity::Error => "error",
+        }
+    }
+}
+
+/// A remark of the reviewer on a line of a file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReviewComment {
+    pub path: String,
+    /// Line of the new version of the file the comment is about
+    pub line: u64,
+    #[serde(default)]
+    pub severity: ReviewSeverity,
+    pub comment: String,
+    /// Code that should replace the line
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+}
+
+impl ReviewComment {
+    /// Reads the comments of an answer of the reviewer, given as a JSON array
+    /// within the review comments tag. An answer without the tag is an error
+    /// rather than a review without comments, as the reviewer may have
+    /// stopped before finishing.
+    pub fn parse_all(answer: &str) -> Result<Vec<ReviewComment>> {
+        let comments = extract_tag_content(answer, REVIEW_COMMENTS_TAG)
+            .ok_or(Error::MissingReviewComments { tag: REVIEW_COMMENTS_TAG })?;
+        serde_json::from_str(comments).map_err(Error::InvalidReviewComments)
+    }
+
+    fn body(&self) -> String {
+        let mut body = format!("**{}**: {}", self.severity.as_str(), self.comment);
+        if let Some(suggestion) = &self.suggestion {
+            body.push_str(&format!("\n\n```suggestion\n{suggestion}\n```"));
+        }
+        body
+    }
+}
+
+/// Comments of a review of a diff
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Review {
+    pub comments: Vec<ReviewComment>,
+}
+
+impl Review {
+    pub fn new(mut comments: Vec<ReviewComment>) -> Self {
+        comments.sort_by(|a, b| a.path.cmp(&b.path).then(a.line.cmp(&b.line)));
+        Self { comments }
+    }
+
+    /// Renders the review as a Markdown report, with the comments grouped by
+    /// file
+    pub fn to_markdown(&self) -> String {
+        let mut report = String::from("# Review\n");
+        if self.comments.is_empty() {
+            report.push_str("\nNo issues found.\n");
+        }
+
+        let mut path = None;
+        for comment in &self.comments {
+            if path != Some(&comment.path) {
+                report.push_str(&format!("\n## {}\n\n", comment.path));
+                path = Some(&comment.path);
+            }
+            report.push_str(&format!("- Line {}: {}\n", comment.line, comment.body()));
+        }
+
+        report
+    }
+
+    /// Renders the review as the payload of the GitHub API creating a pull
+    /// request review. Comments on lines outside of the diff can't be attached
+    /// to a line, so they're listed in the body of the review instead.
+    pub fn to_github(&self, files: &[FileDiff]) -> Value {
+        let (inline, general): (Vec<_>, Vec<_>) = self.comments.iter().partition(|comment| {
+            files
+                .iter()
+                .any(|file| file.path == comment.path && file.contains(comment.line))
+        });
+
+        let mut body = format!("{} comments", self.comments.len());
+        for comment in general {
+            body.push_str(&format!(
+                "\n\n`{}:{}` {}",
+                comment.path,
+                comment.line,
+                comment.body()
+            ));
+        }
+
+        json!({
+            "event": "COMMENT",
+            "body": body,
+            "comments": inline
+                .iter()
+                .map(|comment| json!({
+                    "path": comment.path,
+                    "line": comment.line,
+                    "side": "RIGHT",
+                    "body": comment.body(),
+                }))
+                .collect::<Vec<_>>(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    const DIFF: &str = r#"diff --git a/src/cache.rs b/src/cache.rs
+index 3b18e51..a9c2f4e 100644
+--- a/src/cache.rs
++++ b/src/cache.rs
+@@ -10,3 +10,4 @@ impl Cache {
+     pub fn get(&self, key: &str) -> Option<&Value> {
+-        self.entries.get(key)
++        let key = key.to_lowercase();
++        self.entries.get(&key)
+     }
+diff --git a/src/old.rs b/src/old.rs
+deleted file mode 100644
+index 3b18e51..0000000
+--- a/src/old.rs
++++ /dev/null
+@@ -1 +0,0 @@
+-pub struct Old;
+"#;
+
+    fn comment(path: &str, line: u64) -> ReviewComment {
+        ReviewComment {
+            path: path.to_string(),
+            line,
+            severity: ReviewSeverity::Warning,
+            comment: "Lowercasing allocates on every lookup".to_string(),
+            suggestion: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_diff() {
+        let actual = FileDiff::parse_all(DIFF);
+
+        let expected = vec![FileDiff {
+            path: "src/cache.rs".to_string(),
+            hunks: vec![DiffHunk {
+                header: "@@ -10,3 +10,4 @@ impl Cache {".to_string(),
+                start_line: 10,
+                lines: vec![
+                    "     pub fn get(&self, key: &str) -> Option<&Value> {".to_string(),
+                    "-        self.entries.get(key)".to_string(),
+                    "+        let key = key.to_lowercase();".to_string(),
+                    "+        self.entries.get(&key)".to_string(),
+                    "     }".to_string(),
+                ],
+            }],
+        }];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_numbered_hunk() {
+        let fixture = FileDiff::parse_all(DIFF);
+
+        let actual = fixture[0].hunks[0].numbered();
+
+        let expected = [
+            "@@ -10,3 +10,4 @@ impl Cache {",
+            "10      pub fn get(&self, key: &str) -> Option<&Value> {",
+            "   -        self.entries.get(key)",
+            "11 +        let key = key.to_lowercase();",
+            "12 +        self.entries.get(&key)",
+            "13      }",
+        ]
+        .join("\n");
+        assert_eq!(actual, expected);
+        assert!(fixture[0].contains(13));
+        assert!(!fixture[0].contains(14));
+    }
+
+    #[test]
+    fn test_parse_comments() {
+        let fixture = r#"The change looks fine overall.
+<review_comments>
+[{"path": "src/cache.rs", "line": 11, "severity": "warning", "comment": "Lowercasing allocates on every lookup"}]
+</review_comments>"#;
+
+        let actual = ReviewComment::parse_all(fixture).unwrap();
+
+        let expected = vec![comment("src/cache.rs", 11)];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_comments_without_tag() {
+        let fixture = "The change looks fine overall.";
+
+        let actual = ReviewComment::parse_all(fixture).unwrap_err().to_string();
+
+        let expected = Error::MissingReviewComments { tag: REVIEW_COMMENTS_TAG }.to_string();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_review_to_markdown() {
+        let mut suggested = comment("src/cache.rs", 11);
+        suggested.suggestion = Some("        let key = key.to_ascii_lowercase();".to_string());
+        let fixture = Review::new(vec![suggested, comment("src/cache.rs", 3)]);
+
+        let actual = fixture.to_markdown();
+
+        let expected = "# Review\n\n## src/cache.rs\n\n\
+            - Line 3: **warning**: Lowercasing allocates on every lookup\n\
+            - Line 11: **warning**: Lowercasing allocates on every lookup\n\n\
+            ```suggestion\n        let key = key.to_ascii_lowercase();\n```\n";
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_review_to_github() {
+        let fixture = Review::new(vec![
+            comment("src/cache.rs", 11),
+            comment("src/cache.rs", 3),
+        ]);
+
+        let actual = fixture.to_github(&FileDiff::parse_all(DIFF));
+
+        let expected = json!({
+            "event": "COMMENT",
+            "body": concat!(
+                "2 comments\n\n",
+                "`src/cache.rs:3` **warning**: Lowercasing allocates on every lookup"
+            ),
+            "comments": [{
+                "path": "src/cache.rs",
+                "line": 11,
+                "side": "RIGHT",
+                "body": "**warning**: Lowercasing allocates on every lookup",
+            }],
+        });
+        assert_eq!(actual, expected);
+    }
+}
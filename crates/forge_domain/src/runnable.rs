@@ -0,0 +1,283 @@
+use std::collections::BTreeMap;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use strum_macros::Display;
+
+/// Build tools whose targets the discover tasks tool lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum BuildTool {
+    Cargo,
+    Npm,
+    Make,
+    Just,
+}
+
+impl BuildTool {
+    /// Names of the file of the project defining the targets of the tool, in
+    /// the order the tool looks for them
+    pub fn manifests(&self) -> &'static [&'static str] {
+        match self {
+            Self::Cargo => &["Cargo.toml"],
+            Self::Npm => &["package.json"],
+            Self::Make => &["GNUmakefile", "makefile", "Makefile"],
+            Self::Just => &["justfile", "Justfile", ".justfile"],
+        }
+    }
+}
+
+/// A target of a build tool that can be run from the root of the project
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Runnable {
+    pub tool: BuildTool,
+    /// Name of the target, e.g. the name of a script or a recipe
+    pub name: String,
+    /// Command running the target
+    pub command: String,
+    pub description: Option<String>,
+}
+
+impl Runnable {
+    fn new(tool: BuildTool, name: impl ToString, command: impl ToString) -> Self {
+        Self {
+            tool,
+            name: name.to_string(),
+            command: command.to_string(),
+            description: None,
+        }
+    }
+
+    fn description(mut self, description: impl ToString) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+}
+
+/// Targets of every cargo project, run on the whole workspace
+pub fn cargo_workspace_targets() -> Vec<Runnable> {
+    [
+        ("build", "cargo build --workspace", "Build every package"),
+        (
+            "test",
+            "cargo test --workspace",
+            "Run the tests of every package",
+        ),
+        (
+            "clippy",
+            "cargo clippy --workspace --all-targets",
+            "Lint every package and its tests",
+        ),
+        ("fmt", "cargo fmt --all", "Format the code of every package"),
+    ]
+    .into_iter()
+    .map(|(name, command, description)| {
+        Runnable::new(BuildTool::Cargo, name, command).description(description)
+    })
+    .collect()
+}
+
+/// Parses the output of `cargo metadata --no-deps --format-version 1` into
+/// the tests of each package of the workspace and its binaries and examples
+pub fn parse_cargo_metadata(metadata: &str) -> Vec<Runnable> {
+    let Ok(metadata) = serde_json::from_str::<Value>(metadata) else {
+        return Vec::new();
+    };
+    let packages = metadata["packages"].as_array().into_iter().flatten();
+
+    let mut runnables = Vec::new();
+    for package in packages {
+        let Some(name) = package["name"].as_str() else {
+            continue;
+        };
+        runnables.push(
+            Runnable::new(
+                BuildTool::Cargo,
+                format!("test -p {name}"),
+                format!("cargo test -p {name}"),
+            )
+            .description(format!("Run the tests of {name}")),
+        );
+
+        for target in package["targets"].as_array().into_iter().flatten() {
+            let Some(target_name) = target["name"].as_str() else {
+                continue;
+            };
+            let kinds = target["kind"].as_array().into_iter().flatten();
+            for kind in kinds.filter_map(Value::as_str) {
+                if kind == "bin" || kind == "example" {
+                    let name = format!("run -p {name} --{kind} {target_name}");
+                    runnables.push(Runnable::new(
+                        BuildTool::Cargo,
+                        &name,
+                        format!("cargo {name}"),
+                    ));
+                }
+            }
+        }
+    }
+    runnables
+}
+
+/// Parses the scripts of a `package.json`, run with the package manager
+/// whose lock file is in the project
+pub fn parse_package_json(content: &str, manager: &str) -> Vec<Runnable> {
+    let scripts = serde_json::from_str::<Value>(content)
+        .ok()
+        .and_then(|value| {
+            serde_json::from_value::<BTreeMap<String, String>>(value["scripts"].clone()).ok()
+        })
+        .unwrap_or_default();
+
+    scripts
+        .into_iter()
+        .map(|(name, script)| {
+            Runnable::new(BuildTool::Npm, &name, format!("{manager} run {name}"))
+                .description(script)
+        })
+        .collect()
+}
+
+/// Parses the targets of a Makefile. A target is described by a `## ...`
+/// comment after its prerequisites or by the comment on the line above it.
+/// Special targets like `.PHONY` and pattern rules aren't runnable.
+pub fn parse_makefile(content: &str) -> Vec<Runnable> {
+    let target = Regex::new(r"^([A-Za-z0-9_][A-Za-z0-9_./-]*)\s*:([^=].*)?$").unwrap();
+    parse_recipes(content, BuildTool::Make, &target, "make")
+}
+
+/// Parses the recipes of a justfile, described by the comment on the line
+/// above them. Private recipes, starting with an underscore, are skipped.
+pub fn parse_justfile(content: &str) -> Vec<Runnable> {
+    let recipe = Regex::new(r"^@?([A-Za-z0-9][A-Za-z0-9_-]*)(\s+[^:]*)?:([^=].*)?$").unwrap();
+    parse_recipes(content, BuildTool::Just, &recipe, "just")
+}
+
+fn parse_recipes(content: &str, tool: BuildTool, pattern: &Regex, program: &str) -> Vec<Runnable> {
+    let mut runnables = Vec::<Runnable>::new();
+    let mut comment = None;
+    for line in content.lines() {
+        if let Some(text) = line.strip_prefix('#') {
+            comment = Some(text.trim_start_matches('#').trim().to_string());
+            continue;
+        }
+        let Some(captures) = pattern.captures(line) else {
+            comment = None;
+            continue;
+        };
+        let name = &captures[1];
+        if runnables.iter().any(|runnable| runnable.name == name) {
+            continue;
+        }
+
+        let inline = captures
+            .get(captures.len() - 1)
+            .and_then(|rest| rest.as_str().split_once("##"))
+            .map(|(_, text)| text.trim().to_string());
+        let mut runnable = Runnable::new(tool, name, format!("{program} {name}"));
+        runnable.description = inline.or(comment.take()).filter(|text| !text.is_empty());
+        runnables.push(runnable);
+    }
+    runnables
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_cargo_metadata() {
+        let fixture = r#"{"packages":[{"name":"forge_main","targets":[{"name":"forge_main","kind":["lib"]},{"name":"forge","kind":["bin"]},{"name":"hello","kind":["example"]}]}]}"#;
+
+        let actual = parse_cargo_metadata(fixture);
+
+        let expected = vec![
+            Runnable::new(
+                BuildTool::Cargo,
+                "test -p forge_main",
+                "cargo test -p forge_main",
+            )
+            .description("Run the tests of forge_main"),
+            Runnable::new(
+                BuildTool::Cargo,
+                "run -p forge_main --bin forge",
+                "cargo run -p forge_main --bin forge",
+            ),
+            Runnable::new(
+                BuildTool::Cargo,
+                "run -p forge_main --example hello",
+                "cargo run -p forge_main --example hello",
+            ),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_package_json() {
+        let fixture = r#"{"name":"app","scripts":{"test":"vitest run","build":"tsc -p ."}}"#;
+
+        let actual = parse_package_json(fixture, "pnpm");
+
+        let expected = vec![
+            Runnable::new(BuildTool::Npm, "build", "pnpm run build").description("tsc -p ."),
+            Runnable::new(BuildTool::Npm, "test", "pnpm run test").description("vitest run"),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_makefile() {
+        let fixture = [
+            ".PHONY: build test",
+            "VERSION := 1.0",
+            "# Build the release binary",
+            "build: deps",
+            "\tcargo build --release",
+            "test: build ## Run the tests",
+            "\tcargo test",
+            "%.o: %.c",
+            "\tcc -c $<",
+        ]
+        .join("\n");
+
+        let actual = parse_makefile(&fixture);
+
+        let expected = vec![
+            Runnable::new(BuildTool::Make, "build", "make build")
+                .description("Build the release binary"),
+            Runnable::new(BuildTool::Make, "test", "make test").description("Run the tests"),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_justfile() {
+        let fixture = [
+            "set shell := [\"bash\", \"-c\"]",
+            "version := \"1.0\"",
+            "",
+            "# Run the tests matching the filter",
+            "test filter=\"\":",
+            "    cargo test {{filter}}",
+            "",
+            "_setup:",
+            "    mkdir -p target",
+            "@lint: _setup",
+            "    cargo clippy",
+        ]
+        .join("\n");
+
+        let actual = parse_justfile(&fixture);
+
+        let expected = vec![
+            Runnable::new(BuildTool::Just, "test", "just test")
+                .description("Run the tests matching the filter"),
+            Runnable::new(BuildTool::Just, "lint", "just lint"),
+        ];
+        assert_eq!(actual, expected);
+    }
+}
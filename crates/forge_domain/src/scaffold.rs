@@ -0,0 +1,253 @@
+use std::collections::BTreeMap;
+use std::path::{Component, Path};
+
+use convert_case::{Case, Casing};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result};
+
+/// Project template creating the same layout every time it is used, e.g. a new
+/// service with its module, tests and configuration. The paths and contents
+/// refer to the variables as `{{name}}`, or converted with a filter as
+/// `{{name | snake}}`. The filters are `snake`, `kebab`, `pascal`, `camel`
+/// and `upper`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ScaffoldTemplate {
+    /// Name of the template, e.g. `service`
+    pub name: String,
+    /// What the template creates
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Variables of the template
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variables: Vec<ScaffoldVariable>,
+    /// Directories created with an empty `.gitkeep` file so that git tracks
+    /// them, relative to the target directory
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub directories: Vec<String>,
+    /// Files created, relative to the target directory
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<ScaffoldFile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ScaffoldVariable {
+    pub name: String,
+    /// What the variable is for, shown when asking for its value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Value used when none is given. The variable is required when not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ScaffoldFile {
+    pub path: String,
+    #[serde(default)]
+    pub content: String,
+}
+
+impl ScaffoldTemplate {
+    /// Values of the variables, the given ones or else their defaults
+    ///
+    /// # Errors
+    /// - `MissingScaffoldVariable` if a variable without default has no value
+    pub fn resolve(&self, values: &BTreeMap<String, String>) -> Result<BTreeMap<String, String>> {
+        self.variables
+            .iter()
+            .map(|variable| {
+                let value = values
+                    .get(&variable.name)
+                    .or(variable.default.as_ref())
+                    .cloned()
+                    .ok_or_else(|| Error::MissingScaffoldVariable {
+                        template: self.name.clone(),
+                        variable: variable.name.clone(),
+                    })?;
+                Ok((variable.name.clone(), value))
+            })
+            .collect()
+    }
+
+    /// Files of the template with its variables filled in, including the
+    /// `.gitkeep` files of its directories
+    ///
+    /// # Errors
+    /// - `MissingScaffoldVariable` if a variable without default has no value
+    /// - `UnknownScaffoldVariable` if a placeholder isn't a declared variable
+    /// - `InvalidScaffoldPath` if a path leaves the target directory
+    pub fn render(&self, values: &BTreeMap<String, String>) -> Result<Vec<ScaffoldFile>> {
+        let values = self.resolve(values)?;
+        let directories = self.directories.iter().map(|path| {
+            let path = Path::new(&render_path(path, &values)?).join(".gitkeep");
+            Ok(ScaffoldFile { path: path.display().to_string(), content: String::new() })
+        });
+        let files = self.files.iter().map(|file| {
+            Ok(ScaffoldFile {
+                path: render_path(&file.path, &values)?,
+                content: render_text(&file.content, &values)?,
+            })
+        });
+        directories.chain(files).collect()
+    }
+
+    /// Name of the template followed by its variables and their defaults,
+    /// e.g. `service(name, port = 8080)`
+    pub fn signature(&self) -> String {
+        let variables = self
+            .variables
+            .iter()
+            .map(|variable| match &variable.default {
+                Some(default) => format!("{} = {default}", variable.name),
+                None => variable.name.clone(),
+            })
+            .collect::<Vec<_>>();
+        format!("{}({})", self.name, variables.join(", "))
+    }
+}
+
+/// Renders a path of the template, which must stay in the target directory
+fn render_path(path: &str, values: &BTreeMap<String, String>) -> Result<String> {
+    let rendered = render_text(path, values)?;
+    let is_contained = Path::new(&rendered)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+    if !is_contained || rendered.is_empty() {
+        return Err(Error::InvalidScaffoldPath(rendered));
+    }
+    Ok(rendered)
+}
+
+/// Replaces the `{{name}}` and `{{name | filter}}` placeholders of the text
+fn render_text(text: &str, values: &BTreeMap<String, String>) -> Result<String> {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}") else {
+            break;
+        };
+        result.push_str(&rest[..start]);
+        let placeholder = &rest[start + 2..start + end];
+        let mut parts = placeholder.split('|').map(str::trim);
+        let name = parts.next().unwrap_or_default();
+        let mut value = values
+            .get(name)
+            .cloned()
+            .ok_or_else(|| Error::UnknownScaffoldVariable(name.to_string()))?;
+        for filter in parts {
+            let case = match filter {
+                "snake" => Case::Snake,
+                "kebab" => Case::Kebab,
+                "pascal" => Case::Pascal,
+                "camel" => Case::Camel,
+                "upper" => Case::UpperSnake,
+                _ => return Err(Error::UnknownScaffoldFilter(filter.to_string())),
+            };
+            value = value.to_case(case);
+        }
+        result.push_str(&value);
+        rest = &rest[start + end + 2..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn fixture() -> ScaffoldTemplate {
+        serde_yml::from_str(
+            r#"
+name: service
+variables:
+  - name: name
+  - name: port
+    default: "8080"
+directories:
+  - "{{name | kebab}}/migrations"
+files:
+  - path: "{{name | kebab}}/src/{{name | snake}}.rs"
+    content: "pub struct {{ name | pascal }};\nconst PORT: u16 = {{port}};\n"
+"#,
+        )
+        .unwrap()
+    }
+
+    fn values(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_render() {
+        let actual = fixture()
+            .render(&values(&[("name", "billing service")]))
+            .unwrap();
+
+        let expected = vec![
+            ScaffoldFile {
+                path: "billing-service/migrations/.gitkeep".to_string(),
+                content: String::new(),
+            },
+            ScaffoldFile {
+                path: "billing-service/src/billing_service.rs".to_string(),
+                content: "pub struct BillingService;\nconst PORT: u16 = 8080;\n".to_string(),
+            },
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_signature() {
+        let actual = fixture().signature();
+        let expected = "service(name, port = 8080)";
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_render_missing_variable() {
+        let actual = fixture().render(&values(&[("port", "80")])).unwrap_err();
+
+        let expected = "Template service needs a value for the variable name";
+        assert_eq!(actual.to_string(), expected);
+    }
+
+    #[test]
+    fn test_render_invalid_path() {
+        let fixture = values(&[("name", "../outside")]);
+
+        let actual = render_path("{{name}}/main.rs", &fixture).unwrap_err();
+
+        let expected = "Invalid template path ../outside/main.rs, it must be relative and stay in the target directory";
+        assert_eq!(actual.to_string(), expected);
+    }
+
+    #[test]
+    fn test_render_text_unknown() {
+        let fixture = values(&[("name", "billing")]);
+
+        let actual = (
+            render_text("{{ version }}", &fixture)
+                .unwrap_err()
+                .to_string(),
+            render_text("{{name | title}}", &fixture)
+                .unwrap_err()
+                .to_string(),
+            render_text("{{name}} {{", &fixture).unwrap(),
+        );
+
+        let expected = (
+            "Unknown template variable: version".to_string(),
+            "Unknown template filter: title".to_string(),
+            "billing {{".to_string(),
+        );
+        assert_eq!(actual, expected);
+    }
+}
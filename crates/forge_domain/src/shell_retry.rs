@@ -0,0 +1,166 @@
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use merge::Merge;
+use regex::RegexSet;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::CommandOutput;
+
+/// Number of times a command failing transiently is retried when not
+/// configured. Retries are opt-in, as a command that failed partway, e.g. a
+/// migration or a deployment, isn't always safe to run again.
+const DEFAULT_MAX_RETRIES: usize = 0;
+
+/// Delay before the first retry when not configured, doubled for every
+/// following one
+const DEFAULT_BACKOFF_MS: u64 = 1000;
+
+/// Output of commands that failed because of the network or the
+/// infrastructure rather than the command itself
+const TRANSIENT_PATTERNS: [&str; 13] = [
+    r"(?i)\b(ETIMEDOUT|ECONNRESET|ECONNREFUSED|EAI_AGAIN|ENETUNREACH)\b",
+    r"(?i)(connection|operation|read|request) timed out",
+    r"(?i)connection (reset|refused|closed) by peer",
+    r"(?i)could not resolve host",
+    r"(?i)temporary failure in name resolution",
+    r"(?i)network is unreachable",
+    r"(?i)TLS handshake timeout",
+    r"(?i)\b(502 Bad Gateway|503 Service Unavailable|504 Gateway Time-?out)\b",
+    r"(?i)\b429 Too Many Requests\b",
+    r"(?i)spurious network error",
+    r"(?i)resource temporarily unavailable",
+    r"(?i)text file busy",
+    r"(?i)could not acquire lock",
+];
+
+static TRANSIENT: LazyLock<RegexSet> =
+    LazyLock::new(|| RegexSet::new(TRANSIENT_PATTERNS).expect("Invalid transient pattern"));
+
+/// Retries of shell commands whose output shows a transient failure, e.g. a
+/// network timeout while installing packages
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Merge, JsonSchema)]
+pub struct ShellRetry {
+    /// Number of times a command is retried, 0 disables retries. Defaults
+    /// to 0, set it only when the commands run are safe to repeat.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub max_retries: Option<usize>,
+    /// Delay in milliseconds before the first retry, doubled for every
+    /// following one. Defaults to 1000.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub backoff_ms: Option<u64>,
+    /// Additional regular expressions matching the output of transient
+    /// failures, e.g. the marker of a flaky test runner
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub patterns: Option<Vec<String>>,
+}
+
+impl ShellRetry {
+    pub fn max_retries(&self) -> usize {
+        self.max_retries.unwrap_or(DEFAULT_MAX_RETRIES)
+    }
+
+    /// Delay before the given retry, starting at 1
+    pub fn delay(&self, retry: usize) -> Duration {
+        let backoff = self.backoff_ms.unwrap_or(DEFAULT_BACKOFF_MS);
+        let factor = 2u64.saturating_pow(retry.saturating_sub(1) as u32);
+        Duration::from_millis(backoff.saturating_mul(factor))
+    }
+
+    /// Whether the command failed in a way that may not happen again, judging
+    /// from its output. Invalid configured patterns are ignored.
+    pub fn is_transient(&self, output: &CommandOutput) -> bool {
+        if output.exit_code.is_none_or(|code| code == 0) {
+            return false;
+        }
+
+        let is_match = |patterns: &RegexSet| {
+            patterns.is_match(&output.stdout) || patterns.is_match(&output.stderr)
+        };
+        if is_match(&TRANSIENT) {
+            return true;
+        }
+
+        let configured = self
+            .patterns
+            .iter()
+            .flatten()
+            .filter(|pattern| regex::Regex::new(pattern).is_ok());
+        RegexSet::new(configured).is_ok_and(|patterns| is_match(&patterns))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn output(exit_code: i32, stderr: &str) -> CommandOutput {
+        CommandOutput {
+            command: "npm install".to_string(),
+            stdout: String::new(),
+            stderr: stderr.to_string(),
+            exit_code: Some(exit_code),
+        }
+    }
+
+    #[test]
+    fn test_network_failures_are_transient() {
+        let fixture = ShellRetry::default();
+
+        let actual = [
+            fixture.is_transient(&output(
+                1,
+                "npm ERR! network request failed, reason: ETIMEDOUT",
+            )),
+            fixture.is_transient(&output(101, "warning: spurious network error")),
+            fixture.is_transient(&output(1, "error[E0308]: mismatched types")),
+            fixture.is_transient(&output(0, "Connection reset by peer")),
+            fixture.is_transient(&output(1, "test flaky_parser ... FAILED")),
+        ];
+
+        let expected = [true, true, false, false, false];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_configured_patterns_are_transient() {
+        let fixture = ShellRetry {
+            patterns: Some(vec!["RERUN ME".to_string(), "(invalid".to_string()]),
+            ..Default::default()
+        };
+
+        let actual = fixture.is_transient(&output(1, "test db: RERUN ME"));
+
+        assert!(actual);
+    }
+
+    #[test]
+    fn test_retries_are_opt_in() {
+        let fixture = ShellRetry::default();
+
+        let actual = fixture.max_retries();
+
+        let expected = 0;
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_delay_doubles() {
+        let fixture = ShellRetry { backoff_ms: Some(500), ..Default::default() };
+
+        let actual = [fixture.delay(1), fixture.delay(2), fixture.delay(3)];
+
+        let expected = [
+            Duration::from_millis(500),
+            Duration::from_millis(1000),
+            Duration::from_millis(2000),
+        ];
+        assert_eq!(actual, expected);
+    }
+}
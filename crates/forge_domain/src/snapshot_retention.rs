@@ -0,0 +1,108 @@
+use std::time::Duration;
+
+use merge::Merge;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result};
+
+/// Interval between two purges of the snapshots when not configured
+const DEFAULT_PURGE_INTERVAL_MINUTES: u64 = 60;
+
+/// Limits on the file snapshots kept to undo changes. Snapshots exceeding
+/// them are purged periodically, the oldest first.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Merge, JsonSchema)]
+pub struct SnapshotRetention {
+    /// Maximum number of snapshots kept for each file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub max_snapshots_per_file: Option<usize>,
+    /// Number of days after which a snapshot is removed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub retention_days: Option<u64>,
+    /// Maximum disk space used by all the snapshots together, in megabytes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub max_disk_usage_mb: Option<u64>,
+    /// Number of minutes between two purges
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub purge_interval_minutes: Option<u64>,
+}
+
+impl SnapshotRetention {
+    /// Rejects limits that would remove every snapshot or never run.
+    pub fn validate(&self) -> Result<()> {
+        let limits = [
+            (
+                "max_snapshots_per_file",
+                self.max_snapshots_per_file.map(|value| value as u64),
+            ),
+            ("retention_days", self.retention_days),
+            ("max_disk_usage_mb", self.max_disk_usage_mb),
+            ("purge_interval_minutes", self.purge_interval_minutes),
+        ];
+
+        match limits.into_iter().find(|(_, value)| *value == Some(0)) {
+            Some((name, _)) => Err(Error::InvalidSnapshotRetention(name)),
+            None => Ok(()),
+        }
+    }
+
+    pub fn max_age(&self) -> Option<Duration> {
+        self.retention_days
+            .map(|days| Duration::from_secs(days * 24 * 60 * 60))
+    }
+
+    pub fn max_disk_usage_bytes(&self) -> Option<u64> {
+        self.max_disk_usage_mb.map(|mb| mb * 1024 * 1024)
+    }
+
+    pub fn purge_interval(&self) -> Duration {
+        let minutes = self
+            .purge_interval_minutes
+            .unwrap_or(DEFAULT_PURGE_INTERVAL_MINUTES);
+        Duration::from_secs(minutes * 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_validate_rejects_zero_limits() {
+        let fixture = SnapshotRetention {
+            max_snapshots_per_file: Some(10),
+            retention_days: Some(0),
+            ..Default::default()
+        };
+
+        let actual = fixture.validate().unwrap_err().to_string();
+
+        let expected = "Invalid snapshot retention: retention_days must be greater than zero";
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_retention_from_yaml() {
+        let fixture = "max_snapshots_per_file: 20\nretention_days: 7\nmax_disk_usage_mb: 512\n";
+
+        let actual: SnapshotRetention = serde_yml::from_str(fixture).unwrap();
+
+        let expected = SnapshotRetention {
+            max_snapshots_per_file: Some(20),
+            retention_days: Some(7),
+            max_disk_usage_mb: Some(512),
+            purge_interval_minutes: None,
+        };
+        assert_eq!(actual, expected);
+        assert_eq!(
+            actual.max_age(),
+            Some(Duration::from_secs(7 * 24 * 60 * 60))
+        );
+    }
+}
@@ -0,0 +1,167 @@
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::{Error, Result};
+
+/// Region of the S3 buckets when none is configured
+const DEFAULT_REGION: &str = "us-east-1";
+
+/// Endpoint of the XML API of Google Cloud Storage, which is compatible with
+/// S3 when used with HMAC keys
+const GCS_ENDPOINT: &str = "https://storage.googleapis.com";
+
+/// Where the conversations and the snapshots of the files are kept. They stay
+/// on the local disk by default, a bucket or a database keeps them when forge
+/// runs on machines that don't outlive the session, e.g. ephemeral CI
+/// containers.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Storage {
+    /// Files under the base path
+    #[default]
+    Local,
+    /// Objects of a bucket with an S3 compatible API, e.g. S3, Google Cloud
+    /// Storage or MinIO
+    Bucket(Bucket),
+    /// Rows of a Postgres database. The password isn't part of the URL, it's
+    /// read from `PGPASSWORD` when connecting.
+    Postgres(Url),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bucket {
+    /// Endpoint of the API, the bucket is addressed in the path of the URLs
+    pub endpoint: Url,
+    pub name: String,
+    /// Prefix of the keys of the objects, empty to use the whole bucket
+    pub prefix: String,
+    pub region: String,
+}
+
+impl Storage {
+    /// Parses the location of the storage, `s3://<bucket>/<prefix>`,
+    /// `gs://<bucket>/<prefix>` or `postgres://<host>/<database>`. The
+    /// endpoint replaces the default one of the bucket scheme, e.g. for MinIO.
+    ///
+    /// # Errors
+    /// - `UnsupportedStorage` if the location isn't a bucket of S3 or GCS nor a
+    ///   Postgres database
+    pub fn parse(location: &str, endpoint: Option<&str>, region: Option<&str>) -> Result<Self> {
+        let unsupported = || Error::UnsupportedStorage(location.to_string());
+        let (scheme, path) = location.split_once("://").ok_or_else(unsupported)?;
+        if matches!(scheme, "postgres" | "postgresql") {
+            let mut url = Url::parse(location).map_err(|_| unsupported())?;
+            if !url.has_host() {
+                return Err(unsupported());
+            }
+            // Kept out of the environment, which is logged and serialized
+            let _ = url.set_password(None);
+            return Ok(Self::Postgres(url));
+        }
+        let (name, prefix) = path.split_once('/').unwrap_or((path, ""));
+        if name.is_empty() {
+            return Err(unsupported());
+        }
+
+        let (default_endpoint, region) = match scheme {
+            "s3" => {
+                let region = region.unwrap_or(DEFAULT_REGION).to_string();
+                (format!("https://s3.{region}.amazonaws.com"), region)
+            }
+            "gs" => (
+                GCS_ENDPOINT.to_string(),
+                region.unwrap_or("auto").to_string(),
+            ),
+            _ => return Err(unsupported()),
+        };
+        let endpoint =
+            Url::parse(endpoint.unwrap_or(&default_endpoint)).map_err(|_| unsupported())?;
+
+        Ok(Self::Bucket(Bucket {
+            endpoint,
+            name: name.to_string(),
+            prefix: prefix.trim_matches('/').to_string(),
+            region,
+        }))
+    }
+}
+
+impl Bucket {
+    /// Key of the object storing the given key, below the prefix
+    pub fn object_key(&self, key: &str) -> String {
+        if self.prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}/{key}", self.prefix)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let actual = (
+            Storage::parse("s3://forge-ci/history/", None, Some("eu-west-1")).unwrap(),
+            Storage::parse("gs://forge-ci", None, None).unwrap(),
+            Storage::parse("s3://forge-ci/a", Some("http://localhost:9000"), None).unwrap(),
+        );
+
+        let expected = (
+            Storage::Bucket(Bucket {
+                endpoint: Url::parse("https://s3.eu-west-1.amazonaws.com").unwrap(),
+                name: "forge-ci".to_string(),
+                prefix: "history".to_string(),
+                region: "eu-west-1".to_string(),
+            }),
+            Storage::Bucket(Bucket {
+                endpoint: Url::parse("https://storage.googleapis.com").unwrap(),
+                name: "forge-ci".to_string(),
+                prefix: String::new(),
+                region: "auto".to_string(),
+            }),
+            Storage::Bucket(Bucket {
+                endpoint: Url::parse("http://localhost:9000").unwrap(),
+                name: "forge-ci".to_string(),
+                prefix: "a".to_string(),
+                region: "us-east-1".to_string(),
+            }),
+        );
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_postgres() {
+        let actual = Storage::parse("postgres://forge:secret@db:5432/history", None, None).unwrap();
+
+        let expected = Storage::Postgres(Url::parse("postgres://forge@db:5432/history").unwrap());
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_unsupported() {
+        let actual = ["mysql://localhost/forge", "s3://", "/var/forge"]
+            .map(|location| Storage::parse(location, None, None).is_err());
+
+        let expected = [true, true, true];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_object_key() {
+        let fixture = Storage::parse("s3://forge-ci/history", None, None).unwrap();
+
+        let actual = match fixture {
+            Storage::Bucket(bucket) => bucket.object_key("conversations/1.json"),
+            _ => String::new(),
+        };
+
+        let expected = "history/conversations/1.json";
+        assert_eq!(actual, expected);
+    }
+}
@@ -4,7 +4,7 @@ use derive_setters::Setters;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::Environment;
+use crate::{Environment, PinnedContent};
 
 #[derive(Debug, Setters, Clone, Serialize, Deserialize)]
 #[setters(strip_option)]
@@ -32,6 +32,18 @@ pub struct SystemContext {
     #[serde(skip_serializing_if = "String::is_empty")]
     pub custom_rules: String,
 
+    /// Learnings remembered from the previous conversations of the project
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub memory: String,
+
+    /// Context items the user pinned, sent with every request
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pins: Vec<PinnedContent>,
+
+    /// Language the agent writes its answers in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_language: Option<String>,
+
     // Variables to pass to the system context
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub variables: HashMap<String, Value>,
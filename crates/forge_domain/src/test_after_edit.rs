@@ -0,0 +1,153 @@
+use std::path::Path;
+
+use glob::{MatchOptions, Pattern};
+use merge::Merge;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{ChangeSummary, Runnable};
+
+/// Number of times in a row failing tests are fed back to the agent when not
+/// configured
+const DEFAULT_MAX_ATTEMPTS: usize = 3;
+
+/// Name of the discovered target running the tests
+const TEST_TARGET: &str = "test";
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// Runs the tests once a turn modified files matching the globs, and feeds
+/// their failures back to the agent so that it fixes them before completing
+/// the turn
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Merge, JsonSchema)]
+pub struct TestAfterEdit {
+    /// Globs of the files whose changes run the tests, relative to the
+    /// workspace, e.g. `src/**/*.rs`. The changes of any file run them when
+    /// not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub globs: Option<Vec<String>>,
+    /// Command running the tests. Defaults to the `test` target found by the
+    /// discover tasks tool, e.g. `cargo test --workspace`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub command: Option<String>,
+    /// Number of times in a row the failures are fed back to the agent before
+    /// the turn ends anyway. Defaults to 3.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub max_attempts: Option<usize>,
+}
+
+impl TestAfterEdit {
+    pub fn max_attempts(&self) -> usize {
+        self.max_attempts.unwrap_or(DEFAULT_MAX_ATTEMPTS)
+    }
+
+    /// Whether one of the changed files matches the globs, any changed file
+    /// when there are none
+    pub fn is_triggered(&self, changes: &ChangeSummary, cwd: &Path) -> bool {
+        let Some(globs) = &self.globs else {
+            return !changes.files.is_empty();
+        };
+        let patterns = globs
+            .iter()
+            .filter_map(|glob| Pattern::new(glob).ok())
+            .collect::<Vec<_>>();
+
+        changes.files.iter().any(|file| {
+            let path = Path::new(&file.path);
+            let relative = path.strip_prefix(cwd).unwrap_or(path);
+            patterns.iter().any(|pattern| {
+                pattern.matches_path_with(relative, MATCH_OPTIONS)
+                    || pattern.matches_path_with(path, MATCH_OPTIONS)
+            })
+        })
+    }
+
+    /// The configured command, or the one of the discovered `test` target
+    pub fn test_command(&self, runnables: &[Runnable]) -> Option<String> {
+        self.command.clone().or_else(|| {
+            runnables
+                .iter()
+                .find(|runnable| runnable.name == TEST_TARGET)
+                .map(|runnable| runnable.command.clone())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{cargo_workspace_targets, FileChange, FileChangeKind};
+
+    fn changes(paths: &[&str]) -> ChangeSummary {
+        let mut changes = ChangeSummary::default();
+        for path in paths {
+            changes.record_file(FileChange {
+                path: path.to_string(),
+                kind: FileChangeKind::Modified,
+                insertions: 1,
+                deletions: 0,
+            });
+        }
+        changes
+    }
+
+    #[test]
+    fn test_is_triggered() {
+        let fixture = TestAfterEdit {
+            globs: Some(vec!["src/**/*.rs".to_string()]),
+            ..Default::default()
+        };
+        let cwd = Path::new("/project");
+
+        let actual = (
+            fixture.is_triggered(&changes(&["/project/src/parser/mod.rs"]), cwd),
+            fixture.is_triggered(&changes(&["/project/README.md"]), cwd),
+            fixture.is_triggered(&changes(&[]), cwd),
+        );
+
+        let expected = (true, false, false);
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_is_triggered_without_globs() {
+        let fixture = TestAfterEdit::default();
+        let cwd = Path::new("/project");
+
+        let actual = (
+            fixture.is_triggered(&changes(&["/project/README.md"]), cwd),
+            fixture.is_triggered(&changes(&[]), cwd),
+        );
+
+        let expected = (true, false);
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_test_command() {
+        let runnables = cargo_workspace_targets();
+
+        let actual = (
+            TestAfterEdit::default().test_command(&runnables),
+            TestAfterEdit { command: Some("just test".to_string()), ..Default::default() }
+                .test_command(&runnables),
+            TestAfterEdit::default().test_command(&[]),
+        );
+
+        let expected = (
+            Some("cargo test --workspace".to_string()),
+            Some("just test".to_string()),
+            None,
+        );
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,31 @@
+use std::collections::BTreeMap;
+
+use merge::Merge;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Base colors of the terminal output
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemeName {
+    #[default]
+    Dark,
+    Light,
+    Solarized,
+}
+
+/// Colors of the terminal output. Colors are disabled regardless of the
+/// theme when `NO_COLOR` is set or `CLICOLOR` is `0`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Merge, JsonSchema)]
+pub struct ThemeConfig {
+    /// Base colors, `dark`, `light` or `solarized`. Defaults to `dark`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub name: Option<ThemeName>,
+    /// Colors replacing the ones of the base theme by role (`text`, `accent`,
+    /// `highlight`, `success`, `error`, `insert`, `delete` or `muted`), as
+    /// names such as `cyan` or hex codes such as `#268bd2`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub colors: Option<BTreeMap<String, String>>,
+}
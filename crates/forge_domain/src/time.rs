@@ -0,0 +1,365 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, Local, NaiveDate, NaiveDateTime, TimeZone, Utc,
+};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result, ScheduleReminder, TimeGet};
+
+/// Formats of the times written without an offset, read in a timezone
+const LOCAL_TIME_FORMATS: [&str; 4] = [
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M",
+];
+
+/// Longest delay of a reminder in days, so that forgotten reminders don't
+/// pile up in the conversation
+const MAX_REMINDER_DAYS: i64 = 30;
+
+/// Timezone a time is reported or read in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Zone {
+    /// The timezone of the machine forge runs on
+    Local,
+    /// A timezone of the IANA database, e.g. `Europe/Paris`, following its
+    /// daylight saving time
+    Named(Tz),
+    /// A fixed offset from UTC, e.g. `+05:30`
+    Fixed(FixedOffset),
+}
+
+impl Zone {
+    /// The time of the instant on the clocks of the timezone
+    pub fn at(&self, instant: DateTime<Utc>) -> DateTime<FixedOffset> {
+        match self {
+            Zone::Local => instant.with_timezone(&Local).fixed_offset(),
+            Zone::Named(tz) => instant.with_timezone(tz).fixed_offset(),
+            Zone::Fixed(offset) => instant.with_timezone(offset),
+        }
+    }
+
+    /// The instant the clocks of the timezone show the time, the earliest one
+    /// when clocks turned back show it twice, `None` when clocks skip it
+    pub fn instant(&self, time: NaiveDateTime) -> Option<DateTime<Utc>> {
+        match self {
+            Zone::Local => Local
+                .from_local_datetime(&time)
+                .earliest()
+                .map(|t| t.with_timezone(&Utc)),
+            Zone::Named(tz) => tz
+                .from_local_datetime(&time)
+                .earliest()
+                .map(|t| t.with_timezone(&Utc)),
+            Zone::Fixed(offset) => offset
+                .from_local_datetime(&time)
+                .earliest()
+                .map(|t| t.with_timezone(&Utc)),
+        }
+    }
+}
+
+impl FromStr for Zone {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("local") {
+            return Ok(Zone::Local);
+        }
+        if s.eq_ignore_ascii_case("utc") || s == "Z" {
+            return Ok(Zone::Named(Tz::UTC));
+        }
+        if let Ok(offset) = s.parse::<FixedOffset>() {
+            return Ok(Zone::Fixed(offset));
+        }
+        s.parse::<Tz>()
+            .map(Zone::Named)
+            .map_err(|_| Error::UnknownTimezone(s.to_string()))
+    }
+}
+
+impl fmt::Display for Zone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Zone::Local => write!(f, "local"),
+            Zone::Named(tz) => write!(f, "{}", tz.name()),
+            Zone::Fixed(offset) => write!(f, "{offset}"),
+        }
+    }
+}
+
+/// The instant of a time written in RFC 3339, e.g. `2024-05-01T09:00:00Z`, or
+/// as a date and an optional time read in the timezone, e.g.
+/// `2024-05-01 09:00` or `2024-05-01`
+pub fn parse_time(text: &str, zone: &Zone) -> Result<DateTime<Utc>> {
+    let text = text.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(text) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    LOCAL_TIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })
+        .and_then(|time| zone.instant(time))
+        .ok_or_else(|| Error::InvalidTime(text.to_string()))
+}
+
+/// A duration written as amounts of weeks, days, hours, minutes and seconds,
+/// e.g. `1d12h`, `90m` or `-2w`
+pub fn parse_duration(text: &str) -> Result<Duration> {
+    let invalid = || Error::InvalidDuration(text.to_string());
+    let text = text.trim();
+    let (sign, rest) = match text.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, text.strip_prefix('+').unwrap_or(text)),
+    };
+
+    let mut total = Duration::zero();
+    let mut amount = String::new();
+    for c in rest.chars().filter(|c| !c.is_whitespace()) {
+        if c.is_ascii_digit() {
+            amount.push(c);
+            continue;
+        }
+        let value = amount.parse::<i64>().map_err(|_| invalid())?;
+        amount.clear();
+        let part = match c {
+            'w' => Duration::try_weeks(value),
+            'd' => Duration::try_days(value),
+            'h' => Duration::try_hours(value),
+            'm' => Duration::try_minutes(value),
+            's' => Duration::try_seconds(value),
+            _ => None,
+        };
+        total = part
+            .and_then(|part| total.checked_add(&part))
+            .ok_or_else(invalid)?;
+    }
+    if rest.is_empty() || !amount.is_empty() {
+        return Err(invalid());
+    }
+    Ok(total * sign)
+}
+
+/// A time asked for with the time tool, on the clocks of the timezone it's
+/// reported in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeReport {
+    pub zone: Zone,
+    pub time: DateTime<FixedOffset>,
+}
+
+impl TimeReport {
+    /// Number of the ISO 8601 week of the time, the one used for weekly
+    /// reports
+    pub fn iso_week(&self) -> u32 {
+        self.time.iso_week().week()
+    }
+}
+
+impl TimeGet {
+    /// The time the call asks for: the given time or the current one, shifted
+    /// by the duration to add
+    pub fn report(&self, now: DateTime<Utc>) -> Result<TimeReport> {
+        let zone = match &self.timezone {
+            Some(timezone) => timezone.parse::<Zone>()?,
+            None => Zone::Local,
+        };
+        let from = match &self.from_timezone {
+            Some(timezone) => timezone.parse::<Zone>()?,
+            None => zone,
+        };
+        let mut instant = match &self.time {
+            Some(time) => parse_time(time, &from)?,
+            None => now,
+        };
+        if let Some(add) = &self.add {
+            instant = instant
+                .checked_add_signed(parse_duration(add)?)
+                .ok_or_else(|| Error::InvalidDuration(add.clone()))?;
+        }
+        Ok(TimeReport { zone, time: zone.at(instant) })
+    }
+}
+
+/// A message posted into the conversation once it's due
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Reminder {
+    pub message: String,
+    pub due: DateTime<Utc>,
+    /// Whether the reminder was shown to the user while they were idle, it's
+    /// still posted into the conversation with the next request
+    #[serde(default)]
+    pub shown: bool,
+}
+
+impl Reminder {
+    /// Takes the reminders due at the time out of the scheduled ones, the
+    /// earliest first
+    pub fn take_due(reminders: &mut Vec<Reminder>, now: DateTime<Utc>) -> Vec<Reminder> {
+        let (mut due, pending): (Vec<_>, Vec<_>) = std::mem::take(reminders)
+            .into_iter()
+            .partition(|reminder| reminder.due <= now);
+        *reminders = pending;
+        due.sort_by_key(|reminder| reminder.due);
+        due
+    }
+}
+
+impl ScheduleReminder {
+    /// The reminder the call schedules, due after the delay or at the time,
+    /// read in the local timezone when it has no offset
+    pub fn reminder(&self, now: DateTime<Utc>) -> Result<Reminder> {
+        let due = match (&self.after, &self.at) {
+            (Some(after), None) => now
+                .checked_add_signed(parse_duration(after)?)
+                .ok_or_else(|| Error::InvalidDuration(after.clone()))?,
+            (None, Some(at)) => parse_time(at, &Zone::Local)?,
+            _ => return Err(Error::InvalidReminder("set either after or at".to_string())),
+        };
+        if due <= now {
+            return Err(Error::InvalidReminder(format!(
+                "{} is in the past",
+                due.to_rfc3339()
+            )));
+        }
+        if due - now > Duration::days(MAX_REMINDER_DAYS) {
+            return Err(Error::InvalidReminder(format!(
+                "it can't be due more than {MAX_REMINDER_DAYS} days ahead"
+            )));
+        }
+        Ok(Reminder { message: self.message.clone(), due, shown: false })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        "2024-03-31T00:30:00Z".parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        let fixture = ["90m", "1d12h", "-2w", "+1h 30m 15s", "5", "3y", ""];
+
+        let actual = fixture
+            .into_iter()
+            .map(|text| parse_duration(text).ok())
+            .collect::<Vec<_>>();
+
+        let expected = vec![
+            Some(Duration::minutes(90)),
+            Some(Duration::hours(36)),
+            Some(Duration::weeks(-2)),
+            Some(Duration::seconds(5_415)),
+            None,
+            None,
+            None,
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_report_converts_timezones() {
+        let fixture = TimeGet {
+            timezone: Some("Europe/Paris".to_string()),
+            time: Some("2024-03-30 20:00".to_string()),
+            from_timezone: Some("America/New_York".to_string()),
+            add: Some("6h".to_string()),
+            ..Default::default()
+        };
+
+        let actual = fixture.report(now()).unwrap();
+
+        // Paris moves to summer time in the night
+        let expected = DateTime::parse_from_rfc3339("2024-03-31T08:00:00+02:00").unwrap();
+        assert_eq!(actual.time, expected);
+        assert_eq!(actual.time.offset().to_string(), "+02:00");
+        assert_eq!(actual.iso_week(), 13);
+    }
+
+    #[test]
+    fn test_report_defaults_to_now() {
+        let fixture = TimeGet { timezone: Some("+05:30".to_string()), ..Default::default() };
+
+        let actual = fixture.report(now()).unwrap();
+
+        let expected = DateTime::parse_from_rfc3339("2024-03-31T06:00:00+05:30").unwrap();
+        assert_eq!(actual.time, expected);
+        assert_eq!(actual.zone.to_string(), "+05:30");
+    }
+
+    #[test]
+    fn test_unknown_timezone() {
+        let actual = "Mars/Olympus".parse::<Zone>().is_err();
+        assert!(actual);
+    }
+
+    #[test]
+    fn test_reminder_bounds() {
+        let fixture = [
+            (Some("2h"), None),
+            (None, Some("2024-03-31T12:00:00Z")),
+            (Some("-1h"), None),
+            (Some("31d"), None),
+            (None, None),
+        ];
+
+        let actual = fixture
+            .into_iter()
+            .map(|(after, at)| {
+                ScheduleReminder {
+                    message: "Check the deployment".to_string(),
+                    after: after.map(str::to_string),
+                    at: at.map(str::to_string),
+                    ..Default::default()
+                }
+                .reminder(now())
+                .ok()
+                .map(|reminder| reminder.due.to_rfc3339())
+            })
+            .collect::<Vec<_>>();
+
+        let expected = vec![
+            Some("2024-03-31T02:30:00+00:00".to_string()),
+            Some("2024-03-31T12:00:00+00:00".to_string()),
+            None,
+            None,
+            None,
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_take_due_reminders() {
+        let reminder = |message: &str, minutes: i64| Reminder {
+            message: message.to_string(),
+            due: now() + Duration::minutes(minutes),
+            shown: false,
+        };
+        let mut fixture = vec![
+            reminder("later", 10),
+            reminder("second", -1),
+            reminder("first", -5),
+        ];
+
+        let actual = Reminder::take_due(&mut fixture, now());
+
+        let expected = vec![reminder("first", -5), reminder("second", -1)];
+        assert_eq!(actual, expected);
+        assert_eq!(fixture, vec![reminder("later", 10)]);
+    }
+}
@@ -0,0 +1,154 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{AgentId, ConversationId, ModelId, ToolName};
+
+/// What an agent was doing during a span of a turn
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SpanKind {
+    /// The model was generating a response
+    Generation { model: ModelId },
+    /// A tool was running
+    ToolCall { name: ToolName, success: bool },
+    /// Nothing was generating nor running, e.g. while the context was saved
+    Idle,
+}
+
+/// A span of time of a turn, recorded to find out where slow agent workflows
+/// spend their time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimelineSpan {
+    /// Index of the turn the span belongs to, counted from the start of the
+    /// conversation
+    pub turn: usize,
+    pub agent: AgentId,
+    #[serde(flatten)]
+    pub kind: SpanKind,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+}
+
+impl TimelineSpan {
+    pub fn new(turn: usize, agent: AgentId, kind: SpanKind, started_at: DateTime<Utc>) -> Self {
+        Self { turn, agent, kind, started_at, ended_at: Utc::now() }
+    }
+
+    pub fn duration_ms(&self) -> i64 {
+        (self.ended_at - self.started_at).num_milliseconds().max(0)
+    }
+
+    /// Name of the row of the span in a chart
+    pub fn label(&self) -> String {
+        match &self.kind {
+            SpanKind::Generation { .. } => "generation".to_string(),
+            SpanKind::ToolCall { name, .. } => name.to_string(),
+            SpanKind::Idle => "idle".to_string(),
+        }
+    }
+}
+
+/// Spans of the turns of a conversation, with the idle waits between them
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Timeline {
+    pub conversation_id: ConversationId,
+    pub spans: Vec<TimelineSpan>,
+}
+
+impl Timeline {
+    /// Orders the recorded spans and fills the gaps within each turn with idle
+    /// spans. The time between turns is spent by the user and left out.
+    pub fn new(conversation_id: ConversationId, recorded: &[TimelineSpan]) -> Self {
+        let mut recorded = recorded.to_vec();
+        recorded.sort_by_key(|span| (span.turn, span.started_at));
+
+        let mut spans: Vec<TimelineSpan> = Vec::with_capacity(recorded.len());
+        for span in recorded {
+            let idle = spans
+                .last()
+                .filter(|previous| {
+                    previous.turn == span.turn && previous.ended_at < span.started_at
+                })
+                .map(|previous| TimelineSpan {
+                    turn: span.turn,
+                    agent: span.agent.clone(),
+                    kind: SpanKind::Idle,
+                    started_at: previous.ended_at,
+                    ended_at: span.started_at,
+                });
+            spans.extend(idle);
+            spans.push(span);
+        }
+
+        Self { conversation_id, spans }
+    }
+
+    /// Spans of each turn, in order
+    pub fn turns(&self) -> Vec<&[TimelineSpan]> {
+        self.spans.chunk_by(|a, b| a.turn == b.turn).collect()
+    }
+
+    pub fn to_html(&self) -> String {
+        crate::timeline_html::render_timeline_html(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn span(turn: usize, kind: SpanKind, start_ms: i64, end_ms: i64) -> TimelineSpan {
+        let origin = DateTime::<Utc>::UNIX_EPOCH;
+        TimelineSpan {
+            turn,
+            agent: AgentId::new("forge"),
+            kind,
+            started_at: origin + Duration::milliseconds(start_ms),
+            ended_at: origin + Duration::milliseconds(end_ms),
+        }
+    }
+
+    fn generation() -> SpanKind {
+        SpanKind::Generation { model: ModelId::new("anthropic/claude-3.7-sonnet") }
+    }
+
+    fn tool_call() -> SpanKind {
+        SpanKind::ToolCall { name: ToolName::new("forge_tool_fs_read"), success: true }
+    }
+
+    #[test]
+    fn test_idle_waits_within_turns() {
+        let fixture = vec![
+            span(1, generation(), 60_000, 61_000),
+            span(0, tool_call(), 1_500, 2_000),
+            span(0, generation(), 0, 1_000),
+        ];
+
+        let actual = Timeline::new(ConversationId::generate(), &fixture)
+            .spans
+            .into_iter()
+            .map(|span| (span.turn, span.label(), span.duration_ms()))
+            .collect::<Vec<_>>();
+
+        let expected = vec![
+            (0, "generation".to_string(), 1_000),
+            (0, "idle".to_string(), 500),
+            (0, "forge_tool_fs_read".to_string(), 500),
+            (1, "generation".to_string(), 1_000),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_span_json() {
+        let fixture = span(0, tool_call(), 0, 250);
+
+        let actual = serde_json::to_value(&fixture).unwrap();
+
+        assert_eq!(actual["kind"], "tool_call");
+        assert_eq!(actual["name"], "forge_tool_fs_read");
+    }
+}
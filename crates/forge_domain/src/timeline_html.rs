@@ -0,0 +1,191 @@
+use std::collections::BTreeMap;
+
+use forge_template::Element;
+
+use crate::timeline::{SpanKind, Timeline, TimelineSpan};
+
+/// Width of the column holding the row labels of a chart
+const LABEL_WIDTH: f64 = 220.0;
+/// Width of the bars area of a chart
+const CHART_WIDTH: f64 = 760.0;
+const ROW_HEIGHT: f64 = 22.0;
+
+pub fn render_timeline_html(timeline: &Timeline) -> String {
+    let charts = timeline.turns().into_iter().map(|spans| {
+        let duration = turn_duration_ms(spans);
+        Element::new("div.section")
+            .append(Element::new("h2").text(format!(
+                "Turn {} ({})",
+                spans[0].turn + 1,
+                format_ms(duration)
+            )))
+            .append(create_turn_chart(spans))
+    });
+
+    let html = Element::new("html")
+        .attr("lang", "en")
+        .append(
+            Element::new("head")
+                .append(Element::new("meta").attr("charset", "UTF-8"))
+                .append(
+                    Element::new("title").text(format!("Timeline: {}", timeline.conversation_id)),
+                )
+                .append(Element::new("style").text(include_str!("conversation_style.css"))),
+        )
+        .append(
+            Element::new("body")
+                .append(Element::new("h1").text("Timeline"))
+                .append(create_totals_section(timeline))
+                .append(charts),
+        );
+
+    html.render()
+}
+
+/// Time spent generating, running each tool and waiting over the whole
+/// conversation
+fn create_totals_section(timeline: &Timeline) -> Element {
+    let mut totals = BTreeMap::<String, (usize, i64)>::new();
+    for span in timeline.spans.iter() {
+        let total = totals.entry(span.label()).or_default();
+        total.0 += 1;
+        total.1 += span.duration_ms();
+    }
+
+    let table = Element::new("table").append(
+        Element::new("tr")
+            .append(Element::new("th").text("Activity"))
+            .append(Element::new("th").text("Count"))
+            .append(Element::new("th").text("Total")),
+    );
+    let table = totals
+        .into_iter()
+        .fold(table, |table, (label, (count, duration))| {
+            table.append(
+                Element::new("tr")
+                    .append(Element::new("td").text(label))
+                    .append(Element::new("td").text(count))
+                    .append(Element::new("td").text(format_ms(duration))),
+            )
+        });
+
+    Element::new("div.section")
+        .append(Element::new("h2").text("Totals"))
+        .append(table)
+}
+
+/// Gantt chart of a turn with a row per activity, in the order they first
+/// happened
+fn create_turn_chart(spans: &[TimelineSpan]) -> Element {
+    let mut rows: Vec<String> = Vec::new();
+    for span in spans {
+        let label = span.label();
+        if !rows.contains(&label) {
+            rows.push(label);
+        }
+    }
+
+    let origin = spans[0].started_at;
+    let scale = CHART_WIDTH / turn_duration_ms(spans).max(1) as f64;
+    let height = rows.len() as f64 * ROW_HEIGHT;
+
+    let labels = rows.iter().enumerate().map(|(index, label)| {
+        Element::new("text")
+            .attr("x", 0)
+            .attr("y", index as f64 * ROW_HEIGHT + ROW_HEIGHT * 0.7)
+            .attr("font-size", 12)
+            .text(label)
+    });
+
+    let bars = spans.iter().map(|span| {
+        let row = rows.iter().position(|label| *label == span.label());
+        let offset = (span.started_at - origin).num_milliseconds() as f64;
+        let width = (span.duration_ms() as f64 * scale).max(1.0);
+        Element::new("rect")
+            .attr("x", LABEL_WIDTH + offset * scale)
+            .attr("y", row.unwrap_or_default() as f64 * ROW_HEIGHT + 3.0)
+            .attr("width", width)
+            .attr("height", ROW_HEIGHT - 6.0)
+            .attr("fill", color(&span.kind))
+            .append(Element::new("title").text(format!(
+                "{}: {}",
+                span.label(),
+                format_ms(span.duration_ms())
+            )))
+    });
+
+    Element::new("svg")
+        .attr("xmlns", "http://www.w3.org/2000/svg")
+        .attr("width", LABEL_WIDTH + CHART_WIDTH)
+        .attr("height", height)
+        .append(labels)
+        .append(bars)
+}
+
+fn turn_duration_ms(spans: &[TimelineSpan]) -> i64 {
+    let started_at = spans.iter().map(|span| span.started_at).min();
+    let ended_at = spans.iter().map(|span| span.ended_at).max();
+    match (started_at, ended_at) {
+        (Some(started_at), Some(ended_at)) => (ended_at - started_at).num_milliseconds().max(0),
+        _ => 0,
+    }
+}
+
+fn color(kind: &SpanKind) -> &'static str {
+    match kind {
+        SpanKind::Generation { .. } => "#4e79a7",
+        SpanKind::ToolCall { success: true, .. } => "#59a14f",
+        SpanKind::ToolCall { success: false, .. } => "#e15759",
+        SpanKind::Idle => "#bab0ac",
+    }
+}
+
+fn format_ms(duration: i64) -> String {
+    if duration < 1000 {
+        format!("{duration}ms")
+    } else {
+        format!("{:.1}s", duration as f64 / 1000.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Duration, Utc};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{AgentId, ConversationId, ToolName};
+
+    #[test]
+    fn test_render_timeline_html() {
+        let origin = DateTime::<Utc>::UNIX_EPOCH;
+        let fixture = Timeline::new(
+            ConversationId::generate(),
+            &[
+                TimelineSpan {
+                    turn: 0,
+                    agent: AgentId::new("forge"),
+                    kind: SpanKind::ToolCall {
+                        name: ToolName::new("forge_tool_process_shell"),
+                        success: false,
+                    },
+                    started_at: origin + Duration::milliseconds(1_500),
+                    ended_at: origin + Duration::milliseconds(4_000),
+                },
+                TimelineSpan {
+                    turn: 0,
+                    agent: AgentId::new("forge"),
+                    kind: SpanKind::Idle,
+                    started_at: origin,
+                    ended_at: origin + Duration::milliseconds(1_000),
+                },
+            ],
+        );
+
+        let actual = render_timeline_html(&fixture);
+
+        assert!(actual.contains("Turn 1 (4.0s)"));
+        assert!(actual.contains("forge_tool_process_shell: 2.5s"));
+        assert_eq!(actual.matches("<rect").count(), 3);
+    }
+}
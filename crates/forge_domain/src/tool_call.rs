@@ -3,7 +3,7 @@ use derive_setters::Setters;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::xml::extract_tag_content;
+use crate::xml::extract_all_tag_content;
 use crate::{Error, Result, ToolName};
 
 /// Unique identifier for a using a tool
@@ -63,6 +63,17 @@ impl ToolCall {
     }
 }
 
+/// A tool call whose arguments couldn't be parsed, even leniently. It is
+/// reported back to the model along with the parse error so that the model can
+/// fix the call.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MalformedToolCall {
+    pub name: ToolName,
+    pub call_id: Option<ToolCallId>,
+    pub arguments: String,
+    pub error: String,
+}
+
 /// Contains the full information about using a tool. This is received as a part
 /// of the response from the model when streaming is disabled.
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, Setters)]
@@ -93,14 +104,12 @@ impl ToolCallFull {
         for part in parts.iter() {
             if let Some(value) = &part.call_id {
                 if let Some(tool_name) = tool_name {
+                    let parsed =
+                        Self::parse_call_arguments(tool_name, tool_call_id.as_ref(), &arguments)?;
                     tool_calls.push(ToolCallFull {
                         name: tool_name.clone(),
                         call_id: tool_call_id,
-                        arguments: if arguments.is_empty() {
-                            Value::default()
-                        } else {
-                            serde_json::from_str(&arguments).map_err(Error::ToolCallArgument)?
-                        },
+                        arguments: parsed,
                     });
                     arguments.clear();
                 }
@@ -115,25 +124,54 @@ impl ToolCallFull {
         }
 
         if let Some(tool_name) = tool_name {
+            let arguments =
+                Self::parse_call_arguments(tool_name, tool_call_id.as_ref(), &arguments)?;
             tool_calls.push(ToolCallFull {
                 name: tool_name.clone(),
                 call_id: tool_call_id,
-                arguments: if arguments.is_empty() {
-                    Value::default()
-                } else {
-                    serde_json::from_str(&arguments).map_err(Error::ToolCallArgument)?
-                },
+                arguments,
             });
         }
 
         Ok(tool_calls)
     }
 
-    /// Parse multiple tool calls from XML format.
+    /// Parses the arguments of a tool call. Models occasionally produce
+    /// arguments that are almost JSON, e.g. with trailing commas or single
+    /// quotes, so arguments that aren't valid JSON are parsed as JSON5 before
+    /// reporting the original JSON error.
+    pub fn parse_arguments(arguments: &str) -> std::result::Result<Value, serde_json::Error> {
+        if arguments.trim().is_empty() {
+            return Ok(Value::default());
+        }
+
+        serde_json::from_str(arguments)
+            .or_else(|error| json5::from_str(arguments).map_err(|_| error))
+    }
+
+    /// Parses the arguments of the call of the tool `name`, failing with a
+    /// [`Error::MalformedToolCall`] that can be reported back to the model.
+    pub fn parse_call_arguments(
+        name: &ToolName,
+        call_id: Option<&ToolCallId>,
+        arguments: &str,
+    ) -> Result<Value> {
+        Self::parse_arguments(arguments).map_err(|error| {
+            Error::MalformedToolCall(MalformedToolCall {
+                name: name.clone(),
+                call_id: call_id.cloned(),
+                arguments: arguments.to_string(),
+                error: error.to_string(),
+            })
+        })
+    }
+
+    /// Parse multiple tool calls from XML format. Every `forge_tool_call` block
+    /// is parsed, in the order they appear in the input.
     pub fn try_from_xml(input: &str) -> std::result::Result<Vec<ToolCallFull>, Error> {
-        match extract_tag_content(input, "forge_tool_call") {
-            None => Ok(Default::default()),
-            Some(content) => {
+        extract_all_tag_content(input, "forge_tool_call")
+            .into_iter()
+            .map(|content| {
                 let mut tool_call: ToolCallFull =
                     serde_json::from_str(content).map_err(Error::ToolCallArgument)?;
 
@@ -141,12 +179,103 @@ impl ToolCallFull {
                 // leaving a lot of messages without tool calls
 
                 tool_call.call_id = Some(ToolCallId::generate());
-                Ok(vec![tool_call])
+                Ok(tool_call)
+            })
+            .collect()
+    }
+
+    /// Parses the tool calls written in the text of a model that doesn't
+    /// support tool calling natively. A `forge_tool_call` block may contain
+    /// JSON or the XML protocol, i.e.
+    /// `<tool_name><arg>value</arg></tool_name>`. Blocks that don't
+    /// describe a tool call are skipped.
+    pub fn try_from_text(input: &str) -> Vec<ToolCallFull> {
+        extract_all_tag_content(input, "forge_tool_call")
+            .into_iter()
+            .filter_map(|block| {
+                Self::parse_json_block(block).or_else(|| Self::parse_xml_block(block))
+            })
+            .flatten()
+            .map(Self::with_generated_id)
+            .collect()
+    }
+
+    /// Parses the tool calls that models without `forge_tool_call` blocks
+    /// write in fenced JSON blocks. Only the calls of the offered tools are
+    /// kept, so that JSON examples in an answer, e.g. a `package.json` with a
+    /// `name`, aren't mistaken for tool calls.
+    pub fn try_from_fenced(input: &str, offered: &[ToolName]) -> Vec<ToolCallFull> {
+        fenced_blocks(input)
+            .filter_map(Self::parse_json_block)
+            .flatten()
+            .filter(|tool_call| offered.contains(&tool_call.name))
+            .map(Self::with_generated_id)
+            .collect()
+    }
+
+    fn with_generated_id(tool_call: ToolCallFull) -> ToolCallFull {
+        ToolCallFull { call_id: Some(ToolCallId::generate()), ..tool_call }
+    }
+
+    fn parse_json_block(block: &str) -> Option<Vec<ToolCallFull>> {
+        let value = Self::parse_arguments(block).ok()?;
+        Self::from_emulated_value(value)
+    }
+
+    fn parse_xml_block(block: &str) -> Option<Vec<ToolCallFull>> {
+        crate::tool_call_parser::parse(&format!("<forge_tool_call>{block}</forge_tool_call>"))
+            .ok()
+            .filter(|tool_calls| !tool_calls.is_empty())
+    }
+
+    /// Normalizes the shapes models commonly use to describe tool calls in
+    /// JSON: `{"name", "arguments"}`, `{"name", "parameters"}`, the OpenAI
+    /// `{"function": {"name", "arguments"}}` with arguments encoded as a
+    /// string, or an array of them.
+    fn from_emulated_value(value: Value) -> Option<Vec<ToolCallFull>> {
+        match value {
+            Value::Array(values) => {
+                let tool_calls = values
+                    .into_iter()
+                    .filter_map(Self::from_emulated_value)
+                    .flatten()
+                    .collect::<Vec<_>>();
+                (!tool_calls.is_empty()).then_some(tool_calls)
             }
+            Value::Object(mut object) => {
+                if let Some(function @ Value::Object(_)) = object.remove("function") {
+                    return Self::from_emulated_value(function);
+                }
+
+                let name = object.remove("name")?.as_str()?.to_string();
+                let arguments = match object
+                    .remove("arguments")
+                    .or_else(|| object.remove("parameters"))
+                {
+                    Some(Value::String(arguments)) => Self::parse_arguments(&arguments).ok()?,
+                    Some(arguments) => arguments,
+                    None => Value::default(),
+                };
+                Some(vec![ToolCallFull {
+                    name: ToolName::new(name),
+                    call_id: None,
+                    arguments,
+                }])
+            }
+            _ => None,
         }
     }
 }
 
+/// Contents of the fenced code blocks of a Markdown text that are JSON or have
+/// no language
+fn fenced_blocks(input: &str) -> impl Iterator<Item = &str> {
+    input.split("```").skip(1).step_by(2).filter_map(|block| {
+        let (language, content) = block.split_once('\n')?;
+        matches!(language.trim(), "" | "json" | "json5").then_some(content)
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
@@ -255,6 +384,42 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_lenient_arguments() {
+        let input = [ToolCallPart {
+            call_id: Some(ToolCallId("call_1".to_string())),
+            name: Some(ToolName::new("forge_tool_fs_read")),
+            arguments_part: "{'path': 'docs/onboarding.md', 'start_line': 10,}".to_string(),
+        }];
+
+        let actual = ToolCallFull::try_from_parts(&input).unwrap();
+        let expected = vec![ToolCallFull {
+            call_id: Some(ToolCallId("call_1".to_string())),
+            name: ToolName::new("forge_tool_fs_read"),
+            arguments: serde_json::json!({"path": "docs/onboarding.md", "start_line": 10}),
+        }];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_malformed_arguments() {
+        let input = [ToolCallPart {
+            call_id: Some(ToolCallId("call_1".to_string())),
+            name: Some(ToolName::new("forge_tool_fs_read")),
+            arguments_part: "{\"path\": \"docs/".to_string(),
+        }];
+
+        let actual = ToolCallFull::try_from_parts(&input).unwrap_err();
+
+        let Error::MalformedToolCall(actual) = actual else {
+            panic!("Expected a malformed tool call, got {actual:?}");
+        };
+        assert_eq!(actual.name, ToolName::new("forge_tool_fs_read"));
+        assert_eq!(actual.call_id, Some(ToolCallId("call_1".to_string())));
+        assert_eq!(actual.arguments, "{\"path\": \"docs/");
+    }
+
     #[test]
     fn test_real_example() {
         let message = include_str!("./fixtures/tool_call_01.md");
@@ -264,6 +429,30 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_try_from_xml_multiple_calls() {
+        let message = r#"Reading both files.
+<forge_tool_call>
+{"name": "forge_tool_fs_read", "arguments": {"path": "/a.rs"}}
+</forge_tool_call>
+And the second one:
+<forge_tool_call>
+{"name": "forge_tool_fs_read", "arguments": {"path": "/b.rs"}}
+</forge_tool_call>"#;
+
+        let actual = ToolCallFull::try_from_xml(message)
+            .unwrap()
+            .into_iter()
+            .map(|call| call.arguments)
+            .collect::<Vec<_>>();
+
+        let expected = vec![
+            serde_json::json!({"path": "/a.rs"}),
+            serde_json::json!({"path": "/b.rs"}),
+        ];
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn test_try_from_xml_call_id() {
         let message = include_str!("./fixtures/tool_call_01.md");
@@ -271,4 +460,109 @@ mod tests {
         let actual = tool_call.first().unwrap().call_id.as_ref().unwrap();
         assert!(actual.as_str().starts_with("forge_call_id_"));
     }
+
+    #[test]
+    fn test_try_from_text_xml_protocol() {
+        let message = r#"Reading the file.
+<forge_tool_call>
+<forge_tool_fs_read>
+<path>/a.rs</path>
+<start_line>10</start_line>
+</forge_tool_fs_read>
+</forge_tool_call>"#;
+
+        let actual = ToolCallFull::try_from_text(message)
+            .into_iter()
+            .map(|call| (call.name, call.arguments))
+            .collect::<Vec<_>>();
+
+        let expected = vec![(
+            ToolName::new("forge_tool_fs_read"),
+            serde_json::json!({"path": "/a.rs", "start_line": 10}),
+        )];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_try_from_text_mixed_blocks() {
+        let message = r#"<forge_tool_call>
+{"name": "forge_tool_fs_read", "arguments": {"path": "/a.rs"}}
+</forge_tool_call>
+<forge_tool_call>
+Not a tool call
+</forge_tool_call>
+<forge_tool_call>
+<forge_tool_fs_read><path>/b.rs</path></forge_tool_fs_read>
+</forge_tool_call>"#;
+
+        let actual = ToolCallFull::try_from_text(message)
+            .into_iter()
+            .map(|call| call.arguments)
+            .collect::<Vec<_>>();
+
+        let expected = vec![
+            serde_json::json!({"path": "/a.rs"}),
+            serde_json::json!({"path": "/b.rs"}),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_try_from_fenced_json() {
+        let message = r#"Let me read both files:
+```json
+[
+  {"type": "function", "function": {"name": "forge_tool_fs_read", "arguments": "{\"path\": \"/a.rs\"}"}},
+  {"name": "forge_tool_fs_read", "parameters": {"path": "/b.rs"}}
+]
+```
+```rust
+fn main() {}
+```"#;
+
+        let actual = ToolCallFull::try_from_fenced(message, &[ToolName::new("forge_tool_fs_read")])
+            .into_iter()
+            .map(|call| (call.name, call.arguments))
+            .collect::<Vec<_>>();
+
+        let expected = vec![
+            (
+                ToolName::new("forge_tool_fs_read"),
+                serde_json::json!({"path": "/a.rs"}),
+            ),
+            (
+                ToolName::new("forge_tool_fs_read"),
+                serde_json::json!({"path": "/b.rs"}),
+            ),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_try_from_fenced_json_not_offered() {
+        let message = r#"Create the manifest:
+```json
+{"name": "my-app", "version": "1.0.0"}
+```"#;
+
+        let actual = ToolCallFull::try_from_fenced(message, &[ToolName::new("forge_tool_fs_read")]);
+
+        assert!(actual.is_empty());
+    }
+
+    #[test]
+    fn test_try_from_text_call_id() {
+        let message = include_str!("./fixtures/tool_call_01.md");
+        let tool_call = ToolCallFull::try_from_text(message);
+        let actual = tool_call.first().unwrap().call_id.as_ref().unwrap();
+        assert!(actual.as_str().starts_with("forge_call_id_"));
+    }
+
+    #[test]
+    fn test_try_from_text_plain_text() {
+        let message =
+            "The `name` field of the config is described below:\n```json\n{\"version\": 1}\n```";
+        let actual = ToolCallFull::try_from_fenced(message, &[ToolName::new("forge_tool_fs_read")]);
+        assert!(actual.is_empty());
+    }
 }
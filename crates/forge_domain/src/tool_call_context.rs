@@ -1,9 +1,17 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
 use std::sync::Arc;
 
+use chrono::{DateTime, Utc};
 use derive_setters::Setters;
 use tokio::sync::mpsc::Sender;
+use tokio::sync::OwnedSemaphorePermit;
 
-use crate::{ChatResponse, TaskList};
+use crate::{
+    ChangeSummary, ChatResponse, CommandOutput, ConversationId, Reminder, ResourceCategory,
+    ResourceGovernor, ScaffoldTemplate, ShellRetry, SnapshotRef, TaskList, TimelineSpan,
+    ToolGateRule,
+};
 
 /// Type alias for Arc<Sender<Result<ChatResponse>>>
 type ArcSender = Arc<Sender<anyhow::Result<ChatResponse>>>;
@@ -12,13 +20,86 @@ type ArcSender = Arc<Sender<anyhow::Result<ChatResponse>>>;
 #[derive(Debug, Setters)]
 pub struct ToolCallContext {
     sender: Option<ArcSender>,
+    /// Conversation the tools run in, whose commands are looked up in the
+    /// journal to check the verification of a completion
+    pub conversation_id: Option<ConversationId>,
+    /// When the turn started, the commands journaled before it don't verify
+    /// a completion
+    pub turn_started_at: Option<DateTime<Utc>>,
     pub tasks: TaskList,
+    /// The most recent shell command that exited with a non-zero status, used
+    /// to explain subsequent tool failures
+    pub last_failed_command: Option<CommandOutput>,
+    /// Changes made to the file system by the tools executed so far
+    pub changes: ChangeSummary,
+    /// Whether files must be read before they may be modified
+    pub read_before_write: bool,
+    /// Files whose content the agent has seen in the conversation
+    pub read_files: BTreeSet<String>,
+    /// Rules deciding which tools the agent may call, enforced on the calls
+    /// as the model may call the tools it wasn't offered
+    pub tool_gates: Vec<ToolGateRule>,
+    /// Files read or changed in the conversation before the tools executed
+    /// so far, which unlock the gated tools
+    pub touched_files: BTreeSet<String>,
+    /// MCP servers disabled in the conversation, whose tools may not be called
+    /// even though the model may call the tools it wasn't offered
+    pub disabled_mcp_servers: BTreeSet<String>,
+    /// Whether the claims of the final answer are spot-checked against the
+    /// workspace
+    pub verify_answers: bool,
+    /// Snapshots captured by the tools executed so far
+    pub snapshots: Vec<SnapshotRef>,
+    /// Directory for intermediate artifacts of the tools, like full command
+    /// logs. The system temporary directory is used when not set.
+    pub scratch_dir: Option<PathBuf>,
+    /// Environment variables set for the shell commands of the conversation
+    pub env: BTreeMap<String, String>,
+    /// Retries of the shell commands that fail transiently
+    pub shell_retry: ShellRetry,
+    /// Time spent running the tools executed so far
+    pub timeline: Vec<TimelineSpan>,
+    /// Project templates the scaffold tool creates
+    pub scaffolds: Vec<ScaffoldTemplate>,
+    /// Reminders of the conversation not due yet, including the ones the
+    /// tools scheduled
+    pub reminders: Vec<Reminder>,
+    /// Limits on the tools running at once, shared with the other
+    /// conversations
+    pub governor: ResourceGovernor,
 }
 
 impl ToolCallContext {
     /// Creates a new ToolCallContext with default values
     pub fn new(task_list: TaskList) -> Self {
-        Self { sender: None, tasks: task_list }
+        Self {
+            sender: None,
+            conversation_id: None,
+            turn_started_at: None,
+            tasks: task_list,
+            last_failed_command: None,
+            changes: Default::default(),
+            read_before_write: false,
+            read_files: Default::default(),
+            tool_gates: Default::default(),
+            touched_files: Default::default(),
+            disabled_mcp_servers: Default::default(),
+            verify_answers: false,
+            snapshots: Default::default(),
+            scratch_dir: None,
+            env: Default::default(),
+            shell_retry: Default::default(),
+            timeline: Default::default(),
+            scaffolds: Default::default(),
+            reminders: Default::default(),
+            governor: Default::default(),
+        }
+    }
+
+    /// Waits until a tool consuming the resource may run, the resource being
+    /// released when the permit is dropped
+    pub async fn acquire(&self, resource: ResourceCategory) -> OwnedSemaphorePermit {
+        self.governor.acquire(resource).await
     }
 
     /// Send a message through the sender if available
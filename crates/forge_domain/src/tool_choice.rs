@@ -1,12 +1,39 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::ToolName;
 
-#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+/// Controls whether and which tools the model may call
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
 pub enum ToolChoice {
+    /// The model must not call any tool
     #[default]
     None,
+    /// The model decides whether to call tools
     Auto,
+    /// The model must call at least one tool
     Required,
+    /// The model must call the given tool
     Call(ToolName),
 }
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_tool_choice_from_yaml() {
+        let fixture = "- none\n- auto\n- required\n- call: forge_tool_fs_read\n";
+        let actual: Vec<ToolChoice> = serde_yml::from_str(fixture).unwrap();
+        let expected = vec![
+            ToolChoice::None,
+            ToolChoice::Auto,
+            ToolChoice::Required,
+            ToolChoice::Call(ToolName::new("forge_tool_fs_read")),
+        ];
+        assert_eq!(actual, expected);
+    }
+}
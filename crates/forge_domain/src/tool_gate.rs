@@ -0,0 +1,190 @@
+use std::collections::BTreeSet;
+
+use derive_setters::Setters;
+use glob::Pattern;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{AgentId, ToolDefinition, ToolName};
+
+/// Rule deciding in which phase of a conversation a group of tools is offered
+/// to the model, e.g. no write tools while planning or browser tools only
+/// once a frontend file was touched.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema, Setters)]
+#[setters(strip_option, into)]
+pub struct ToolGateRule {
+    /// Names of the tools the rule applies to. Globs are supported, e.g.
+    /// `mcp_browser_*`.
+    pub tools: Vec<String>,
+
+    /// Agents the tools are hidden from, e.g. `muse` to keep write tools out
+    /// of planning.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hide_for: Option<Vec<AgentId>>,
+
+    /// Globs of files one of which must have been read or changed in the
+    /// conversation before the tools are offered, e.g. `*.tsx`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after_touching: Option<Vec<String>>,
+}
+
+impl ToolGateRule {
+    fn applies_to(&self, tool: &ToolName) -> bool {
+        self.tools
+            .iter()
+            .filter_map(|glob| Pattern::new(glob).ok())
+            .any(|pattern| pattern.matches(tool.as_str()))
+    }
+
+    fn allows(&self, agent: &AgentId, touched: &BTreeSet<String>) -> bool {
+        let hidden = self
+            .hide_for
+            .as_ref()
+            .is_some_and(|agents| agents.contains(agent));
+        let locked = self.after_touching.as_ref().is_some_and(|globs| {
+            let patterns = globs
+                .iter()
+                .filter_map(|glob| Pattern::new(glob).ok())
+                .collect::<Vec<_>>();
+            !touched
+                .iter()
+                .any(|path| patterns.iter().any(|pattern| pattern.matches(path)))
+        });
+
+        !hidden && !locked
+    }
+}
+
+/// Filters the tool definitions sent on each request with the rules of the
+/// workflow. A tool is offered when every rule that applies to it allows it.
+pub struct ToolGate<'a> {
+    rules: &'a [ToolGateRule],
+}
+
+impl<'a> ToolGate<'a> {
+    pub fn new(rules: &'a [ToolGateRule]) -> Self {
+        Self { rules }
+    }
+
+    /// Keeps the tools the agent may use, given the files that were read or
+    /// changed in the conversation
+    pub fn filter(
+        &self,
+        tools: Vec<ToolDefinition>,
+        agent: &AgentId,
+        touched: &BTreeSet<String>,
+    ) -> Vec<ToolDefinition> {
+        tools
+            .into_iter()
+            .filter(|tool| self.allows(&tool.name, agent, touched))
+            .collect()
+    }
+
+    /// Whether the agent may use the tool, given the files that were read or
+    /// changed in the conversation
+    pub fn allows(&self, tool: &ToolName, agent: &AgentId, touched: &BTreeSet<String>) -> bool {
+        self.rules
+            .iter()
+            .filter(|rule| rule.applies_to(tool))
+            .all(|rule| rule.allows(agent, touched))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn tools() -> Vec<ToolDefinition> {
+        [
+            "forge_tool_fs_read",
+            "forge_tool_fs_patch",
+            "mcp_browser_click",
+        ]
+        .into_iter()
+        .map(ToolDefinition::new)
+        .collect()
+    }
+
+    fn rules() -> Vec<ToolGateRule> {
+        vec![
+            ToolGateRule::default()
+                .tools(vec!["forge_tool_fs_patch".to_string()])
+                .hide_for(vec![AgentId::new("muse")]),
+            ToolGateRule::default()
+                .tools(vec!["mcp_browser_*".to_string()])
+                .after_touching(vec!["*.tsx".to_string()]),
+        ]
+    }
+
+    fn names(tools: Vec<ToolDefinition>) -> Vec<String> {
+        tools
+            .into_iter()
+            .map(|tool| tool.name.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_hides_tools_from_agent() {
+        let rules = rules();
+        let fixture = ToolGate::new(&rules);
+
+        let actual = names(fixture.filter(tools(), &AgentId::new("muse"), &BTreeSet::new()));
+
+        let expected = vec!["forge_tool_fs_read"];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_unlocks_tools_after_touching_file() {
+        let rules = rules();
+        let fixture = ToolGate::new(&rules);
+        let touched = BTreeSet::from(["/project/src/App.tsx".to_string()]);
+
+        let actual = names(fixture.filter(tools(), &AgentId::new("forge"), &touched));
+
+        let expected = vec![
+            "forge_tool_fs_read",
+            "forge_tool_fs_patch",
+            "mcp_browser_click",
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_without_rules_all_tools_are_offered() {
+        let fixture = ToolGate::new(&[]);
+
+        let actual = names(fixture.filter(tools(), &AgentId::new("muse"), &BTreeSet::new()));
+
+        let expected = vec![
+            "forge_tool_fs_read",
+            "forge_tool_fs_patch",
+            "mcp_browser_click",
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_allows_tool() {
+        let rules = rules();
+        let fixture = ToolGate::new(&rules);
+
+        let actual = [
+            fixture.allows(
+                &ToolName::new("forge_tool_fs_patch"),
+                &AgentId::new("muse"),
+                &BTreeSet::new(),
+            ),
+            fixture.allows(
+                &ToolName::new("forge_tool_fs_read"),
+                &AgentId::new("muse"),
+                &BTreeSet::new(),
+            ),
+        ];
+
+        let expected = [false, true];
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::ToolName;
+
+/// Share of failed calls from which a tool is reported as failing often
+const HIGH_FAILURE_RATE: f64 = 0.25;
+
+/// Number of calls below which the failure rate of a tool isn't reported
+const MIN_CALLS: u64 = 5;
+
+/// Average tokens of the results from which a tool is reported as bloating
+/// the context
+const BLOATED_RESULT_TOKENS: u64 = 4_000;
+
+/// Outcome of a tool call, as recorded in the statistics
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolCallSample {
+    pub tool: ToolName,
+    pub success: bool,
+    pub duration: Duration,
+    /// Estimated tokens of the result sent back to the model
+    pub result_tokens: u64,
+}
+
+/// Usage of a tool across sessions
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolStats {
+    pub calls: u64,
+    pub failures: u64,
+    /// Time spent in the calls, in milliseconds
+    pub duration_ms: u64,
+    /// Estimated tokens of the results
+    pub result_tokens: u64,
+}
+
+impl ToolStats {
+    pub fn failure_rate(&self) -> f64 {
+        if self.calls == 0 {
+            return 0.0;
+        }
+        self.failures as f64 / self.calls as f64
+    }
+
+    pub fn average_duration(&self) -> Duration {
+        Duration::from_millis(self.duration_ms.checked_div(self.calls).unwrap_or_default())
+    }
+
+    pub fn average_result_tokens(&self) -> u64 {
+        self.result_tokens
+            .checked_div(self.calls)
+            .unwrap_or_default()
+    }
+
+    /// Whether the tool fails often enough for its configuration to be
+    /// reviewed
+    pub fn is_failing(&self) -> bool {
+        self.calls >= MIN_CALLS && self.failure_rate() >= HIGH_FAILURE_RATE
+    }
+
+    /// Whether the results of the tool fill the context, e.g. reads of whole
+    /// files that a range or a search would avoid
+    pub fn is_bloated(&self) -> bool {
+        self.average_result_tokens() >= BLOATED_RESULT_TOKENS
+    }
+}
+
+/// Usage of the tools accounted on this machine, persisted so that it can be
+/// reported across sessions
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolStatsLedger {
+    pub tools: BTreeMap<String, ToolStats>,
+}
+
+impl ToolStatsLedger {
+    pub fn record(&mut self, sample: &ToolCallSample) {
+        let stats = self.tools.entry(sample.tool.to_string()).or_default();
+        stats.calls += 1;
+        stats.failures += u64::from(!sample.success);
+        stats.duration_ms += sample.duration.as_millis() as u64;
+        stats.result_tokens += sample.result_tokens;
+    }
+
+    /// Statistics of the tools, the most used first
+    pub fn report(&self) -> Vec<(&str, &ToolStats)> {
+        let mut tools = self
+            .tools
+            .iter()
+            .map(|(name, stats)| (name.as_str(), stats))
+            .collect::<Vec<_>>();
+        tools.sort_by(|a, b| b.1.calls.cmp(&a.1.calls));
+        tools
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn sample(tool: &str, success: bool, millis: u64, result_tokens: u64) -> ToolCallSample {
+        ToolCallSample {
+            tool: ToolName::new(tool),
+            success,
+            duration: Duration::from_millis(millis),
+            result_tokens,
+        }
+    }
+
+    fn ledger() -> ToolStatsLedger {
+        let mut ledger = ToolStatsLedger::default();
+        ledger.record(&sample("forge_tool_fs_read", true, 10, 6_000));
+        ledger.record(&sample("forge_tool_fs_read", true, 30, 4_000));
+        for success in [true, false, false, true, true, true] {
+            ledger.record(&sample("forge_tool_fs_patch", success, 100, 50));
+        }
+        ledger
+    }
+
+    #[test]
+    fn test_record_accumulates_per_tool() {
+        let fixture = ledger();
+
+        let actual = fixture
+            .report()
+            .into_iter()
+            .map(|(name, stats)| {
+                (
+                    name,
+                    stats.calls,
+                    stats.failures,
+                    stats.average_duration(),
+                    stats.average_result_tokens(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let expected = vec![
+            ("forge_tool_fs_patch", 6, 2, Duration::from_millis(100), 50),
+            ("forge_tool_fs_read", 2, 0, Duration::from_millis(20), 5_000),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_flags_failing_and_bloated_tools() {
+        let fixture = ledger();
+
+        let actual = fixture
+            .report()
+            .into_iter()
+            .map(|(name, stats)| (name, stats.is_failing(), stats.is_bloated()))
+            .collect::<Vec<_>>();
+
+        let expected = vec![
+            ("forge_tool_fs_patch", true, false),
+            ("forge_tool_fs_read", false, true),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_failure_rate_needs_enough_calls() {
+        let mut fixture = ToolStatsLedger::default();
+        fixture.record(&sample("forge_tool_net_fetch", false, 500, 0));
+
+        let actual = fixture.tools["forge_tool_net_fetch"].clone();
+
+        assert_eq!(actual.failure_rate(), 1.0);
+        assert!(!actual.is_failing());
+    }
+}
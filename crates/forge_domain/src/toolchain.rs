@@ -0,0 +1,91 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Programs whose version the toolchain info tool reports, with the program
+/// and the arguments printing it
+pub const TOOLCHAIN_PROBES: &[(&str, &str, &[&str])] = &[
+    ("rustc", "rustc", &["--version"]),
+    ("cargo", "cargo", &["--version"]),
+    ("node", "node", &["--version"]),
+    ("npm", "npm", &["--version"]),
+    ("pnpm", "pnpm", &["--version"]),
+    ("yarn", "yarn", &["--version"]),
+    ("bun", "bun", &["--version"]),
+    ("deno", "deno", &["--version"]),
+    ("python", "python3", &["--version"]),
+    ("pip", "pip3", &["--version"]),
+    ("go", "go", &["version"]),
+    ("java", "java", &["-version"]),
+    ("ruby", "ruby", &["--version"]),
+    ("gcc", "gcc", &["--version"]),
+    ("clang", "clang", &["--version"]),
+    ("cmake", "cmake", &["--version"]),
+    ("docker", "docker", &["--version"]),
+];
+
+static VERSION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\d+\.\d+(\.\d+)?([-+][0-9A-Za-z.]+)?").unwrap());
+
+/// A program of a toolchain found on the PATH
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Toolchain {
+    pub name: String,
+    /// Version of the program, `None` when it couldn't be read from the
+    /// output
+    pub version: Option<String>,
+    /// First line printed by the program when asked for its version
+    pub output: String,
+}
+
+impl Toolchain {
+    /// Reads the toolchain from what the program printed when asked for its
+    /// version, Java printing it on stderr
+    pub fn parse(name: impl ToString, output: &str) -> Self {
+        let output = output
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .unwrap_or_default()
+            .to_string();
+        let version = VERSION
+            .find(&output)
+            .map(|version| version.as_str().to_string());
+        Self { name: name.to_string(), version, output }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let fixture = [
+            ("rustc", "rustc 1.80.0 (051478957 2024-07-21)\n"),
+            ("node", "v20.11.0\n"),
+            ("go", "go version go1.22.0 linux/amd64\n"),
+            (
+                "java",
+                "openjdk version \"21.0.1\" 2023-10-17\nOpenJDK Runtime Environment\n",
+            ),
+            (
+                "deno",
+                "deno 2.0.0-rc.1 (release candidate)\nv8 12.9.202.2\n",
+            ),
+        ];
+
+        let actual = fixture
+            .iter()
+            .map(|(name, output)| Toolchain::parse(name, output).version)
+            .collect::<Vec<_>>();
+
+        let expected = ["1.80.0", "20.11.0", "1.22.0", "21.0.1", "2.0.0-rc.1"]
+            .map(|version| Some(version.to_string()))
+            .to_vec();
+        assert_eq!(actual, expected);
+    }
+}
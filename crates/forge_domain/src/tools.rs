@@ -1,5 +1,5 @@
 #![allow(clippy::enum_variant_names)]
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::path::PathBuf;
 
 use convert_case::{Case, Casing};
@@ -13,7 +13,8 @@ use strum::IntoEnumIterator;
 use strum_macros::{AsRefStr, Display, EnumDiscriminants, EnumIter};
 
 use crate::{
-    Status, ToolCallArgumentError, ToolCallFull, ToolDefinition, ToolDescription, ToolName,
+    ResourceCategory, Status, ToolCallArgumentError, ToolCallFull, ToolDefinition, ToolDescription,
+    ToolName,
 };
 
 /// Enum representing all possible tool input types.
@@ -39,12 +40,31 @@ use crate::{
 pub enum Tools {
     ForgeToolFsRead(FSRead),
     ForgeToolFsCreate(FSWrite),
+    ForgeToolFsWriteBegin(FSWriteBegin),
+    ForgeToolFsWriteAppend(FSWriteAppend),
+    ForgeToolFsWriteCommit(FSWriteCommit),
     ForgeToolFsSearch(FSSearch),
     ForgeToolFsRemove(FSRemove),
+    ForgeToolFsRemoveDir(FSRemoveDir),
     ForgeToolFsPatch(FSPatch),
     ForgeToolFsUndo(FSUndo),
+    ForgeToolFsWait(FSWait),
     ForgeToolProcessShell(Shell),
+    ForgeToolProcessEnv(ProcessEnv),
     ForgeToolNetFetch(NetFetch),
+    ForgeToolGitLog(GitLog),
+    ForgeToolGitBlame(GitBlame),
+    ForgeToolGitCommitMessage(GitCommitMessage),
+    ForgeToolScaffold(Scaffold),
+    ForgeToolLint(Lint),
+    ForgeToolDiscoverTasks(DiscoverTasks),
+    ForgeToolToolchainInfo(ToolchainInfo),
+    ForgeToolTimeGet(TimeGet),
+    ForgeToolReminderSchedule(ScheduleReminder),
+    ForgeToolCalculate(Calculate),
+    ForgeToolArchiveList(ArchiveList),
+    ForgeToolArchiveExtract(ArchiveExtract),
+    ForgeToolImageDiff(ImageDiff),
     ForgeToolFollowup(Followup),
     ForgeToolAttemptCompletion(AttemptCompletion),
     ForgeToolTaskListAppend(TaskListAppend),
@@ -121,6 +141,69 @@ pub struct FSWrite {
     #[serde(default)]
     #[serde(skip_serializing_if = "is_default")]
     pub overwrite: bool,
+    /// Set to true only when the content is intentionally larger than the
+    /// write size limit, such as generated fixtures or data files. Writes
+    /// above the limit are rejected otherwise.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub allow_large: bool,
+    /// One sentence explanation as to why this specific tool is being used, and
+    /// how it contributes to the goal.
+    #[serde(default)]
+    pub explanation: Option<String>,
+}
+
+/// Starts writing a file in several parts, for content too large to generate
+/// in a single `forge_tool_fs_create` call. Send the content in order with
+/// `forge_tool_fs_write_append`, then write the file with
+/// `forge_tool_fs_write_commit`. Nothing is written before the commit, and
+/// starting again discards the parts sent so far.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, ToolDescription, PartialEq)]
+pub struct FSWriteBegin {
+    /// The path of the file to write to (absolute path required)
+    pub path: String,
+
+    /// If set to true, an existing file will be overwritten by the commit. If
+    /// not set and the file exists, an error is returned right away.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub overwrite: bool,
+    /// Set to true only when the content is intentionally larger than the
+    /// write size limit, such as generated fixtures or data files. Writes
+    /// above the limit are rejected otherwise.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub allow_large: bool,
+    /// One sentence explanation as to why this specific tool is being used, and
+    /// how it contributes to the goal.
+    #[serde(default)]
+    pub explanation: Option<String>,
+}
+
+/// Appends the next part of the content of a file started with
+/// `forge_tool_fs_write_begin`. Parts are joined exactly as they are sent, so
+/// include the line breaks between them.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, ToolDescription, PartialEq)]
+pub struct FSWriteAppend {
+    /// The path of the file being written (absolute path required)
+    pub path: String,
+
+    /// The next part of the content of the file
+    pub content: String,
+    /// One sentence explanation as to why this specific tool is being used, and
+    /// how it contributes to the goal.
+    #[serde(default)]
+    pub explanation: Option<String>,
+}
+
+/// Writes the file assembled from the parts sent with
+/// `forge_tool_fs_write_append`. The content is checked for syntax errors and
+/// the file is snapshotted first, so that it can be restored with
+/// `forge_tool_fs_undo`.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, ToolDescription, PartialEq)]
+pub struct FSWriteCommit {
+    /// The path of the file being written (absolute path required)
+    pub path: String,
     /// One sentence explanation as to why this specific tool is being used, and
     /// how it contributes to the goal.
     #[serde(default)]
@@ -166,12 +249,38 @@ pub struct FSSearch {
 }
 
 /// Request to remove a file at the specified path. Use this when you need to
-/// delete an existing file. The path must be absolute. This operation cannot
-/// be undone, so use it carefully.
+/// delete an existing file. The path must be absolute. The file is moved to
+/// the trash unless `permanent` is set, and can be restored with
+/// `forge_tool_fs_undo`.
 #[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, ToolDescription, PartialEq)]
 pub struct FSRemove {
     /// The path of the file to remove (absolute path required)
     pub path: String,
+    /// If set to true, the file is deleted permanently instead of being moved
+    /// to the trash. Only use it when explicitly asked to.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub permanent: bool,
+    /// One sentence explanation as to why this specific tool is being used, and
+    /// how it contributes to the goal.
+    #[serde(default)]
+    pub explanation: Option<String>,
+}
+
+/// Recursively removes a directory and everything in it. The user is asked to
+/// approve the removal first. Files that are not ignored by .gitignore are
+/// snapshotted so that each of them can be restored with
+/// `forge_tool_fs_undo`. The directory is moved to the trash unless
+/// `permanent` is set. Use `forge_tool_fs_remove` for single files.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, ToolDescription, PartialEq)]
+pub struct FSRemoveDir {
+    /// The path of the directory to remove (absolute path required)
+    pub path: String,
+    /// If set to true, the directory is deleted permanently instead of being
+    /// moved to the trash. Only use it when explicitly asked to.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub permanent: bool,
     /// One sentence explanation as to why this specific tool is being used, and
     /// how it contributes to the goal.
     #[serde(default)]
@@ -267,6 +376,13 @@ pub struct FSPatch {
     /// prepend/append, or target line for swap operations)
     pub content: String,
 
+    /// Set to true only when the content is intentionally larger than the
+    /// write size limit, such as generated fixtures or data files. Writes
+    /// above the limit are rejected otherwise.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub allow_large: bool,
+
     /// One sentence explanation as to why this specific tool is being used, and
     /// how it contributes to the goal.
     #[serde(default)]
@@ -286,6 +402,26 @@ pub struct FSUndo {
     pub explanation: Option<String>,
 }
 
+/// Waits until a file or directory is created, modified or removed, or until
+/// the timeout elapses. Use it instead of reading a file repeatedly when the
+/// next step depends on a change made outside of the agent, e.g. a build
+/// watcher rewriting its output or the user editing a file. The path is
+/// checked every half second and the wait lasts at most 10 minutes.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, ToolDescription, PartialEq)]
+pub struct FSWait {
+    /// The absolute path of the file or directory to watch. It doesn't need
+    /// to exist, its creation is reported as a change.
+    pub path: String,
+    /// Optional number of seconds to wait for a change (default: 60, at most
+    /// 600).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+    /// One sentence explanation as to why this specific tool is being used, and
+    /// how it contributes to the goal.
+    #[serde(default)]
+    pub explanation: Option<String>,
+}
+
 /// Executes shell commands with safety measures using restricted bash (rbash).
 /// Prevents potentially harmful operations like absolute path execution and
 /// directory changes. Use for file system interaction, running utilities,
@@ -314,6 +450,25 @@ pub struct Shell {
     pub explanation: Option<String>,
 }
 
+/// Lists the environment variables of the process running the shell commands,
+/// or reads one of them. Names are always listed, values are only shown for
+/// paths, locale, toolchain and proxy settings (e.g. PATH, LANG, CARGO_*,
+/// JAVA_HOME, HTTPS_PROXY), without credentials, and are redacted otherwise.
+/// Use it instead of printing variables with the shell, which could reveal
+/// secrets.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, ToolDescription, PartialEq)]
+pub struct ProcessEnv {
+    /// Optional name of the variable to read (default: all the variables are
+    /// listed).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// One sentence explanation as to why this specific tool is being used, and
+    /// how it contributes to the goal.
+    #[serde(default)]
+    pub explanation: Option<String>,
+}
+
 /// Input type for the net fetch tool
 #[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, ToolDescription, PartialEq)]
 pub struct NetFetch {
@@ -331,6 +486,286 @@ pub struct NetFetch {
     pub explanation: Option<String>,
 }
 
+/// Lists the commits of the git history of the workspace, most recent first,
+/// each with its hash, author, date and summary. Use it to understand why code
+/// changed, who changed it and when, or to gather the changes that go into a
+/// changelog. Narrow the history down to a file or directory with `path` and to
+/// a revision range such as `v1.2.0..HEAD` with `range`. Returns the 50 most
+/// recent commits unless `max_count` is provided.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, ToolDescription, PartialEq)]
+pub struct GitLog {
+    /// Optional path of a file or directory whose history to list, always
+    /// provide absolute paths.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+
+    /// Optional revision range to list, such as `main..HEAD` or `v1.0.0..`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub range: Option<String>,
+
+    /// Optional maximum number of commits to list (default: 50).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_count: Option<u64>,
+
+    /// One sentence explanation as to why this specific tool is being used, and
+    /// how it contributes to the goal.
+    #[serde(default)]
+    pub explanation: Option<String>,
+}
+
+/// Shows the commit that last changed each line of a file, with its hash,
+/// author, date and summary. Use it to find out why specific lines are the way
+/// they are before changing them, then look at the history around those
+/// commits with the git log tool. Consecutive lines changed by the same commit
+/// are grouped together. Blames the first 2,000 lines unless a range is
+/// specified with the start_line and end_line parameters.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, ToolDescription, PartialEq)]
+pub struct GitBlame {
+    /// The path of the file to blame, always provide absolute paths.
+    pub path: String,
+
+    /// Optional start position in lines (1-based).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_line: Option<i32>,
+
+    /// Optional end position in lines (inclusive).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<i32>,
+
+    /// One sentence explanation as to why this specific tool is being used, and
+    /// how it contributes to the goal.
+    #[serde(default)]
+    pub explanation: Option<String>,
+}
+
+/// Reads the changes staged in the git repository of the workspace, along
+/// with the subjects of the most recent commits, to write the message of the
+/// commit recording them. Write the message following the conventional commits
+/// specification: a `type(scope): subject` header where the type is one of
+/// feat, fix, docs, style, refactor, perf, test, build, ci, chore or revert,
+/// then a blank line and a bullet for each notable change. Match the scopes and
+/// the tone of the recent commits. Wrap the message in <commit_message> tags.
+/// Never commits the changes itself, the user reviews the message first.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, ToolDescription, PartialEq)]
+pub struct GitCommitMessage {
+    /// One sentence explanation as to why this specific tool is being used, and
+    /// how it contributes to the goal.
+    #[serde(default)]
+    pub explanation: Option<String>,
+}
+
+/// Creates the directories and files of a project template of the workflow,
+/// e.g. a new service or module, with the variables of the template filled in.
+/// Prefer it to writing the files one by one whenever a template matches what
+/// needs to be created, so that the layout is the same every time. Nothing is
+/// created when one of the files already exists. Call it with an empty
+/// template name to list the available templates and their variables.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, ToolDescription, PartialEq)]
+pub struct Scaffold {
+    /// Name of the template to create.
+    pub template: String,
+
+    /// Values of the variables of the template by name. The variables with a
+    /// default value may be omitted.
+    #[serde(default)]
+    pub variables: BTreeMap<String, String>,
+
+    /// Optional absolute path of the directory to create the template in
+    /// (default: the current working directory).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+
+    /// One sentence explanation as to why this specific tool is being used, and
+    /// how it contributes to the goal.
+    #[serde(default)]
+    pub explanation: Option<String>,
+}
+
+/// Runs the linters configured in the project on the given files and returns
+/// their diagnostics, each with its path, line, rule and severity. Rust files
+/// are checked with clippy and JavaScript or TypeScript files with eslint,
+/// when the project has a configuration for it. Use it after changing files to
+/// catch the problems the linters would report. Set fix to apply the fixes the
+/// linters mark as safe, the remaining diagnostics are returned afterwards.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, ToolDescription, PartialEq)]
+pub struct Lint {
+    /// The absolute paths of the files to lint.
+    pub paths: Vec<String>,
+
+    /// If set to true, the fixes the linters mark as safe are applied to the
+    /// files before the remaining diagnostics are returned.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub fix: bool,
+
+    /// One sentence explanation as to why this specific tool is being used, and
+    /// how it contributes to the goal.
+    #[serde(default)]
+    pub explanation: Option<String>,
+}
+
+/// Lists the targets that can be run in the project with their commands and
+/// descriptions: the packages, binaries and examples of the cargo workspace,
+/// the scripts of package.json and the targets of the Makefile and the
+/// justfile. Use it before building, testing or running the project to pick
+/// the commands the project defines instead of guessing them.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, ToolDescription, PartialEq)]
+pub struct DiscoverTasks {
+    /// Optional absolute path of the directory of the project (default: the
+    /// current working directory).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+
+    /// One sentence explanation as to why this specific tool is being used, and
+    /// how it contributes to the goal.
+    #[serde(default)]
+    pub explanation: Option<String>,
+}
+
+/// Reports the versions of the toolchains installed on the PATH: rustc, cargo,
+/// node, npm, pnpm, yarn, bun, deno, python, pip, go, java, ruby, gcc, clang,
+/// cmake and docker. Use it before writing code or commands that depend on the
+/// version of a language or tool, e.g. language features, flags or lockfile
+/// formats, instead of assuming the latest version is installed. Versions are
+/// read once per session, set refresh after installing or upgrading a tool.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, ToolDescription, PartialEq)]
+pub struct ToolchainInfo {
+    /// If set to true, the versions are read again instead of being reused
+    /// from earlier in the session.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub refresh: bool,
+
+    /// One sentence explanation as to why this specific tool is being used, and
+    /// how it contributes to the goal.
+    #[serde(default)]
+    pub explanation: Option<String>,
+}
+
+/// Reports the current date and time, or converts a given time, in a
+/// timezone: the local one by default, an IANA name like Europe/Paris or an
+/// offset like +05:30. Set add to shift the time by a duration, e.g. 2h30m,
+/// -3d or 1w. Use it for the dates written into files, like changelog entries
+/// or report headers, and for time calculations instead of guessing the
+/// current date.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, ToolDescription, PartialEq)]
+pub struct TimeGet {
+    /// Optional timezone the time is reported in: local (default), UTC, an
+    /// IANA name like America/New_York or an offset like +05:30.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// Optional time to convert instead of the current time, in RFC 3339 like
+    /// 2024-05-01T09:00:00Z or as YYYY-MM-DD HH:MM in from_timezone.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<String>,
+    /// Optional timezone of a time written without an offset (default: the
+    /// timezone it's reported in).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_timezone: Option<String>,
+    /// Optional duration added to the time, in weeks (w), days (d), hours
+    /// (h), minutes (m) and seconds (s), e.g. 90m, 1d12h or -2w.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub add: Option<String>,
+    /// One sentence explanation as to why this specific tool is being used, and
+    /// how it contributes to the goal.
+    #[serde(default)]
+    pub explanation: Option<String>,
+}
+
+/// Schedules a reminder posted into the conversation once it's due, e.g. to
+/// check on a deployment or a long running job later. Set either after or at.
+/// The reminder is shown once it's due and delivered with the next request, it
+/// doesn't resume a finished task by itself. Reminders can be due at most 30
+/// days ahead.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, ToolDescription, PartialEq)]
+pub struct ScheduleReminder {
+    /// The message posted when the reminder is due.
+    pub message: String,
+    /// Optional delay before the reminder is due, e.g. 30m or 2h.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+    /// Optional time the reminder is due at, in RFC 3339 or as YYYY-MM-DD
+    /// HH:MM in the local timezone.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub at: Option<String>,
+    /// One sentence explanation as to why this specific tool is being used, and
+    /// how it contributes to the goal.
+    #[serde(default)]
+    pub explanation: Option<String>,
+}
+
+/// Evaluates an arithmetic expression exactly, with fractions instead of
+/// floating point rounding. Use it instead of computing by yourself for
+/// version bumps, byte budgets, offsets and any other number written into
+/// files. Supports + - * / % (remainder) ^ (whole powers), parentheses,
+/// abs, floor, ceil, round, min and max, hexadecimal (0x1F), octal (0o17) and
+/// binary (0b101) numbers and digit separators (1_000). Sizes in B, KB, MB, GB,
+/// TB, KiB, MiB, GiB, TiB and durations in ms, s, min, h, d, w can be added
+/// together and converted with in, e.g. 3.5 GiB in MB or 2h + 45min in s.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, ToolDescription, PartialEq)]
+pub struct Calculate {
+    /// The expression to evaluate, e.g. (1 GB - 200 MB) / 3 in MiB.
+    pub expression: String,
+    /// One sentence explanation as to why this specific tool is being used, and
+    /// how it contributes to the goal.
+    #[serde(default)]
+    pub explanation: Option<String>,
+}
+
+/// Lists the entries of a zip, tar or tar.gz archive with their sizes, without
+/// extracting it. Use it to inspect downloaded release artifacts or vendored
+/// bundles before extracting them. The path must be absolute.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, ToolDescription, PartialEq)]
+pub struct ArchiveList {
+    /// The absolute path of the archive.
+    pub path: String,
+    /// One sentence explanation as to why this specific tool is being used, and
+    /// how it contributes to the goal.
+    #[serde(default)]
+    pub explanation: Option<String>,
+}
+
+/// Extracts a zip, tar or tar.gz archive into the scratch directory of the
+/// conversation and reports the directory, whose files can then be read and
+/// searched with the other tools. Use it instead of unzip or tar commands.
+/// Archives with entries pointing outside of the directory are refused, links
+/// are skipped and archives larger than 512 MiB once extracted are rejected.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, ToolDescription, PartialEq)]
+pub struct ArchiveExtract {
+    /// The absolute path of the archive.
+    pub path: String,
+    /// Optional directory inside the scratch directory to extract into
+    /// (default: the name of the archive without its extension).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination: Option<String>,
+    /// One sentence explanation as to why this specific tool is being used, and
+    /// how it contributes to the goal.
+    #[serde(default)]
+    pub explanation: Option<String>,
+}
+
+/// Compares two images, e.g. screenshots taken before and after a change, and
+/// reports the share of pixels that differ and where, along with the second
+/// image faded with the changed pixels highlighted in red. Use it for visual
+/// regression checks of frontend changes. PNG, JPEG and WebP images are
+/// supported and the paths must be absolute.
+#[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, ToolDescription, PartialEq)]
+pub struct ImageDiff {
+    /// The absolute path of the image before the change.
+    pub before: String,
+    /// The absolute path of the image after the change.
+    pub after: String,
+    /// Optional largest difference of a color channel, from 0 to 255, below
+    /// which a pixel is considered unchanged, to ignore anti-aliasing and
+    /// compression noise (default: 16).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tolerance: Option<u8>,
+    /// One sentence explanation as to why this specific tool is being used, and
+    /// how it contributes to the goal.
+    #[serde(default)]
+    pub explanation: Option<String>,
+}
+
 /// Use this tool when you encounter ambiguities, need clarification, or require
 /// more details to proceed effectively. Use this tool judiciously to maintain a
 /// balance between gathering necessary information and avoiding excessive
@@ -382,13 +817,31 @@ pub struct Followup {
 /// result in code corruption and system failure. Before using this tool, you
 /// must ask yourself in <forge_thinking></forge_thinking> tags if you've
 /// confirmed from the user that any previous tool uses were successful. If not,
-/// then DO NOT use this tool.
+/// then DO NOT use this tool. Report what you changed, the commands you ran to
+/// verify the changes and the risks left open. The verification commands are
+/// checked against the commands actually run in the conversation, and the user
+/// is warned about the completions that weren't verified.
 #[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema, ToolDescription, PartialEq)]
 pub struct AttemptCompletion {
     /// The result of the task. Formulate this result in a way that is final and
     /// does not require further input from the user. Don't end your result with
     /// questions or offers for further assistance.
     pub result: String,
+    /// What was changed to complete the task, one short sentence per change.
+    /// Leave it empty when nothing was changed.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub changes: Vec<String>,
+    /// The commands run to verify the changes, e.g. the tests, exactly as they
+    /// were executed. Leave it empty when the changes weren't verified.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub verification: Vec<String>,
+    /// The risks left open, like the cases that weren't tested or the
+    /// follow-ups the user should know about.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_default")]
+    pub risks: Vec<String>,
 }
 
 /// Add a new task to the end of the task list. Tasks are stored in conversation
@@ -570,14 +1023,33 @@ impl ToolDescription for Tools {
         match self {
             Tools::ForgeToolFsPatch(v) => v.description(),
             Tools::ForgeToolProcessShell(v) => v.description(),
+            Tools::ForgeToolProcessEnv(v) => v.description(),
             Tools::ForgeToolFollowup(v) => v.description(),
             Tools::ForgeToolNetFetch(v) => v.description(),
+            Tools::ForgeToolGitLog(v) => v.description(),
+            Tools::ForgeToolGitBlame(v) => v.description(),
+            Tools::ForgeToolGitCommitMessage(v) => v.description(),
+            Tools::ForgeToolScaffold(v) => v.description(),
+            Tools::ForgeToolLint(v) => v.description(),
+            Tools::ForgeToolDiscoverTasks(v) => v.description(),
+            Tools::ForgeToolToolchainInfo(v) => v.description(),
+            Tools::ForgeToolTimeGet(v) => v.description(),
+            Tools::ForgeToolReminderSchedule(v) => v.description(),
+            Tools::ForgeToolCalculate(v) => v.description(),
+            Tools::ForgeToolArchiveList(v) => v.description(),
+            Tools::ForgeToolArchiveExtract(v) => v.description(),
+            Tools::ForgeToolImageDiff(v) => v.description(),
             Tools::ForgeToolAttemptCompletion(v) => v.description(),
             Tools::ForgeToolFsSearch(v) => v.description(),
             Tools::ForgeToolFsRead(v) => v.description(),
             Tools::ForgeToolFsRemove(v) => v.description(),
+            Tools::ForgeToolFsRemoveDir(v) => v.description(),
             Tools::ForgeToolFsUndo(v) => v.description(),
+            Tools::ForgeToolFsWait(v) => v.description(),
             Tools::ForgeToolFsCreate(v) => v.description(),
+            Tools::ForgeToolFsWriteBegin(v) => v.description(),
+            Tools::ForgeToolFsWriteAppend(v) => v.description(),
+            Tools::ForgeToolFsWriteCommit(v) => v.description(),
             Tools::ForgeToolTaskListAppend(v) => v.description(),
             Tools::ForgeToolTaskListAppendMultiple(v) => v.description(),
             Tools::ForgeToolTaskListUpdate(v) => v.description(),
@@ -609,14 +1081,33 @@ impl Tools {
         match self {
             Tools::ForgeToolFsPatch(_) => gen.into_root_schema_for::<FSPatch>(),
             Tools::ForgeToolProcessShell(_) => gen.into_root_schema_for::<Shell>(),
+            Tools::ForgeToolProcessEnv(_) => gen.into_root_schema_for::<ProcessEnv>(),
             Tools::ForgeToolFollowup(_) => gen.into_root_schema_for::<Followup>(),
             Tools::ForgeToolNetFetch(_) => gen.into_root_schema_for::<NetFetch>(),
+            Tools::ForgeToolGitLog(_) => gen.into_root_schema_for::<GitLog>(),
+            Tools::ForgeToolGitBlame(_) => gen.into_root_schema_for::<GitBlame>(),
+            Tools::ForgeToolGitCommitMessage(_) => gen.into_root_schema_for::<GitCommitMessage>(),
+            Tools::ForgeToolScaffold(_) => gen.into_root_schema_for::<Scaffold>(),
+            Tools::ForgeToolLint(_) => gen.into_root_schema_for::<Lint>(),
+            Tools::ForgeToolDiscoverTasks(_) => gen.into_root_schema_for::<DiscoverTasks>(),
+            Tools::ForgeToolToolchainInfo(_) => gen.into_root_schema_for::<ToolchainInfo>(),
+            Tools::ForgeToolTimeGet(_) => gen.into_root_schema_for::<TimeGet>(),
+            Tools::ForgeToolReminderSchedule(_) => gen.into_root_schema_for::<ScheduleReminder>(),
+            Tools::ForgeToolCalculate(_) => gen.into_root_schema_for::<Calculate>(),
+            Tools::ForgeToolArchiveList(_) => gen.into_root_schema_for::<ArchiveList>(),
+            Tools::ForgeToolArchiveExtract(_) => gen.into_root_schema_for::<ArchiveExtract>(),
+            Tools::ForgeToolImageDiff(_) => gen.into_root_schema_for::<ImageDiff>(),
             Tools::ForgeToolAttemptCompletion(_) => gen.into_root_schema_for::<AttemptCompletion>(),
             Tools::ForgeToolFsSearch(_) => gen.into_root_schema_for::<FSSearch>(),
             Tools::ForgeToolFsRead(_) => gen.into_root_schema_for::<FSRead>(),
             Tools::ForgeToolFsRemove(_) => gen.into_root_schema_for::<FSRemove>(),
+            Tools::ForgeToolFsRemoveDir(_) => gen.into_root_schema_for::<FSRemoveDir>(),
             Tools::ForgeToolFsUndo(_) => gen.into_root_schema_for::<FSUndo>(),
+            Tools::ForgeToolFsWait(_) => gen.into_root_schema_for::<FSWait>(),
             Tools::ForgeToolFsCreate(_) => gen.into_root_schema_for::<FSWrite>(),
+            Tools::ForgeToolFsWriteBegin(_) => gen.into_root_schema_for::<FSWriteBegin>(),
+            Tools::ForgeToolFsWriteAppend(_) => gen.into_root_schema_for::<FSWriteAppend>(),
+            Tools::ForgeToolFsWriteCommit(_) => gen.into_root_schema_for::<FSWriteCommit>(),
             Tools::ForgeToolTaskListAppend(_) => gen.into_root_schema_for::<TaskListAppend>(),
             Tools::ForgeToolTaskListAppendMultiple(_) => {
                 gen.into_root_schema_for::<TaskListAppendMultiple>()
@@ -644,6 +1135,99 @@ impl Tools {
         .iter()
         .any(|v| v.to_string().to_case(Case::Snake).eq(tool_name.as_str()))
     }
+
+    /// Tools that can't change the workspace nor wait on the user
+    pub fn is_read_only(tool_name: &ToolName) -> bool {
+        [
+            ToolsDiscriminants::ForgeToolFsRead,
+            ToolsDiscriminants::ForgeToolFsSearch,
+            ToolsDiscriminants::ForgeToolFsWait,
+            ToolsDiscriminants::ForgeToolNetFetch,
+            ToolsDiscriminants::ForgeToolGitLog,
+            ToolsDiscriminants::ForgeToolGitBlame,
+            ToolsDiscriminants::ForgeToolGitCommitMessage,
+            ToolsDiscriminants::ForgeToolDiscoverTasks,
+            ToolsDiscriminants::ForgeToolProcessEnv,
+            ToolsDiscriminants::ForgeToolToolchainInfo,
+            ToolsDiscriminants::ForgeToolTimeGet,
+            ToolsDiscriminants::ForgeToolCalculate,
+            ToolsDiscriminants::ForgeToolArchiveList,
+            ToolsDiscriminants::ForgeToolImageDiff,
+            ToolsDiscriminants::ForgeToolAttemptCompletion,
+        ]
+        .iter()
+        .any(|v| v.name() == *tool_name)
+    }
+
+    /// Paths of the files and directories the call operates on, as supplied
+    /// by the model, so that they can be resolved before the call is executed
+    pub fn paths_mut(&mut self) -> Vec<&mut String> {
+        match self {
+            Tools::ForgeToolFsRead(input) => vec![&mut input.path],
+            Tools::ForgeToolFsCreate(input) => vec![&mut input.path],
+            Tools::ForgeToolFsWriteBegin(input) => vec![&mut input.path],
+            Tools::ForgeToolFsWriteAppend(input) => vec![&mut input.path],
+            Tools::ForgeToolFsWriteCommit(input) => vec![&mut input.path],
+            Tools::ForgeToolFsSearch(input) => vec![&mut input.path],
+            Tools::ForgeToolFsRemove(input) => vec![&mut input.path],
+            Tools::ForgeToolFsRemoveDir(input) => vec![&mut input.path],
+            Tools::ForgeToolFsPatch(input) => vec![&mut input.path],
+            Tools::ForgeToolFsUndo(input) => vec![&mut input.path],
+            Tools::ForgeToolFsWait(input) => vec![&mut input.path],
+            Tools::ForgeToolGitLog(input) => input.path.iter_mut().collect(),
+            Tools::ForgeToolGitBlame(input) => vec![&mut input.path],
+            Tools::ForgeToolScaffold(input) => input.path.iter_mut().collect(),
+            Tools::ForgeToolLint(input) => input.paths.iter_mut().collect(),
+            Tools::ForgeToolDiscoverTasks(input) => input.path.iter_mut().collect(),
+            Tools::ForgeToolArchiveList(input) => vec![&mut input.path],
+            Tools::ForgeToolArchiveExtract(input) => vec![&mut input.path],
+            Tools::ForgeToolImageDiff(input) => vec![&mut input.before, &mut input.after],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Resource the call mostly consumes, `None` for the calls that only
+    /// exchange with the user, update the conversation or wait, so that they
+    /// don't hold back the other calls
+    pub fn resource(&self) -> Option<ResourceCategory> {
+        match self {
+            Tools::ForgeToolProcessShell(_) | Tools::ForgeToolImageDiff(_) => {
+                Some(ResourceCategory::Cpu)
+            }
+            Tools::ForgeToolLint(_)
+            | Tools::ForgeToolDiscoverTasks(_)
+            | Tools::ForgeToolToolchainInfo(_) => Some(ResourceCategory::Probe),
+            Tools::ForgeToolFsRead(_)
+            | Tools::ForgeToolFsCreate(_)
+            | Tools::ForgeToolFsWriteBegin(_)
+            | Tools::ForgeToolFsWriteAppend(_)
+            | Tools::ForgeToolFsWriteCommit(_)
+            | Tools::ForgeToolFsSearch(_)
+            | Tools::ForgeToolFsRemove(_)
+            | Tools::ForgeToolFsRemoveDir(_)
+            | Tools::ForgeToolFsPatch(_)
+            | Tools::ForgeToolFsUndo(_)
+            | Tools::ForgeToolGitLog(_)
+            | Tools::ForgeToolGitBlame(_)
+            | Tools::ForgeToolGitCommitMessage(_)
+            | Tools::ForgeToolScaffold(_)
+            | Tools::ForgeToolArchiveList(_)
+            | Tools::ForgeToolArchiveExtract(_) => Some(ResourceCategory::Io),
+            Tools::ForgeToolNetFetch(_) => Some(ResourceCategory::Network),
+            Tools::ForgeToolFsWait(_)
+            | Tools::ForgeToolProcessEnv(_)
+            | Tools::ForgeToolTimeGet(_)
+            | Tools::ForgeToolReminderSchedule(_)
+            | Tools::ForgeToolCalculate(_)
+            | Tools::ForgeToolFollowup(_)
+            | Tools::ForgeToolAttemptCompletion(_)
+            | Tools::ForgeToolTaskListAppend(_)
+            | Tools::ForgeToolTaskListAppendMultiple(_)
+            | Tools::ForgeToolTaskListUpdate(_)
+            | Tools::ForgeToolTaskListList(_)
+            | Tools::ForgeToolTaskListClear(_) => None,
+        }
+    }
 }
 
 impl ToolsDiscriminants {
@@ -739,6 +1323,27 @@ mod tests {
         insta::assert_snapshot!(tools);
     }
 
+    #[test]
+    fn test_paths_mut() {
+        let mut fixture = Tools::ForgeToolLint(Lint {
+            paths: vec!["src/lib.rs".to_string(), "/project/README.md".to_string()],
+            fix: false,
+            explanation: None,
+        });
+
+        for path in fixture.paths_mut() {
+            *path = path.to_uppercase();
+        }
+
+        let actual = fixture;
+        let expected = Tools::ForgeToolLint(Lint {
+            paths: vec!["SRC/LIB.RS".to_string(), "/PROJECT/README.MD".to_string()],
+            fix: false,
+            explanation: None,
+        });
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn test_tool_deser_failure() {
         let tool_call = ToolCallFull::new("forge_tool_fs_create".into());
@@ -77,12 +77,14 @@ mod drop_reasoning_details;
 mod image_handling;
 mod reasoning_normalizer;
 mod set_model;
+mod slim_tool_schemas;
 mod transform_tool_calls;
 
 pub use drop_reasoning_details::DropReasoningDetails;
 pub use image_handling::ImageHandling;
 pub use reasoning_normalizer::ReasoningNormalizer;
 pub use set_model::SetModel;
+pub use slim_tool_schemas::SlimToolSchemas;
 pub use transform_tool_calls::TransformToolCalls;
 
 #[cfg(test)]
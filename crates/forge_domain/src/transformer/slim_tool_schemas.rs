@@ -0,0 +1,275 @@
+use serde_json::{Map, Value};
+
+use super::Transformer;
+use crate::{Context, ToolDefinition};
+
+/// Transformer that reduces the size of the tool schemas sent with every
+/// request. Titles and descriptions that repeat the tool description are
+/// removed, and definitions that don't reference other definitions are inlined
+/// where they are used.
+#[derive(Default)]
+pub struct SlimToolSchemas;
+
+impl Transformer for SlimToolSchemas {
+    type Value = Context;
+
+    fn transform(&mut self, mut value: Self::Value) -> Self::Value {
+        value.tools = value.tools.into_iter().map(slim_tool).collect();
+        value
+    }
+}
+
+fn slim_tool(tool: ToolDefinition) -> ToolDefinition {
+    let Ok(mut schema) = serde_json::to_value(&tool.input_schema) else {
+        return tool;
+    };
+
+    if let Some(root) = schema.as_object_mut() {
+        // The description of the tool is already sent next to its schema
+        let duplicate = root
+            .get("description")
+            .and_then(Value::as_str)
+            .is_some_and(|description| same_text(description, &tool.description));
+        if duplicate {
+            root.remove("description");
+        }
+
+        inline_definitions(root);
+    }
+    strip_titles(&mut schema);
+
+    match serde_json::from_value(schema) {
+        Ok(input_schema) => ToolDefinition { input_schema, ..tool },
+        Err(_) => tool,
+    }
+}
+
+/// Compares two texts ignoring differences in whitespace, since doc comments
+/// keep their line breaks in tool descriptions but not in schemas.
+fn same_text(a: &str, b: &str) -> bool {
+    a.split_whitespace().eq(b.split_whitespace())
+}
+
+/// Replaces references to definitions that don't reference other definitions
+/// with the definitions themselves. Definitions that become unused are
+/// removed, while recursive ones are kept.
+fn inline_definitions(root: &mut Map<String, Value>) {
+    let Some(Value::Object(mut definitions)) = root.remove("definitions") else {
+        return;
+    };
+
+    loop {
+        let trivial = definitions
+            .iter()
+            .filter(|(_, schema)| !has_reference(schema))
+            .map(|(name, schema)| (format!("#/definitions/{name}"), schema.clone()))
+            .collect::<Map<_, _>>();
+        if trivial.is_empty() {
+            break;
+        }
+
+        definitions.retain(|name, _| !trivial.contains_key(&format!("#/definitions/{name}")));
+        for schema in root.values_mut().chain(definitions.values_mut()) {
+            replace_references(schema, &trivial);
+        }
+    }
+
+    if !definitions.is_empty() {
+        root.insert("definitions".to_string(), Value::Object(definitions));
+    }
+}
+
+fn has_reference(value: &Value) -> bool {
+    match value {
+        Value::Object(object) => object.contains_key("$ref") || object.values().any(has_reference),
+        Value::Array(values) => values.iter().any(has_reference),
+        _ => false,
+    }
+}
+
+fn replace_references(value: &mut Value, definitions: &Map<String, Value>) {
+    match value {
+        Value::Object(object) => {
+            let definition = object
+                .get("$ref")
+                .and_then(Value::as_str)
+                .and_then(|reference| definitions.get(reference));
+            if let Some(Value::Object(definition)) = definition {
+                // Keywords next to the reference, e.g. a description, take precedence
+                object.remove("$ref");
+                for (key, value) in definition {
+                    object.entry(key.clone()).or_insert_with(|| value.clone());
+                }
+            }
+            object
+                .values_mut()
+                .for_each(|value| replace_references(value, definitions));
+        }
+        Value::Array(values) => values
+            .iter_mut()
+            .for_each(|value| replace_references(value, definitions)),
+        _ => {}
+    }
+}
+
+/// Removes the titles of a schema and its subschemas. Titles are derived from
+/// type names and carry no information for the model.
+fn strip_titles(schema: &mut Value) {
+    let Some(object) = schema.as_object_mut() else {
+        return;
+    };
+    object.remove("title");
+
+    for (key, value) in object.iter_mut() {
+        match (key.as_str(), value) {
+            // Maps from names to schemas, where a name can be "title"
+            ("properties" | "patternProperties" | "definitions", Value::Object(schemas)) => {
+                schemas.values_mut().for_each(strip_titles)
+            }
+            ("items" | "allOf" | "anyOf" | "oneOf", Value::Array(schemas)) => {
+                schemas.iter_mut().for_each(strip_titles)
+            }
+            (
+                "items"
+                | "additionalProperties"
+                | "additionalItems"
+                | "contains"
+                | "not"
+                | "if"
+                | "then"
+                | "else",
+                schema,
+            ) => strip_titles(schema),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use strum::IntoEnumIterator;
+
+    use super::*;
+    use crate::Tools;
+
+    fn tool(description: &str, schema: Value) -> ToolDefinition {
+        ToolDefinition::new("test_tool")
+            .description(description)
+            .input_schema(serde_json::from_value::<schemars::schema::RootSchema>(schema).unwrap())
+    }
+
+    fn slim(tool: ToolDefinition) -> Value {
+        let context = Context::default().add_tool(tool);
+        let actual = SlimToolSchemas.transform(context);
+        serde_json::to_value(&actual.tools[0].input_schema).unwrap()
+    }
+
+    #[test]
+    fn test_strips_titles_and_duplicate_description() {
+        let fixture = tool(
+            "Reads a file\n from disk.",
+            json!({
+                "title": "FSRead",
+                "description": "Reads a file from disk.",
+                "type": "object",
+                "properties": {
+                    "title": {"title": "Title", "type": "string"},
+                    "path": {"description": "Path of the file", "type": "string"}
+                }
+            }),
+        );
+
+        let actual = slim(fixture);
+
+        let expected = json!({
+            "type": "object",
+            "properties": {
+                "title": {"type": "string"},
+                "path": {"description": "Path of the file", "type": "string"}
+            }
+        });
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_keeps_distinct_description() {
+        let fixture = tool(
+            "Reads a file.",
+            json!({"description": "Arguments for reading", "type": "object"}),
+        );
+
+        let actual = slim(fixture);
+
+        let expected = json!({"description": "Arguments for reading", "type": "object"});
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_inlines_trivial_definitions() {
+        let fixture = tool(
+            "Patches a file.",
+            json!({
+                "type": "object",
+                "properties": {
+                    "operation": {"description": "What to do", "$ref": "#/definitions/Operation"},
+                    "node": {"$ref": "#/definitions/Node"}
+                },
+                "definitions": {
+                    "Operation": {"type": "string", "enum": ["append", "replace"]},
+                    "Node": {
+                        "type": "object",
+                        "properties": {
+                            "operation": {"$ref": "#/definitions/Operation"},
+                            "children": {"type": "array", "items": {"$ref": "#/definitions/Node"}}
+                        }
+                    }
+                }
+            }),
+        );
+
+        let actual = slim(fixture);
+
+        let expected = json!({
+            "type": "object",
+            "properties": {
+                "operation": {
+                    "description": "What to do",
+                    "type": "string",
+                    "enum": ["append", "replace"]
+                },
+                "node": {"$ref": "#/definitions/Node"}
+            },
+            "definitions": {
+                "Node": {
+                    "type": "object",
+                    "properties": {
+                        "operation": {"type": "string", "enum": ["append", "replace"]},
+                        "children": {"type": "array", "items": {"$ref": "#/definitions/Node"}}
+                    }
+                }
+            }
+        });
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_reduces_forge_tool_schemas() {
+        let fixture = Context::default().tools(
+            Tools::iter()
+                .map(|tool| tool.definition())
+                .collect::<Vec<_>>(),
+        );
+        let before = serde_json::to_string(&fixture.tools).unwrap().len();
+
+        let actual = SlimToolSchemas.transform(fixture);
+
+        let after = serde_json::to_string(&actual.tools).unwrap().len();
+        assert!(
+            after * 100 < before * 85,
+            "{after} is not 15% less than {before}"
+        );
+        assert_eq!(actual.tools.len(), Tools::iter().count());
+    }
+}
@@ -0,0 +1,144 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{ChangeSummary, FileChangeKind};
+
+/// A copy of a file captured right before a tool modified it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotRef {
+    pub id: String,
+    pub path: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// What an assistant turn did to the workspace, linked to the messages of the
+/// turn and to the snapshots it created.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TurnRecord {
+    /// Index of the first context message of the turn
+    pub message_index: usize,
+    pub started_at: DateTime<Utc>,
+    /// First line of the request that started the turn
+    pub prompt: String,
+    pub changes: ChangeSummary,
+    /// Snapshots created by the turn, oldest first
+    pub snapshots: Vec<SnapshotRef>,
+}
+
+/// Operations that bring the workspace back to its state before a sequence
+/// of turns.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RestorePlan {
+    /// Snapshots to restore, at most one per file
+    pub restore: Vec<SnapshotRef>,
+    /// Files that didn't exist before the turns
+    pub remove: Vec<String>,
+    /// Files that were changed without a snapshot to restore them from
+    pub unrecoverable: Vec<String>,
+}
+
+impl RestorePlan {
+    /// Plans the restoration of the files changed by `turns`, given in the
+    /// order they happened.
+    pub fn new(turns: &[TurnRecord]) -> Self {
+        let mut plan = Self::default();
+        let mut seen = HashSet::new();
+
+        for turn in turns {
+            for change in turn.changes.files.iter() {
+                if !seen.insert(change.path.as_str()) {
+                    continue;
+                }
+
+                // The earliest snapshot holds the content before the first change
+                let snapshot = turns
+                    .iter()
+                    .flat_map(|turn| turn.snapshots.iter())
+                    .find(|snapshot| snapshot.path == change.path);
+
+                match (change.kind, snapshot) {
+                    (FileChangeKind::Added, _) => plan.remove.push(change.path.clone()),
+                    (_, Some(snapshot)) => plan.restore.push(snapshot.clone()),
+                    (_, None) => plan.unrecoverable.push(change.path.clone()),
+                }
+            }
+        }
+
+        plan
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::FileChange;
+
+    fn snapshot(id: &str, path: &str) -> SnapshotRef {
+        SnapshotRef {
+            id: id.to_string(),
+            path: path.to_string(),
+            created_at: Utc::now(),
+        }
+    }
+
+    fn turn(changes: &[(&str, FileChangeKind)], snapshots: Vec<SnapshotRef>) -> TurnRecord {
+        let mut summary = ChangeSummary::default();
+        for (path, kind) in changes {
+            summary.record_file(FileChange {
+                path: path.to_string(),
+                kind: *kind,
+                insertions: 1,
+                deletions: 1,
+            });
+        }
+        TurnRecord {
+            message_index: 0,
+            started_at: Utc::now(),
+            prompt: "fix the bug".to_string(),
+            changes: summary,
+            snapshots,
+        }
+    }
+
+    #[test]
+    fn test_restore_plan() {
+        let fixture = vec![
+            turn(
+                &[
+                    ("/a.rs", FileChangeKind::Modified),
+                    ("/new.rs", FileChangeKind::Added),
+                ],
+                vec![snapshot("1", "/a.rs")],
+            ),
+            turn(
+                &[
+                    ("/a.rs", FileChangeKind::Modified),
+                    ("/new.rs", FileChangeKind::Modified),
+                    ("/b.rs", FileChangeKind::Deleted),
+                    ("/c.rs", FileChangeKind::Modified),
+                ],
+                vec![
+                    snapshot("2", "/a.rs"),
+                    snapshot("3", "/new.rs"),
+                    snapshot("4", "/b.rs"),
+                ],
+            ),
+        ];
+
+        let actual = RestorePlan::new(&fixture);
+
+        let expected = RestorePlan {
+            restore: vec![
+                fixture[0].snapshots[0].clone(),
+                fixture[1].snapshots[2].clone(),
+            ],
+            remove: vec!["/new.rs".to_string()],
+            unrecoverable: vec!["/c.rs".to_string()],
+        };
+        assert_eq!(actual, expected);
+    }
+}
@@ -27,4 +27,6 @@ impl From<UpdateFrequency> for Duration {
 pub struct Update {
     pub frequency: Option<UpdateFrequency>,
     pub auto_update: Option<bool>,
+    /// Show a notice in the banner when a new version is available
+    pub notify: Option<bool>,
 }
@@ -0,0 +1,266 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use chrono::{Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Usage};
+
+/// Number of days the usage is kept in the ledger
+const RETENTION_DAYS: u64 = 90;
+
+/// Daily limits on the usage of the providers, set by the administrators of
+/// shared deployments
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageQuota {
+    /// Tokens that may be used per day
+    pub daily_tokens: Option<u64>,
+    /// Dollars that may be spent per day
+    pub daily_cost: Option<f64>,
+    /// Whether the limits apply to the API key across all workspaces instead
+    /// of each workspace
+    pub per_api_key: bool,
+}
+
+impl UsageQuota {
+    pub fn is_enabled(&self) -> bool {
+        self.daily_tokens.is_some() || self.daily_cost.is_some()
+    }
+}
+
+/// Usage of a workspace with an API key on a day
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsageRecord {
+    pub date: NaiveDate,
+    pub workspace: PathBuf,
+    /// Fingerprint of the API key, never the key itself
+    pub account: String,
+    pub requests: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub cost: f64,
+}
+
+impl UsageRecord {
+    pub fn tokens(&self) -> u64 {
+        self.prompt_tokens + self.completion_tokens
+    }
+}
+
+/// Usage of the providers accounted on this machine, persisted so that
+/// quotas hold across sessions
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsageLedger {
+    pub records: Vec<UsageRecord>,
+}
+
+impl UsageLedger {
+    /// Adds the usage of a request and drops the records older than the
+    /// retention period
+    pub fn record(&mut self, date: NaiveDate, workspace: &Path, account: &str, usage: &Usage) {
+        let index = self.records.iter().position(|record| {
+            record.date == date && record.workspace == workspace && record.account == account
+        });
+        let record = match index {
+            Some(index) => &mut self.records[index],
+            None => {
+                self.records.push(UsageRecord {
+                    date,
+                    workspace: workspace.to_path_buf(),
+                    account: account.to_string(),
+                    ..Default::default()
+                });
+                self.records.last_mut().unwrap()
+            }
+        };
+        record.requests += 1;
+        record.prompt_tokens += usage.prompt_tokens as u64;
+        record.completion_tokens += usage.completion_tokens as u64;
+        record.cost += usage.cost.unwrap_or_default();
+
+        if let Some(oldest) = date.checked_sub_days(Days::new(RETENTION_DAYS)) {
+            self.records.retain(|record| record.date > oldest);
+        }
+    }
+
+    /// Usage of the day counted against the quota
+    pub fn spent(
+        &self,
+        quota: &UsageQuota,
+        date: NaiveDate,
+        workspace: &Path,
+        account: &str,
+    ) -> UsageRecord {
+        self.records
+            .iter()
+            .filter(|record| record.date == date)
+            .filter(|record| {
+                if quota.per_api_key {
+                    record.account == account
+                } else {
+                    record.workspace == workspace
+                }
+            })
+            .fold(
+                UsageRecord {
+                    date,
+                    workspace: workspace.to_path_buf(),
+                    account: account.to_string(),
+                    ..Default::default()
+                },
+                |mut total, record| {
+                    total.requests += record.requests;
+                    total.prompt_tokens += record.prompt_tokens;
+                    total.completion_tokens += record.completion_tokens;
+                    total.cost += record.cost;
+                    total
+                },
+            )
+    }
+
+    /// Usage per day since the given date, most recent first, of a workspace
+    /// or of all of them
+    pub fn daily(&self, workspace: Option<&Path>, since: NaiveDate) -> Vec<UsageRecord> {
+        let mut days = BTreeMap::<NaiveDate, UsageRecord>::new();
+        let records = self
+            .records
+            .iter()
+            .filter(|record| record.date >= since)
+            .filter(|record| workspace.is_none_or(|workspace| record.workspace == workspace));
+        for record in records {
+            let day = days.entry(record.date).or_insert_with(|| UsageRecord {
+                date: record.date,
+                workspace: workspace.map(Path::to_path_buf).unwrap_or_default(),
+                ..Default::default()
+            });
+            day.requests += record.requests;
+            day.prompt_tokens += record.prompt_tokens;
+            day.completion_tokens += record.completion_tokens;
+            day.cost += record.cost;
+        }
+
+        days.into_values().rev().collect()
+    }
+
+    /// Fails when the usage of the day already reached a limit of the quota
+    pub fn check(
+        &self,
+        quota: &UsageQuota,
+        date: NaiveDate,
+        workspace: &Path,
+        account: &str,
+    ) -> Result<(), Error> {
+        let spent = self.spent(quota, date, workspace, account);
+        if let Some(limit) = quota.daily_tokens.filter(|limit| spent.tokens() >= *limit) {
+            return Err(Error::QuotaExceeded {
+                limit: format!("{limit} tokens"),
+                used: format!("{} tokens", spent.tokens()),
+            });
+        }
+        if let Some(limit) = quota.daily_cost.filter(|limit| spent.cost >= *limit) {
+            return Err(Error::QuotaExceeded {
+                limit: format!("${limit:.2}"),
+                used: format!("${:.2}", spent.cost),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 6, day).unwrap()
+    }
+
+    fn usage(tokens: usize, cost: f64) -> Usage {
+        Usage {
+            prompt_tokens: tokens,
+            completion_tokens: 0,
+            total_tokens: tokens,
+            cost: Some(cost),
+            ..Default::default()
+        }
+    }
+
+    fn ledger() -> UsageLedger {
+        let mut ledger = UsageLedger::default();
+        ledger.record(date(1), Path::new("/repo"), "key-a", &usage(500, 1.0));
+        ledger.record(date(2), Path::new("/repo"), "key-a", &usage(600, 1.0));
+        ledger.record(date(2), Path::new("/repo"), "key-a", &usage(400, 0.5));
+        ledger.record(date(2), Path::new("/other"), "key-a", &usage(5_000, 2.0));
+        ledger
+    }
+
+    #[test]
+    fn test_record_accumulates_per_day() {
+        let fixture = ledger();
+
+        let actual = fixture.spent(&UsageQuota::default(), date(2), Path::new("/repo"), "key-a");
+
+        assert_eq!(
+            (actual.requests, actual.tokens(), actual.cost),
+            (2, 1_000, 1.5)
+        );
+    }
+
+    #[test]
+    fn test_check_per_workspace() {
+        let fixture = UsageQuota { daily_tokens: Some(1_000), ..Default::default() };
+
+        let actual = ledger()
+            .check(&fixture, date(2), Path::new("/repo"), "key-a")
+            .map_err(|error| error.to_string());
+
+        let expected =
+            Err("Daily quota of 1000 tokens exceeded, 1000 tokens were used today".to_string());
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_check_per_api_key() {
+        let fixture = UsageQuota {
+            daily_cost: Some(5.0),
+            per_api_key: true,
+            ..Default::default()
+        };
+
+        let actual = ledger().check(&fixture, date(2), Path::new("/repo"), "key-a");
+
+        assert!(actual.is_ok());
+    }
+
+    #[test]
+    fn test_daily_usage_of_workspace() {
+        let fixture = ledger();
+
+        let actual = fixture
+            .daily(Some(Path::new("/repo")), date(1))
+            .into_iter()
+            .map(|day| (day.date, day.requests, day.tokens()))
+            .collect::<Vec<_>>();
+
+        let expected = vec![(date(2), 2, 1_000), (date(1), 1, 500)];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_record_drops_old_usage() {
+        let mut fixture = ledger();
+
+        fixture.record(
+            date(1) + Days::new(RETENTION_DAYS + 1),
+            Path::new("/repo"),
+            "key-a",
+            &usage(1, 0.0),
+        );
+
+        assert_eq!(fixture.records.len(), 1);
+    }
+}
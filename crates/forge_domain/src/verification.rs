@@ -0,0 +1,190 @@
+use crate::{JournalEntry, JournalEvent};
+
+/// The verification commands reported by a completion checked against the
+/// commands the journal recorded for the conversation.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VerificationCheck {
+    /// Reported commands that were run
+    pub verified: Vec<String>,
+    /// Reported commands that were never run
+    pub unverified: Vec<String>,
+    /// Whether files were modified without any command reported to verify
+    /// them
+    pub missing: bool,
+}
+
+impl VerificationCheck {
+    /// A reported command is verified when each of its simple commands was
+    /// run with the same program and at least its arguments, in order, so
+    /// that `cargo test` is verified by `cd app && cargo test --workspace`
+    /// but not by `echo cargo test`.
+    pub fn new(reported: &[String], entries: &[JournalEntry]) -> Self {
+        let commands = entries
+            .iter()
+            .filter_map(|entry| match &entry.event {
+                JournalEvent::CommandExecuted { command } => Some(simple_commands(command)),
+                _ => None,
+            })
+            .flatten()
+            .collect::<Vec<_>>();
+        let modified = entries
+            .iter()
+            .any(|entry| matches!(entry.event, JournalEvent::FileModified { .. }));
+
+        let (verified, unverified) = reported
+            .iter()
+            .filter(|command| !command.trim().is_empty())
+            .cloned()
+            .partition::<Vec<_>, _>(|command| {
+                simple_commands(command)
+                    .iter()
+                    .all(|reported| commands.iter().any(|run| is_covered_by(reported, run)))
+            });
+        let missing = modified && reported.is_empty();
+
+        Self { verified, unverified, missing }
+    }
+
+    /// Whether the completion can be trusted: every reported command was run
+    /// and the changes to the files were verified
+    pub fn is_verified(&self) -> bool {
+        self.unverified.is_empty() && !self.missing
+    }
+}
+
+/// Whether the command that was run has the program of the reported one and
+/// its arguments in the same order, possibly with others in between
+fn is_covered_by(reported: &[String], run: &[String]) -> bool {
+    let (Some((program, args)), Some((run_program, run_args))) =
+        (reported.split_first(), run.split_first())
+    else {
+        return false;
+    };
+    let mut run_args = run_args.iter();
+    program == run_program && args.iter().all(|arg| run_args.any(|run| run == arg))
+}
+
+/// Words of the simple commands of a shell command line, split on the
+/// unquoted `;`, `&&`, `||`, `|` and newlines. The quotes are removed and the
+/// leading variable assignments skipped.
+fn simple_commands(line: &str) -> Vec<Vec<String>> {
+    let mut commands = Vec::new();
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut chars = line.chars().peekable();
+    let mut quote = None;
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"'), '\\') => {
+                if let Some(next) = chars.next() {
+                    word.get_or_insert_default().push(next);
+                }
+            }
+            (Some(_), c) => word.get_or_insert_default().push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                word.get_or_insert_default();
+            }
+            (None, '\\') => {
+                if let Some(next) = chars.next() {
+                    word.get_or_insert_default().push(next);
+                }
+            }
+            (None, ';' | '&' | '|' | '\n') => {
+                words.extend(word.take());
+                if matches!(c, '&' | '|') && chars.peek() == Some(&c) {
+                    chars.next();
+                }
+                commands.push(std::mem::take(&mut words));
+            }
+            (None, c) if c.is_whitespace() => words.extend(word.take()),
+            (None, c) => word.get_or_insert_default().push(c),
+        }
+    }
+    words.extend(word);
+    commands.push(words);
+
+    commands
+        .into_iter()
+        .map(|words| {
+            words
+                .into_iter()
+                .skip_while(|word| is_assignment(word))
+                .collect::<Vec<_>>()
+        })
+        .filter(|words| !words.is_empty())
+        .collect()
+}
+
+/// Whether the word sets a variable for the command, like `RUST_LOG=debug`
+fn is_assignment(word: &str) -> bool {
+    word.split_once('=').is_some_and(|(name, _)| {
+        !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{ConversationId, FileChangeKind};
+
+    fn entry(event: JournalEvent) -> JournalEntry {
+        JournalEntry::new(ConversationId::generate(), event)
+    }
+
+    #[test]
+    fn test_verification_check() {
+        let entries = [
+            entry(JournalEvent::FileModified {
+                path: "/project/src/main.rs".to_string(),
+                kind: FileChangeKind::Modified,
+            }),
+            entry(JournalEvent::CommandExecuted {
+                command: "cd app &&  RUST_LOG=debug cargo test --workspace".to_string(),
+            }),
+            entry(JournalEvent::CommandExecuted { command: "echo 'cargo clippy'".to_string() }),
+        ];
+        let fixture = ["cargo test", "cargo clippy"].map(String::from);
+
+        let actual = VerificationCheck::new(&fixture, &entries);
+
+        let expected = VerificationCheck {
+            verified: vec!["cargo test".to_string()],
+            unverified: vec!["cargo clippy".to_string()],
+            missing: false,
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_simple_commands() {
+        let fixture = r#"cd "my app" && FOO=1 cargo test -- --nocapture | tee log; echo a\;b"#;
+
+        let actual = simple_commands(fixture);
+
+        let expected = vec![
+            vec!["cd", "my app"],
+            vec!["cargo", "test", "--", "--nocapture"],
+            vec!["tee", "log"],
+            vec!["echo", "a;b"],
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_verification_check_missing() {
+        let entries = [entry(JournalEvent::FileModified {
+            path: "/project/src/main.rs".to_string(),
+            kind: FileChangeKind::Added,
+        })];
+
+        let check = VerificationCheck::new(&[], &entries);
+
+        let actual = (check.missing, check.is_verified());
+        let expected = (true, false);
+        assert_eq!(actual, expected);
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use derive_setters::Setters;
 use merge::Merge;
@@ -8,7 +8,11 @@ use serde_json::Value;
 
 use crate::temperature::Temperature;
 use crate::update::Update;
-use crate::{Agent, AgentId, Compact, MaxTokens, ModelId, TopK, TopP};
+use crate::{
+    Agent, AgentId, Autonomy, Compact, EditMode, Logging, MaxTokens, ModelId, Moderation,
+    Notifications, PathPermissions, ScaffoldTemplate, Sharing, ShellRetry, SnapshotRetention,
+    TestAfterEdit, ThemeConfig, ToolGateRule, TopK, TopP,
+};
 
 /// Configuration for a workflow that contains all settings
 /// required to initialize a workflow.
@@ -133,6 +137,21 @@ pub struct Workflow {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[merge(strategy = crate::merge::option)]
     pub max_requests_per_turn: Option<usize>,
+
+    /// Requires a file to be read in the conversation before the agent may
+    /// overwrite or patch it. Disabled by default.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub read_before_write: Option<bool>,
+
+    /// Path globs the tools of all agents are restricted to. Agents with their
+    /// own permissions keep them.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub permissions: Option<PathPermissions>,
+
     /// Configuration for automatic context compaction for all agents
     /// If specified, this will be applied to all agents in the workflow
     /// If not specified, each agent's individual setting will be used
@@ -140,6 +159,129 @@ pub struct Workflow {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[merge(strategy = crate::merge::option)]
     pub compact: Option<Compact>,
+
+    /// Configuration of the log files, their format and per-module levels
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub logging: Option<Logging>,
+
+    /// Limits on the file snapshots kept to undo changes
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub snapshots: Option<SnapshotRetention>,
+
+    /// Rules deciding in which phase of a conversation tools are offered to
+    /// the agents
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub tool_gates: Option<Vec<ToolGateRule>>,
+
+    /// Environment variables set for the shell commands run by the agents,
+    /// e.g. a `DATABASE_URL` pointing to a test database
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub env: Option<BTreeMap<String, String>>,
+
+    /// Retries of the shell commands that fail transiently, e.g. because of a
+    /// network timeout
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub shell_retry: Option<ShellRetry>,
+
+    /// Checks of the responses of the models before their tool calls are
+    /// executed, to block or flag out-of-policy actions
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub moderation: Option<Moderation>,
+
+    /// Budget of the agents running without interactive approval, when
+    /// started with `--autonomous`
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub autonomy: Option<Autonomy>,
+
+    /// Template of the status line shown on the right of the prompt, e.g.
+    /// `{model} {context} {cost} {branch}{dirty} mcp:{mcp}`. Available
+    /// fields are `{version}`, `{model}`, `{tokens}`, `{context}`, `{cost}`,
+    /// `{branch}`, `{dirty}` and `{mcp}`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub status_line: Option<String>,
+
+    /// Desktop notifications sent when a long turn completes, an agent waits
+    /// for input or an autonomous agent spent its budget. Disabled when not
+    /// set.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub notifications: Option<Notifications>,
+
+    /// Key bindings used to edit the prompts, `emacs` or `vi`. Defaults to
+    /// `emacs`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub edit_mode: Option<EditMode>,
+
+    /// Colors of the terminal output, a base theme and the colors replacing
+    /// its ones
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub theme: Option<ThemeConfig>,
+
+    /// Project templates created with `forge new` or the scaffold tool, so
+    /// that the same layout is created every time instead of being generated
+    /// by the model
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub scaffolds: Option<Vec<ScaffoldTemplate>>,
+
+    /// Paste service `/share` uploads the encrypted transcripts to, and
+    /// whether the contents of the files are removed from them
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub sharing: Option<Sharing>,
+
+    /// Tests run once a turn modified some files, their failures being fed
+    /// back to the agent before the turn completes
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub test_after_edit: Option<TestAfterEdit>,
+
+    /// Language the agents write their answers, summaries and commit messages
+    /// in, e.g. `French`. The code, identifiers and commands stay unchanged.
+    /// Defaults to the language of the user.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub response_language: Option<String>,
+
+    /// Spot-checks the functions, types and configuration keys the final
+    /// answers mention with searches of the workspace, flagging the ones
+    /// that can't be found. Disabled by default.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub verify_answers: Option<bool>,
+
+    /// Distills the learnings of a conversation when it ends and offers to
+    /// add them to `.forge/memory.md`. Disabled by default.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[merge(strategy = crate::merge::option)]
+    pub remember_learnings: Option<bool>,
 }
 
 impl Default for Workflow {
@@ -182,7 +324,26 @@ impl Workflow {
             templates: None,
             max_tool_failure_per_turn: None,
             max_requests_per_turn: None,
+            read_before_write: None,
+            permissions: None,
             compact: None,
+            logging: None,
+            snapshots: None,
+            tool_gates: None,
+            env: None,
+            shell_retry: None,
+            moderation: None,
+            autonomy: None,
+            status_line: None,
+            notifications: None,
+            edit_mode: None,
+            theme: None,
+            scaffolds: None,
+            sharing: None,
+            test_after_edit: None,
+            response_language: None,
+            verify_answers: None,
+            remember_learnings: None,
         }
     }
 
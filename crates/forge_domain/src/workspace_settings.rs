@@ -0,0 +1,81 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use derive_setters::Setters;
+use serde::{Deserialize, Serialize};
+
+use crate::{AgentId, Issue, IssueLink, ModelId};
+
+/// Choices remembered for a single workspace across sessions. Stored in
+/// `.forge/state.json` relative to the workspace root.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, Setters)]
+#[serde(rename_all = "camelCase")]
+#[setters(strip_option, into)]
+pub struct WorkspaceSettings {
+    /// The model that was last used in this workspace
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<ModelId>,
+
+    /// The agent that was last operating in this workspace
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operating_agent: Option<AgentId>,
+
+    /// Actions the user has approved for good in this workspace, e.g.
+    /// `remove <path>` for the recursive removal of a directory
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub approvals: BTreeSet<String>,
+
+    /// Issues the branches of this workspace were started from, by branch
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tasks: BTreeMap<String, IssueLink>,
+}
+
+impl WorkspaceSettings {
+    /// Records an approval grant for an action
+    pub fn approve(&mut self, grant: impl Into<String>) -> bool {
+        self.approvals.insert(grant.into())
+    }
+
+    /// Checks whether an action has been approved
+    pub fn is_approved(&self, grant: &str) -> bool {
+        self.approvals.contains(grant)
+    }
+
+    /// Records that the work on `issue` happens on `branch`
+    pub fn start_task(&mut self, branch: impl Into<String>, issue: &Issue) {
+        self.tasks.insert(branch.into(), issue.into());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_empty_settings_serialize_to_empty_object() {
+        let fixture = WorkspaceSettings::default();
+        let actual = serde_json::to_string(&fixture).unwrap();
+        let expected = "{}";
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_settings_roundtrip() {
+        let mut fixture = WorkspaceSettings::default()
+            .model(ModelId::new("anthropic/claude-3.7-sonnet"))
+            .operating_agent(AgentId::new("muse"));
+        fixture.approve("remove /project/target");
+
+        let json = serde_json::to_string(&fixture).unwrap();
+        let actual: WorkspaceSettings = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(actual, fixture);
+        assert!(actual.is_approved("remove /project/target"));
+        assert!(!actual.is_empty());
+    }
+}
@@ -0,0 +1,111 @@
+use std::path::PathBuf;
+
+/// Prefix of the branches of the worktrees created for the agents
+pub const WORKTREE_BRANCH_PREFIX: &str = "forge/worktree/";
+
+/// A git worktree, as listed by `git worktree list --porcelain`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Worktree {
+    pub path: PathBuf,
+    /// Commit checked out in the worktree
+    pub head: String,
+    /// Branch checked out in the worktree, without `refs/heads/`. `None` when
+    /// the head is detached.
+    pub branch: Option<String>,
+}
+
+impl Worktree {
+    /// Name of the branch of a worktree created for the agents
+    pub fn branch_name(name: &str) -> String {
+        format!("{WORKTREE_BRANCH_PREFIX}{name}")
+    }
+
+    /// Parses the worktrees listed by `git worktree list --porcelain`
+    pub fn parse_all(porcelain: &str) -> Vec<Self> {
+        porcelain
+            .split("\n\n")
+            .filter_map(|entry| {
+                let mut path = None;
+                let mut head = String::new();
+                let mut branch = None;
+                for line in entry.lines() {
+                    if let Some(value) = line.strip_prefix("worktree ") {
+                        path = Some(PathBuf::from(value));
+                    } else if let Some(value) = line.strip_prefix("HEAD ") {
+                        head = value.to_string();
+                    } else if let Some(value) = line.strip_prefix("branch ") {
+                        let value = value.strip_prefix("refs/heads/").unwrap_or(value);
+                        branch = Some(value.to_string());
+                    }
+                }
+                Some(Self { path: path?, head, branch })
+            })
+            .collect()
+    }
+
+    /// Name the worktree was created with for the agents, `None` for the
+    /// other worktrees
+    pub fn name(&self) -> Option<&str> {
+        self.branch
+            .as_deref()
+            .and_then(|branch| branch.strip_prefix(WORKTREE_BRANCH_PREFIX))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_all() {
+        let fixture = concat!(
+            "worktree /project\n",
+            "HEAD 1f2e3d\n",
+            "branch refs/heads/main\n",
+            "\n",
+            "worktree /tmp/forge-worktrees/project/rename-config\n",
+            "HEAD 4a5b6c\n",
+            "branch refs/heads/forge/worktree/rename-config\n",
+            "\n",
+            "worktree /tmp/review\n",
+            "HEAD 7d8e9f\n",
+            "detached\n",
+        );
+
+        let actual = Worktree::parse_all(fixture);
+
+        let expected = vec![
+            Worktree {
+                path: PathBuf::from("/project"),
+                head: "1f2e3d".to_string(),
+                branch: Some("main".to_string()),
+            },
+            Worktree {
+                path: PathBuf::from("/tmp/forge-worktrees/project/rename-config"),
+                head: "4a5b6c".to_string(),
+                branch: Some("forge/worktree/rename-config".to_string()),
+            },
+            Worktree {
+                path: PathBuf::from("/tmp/review"),
+                head: "7d8e9f".to_string(),
+                branch: None,
+            },
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_name() {
+        let fixture = Worktree::parse_all(concat!(
+            "worktree /project\nHEAD 1f2e3d\nbranch refs/heads/main\n\n",
+            "worktree /tmp/a\nHEAD 4a5b6c\nbranch refs/heads/forge/worktree/rename-config\n",
+        ));
+
+        let actual = fixture.iter().map(Worktree::name).collect::<Vec<_>>();
+
+        let expected = vec![None, Some("rename-config")];
+        assert_eq!(actual, expected);
+    }
+}
@@ -26,6 +26,30 @@ pub fn extract_tag_content<'a>(text: &'a str, tag_name: &str) -> Option<&'a str>
     None
 }
 
+/// Extracts the content of every occurrence of the specified XML-style tags, in
+/// the order they appear in the text. Unlike [`extract_tag_content`] each
+/// opening tag is matched with the closing tag that follows it.
+pub fn extract_all_tag_content<'a>(text: &'a str, tag_name: &str) -> Vec<&'a str> {
+    let opening_tag = format!("<{tag_name}>");
+    let closing_tag = format!("</{tag_name}>");
+
+    let mut contents = Vec::new();
+    let mut rest = text;
+    while let Some(start_idx) = rest.find(&opening_tag) {
+        let content_start = start_idx + opening_tag.len();
+        let Some(end_idx) = rest[content_start..].find(&closing_tag) else {
+            break;
+        };
+        let content = rest[content_start..content_start + end_idx].trim();
+        if !content.is_empty() {
+            contents.push(content);
+        }
+        rest = &rest[content_start + end_idx + closing_tag.len()..];
+    }
+
+    contents
+}
+
 /// Removes content within XML-style tags that start with the specified prefix
 pub fn remove_tag_with_prefix(text: &str, prefix: &str) -> String {
     // First, find all unique tag names that start with the prefix
@@ -72,6 +96,14 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_extract_all_tag_content() {
+        let fixture = "<call>first</call> text <call> second </call><call></call><call>open";
+        let actual = extract_all_tag_content(fixture, "call");
+        let expected = vec!["first", "second"];
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn test_extract_tag_content_no_tags() {
         let fixture = "Some text without any tags";
@@ -0,0 +1,66 @@
+#ifndef FORGE_H
+#define FORGE_H
+
+/* Generated by cbindgen from crates/forge_ffi. Do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Opaque handle to an embedded forge client.
+typedef struct ForgeFfiClient ForgeFfiClient;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Creates a new client rooted at `workspace` (or the current directory when
+// null). Returns null if the client could not be created.
+//
+// # Safety
+// `workspace` must be null or point to a valid NUL terminated string.
+ForgeFfiClient *forge_client_new(const char *workspace, bool restricted);
+
+// Submits a prompt to the client's conversation. Any prompt still running is
+// cancelled first. Returns `0` on success and `-1` on failure.
+//
+// # Safety
+// `client` must be a pointer returned by [`forge_client_new`] and `prompt`
+// must point to a valid NUL terminated string.
+int forge_client_send(const ForgeFfiClient *client, const char *prompt);
+
+// Waits up to `timeout_ms` milliseconds for the next event and returns it as
+// a JSON string, or null if no event arrived in time. The returned string
+// must be released with [`forge_string_free`].
+//
+// # Safety
+// `client` must be a pointer returned by [`forge_client_new`].
+char *forge_client_poll(const ForgeFfiClient *client, uint64_t timeout_ms);
+
+// Cancels the prompt currently being processed. Returns `1` if a running
+// prompt was cancelled, `0` otherwise and `-1` on failure.
+//
+// # Safety
+// `client` must be a pointer returned by [`forge_client_new`].
+int forge_client_cancel(const ForgeFfiClient *client);
+
+// Destroys a client, cancelling any running prompt.
+//
+// # Safety
+// `client` must be null or a pointer returned by [`forge_client_new`] that
+// has not been freed yet.
+void forge_client_free(ForgeFfiClient *client);
+
+// Releases a string returned by this library.
+//
+// # Safety
+// `value` must be null or a pointer returned by [`forge_client_poll`] that
+// has not been freed yet.
+void forge_string_free(char *value);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  // FORGE_H
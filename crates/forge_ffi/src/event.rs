@@ -0,0 +1,168 @@
+use forge_api::{ChatResponse, FileChange, InterruptionReason};
+use serde::Serialize;
+
+/// JSON representation of the events emitted to FFI consumers. Every event is
+/// serialized as an object with a `type` discriminator.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FfiEvent {
+    Text {
+        text: String,
+        is_complete: bool,
+        is_md: bool,
+    },
+    Summary {
+        content: String,
+    },
+    Reasoning {
+        content: String,
+    },
+    ToolCallStart {
+        name: String,
+        arguments: serde_json::Value,
+    },
+    /// Emitted as a running tool, e.g. a shell command, produces output.
+    ToolProgress {
+        name: String,
+        output: String,
+    },
+    ToolCallEnd {
+        name: String,
+        is_error: bool,
+    },
+    Usage {
+        prompt_tokens: usize,
+        completion_tokens: usize,
+        total_tokens: usize,
+        cost: Option<f64>,
+    },
+    Retry {
+        cause: String,
+        duration_ms: u128,
+    },
+    Interrupt {
+        reason: String,
+    },
+    /// Emitted when the moderation blocked a response of the model.
+    Blocked {
+        reason: String,
+    },
+    /// Emitted at the end of a turn that changed files.
+    ChangeSummary {
+        files: Vec<FileChange>,
+        commands: Vec<String>,
+    },
+    /// Emitted periodically and when it stops by an agent running
+    /// autonomously.
+    ProgressReport {
+        done: Vec<String>,
+        next: Vec<String>,
+        confidence: f64,
+        elapsed_ms: u128,
+        requests: usize,
+    },
+    Error {
+        message: String,
+    },
+    /// Emitted once the agent has finished processing the prompt, whether it
+    /// succeeded, failed or was cancelled.
+    Done,
+}
+
+impl From<ChatResponse> for FfiEvent {
+    fn from(response: ChatResponse) -> Self {
+        match response {
+            ChatResponse::Text { text, is_complete, is_md } => {
+                FfiEvent::Text { text, is_complete, is_md }
+            }
+            ChatResponse::Summary { content } => FfiEvent::Summary { content },
+            ChatResponse::Reasoning { content } => FfiEvent::Reasoning { content },
+            ChatResponse::ToolCallStart(call) => {
+                FfiEvent::ToolCallStart { name: call.name.to_string(), arguments: call.arguments }
+            }
+            ChatResponse::ToolProgress { name, output } => {
+                FfiEvent::ToolProgress { name: name.to_string(), output }
+            }
+            ChatResponse::ToolCallEnd(result) => {
+                FfiEvent::ToolCallEnd { is_error: result.is_error(), name: result.name.to_string() }
+            }
+            ChatResponse::Usage(usage) => FfiEvent::Usage {
+                prompt_tokens: usage.prompt_tokens,
+                completion_tokens: usage.completion_tokens,
+                total_tokens: usage.total_tokens,
+                cost: usage.cost,
+            },
+            ChatResponse::RetryAttempt { cause, duration } => FfiEvent::Retry {
+                cause: cause.into_string(),
+                duration_ms: duration.as_millis(),
+            },
+            ChatResponse::Interrupt { reason } => FfiEvent::Interrupt {
+                reason: match reason {
+                    InterruptionReason::MaxToolFailurePerTurnLimitReached { limit } => {
+                        format!("Maximum tool failure limit ({limit}) reached for this turn")
+                    }
+                    InterruptionReason::MaxRequestPerTurnLimitReached { limit } => {
+                        format!("Maximum request ({limit}) per turn achieved")
+                    }
+                    InterruptionReason::AutonomyBudgetExhausted { elapsed, requests } => {
+                        format!(
+                            "Autonomous budget spent after {requests} request(s) in {}s",
+                            elapsed.as_secs()
+                        )
+                    }
+                    InterruptionReason::DecisionRequired { question } => {
+                        format!("Decision required: {question}")
+                    }
+                },
+            },
+            ChatResponse::Blocked { reason } => FfiEvent::Blocked { reason },
+            ChatResponse::ChangeSummary(changes) => {
+                FfiEvent::ChangeSummary { files: changes.files, commands: changes.commands }
+            }
+            ChatResponse::ProgressReport(report) => FfiEvent::ProgressReport {
+                done: report.done,
+                next: report.next,
+                confidence: report.confidence,
+                elapsed_ms: report.elapsed.as_millis(),
+                requests: report.requests,
+            },
+        }
+    }
+}
+
+impl From<anyhow::Error> for FfiEvent {
+    fn from(error: anyhow::Error) -> Self {
+        FfiEvent::Error { message: format!("{error:?}") }
+    }
+}
+
+impl FfiEvent {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| r#"{"type":"error"}"#.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_text_event_json() {
+        let fixture =
+            ChatResponse::Text { text: "Hello".to_string(), is_complete: true, is_md: false };
+
+        let actual = FfiEvent::from(fixture).to_json();
+
+        let expected = r#"{"type":"text","text":"Hello","is_complete":true,"is_md":false}"#;
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_done_event_json() {
+        let actual = FfiEvent::Done.to_json();
+        let expected = r#"{"type":"done"}"#;
+        assert_eq!(actual, expected);
+    }
+}
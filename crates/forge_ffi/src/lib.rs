@@ -0,0 +1,297 @@
+//! C ABI for embedding the forge agent loop in other languages.
+//!
+//! A client is created with [`forge_client_new`], prompts are submitted with
+//! [`forge_client_send`] and the resulting events are drained as JSON strings
+//! with [`forge_client_poll`]. Every string returned by this library must be
+//! released with [`forge_string_free`] and every client with
+//! [`forge_client_free`]. Panics are caught before they reach the caller,
+//! the functions then fail as documented.
+//!
+//! The C header lives in `include/forge.h`. Regenerate it after changing the
+//! API with:
+//!
+//! ```sh
+//! cbindgen --config crates/forge_ffi/cbindgen.toml --crate forge_ffi --output crates/forge_ffi/include/forge.h
+//! ```
+
+mod event;
+
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::Context;
+pub use event::*;
+use forge_api::{ConversationId, Event, ForgeClient};
+use futures::StreamExt;
+use tokio::runtime::Runtime;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::task::JoinHandle;
+
+const EVENT_USER_TASK_INIT: &str = "forge/user_task_init";
+const EVENT_USER_TASK_UPDATE: &str = "forge/user_task_update";
+
+/// Opaque handle to an embedded forge client.
+pub struct ForgeFfiClient {
+    runtime: Runtime,
+    client: Arc<ForgeClient>,
+    conversation_id: Mutex<Option<ConversationId>>,
+    task: Mutex<Option<JoinHandle<()>>>,
+    sender: UnboundedSender<FfiEvent>,
+    receiver: Mutex<UnboundedReceiver<FfiEvent>>,
+}
+
+impl ForgeFfiClient {
+    fn new(workspace: Option<PathBuf>, restricted: bool) -> anyhow::Result<Self> {
+        let runtime = Runtime::new().context("Failed to start tokio runtime")?;
+        let mut builder = ForgeClient::builder().restricted(restricted);
+        if let Some(workspace) = workspace {
+            builder = builder.workspace(workspace);
+        }
+        let client = {
+            let _guard = runtime.enter();
+            builder.build()
+        };
+        let (sender, receiver) = unbounded_channel();
+        Ok(Self {
+            runtime,
+            client: Arc::new(client),
+            conversation_id: Mutex::new(None),
+            task: Mutex::new(None),
+            sender,
+            receiver: Mutex::new(receiver),
+        })
+    }
+
+    fn send(&self, prompt: String) -> anyhow::Result<()> {
+        self.cancel();
+
+        let mut conversation_id = self
+            .conversation_id
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Client state is poisoned"))?;
+        let (id, event_name) = match conversation_id.as_ref() {
+            Some(id) => (id.clone(), EVENT_USER_TASK_UPDATE),
+            None => {
+                let conversation = self.runtime.block_on(self.client.create_conversation())?;
+                *conversation_id = Some(conversation.id.clone());
+                (conversation.id, EVENT_USER_TASK_INIT)
+            }
+        };
+
+        let client = self.client.clone();
+        let sender = self.sender.clone();
+        let event = Event::new(event_name, Some(prompt));
+        let task = self.runtime.spawn(async move {
+            match client.chat_stream(&id, event).await {
+                Ok(mut stream) => {
+                    while let Some(message) = stream.next().await {
+                        let event = match message {
+                            Ok(response) => FfiEvent::from(response),
+                            Err(error) => FfiEvent::from(error),
+                        };
+                        if sender.send(event).is_err() {
+                            return;
+                        }
+                    }
+                }
+                Err(error) => {
+                    let _ = sender.send(FfiEvent::from(error));
+                }
+            }
+            let _ = sender.send(FfiEvent::Done);
+        });
+
+        if let Ok(mut guard) = self.task.lock() {
+            *guard = Some(task);
+        }
+        Ok(())
+    }
+
+    fn poll(&self, timeout: Duration) -> Option<FfiEvent> {
+        let mut receiver = self.receiver.lock().ok()?;
+        self.runtime.block_on(async {
+            tokio::time::timeout(timeout, receiver.recv())
+                .await
+                .ok()
+                .flatten()
+        })
+    }
+
+    fn cancel(&self) -> bool {
+        let task = self.task.lock().ok().and_then(|mut guard| guard.take());
+        match task {
+            Some(task) if !task.is_finished() => {
+                // Aborting the task drops the chat stream which in turn aborts
+                // the agent loop.
+                task.abort();
+                let _ = self.sender.send(FfiEvent::Done);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Runs the body of an exported function, returning `fallback` if it panics
+/// since unwinding into the caller aborts it.
+fn guard<T>(fallback: T, body: impl FnOnce() -> T) -> T {
+    catch_unwind(AssertUnwindSafe(body)).unwrap_or(fallback)
+}
+
+/// Reads an optional UTF-8 string from a C pointer.
+///
+/// # Safety
+/// `ptr` must be null or point to a valid NUL terminated string.
+unsafe fn read_str(ptr: *const c_char) -> Option<String> {
+    if ptr.is_null() {
+        return None;
+    }
+    CStr::from_ptr(ptr).to_str().ok().map(str::to_string)
+}
+
+/// Creates a new client rooted at `workspace` (or the current directory when
+/// null). Returns null if the client could not be created.
+///
+/// # Safety
+/// `workspace` must be null or point to a valid NUL terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn forge_client_new(
+    workspace: *const c_char,
+    restricted: bool,
+) -> *mut ForgeFfiClient {
+    guard(std::ptr::null_mut(), || {
+        let workspace = read_str(workspace).map(PathBuf::from);
+        match ForgeFfiClient::new(workspace, restricted) {
+            Ok(client) => Box::into_raw(Box::new(client)),
+            Err(_) => std::ptr::null_mut(),
+        }
+    })
+}
+
+/// Submits a prompt to the client's conversation. Any prompt still running is
+/// cancelled first. Returns `0` on success and `-1` on failure.
+///
+/// # Safety
+/// `client` must be a pointer returned by [`forge_client_new`] and `prompt`
+/// must point to a valid NUL terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn forge_client_send(
+    client: *const ForgeFfiClient,
+    prompt: *const c_char,
+) -> c_int {
+    guard(-1, || {
+        let (Some(client), Some(prompt)) = (client.as_ref(), read_str(prompt)) else {
+            return -1;
+        };
+        match client.send(prompt) {
+            Ok(()) => 0,
+            Err(error) => {
+                let _ = client.sender.send(FfiEvent::from(error));
+                -1
+            }
+        }
+    })
+}
+
+/// Waits up to `timeout_ms` milliseconds for the next event and returns it as
+/// a JSON string, or null if no event arrived in time. The returned string
+/// must be released with [`forge_string_free`].
+///
+/// # Safety
+/// `client` must be a pointer returned by [`forge_client_new`].
+#[no_mangle]
+pub unsafe extern "C" fn forge_client_poll(
+    client: *const ForgeFfiClient,
+    timeout_ms: u64,
+) -> *mut c_char {
+    guard(std::ptr::null_mut(), || {
+        let Some(client) = client.as_ref() else {
+            return std::ptr::null_mut();
+        };
+        client
+            .poll(Duration::from_millis(timeout_ms))
+            .and_then(|event| CString::new(event.to_json()).ok())
+            .map_or(std::ptr::null_mut(), CString::into_raw)
+    })
+}
+
+/// Cancels the prompt currently being processed. Returns `1` if a running
+/// prompt was cancelled, `0` otherwise and `-1` on failure.
+///
+/// # Safety
+/// `client` must be a pointer returned by [`forge_client_new`].
+#[no_mangle]
+pub unsafe extern "C" fn forge_client_cancel(client: *const ForgeFfiClient) -> c_int {
+    guard(-1, || match client.as_ref() {
+        Some(client) => client.cancel() as c_int,
+        None => 0,
+    })
+}
+
+/// Destroys a client, cancelling any running prompt.
+///
+/// # Safety
+/// `client` must be null or a pointer returned by [`forge_client_new`] that
+/// has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn forge_client_free(client: *mut ForgeFfiClient) {
+    guard((), || {
+        if !client.is_null() {
+            let client = Box::from_raw(client);
+            client.cancel();
+        }
+    })
+}
+
+/// Releases a string returned by this library.
+///
+/// # Safety
+/// `value` must be null or a pointer returned by [`forge_client_poll`] that
+/// has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn forge_string_free(value: *mut c_char) {
+    guard((), || {
+        if !value.is_null() {
+            drop(CString::from_raw(value));
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_guard_returns_fallback_on_panic() {
+        let actual = guard(-1, || -> c_int { panic!("unexpected state") });
+        let expected = -1;
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_header_is_up_to_date() {
+        let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml")).unwrap();
+        let mut header = Vec::new();
+        cbindgen::Builder::new()
+            .with_crate(crate_dir)
+            .with_config(config)
+            .generate()
+            .unwrap()
+            .write(&mut header);
+
+        let actual = String::from_utf8(header).unwrap();
+        let expected = std::fs::read_to_string(crate_dir.join("include/forge.h")).unwrap();
+        assert_eq!(
+            actual, expected,
+            "include/forge.h is stale, regenerate it with cbindgen"
+        );
+    }
+}
@@ -0,0 +1,158 @@
+use std::fmt::{Display, Formatter};
+
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+
+/// The encoding a text file is stored in, detected when reading it so that
+/// writing it back keeps the original encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextEncoding {
+    encoding: &'static Encoding,
+    /// Whether the content starts with a byte order mark
+    bom: bool,
+}
+
+impl Default for TextEncoding {
+    fn default() -> Self {
+        Self::UTF_8
+    }
+}
+
+impl TextEncoding {
+    /// UTF-8 without a byte order mark
+    pub const UTF_8: Self = Self { encoding: UTF_8, bom: false };
+
+    /// Detects the encoding of `bytes`. A byte order mark takes precedence,
+    /// then valid UTF-8, and finally the guess of the detector for legacy
+    /// encodings like Latin-1.
+    pub fn detect(bytes: &[u8]) -> Self {
+        if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+            return Self { encoding, bom: true };
+        }
+
+        if std::str::from_utf8(bytes).is_ok() {
+            return Self::UTF_8;
+        }
+
+        let mut detector = EncodingDetector::new();
+        detector.feed(bytes, true);
+        Self { encoding: detector.guess(None, true), bom: false }
+    }
+
+    /// Name of the encoding, e.g. `UTF-8` or `windows-1252`
+    pub fn name(&self) -> &'static str {
+        self.encoding.name()
+    }
+
+    /// Whether the content is plain UTF-8 and doesn't need transcoding
+    pub fn is_utf8(&self) -> bool {
+        *self == Self::UTF_8
+    }
+
+    /// Decodes `bytes` to UTF-8, dropping the byte order mark. Malformed
+    /// sequences are replaced with the replacement character.
+    pub fn decode(&self, bytes: &[u8]) -> String {
+        let bytes = if self.bom {
+            Encoding::for_bom(bytes).map_or(bytes, |(_, length)| &bytes[length..])
+        } else {
+            bytes
+        };
+
+        self.encoding
+            .decode_without_bom_handling(bytes)
+            .0
+            .into_owned()
+    }
+
+    /// Encodes `content` back to this encoding, including the byte order mark
+    /// if the original content had one. Characters that can't be represented
+    /// are written as numeric character references.
+    pub fn encode(&self, content: &str) -> Vec<u8> {
+        // encoding_rs only decodes UTF-16, so it's encoded by hand
+        if self.encoding == UTF_16LE || self.encoding == UTF_16BE {
+            let little_endian = self.encoding == UTF_16LE;
+            let bom = self.bom.then_some('\u{feff}');
+            return bom
+                .into_iter()
+                .chain(content.chars())
+                .collect::<String>()
+                .encode_utf16()
+                .flat_map(|unit| {
+                    if little_endian {
+                        unit.to_le_bytes()
+                    } else {
+                        unit.to_be_bytes()
+                    }
+                })
+                .collect();
+        }
+
+        let mut bytes = Vec::new();
+        if self.bom {
+            bytes.extend_from_slice(b"\xEF\xBB\xBF");
+        }
+        bytes.extend_from_slice(&self.encoding.encode(content).0);
+        bytes
+    }
+}
+
+impl Display for TextEncoding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.bom {
+            write!(f, "{} with BOM", self.name())
+        } else {
+            write!(f, "{}", self.name())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_detect_utf8() {
+        let fixture = "Grüße, こんにちは".as_bytes();
+        let actual = TextEncoding::detect(fixture);
+        let expected = TextEncoding::UTF_8;
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_latin1_round_trip() {
+        let fixture = b"Caf\xe9 cr\xe8me br\xfbl\xe9e, d\xe9j\xe0 vu, na\xefve fa\xe7ade".to_vec();
+        let encoding = TextEncoding::detect(&fixture);
+
+        let actual = encoding.decode(&fixture);
+        let expected = "Café crème brûlée, déjà vu, naïve façade";
+        assert_eq!(actual, expected);
+        assert_eq!(encoding.name(), "windows-1252");
+        assert_eq!(encoding.encode(&actual), fixture);
+    }
+
+    #[test]
+    fn test_utf16_round_trip() {
+        let fixture = [0xFF, 0xFE, b'h', 0, b'i', 0, b'\n', 0];
+        let encoding = TextEncoding::detect(&fixture);
+
+        let actual = encoding.decode(&fixture);
+        let expected = "hi\n";
+        assert_eq!(actual, expected);
+        assert_eq!(encoding.to_string(), "UTF-16LE with BOM");
+        assert_eq!(encoding.encode(&actual), fixture);
+    }
+
+    #[test]
+    fn test_utf8_bom_round_trip() {
+        let fixture = b"\xEF\xBB\xBFhello".to_vec();
+        let encoding = TextEncoding::detect(&fixture);
+
+        let actual = encoding.decode(&fixture);
+        let expected = "hello";
+        assert_eq!(actual, expected);
+        assert!(!encoding.is_utf8());
+        assert_eq!(encoding.encode(&actual), fixture);
+    }
+}
@@ -1,3 +1,5 @@
+use crate::TextEncoding;
+
 /// Information about a file or file range read operation
 #[derive(Debug, Clone, PartialEq)]
 pub struct FileInfo {
@@ -9,12 +11,26 @@ pub struct FileInfo {
 
     /// Total number of lines in the file
     pub total_lines: u64,
+
+    /// Encoding the file is stored in
+    pub encoding: TextEncoding,
 }
 
 impl FileInfo {
     /// Creates a new FileInfo with the specified parameters
     pub fn new(start_line: u64, end_line: u64, total_lines: u64) -> Self {
-        Self { start_line, end_line, total_lines }
+        Self {
+            start_line,
+            end_line,
+            total_lines,
+            encoding: TextEncoding::UTF_8,
+        }
+    }
+
+    /// Sets the encoding the file was decoded from
+    pub fn encoding(mut self, encoding: TextEncoding) -> Self {
+        self.encoding = encoding;
+        self
     }
 
     /// Returns true if this represents a partial file read
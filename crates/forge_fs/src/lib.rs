@@ -8,17 +8,21 @@
 //! the format "Failed to [operation] [path]", ensuring uniform error reporting
 //! throughout the application while preserving the original error cause.
 
+mod encoding;
 mod error;
 mod file_info;
 mod file_size;
 mod is_binary;
+mod lock;
 mod meta;
 mod read;
 mod read_range;
 mod write;
 
+pub use crate::encoding::TextEncoding;
 pub use crate::error::Error;
 pub use crate::file_info::FileInfo;
+pub use crate::lock::FileLock;
 
 /// ForgeFS provides a standardized interface for file system operations
 /// with consistent error handling.
@@ -0,0 +1,95 @@
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result};
+
+/// How long a process waits for the lock of another one
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Age after which a lock is considered left behind by a process that died
+/// while holding it
+const STALE_AFTER: Duration = Duration::from_secs(30);
+
+const RETRY_INTERVAL: Duration = Duration::from_millis(25);
+
+/// Lock of a file shared by several processes, held until it's dropped. It's
+/// a `.lock` file next to the locked one, created exclusively.
+#[derive(Debug)]
+pub struct FileLock {
+    path: PathBuf,
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+impl crate::ForgeFS {
+    /// Waits until no other process holds the lock of the file and takes it,
+    /// so that a read-modify-write of the file doesn't lose the updates of
+    /// the others
+    pub async fn lock<T: AsRef<Path>>(path: T) -> Result<FileLock> {
+        let mut lock = path.as_ref().as_os_str().to_owned();
+        lock.push(".lock");
+        let lock = PathBuf::from(lock);
+        if let Some(parent) = lock.parent() {
+            Self::create_dir_all(parent).await?;
+        }
+
+        let started = SystemTime::now();
+        loop {
+            let created = tokio::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&lock)
+                .await;
+            match created {
+                Ok(_) => return Ok(FileLock { path: lock }),
+                Err(error) if error.kind() == ErrorKind::AlreadyExists => {}
+                Err(error) => {
+                    return Err(error)
+                        .with_context(|| format!("Failed to lock file {}", lock.display()));
+                }
+            }
+
+            let stale = tokio::fs::metadata(&lock)
+                .await
+                .and_then(|metadata| metadata.modified())
+                .is_ok_and(|modified| modified.elapsed().unwrap_or_default() > STALE_AFTER);
+            if stale {
+                let _ = tokio::fs::remove_file(&lock).await;
+                continue;
+            }
+
+            if started.elapsed().unwrap_or_default() > LOCK_TIMEOUT {
+                anyhow::bail!("Timed out waiting for the lock {}", lock.display());
+            }
+            tokio::time::sleep(RETRY_INTERVAL).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn test_lock_is_released_on_drop() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("ledger.json");
+        let lock_path = dir.path().join("ledger.json.lock");
+
+        let lock = crate::ForgeFS::lock(&path).await?;
+        let held = lock_path.exists();
+        drop(lock);
+        let released = !lock_path.exists();
+
+        let actual = (held, released);
+        let expected = (true, true);
+        assert_eq!(actual, expected);
+        Ok(())
+    }
+}
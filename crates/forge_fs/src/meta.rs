@@ -1,4 +1,5 @@
 use std::path::Path;
+use std::time::SystemTime;
 
 use anyhow::{Context, Result};
 
@@ -11,9 +12,39 @@ impl crate::ForgeFS {
         path.as_ref().is_file()
     }
 
+    /// Gets the time of the last modification of the file or directory
+    pub async fn modified<T: AsRef<Path>>(path: T) -> Result<SystemTime> {
+        tokio::fs::metadata(path.as_ref())
+            .await
+            .and_then(|metadata| metadata.modified())
+            .with_context(|| {
+                format!(
+                    "Failed to get modification time of {}",
+                    path.as_ref().display()
+                )
+            })
+    }
+
     pub async fn read_dir<T: AsRef<Path>>(path: T) -> Result<tokio::fs::ReadDir> {
         tokio::fs::read_dir(path.as_ref())
             .await
             .with_context(|| format!("Failed to read directory {}", path.as_ref().display()))
     }
+
+    /// Names of the entries of the directory, skipping the names that aren't
+    /// valid UTF-8
+    pub async fn list_dir<T: AsRef<Path>>(path: T) -> Result<Vec<String>> {
+        let mut entries = Self::read_dir(path.as_ref()).await?;
+        let mut names = Vec::new();
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .with_context(|| format!("Failed to read directory {}", path.as_ref().display()))?
+        {
+            if let Ok(name) = entry.file_name().into_string() {
+                names.push(name);
+            }
+        }
+        Ok(names)
+    }
 }
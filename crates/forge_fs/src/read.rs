@@ -1,12 +1,23 @@
+use std::io::SeekFrom;
 use std::path::Path;
 
 use anyhow::{Context, Result};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+use crate::TextEncoding;
 
 impl crate::ForgeFS {
+    /// Reads a file as UTF-8, transcoding it from the encoding it's stored in
     pub async fn read_utf8<T: AsRef<Path>>(path: T) -> Result<String> {
-        Self::read(path)
-            .await
-            .map(|bytes| String::from_utf8_lossy(&bytes).to_string())
+        Self::read_text(path).await.map(|(content, _)| content)
+    }
+
+    /// Reads a file as UTF-8 along with the encoding it's stored in, so that
+    /// it can be written back in the same encoding
+    pub async fn read_text<T: AsRef<Path>>(path: T) -> Result<(String, TextEncoding)> {
+        let bytes = Self::read(path).await?;
+        let encoding = TextEncoding::detect(&bytes);
+        Ok((encoding.decode(&bytes), encoding))
     }
 
     pub async fn read<T: AsRef<Path>>(path: T) -> Result<Vec<u8>> {
@@ -15,9 +26,41 @@ impl crate::ForgeFS {
             .with_context(|| format!("Failed to read file {}", path.as_ref().display()))
     }
 
+    /// Reads the bytes of a file that follow `offset`, seeking past the
+    /// content before it
+    pub async fn read_from<T: AsRef<Path>>(path: T, offset: u64) -> Result<Vec<u8>> {
+        let mut file = tokio::fs::File::open(path.as_ref())
+            .await
+            .with_context(|| format!("Failed to open file {}", path.as_ref().display()))?;
+        file.seek(SeekFrom::Start(offset)).await?;
+        let mut content = Vec::new();
+        file.read_to_end(&mut content)
+            .await
+            .with_context(|| format!("Failed to read file {}", path.as_ref().display()))?;
+        Ok(content)
+    }
+
     pub async fn read_to_string<T: AsRef<Path>>(path: T) -> Result<String> {
         tokio::fs::read_to_string(path.as_ref())
             .await
             .with_context(|| format!("Failed to read file as string {}", path.as_ref().display()))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn test_read_from_offset() -> Result<()> {
+        let file = tempfile::NamedTempFile::new()?;
+        tokio::fs::write(file.path(), "first\nsecond\n").await?;
+
+        let actual = crate::ForgeFS::read_from(file.path(), 6).await?;
+
+        let expected = b"second\n".to_vec();
+        assert_eq!(actual, expected);
+        Ok(())
+    }
+}
@@ -5,6 +5,7 @@ use anyhow::{Context, Result};
 
 use crate::error::Error;
 use crate::file_info::FileInfo;
+use crate::TextEncoding;
 
 impl crate::ForgeFS {
     /// Reads a specific range of lines from a file.
@@ -44,10 +45,12 @@ impl crate::ForgeFS {
             return Err(Error::BinaryFileNotSupported(file_type).into());
         }
 
-        // Read file content
-        let content = tokio::fs::read_to_string(path_ref)
+        // Read file content, transcoding it to UTF-8
+        let bytes = tokio::fs::read(path_ref)
             .await
             .with_context(|| format!("Failed to read file content from {}", path_ref.display()))?;
+        let encoding = TextEncoding::detect(&bytes);
+        let content = encoding.decode(&bytes);
         if start_line < 2 && content.is_empty() {
             // If the file is empty, return empty content
             return Ok((String::new(), FileInfo::new(start_line, end_line, 0)));
@@ -70,7 +73,7 @@ impl crate::ForgeFS {
         // Cap end position at last line
         end_pos = cmp::min(end_pos, total_lines - 1);
 
-        let info = FileInfo::new(start_line, end_line, total_lines);
+        let info = FileInfo::new(start_line, end_line, total_lines).encoding(encoding);
 
         // Extract requested lines
         let result_content = if start_pos == 0 && end_pos == total_lines - 1 {
@@ -167,4 +170,16 @@ mod test {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_read_range_latin1() -> Result<()> {
+        let file = tempfile::NamedTempFile::new()?;
+        fs::write(file.path(), b"na\xefve\ncaf\xe9 cr\xe8me br\xfbl\xe9e").await?;
+
+        let (result, info) = crate::ForgeFS::read_range_utf8(file.path(), 2, 2).await?;
+        assert_eq!(result, "café crème brûlée");
+        assert_eq!(info.encoding.name(), "windows-1252");
+
+        Ok(())
+    }
 }
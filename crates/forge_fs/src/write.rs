@@ -15,9 +15,30 @@ impl crate::ForgeFS {
             .with_context(|| format!("Failed to write file {}", path.as_ref().display()))
     }
 
+    /// Appends to a file, creating it if it doesn't exist
+    pub async fn append<T: AsRef<Path>, U: AsRef<[u8]>>(path: T, contents: U) -> Result<()> {
+        use tokio::io::AsyncWriteExt;
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path.as_ref())
+            .await
+            .with_context(|| format!("Failed to open file {}", path.as_ref().display()))?;
+        file.write_all(contents.as_ref())
+            .await
+            .with_context(|| format!("Failed to append to file {}", path.as_ref().display()))
+    }
+
     pub async fn remove_file<T: AsRef<Path>>(path: T) -> Result<()> {
         tokio::fs::remove_file(path.as_ref())
             .await
             .with_context(|| format!("Failed to remove file {}", path.as_ref().display()))
     }
+
+    pub async fn remove_dir_all<T: AsRef<Path>>(path: T) -> Result<()> {
+        tokio::fs::remove_dir_all(path.as_ref())
+            .await
+            .with_context(|| format!("Failed to remove dir {}", path.as_ref().display()))
+    }
 }
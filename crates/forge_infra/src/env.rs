@@ -1,12 +1,13 @@
 use std::path::{Path, PathBuf};
 
-use forge_domain::{Environment, Provider, RetryConfig};
+use forge_domain::{Environment, Provider, ResourceLimits, RetryConfig, Storage, UsageQuota};
 use forge_services::EnvironmentInfra;
 use reqwest::Url;
 
 #[derive(Clone)]
 pub struct ForgeEnvironmentInfra {
     restricted: bool,
+    cwd: PathBuf,
 }
 
 impl ForgeEnvironmentInfra {
@@ -16,10 +17,17 @@ impl ForgeEnvironmentInfra {
     /// * `unrestricted` - If true, use unrestricted shell mode (sh/bash) If
     ///   false, use restricted shell mode (rbash)
     pub fn new(restricted: bool) -> Self {
-        Self::dot_env(&Self::cwd());
-        Self { restricted }
+        Self::with_cwd(restricted, Self::current_dir())
     }
-    fn cwd() -> PathBuf {
+
+    /// Creates a new EnvironmentFactory rooted at the given working directory
+    /// instead of the process working directory.
+    pub fn with_cwd(restricted: bool, cwd: PathBuf) -> Self {
+        Self::dot_env(&cwd);
+        Self { restricted, cwd }
+    }
+
+    fn current_dir() -> PathBuf {
         std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."))
     }
 
@@ -100,12 +108,133 @@ impl ForgeEnvironmentInfra {
                 config.max_redirects = parsed;
             }
         }
+        if let Ok(val) = std::env::var("FORGE_HTTP_TCP_KEEPALIVE") {
+            if let Ok(parsed) = val.parse::<u64>() {
+                config.tcp_keepalive = parsed;
+            }
+        }
+        if let Ok(val) = std::env::var("FORGE_HTTP_HTTP2") {
+            if let Ok(parsed) = val.parse::<bool>() {
+                config.http2 = parsed;
+            }
+        }
+        if let Ok(val) = std::env::var("FORGE_HTTP_HTTP2_KEEP_ALIVE_INTERVAL") {
+            if let Ok(parsed) = val.parse::<u64>() {
+                config.http2_keep_alive_interval = parsed;
+            }
+        }
+        if let Ok(val) = std::env::var("FORGE_HTTP_COMPRESS_REQUESTS") {
+            if let Ok(parsed) = val.parse::<bool>() {
+                config.compress_requests = parsed;
+            }
+        }
+        self.resolve_stream_stall(&mut config);
 
         config
     }
 
+    /// Resolves the stall detection of the responses of the providers:
+    /// `FORGE_STREAM_STALL_WARN` and `FORGE_STREAM_STALL_TIMEOUT` set it for
+    /// all of them, the same variables suffixed with the id of a provider,
+    /// e.g. `FORGE_STREAM_STALL_TIMEOUT_ANTHROPIC`, for that one
+    fn resolve_stream_stall(&self, config: &mut forge_domain::HttpConfig) {
+        let seconds = |key: &str| self.get_env_var(key).and_then(|value| value.parse().ok());
+        if let Some(warn_after) = seconds("FORGE_STREAM_STALL_WARN") {
+            config.stream_stall.warn_after = warn_after;
+        }
+        if let Some(abort_after) = seconds("FORGE_STREAM_STALL_TIMEOUT") {
+            config.stream_stall.abort_after = abort_after;
+        }
+
+        let defaults = config.stream_stall;
+        for (key, value) in self.get_env_vars() {
+            let Ok(value) = value.parse() else {
+                continue;
+            };
+            let (provider, warns) = match key.strip_prefix("FORGE_STREAM_STALL_WARN_") {
+                Some(provider) => (provider, true),
+                None => match key.strip_prefix("FORGE_STREAM_STALL_TIMEOUT_") {
+                    Some(provider) => (provider, false),
+                    None => continue,
+                },
+            };
+            let stall = config
+                .provider_stream_stall
+                .entry(provider.to_lowercase())
+                .or_insert(defaults);
+            if warns {
+                stall.warn_after = value;
+            } else {
+                stall.abort_after = value;
+            }
+        }
+    }
+
+    /// Resolves the daily usage limits set by the administrators of the
+    /// deployment
+    fn resolve_quota(&self) -> UsageQuota {
+        UsageQuota {
+            daily_tokens: self
+                .get_env_var("FORGE_QUOTA_DAILY_TOKENS")
+                .and_then(|value| value.parse().ok()),
+            daily_cost: self
+                .get_env_var("FORGE_QUOTA_DAILY_COST")
+                .and_then(|value| value.parse().ok()),
+            per_api_key: self
+                .get_env_var("FORGE_QUOTA_PER_API_KEY")
+                .and_then(|value| value.parse().ok())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Resolves where the conversations and the snapshots are kept, the local
+    /// disk unless `FORGE_STORAGE` points to a bucket
+    fn resolve_storage(&self) -> Storage {
+        let Some(location) = self.get_env_var("FORGE_STORAGE") else {
+            return Storage::Local;
+        };
+        let endpoint = self.get_env_var("FORGE_STORAGE_ENDPOINT");
+        let region = self
+            .get_env_var("FORGE_STORAGE_REGION")
+            .or_else(|| self.get_env_var("AWS_REGION"));
+        Storage::parse(&location, endpoint.as_deref(), region.as_deref()).unwrap_or_else(|error| {
+            tracing::warn!(%error, "Keeping the conversations and snapshots on the local disk");
+            Storage::Local
+        })
+    }
+
+    /// Resolves the directories outside of the workspace the tools may
+    /// access, relative ones being relative to the workspace
+    fn resolve_external_paths(&self) -> Vec<PathBuf> {
+        let Some(paths) = self.get_env_var("FORGE_EXTERNAL_PATHS") else {
+            return Vec::new();
+        };
+        std::env::split_paths(&paths)
+            .filter(|path| !path.as_os_str().is_empty())
+            .map(|path| self.cwd.join(path))
+            .collect()
+    }
+
+    /// Resolves the limits on the tools running at once, the defaults being
+    /// kept for the missing or invalid ones
+    fn resolve_resource_limits(&self) -> ResourceLimits {
+        let limit = |key: &str, default: usize| {
+            self.get_env_var(key)
+                .and_then(|value| value.parse().ok())
+                .filter(|limit| *limit > 0)
+                .unwrap_or(default)
+        };
+        let defaults = ResourceLimits::default();
+        ResourceLimits {
+            cpu: limit("FORGE_TOOL_LIMIT_CPU", defaults.cpu),
+            io: limit("FORGE_TOOL_LIMIT_IO", defaults.io),
+            network: limit("FORGE_TOOL_LIMIT_NETWORK", defaults.network),
+            probe: limit("FORGE_TOOL_LIMIT_PROBE", defaults.probe),
+        }
+    }
+
     fn get(&self) -> Environment {
-        let cwd = Self::cwd();
+        let cwd = self.cwd.clone();
         let retry_config = self.resolve_retry_config();
 
         let forge_api_url = self
@@ -131,6 +260,22 @@ impl ForgeEnvironmentInfra {
             stdout_max_suffix_length: 200,
             http: self.resolve_timeout_config(),
             max_file_size: 256 << 10, // 256 KiB
+            max_write_size: self
+                .get_env_var("FORGE_MAX_WRITE_SIZE")
+                .and_then(|size| size.parse().ok())
+                .unwrap_or(1 << 20), // 1 MiB
+            response_cache: self
+                .get_env_var("FORGE_RESPONSE_CACHE")
+                .and_then(|value| value.parse().ok())
+                .unwrap_or_default(),
+            quota: self.resolve_quota(),
+            storage: self.resolve_storage(),
+            external_paths: self.resolve_external_paths(),
+            resource_limits: self.resolve_resource_limits(),
+            text_coalesce_ms: self
+                .get_env_var("FORGE_TEXT_COALESCE_MS")
+                .and_then(|ms| ms.parse().ok())
+                .filter(|ms| *ms > 0),
             forge_api_url,
         }
     }
@@ -166,6 +311,12 @@ impl EnvironmentInfra for ForgeEnvironmentInfra {
     fn get_env_var(&self, key: &str) -> Option<String> {
         std::env::var(key).ok()
     }
+
+    fn get_env_vars(&self) -> Vec<(String, String)> {
+        std::env::vars_os()
+            .filter_map(|(name, value)| Some((name.into_string().ok()?, value.into_string().ok()?)))
+            .collect()
+    }
 }
 
 #[cfg(test)]
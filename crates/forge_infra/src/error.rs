@@ -2,4 +2,22 @@
 pub enum Error {
     #[error("Unsupported MCP response: {0}")]
     UnsupportedMcpResponse(&'static str),
+
+    #[error("MCP server doesn't support resource subscriptions")]
+    UnsupportedMcpSubscription,
+
+    #[error("MCP server answered with status {0}")]
+    McpHttpStatus(reqwest::StatusCode),
+
+    #[error(transparent)]
+    McpHttp(#[from] reqwest::Error),
+
+    #[error("MCP WebSocket failed: {0}")]
+    McpWebSocket(Box<tokio_tungstenite::tungstenite::Error>),
+
+    #[error("MCP WebSocket is closed")]
+    McpWebSocketClosed,
+
+    #[error("Invalid MCP message: {0}")]
+    InvalidMcpMessage(#[from] serde_json::Error),
 }
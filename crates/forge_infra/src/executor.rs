@@ -1,8 +1,10 @@
+use std::collections::BTreeMap;
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use forge_domain::{CommandOutput, Environment};
+use anyhow::Context;
+use forge_domain::{CommandOutput, Environment, OutputRing};
 use forge_services::CommandInfra;
 use tokio::io::AsyncReadExt;
 use tokio::process::Command;
@@ -23,7 +25,12 @@ impl ForgeCommandExecutorService {
         Self { restricted, env, ready: Arc::new(Mutex::new(())) }
     }
 
-    fn prepare_command(&self, command_str: &str, working_dir: Option<&Path>) -> Command {
+    fn prepare_command(
+        &self,
+        command_str: &str,
+        working_dir: Option<&Path>,
+        env: &BTreeMap<String, String>,
+    ) -> Command {
         // Create a basic command
         let is_windows = cfg!(target_os = "windows");
         let shell = if self.restricted && !is_windows {
@@ -50,6 +57,9 @@ impl ForgeCommandExecutorService {
         // Other common tools
         command.env("GREP_OPTIONS", "--color=always"); // GNU grep
 
+        // Variables of the conversation take precedence over the defaults above
+        command.envs(env);
+
         let parameter = if is_windows { "/C" } else { "-c" };
         command.arg(parameter);
 
@@ -76,15 +86,18 @@ impl ForgeCommandExecutorService {
         command
     }
 
-    /// Internal method to execute commands with streaming to console
+    /// Internal method to execute commands with streaming to console, or to
+    /// the ring when one is given
     async fn execute_command_internal(
         &self,
         command: String,
         working_dir: &Path,
+        env: &BTreeMap<String, String>,
+        progress: Option<OutputRing>,
     ) -> anyhow::Result<CommandOutput> {
         let ready = self.ready.lock().await;
 
-        let mut prepared_command = self.prepare_command(&command, Some(working_dir));
+        let mut prepared_command = self.prepare_command(&command, Some(working_dir), env);
 
         // Spawn the command
         let mut child = prepared_command.spawn()?;
@@ -92,11 +105,19 @@ impl ForgeCommandExecutorService {
         let mut stdout_pipe = child.stdout.take();
         let mut stderr_pipe = child.stderr.take();
 
+        let (stdout, stderr): (Box<dyn Write + Send>, Box<dyn Write + Send>) = match progress {
+            Some(ring) => (
+                Box::new(RingWriter::new(ring.clone())),
+                Box::new(RingWriter::new(ring)),
+            ),
+            None => (Box::new(io::stdout()), Box::new(io::stderr())),
+        };
+
         // Stream the output of the command to stdout and stderr concurrently
         let (status, stdout_buffer, stderr_buffer) = tokio::try_join!(
             child.wait(),
-            stream(&mut stdout_pipe, io::stdout()),
-            stream(&mut stderr_pipe, io::stderr())
+            stream(&mut stdout_pipe, stdout),
+            stream(&mut stderr_pipe, stderr)
         )?;
 
         // Drop happens after `try_join` due to <https://github.com/tokio-rs/tokio/issues/4309>
@@ -113,6 +134,59 @@ impl ForgeCommandExecutorService {
     }
 }
 
+/// Writes the output of a command to a ring streamed to the UI
+struct RingWriter {
+    ring: OutputRing,
+    /// Bytes of a character split across writes, kept until the next write
+    pending: Vec<u8>,
+}
+
+impl RingWriter {
+    fn new(ring: OutputRing) -> Self {
+        Self { ring, pending: Vec::new() }
+    }
+}
+
+impl Write for RingWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+        let complete = self.pending.len() - incomplete_tail(&self.pending);
+        let chunk = self.pending.drain(..complete).collect::<Vec<_>>();
+        self.ring.push(&String::from_utf8_lossy(&chunk));
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for RingWriter {
+    fn drop(&mut self) {
+        if !self.pending.is_empty() {
+            self.ring.push(&String::from_utf8_lossy(&self.pending));
+        }
+    }
+}
+
+/// Length of the UTF-8 character the bytes end in the middle of, 0 when they
+/// end on a character boundary
+fn incomplete_tail(bytes: &[u8]) -> usize {
+    for (len, byte) in bytes.iter().rev().take(4).enumerate() {
+        let len = len + 1;
+        // Continuation bytes are 0b10xxxxxx, the others start a character
+        if byte & 0b1100_0000 == 0b1000_0000 {
+            continue;
+        }
+        let expected = match byte.leading_ones() {
+            count @ 2..=4 => count as usize,
+            _ => 1,
+        };
+        return if expected > len { len } else { 0 };
+    }
+    0
+}
+
 /// reads the output from A and writes it to W
 async fn stream<A: AsyncReadExt + Unpin, W: Write>(
     io: &mut Option<A>,
@@ -142,12 +216,52 @@ impl CommandInfra for ForgeCommandExecutorService {
         &self,
         command: String,
         working_dir: PathBuf,
+        env: BTreeMap<String, String>,
     ) -> anyhow::Result<CommandOutput> {
-        self.execute_command_internal(command, &working_dir).await
+        self.execute_command_internal(command, &working_dir, &env, None)
+            .await
+    }
+
+    async fn execute_command_with_progress(
+        &self,
+        command: String,
+        working_dir: PathBuf,
+        env: BTreeMap<String, String>,
+        progress: OutputRing,
+    ) -> anyhow::Result<CommandOutput> {
+        self.execute_command_internal(command, &working_dir, &env, Some(progress))
+            .await
+    }
+
+    async fn execute_program(
+        &self,
+        program: &str,
+        args: Vec<String>,
+        working_dir: PathBuf,
+    ) -> anyhow::Result<CommandOutput> {
+        tracing::info!(program, args = ?args, "Executing program");
+        let output = Command::new(program)
+            .args(&args)
+            .current_dir(working_dir)
+            .stdin(std::process::Stdio::null())
+            .kill_on_drop(true)
+            .output()
+            .await
+            .with_context(|| format!("Failed to run {program}, is it installed?"))?;
+
+        Ok(CommandOutput {
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            exit_code: output.status.code(),
+            command: std::iter::once(program.to_string())
+                .chain(args)
+                .collect::<Vec<_>>()
+                .join(" "),
+        })
     }
 
     async fn execute_command_raw(&self, command: &str) -> anyhow::Result<std::process::ExitStatus> {
-        let mut prepared_command = self.prepare_command(command, None);
+        let mut prepared_command = self.prepare_command(command, None, &BTreeMap::new());
 
         // overwrite the stdin, stdout and stderr to inherit
         prepared_command
@@ -188,6 +302,13 @@ mod tests {
             stdout_max_suffix_length: 0,
             http: Default::default(),
             max_file_size: 10_000_000,
+            max_write_size: 10_000_000,
+            external_paths: vec![],
+            resource_limits: Default::default(),
+            text_coalesce_ms: None,
+            response_cache: false,
+            quota: Default::default(),
+            storage: Default::default(),
             forge_api_url: Url::parse("http://forgecode.dev/api").unwrap(),
         }
     }
@@ -199,7 +320,7 @@ mod tests {
         let dir = ".";
 
         let actual = fixture
-            .execute_command(cmd.to_string(), PathBuf::new().join(dir))
+            .execute_command(cmd.to_string(), PathBuf::new().join(dir), BTreeMap::new())
             .await
             .unwrap();
 
@@ -218,4 +339,41 @@ mod tests {
         assert_eq!(actual.stderr, expected.stderr);
         assert_eq!(actual.success(), expected.success());
     }
+
+    #[test]
+    fn test_ring_writer_split_character() {
+        let ring = OutputRing::new(10);
+        let bytes = "héllo ✓\n".as_bytes();
+        let mut fixture = RingWriter::new(ring.clone());
+
+        // Splits both multi-byte characters across writes
+        fixture.write_all(&bytes[..2]).unwrap();
+        fixture.write_all(&bytes[2..8]).unwrap();
+        fixture.write_all(&bytes[8..]).unwrap();
+
+        let actual = ring.flush();
+        let expected = Some("héllo ✓\n".to_string());
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_command_executor_with_env() {
+        let fixture = ForgeCommandExecutorService::new(false, test_env());
+        let cmd = if cfg!(target_os = "windows") {
+            "echo %DATABASE_URL%"
+        } else {
+            "echo $DATABASE_URL"
+        };
+        let env = BTreeMap::from([(
+            "DATABASE_URL".to_string(),
+            "postgres://localhost/test".to_string(),
+        )]);
+
+        let actual = fixture
+            .execute_command(cmd.to_string(), PathBuf::new().join("."), env)
+            .await
+            .unwrap();
+
+        assert_eq!(actual.stdout.trim(), "postgres://localhost/test");
+    }
 }
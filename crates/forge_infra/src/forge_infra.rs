@@ -1,14 +1,16 @@
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::process::ExitStatus;
 use std::sync::Arc;
+use std::time::SystemTime;
 
 use bytes::Bytes;
-use forge_domain::{CommandOutput, Environment, McpServerConfig};
+use forge_domain::{CommandOutput, Environment, McpServerConfig, OutputRing};
 use forge_fs::FileInfo as FileInfoData;
 use forge_services::{
     CommandInfra, EnvironmentInfra, FileDirectoryInfra, FileInfoInfra, FileReaderInfra,
-    FileRemoverInfra, FileWriterInfra, HttpInfra, McpServerInfra, SnapshotInfra, UserInfra,
-    WalkerInfra,
+    FileRemoverInfra, FileWriterInfra, HttpInfra, McpServerInfra, SnapshotInfra, StorageInfra,
+    UserInfra, WalkerInfra,
 };
 use reqwest::header::HeaderMap;
 use reqwest::Response;
@@ -25,6 +27,7 @@ use crate::http::ForgeHttpService;
 use crate::inquire::ForgeInquire;
 use crate::mcp_client::ForgeMcpClient;
 use crate::mcp_server::ForgeMcpServer;
+use crate::storage::ForgeStorageService;
 use crate::walker::ForgeWalkerService;
 
 #[derive(Clone)]
@@ -41,13 +44,28 @@ pub struct ForgeInfra {
     mcp_server: ForgeMcpServer,
     walker_service: Arc<ForgeWalkerService>,
     http_service: Arc<ForgeHttpService>,
+    storage_service: Arc<ForgeStorageService>,
 }
 
 impl ForgeInfra {
     pub fn new(restricted: bool) -> Self {
-        let environment_service = Arc::new(ForgeEnvironmentInfra::new(restricted));
+        Self::from_env_infra(restricted, ForgeEnvironmentInfra::new(restricted))
+    }
+
+    /// Creates the infrastructure rooted at `cwd` rather than the process
+    /// working directory.
+    pub fn with_cwd(restricted: bool, cwd: PathBuf) -> Self {
+        Self::from_env_infra(restricted, ForgeEnvironmentInfra::with_cwd(restricted, cwd))
+    }
+
+    fn from_env_infra(restricted: bool, environment_service: ForgeEnvironmentInfra) -> Self {
+        let environment_service = Arc::new(environment_service);
         let env = environment_service.get_environment();
-        let file_snapshot_service = Arc::new(ForgeFileSnapshotService::new(env.clone()));
+        let storage_service = Arc::new(ForgeStorageService::new(&env));
+        let file_snapshot_service = Arc::new(ForgeFileSnapshotService::new(
+            env.clone(),
+            storage_service.clone(),
+        ));
         let http_service = Arc::new(ForgeHttpService::new());
         Self {
             file_read_service: Arc::new(ForgeFileReadService::new()),
@@ -64,9 +82,10 @@ impl ForgeInfra {
                 env.clone(),
             )),
             inquire_service: Arc::new(ForgeInquire::new()),
-            mcp_server: ForgeMcpServer,
+            mcp_server: ForgeMcpServer::new(env.http.clone()),
             walker_service: Arc::new(ForgeWalkerService::new()),
             http_service,
+            storage_service,
         }
     }
 }
@@ -79,6 +98,10 @@ impl EnvironmentInfra for ForgeInfra {
     fn get_env_var(&self, key: &str) -> Option<String> {
         self.environment_service.get_env_var(key)
     }
+
+    fn get_env_vars(&self) -> Vec<(String, String)> {
+        self.environment_service.get_env_vars()
+    }
 }
 
 #[async_trait::async_trait]
@@ -91,6 +114,10 @@ impl FileReaderInfra for ForgeInfra {
         self.file_read_service.read(path).await
     }
 
+    async fn read_from(&self, path: &Path, offset: u64) -> anyhow::Result<Vec<u8>> {
+        self.file_read_service.read_from(path, offset).await
+    }
+
     async fn range_read_utf8(
         &self,
         path: &Path,
@@ -116,11 +143,19 @@ impl FileWriterInfra for ForgeInfra {
             .await
     }
 
+    async fn append(&self, path: &Path, contents: Bytes) -> anyhow::Result<()> {
+        self.file_write_service.append(path, contents).await
+    }
+
     async fn write_temp(&self, prefix: &str, ext: &str, content: &str) -> anyhow::Result<PathBuf> {
         self.file_write_service
             .write_temp(prefix, ext, content)
             .await
     }
+
+    async fn lock(&self, path: &Path) -> anyhow::Result<forge_fs::FileLock> {
+        self.file_write_service.lock(path).await
+    }
 }
 
 #[async_trait::async_trait]
@@ -136,6 +171,14 @@ impl FileInfoInfra for ForgeInfra {
     async fn file_size(&self, path: &Path) -> anyhow::Result<u64> {
         self.file_meta_service.file_size(path).await
     }
+
+    async fn modified(&self, path: &Path) -> anyhow::Result<SystemTime> {
+        self.file_meta_service.modified(path).await
+    }
+
+    async fn list_dir(&self, path: &Path) -> anyhow::Result<Vec<String>> {
+        self.file_meta_service.list_dir(path).await
+    }
 }
 
 #[async_trait::async_trait]
@@ -147,12 +190,47 @@ impl SnapshotInfra for ForgeInfra {
     async fn undo_snapshot(&self, file_path: &Path) -> anyhow::Result<()> {
         self.file_snapshot_service.undo_snapshot(file_path).await
     }
+
+    async fn list_snapshots(&self, file_path: &Path) -> anyhow::Result<Vec<forge_snaps::Snapshot>> {
+        self.file_snapshot_service.list_snapshots(file_path).await
+    }
+
+    async fn restore_snapshot(
+        &self,
+        file_path: &Path,
+        id: &forge_snaps::SnapshotId,
+    ) -> anyhow::Result<()> {
+        self.file_snapshot_service
+            .restore_snapshot(file_path, id)
+            .await
+    }
+
+    async fn apply_snapshot(
+        &self,
+        file_path: &Path,
+        id: &forge_snaps::SnapshotId,
+    ) -> anyhow::Result<()> {
+        self.file_snapshot_service
+            .apply_snapshot(file_path, id)
+            .await
+    }
+
+    async fn purge_snapshots(
+        &self,
+        policy: &forge_snaps::RetentionPolicy,
+    ) -> anyhow::Result<usize> {
+        self.file_snapshot_service.purge_snapshots(policy).await
+    }
 }
 
 #[async_trait::async_trait]
 impl FileRemoverInfra for ForgeInfra {
-    async fn remove(&self, path: &Path) -> anyhow::Result<()> {
-        self.file_remove_service.remove(path).await
+    async fn remove(&self, path: &Path, permanent: bool) -> anyhow::Result<()> {
+        self.file_remove_service.remove(path, permanent).await
+    }
+
+    async fn remove_dir(&self, path: &Path, permanent: bool) -> anyhow::Result<Vec<PathBuf>> {
+        self.file_remove_service.remove_dir(path, permanent).await
     }
 }
 
@@ -169,9 +247,33 @@ impl CommandInfra for ForgeInfra {
         &self,
         command: String,
         working_dir: PathBuf,
+        env: BTreeMap<String, String>,
+    ) -> anyhow::Result<CommandOutput> {
+        self.command_executor_service
+            .execute_command(command, working_dir, env)
+            .await
+    }
+
+    async fn execute_command_with_progress(
+        &self,
+        command: String,
+        working_dir: PathBuf,
+        env: BTreeMap<String, String>,
+        progress: OutputRing,
+    ) -> anyhow::Result<CommandOutput> {
+        self.command_executor_service
+            .execute_command_with_progress(command, working_dir, env, progress)
+            .await
+    }
+
+    async fn execute_program(
+        &self,
+        program: &str,
+        args: Vec<String>,
+        working_dir: PathBuf,
     ) -> anyhow::Result<CommandOutput> {
         self.command_executor_service
-            .execute_command(command, working_dir)
+            .execute_program(program, args, working_dir)
             .await
     }
 
@@ -219,6 +321,10 @@ impl WalkerInfra for ForgeInfra {
     async fn walk(&self, config: forge_app::Walker) -> anyhow::Result<Vec<forge_app::WalkedFile>> {
         self.walker_service.walk(config).await
     }
+
+    async fn count_entries(&self, cwd: PathBuf, limit: usize) -> anyhow::Result<usize> {
+        self.walker_service.count(cwd, limit).await
+    }
 }
 
 #[async_trait::async_trait]
@@ -235,3 +341,22 @@ impl HttpInfra for ForgeInfra {
         self.http_service.delete(url).await
     }
 }
+
+#[async_trait::async_trait]
+impl StorageInfra for ForgeInfra {
+    async fn put_object(&self, key: &str, content: Bytes) -> anyhow::Result<()> {
+        self.storage_service.put_object(key, content).await
+    }
+
+    async fn get_object(&self, key: &str) -> anyhow::Result<Option<Bytes>> {
+        self.storage_service.get_object(key).await
+    }
+
+    async fn list_objects(&self, prefix: &str) -> anyhow::Result<Vec<String>> {
+        self.storage_service.list_objects(prefix).await
+    }
+
+    async fn delete_object(&self, key: &str) -> anyhow::Result<()> {
+        self.storage_service.delete_object(key).await
+    }
+}
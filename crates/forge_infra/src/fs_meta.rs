@@ -1,4 +1,5 @@
 use std::path::Path;
+use std::time::SystemTime;
 
 use anyhow::Result;
 use forge_services::FileInfoInfra;
@@ -17,4 +18,12 @@ impl FileInfoInfra for ForgeFileMetaService {
     async fn file_size(&self, path: &Path) -> Result<u64> {
         forge_fs::ForgeFS::file_size(path).await
     }
+
+    async fn modified(&self, path: &Path) -> Result<SystemTime> {
+        forge_fs::ForgeFS::modified(path).await
+    }
+
+    async fn list_dir(&self, path: &Path) -> Result<Vec<String>> {
+        forge_fs::ForgeFS::list_dir(path).await
+    }
 }
@@ -27,6 +27,10 @@ impl FileReaderInfra for ForgeFileReadService {
         forge_fs::ForgeFS::read(path).await
     }
 
+    async fn read_from(&self, path: &Path, offset: u64) -> Result<Vec<u8>> {
+        forge_fs::ForgeFS::read_from(path, offset).await
+    }
+
     async fn range_read_utf8(
         &self,
         path: &Path,
@@ -1,7 +1,10 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+use anyhow::Context;
+use forge_fs::ForgeFS;
 use forge_services::{FileRemoverInfra, SnapshotInfra};
+use forge_walker::Walker;
 
 #[derive(Default)]
 pub struct ForgeFileRemoveService<S> {
@@ -16,8 +19,76 @@ impl<S> ForgeFileRemoveService<S> {
 
 #[async_trait::async_trait]
 impl<S: SnapshotInfra> FileRemoverInfra for ForgeFileRemoveService<S> {
-    async fn remove(&self, path: &Path) -> anyhow::Result<()> {
+    async fn remove(&self, path: &Path, permanent: bool) -> anyhow::Result<()> {
         let _ = self.snaps.create_snapshot(path).await?;
-        Ok(forge_fs::ForgeFS::remove_file(path).await?)
+        if permanent {
+            ForgeFS::remove_file(path).await
+        } else {
+            move_to_trash(path).await
+        }
+    }
+
+    async fn remove_dir(&self, path: &Path, permanent: bool) -> anyhow::Result<Vec<PathBuf>> {
+        if !path.is_dir() {
+            anyhow::bail!("{} is not a directory", path.display());
+        }
+
+        // Ignored files are usually build artifacts or dependencies that can be
+        // regenerated, so only the others are worth snapshotting
+        let files = Walker::max_all().cwd(path.to_path_buf()).get().await?;
+        let mut snapshotted = Vec::new();
+        for file in files.into_iter().filter(|file| !file.is_dir()) {
+            let file = path.join(file.path);
+            let _ = self.snaps.create_snapshot(&file).await?;
+            snapshotted.push(file);
+        }
+
+        if permanent {
+            ForgeFS::remove_dir_all(path).await?;
+        } else {
+            move_to_trash(path).await?;
+        }
+
+        Ok(snapshotted)
+    }
+}
+
+/// Moves a file or directory to the trash of the operating system
+async fn move_to_trash(path: &Path) -> anyhow::Result<()> {
+    let target = path.to_path_buf();
+    tokio::task::spawn_blocking(move || trash::delete(target))
+        .await?
+        .with_context(|| {
+            format!(
+                "Failed to move {} to the trash, remove it permanently instead",
+                path.display()
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::fs_write::tests::MockSnapshotService;
+
+    #[tokio::test]
+    async fn test_remove_dir_skips_ignored_files() {
+        let fixture = tempfile::TempDir::new().unwrap();
+        let dir = fixture.path().join("build");
+        std::fs::create_dir_all(dir.join("target")).unwrap();
+        std::fs::write(dir.join(".gitignore"), "target/\n").unwrap();
+        std::fs::write(dir.join("notes.md"), "notes").unwrap();
+        std::fs::write(dir.join("target").join("artifact.bin"), "bin").unwrap();
+        // Git ignore files are only honored inside of a repository
+        std::fs::create_dir_all(dir.join(".git")).unwrap();
+
+        let service = ForgeFileRemoveService::new(Arc::new(MockSnapshotService));
+        let actual = service.remove_dir(&dir, true).await.unwrap();
+
+        let expected = vec![dir.join("notes.md")];
+        assert_eq!(actual, expected);
+        assert!(!dir.exists());
     }
 }
@@ -3,18 +3,72 @@ use std::sync::Arc;
 
 use anyhow::Result;
 use forge_domain::Environment;
-use forge_services::SnapshotInfra;
-use forge_snaps::Snapshot;
+use forge_services::{SnapshotInfra, StorageInfra};
+use forge_snaps::{RetentionPolicy, Snapshot, SnapshotId};
+
+use crate::storage::{ForgeStorageService, LocalStorage};
+
+/// Directory of the snapshots, under the base path and in the storage
+const SNAPSHOTS: &str = "snapshots";
 
 pub struct ForgeFileSnapshotService {
     inner: Arc<forge_snaps::SnapshotService>,
+    /// Base path, whose snapshots directory is mirrored to the remote storage
+    local: LocalStorage,
+    /// Storage the snapshots are mirrored to when it isn't the local disk, so
+    /// that they can be undone on another machine
+    remote: Option<Arc<ForgeStorageService>>,
 }
 
 impl ForgeFileSnapshotService {
-    pub fn new(env: Environment) -> Self {
+    pub fn new(env: Environment, storage: Arc<ForgeStorageService>) -> Self {
         Self {
             inner: Arc::new(forge_snaps::SnapshotService::new(env.snapshot_path())),
+            local: LocalStorage::new(env.base_path.clone()),
+            remote: storage.is_remote().then_some(storage),
+        }
+    }
+
+    /// Prefix of the keys of the snapshots of the file
+    fn prefix(file_path: &Path) -> Result<String> {
+        let snapshot = Snapshot::create(file_path.to_path_buf())?;
+        Ok(format!("{SNAPSHOTS}/{}/", snapshot.path_hash()))
+    }
+
+    /// Downloads the snapshots under the prefix that aren't on the disk
+    async fn pull(&self, prefix: &str) -> Result<()> {
+        let Some(remote) = &self.remote else {
+            return Ok(());
+        };
+        let local = self.local.list_objects(prefix).await?;
+        for key in remote.list_objects(prefix).await? {
+            if local.contains(&key) {
+                continue;
+            }
+            if let Some(content) = remote.get_object(&key).await? {
+                self.local.put_object(&key, content).await?;
+            }
         }
+        Ok(())
+    }
+
+    /// Uploads the snapshots under the prefix missing from the storage and
+    /// removes the ones that were removed from the disk
+    async fn push(&self, prefix: &str) -> Result<()> {
+        let Some(remote) = &self.remote else {
+            return Ok(());
+        };
+        let local = self.local.list_objects(prefix).await?;
+        let stored = remote.list_objects(prefix).await?;
+        for key in local.iter().filter(|key| !stored.contains(key)) {
+            if let Some(content) = self.local.get_object(key).await? {
+                remote.put_object(key, content).await?;
+            }
+        }
+        for key in stored.iter().filter(|key| !local.contains(key)) {
+            remote.delete_object(key).await?;
+        }
+        Ok(())
     }
 }
 
@@ -22,11 +76,45 @@ impl ForgeFileSnapshotService {
 impl SnapshotInfra for ForgeFileSnapshotService {
     // Creation
     async fn create_snapshot(&self, file_path: &Path) -> Result<Snapshot> {
-        self.inner.create_snapshot(file_path.to_path_buf()).await
+        let prefix = Self::prefix(file_path)?;
+        self.pull(&prefix).await?;
+        let snapshot = self.inner.create_snapshot(file_path.to_path_buf()).await?;
+        self.push(&prefix).await?;
+        Ok(snapshot)
     }
 
     // Undo
     async fn undo_snapshot(&self, file_path: &Path) -> Result<()> {
-        self.inner.undo_snapshot(file_path.to_path_buf()).await
+        let prefix = Self::prefix(file_path)?;
+        self.pull(&prefix).await?;
+        self.inner.undo_snapshot(file_path.to_path_buf()).await?;
+        self.push(&prefix).await
+    }
+
+    async fn list_snapshots(&self, file_path: &Path) -> Result<Vec<Snapshot>> {
+        self.pull(&Self::prefix(file_path)?).await?;
+        self.inner.list_snapshots(file_path.to_path_buf()).await
+    }
+
+    async fn restore_snapshot(&self, file_path: &Path, id: &SnapshotId) -> Result<()> {
+        let prefix = Self::prefix(file_path)?;
+        self.pull(&prefix).await?;
+        self.inner
+            .restore_snapshot(file_path.to_path_buf(), id)
+            .await?;
+        self.push(&prefix).await
+    }
+
+    async fn apply_snapshot(&self, file_path: &Path, id: &SnapshotId) -> Result<()> {
+        self.pull(&Self::prefix(file_path)?).await?;
+        self.inner.apply_snapshot(file_path.to_path_buf(), id).await
+    }
+
+    async fn purge_snapshots(&self, policy: &RetentionPolicy) -> Result<usize> {
+        let prefix = format!("{SNAPSHOTS}/");
+        self.pull(&prefix).await?;
+        let purged = self.inner.purge(policy).await?;
+        self.push(&prefix).await?;
+        Ok(purged)
     }
 }
@@ -41,6 +41,15 @@ impl<S: SnapshotInfra> FileWriterInfra for ForgeFileWriteService<S> {
         Ok(forge_fs::ForgeFS::write(path, contents.to_vec()).await?)
     }
 
+    async fn append(&self, path: &Path, contents: Bytes) -> anyhow::Result<()> {
+        self.create_parent_dirs(path).await?;
+        forge_fs::ForgeFS::append(path, contents).await
+    }
+
+    async fn lock(&self, path: &Path) -> anyhow::Result<forge_fs::FileLock> {
+        forge_fs::ForgeFS::lock(path).await
+    }
+
     async fn write_temp(&self, prefix: &str, ext: &str, content: &str) -> anyhow::Result<PathBuf> {
         let path = tempfile::Builder::new()
             .disable_cleanup(true)
@@ -58,15 +67,15 @@ impl<S: SnapshotInfra> FileWriterInfra for ForgeFileWriteService<S> {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use std::sync::Arc;
 
-    use forge_snaps::Snapshot;
+    use forge_snaps::{RetentionPolicy, Snapshot, SnapshotId};
     use tempfile::tempdir;
 
     use super::*;
 
-    struct MockSnapshotService;
+    pub(crate) struct MockSnapshotService;
 
     #[async_trait::async_trait]
     impl SnapshotInfra for MockSnapshotService {
@@ -81,6 +90,22 @@ mod tests {
         async fn undo_snapshot(&self, _path: &Path) -> anyhow::Result<()> {
             Ok(())
         }
+
+        async fn list_snapshots(&self, _path: &Path) -> anyhow::Result<Vec<Snapshot>> {
+            Ok(Vec::new())
+        }
+
+        async fn restore_snapshot(&self, _path: &Path, _id: &SnapshotId) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn apply_snapshot(&self, _path: &Path, _id: &SnapshotId) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn purge_snapshots(&self, _policy: &RetentionPolicy) -> anyhow::Result<usize> {
+            Ok(0)
+        }
     }
 
     fn create_test_service() -> ForgeFileWriteService<MockSnapshotService> {
@@ -109,4 +134,18 @@ mod tests {
         assert!(actual.is_ok());
         assert!(nested_file_path.parent().unwrap().exists());
     }
+
+    #[tokio::test]
+    async fn test_append_creates_and_extends_file() {
+        let temp_dir = tempdir().unwrap();
+        let service = create_test_service();
+        let path = temp_dir.path().join("journal").join("events.jsonl");
+
+        service.append(&path, Bytes::from("a\n")).await.unwrap();
+        service.append(&path, Bytes::from("b\n")).await.unwrap();
+
+        let actual = std::fs::read_to_string(&path).unwrap();
+        let expected = "a\nb\n";
+        assert_eq!(actual, expected);
+    }
 }
@@ -12,7 +12,10 @@ mod fs_write;
 mod http;
 mod inquire;
 mod mcp_client;
+mod mcp_http;
 mod mcp_server;
+mod mcp_ws;
+mod storage;
 mod walker;
 
 pub use executor::ForgeCommandExecutorService;
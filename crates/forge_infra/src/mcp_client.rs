@@ -1,43 +1,175 @@
 use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::future::Future;
 use std::sync::{Arc, RwLock};
 
 use backon::{ExponentialBuilder, Retryable};
-use forge_domain::{Image, McpServerConfig, ToolDefinition, ToolName, ToolOutput};
+use forge_domain::{
+    AttachmentContent, HttpConfig, Image, McpHttpTransport, McpNotification, McpPrompt,
+    McpPromptArgument, McpResource, McpSampling, McpSamplingMessage, McpSamplingRequest,
+    McpServerConfig, Role, ToolDefinition, ToolName, ToolOutput,
+};
 use forge_services::McpClientInfra;
-use rmcp::model::{CallToolRequestParam, ClientInfo, Implementation, InitializeRequestParam};
+use rmcp::model::{
+    CallToolRequestParam, ClientCapabilities, ClientInfo, Content, CreateMessageRequestParam,
+    CreateMessageResult, GetPromptRequestParam, Implementation, PromptMessageContent, RawContent,
+    ReadResourceRequestParam, ResourceContents, ResourceUpdatedNotificationParam, SamplingMessage,
+    SubscribeRequestParam, UnsubscribeRequestParam,
+};
 use rmcp::schemars::schema::RootSchema;
-use rmcp::service::RunningService;
+use rmcp::service::{RequestContext, RunningService};
 use rmcp::transport::TokioChildProcess;
-use rmcp::{RoleClient, ServiceExt};
+use rmcp::{ClientHandler, Error as McpError, RoleClient, ServiceExt};
 use serde_json::Value;
 use tokio::process::Command;
+use tokio::sync::{broadcast, mpsc, oneshot, Mutex};
 
 use crate::error::Error;
+use crate::mcp_http::StreamableHttpTransport;
+use crate::mcp_ws::{is_websocket, WebSocketTransport};
 
 const VERSION: &str = match option_env!("APP_VERSION") {
     Some(val) => val,
     None => env!("CARGO_PKG_VERSION"),
 };
 
-type RmcpClient = RunningService<RoleClient, InitializeRequestParam>;
+/// Notifications kept for the subscribers that haven't received them yet
+const NOTIFICATION_CAPACITY: usize = 32;
+
+type RmcpClient = RunningService<RoleClient, ForgeMcpHandler>;
+
+/// Handles what the server sends on its own initiative, forwarding its
+/// notifications to the subscribers of the client and its sampling requests
+/// to the one answering them
+#[derive(Clone)]
+struct ForgeMcpHandler {
+    info: ClientInfo,
+    notifications: broadcast::Sender<McpNotification>,
+    sampling: mpsc::UnboundedSender<McpSampling>,
+}
+
+impl ForgeMcpHandler {
+    fn notify(&self, notification: McpNotification) -> impl Future<Output = ()> + Send + 'static {
+        // Notifications nobody subscribed to are dropped
+        let _ = self.notifications.send(notification);
+        std::future::ready(())
+    }
+}
+
+impl ClientHandler for ForgeMcpHandler {
+    fn get_info(&self) -> ClientInfo {
+        self.info.clone()
+    }
+
+    fn on_tool_list_changed(&self) -> impl Future<Output = ()> + Send + '_ {
+        self.notify(McpNotification::ToolListChanged)
+    }
+
+    fn on_resource_list_changed(&self) -> impl Future<Output = ()> + Send + '_ {
+        self.notify(McpNotification::ResourceListChanged)
+    }
+
+    fn on_resource_updated(
+        &self,
+        params: ResourceUpdatedNotificationParam,
+    ) -> impl Future<Output = ()> + Send + '_ {
+        self.notify(McpNotification::ResourceUpdated { uri: params.uri })
+    }
+
+    fn on_prompt_list_changed(&self) -> impl Future<Output = ()> + Send + '_ {
+        self.notify(McpNotification::PromptListChanged)
+    }
+
+    fn create_message(
+        &self,
+        params: CreateMessageRequestParam,
+        _context: RequestContext<RoleClient>,
+    ) -> impl Future<Output = Result<CreateMessageResult, McpError>> + Send + '_ {
+        async move {
+            let messages = params
+                .messages
+                .into_iter()
+                .map(|message| match message.content.raw {
+                    RawContent::Text(content) => Ok(McpSamplingMessage {
+                        role: match message.role {
+                            rmcp::model::Role::User => Role::User,
+                            rmcp::model::Role::Assistant => Role::Assistant,
+                        },
+                        text: content.text,
+                    }),
+                    _ => Err(McpError::invalid_params(
+                        "Only text messages can be sampled",
+                        None,
+                    )),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let request = McpSamplingRequest {
+                // Filled in by the service, which knows the servers by name
+                server: String::new(),
+                messages,
+                system_prompt: params.system_prompt,
+                max_tokens: params.max_tokens as usize,
+                temperature: params.temperature,
+            };
+
+            let (reply, response) = oneshot::channel();
+            self.sampling
+                .send(McpSampling { request, reply })
+                .map_err(|_| McpError::internal_error("Sampling isn't available", None))?;
+            let response = response
+                .await
+                .map_err(|_| McpError::internal_error("Sampling request was dropped", None))?
+                .map_err(|error| McpError::internal_error(error.to_string(), None))?;
+
+            Ok(CreateMessageResult {
+                model: response.model,
+                stop_reason: Some("endTurn".to_string()),
+                message: SamplingMessage {
+                    role: rmcp::model::Role::Assistant,
+                    content: Content::text(response.text),
+                },
+            })
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct ForgeMcpClient {
     client: Arc<RwLock<Option<Arc<RmcpClient>>>>,
     config: McpServerConfig,
+    http: HttpConfig,
+    notifications: broadcast::Sender<McpNotification>,
+    sampling: mpsc::UnboundedSender<McpSampling>,
+    sampling_requests: Arc<Mutex<mpsc::UnboundedReceiver<McpSampling>>>,
 }
 
 impl ForgeMcpClient {
-    pub fn new(config: McpServerConfig) -> Self {
-        Self { client: Default::default(), config }
+    pub fn new(config: McpServerConfig, http: HttpConfig) -> Self {
+        let (notifications, _) = broadcast::channel(NOTIFICATION_CAPACITY);
+        let (sampling, sampling_requests) = mpsc::unbounded_channel();
+        Self {
+            client: Default::default(),
+            config,
+            http,
+            notifications,
+            sampling,
+            sampling_requests: Arc::new(Mutex::new(sampling_requests)),
+        }
     }
 
-    fn client_info(&self) -> ClientInfo {
-        ClientInfo {
+    fn handler(&self) -> ForgeMcpHandler {
+        let info = ClientInfo {
             protocol_version: Default::default(),
-            capabilities: Default::default(),
+            capabilities: ClientCapabilities {
+                sampling: Some(Default::default()),
+                ..Default::default()
+            },
             client_info: Implementation { name: "Forge".to_string(), version: VERSION.to_string() },
+        };
+        ForgeMcpHandler {
+            info,
+            notifications: self.notifications.clone(),
+            sampling: self.sampling.clone(),
         }
     }
 
@@ -75,19 +207,45 @@ impl ForgeMcpClient {
                 cmd.stdin(std::process::Stdio::inherit())
                     .stdout(std::process::Stdio::piped())
                     .stderr(std::process::Stdio::piped());
-                self.client_info()
+                self.handler()
                     .serve(TokioChildProcess::new(cmd.args(&stdio.args))?)
                     .await?
             }
-            McpServerConfig::Sse(sse) => {
-                let transport = rmcp::transport::SseTransport::start(sse.url.clone()).await?;
-                self.client_info().serve(transport).await?
+            // WebSocket URLs can't be reached over HTTP, whatever the transport
+            McpServerConfig::Sse(http) if is_websocket(&http.url) => {
+                let transport = WebSocketTransport::start(&http.url).await?;
+                self.handler().serve(transport).await?
             }
+            McpServerConfig::Sse(http) => match http.transport {
+                McpHttpTransport::StreamableHttp => self.connect_streamable_http(&http.url).await?,
+                McpHttpTransport::Sse => self.connect_sse(&http.url).await?,
+                McpHttpTransport::Auto => match self.connect_streamable_http(&http.url).await {
+                    Ok(client) => client,
+                    Err(error) => {
+                        tracing::debug!(
+                            url = %http.url,
+                            error = %error,
+                            "Streamable HTTP not supported, falling back to SSE"
+                        );
+                        self.connect_sse(&http.url).await?
+                    }
+                },
+            },
         };
 
         Ok(Arc::new(client))
     }
 
+    async fn connect_streamable_http(&self, url: &str) -> anyhow::Result<RmcpClient> {
+        let transport = StreamableHttpTransport::start(url, &self.http)?;
+        Ok(self.handler().serve(transport).await?)
+    }
+
+    async fn connect_sse(&self, url: &str) -> anyhow::Result<RmcpClient> {
+        let transport = rmcp::transport::SseTransport::start(url.to_string()).await?;
+        Ok(self.handler().serve(transport).await?)
+    }
+
     async fn list(&self) -> anyhow::Result<Vec<ToolDefinition>> {
         let client = self.connect().await?;
         let tools = client.list_tools(None).await?;
@@ -146,6 +304,139 @@ impl ForgeMcpClient {
             .is_error(result.is_error.unwrap_or_default()))
     }
 
+    async fn list_prompts(&self) -> anyhow::Result<Vec<McpPrompt>> {
+        let client = self.connect().await?;
+        // Servers without the capability don't answer the request
+        if client.peer_info().capabilities.prompts.is_none() {
+            return Ok(vec![]);
+        }
+        let prompts = client.list_prompts(None).await?;
+        Ok(prompts
+            .prompts
+            .into_iter()
+            .map(|prompt| McpPrompt {
+                name: prompt.name,
+                description: prompt.description,
+                arguments: prompt
+                    .arguments
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|argument| McpPromptArgument {
+                        name: argument.name,
+                        description: argument.description,
+                        required: argument.required.unwrap_or_default(),
+                    })
+                    .collect(),
+            })
+            .collect())
+    }
+
+    async fn get_prompt(
+        &self,
+        name: &str,
+        arguments: &BTreeMap<String, String>,
+    ) -> anyhow::Result<String> {
+        let client = self.connect().await?;
+        let result = client
+            .get_prompt(GetPromptRequestParam {
+                name: name.to_string(),
+                arguments: Some(
+                    arguments
+                        .iter()
+                        .map(|(name, value)| (name.clone(), Value::from(value.as_str())))
+                        .collect(),
+                ),
+            })
+            .await?;
+
+        // The messages become a single message of the user, whatever their role
+        let texts = result
+            .messages
+            .into_iter()
+            .map(|message| match message.content {
+                PromptMessageContent::Text { text } => Ok(text),
+                PromptMessageContent::Image { .. } => {
+                    Err(Error::UnsupportedMcpResponse("Image").into())
+                }
+                PromptMessageContent::Resource { .. } => {
+                    Err(Error::UnsupportedMcpResponse("Resource").into())
+                }
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(texts.join("\n\n"))
+    }
+
+    async fn list_resources(&self) -> anyhow::Result<Vec<McpResource>> {
+        let client = self.connect().await?;
+        // Servers without the capability don't answer the request
+        if client.peer_info().capabilities.resources.is_none() {
+            return Ok(vec![]);
+        }
+        let resources = client.list_resources(None).await?;
+        Ok(resources
+            .resources
+            .into_iter()
+            .map(|resource| McpResource {
+                server: String::new(),
+                uri: resource.raw.uri,
+                name: resource.raw.name,
+                description: resource.raw.description,
+                mime_type: resource.raw.mime_type,
+            })
+            .collect())
+    }
+
+    async fn read_resource(&self, uri: &str) -> anyhow::Result<Vec<AttachmentContent>> {
+        let client = self.connect().await?;
+        let result = client
+            .read_resource(ReadResourceRequestParam { uri: uri.to_string() })
+            .await?;
+
+        result
+            .contents
+            .into_iter()
+            .map(|contents| match contents {
+                ResourceContents::TextResourceContents { text, .. } => {
+                    Ok(AttachmentContent::FileContent(text))
+                }
+                ResourceContents::BlobResourceContents {
+                    blob, mime_type: Some(mime_type), ..
+                } if mime_type.starts_with("image/") => Ok(AttachmentContent::Image(
+                    Image::new_base64(blob, mime_type.as_str()),
+                )),
+                ResourceContents::BlobResourceContents { .. } => {
+                    Err(Error::UnsupportedMcpResponse("Blob").into())
+                }
+            })
+            .collect()
+    }
+
+    async fn subscribe_resource(&self, uri: &str) -> anyhow::Result<()> {
+        let client = self.connect().await?;
+        let supported = client
+            .peer_info()
+            .capabilities
+            .resources
+            .as_ref()
+            .and_then(|resources| resources.subscribe)
+            .unwrap_or_default();
+        if !supported {
+            return Err(Error::UnsupportedMcpSubscription.into());
+        }
+        client
+            .subscribe(SubscribeRequestParam { uri: uri.to_string() })
+            .await?;
+        Ok(())
+    }
+
+    async fn unsubscribe_resource(&self, uri: &str) -> anyhow::Result<()> {
+        let client = self.connect().await?;
+        client
+            .unsubscribe(UnsubscribeRequestParam { uri: uri.to_string() })
+            .await?;
+        Ok(())
+    }
+
     async fn attempt_with_retry<T, F>(&self, call: impl Fn() -> F) -> anyhow::Result<T>
     where
         F: Future<Output = anyhow::Result<T>>,
@@ -181,4 +472,43 @@ impl McpClientInfra for ForgeMcpClient {
         self.attempt_with_retry(|| self.call(tool_name, &input))
             .await
     }
+
+    async fn list_prompts(&self) -> anyhow::Result<Vec<McpPrompt>> {
+        self.attempt_with_retry(|| self.list_prompts()).await
+    }
+
+    async fn get_prompt(
+        &self,
+        name: &str,
+        arguments: BTreeMap<String, String>,
+    ) -> anyhow::Result<String> {
+        self.attempt_with_retry(|| self.get_prompt(name, &arguments))
+            .await
+    }
+
+    async fn list_resources(&self) -> anyhow::Result<Vec<McpResource>> {
+        self.attempt_with_retry(|| self.list_resources()).await
+    }
+
+    async fn read_resource(&self, uri: &str) -> anyhow::Result<Vec<AttachmentContent>> {
+        self.attempt_with_retry(|| self.read_resource(uri)).await
+    }
+
+    async fn subscribe_resource(&self, uri: &str) -> anyhow::Result<()> {
+        self.attempt_with_retry(|| self.subscribe_resource(uri))
+            .await
+    }
+
+    async fn unsubscribe_resource(&self, uri: &str) -> anyhow::Result<()> {
+        self.attempt_with_retry(|| self.unsubscribe_resource(uri))
+            .await
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<McpNotification> {
+        self.notifications.subscribe()
+    }
+
+    async fn next_sampling(&self) -> Option<McpSampling> {
+        self.sampling_requests.lock().await.recv().await
+    }
 }
@@ -0,0 +1,273 @@
+use std::time::Duration;
+
+use forge_domain::HttpConfig;
+use futures::{Sink, Stream};
+use reqwest::header::{HeaderValue, ACCEPT, CONTENT_TYPE};
+use reqwest::{Client, Response, StatusCode, Url};
+use rmcp::model::{ClientJsonRpcMessage, ServerJsonRpcMessage};
+use serde_json::Value;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::error::Error;
+
+/// Header of the session the server assigns when it is initialized, sent back
+/// with every following message
+const SESSION_ID: &str = "Mcp-Session-Id";
+
+/// Method of the notification ending the initialization, after which the
+/// server may send messages of its own
+const INITIALIZED: &str = "notifications/initialized";
+
+/// Delay before the stream of the messages the server sends on its own is
+/// opened again once it ended
+const LISTEN_RETRY: Duration = Duration::from_secs(1);
+
+/// Client side of the Streamable HTTP transport of MCP. Each message is posted
+/// to the single endpoint of the server, which answers with a JSON body or
+/// with a stream of events carrying the responses. The notifications and
+/// requests of the server are streamed from a GET of the endpoint, and the
+/// session is deleted once the transport is closed.
+pub struct StreamableHttpTransport {
+    client: Client,
+    url: Url,
+    session_id: Option<HeaderValue>,
+    sender: mpsc::UnboundedSender<ServerJsonRpcMessage>,
+    /// Task streaming the messages the server sends on its own
+    listener: Option<JoinHandle<()>>,
+}
+
+impl StreamableHttpTransport {
+    /// Sink of the messages sent to the server and stream of the messages it
+    /// answers, as served by rmcp
+    pub fn start(
+        url: &str,
+        http: &HttpConfig,
+    ) -> anyhow::Result<(
+        impl Sink<ClientJsonRpcMessage, Error = Error> + Send + 'static,
+        impl Stream<Item = ServerJsonRpcMessage> + Send + 'static,
+    )> {
+        let client = Client::builder()
+            .connect_timeout(Duration::from_secs(http.connect_timeout))
+            .read_timeout(Duration::from_secs(http.read_timeout))
+            .pool_idle_timeout(Duration::from_secs(http.pool_idle_timeout))
+            .pool_max_idle_per_host(http.pool_max_idle_per_host)
+            .build()?;
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let transport = Self {
+            client,
+            url: Url::parse(url)?,
+            session_id: None,
+            sender,
+            listener: None,
+        };
+
+        let sink = futures::sink::unfold(transport, |mut transport, message| async move {
+            transport.post(message).await?;
+            Ok::<_, Error>(transport)
+        });
+        let stream = futures::stream::unfold(receiver, |mut receiver| async move {
+            receiver.recv().await.map(|message| (message, receiver))
+        });
+        Ok((Box::pin(sink), Box::pin(stream)))
+    }
+
+    async fn post(&mut self, message: ClientJsonRpcMessage) -> Result<(), Error> {
+        let is_initialized = serde_json::to_value(&message)?
+            .get("method")
+            .and_then(Value::as_str)
+            == Some(INITIALIZED);
+        let mut request = self
+            .client
+            .post(self.url.clone())
+            .header(ACCEPT, "application/json, text/event-stream")
+            .json(&message);
+        if let Some(session_id) = &self.session_id {
+            request = request.header(SESSION_ID, session_id.clone());
+        }
+
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(Error::McpHttpStatus(response.status()));
+        }
+        if let Some(session_id) = response.headers().get(SESSION_ID) {
+            self.session_id = Some(session_id.clone());
+        }
+        // Notifications and responses sent to the server are only acknowledged
+        if response.status() == StatusCode::ACCEPTED {
+            if is_initialized && self.listener.is_none() {
+                self.listener = Some(tokio::spawn(listen(
+                    self.client.clone(),
+                    self.url.clone(),
+                    self.session_id.clone(),
+                    self.sender.clone(),
+                )));
+            }
+            return Ok(());
+        }
+
+        let is_stream = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("text/event-stream"));
+        if is_stream {
+            // The events can take as long as the tool call they answer, so they
+            // are read without holding back the next messages
+            tokio::spawn(forward_events(response, self.sender.clone()));
+        } else {
+            let body = response.bytes().await?;
+            for message in parse_messages(&body)? {
+                let _ = self.sender.send(message);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for StreamableHttpTransport {
+    fn drop(&mut self) {
+        if let Some(listener) = self.listener.take() {
+            listener.abort();
+        }
+
+        // The server frees the session rather than keeping it until it expires
+        let Some(session_id) = self.session_id.take() else {
+            return;
+        };
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let request = self
+            .client
+            .delete(self.url.clone())
+            .header(SESSION_ID, session_id);
+        runtime.spawn(async move {
+            if let Err(error) = request.send().await {
+                tracing::debug!(error = %error, "Failed to delete the MCP session");
+            }
+        });
+    }
+}
+
+/// Streams the notifications and requests the server sends on its own, opening
+/// the stream again when it ends until the transport is closed
+async fn listen(
+    client: Client,
+    url: Url,
+    session_id: Option<HeaderValue>,
+    sender: mpsc::UnboundedSender<ServerJsonRpcMessage>,
+) {
+    while !sender.is_closed() {
+        let mut request = client.get(url.clone()).header(ACCEPT, "text/event-stream");
+        if let Some(session_id) = &session_id {
+            request = request.header(SESSION_ID, session_id.clone());
+        }
+        match request.send().await {
+            Ok(response) if response.status().is_success() => {
+                forward_events(response, sender.clone()).await;
+            }
+            // The server doesn't send messages on its own
+            Ok(response) if response.status() == StatusCode::METHOD_NOT_ALLOWED => return,
+            Ok(response) => {
+                tracing::warn!(status = %response.status(), "MCP server refused the stream");
+                return;
+            }
+            Err(error) => {
+                tracing::warn!(error = %error, "MCP server stream failed");
+                return;
+            }
+        }
+        tokio::time::sleep(LISTEN_RETRY).await;
+    }
+}
+
+/// Forwards the messages of the events streamed in the response until it ends
+async fn forward_events(
+    mut response: Response,
+    sender: mpsc::UnboundedSender<ServerJsonRpcMessage>,
+) {
+    // Bytes received after the last complete character
+    let mut pending = Vec::new();
+    let mut buffer = String::new();
+    loop {
+        let chunk = match response.chunk().await {
+            Ok(Some(chunk)) => chunk,
+            Ok(None) => break,
+            Err(error) => {
+                tracing::warn!(error = %error, "MCP event stream interrupted");
+                break;
+            }
+        };
+        pending.extend_from_slice(&chunk);
+        let valid = match std::str::from_utf8(&pending) {
+            Ok(text) => text.len(),
+            Err(error) => error.valid_up_to(),
+        };
+        buffer.push_str(&String::from_utf8_lossy(&pending[..valid]));
+        pending.drain(..valid);
+        for data in take_events(&mut buffer) {
+            match parse_messages(data.as_bytes()) {
+                Ok(messages) => messages.into_iter().for_each(|message| {
+                    let _ = sender.send(message);
+                }),
+                Err(error) => tracing::warn!(error = %error, "Invalid MCP event"),
+            }
+        }
+    }
+}
+
+/// Takes the data of the complete events out of the buffer, leaving the event
+/// still being received
+fn take_events(buffer: &mut String) -> Vec<String> {
+    if buffer.contains('\r') {
+        *buffer = buffer.replace("\r\n", "\n");
+    }
+    let mut events = Vec::new();
+    while let Some(end) = buffer.find("\n\n") {
+        let event = buffer.drain(..end + 2).collect::<String>();
+        let data = event
+            .lines()
+            .filter_map(|line| line.strip_prefix("data:"))
+            .map(|data| data.strip_prefix(' ').unwrap_or(data))
+            .collect::<Vec<_>>();
+        if !data.is_empty() {
+            events.push(data.join("\n"));
+        }
+    }
+    events
+}
+
+/// Messages of a body, a single message or a batch of them
+fn parse_messages(body: &[u8]) -> Result<Vec<ServerJsonRpcMessage>, Error> {
+    let messages = match serde_json::from_slice::<Value>(body)? {
+        Value::Array(batch) => batch,
+        message => vec![message],
+    };
+    messages
+        .into_iter()
+        .map(|message| serde_json::from_value(message).map_err(Error::from))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_take_events() {
+        let mut fixture = "event: message\r\ndata: {\"a\":1}\r\n\r\n\
+                           : ping\n\n\
+                           data: {\"b\":\ndata: 2}\n\n\
+                           data: {\"c\""
+            .to_string();
+
+        let actual = take_events(&mut fixture);
+
+        let expected = vec!["{\"a\":1}".to_string(), "{\"b\":\n2}".to_string()];
+        assert_eq!(actual, expected);
+        assert_eq!(fixture, "data: {\"c\"");
+    }
+}
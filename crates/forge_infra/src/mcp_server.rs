@@ -1,16 +1,24 @@
-use forge_domain::McpServerConfig;
+use forge_domain::{HttpConfig, McpServerConfig};
 use forge_services::McpServerInfra;
 
 use crate::mcp_client::ForgeMcpClient;
 
 #[derive(Clone)]
-pub struct ForgeMcpServer;
+pub struct ForgeMcpServer {
+    http: HttpConfig,
+}
+
+impl ForgeMcpServer {
+    pub fn new(http: HttpConfig) -> Self {
+        Self { http }
+    }
+}
 
 #[async_trait::async_trait]
 impl McpServerInfra for ForgeMcpServer {
     type Client = ForgeMcpClient;
 
     async fn connect(&self, config: McpServerConfig) -> anyhow::Result<Self::Client> {
-        Ok(ForgeMcpClient::new(config))
+        Ok(ForgeMcpClient::new(config, self.http.clone()))
     }
 }
@@ -0,0 +1,265 @@
+use std::time::Duration;
+
+use backon::{ExponentialBuilder, Retryable};
+use futures::{Sink, SinkExt, Stream, StreamExt};
+use rmcp::model::{ClientJsonRpcMessage, ServerJsonRpcMessage};
+use serde_json::Value;
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+use crate::error::Error;
+
+/// Interval at which the connection is checked with a ping, the server being
+/// considered gone when it hasn't answered the previous one
+const PING_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Attempts to reopen a connection that dropped before giving up on the server
+const RECONNECT_ATTEMPTS: usize = 5;
+
+/// Methods of the messages initializing the session, sent again on a new
+/// connection
+const HANDSHAKE_METHODS: [&str; 2] = ["initialize", "notifications/initialized"];
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Client side of MCP over a WebSocket, each message being a text frame. The
+/// connection is kept alive with pings and reopened when it drops, the
+/// initialization of the session being replayed on the new connection.
+pub struct WebSocketTransport {
+    url: String,
+    /// Messages initializing the session
+    handshake: Vec<Value>,
+    /// Messages that couldn't be sent before the connection dropped
+    unsent: Vec<Value>,
+    /// Id of the replayed initialization request, whose response was already
+    /// received on the first connection
+    replayed: Option<Value>,
+    incoming: mpsc::UnboundedSender<ServerJsonRpcMessage>,
+}
+
+impl WebSocketTransport {
+    /// Sink of the messages sent to the server and stream of the messages it
+    /// sends, as served by rmcp
+    pub async fn start(
+        url: &str,
+    ) -> anyhow::Result<(
+        impl Sink<ClientJsonRpcMessage, Error = Error> + Send + 'static,
+        impl Stream<Item = ServerJsonRpcMessage> + Send + 'static,
+    )> {
+        let socket = connect(url).await?;
+        let (outgoing, outgoing_receiver) = mpsc::unbounded_channel();
+        let (incoming, incoming_receiver) = mpsc::unbounded_channel();
+        let transport = Self {
+            url: url.to_string(),
+            handshake: Vec::new(),
+            unsent: Vec::new(),
+            replayed: None,
+            incoming,
+        };
+        tokio::spawn(transport.run(socket, outgoing_receiver));
+
+        let sink = futures::sink::unfold(
+            outgoing,
+            |outgoing: mpsc::UnboundedSender<ClientJsonRpcMessage>, message| async move {
+                outgoing
+                    .send(message)
+                    .map_err(|_| Error::McpWebSocketClosed)?;
+                Ok::<_, Error>(outgoing)
+            },
+        );
+        let stream = futures::stream::unfold(incoming_receiver, |mut receiver| async move {
+            receiver.recv().await.map(|message| (message, receiver))
+        });
+        Ok((Box::pin(sink), Box::pin(stream)))
+    }
+
+    /// Exchanges the messages until the client closes the transport or the
+    /// server can't be reached anymore, which ends the stream of its messages
+    async fn run(
+        mut self,
+        mut socket: Socket,
+        mut outgoing: mpsc::UnboundedReceiver<ClientJsonRpcMessage>,
+    ) {
+        let mut ping = tokio::time::interval(PING_INTERVAL);
+        // The first tick completes immediately
+        ping.tick().await;
+        let mut awaiting_pong = false;
+
+        loop {
+            let connected = tokio::select! {
+                message = outgoing.recv() => match message {
+                    Some(message) => self.send(&mut socket, message).await,
+                    None => {
+                        let _ = socket.close(None).await;
+                        return;
+                    }
+                },
+                frame = socket.next() => match frame {
+                    Some(Ok(Message::Pong(_))) => {
+                        awaiting_pong = false;
+                        true
+                    }
+                    Some(Ok(frame)) => self.receive(frame),
+                    Some(Err(error)) => {
+                        tracing::warn!(url = %self.url, error = %error, "MCP WebSocket failed");
+                        false
+                    }
+                    None => false,
+                },
+                _ = ping.tick() => {
+                    if awaiting_pong {
+                        tracing::warn!(url = %self.url, "MCP server didn't answer the ping");
+                        false
+                    } else {
+                        awaiting_pong = true;
+                        socket.send(Message::Ping(Default::default())).await.is_ok()
+                    }
+                }
+            };
+            if connected {
+                continue;
+            }
+
+            match self.reconnect().await {
+                Some(reconnected) => {
+                    socket = reconnected;
+                    awaiting_pong = false;
+                    ping.reset();
+                }
+                None => return,
+            }
+        }
+    }
+
+    /// Sends the message, kept to be sent on the next connection when this
+    /// one dropped. Returns whether the connection is still open.
+    async fn send(&mut self, socket: &mut Socket, message: ClientJsonRpcMessage) -> bool {
+        let message = match serde_json::to_value(&message) {
+            Ok(message) => message,
+            Err(error) => {
+                tracing::warn!(error = %error, "Invalid MCP message");
+                return true;
+            }
+        };
+        let is_handshake = message
+            .get("method")
+            .and_then(Value::as_str)
+            .is_some_and(|method| HANDSHAKE_METHODS.contains(&method));
+        if is_handshake {
+            self.handshake.push(message.clone());
+        }
+
+        if socket
+            .send(Message::text(message.to_string()))
+            .await
+            .is_err()
+        {
+            // The handshake is replayed anyway
+            if !is_handshake {
+                self.unsent.push(message);
+            }
+            return false;
+        }
+        true
+    }
+
+    /// Forwards the messages of the frame to the client. Returns whether the
+    /// connection is still open.
+    fn receive(&mut self, frame: Message) -> bool {
+        let text = match frame {
+            Message::Text(text) => text.to_string(),
+            Message::Binary(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+            Message::Close(_) => return false,
+            // Pings are answered by the socket
+            _ => return true,
+        };
+
+        let messages = match serde_json::from_str::<Value>(&text) {
+            Ok(Value::Array(batch)) => batch,
+            Ok(message) => vec![message],
+            Err(error) => {
+                tracing::warn!(error = %error, "Invalid MCP message");
+                return true;
+            }
+        };
+        for message in messages {
+            if self.replayed.is_some() && message.get("id") == self.replayed.as_ref() {
+                self.replayed = None;
+                continue;
+            }
+            match serde_json::from_value(message) {
+                Ok(message) => {
+                    let _ = self.incoming.send(message);
+                }
+                Err(error) => tracing::warn!(error = %error, "Invalid MCP message"),
+            }
+        }
+        true
+    }
+
+    /// Opens a new connection to the server and initializes the session
+    /// again, `None` when the server can't be reached
+    async fn reconnect(&mut self) -> Option<Socket> {
+        tracing::warn!(url = %self.url, "MCP WebSocket disconnected, reconnecting");
+        let url = self.url.clone();
+        let mut socket = (|| connect(&url))
+            .retry(
+                ExponentialBuilder::default()
+                    .with_max_times(RECONNECT_ATTEMPTS)
+                    .with_jitter(),
+            )
+            .await
+            .inspect_err(|error| {
+                tracing::warn!(url = %self.url, error = %error, "MCP server unreachable");
+            })
+            .ok()?;
+
+        for message in &self.handshake {
+            if message.get("method").and_then(Value::as_str) == Some(HANDSHAKE_METHODS[0]) {
+                self.replayed = message.get("id").cloned();
+            }
+        }
+        let messages = self
+            .handshake
+            .iter()
+            .cloned()
+            .chain(std::mem::take(&mut self.unsent));
+        for message in messages {
+            socket.send(Message::text(message.to_string())).await.ok()?;
+        }
+        Some(socket)
+    }
+}
+
+/// Whether the server is reached over a WebSocket rather than HTTP
+pub fn is_websocket(url: &str) -> bool {
+    url.starts_with("ws://") || url.starts_with("wss://")
+}
+
+async fn connect(url: &str) -> Result<Socket, Error> {
+    let (socket, _) = tokio_tungstenite::connect_async(url)
+        .await
+        .map_err(|error| Error::McpWebSocket(Box::new(error)))?;
+    Ok(socket)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_is_websocket() {
+        let actual = [
+            is_websocket("wss://mcp.example.com"),
+            is_websocket("ws://localhost:8080/mcp"),
+            is_websocket("https://mcp.example.com/mcp"),
+        ];
+
+        let expected = [true, true, false];
+        assert_eq!(actual, expected);
+    }
+}
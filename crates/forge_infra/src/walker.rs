@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use anyhow::Result;
 use forge_app::{WalkedFile, Walker};
 
@@ -50,6 +52,10 @@ impl ForgeWalkerService {
 
         Ok(walked_files)
     }
+
+    pub async fn count(&self, cwd: PathBuf, limit: usize) -> Result<usize> {
+        forge_walker::Walker::max_all().cwd(cwd).count(limit).await
+    }
 }
 
 #[cfg(test)]
@@ -1,16 +1,20 @@
 use std::io;
 
 use colored::Colorize;
+use forge_display::theme;
 use forge_tracker::VERSION;
 
 const BANNER: &str = include_str!("banner");
 
-pub fn display() -> io::Result<()> {
+/// Displays the banner, including a notice when `update` holds a newer
+/// version than the one running.
+pub fn display(update: Option<&str>) -> io::Result<()> {
     let mut banner = BANNER.to_string();
 
     // Define the labels as tuples of (key, value)
 
-    let labels = [
+    let update_notice = update.map(|version| format!("{version} available, run `forge update`"));
+    let mut labels = vec![
         ("Version:", VERSION),
         ("New conversation:", "/new"),
         ("Get started:", "/info, /help"),
@@ -18,6 +22,9 @@ pub fn display() -> io::Result<()> {
         ("Update:", "/update"),
         ("Quit:", "/exit or <CTRL+D>"),
     ];
+    if let Some(notice) = update_notice.as_deref() {
+        labels.insert(1, ("New version:", notice));
+    }
 
     // Calculate the width of the longest label key for alignment
     let max_width = labels.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
@@ -28,7 +35,7 @@ pub fn display() -> io::Result<()> {
             format!(
                 "\n{}{}",
                 format!("{key:>max_width$} ").dimmed(),
-                value.cyan()
+                value.color(theme().highlight)
             )
             .as_str(),
         );
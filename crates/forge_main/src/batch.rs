@@ -0,0 +1,110 @@
+use std::path::PathBuf;
+use std::time::Instant;
+
+use anyhow::Result;
+use forge_api::{ChatResponse, ForgeClient, InterruptionReason};
+use forge_domain::{BatchStatus, BatchTask, BatchTaskResult, Event, Workflow};
+use tokio_stream::StreamExt;
+
+use crate::ui::EVENT_USER_TASK_INIT;
+
+/// Runs a task autonomously in `worktree`, or in the current directory, and
+/// collects its outcome
+pub async fn run_task(
+    restricted: bool,
+    workflow: Workflow,
+    task: BatchTask,
+    name: String,
+    worktree: Option<PathBuf>,
+) -> BatchTaskResult {
+    let started_at = Instant::now();
+    let mut result = BatchTaskResult {
+        name,
+        status: BatchStatus::Succeeded,
+        answer: None,
+        reason: None,
+        changed_files: Vec::new(),
+        worktree: worktree.clone(),
+        branch: None,
+        duration_ms: 0,
+    };
+
+    if let Err(error) = chat(restricted, workflow, task, worktree, &mut result).await {
+        result.status = BatchStatus::Failed;
+        result.reason = Some(format!("{error:#}"));
+    }
+    result.duration_ms = started_at.elapsed().as_millis();
+    result
+}
+
+async fn chat(
+    restricted: bool,
+    workflow: Workflow,
+    task: BatchTask,
+    worktree: Option<PathBuf>,
+    result: &mut BatchTaskResult,
+) -> Result<()> {
+    let mut builder = ForgeClient::builder().restricted(restricted);
+    if let Some(worktree) = worktree {
+        builder = builder.workspace(worktree);
+    }
+    let client = builder.build();
+
+    // Nobody is there to approve the tools of a batch
+    let autonomy = task
+        .budget
+        .or_else(|| workflow.autonomy.clone())
+        .unwrap_or_default();
+    let mut conversation = client.create_conversation_with(workflow).await?;
+    conversation.autonomy = Some(autonomy);
+    let conversation_id = conversation.id;
+    client.upsert_conversation(conversation).await?;
+
+    let agent = task.agent.unwrap_or_default();
+    let event = Event::new(format!("{agent}/{EVENT_USER_TASK_INIT}"), Some(task.prompt));
+    let mut stream = client.chat_stream(&conversation_id, event).await?;
+    while let Some(message) = stream.next().await {
+        match message? {
+            ChatResponse::Text { text, is_complete: true, .. }
+            | ChatResponse::Summary { content: text }
+                if !text.trim().is_empty() =>
+            {
+                result.answer = Some(text);
+            }
+            ChatResponse::Interrupt { reason } => {
+                result.status = BatchStatus::Interrupted;
+                result.reason = Some(describe(reason));
+            }
+            ChatResponse::Blocked { reason } => {
+                result.status = BatchStatus::Interrupted;
+                result.reason = Some(format!("Response blocked: {reason}"));
+            }
+            ChatResponse::ChangeSummary(changes) => {
+                result
+                    .changed_files
+                    .extend(changes.files.into_iter().map(|file| file.path));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn describe(reason: InterruptionReason) -> String {
+    match reason {
+        InterruptionReason::MaxToolFailurePerTurnLimitReached { limit } => {
+            format!("Maximum tool failure limit ({limit}) reached")
+        }
+        InterruptionReason::MaxRequestPerTurnLimitReached { limit } => {
+            format!("Maximum request ({limit}) per turn achieved")
+        }
+        InterruptionReason::AutonomyBudgetExhausted { elapsed, requests } => format!(
+            "Budget spent after {requests} request(s) in {}s",
+            elapsed.as_secs()
+        ),
+        InterruptionReason::DecisionRequired { question } => {
+            format!("Decision required: {question}")
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use forge_display::hyperlink;
+use forge_domain::{CheckedCitation, Citation, CitationStatus};
+
+/// Checks the files and lines answers cite against the workspace, so that
+/// valid citations can be opened from the terminal and hallucinated ones
+/// reported
+pub struct CitationChecker {
+    cwd: PathBuf,
+    /// Number of lines of the cited files, `None` for the missing ones
+    lines: HashMap<PathBuf, Option<usize>>,
+}
+
+impl CitationChecker {
+    pub fn new(cwd: impl Into<PathBuf>) -> Self {
+        Self { cwd: cwd.into(), lines: HashMap::new() }
+    }
+
+    pub async fn check(&mut self, citation: &Citation) -> CheckedCitation {
+        let path = citation.resolve(&self.cwd);
+        let lines = match self.lines.get(&path) {
+            Some(lines) => *lines,
+            None => {
+                let lines = line_count(&path).await;
+                self.lines.insert(path, lines);
+                lines
+            }
+        };
+        CheckedCitation { citation: citation.clone(), status: citation.check(lines) }
+    }
+
+    /// Makes the valid citations of the answer clickable in its rendering,
+    /// returning it with the citations that don't designate existing lines
+    pub async fn link(
+        &mut self,
+        answer: &str,
+        rendered: String,
+        hyperlinks: bool,
+    ) -> (String, Vec<CheckedCitation>) {
+        let mut output = String::with_capacity(rendered.len());
+        let mut invalid = Vec::<CheckedCitation>::new();
+        // The citations appear in the rendering in the order of the answer, each
+        // one is looked up after the previous one so that only its own span is
+        // linked
+        let mut cursor = 0;
+        for (span, citation) in Citation::extract(answer) {
+            let checked = self.check(&citation).await;
+            let valid = checked.status == CitationStatus::Valid;
+            if !valid && !invalid.contains(&checked) {
+                invalid.push(checked);
+            }
+
+            let text = &answer[span];
+            let Some(start) = find_citation(&rendered, cursor, text) else {
+                continue;
+            };
+            let end = start + text.len();
+            output.push_str(&rendered[cursor..start]);
+            if valid && hyperlinks {
+                output.push_str(&hyperlink(&citation.url(&self.cwd), text));
+            } else {
+                output.push_str(&rendered[start..end]);
+            }
+            cursor = end;
+        }
+        output.push_str(&rendered[cursor..]);
+        (output, invalid)
+    }
+}
+
+/// Number of lines of the file, `None` when it can't be read
+async fn line_count(path: &Path) -> Option<usize> {
+    let content = tokio::fs::read(path).await.ok()?;
+    let breaks = content.iter().filter(|byte| **byte == b'\n').count();
+    Some(breaks + usize::from(content.last().is_some_and(|byte| *byte != b'\n')))
+}
+
+/// Position of the first occurrence of the citation in the rendering after
+/// `from`, skipping the ones that are part of a longer path or URL
+fn find_citation(rendered: &str, from: usize, text: &str) -> Option<usize> {
+    rendered[from..]
+        .match_indices(text)
+        .map(|(start, _)| from + start)
+        .find(|start| {
+            let preceding = visible_before(&rendered[..*start]);
+            let following = rendered[start + text.len()..].chars().next();
+            !preceding.is_some_and(|c| c.is_alphanumeric() || "/.:_-".contains(c))
+                && !following.is_some_and(|c| c.is_ascii_digit())
+        })
+}
+
+/// Last character of the text as displayed, ignoring the styling the
+/// renderer put before a citation
+fn visible_before(text: &str) -> Option<char> {
+    let mut text = text;
+    while let Some(start) = text.rfind("\x1b[") {
+        let Some(parameters) = text[start + 2..].strip_suffix('m') else {
+            break;
+        };
+        if !parameters.chars().all(|c| c.is_ascii_digit() || c == ';') {
+            break;
+        }
+        text = &text[..start];
+    }
+    text.chars().next_back()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn fixture() -> (tempfile::TempDir, CitationChecker) {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/lib.rs"), "mod a;\nmod b;\nmod c;").unwrap();
+        let checker = CitationChecker::new(dir.path());
+        (dir, checker)
+    }
+
+    #[tokio::test]
+    async fn test_link_valid_citations() {
+        let (dir, mut checker) = fixture();
+        let answer = "See src/lib.rs:2 and src/lib.rs:2-3.";
+
+        let (actual, invalid) = checker.link(answer, answer.to_string(), true).await;
+
+        let url = |lines: &str| format!("file://{}/src/lib.rs#{lines}", dir.path().display());
+        let expected = format!(
+            "See {} and {}.",
+            hyperlink(&url("L2"), "src/lib.rs:2"),
+            hyperlink(&url("L2-L3"), "src/lib.rs:2-3")
+        );
+        assert_eq!(actual, expected);
+        assert_eq!(invalid, vec![]);
+    }
+
+    #[tokio::test]
+    async fn test_link_reports_invalid_citations() {
+        let (_dir, mut checker) = fixture();
+        let answer = "See src/lib.rs:4 and src/main.rs:1, twice src/main.rs:1.";
+
+        let (actual, invalid) = checker.link(answer, answer.to_string(), true).await;
+
+        let expected = vec![
+            CitationStatus::MissingLines { lines: 3 },
+            CitationStatus::MissingFile,
+        ];
+        assert_eq!(actual, answer);
+        assert_eq!(
+            invalid
+                .into_iter()
+                .map(|checked| checked.status)
+                .collect::<Vec<_>>(),
+            expected
+        );
+    }
+
+    #[tokio::test]
+    async fn test_link_only_cited_spans() {
+        let (dir, mut checker) = fixture();
+        let answer = "See https://example.com/src/lib.rs:2 and `src/lib.rs:2`.";
+        let rendered = "See https://example.com/src/lib.rs:2 and \x1b[36msrc/lib.rs:2\x1b[0m.";
+
+        let (actual, _) = checker.link(answer, rendered.to_string(), true).await;
+
+        let url = format!("file://{}/src/lib.rs#L2", dir.path().display());
+        let expected = format!(
+            "See https://example.com/src/lib.rs:2 and \x1b[36m{}\x1b[0m.",
+            hyperlink(&url, "src/lib.rs:2")
+        );
+        assert_eq!(actual, expected);
+    }
+}
@@ -1,6 +1,10 @@
 use std::path::PathBuf;
 
 use clap::{Parser, Subcommand, ValueEnum};
+use clap_complete::engine::ArgValueCompleter;
+use clap_complete::Shell;
+
+use crate::completions::{complete_conversations, complete_mcp_servers, complete_models};
 
 #[derive(Parser)]
 #[command(version = env!("CARGO_PKG_VERSION"))]
@@ -50,11 +54,43 @@ pub struct Cli {
     #[arg(long, short = 'e')]
     pub event: Option<String>,
 
-    /// Path to a file containing the conversation to execute.
+    /// Path to a file containing the conversation to execute, or the ID of a
+    /// stored conversation to resume.
     /// This file should be in JSON format.
-    #[arg(long)]
+    #[arg(long, add = ArgValueCompleter::new(complete_conversations))]
     pub conversation: Option<PathBuf>,
 
+    /// Model to use for this session, overriding the workflow's model.
+    #[arg(long, short = 'm', add = ArgValueCompleter::new(complete_models))]
+    pub model: Option<String>,
+
+    /// Run the agents without interactive approval.
+    ///
+    /// The agents work on each prompt within the time and requests budget of
+    /// the workflow's `autonomy` setting, reporting their progress
+    /// periodically. They stop early when a decision of the user is needed,
+    /// e.g. to answer a question or to approve a removal.
+    #[arg(long, default_value_t = false)]
+    pub autonomous: bool,
+
+    /// Answer the prompts in ask mode, without tools nor agent loop.
+    ///
+    /// Each prompt gets a single streamed answer from the model, which is
+    /// faster and cheaper for quick questions. Switch back to the agent with
+    /// /ask or Alt+A.
+    #[arg(long, default_value_t = false)]
+    pub ask: bool,
+
+    /// Make the run reproducible with the given seed.
+    ///
+    /// The seed is sent to the providers supporting it, the temperature is 0
+    /// and the prompts don't include the time. The fingerprint of the
+    /// environment is recorded in the conversation, and a conversation
+    /// replayed with `--conversation` and a seed reports what differs from
+    /// the environment it was recorded in.
+    #[arg(long)]
+    pub seed: Option<u32>,
+
     /// Top-level subcommands
     #[command(subcommand)]
     pub subcommands: Option<TopLevelCommand>,
@@ -63,6 +99,267 @@ pub struct Cli {
 #[derive(Subcommand, Debug, Clone)]
 pub enum TopLevelCommand {
     Mcp(McpCommandGroup),
+
+    /// Set up forge for the current directory.
+    ///
+    /// Detects the configured providers and a local Ollama server, checks the
+    /// connection, then asks for the default model and the approval policy
+    /// and writes them to a commented forge.yaml.
+    Init,
+
+    /// Create the files of a project template of forge.yaml.
+    ///
+    /// Asks for the values of the variables of the template that aren't given
+    /// with --var. Lists the templates when none is given.
+    New(NewArgs),
+
+    /// Check the setup of forge and print how to fix the problems found.
+    ///
+    /// Checks the connection to the provider, the MCP servers, git, the shell,
+    /// the base path and the keys of forge.yaml unknown to this version.
+    Doctor,
+
+    /// Print a conversation shared with /share.
+    ///
+    /// Downloads the encrypted transcript from its link and decrypts it with
+    /// its key.
+    Shared(SharedArgs),
+
+    /// Print the shell completion script.
+    ///
+    /// For completion of dynamic values such as model and conversation IDs,
+    /// register the runtime hook instead, e.g. `source <(COMPLETE=bash forge)`.
+    Completions(CompletionsArgs),
+
+    /// Print the man page in roff format
+    Manpage,
+
+    /// Download and install the latest release of forge.
+    ///
+    /// Installations made by npm are updated through it. Otherwise the
+    /// downloaded binary is verified against the published checksum before it
+    /// replaces the current executable.
+    Update,
+
+    /// Report the usage of the providers per day, along with the daily quota
+    /// of the deployment when one is set.
+    Usage(UsageArgs),
+
+    /// Report statistics recorded across sessions
+    Stats(StatsCommandGroup),
+
+    /// Review the changes of a branch or of a GitHub pull request.
+    ///
+    /// Each changed file is reviewed by the reviewer agent, which only has
+    /// read-only tools. The comments are printed as a Markdown report, or as
+    /// the payload of a GitHub pull request review.
+    Review(ReviewArgs),
+
+    /// Draft the release notes of the commits between two refs and add them to
+    /// the changelog.
+    ///
+    /// The commits are grouped by their conventional commit type and scope,
+    /// then the model rewrites them as release notes for the users.
+    ReleaseNotes(ReleaseNotesArgs),
+
+    /// Manage the tasks started from issues
+    Task(TaskCommandGroup),
+
+    /// Run the prompts of a YAML file autonomously, one task after the other.
+    ///
+    /// Each task has a prompt, and optionally a name, an agent and a budget.
+    /// A summary of the results is printed, the exit status is non-zero when
+    /// a task wasn't completed.
+    Batch(BatchArgs),
+
+    /// Manage the git worktrees the agents run in when they work in parallel
+    Worktree(WorktreeCommandGroup),
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct SharedArgs {
+    /// Link printed by /share
+    pub link: String,
+
+    /// Key printed by /share
+    pub key: String,
+
+    /// File the transcript is written to as Markdown instead of being printed
+    #[arg(long, short = 'o')]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct NewArgs {
+    /// Name of the template
+    pub template: Option<String>,
+
+    /// Value of a variable of the template, e.g. --var name=billing
+    #[arg(long = "var")]
+    pub vars: Vec<String>,
+
+    /// Directory the files are created in, the current one by default
+    #[arg(long)]
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct WorktreeCommandGroup {
+    /// Subcommands under `worktree`
+    #[command(subcommand)]
+    pub command: WorktreeCommand,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum WorktreeCommand {
+    /// List the worktrees
+    List,
+
+    /// Merge the branch of a worktree into the current branch and remove the
+    /// worktree
+    Merge(WorktreeArgs),
+
+    /// Remove a worktree and its branch without merging it
+    Remove(WorktreeArgs),
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct WorktreeArgs {
+    /// Name of the worktree, as listed by `forge worktree list`
+    pub name: String,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct BatchArgs {
+    /// YAML file listing the tasks
+    pub file: PathBuf,
+
+    /// Number of tasks run at the same time. Above 1, each task runs in its
+    /// own git worktree on a branch named after it.
+    #[arg(long, short = 'j', default_value_t = 1)]
+    pub parallel: usize,
+
+    /// File the report is written to as JSON
+    #[arg(long, short = 'o')]
+    pub output: Option<PathBuf>,
+
+    /// Merge the branches of the completed tasks into the current branch
+    /// once the batch ran in parallel
+    #[arg(long)]
+    pub merge: bool,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct TaskCommandGroup {
+    /// Subcommands under `task`
+    #[command(subcommand)]
+    pub command: TaskCommand,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum TaskCommand {
+    /// Start working on a GitHub issue.
+    ///
+    /// Creates a branch named after the issue and starts a conversation with
+    /// the issue and the files it refers to.
+    Start(TaskStartArgs),
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct TaskStartArgs {
+    /// URL or number of the issue
+    pub issue: String,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct ReleaseNotesArgs {
+    /// Ref of the previous release, usually its tag
+    #[arg(long)]
+    pub from: String,
+
+    /// Ref of the release
+    #[arg(long, default_value = "HEAD")]
+    pub to: String,
+
+    /// Version of the release, used as the heading of the notes
+    #[arg(long, default_value = "Unreleased")]
+    pub version: String,
+
+    /// Changelog the notes are added to
+    #[arg(long, short = 'o', default_value = "CHANGELOG.md")]
+    pub output: PathBuf,
+
+    /// Print the notes instead of adding them to the changelog
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct ReviewArgs {
+    /// Number of the GitHub pull request to review, its diff is fetched with
+    /// the GitHub CLI. The changes of the current branch are reviewed
+    /// otherwise.
+    #[arg(long)]
+    pub pr: Option<u64>,
+
+    /// Branch or commit the current branch is compared against
+    #[arg(long, default_value = "main", conflicts_with = "pr")]
+    pub base: String,
+
+    /// Format of the review
+    #[arg(long, value_enum, default_value_t = ReviewFormat::Markdown)]
+    pub format: ReviewFormat,
+
+    /// File the review is written to instead of the standard output
+    #[arg(long, short = 'o')]
+    pub output: Option<PathBuf>,
+
+    /// Submit the review to the pull request with the GitHub CLI
+    #[arg(long, requires = "pr")]
+    pub submit: bool,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum ReviewFormat {
+    /// A report with the comments grouped by file
+    Markdown,
+    /// The payload of the GitHub API creating a pull request review
+    Github,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct UsageArgs {
+    /// Number of days to report, including today
+    #[arg(long, default_value_t = 7)]
+    pub days: u64,
+
+    /// Report the usage of all workspaces instead of the current one
+    #[arg(long)]
+    pub all: bool,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct StatsCommandGroup {
+    /// Subcommands under `stats`
+    #[command(subcommand)]
+    pub command: StatsCommand,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum StatsCommand {
+    /// Report the calls, failure rate, average duration and result size of
+    /// each tool.
+    ///
+    /// Tools failing often or returning large results are highlighted, so
+    /// that their configuration or the instructions of the agents can be
+    /// tuned.
+    Tools,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct CompletionsArgs {
+    /// Shell to generate the completion script for
+    pub shell: Shell,
 }
 
 /// Group of MCP-related commands
@@ -89,6 +386,9 @@ pub enum McpCommand {
 
     /// Add a server in JSON format
     AddJson(McpAddJsonArgs),
+
+    /// Import the servers configured in Claude Desktop, Cursor or VS Code
+    Import(McpImportArgs),
 }
 
 #[derive(Parser, Debug, Clone)]
@@ -97,7 +397,7 @@ pub struct McpAddArgs {
     #[arg(short = 's', long = "scope", default_value = "local")]
     pub scope: Scope,
 
-    /// Transport type (stdio or sse)
+    /// Transport type (stdio, http or sse)
     #[arg(short = 't', long = "transport", default_value = "stdio")]
     pub transport: Transport,
 
@@ -123,12 +423,14 @@ pub struct McpRemoveArgs {
     pub scope: Scope,
 
     /// Name of the server to remove
+    #[arg(add = ArgValueCompleter::new(complete_mcp_servers))]
     pub name: String,
 }
 
 #[derive(Parser, Debug, Clone)]
 pub struct McpGetArgs {
     /// Name of the server to get details for
+    #[arg(add = ArgValueCompleter::new(complete_mcp_servers))]
     pub name: String,
 }
 
@@ -145,6 +447,39 @@ pub struct McpAddJsonArgs {
     pub json: String,
 }
 
+#[derive(Parser, Debug, Clone)]
+pub struct McpImportArgs {
+    /// Configuration scope (local, user, or project)
+    #[arg(short = 's', long = "scope", default_value = "local")]
+    pub scope: Scope,
+
+    /// Tool to import the servers from
+    #[arg(long = "from")]
+    pub from: McpImportSource,
+
+    /// Configuration file to read instead of the default one of the tool
+    #[arg(long = "path")]
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum McpImportSource {
+    ClaudeDesktop,
+    Cursor,
+    #[value(name = "vscode")]
+    VsCode,
+}
+
+impl From<McpImportSource> for forge_domain::McpSource {
+    fn from(value: McpImportSource) -> Self {
+        match value {
+            McpImportSource::ClaudeDesktop => forge_domain::McpSource::ClaudeDesktop,
+            McpImportSource::Cursor => forge_domain::McpSource::Cursor,
+            McpImportSource::VsCode => forge_domain::McpSource::VsCode,
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, ValueEnum, Default)]
 pub enum Scope {
     #[default]
@@ -165,5 +500,6 @@ impl From<Scope> for forge_domain::Scope {
 #[clap(rename_all = "lower")]
 pub enum Transport {
     Stdio,
+    Http,
     Sse,
 }
@@ -0,0 +1,35 @@
+use std::path::Path;
+
+use anyhow::Result;
+use forge_domain::{CommitMessage, ToolsDiscriminants, COMMIT_MESSAGE_TAG};
+
+use crate::process::run;
+
+/// Task asking the agent for the message of the commit of the staged changes,
+/// in the response language of the conversation when one is set
+pub fn commit_task(language: Option<&str>) -> String {
+    let task = format!(
+        "Write the commit message of the staged changes. Read them with the {} tool and answer \
+         with the message only, within <{COMMIT_MESSAGE_TAG}> tags.",
+        ToolsDiscriminants::ForgeToolGitCommitMessage.name()
+    );
+    match language {
+        Some(language) => format!(
+            "{task} Write the description and the body in {language}, keeping the type and the \
+             scope in English."
+        ),
+        None => task,
+    }
+}
+
+/// Commits the staged changes of the repository of `cwd` with the message
+pub async fn commit(cwd: &Path, message: &CommitMessage) -> Result<String> {
+    let output = run(
+        cwd,
+        "git",
+        &["commit", "--file", "-"],
+        Some(&message.to_string()),
+    )
+    .await?;
+    Ok(output.lines().next().unwrap_or_default().to_string())
+}
@@ -0,0 +1,108 @@
+use console::measure_text_width;
+
+/// Space between the columns
+const GUTTER: &str = " │ ";
+
+/// Lays out answers in columns fitting the given width, each under the name
+/// of the model that wrote it
+pub fn side_by_side(columns: &[(String, String)], width: usize) -> String {
+    let count = columns.len().max(1);
+    let gutters = GUTTER.chars().count() * (count - 1);
+    let column_width = (width.saturating_sub(gutters) / count).max(10);
+
+    let wrapped = columns
+        .iter()
+        .map(|(title, answer)| {
+            let mut lines = wrap(title, column_width);
+            lines.push("─".repeat(column_width));
+            lines.extend(answer.lines().flat_map(|line| wrap(line, column_width)));
+            lines
+        })
+        .collect::<Vec<_>>();
+    let height = wrapped.iter().map(Vec::len).max().unwrap_or_default();
+
+    (0..height)
+        .map(|row| {
+            wrapped
+                .iter()
+                .map(|lines| {
+                    let line = lines.get(row).map(String::as_str).unwrap_or_default();
+                    let padding = column_width.saturating_sub(measure_text_width(line));
+                    format!("{line}{}", " ".repeat(padding))
+                })
+                .collect::<Vec<_>>()
+                .join(GUTTER)
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Breaks a line on whitespace so that no part is wider than `width`, splitting
+/// the words that are wider on their own
+fn wrap(line: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for word in line.split_whitespace() {
+        if !current.is_empty()
+            && measure_text_width(&current) + 1 + measure_text_width(word) > width
+        {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        for char in word.chars() {
+            if measure_text_width(&current) >= width {
+                lines.push(std::mem::take(&mut current));
+            }
+            current.push(char);
+        }
+    }
+    lines.push(current);
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_side_by_side() {
+        let fixture = [
+            (
+                "model-a".to_string(),
+                "Use a mutex around the cache".to_string(),
+            ),
+            ("model-b".to_string(), "Use a channel".to_string()),
+        ];
+
+        let actual = side_by_side(&fixture, 23);
+
+        let expected = [
+            "model-a    │ model-b",
+            "────────── │ ──────────",
+            "Use a      │ Use a",
+            "mutex      │ channel",
+            "around the │",
+            "cache      │",
+        ]
+        .join("\n");
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_wrap_splits_long_words() {
+        let actual = wrap("see src/compare.rs", 8);
+
+        let expected = vec![
+            "see".to_string(),
+            "src/comp".to_string(),
+            "are.rs".to_string(),
+        ];
+        assert_eq!(actual, expected);
+    }
+}
@@ -12,12 +12,21 @@ use crate::model::ForgeCommandManager;
 pub struct InputCompleter {
     walker: Walker,
     command: CommandCompleter,
+    command_manager: Arc<ForgeCommandManager>,
 }
 
 impl InputCompleter {
-    pub fn new(cwd: PathBuf, command_manager: Arc<ForgeCommandManager>) -> Self {
-        let walker = Walker::max_all().cwd(cwd).skip_binary(true);
-        Self { walker, command: CommandCompleter::new(command_manager) }
+    /// In sparse mode only the top level of the workspace is walked
+    pub fn new(cwd: PathBuf, command_manager: Arc<ForgeCommandManager>, sparse: bool) -> Self {
+        let mut walker = Walker::max_all().cwd(cwd).skip_binary(true);
+        if sparse {
+            walker = walker.max_depth(1);
+        }
+        Self {
+            walker,
+            command: CommandCompleter::new(command_manager.clone()),
+            command_manager,
+        }
     }
 }
 
@@ -33,6 +42,24 @@ impl Completer for InputCompleter {
         }
 
         if let Some(query) = SearchTerm::new(line, pos).process() {
+            let query_lower = query.term.to_lowercase();
+            // Resources of the MCP servers are attached as @[mcp:server/uri]
+            let resources = self
+                .command_manager
+                .mcp_resources()
+                .into_iter()
+                .filter(|resource| {
+                    resource.reference().to_lowercase().contains(&query_lower)
+                        || resource.name.to_lowercase().contains(&query_lower)
+                })
+                .map(|resource| Suggestion {
+                    value: format!("[{}]", resource.reference()),
+                    description: Some(resource.description.unwrap_or(resource.name)),
+                    style: None,
+                    extra: None,
+                    span: query.span,
+                    append_whitespace: true,
+                });
             let files = self.walker.get_blocking().unwrap_or_default();
             files
                 .into_iter()
@@ -40,7 +67,6 @@ impl Completer for InputCompleter {
                 .filter_map(|file| {
                     if let Some(file_name) = file.file_name.as_ref() {
                         let file_name_lower = file_name.to_lowercase();
-                        let query_lower = query.term.to_lowercase();
                         if file_name_lower.contains(&query_lower) {
                             let path_md_fmt = format!("[{}]", file.path);
                             Some(Suggestion {
@@ -58,6 +84,7 @@ impl Completer for InputCompleter {
                         None
                     }
                 })
+                .chain(resources)
                 .collect()
         } else {
             vec![]
@@ -0,0 +1,120 @@
+use std::ffi::OsStr;
+use std::io::Write;
+use std::time::Duration;
+
+use clap::CommandFactory;
+use clap_complete::engine::CompletionCandidate;
+use clap_complete::Shell;
+use forge_api::{ForgeAPI, API};
+
+use crate::Cli;
+
+const BIN_NAME: &str = "forge";
+
+/// Upper bound on the time spent fetching dynamic values so that a slow
+/// provider never blocks the shell.
+const COMPLETION_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Writes the completion script for the given shell.
+pub fn generate_completions(shell: Shell, out: &mut impl Write) {
+    clap_complete::generate(shell, &mut Cli::command(), BIN_NAME, out);
+}
+
+/// Writes the man page generated from the CLI definition.
+pub fn generate_manpage(out: &mut impl Write) -> std::io::Result<()> {
+    clap_mangen::Man::new(Cli::command().name(BIN_NAME)).render(out)
+}
+
+/// Completes model IDs from the provider's model catalog.
+pub fn complete_models(current: &OsStr) -> Vec<CompletionCandidate> {
+    let current = current.to_string_lossy();
+    let models = block_on(async {
+        let api = ForgeAPI::init(false);
+        api.models().await.unwrap_or_default()
+    });
+
+    models
+        .into_iter()
+        .filter(|model| model.id.as_str().starts_with(current.as_ref()))
+        .map(|model| {
+            let candidate = CompletionCandidate::new(model.id.as_str());
+            match model.name {
+                Some(name) => candidate.help(Some(name.into())),
+                None => candidate,
+            }
+        })
+        .collect()
+}
+
+/// Completes the IDs of the conversations in the storage.
+pub fn complete_conversations(current: &OsStr) -> Vec<CompletionCandidate> {
+    let current = current.to_string_lossy();
+    let ids = block_on(async {
+        let api = ForgeAPI::init(false);
+        api.conversations().await.unwrap_or_default()
+    });
+
+    ids.into_iter()
+        .map(|id| id.into_string())
+        .filter(|id| id.starts_with(current.as_ref()))
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+/// Completes the names of the configured MCP servers.
+pub fn complete_mcp_servers(current: &OsStr) -> Vec<CompletionCandidate> {
+    let current = current.to_string_lossy();
+    let servers = block_on(async {
+        let api = ForgeAPI::init(false);
+        api.read_mcp_config().await.unwrap_or_default()
+    });
+
+    servers
+        .mcp_servers
+        .into_keys()
+        .filter(|name| name.starts_with(current.as_ref()))
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+/// Runs a future to completion with [`COMPLETION_TIMEOUT`], from either inside
+/// or outside of a tokio runtime.
+fn block_on<T: Default, F: std::future::Future<Output = T>>(future: F) -> T {
+    let future = async { tokio::time::timeout(COMPLETION_TIMEOUT, future).await };
+    let result = match tokio::runtime::Handle::try_current() {
+        Ok(handle) => tokio::task::block_in_place(|| handle.block_on(future)),
+        Err(_) => match tokio::runtime::Runtime::new() {
+            Ok(runtime) => runtime.block_on(future),
+            Err(_) => return T::default(),
+        },
+    };
+    result.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use clap_complete::Shell;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_generate_completions_references_binary() {
+        let mut fixture = Vec::new();
+        generate_completions(Shell::Bash, &mut fixture);
+
+        let actual = String::from_utf8(fixture).unwrap().contains("_forge()");
+        let expected = true;
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_generate_manpage() {
+        let mut fixture = Vec::new();
+        generate_manpage(&mut fixture).unwrap();
+
+        let actual = String::from_utf8(fixture).unwrap().contains(".TH forge");
+        let expected = true;
+        assert_eq!(actual, expected);
+    }
+}
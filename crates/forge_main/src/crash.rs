@@ -0,0 +1,216 @@
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use chrono::Local;
+use forge_api::{ChatResponse, Environment, Subscription, TurnEvent};
+use forge_tracker::{EventKind, VERSION};
+use serde::Serialize;
+
+use crate::TRACKER;
+
+/// Number of recent turn events kept for crash reports.
+const MAX_EVENTS: usize = 50;
+
+/// Number of trailing log lines included in crash reports.
+const MAX_LOG_LINES: usize = 200;
+
+/// Environment variable that opts into submitting crash reports.
+pub const SUBMIT_ENV: &str = "FORGE_SUBMIT_CRASH_REPORTS";
+
+/// Fragments of variable names whose values are never written to a report.
+const SECRET_MARKERS: [&str; 5] = ["KEY", "TOKEN", "SECRET", "PASSWORD", "AUTH"];
+
+static ENVIRONMENT: OnceLock<Environment> = OnceLock::new();
+static RECENT_EVENTS: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+#[derive(Serialize)]
+struct CrashReport {
+    message: String,
+    version: String,
+    os: String,
+    arch: String,
+    timestamp: String,
+    environment: Option<Environment>,
+    variables: Vec<(String, String)>,
+    recent_events: Vec<String>,
+}
+
+/// Records the environment used to locate logs and the crash directory.
+pub fn init(env: &Environment) {
+    let _ = ENVIRONMENT.set(env.clone());
+}
+
+/// Keeps the most recent turn events so they can be attached to a report.
+pub fn subscribe(mut subscription: Subscription<TurnEvent>) {
+    tokio::spawn(async move {
+        while let Some(event) = subscription.recv().await {
+            record_event(describe(&event.response));
+        }
+    });
+}
+
+fn describe(response: &ChatResponse) -> String {
+    match response {
+        ChatResponse::Text { is_complete, .. } => format!("text (complete: {is_complete})"),
+        ChatResponse::Summary { .. } => "summary".to_string(),
+        ChatResponse::ToolCallStart(call) => format!("tool call start: {}", call.name),
+        ChatResponse::ToolProgress { name, .. } => format!("tool progress: {name}"),
+        ChatResponse::ToolCallEnd(result) => {
+            format!(
+                "tool call end: {} (error: {})",
+                result.name,
+                result.is_error()
+            )
+        }
+        ChatResponse::Usage(usage) => format!("usage: {} tokens", usage.total_tokens),
+        ChatResponse::RetryAttempt { cause, .. } => format!("retry: {}", cause.as_str()),
+        ChatResponse::Interrupt { reason } => format!("interrupt: {reason:?}"),
+        ChatResponse::Blocked { reason } => format!("blocked: {reason}"),
+        ChatResponse::Reasoning { .. } => "reasoning".to_string(),
+        ChatResponse::ChangeSummary(changes) => {
+            format!("change summary: {} files", changes.files.len())
+        }
+        ChatResponse::ProgressReport(report) => {
+            format!("progress report: {} requests", report.requests)
+        }
+    }
+}
+
+fn record_event(event: String) {
+    if let Ok(mut events) = RECENT_EVENTS.lock() {
+        if events.len() == MAX_EVENTS {
+            events.pop_front();
+        }
+        events.push_back(event);
+    }
+}
+
+/// Whether the variable holds a secret, e.g. an API key
+pub fn is_secret(name: &str) -> bool {
+    let name = name.to_uppercase();
+    SECRET_MARKERS.iter().any(|marker| name.contains(marker))
+}
+
+/// Collects the forge related environment variables, redacting secrets.
+fn redacted_variables(vars: impl Iterator<Item = (String, String)>) -> Vec<(String, String)> {
+    let mut variables = vars
+        .filter(|(name, _)| name.starts_with("FORGE_") || is_secret(name))
+        .map(|(name, value)| {
+            let value = if is_secret(&name) {
+                "<redacted>".to_string()
+            } else {
+                value
+            };
+            (name, value)
+        })
+        .collect::<Vec<_>>();
+    variables.sort();
+    variables
+}
+
+/// Returns the last lines of the most recently modified log file.
+fn recent_logs(log_dir: &Path) -> Option<String> {
+    let latest = std::fs::read_dir(log_dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_file())
+        .max_by_key(|entry| entry.metadata().and_then(|meta| meta.modified()).ok())?;
+    let content = std::fs::read_to_string(latest.path()).ok()?;
+    let lines = content.lines().collect::<Vec<_>>();
+    let start = lines.len().saturating_sub(MAX_LOG_LINES);
+    Some(lines[start..].join("\n"))
+}
+
+/// Writes a diagnostic bundle for a panic or fatal error and returns the
+/// directory it was written to.
+pub fn write_report(message: &str) -> Option<PathBuf> {
+    let env = ENVIRONMENT.get();
+    let base_path = env
+        .map(|env| env.base_path.clone())
+        .or_else(dirs_base_path)?;
+    let timestamp = Local::now();
+    let dir = base_path
+        .join("crash")
+        .join(timestamp.format("%Y-%m-%d_%H-%M-%S").to_string());
+    std::fs::create_dir_all(&dir).ok()?;
+
+    let report = CrashReport {
+        message: message.to_string(),
+        version: VERSION.to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        timestamp: timestamp.to_rfc3339(),
+        environment: env.cloned(),
+        variables: redacted_variables(std::env::vars()),
+        recent_events: RECENT_EVENTS
+            .lock()
+            .map(|events| events.iter().cloned().collect())
+            .unwrap_or_default(),
+    };
+    std::fs::write(
+        dir.join("report.json"),
+        serde_json::to_string_pretty(&report).ok()?,
+    )
+    .ok()?;
+
+    if let Some(logs) = env.and_then(|env| recent_logs(&env.log_path())) {
+        let _ = std::fs::write(dir.join("logs.txt"), logs);
+    }
+
+    Some(dir)
+}
+
+fn dirs_base_path() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join("forge"))
+}
+
+/// Submits the crash report when the user has opted in via
+/// [`SUBMIT_ENV`].
+pub async fn submit_report(dir: &Path) {
+    if std::env::var(SUBMIT_ENV).is_err() {
+        return;
+    }
+    if let Ok(report) = std::fs::read_to_string(dir.join("report.json")) {
+        let _ = TRACKER.dispatch(EventKind::Error(report)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_redacted_variables() {
+        let fixture = vec![
+            ("FORGE_LOG".to_string(), "debug".to_string()),
+            ("OPENAI_API_KEY".to_string(), "sk-123".to_string()),
+            ("PATH".to_string(), "/usr/bin".to_string()),
+        ];
+
+        let actual = redacted_variables(fixture.into_iter());
+
+        let expected = vec![
+            ("FORGE_LOG".to_string(), "debug".to_string()),
+            ("OPENAI_API_KEY".to_string(), "<redacted>".to_string()),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_recent_logs_keeps_tail() {
+        let dir = tempfile::tempdir().unwrap();
+        let fixture = (0..MAX_LOG_LINES + 10)
+            .map(|i| format!("line {i}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        std::fs::write(dir.path().join("forge.log"), fixture).unwrap();
+
+        let actual = recent_logs(dir.path()).unwrap();
+
+        assert_eq!(actual.lines().count(), MAX_LOG_LINES);
+        assert_eq!(actual.lines().next(), Some("line 10"));
+    }
+}
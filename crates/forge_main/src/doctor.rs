@@ -0,0 +1,241 @@
+use std::ffi::OsStr;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use forge_api::Workflow;
+use forge_domain::McpServerConfig;
+use tokio::process::Command;
+
+/// Result of a check of the environment
+#[derive(Debug, Clone, PartialEq)]
+pub enum Check {
+    Passed {
+        name: String,
+        detail: String,
+    },
+    /// Failed check with the fix to apply
+    Failed {
+        name: String,
+        problem: String,
+        fix: String,
+    },
+}
+
+impl Check {
+    pub fn passed(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self::Passed { name: name.into(), detail: detail.into() }
+    }
+
+    pub fn failed(
+        name: impl Into<String>,
+        problem: impl Into<String>,
+        fix: impl Into<String>,
+    ) -> Self {
+        Self::Failed { name: name.into(), problem: problem.into(), fix: fix.into() }
+    }
+
+    pub fn is_failed(&self) -> bool {
+        matches!(self, Self::Failed { .. })
+    }
+}
+
+/// Path of the executable run for the command, looked up in the directories
+/// of `path_var` unless the command is a path
+pub fn find_executable(command: &str, path_var: Option<&OsStr>) -> Option<PathBuf> {
+    let command_path = Path::new(command);
+    if command_path.components().count() > 1 {
+        return command_path.is_file().then(|| command_path.to_path_buf());
+    }
+
+    let names = if cfg!(windows) {
+        vec![
+            command.to_string(),
+            format!("{command}.exe"),
+            format!("{command}.cmd"),
+        ]
+    } else {
+        vec![command.to_string()]
+    };
+    std::env::split_paths(path_var?)
+        .flat_map(|dir| names.iter().map(move |name| dir.join(name)))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Top-level keys of a forge.yaml that this version of forge doesn't know,
+/// e.g. settings of a newer version or misspelled ones, which are ignored
+pub fn unknown_workflow_keys(content: &str) -> anyhow::Result<Vec<String>> {
+    let value: serde_yml::Value = serde_yml::from_str(content)?;
+    let schema = schemars::schema_for!(Workflow);
+    let known = schema
+        .schema
+        .object
+        .map(|object| object.properties.into_keys().collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    Ok(value
+        .as_mapping()
+        .into_iter()
+        .flat_map(|mapping| mapping.keys())
+        .filter_map(|key| key.as_str())
+        .filter(|key| !known.iter().any(|known| known == key))
+        .map(str::to_string)
+        .collect())
+}
+
+/// Checks that files can be created in the directory, creating it when missing
+pub fn check_writable(dir: &Path) -> Check {
+    let name = "Base path";
+    let probe = dir.join(format!(".doctor-{}", std::process::id()));
+    let result = std::fs::create_dir_all(dir)
+        .and_then(|_| std::fs::write(&probe, b""))
+        .and_then(|_| std::fs::remove_file(&probe));
+    match result {
+        Ok(_) => Check::passed(name, dir.display().to_string()),
+        Err(error) => Check::failed(
+            name,
+            format!("{} is not writable: {error}", dir.display()),
+            format!(
+                "Give your user write access to {}, forge stores its conversations, snapshots and logs there",
+                dir.display()
+            ),
+        ),
+    }
+}
+
+pub async fn check_git() -> Check {
+    let name = "Git";
+    match Command::new("git").arg("--version").output().await {
+        Ok(output) if output.status.success() => Check::passed(
+            name,
+            String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        ),
+        Ok(output) => Check::failed(
+            name,
+            format!(
+                "git --version failed with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+            "Check the git installation, forge uses it for checkpoints, commits and worktrees",
+        ),
+        Err(error) if error.kind() == ErrorKind::NotFound => Check::failed(
+            name,
+            "git was not found",
+            "Install git from https://git-scm.com/downloads, forge uses it for checkpoints, commits and worktrees",
+        ),
+        Err(error) => Check::failed(
+            name,
+            format!("git could not be run: {error}"),
+            "Check that git is executable, forge uses it for checkpoints, commits and worktrees",
+        ),
+    }
+}
+
+/// Checks that the shell the commands of the agents run in is installed
+pub fn check_shell(shell: &str, restricted: bool) -> Check {
+    let name = "Shell";
+    match find_executable(shell, std::env::var_os("PATH").as_deref()) {
+        Some(path) => Check::passed(name, path.display().to_string()),
+        None if restricted => Check::failed(
+            name,
+            format!("{shell} was not found"),
+            "Install bash, which provides rbash, or run forge without --restricted",
+        ),
+        None => Check::failed(
+            name,
+            format!("{shell} was not found"),
+            "Set the SHELL environment variable to the path of an installed shell",
+        ),
+    }
+}
+
+/// Checks that the command of a stdio server can be spawned, or that the URL
+/// of an SSE server answers
+pub async fn check_mcp_server(name: &str, server: &McpServerConfig) -> Check {
+    let name = format!("MCP server {name}");
+    match server {
+        McpServerConfig::Stdio(stdio) => {
+            match find_executable(&stdio.command, std::env::var_os("PATH").as_deref()) {
+                Some(path) => Check::passed(name, path.display().to_string()),
+                None => Check::failed(
+                    name,
+                    format!("{} was not found", stdio.command),
+                    format!(
+                        "Install {} or fix the command of the server in .mcp.json",
+                        stdio.command
+                    ),
+                ),
+            }
+        }
+        McpServerConfig::Sse(sse) => {
+            // A WebSocket server answers the plain HTTP request of the handshake
+            let url = match sse.url.strip_prefix("ws") {
+                Some(rest) if rest.starts_with("://") || rest.starts_with("s://") => {
+                    format!("http{rest}")
+                }
+                _ => sse.url.clone(),
+            };
+            let response = match reqwest::Client::builder()
+                .timeout(Duration::from_secs(5))
+                .build()
+            {
+                Ok(client) => client.get(&url).send().await.map(|_| ()),
+                Err(error) => Err(error),
+            };
+            match response {
+                Ok(_) => Check::passed(name, sse.url.clone()),
+                Err(error) => Check::failed(
+                    name,
+                    format!("{} is unreachable: {error}", sse.url),
+                    "Start the server or fix its URL in .mcp.json",
+                ),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_find_executable() {
+        let dir = tempfile::tempdir().unwrap();
+        let fixture = dir.path().join("npx");
+        std::fs::write(&fixture, "").unwrap();
+        let path_var = std::env::join_paths([Path::new("/nonexistent"), dir.path()]).unwrap();
+
+        let actual = (
+            find_executable("npx", Some(&path_var)),
+            find_executable("uvx", Some(&path_var)),
+            find_executable(fixture.to_str().unwrap(), None),
+        );
+
+        let expected = (Some(fixture.clone()), None, Some(fixture.clone()));
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_unknown_workflow_keys() {
+        let fixture = "model: anthropic/claude-sonnet-4\nmax_request_per_turn: 10\nstatus_line: '{model}'\nsandbox: true\n";
+
+        let actual = unknown_workflow_keys(fixture).unwrap();
+
+        let expected = vec!["max_request_per_turn".to_string(), "sandbox".to_string()];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_check_writable() {
+        let dir = tempfile::tempdir().unwrap();
+        let fixture = dir.path().join("forge");
+
+        let actual = check_writable(&fixture);
+
+        let expected = Check::passed("Base path", fixture.display().to_string());
+        assert_eq!(actual, expected);
+    }
+}
@@ -1,18 +1,27 @@
+use std::process::Command;
 use std::sync::Arc;
 
-use forge_api::Environment;
+use forge_api::{EditMode, Environment};
 use nu_ansi_term::{Color, Style};
 use reedline::{
-    default_emacs_keybindings, ColumnarMenu, DefaultHinter, EditCommand, Emacs, FileBackedHistory,
-    KeyCode, KeyModifiers, MenuBuilder, Prompt, Reedline, ReedlineEvent, ReedlineMenu, Signal,
+    default_emacs_keybindings, default_vi_insert_keybindings, default_vi_normal_keybindings,
+    ColumnarMenu, DefaultHinter, EditCommand, Emacs, ExternalPrinter, FileBackedHistory, KeyCode,
+    KeyModifiers, Keybindings, MenuBuilder, Prompt, Reedline, ReedlineEvent, ReedlineMenu, Signal,
+    ValidationResult, Validator, Vi,
 };
 
 use super::completer::InputCompleter;
+use crate::history::migrate_history;
 use crate::model::ForgeCommandManager;
+use crate::paste::PasteAwareEditMode;
 
 // TODO: Store the last `HISTORY_CAPACITY` commands in the history file
 const HISTORY_CAPACITY: usize = 1024 * 1024;
 const COMPLETION_MENU: &str = "completion_menu";
+const CODE_FENCE: &str = "```";
+/// Host command of the key binding toggling the ask mode, which keeps the
+/// input being typed
+const TOGGLE_ASK: &str = "forge:toggle-ask";
 
 pub struct ForgeEditor {
     editor: Reedline,
@@ -20,14 +29,15 @@ pub struct ForgeEditor {
 
 pub enum ReadResult {
     Success(String),
+    /// The ask mode was toggled while the input held the text
+    ToggleAsk(String),
     Empty,
     Continue,
     Exit,
 }
 
 impl ForgeEditor {
-    fn init() -> reedline::Keybindings {
-        let mut keybindings = default_emacs_keybindings();
+    fn init(keybindings: &mut Keybindings) {
         // on TAB press shows the completion menu, and if we've exact match it will
         // insert it
         keybindings.add_binding(
@@ -60,12 +70,33 @@ impl ForgeEditor {
             ReedlineEvent::Edit(vec![EditCommand::InsertNewline]),
         );
 
-        keybindings
+        // on ALT + a press toggles the ask mode
+        keybindings.add_binding(
+            KeyModifiers::ALT,
+            KeyCode::Char('a'),
+            ReedlineEvent::ExecuteHostCommand(TOGGLE_ASK.to_string()),
+        );
+
+        // on CTRL + o press opens the prompt in $VISUAL or $EDITOR
+        keybindings.add_binding(
+            KeyModifiers::CONTROL,
+            KeyCode::Char('o'),
+            ReedlineEvent::OpenEditor,
+        );
     }
 
-    pub fn new(env: Environment, manager: Arc<ForgeCommandManager>) -> Self {
-        // Store file history in system config directory
+    pub fn new(
+        env: Environment,
+        manager: Arc<ForgeCommandManager>,
+        mode: EditMode,
+        sparse: bool,
+        printer: ExternalPrinter<String>,
+    ) -> Self {
+        // Store the history of the prompts in the workspace
         let history_file = env.history_path();
+        if let Err(error) = migrate_history(&env.legacy_history_path(), &history_file) {
+            tracing::warn!(error = %error, "Failed to migrate the history");
+        }
 
         let history = Box::new(
             FileBackedHistory::with_file(HISTORY_CAPACITY, history_file).unwrap_or_default(),
@@ -78,28 +109,101 @@ impl ForgeEditor {
                 .with_selected_text_style(Style::new().on(Color::White).fg(Color::Black)),
         );
 
-        let edit_mode = Box::new(Emacs::new(Self::init()));
+        let edit_mode: Box<dyn reedline::EditMode> = match mode {
+            EditMode::Emacs => {
+                let mut keybindings = default_emacs_keybindings();
+                Self::init(&mut keybindings);
+                Box::new(Emacs::new(keybindings))
+            }
+            EditMode::Vi => {
+                let mut insert = default_vi_insert_keybindings();
+                Self::init(&mut insert);
+                let mut normal = default_vi_normal_keybindings();
+                Self::init(&mut normal);
+                Box::new(Vi::new(insert, normal))
+            }
+        };
+
+        // The prompt is edited in a file named after the process, so that
+        // sessions running side by side don't share it
+        let draft_file = std::env::temp_dir().join(format!("forge-prompt-{}.md", env.pid));
+        let edit_mode = Box::new(PasteAwareEditMode::new(edit_mode, env.pastes_path()));
 
         let editor = Reedline::create()
-            .with_completer(Box::new(InputCompleter::new(env.cwd, manager)))
+            .with_completer(Box::new(InputCompleter::new(env.cwd, manager, sparse)))
             .with_history(history)
             .with_hinter(Box::new(
                 DefaultHinter::default().with_style(Style::new().fg(Color::DarkGray)),
             ))
             .with_menu(ReedlineMenu::EngineCompleter(completion_menu))
             .with_edit_mode(edit_mode)
+            .with_validator(Box::new(PromptValidator))
+            .with_buffer_editor(external_editor(), draft_file)
             .with_quick_completions(true)
             .with_ansi_colors(true)
+            .with_external_printer(printer)
             .use_bracketed_paste(true);
         Self { editor }
     }
 
+    /// Puts the text in the input, to be edited before being sent
+    pub fn set_draft(&mut self, draft: String) {
+        self.editor
+            .run_edit_commands(&[EditCommand::InsertString(draft)]);
+    }
+
     pub fn prompt(&mut self, prompt: &dyn Prompt) -> anyhow::Result<ReadResult> {
-        let signal = self.editor.read_line(prompt);
-        signal.map(Into::into).map_err(|e| anyhow::anyhow!(e))
+        match self.editor.read_line(prompt) {
+            Ok(Signal::Success(command)) if command == TOGGLE_ASK => Ok(ReadResult::ToggleAsk(
+                self.editor.current_buffer_contents().to_string(),
+            )),
+            signal => signal.map(Into::into).map_err(|e| anyhow::anyhow!(e)),
+        }
+    }
+}
+
+/// Keeps reading lines while a code block of the prompt isn't closed, so that
+/// code can be pasted or typed without sending the prompt halfway
+struct PromptValidator;
+
+impl Validator for PromptValidator {
+    fn validate(&self, line: &str) -> ValidationResult {
+        if is_complete(line) {
+            ValidationResult::Complete
+        } else {
+            ValidationResult::Incomplete
+        }
     }
 }
 
+/// Whether every code block of the prompt is closed
+fn is_complete(prompt: &str) -> bool {
+    prompt
+        .lines()
+        .filter(|line| line.trim_start().starts_with(CODE_FENCE))
+        .count()
+        % 2
+        == 0
+}
+
+/// Editor opened to compose the prompt, from `$VISUAL` or `$EDITOR`, which may
+/// include arguments such as `code --wait`
+fn external_editor() -> Command {
+    let editor = ["VISUAL", "EDITOR"]
+        .into_iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.trim().is_empty());
+    editor_command(editor.as_deref())
+}
+
+fn editor_command(editor: Option<&str>) -> Command {
+    let default = if cfg!(windows) { "notepad" } else { "vi" };
+    let mut words = editor.unwrap_or(default).split_whitespace();
+    let mut command = Command::new(words.next().unwrap_or(default));
+    command.args(words);
+    command
+}
+
 impl From<Signal> for ReadResult {
     fn from(signal: Signal) -> Self {
         match signal {
@@ -116,3 +220,38 @@ impl From<Signal> for ReadResult {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_is_complete() {
+        let actual = [
+            is_complete("Fix the tests"),
+            is_complete("Explain this:\n```rust\nfn main() {}"),
+            is_complete("Explain this:\n```rust\nfn main() {}\n```"),
+        ];
+
+        let expected = [true, false, true];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_editor_command() {
+        let command = editor_command(Some("code --wait"));
+
+        let actual = (
+            command.get_program().to_string_lossy().to_string(),
+            command
+                .get_args()
+                .map(|arg| arg.to_string_lossy().to_string())
+                .collect::<Vec<_>>(),
+        );
+
+        let expected = ("code".to_string(), vec!["--wait".to_string()]);
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,65 @@
+use std::path::Path;
+
+use forge_api::{ModelId, Workflow};
+use forge_domain::Fingerprint;
+use sha2::{Digest, Sha256};
+use tokio::process::Command;
+
+/// Fingerprint of the environment a seeded run starts in
+pub async fn fingerprint(
+    version: String,
+    model: &ModelId,
+    workflow: &Workflow,
+    cwd: &Path,
+) -> Fingerprint {
+    Fingerprint {
+        version,
+        os: format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH),
+        model: model.to_string(),
+        workflow: workflow_hash(workflow),
+        commit: head_commit(cwd).await,
+    }
+}
+
+/// Short hash of the workflow. Its maps are serialized as sorted JSON objects
+/// so that the hash doesn't depend on their order.
+fn workflow_hash(workflow: &Workflow) -> String {
+    let value = serde_json::to_value(workflow).unwrap_or_default();
+    let hash = format!("{:x}", Sha256::digest(value.to_string().as_bytes()));
+    hash[..12].to_string()
+}
+
+async fn head_commit(cwd: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(cwd)
+        .output()
+        .await
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_workflow_hash() {
+        let fixture = Workflow::new();
+        let changed = Workflow::new().model(ModelId::new("gpt-4.1"));
+
+        let actual = (
+            workflow_hash(&fixture) == workflow_hash(&Workflow::new()),
+            workflow_hash(&fixture) == workflow_hash(&changed),
+            workflow_hash(&fixture).len(),
+        );
+
+        let expected = (true, false, 12);
+        assert_eq!(actual, expected);
+    }
+}
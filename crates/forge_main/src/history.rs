@@ -0,0 +1,129 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Escape of the newlines of multi-line entries in the history file of the
+/// editor
+const NEWLINE_ESCAPE: &str = "<\\n>";
+
+/// Seeds the history of a workspace with the history shared by all
+/// workspaces before it was kept per workspace, so that the earlier prompts
+/// aren't lost. The shared history is copied rather than moved as the other
+/// workspaces are seeded from it too.
+pub fn migrate_history(legacy: &Path, history: &Path) -> std::io::Result<()> {
+    if history.exists() || !legacy.is_file() {
+        return Ok(());
+    }
+    if let Some(parent) = history.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::copy(legacy, history)?;
+    Ok(())
+}
+
+/// Prompts and commands of a history file, most recent first and without
+/// duplicates
+pub fn parse_history(content: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    content
+        .lines()
+        .rev()
+        .map(|line| line.replace(NEWLINE_ESCAPE, "\n"))
+        .filter(|entry| !entry.trim().is_empty())
+        .filter(|entry| seen.insert(entry.clone()))
+        .collect()
+}
+
+/// Entries containing the characters of the query in order, ignoring case
+/// and whitespace, best matches first. Entries matching equally well keep
+/// their order.
+pub fn fuzzy_filter<'a>(entries: &'a [String], query: &str) -> Vec<&'a String> {
+    let mut matches = entries
+        .iter()
+        .filter_map(|entry| fuzzy_score(entry, query).map(|score| (score, entry)))
+        .collect::<Vec<_>>();
+    matches.sort_by_key(|(score, _)| *score);
+    matches.into_iter().map(|(_, entry)| entry).collect()
+}
+
+/// Number of characters skipped to match the query, lower is better. `None`
+/// when the entry doesn't match.
+fn fuzzy_score(entry: &str, query: &str) -> Option<usize> {
+    let mut candidates = entry.chars().flat_map(char::to_lowercase);
+    let mut skipped = 0;
+    for expected in query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+    {
+        loop {
+            let actual = candidates.next()?;
+            if actual == expected {
+                break;
+            }
+            skipped += 1;
+        }
+    }
+    Some(skipped)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_history() {
+        let fixture = "/model\nfix the tests\n\nexplain<\\n>this file\nfix the tests\n";
+
+        let actual = parse_history(fixture);
+
+        let expected = vec![
+            "fix the tests".to_string(),
+            "explain\nthis file".to_string(),
+            "/model".to_string(),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_fuzzy_filter() {
+        let fixture = vec![
+            "refactor the config loader".to_string(),
+            "/model".to_string(),
+            "fix the failing tests".to_string(),
+            "fix tests".to_string(),
+        ];
+
+        let actual = fuzzy_filter(&fixture, "fix tests");
+
+        let expected = vec![&fixture[3], &fixture[2]];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_fuzzy_filter_empty_query() {
+        let fixture = vec!["/model".to_string(), "fix tests".to_string()];
+
+        let actual = fuzzy_filter(&fixture, "");
+
+        let expected = vec![&fixture[0], &fixture[1]];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_migrate_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let legacy = dir.path().join(".forge_history");
+        let history = dir.path().join("workspace/.forge/history");
+        std::fs::write(&legacy, "cargo test\n").unwrap();
+
+        migrate_history(&legacy, &history).unwrap();
+        std::fs::write(&legacy, "cargo build\n").unwrap();
+        migrate_history(&legacy, &history).unwrap();
+
+        let actual = std::fs::read_to_string(&history).unwrap();
+        let expected = "cargo test\n";
+        assert_eq!(actual, expected);
+    }
+}
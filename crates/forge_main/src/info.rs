@@ -2,7 +2,11 @@ use std::fmt;
 use std::path::{Path, PathBuf};
 
 use colored::Colorize;
-use forge_api::{Environment, LoginInfo};
+use forge_api::{
+    Checkpoint, Context, ContextDiff, ContextInspection, Environment, FileChangeKind, LoginInfo,
+    MessageSummary, Pin, ToolStatsLedger, TurnRecord, UsageQuota, UsageRecord, WorkspaceSettings,
+};
+use forge_display::theme;
 use forge_tracker::VERSION;
 
 use crate::model::ForgeCommandManager;
@@ -116,8 +120,254 @@ impl From<&UIState> for Info {
     }
 }
 
+impl From<&WorkspaceSettings> for Info {
+    fn from(settings: &WorkspaceSettings) -> Self {
+        let mut info = Info::new().add_title("Workspace Settings");
+
+        if let Some(model) = &settings.model {
+            info = info.add_key_value("Model", model);
+        }
+
+        if let Some(agent) = &settings.operating_agent {
+            info = info.add_key_value("Agent", agent);
+        }
+
+        if !settings.approvals.is_empty() {
+            info = info.add_title("Approvals");
+            for grant in &settings.approvals {
+                info = info.add_key(grant);
+            }
+        }
+
+        if !settings.tasks.is_empty() {
+            info = info.add_title("Tasks");
+            for (branch, issue) in &settings.tasks {
+                info = info.add_key_value(branch, format!("#{} {}", issue.number, issue.title));
+            }
+        }
+
+        info
+    }
+}
+
+impl From<&[UsageRecord]> for Info {
+    fn from(days: &[UsageRecord]) -> Self {
+        days.iter()
+            .fold(Info::new().add_title("Usage"), |info, day| {
+                let usage = format!(
+                    "{} requests, {} tokens, ${:.2}",
+                    day.requests,
+                    day.tokens(),
+                    day.cost
+                );
+                info.add_key_value(day.date, usage)
+            })
+    }
+}
+
+impl From<&ToolStatsLedger> for Info {
+    fn from(ledger: &ToolStatsLedger) -> Self {
+        ledger
+            .report()
+            .into_iter()
+            .fold(Info::new().add_title("Tools"), |info, (name, stats)| {
+                let mut value = format!(
+                    "{} calls, {:.0}% failed, {}ms avg, {} result tokens avg",
+                    stats.calls,
+                    stats.failure_rate() * 100.0,
+                    stats.average_duration().as_millis(),
+                    stats.average_result_tokens()
+                );
+                if stats.is_failing() {
+                    value = format!("{value} {}", "(fails often)".yellow());
+                }
+                if stats.is_bloated() {
+                    value = format!("{value} {}", "(large results)".yellow());
+                }
+                info.add_key_value(name, value)
+            })
+    }
+}
+
+impl From<&UsageQuota> for Info {
+    fn from(quota: &UsageQuota) -> Self {
+        let mut info = Info::new().add_title("Daily Quota");
+
+        if let Some(tokens) = quota.daily_tokens {
+            info = info.add_key_value("Tokens", tokens);
+        }
+
+        if let Some(cost) = quota.daily_cost {
+            info = info.add_key_value("Cost", format!("${cost:.2}"));
+        }
+
+        let scope = if quota.per_api_key {
+            "API key"
+        } else {
+            "Workspace"
+        };
+        info.add_key_value("Applies To", scope)
+    }
+}
+
+impl From<&ContextInspection> for Info {
+    fn from(inspection: &ContextInspection) -> Self {
+        let usage = &inspection.usage;
+        let info = Info::new()
+            .add_title("Context")
+            .add_key_value("Model", &inspection.model)
+            .add_key_value("Messages", usage.messages)
+            .add_key_value("Tools", usage.tools)
+            .add_title("Estimated Tokens")
+            .add_key_value("System Prompt", usage.system_tokens)
+            .add_key_value("Messages", usage.message_tokens)
+            .add_key_value("Tool Schemas", usage.tool_tokens)
+            .add_key_value("Total", usage.total_tokens());
+
+        if inspection.pins.is_empty() {
+            info
+        } else {
+            add_pins(info, &inspection.pins)
+        }
+    }
+}
+
+impl From<&Context> for Info {
+    fn from(context: &Context) -> Self {
+        context.messages.iter().enumerate().fold(
+            Info::new().add_title("Messages"),
+            |info, (index, message)| {
+                let summary = MessageSummary::from(message);
+                let value = format!(
+                    "{} ~{} tokens {}",
+                    summary.role, summary.tokens, summary.preview
+                );
+                info.add_key_value(index + 1, value)
+            },
+        )
+    }
+}
+
+impl From<&[Pin]> for Info {
+    fn from(pins: &[Pin]) -> Self {
+        add_pins(Info::new(), pins)
+    }
+}
+
+/// Lists the pins under their position, which `/unpin` takes
+fn add_pins(info: Info, pins: &[Pin]) -> Info {
+    pins.iter()
+        .enumerate()
+        .fold(info.add_title("Pinned"), |info, (index, pin)| {
+            info.add_key_value((index + 1).to_string(), pin)
+        })
+}
+
+impl From<&ContextDiff> for Info {
+    fn from(diff: &ContextDiff) -> Self {
+        let (before, after) = (&diff.before, &diff.after);
+        let change = |before: usize, after: usize| format!("{before} -> {after}");
+        let info = Info::new()
+            .add_title("Context Diff")
+            .add_key_value("Messages", change(before.messages, after.messages))
+            .add_key_value("Tools", change(before.tools, after.tools))
+            .add_title("Estimated Tokens")
+            .add_key_value(
+                "System Prompt",
+                change(before.system_tokens, after.system_tokens),
+            )
+            .add_key_value(
+                "Messages",
+                change(before.message_tokens, after.message_tokens),
+            )
+            .add_key_value(
+                "Tool Schemas",
+                change(before.tool_tokens, after.tool_tokens),
+            )
+            .add_key_value("Total", format!("{:+}", diff.token_delta()));
+
+        let info = [
+            ("Added Messages", &diff.added_messages),
+            ("Removed Messages", &diff.removed_messages),
+        ]
+        .into_iter()
+        .filter(|(_, messages)| !messages.is_empty())
+        .fold(info, |info, (title, messages)| {
+            messages
+                .iter()
+                .fold(info.add_title(title), |info, message| {
+                    info.add_key_value(
+                        format!("{} ({} tokens)", message.role, message.tokens),
+                        &message.preview,
+                    )
+                })
+        });
+
+        [
+            ("Added Tools", &diff.added_tools),
+            ("Removed Tools", &diff.removed_tools),
+        ]
+        .into_iter()
+        .filter(|(_, tools)| !tools.is_empty())
+        .fold(info, |info, (title, tools)| {
+            tools
+                .iter()
+                .fold(info.add_title(title), |info, tool| info.add_key(tool))
+        })
+    }
+}
+
+impl From<&[Checkpoint]> for Info {
+    fn from(checkpoints: &[Checkpoint]) -> Self {
+        checkpoints
+            .iter()
+            .fold(Info::new().add_title("Checkpoints"), |info, checkpoint| {
+                let created_at = checkpoint.created_at.with_timezone(&chrono::Local);
+                info.add_key_value(
+                    &checkpoint.name,
+                    format!(
+                        "{}, {} file(s)",
+                        created_at.format("%H:%M:%S"),
+                        checkpoint.files.len()
+                    ),
+                )
+            })
+    }
+}
+
+impl From<&[TurnRecord]> for Info {
+    fn from(turns: &[TurnRecord]) -> Self {
+        turns
+            .iter()
+            .enumerate()
+            .fold(Info::new(), |info, (index, turn)| {
+                let started_at = turn.started_at.with_timezone(&chrono::Local);
+                let info = info
+                    .add_title(format!(
+                        "Turn {} ({})",
+                        index + 1,
+                        started_at.format("%H:%M:%S")
+                    ))
+                    .add_key_value("Request", &turn.prompt);
+
+                turn.changes.files.iter().fold(info, |info, file| {
+                    let kind = match file.kind {
+                        FileChangeKind::Added => "added",
+                        FileChangeKind::Modified => "modified",
+                        FileChangeKind::Deleted => "deleted",
+                    };
+                    info.add_key_value(
+                        &file.path,
+                        format!("{kind} +{} -{}", file.insertions, file.deletions),
+                    )
+                })
+            })
+    }
+}
+
 impl fmt::Display for Info {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let theme = theme();
         for section in &self.sections {
             match section {
                 Section::Title(title) => {
@@ -126,7 +376,7 @@ impl fmt::Display for Info {
                 }
                 Section::Items(key, value) => {
                     if let Some(value) = value {
-                        writeln!(f, "{}: {}", key.bright_cyan().bold(), value)?;
+                        writeln!(f, "{}: {}", key.color(theme.highlight).bold(), value)?;
                     } else {
                         writeln!(f, "{key}")?;
                     }
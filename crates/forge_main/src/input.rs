@@ -1,8 +1,9 @@
 use std::path::PathBuf;
 use std::sync::Arc;
 
-use forge_api::Environment;
+use forge_api::{EditMode, Environment};
 use forge_display::TitleFormat;
+use reedline::ExternalPrinter;
 use tokio::fs;
 use tokio::sync::Mutex;
 use tokio::task::block_in_place;
@@ -17,12 +18,42 @@ use crate::tracker;
 pub struct Console {
     env: Environment,
     command: Arc<ForgeCommandManager>,
+    edit_mode: EditMode,
+    /// Whether the workspace is too large for the files to be completed
+    /// beyond its top level
+    sparse: bool,
+    /// Input left when the ask mode was toggled, put back in the next prompt
+    pending: Option<String>,
+    /// Prints above the prompt while the user is typing
+    printer: ExternalPrinter<String>,
 }
 
 impl Console {
     /// Creates a new instance of `Console`.
     pub fn new(env: Environment, command: Arc<ForgeCommandManager>) -> Self {
-        Self { env, command }
+        Self {
+            env,
+            command,
+            edit_mode: EditMode::default(),
+            sparse: false,
+            pending: None,
+            printer: ExternalPrinter::default(),
+        }
+    }
+
+    /// Printer of the lines shown above the prompt while the user is typing
+    pub fn printer(&self) -> ExternalPrinter<String> {
+        self.printer.clone()
+    }
+
+    /// Sets the key bindings of the following prompts
+    pub fn set_edit_mode(&mut self, edit_mode: EditMode) {
+        self.edit_mode = edit_mode;
+    }
+
+    /// Limits the completion of the files to the top level of the workspace
+    pub fn set_sparse(&mut self, sparse: bool) {
+        self.sparse = sparse;
     }
 }
 
@@ -35,8 +66,25 @@ impl Console {
         Ok(Command::Message(content))
     }
 
-    pub async fn prompt(&self, prompt: ForgePrompt) -> anyhow::Result<Command> {
-        let engine = Mutex::new(ForgeEditor::new(self.env.clone(), self.command.clone()));
+    /// Reads a command from the user. The draft, if any, is put in the input
+    /// to be edited before being sent, otherwise the input left when the ask
+    /// mode was toggled.
+    pub async fn prompt(
+        &mut self,
+        prompt: ForgePrompt,
+        draft: Option<String>,
+    ) -> anyhow::Result<Command> {
+        let mut editor = ForgeEditor::new(
+            self.env.clone(),
+            self.command.clone(),
+            self.edit_mode,
+            self.sparse,
+            self.printer.clone(),
+        );
+        if let Some(draft) = draft.or_else(|| self.pending.take()) {
+            editor.set_draft(draft);
+        }
+        let engine = Mutex::new(editor);
 
         loop {
             let user_input =
@@ -45,6 +93,10 @@ impl Console {
                 ReadResult::Continue => continue,
                 ReadResult::Exit => return Ok(Command::Exit),
                 ReadResult::Empty => continue,
+                ReadResult::ToggleAsk(input) => {
+                    self.pending = Some(input).filter(|input| !input.is_empty());
+                    return Ok(Command::Ask { question: None });
+                }
                 ReadResult::Success(text) => {
                     tracker::prompt(text.clone());
                     match self.command.parse(&text) {
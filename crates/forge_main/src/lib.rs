@@ -1,16 +1,36 @@
 mod banner;
+mod batch;
+mod citation;
 mod cli;
+mod commit;
+mod compare;
 mod completer;
+mod completions;
+pub mod crash;
+mod doctor;
 mod editor;
+mod fingerprint;
+mod history;
 mod info;
 mod input;
+mod memory;
 mod model;
+mod notification;
+mod onboarding;
+mod paste;
+mod process;
 mod prompt;
+mod release_notes;
+mod review;
+mod share;
 mod state;
+mod status_line;
+mod task;
 mod tools_display;
 pub mod tracker;
 mod ui;
 mod update;
+mod worktree;
 
 pub use cli::Cli;
 use lazy_static::lazy_static;
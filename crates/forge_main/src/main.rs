@@ -1,13 +1,17 @@
 use std::panic;
 
 use anyhow::Result;
-use clap::Parser;
+use clap::{CommandFactory, Parser};
+use clap_complete::env::CompleteEnv;
 use forge_api::ForgeAPI;
 use forge_display::TitleFormat;
-use forge_main::{tracker, Cli, UI};
+use forge_main::{crash, tracker, Cli, UI};
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    // Respond to shell completion requests (COMPLETE=<shell>) and exit
+    CompleteEnv::with_factory(|| Cli::command().name("forge")).complete();
+
     // Set up panic hook for better error display
     panic::set_hook(Box::new(|panic_info| {
         let message = if let Some(s) = panic_info.payload().downcast_ref::<&str>() {
@@ -19,6 +23,13 @@ async fn main() -> Result<()> {
         };
 
         eprintln!("{}", TitleFormat::error(message.to_string()));
+        if let Some(dir) = crash::write_report(&message) {
+            eprintln!(
+                "{}",
+                TitleFormat::info(format!("Crash report written to {}", dir.display()))
+            );
+            tracker::block_on(async move { crash::submit_report(&dir).await });
+        }
         tracker::error_blocking(message);
         std::process::exit(1);
     }));
@@ -0,0 +1,19 @@
+use forge_domain::{ProjectMemory, PROJECT_MEMORY_TAG};
+
+/// Task asking the agent for the durable learnings of the conversation that
+/// aren't in the memory of the project yet
+pub fn memory_task(memory: &ProjectMemory) -> String {
+    let mut task = format!(
+        "The conversation is ending. Distill what is worth knowing about this project in the next \
+         conversations: commands building, testing or running it, conventions its code follows \
+         and gotchas that were run into. Leave out what only matters to the current task. Answer \
+         with Markdown lists under `## Commands`, `## Conventions` and `## Gotchas` headings, \
+         one short fact per item and without the empty sections, within <{PROJECT_MEMORY_TAG}> \
+         tags. Answer with empty tags when there's nothing new to remember. Finish with the \
+         `forge_tool_attempt_completion` tool, giving the tags as its result."
+    );
+    if !memory.is_empty() {
+        task.push_str(&format!("\n\nAlready remembered:\n\n{memory}"));
+    }
+    task
+}
@@ -1,6 +1,7 @@
+use std::collections::BTreeMap;
 use std::sync::{Arc, Mutex};
 
-use forge_api::{Model, Workflow};
+use forge_api::{DropTarget, McpPrompt, McpResource, Model, Workflow};
 use strum::{EnumProperty, IntoEnumIterator};
 use strum_macros::{EnumIter, EnumProperty};
 
@@ -51,12 +52,20 @@ impl From<&Workflow> for ForgeCommandManager {
 #[derive(Debug)]
 pub struct ForgeCommandManager {
     commands: Arc<Mutex<Vec<ForgeCommand>>>,
+    /// Prompts of the MCP servers, registered as commands too
+    mcp_prompts: Arc<Mutex<Vec<McpPrompt>>>,
+    /// Resources of the MCP servers, suggested as attachments
+    mcp_resources: Arc<Mutex<Vec<McpResource>>>,
 }
 
 impl Default for ForgeCommandManager {
     fn default() -> Self {
         let commands = Self::default_commands();
-        ForgeCommandManager { commands: Arc::new(Mutex::new(commands)) }
+        ForgeCommandManager {
+            commands: Arc::new(Mutex::new(commands)),
+            mcp_prompts: Default::default(),
+            mcp_resources: Default::default(),
+        }
     }
 }
 
@@ -66,6 +75,7 @@ impl ForgeCommandManager {
             .filter(|command| !matches!(command, Command::Message(_)))
             .filter(|command| !matches!(command, Command::Custom(_)))
             .filter(|command| !matches!(command, Command::Shell(_)))
+            .filter(|command| !matches!(command, Command::McpPrompt { .. }))
             .map(|command| ForgeCommand {
                 name: command.name().to_string(),
                 description: command.usage().to_string(),
@@ -92,6 +102,44 @@ impl ForgeCommandManager {
         *guard = commands;
     }
 
+    /// Registers the prompts of the MCP servers as commands, replacing the
+    /// ones registered before
+    pub fn register_mcp_prompts(&self, prompts: Vec<McpPrompt>) {
+        let mut commands = self.commands.lock().unwrap();
+        let mut mcp_prompts = self.mcp_prompts.lock().unwrap();
+        commands.retain(|command| !mcp_prompts.iter().any(|p| p.command() == command.name));
+        commands.extend(prompts.iter().map(|prompt| ForgeCommand {
+            name: prompt.command(),
+            description: format!(
+                "⚙ {}",
+                prompt.description.as_deref().unwrap_or(&prompt.name)
+            ),
+            value: None,
+        }));
+        *mcp_prompts = prompts;
+    }
+
+    /// Registers the resources of the MCP servers, replacing the ones
+    /// registered before
+    pub fn register_mcp_resources(&self, resources: Vec<McpResource>) {
+        *self.mcp_resources.lock().unwrap() = resources;
+    }
+
+    /// Resources of the MCP servers that can be attached to a message
+    pub fn mcp_resources(&self) -> Vec<McpResource> {
+        self.mcp_resources.lock().unwrap().clone()
+    }
+
+    /// Finds the MCP prompt a command runs
+    fn find_mcp_prompt(&self, command: &str) -> Option<McpPrompt> {
+        self.mcp_prompts
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|prompt| prompt.command() == command)
+            .cloned()
+    }
+
     /// Finds a command by name.
     fn find(&self, command: &str) -> Option<ForgeCommand> {
         self.commands
@@ -170,13 +218,10 @@ impl ForgeCommandManager {
             "/info" => Ok(Command::Info),
             "/exit" => Ok(Command::Exit),
             "/update" => Ok(Command::Update),
-            "/dump" => {
-                if !parameters.is_empty() && parameters[0] == "html" {
-                    Ok(Command::Dump(Some("html".to_string())))
-                } else {
-                    Ok(Command::Dump(None))
-                }
-            }
+            "/dump" => match parameters.first() {
+                Some(format @ ("html" | "timeline")) => Ok(Command::Dump(Some(format.to_string()))),
+                _ => Ok(Command::Dump(None)),
+            },
             "/act" | "/forge" => Ok(Command::Forge),
             "/plan" | "/muse" => Ok(Command::Muse),
             "/help" => Ok(Command::Help),
@@ -185,7 +230,124 @@ impl ForgeCommandManager {
             "/agent" => Ok(Command::Agent),
             "/login" => Ok(Command::Login),
             "/logout" => Ok(Command::Logout),
+            "/settings" => Ok(Command::Settings {
+                clear: parameters.first().is_some_and(|param| *param == "clear"),
+            }),
+            "/debug" => Ok(Command::Debug),
+            "/context" => match parameters.as_slice() {
+                [] => Ok(Command::Context { diff: false }),
+                ["diff"] => Ok(Command::Context { diff: true }),
+                _ => Err(anyhow::anyhow!("Usage: /context [diff]")),
+            },
+            "/notools" => Ok(Command::NoTools),
+            "/ask" => Ok(Command::Ask {
+                question: (!parameters.is_empty()).then(|| parameters.join(" ")),
+            }),
+            "/history" => match parameters.as_slice() {
+                [] => Ok(Command::History { action: None }),
+                ["restore", turn] => match turn.parse::<usize>() {
+                    Ok(turn) if turn > 0 => {
+                        Ok(Command::History { action: Some(HistoryAction::Restore { turn }) })
+                    }
+                    _ => Err(anyhow::anyhow!("{turn} is not a valid turn number")),
+                },
+                ["list", query @ ..] => Ok(Command::History {
+                    action: Some(HistoryAction::List { query: query.join(" ") }),
+                }),
+                _ => Err(anyhow::anyhow!(
+                    "Usage: /history [restore <turn> | list [<query>]]"
+                )),
+            },
+            "/env" => match parameters.as_slice() {
+                [] => Ok(Command::Env { update: None }),
+                ["set", assignment @ ..] => match assignment.join(" ").split_once('=') {
+                    Some((key, value)) if !key.trim().is_empty() => Ok(Command::Env {
+                        update: Some(EnvUpdate::Set {
+                            key: key.trim().to_string(),
+                            value: value.to_string(),
+                        }),
+                    }),
+                    _ => Err(anyhow::anyhow!("Usage: /env set <KEY>=<VALUE>")),
+                },
+                ["unset", key] => {
+                    Ok(Command::Env { update: Some(EnvUpdate::Unset { key: key.to_string() }) })
+                }
+                _ => Err(anyhow::anyhow!(
+                    "Usage: /env [set <KEY>=<VALUE> | unset <KEY>]"
+                )),
+            },
+            "/compare" => match parameters.as_slice() {
+                [model_a, model_b, prompt @ ..] if !prompt.is_empty() => Ok(Command::Compare {
+                    models: [model_a.to_string(), model_b.to_string()],
+                    prompt: prompt.join(" "),
+                }),
+                _ => Err(anyhow::anyhow!(
+                    "Usage: /compare <model-a> <model-b> <prompt>"
+                )),
+            },
+            "/commit" => Ok(Command::Commit),
+            "/checkpoint" => match parameters.as_slice() {
+                [] => Ok(Command::Checkpoint { action: None }),
+                ["save", name] => Ok(Command::Checkpoint {
+                    action: Some(CheckpointAction::Save { name: name.to_string() }),
+                }),
+                ["restore", name] => Ok(Command::Checkpoint {
+                    action: Some(CheckpointAction::Restore { name: name.to_string() }),
+                }),
+                _ => Err(anyhow::anyhow!(
+                    "Usage: /checkpoint [save <name> | restore <name>]"
+                )),
+            },
+            "/pin" => match parameters.as_slice() {
+                [] => Ok(Command::Pin { action: None }),
+                ["last"] => Ok(Command::Pin { action: Some(PinAction::Last) }),
+                ["file", path] => {
+                    Ok(Command::Pin { action: Some(PinAction::File { path: path.to_string() }) })
+                }
+                ["file", ..] => Err(anyhow::anyhow!("Usage: /pin file <path>")),
+                text => Ok(Command::Pin { action: Some(PinAction::Text { text: text.join(" ") }) }),
+            },
+            "/unpin" => match parameters.as_slice() {
+                [position] => match position.parse::<usize>() {
+                    Ok(position) if position > 0 => Ok(Command::Unpin { position }),
+                    _ => Err(anyhow::anyhow!("{position} is not a valid pin number")),
+                },
+                _ => Err(anyhow::anyhow!("Usage: /unpin <number>")),
+            },
+            "/drop" => match parameters.as_slice() {
+                [] => Ok(Command::Drop { target: None }),
+                [target] => Ok(Command::Drop { target: Some(DropTarget::parse(target)) }),
+                _ => Err(anyhow::anyhow!("Usage: /drop [<number> | <path>]")),
+            },
+            "/lang" => match parameters.as_slice() {
+                [] => Ok(Command::Lang { update: None }),
+                ["reset"] => Ok(Command::Lang { update: Some(LanguageUpdate::Reset) }),
+                language => Ok(Command::Lang {
+                    update: Some(LanguageUpdate::Set { language: language.join(" ") }),
+                }),
+            },
+            "/mcp" => match parameters.as_slice() {
+                [] => Ok(Command::Mcp { toggle: None }),
+                ["enable", server] => Ok(Command::Mcp {
+                    toggle: Some(McpToggle::Enable { server: server.to_string() }),
+                }),
+                ["disable", server] => Ok(Command::Mcp {
+                    toggle: Some(McpToggle::Disable { server: server.to_string() }),
+                }),
+                _ => Err(anyhow::anyhow!(
+                    "Usage: /mcp [enable <server> | disable <server>]"
+                )),
+            },
+            "/share" => match parameters.as_slice() {
+                [] => Ok(Command::Share { strip_files: false }),
+                ["strip"] => Ok(Command::Share { strip_files: true }),
+                _ => Err(anyhow::anyhow!("Usage: /share [strip]")),
+            },
             text => {
+                if let Some(prompt) = self.find_mcp_prompt(text) {
+                    let arguments = prompt.parse_arguments(&parameters)?;
+                    return Ok(Command::McpPrompt { name: prompt.name, arguments });
+                }
                 let parts = text.split_ascii_whitespace().collect::<Vec<&str>>();
 
                 if let Some(command) = parts.first() {
@@ -249,8 +411,11 @@ pub enum Command {
     /// This can be triggered with the '/help' command.
     #[strum(props(usage = "Enable help mode for tool questions"))]
     Help,
-    /// Dumps the current conversation into a json file or html file
-    #[strum(props(usage = "Save conversation as JSON or HTML (use /dump html for HTML format)"))]
+    /// Dumps the current conversation into a json file or html file, or its
+    /// timeline of generations and tool calls with '/dump timeline'
+    #[strum(props(
+        usage = "Save conversation as JSON or HTML (use /dump html for HTML format, /dump timeline for a timeline of the turns)"
+    ))]
     Dump(Option<String>),
     /// Switch or select the active model
     /// This can be triggered with the '/model' command.
@@ -280,6 +445,164 @@ pub enum Command {
     /// Logs out of the current session.
     #[strum(props(usage = "Logout of the current session"))]
     Logout,
+
+    /// Inspects or clears the settings remembered for this workspace.
+    /// This can be triggered with the '/settings' command.
+    #[strum(props(
+        usage = "Show the settings remembered for this workspace (use /settings clear to reset them)"
+    ))]
+    Settings { clear: bool },
+
+    /// Raises the log verbosity for the rest of the session.
+    /// This can be triggered with the '/debug' command.
+    #[strum(props(usage = "Enable trace level logging for the current session"))]
+    Debug,
+
+    /// Writes the exact request that would be sent to the provider to a file
+    /// and summarizes it, or shows what changed in the context between the
+    /// last two requests with '/context diff'. This can be triggered with the
+    /// '/context' command.
+    #[strum(props(
+        usage = "Save the exact request sent to the provider as JSON and show its token breakdown (use /context diff to see what changed since the previous request)"
+    ))]
+    Context { diff: bool },
+    /// Lists the turns that changed the workspace, restores the workspace to
+    /// its state before one of them with '/history restore <turn>', or
+    /// searches the prompts entered in the workspace with '/history list'.
+    #[strum(props(
+        usage = "List the turns that changed files (use /history restore <turn> to revert them, or /history list [<query>] to reuse a previous prompt)"
+    ))]
+    History { action: Option<HistoryAction> },
+    /// Toggles tool use off for the following messages, for plain question
+    /// answering. This can be triggered with the '/notools' command.
+    #[strum(props(usage = "Toggle tool use for the following messages (for pure Q&A)"))]
+    NoTools,
+    /// Toggles the ask mode, in which the following messages are answered with
+    /// a single streamed response, without tools nor agent loop, or asks a
+    /// single question in this mode. This can be triggered with the '/ask'
+    /// command or with Alt+A.
+    #[strum(props(
+        usage = "Toggle the ask mode, answering quick questions without tools (use /ask <question> to ask once, or Alt+A to switch)"
+    ))]
+    Ask { question: Option<String> },
+    /// Lists or changes the environment variables set for the shell commands
+    /// of the conversation. This can be triggered with the '/env' command.
+    #[strum(props(
+        usage = "Show the environment variables of the conversation (use /env set <KEY>=<VALUE> or /env unset <KEY> to change them)"
+    ))]
+    Env { update: Option<EnvUpdate> },
+    /// Answers a prompt with two models without changing the workspace, shows
+    /// the answers side by side and continues with the chosen one. This can be
+    /// triggered with the '/compare' command.
+    #[strum(props(
+        usage = "Compare the answers of two models to a prompt (use /compare <model-a> <model-b> <prompt>)"
+    ))]
+    Compare { models: [String; 2], prompt: String },
+    /// Writes a conventional commit message for the staged changes and commits
+    /// them once confirmed. This can be triggered with the '/commit' command.
+    #[strum(props(usage = "Write a commit message for the staged changes and commit them"))]
+    Commit,
+    /// Lists the checkpoints of the conversation, saves the context and the
+    /// touched files with '/checkpoint save <name>', or brings them back with
+    /// '/checkpoint restore <name>'.
+    #[strum(props(
+        usage = "List the checkpoints (use /checkpoint save <name> or /checkpoint restore <name> to save or restore the conversation and its files)"
+    ))]
+    Checkpoint { action: Option<CheckpointAction> },
+    /// Lists the pinned context items, or pins a message with '/pin <text>',
+    /// the last prompt with '/pin last' or a file with '/pin file <path>'.
+    /// Pins are sent with every request and compaction never removes them.
+    #[strum(props(
+        usage = "List the pinned context (use /pin <text>, /pin last or /pin file <path> to keep a requirement or a file in every request)"
+    ))]
+    Pin { action: Option<PinAction> },
+    /// Removes a pinned context item by its number in the '/pin' list.
+    #[strum(props(usage = "Remove a pinned context item (use /unpin <number>)"))]
+    Unpin { position: usize },
+    /// Lists the messages of the context, or removes one of them with
+    /// '/drop <number>' or the attachments and reads of a file with
+    /// '/drop <path>'. The removal is recorded in the context.
+    #[strum(props(
+        usage = "List the messages of the context (use /drop <number> or /drop <path> to remove a message or an attached file)"
+    ))]
+    Drop { target: Option<DropTarget> },
+    /// Shows or changes the language the agents answer in. This can be
+    /// triggered with the '/lang' command.
+    #[strum(props(
+        usage = "Show the language the agents answer in (use /lang <language> to change it, or /lang reset to go back to the configured one)"
+    ))]
+    Lang { update: Option<LanguageUpdate> },
+    /// Lists the configured MCP servers, or offers or stops offering the tools
+    /// of one of them in the conversation with '/mcp enable <server>' and
+    /// '/mcp disable <server>'.
+    #[strum(props(
+        usage = "List the MCP servers of the conversation (use /mcp enable <server> or /mcp disable <server> to toggle their tools)"
+    ))]
+    Mcp { toggle: Option<McpToggle> },
+    /// Uploads the transcript of the conversation encrypted, with its secrets
+    /// redacted, and prints its link and key. The contents of the files are
+    /// removed with '/share strip'.
+    #[strum(props(
+        usage = "Share the conversation as an encrypted link with its secrets redacted (use /share strip to remove the contents of the files)"
+    ))]
+    Share { strip_files: bool },
+    /// Runs a prompt of an MCP server, sending the messages it returns. This
+    /// can be triggered with '/<server>:<prompt>' followed by its arguments.
+    #[strum(props(usage = "Run a prompt of an MCP server"))]
+    McpPrompt {
+        name: String,
+        arguments: BTreeMap<String, String>,
+    },
+}
+
+/// Operation on the history of the workspace
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HistoryAction {
+    /// Restores the workspace to its state before the turn, starting at 1
+    Restore { turn: usize },
+    /// Lists the previous prompts and commands matching the query, fuzzily
+    List { query: String },
+}
+
+/// Operation on the checkpoints of a conversation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckpointAction {
+    Save { name: String },
+    Restore { name: String },
+}
+
+/// Context item to pin
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PinAction {
+    /// A message, e.g. a key requirement
+    Text { text: String },
+    /// The last prompt of the conversation
+    Last,
+    /// A file, resolved against the working directory
+    File { path: String },
+}
+
+/// Change to the response language of a conversation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LanguageUpdate {
+    /// Answers in the language, e.g. `French`
+    Set { language: String },
+    /// Goes back to the language of the workflow
+    Reset,
+}
+
+/// Change to the MCP servers whose tools a conversation offers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum McpToggle {
+    Enable { server: String },
+    Disable { server: String },
+}
+
+/// Change to the environment variables of a conversation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EnvUpdate {
+    Set { key: String, value: String },
+    Unset { key: String },
 }
 
 impl Command {
@@ -302,6 +625,23 @@ impl Command {
             Command::Agent => "/agent",
             Command::Login => "/login",
             Command::Logout => "/logout",
+            Command::Settings { .. } => "/settings",
+            Command::Debug => "/debug",
+            Command::Context { .. } => "/context",
+            Command::History { .. } => "/history",
+            Command::NoTools => "/notools",
+            Command::Ask { .. } => "/ask",
+            Command::Env { .. } => "/env",
+            Command::Compare { .. } => "/compare",
+            Command::Commit => "/commit",
+            Command::Checkpoint { .. } => "/checkpoint",
+            Command::Pin { .. } => "/pin",
+            Command::Unpin { .. } => "/unpin",
+            Command::Drop { .. } => "/drop",
+            Command::Lang { .. } => "/lang",
+            Command::Mcp { .. } => "/mcp",
+            Command::Share { .. } => "/share",
+            Command::McpPrompt { name, .. } => name,
         }
     }
 
@@ -510,4 +850,393 @@ mod tests {
             "Shell command should not be in default commands"
         );
     }
+
+    #[test]
+    fn test_parse_settings_command() {
+        // Setup
+        let cmd_manager = ForgeCommandManager::default();
+
+        // Execute
+        let show = cmd_manager.parse("/settings").unwrap();
+        let clear = cmd_manager.parse("/settings clear").unwrap();
+
+        // Verify
+        assert_eq!(show, Command::Settings { clear: false });
+        assert_eq!(clear, Command::Settings { clear: true });
+    }
+
+    #[test]
+    fn test_parse_debug_command() {
+        // Setup
+        let cmd_manager = ForgeCommandManager::default();
+
+        // Execute
+        let actual = cmd_manager.parse("/debug").unwrap();
+
+        // Verify
+        assert_eq!(actual, Command::Debug);
+    }
+
+    #[test]
+    fn test_parse_notools_command() {
+        // Setup
+        let cmd_manager = ForgeCommandManager::default();
+
+        // Execute
+        let actual = cmd_manager.parse("/notools").unwrap();
+
+        // Verify
+        assert_eq!(actual, Command::NoTools);
+    }
+
+    #[test]
+    fn test_parse_ask_command() {
+        let cmd_manager = ForgeCommandManager::default();
+
+        let actual = (
+            cmd_manager.parse("/ask").unwrap(),
+            cmd_manager.parse("/ask what is a  trait object?").unwrap(),
+        );
+
+        let expected = (
+            Command::Ask { question: None },
+            Command::Ask { question: Some("what is a trait object?".to_string()) },
+        );
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_context_command() {
+        // Setup
+        let cmd_manager = ForgeCommandManager::default();
+
+        // Execute
+        let actual = cmd_manager.parse("/context").unwrap();
+
+        // Verify
+        assert_eq!(actual, Command::Context { diff: false });
+    }
+
+    #[test]
+    fn test_parse_context_diff_command() {
+        // Setup
+        let cmd_manager = ForgeCommandManager::default();
+
+        // Execute
+        let actual = cmd_manager.parse("/context diff").unwrap();
+
+        // Verify
+        assert_eq!(actual, Command::Context { diff: true });
+    }
+
+    #[test]
+    fn test_parse_history_command() {
+        // Setup
+        let cmd_manager = ForgeCommandManager::default();
+
+        // Execute
+        let actual = (
+            cmd_manager.parse("/history").unwrap(),
+            cmd_manager.parse("/history restore 2").unwrap(),
+            cmd_manager.parse("/history restore 0").is_err(),
+            cmd_manager.parse("/history list fix tests").unwrap(),
+        );
+
+        // Verify
+        let expected = (
+            Command::History { action: None },
+            Command::History { action: Some(HistoryAction::Restore { turn: 2 }) },
+            true,
+            Command::History {
+                action: Some(HistoryAction::List { query: "fix tests".to_string() }),
+            },
+        );
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_dump_command() {
+        // Setup
+        let cmd_manager = ForgeCommandManager::default();
+
+        // Execute
+        let actual = (
+            cmd_manager.parse("/dump").unwrap(),
+            cmd_manager.parse("/dump html").unwrap(),
+            cmd_manager.parse("/dump timeline").unwrap(),
+        );
+
+        // Verify
+        let expected = (
+            Command::Dump(None),
+            Command::Dump(Some("html".to_string())),
+            Command::Dump(Some("timeline".to_string())),
+        );
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_env_command() {
+        // Setup
+        let cmd_manager = ForgeCommandManager::default();
+
+        // Execute
+        let actual = (
+            cmd_manager.parse("/env").unwrap(),
+            cmd_manager
+                .parse("/env set DATABASE_URL=postgres://localhost/test?a=b")
+                .unwrap(),
+            cmd_manager.parse("/env unset DATABASE_URL").unwrap(),
+            cmd_manager.parse("/env set DATABASE_URL").is_err(),
+        );
+
+        // Verify
+        let expected = (
+            Command::Env { update: None },
+            Command::Env {
+                update: Some(EnvUpdate::Set {
+                    key: "DATABASE_URL".to_string(),
+                    value: "postgres://localhost/test?a=b".to_string(),
+                }),
+            },
+            Command::Env {
+                update: Some(EnvUpdate::Unset { key: "DATABASE_URL".to_string() }),
+            },
+            true,
+        );
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_checkpoint_command() {
+        // Setup
+        let cmd_manager = ForgeCommandManager::default();
+
+        // Execute
+        let actual = (
+            cmd_manager.parse("/checkpoint").unwrap(),
+            cmd_manager
+                .parse("/checkpoint save before-refactor")
+                .unwrap(),
+            cmd_manager
+                .parse("/checkpoint restore before-refactor")
+                .unwrap(),
+            cmd_manager.parse("/checkpoint restore").is_err(),
+        );
+
+        // Verify
+        let expected = (
+            Command::Checkpoint { action: None },
+            Command::Checkpoint {
+                action: Some(CheckpointAction::Save { name: "before-refactor".to_string() }),
+            },
+            Command::Checkpoint {
+                action: Some(CheckpointAction::Restore { name: "before-refactor".to_string() }),
+            },
+            true,
+        );
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_pin_command() {
+        // Setup
+        let cmd_manager = ForgeCommandManager::default();
+
+        // Execute
+        let actual = (
+            cmd_manager.parse("/pin").unwrap(),
+            cmd_manager.parse("/pin Keep the API stable").unwrap(),
+            cmd_manager.parse("/pin last").unwrap(),
+            cmd_manager.parse("/pin file src/api.rs").unwrap(),
+            cmd_manager.parse("/unpin 2").unwrap(),
+            cmd_manager.parse("/unpin 0").is_err(),
+        );
+
+        // Verify
+        let expected = (
+            Command::Pin { action: None },
+            Command::Pin {
+                action: Some(PinAction::Text { text: "Keep the API stable".to_string() }),
+            },
+            Command::Pin { action: Some(PinAction::Last) },
+            Command::Pin {
+                action: Some(PinAction::File { path: "src/api.rs".to_string() }),
+            },
+            Command::Unpin { position: 2 },
+            true,
+        );
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_drop_command() {
+        // Setup
+        let cmd_manager = ForgeCommandManager::default();
+
+        // Execute
+        let actual = (
+            cmd_manager.parse("/drop").unwrap(),
+            cmd_manager.parse("/drop 3").unwrap(),
+            cmd_manager.parse("/drop assets/dump.sql").unwrap(),
+            cmd_manager.parse("/drop 3 4").is_err(),
+        );
+
+        // Verify
+        let expected = (
+            Command::Drop { target: None },
+            Command::Drop { target: Some(DropTarget::Message(3)) },
+            Command::Drop {
+                target: Some(DropTarget::Path("assets/dump.sql".to_string())),
+            },
+            true,
+        );
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_lang_command() {
+        // Setup
+        let cmd_manager = ForgeCommandManager::default();
+
+        // Execute
+        let actual = (
+            cmd_manager.parse("/lang").unwrap(),
+            cmd_manager.parse("/lang Brazilian Portuguese").unwrap(),
+            cmd_manager.parse("/lang reset").unwrap(),
+        );
+
+        // Verify
+        let expected = (
+            Command::Lang { update: None },
+            Command::Lang {
+                update: Some(LanguageUpdate::Set { language: "Brazilian Portuguese".to_string() }),
+            },
+            Command::Lang { update: Some(LanguageUpdate::Reset) },
+        );
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_mcp_command() {
+        // Setup
+        let cmd_manager = ForgeCommandManager::default();
+
+        // Execute
+        let actual = (
+            cmd_manager.parse("/mcp").unwrap(),
+            cmd_manager.parse("/mcp enable github").unwrap(),
+            cmd_manager.parse("/mcp disable github").unwrap(),
+            cmd_manager.parse("/mcp disable").is_err(),
+        );
+
+        // Verify
+        let expected = (
+            Command::Mcp { toggle: None },
+            Command::Mcp {
+                toggle: Some(McpToggle::Enable { server: "github".to_string() }),
+            },
+            Command::Mcp {
+                toggle: Some(McpToggle::Disable { server: "github".to_string() }),
+            },
+            true,
+        );
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_compare_command() {
+        // Setup
+        let cmd_manager = ForgeCommandManager::default();
+
+        // Execute
+        let actual = (
+            cmd_manager
+                .parse("/compare gpt-4.1 claude-sonnet-4 Why is the build slow?")
+                .unwrap(),
+            cmd_manager
+                .parse("/compare gpt-4.1 claude-sonnet-4")
+                .is_err(),
+        );
+
+        // Verify
+        let expected = (
+            Command::Compare {
+                models: ["gpt-4.1".to_string(), "claude-sonnet-4".to_string()],
+                prompt: "Why is the build slow?".to_string(),
+            },
+            true,
+        );
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_commit_command() {
+        // Setup
+        let cmd_manager = ForgeCommandManager::default();
+
+        // Execute
+        let actual = cmd_manager.parse("/commit").unwrap();
+
+        // Verify
+        assert_eq!(actual, Command::Commit);
+    }
+
+    #[test]
+    fn test_parse_share_command() {
+        // Setup
+        let cmd_manager = ForgeCommandManager::default();
+
+        // Execute
+        let actual = (
+            cmd_manager.parse("/share").unwrap(),
+            cmd_manager.parse("/share strip").unwrap(),
+            cmd_manager.parse("/share all").is_err(),
+        );
+
+        // Verify
+        let expected = (
+            Command::Share { strip_files: false },
+            Command::Share { strip_files: true },
+            true,
+        );
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_mcp_prompt_command() {
+        // Setup
+        let cmd_manager = ForgeCommandManager::default();
+        cmd_manager.register_mcp_prompts(vec![McpPrompt {
+            name: "github:review".to_string(),
+            description: Some("Reviews a pull request".to_string()),
+            arguments: vec![forge_api::McpPromptArgument {
+                name: "pr".to_string(),
+                description: None,
+                required: true,
+            }],
+        }]);
+
+        // Execute
+        let actual = (
+            cmd_manager.parse("/github:review 42").unwrap(),
+            cmd_manager.parse("/github:review").is_err(),
+            cmd_manager.parse("/github:other").is_err(),
+        );
+
+        // Verify
+        let expected = (
+            Command::McpPrompt {
+                name: "github:review".to_string(),
+                arguments: BTreeMap::from([("pr".to_string(), "42".to_string())]),
+            },
+            true,
+            true,
+        );
+        assert_eq!(actual, expected);
+        assert!(cmd_manager
+            .list()
+            .iter()
+            .any(|command| command.name == "/github:review"));
+    }
 }
@@ -0,0 +1,18 @@
+use notify_rust::Notification;
+
+/// Shows a desktop notification without waiting for it. Failures are only
+/// logged, e.g. when no notification server is running.
+pub fn notify(summary: impl Into<String>, body: impl Into<String>) {
+    let summary = summary.into();
+    let body = body.into();
+    tokio::task::spawn_blocking(move || {
+        if let Err(error) = Notification::new()
+            .appname("Forge")
+            .summary(&summary)
+            .body(&body)
+            .show()
+        {
+            tracing::debug!(error = ?error, "Failed to show a notification");
+        }
+    });
+}
@@ -0,0 +1,295 @@
+use std::fmt::Display;
+use std::io::Write;
+use std::path::Path;
+use std::time::Duration;
+
+use forge_api::{ModelId, Provider, ProviderUrl};
+
+/// Address of a local Ollama server
+const OLLAMA_URL: &str = "http://localhost:11434";
+
+/// OpenAI compatible endpoint of a local Ollama server
+const OLLAMA_OPENAI_URL: &str = "http://localhost:11434/v1/";
+
+const SCHEMA_URL: &str =
+    "https://raw.githubusercontent.com/antinomyhq/forge/refs/heads/main/forge.schema.json";
+
+type ProviderKey = (&'static str, &'static str, fn(&str) -> Provider);
+
+/// Environment variables of the API keys of the providers with the name of
+/// the provider, in the order they are looked up
+const PROVIDER_KEYS: [ProviderKey; 6] = [
+    ("FORGE_KEY", "Forge", Provider::forge),
+    ("OPENROUTER_API_KEY", "OpenRouter", Provider::open_router),
+    ("REQUESTY_API_KEY", "Requesty", Provider::requesty),
+    ("XAI_API_KEY", "xAI", Provider::xai),
+    ("OPENAI_API_KEY", "OpenAI", Provider::openai),
+    ("ANTHROPIC_API_KEY", "Anthropic", Provider::anthropic),
+];
+
+/// Providers whose API key is set, as pairs of the environment variable and
+/// the name of the provider
+pub fn detect_providers(var: impl Fn(&str) -> Option<String>) -> Vec<(&'static str, &'static str)> {
+    PROVIDER_KEYS
+        .into_iter()
+        .filter(|(key, ..)| var(key).is_some_and(|value| !value.trim().is_empty()))
+        .map(|(key, name, _)| (key, name))
+        .collect()
+}
+
+/// Provider configured by the variables saved during the setup, resolved
+/// like the environment is at startup
+pub fn configured_provider(vars: &[(&str, String)]) -> Option<Provider> {
+    let var = |name: &str| {
+        vars.iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value.as_str())
+    };
+    let mut provider = PROVIDER_KEYS
+        .into_iter()
+        .find_map(|(key, _, provider)| var(key).map(provider))?;
+    if let Some(url) = var("OPENAI_URL") {
+        provider.url(ProviderUrl::OpenAI(url.to_string()));
+    } else if let Some(url) = var("ANTHROPIC_URL") {
+        provider.url(ProviderUrl::Anthropic(url.to_string()));
+    }
+    Some(provider)
+}
+
+/// Whether an Ollama server answers on its default port
+pub async fn is_ollama_running() -> bool {
+    let Ok(client) = reqwest::Client::builder()
+        .timeout(Duration::from_secs(1))
+        .build()
+    else {
+        return false;
+    };
+    client
+        .get(format!("{OLLAMA_URL}/api/tags"))
+        .send()
+        .await
+        .is_ok_and(|response| response.status().is_success())
+}
+
+/// Ways to set up a provider when none is configured
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProviderSetup {
+    Login,
+    Ollama,
+    Key {
+        env: &'static str,
+        name: &'static str,
+    },
+}
+
+impl ProviderSetup {
+    /// Setups offered to the user, the local Ollama server only when it runs
+    pub fn options(ollama: bool) -> Vec<Self> {
+        let keys = PROVIDER_KEYS
+            .into_iter()
+            .skip(1)
+            .map(|(env, name, _)| Self::Key { env, name });
+        [Self::Login]
+            .into_iter()
+            .chain(ollama.then_some(Self::Ollama))
+            .chain(keys)
+            .collect()
+    }
+
+    /// Environment variables pointing the OpenAI provider to the local Ollama
+    /// server, which ignores the API key
+    pub fn ollama_env() -> Vec<(&'static str, String)> {
+        vec![
+            ("OPENAI_URL", OLLAMA_OPENAI_URL.to_string()),
+            ("OPENAI_API_KEY", "ollama".to_string()),
+        ]
+    }
+}
+
+impl Display for ProviderSetup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Login => write!(f, "Log in to Forge"),
+            Self::Ollama => write!(f, "Use the local Ollama server"),
+            Self::Key { env, name } => write!(f, "Enter an API key for {name} ({env})"),
+        }
+    }
+}
+
+/// How much the agents may do without asking the user
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ApprovalPolicy {
+    /// The agents ask before removing files and when they need a decision
+    Ask,
+    /// The agents may only change the files of the workspace, after reading
+    /// them
+    Workspace,
+    /// The agents started with `--autonomous` work within a budget
+    Autonomous,
+}
+
+impl ApprovalPolicy {
+    pub const ALL: [Self; 3] = [Self::Ask, Self::Workspace, Self::Autonomous];
+}
+
+impl Display for ApprovalPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ask => write!(f, "Ask before removing files and for decisions"),
+            Self::Workspace => write!(f, "Only change the files of this workspace"),
+            Self::Autonomous => write!(f, "Run autonomously within a budget"),
+        }
+    }
+}
+
+/// Commented forge.yaml of a workspace with its default model and approval
+/// policy
+pub fn render_config(model: &ModelId, policy: ApprovalPolicy, cwd: &Path) -> String {
+    let workspace = yaml_scalar(&format!("{}/**", cwd.display()));
+    let policy = match policy {
+        ApprovalPolicy::Ask => format!(
+            "# The agents ask before removing files and when they need a decision.\n\
+             # Uncomment to restrict the files they may change to this workspace:\n\
+             # permissions:\n\
+             #   write:\n\
+             #   - {workspace}\n"
+        ),
+        ApprovalPolicy::Workspace => format!(
+            "# The agents may only change the files of this workspace, after reading them.\n\
+             read_before_write: true\n\
+             permissions:\n\
+             \x20 write:\n\
+             \x20 - {workspace}\n"
+        ),
+        ApprovalPolicy::Autonomous => {
+            "# Budget of the agents started with `--autonomous`, which don't wait for\n\
+             # approvals and stop once it is spent or when they need a decision.\n\
+             autonomy:\n\
+             \x20 max_minutes: 60\n\
+             \x20 max_requests: 200\n\
+             \x20 report_every: 10\n"
+                .to_string()
+        }
+    };
+
+    format!(
+        "# yaml-language-server: $schema={SCHEMA_URL}\n\
+         # Configuration of forge for this workspace, created by `forge init`.\n\
+         # The schema above documents all the settings.\n\
+         \n\
+         # Default model of the agents, change it in a session with /model\n\
+         model: {model}\n\
+         \n\
+         {policy}\
+         \n\
+         # Rules added to the instructions of all the agents\n\
+         # custom_rules: |\n\
+         #   - Run the tests after changing the code\n\
+         \n\
+         # Commands available in the prompt as /<name>\n\
+         # commands:\n\
+         # - name: check\n\
+         #   description: Runs the linter and the tests\n\
+         #   prompt: Run the linter and the tests, then fix the issues found\n",
+        model = yaml_scalar(model.as_str())
+    )
+}
+
+/// The string as a YAML scalar, quoted and escaped when it needs to be
+fn yaml_scalar(value: &str) -> String {
+    serde_yml::to_string(value)
+        .map(|yaml| yaml.trim_end().to_string())
+        .unwrap_or_else(|_| format!("{value:?}"))
+}
+
+/// Appends the variables to a `.env` file, creating it when missing
+pub fn append_env(path: &Path, vars: &[(&str, String)]) -> std::io::Result<()> {
+    let existing = std::fs::read_to_string(path).unwrap_or_default();
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    if !existing.is_empty() && !existing.ends_with('\n') {
+        writeln!(file)?;
+    }
+    for (key, value) in vars {
+        writeln!(file, "{key}={value}")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use forge_api::Workflow;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_detect_providers() {
+        let fixture = |name: &str| match name {
+            "OPENAI_API_KEY" => Some("sk-test".to_string()),
+            "XAI_API_KEY" => Some(" ".to_string()),
+            "FORGE_KEY" => Some("forge-test".to_string()),
+            _ => None,
+        };
+
+        let actual = detect_providers(fixture);
+
+        let expected = vec![("FORGE_KEY", "Forge"), ("OPENAI_API_KEY", "OpenAI")];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_render_config() {
+        let fixture = ModelId::new("qwen3:8b");
+
+        let actual = ApprovalPolicy::ALL.map(|policy| {
+            let config = render_config(&fixture, policy, Path::new("/project"));
+            let workflow: Workflow = serde_yml::from_str(&config).unwrap();
+            (
+                workflow.model,
+                workflow
+                    .permissions
+                    .and_then(|permissions| permissions.write),
+                workflow.autonomy.and_then(|autonomy| autonomy.max_requests),
+            )
+        });
+
+        let expected = [
+            (Some(fixture.clone()), None, None),
+            (
+                Some(fixture.clone()),
+                Some(vec!["/project/**".to_string()]),
+                None,
+            ),
+            (Some(fixture.clone()), None, Some(200)),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_configured_provider() {
+        let fixture = ProviderSetup::ollama_env();
+
+        let actual = configured_provider(&fixture);
+
+        let mut expected = Provider::openai("ollama");
+        expected.url(ProviderUrl::OpenAI(OLLAMA_OPENAI_URL.to_string()));
+        assert_eq!(actual, Some(expected));
+    }
+
+    #[test]
+    fn test_append_env() {
+        let dir = tempfile::tempdir().unwrap();
+        let fixture = dir.path().join(".env");
+        std::fs::write(&fixture, "RUST_LOG=debug").unwrap();
+
+        append_env(&fixture, &ProviderSetup::ollama_env()).unwrap();
+
+        let actual = std::fs::read_to_string(&fixture).unwrap();
+        let expected =
+            "RUST_LOG=debug\nOPENAI_URL=http://localhost:11434/v1/\nOPENAI_API_KEY=ollama\n";
+        assert_eq!(actual, expected);
+    }
+}
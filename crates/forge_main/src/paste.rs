@@ -0,0 +1,125 @@
+use std::path::{Path, PathBuf};
+
+use crossterm::event::Event;
+use reedline::{EditCommand, EditMode, PromptEditMode, ReedlineEvent, ReedlineRawEvent};
+
+/// Pastes with more lines are saved to a file instead of being put in the
+/// prompt
+const MAX_INLINE_LINES: usize = 40;
+
+/// Pastes with more characters are saved to a file, e.g. a minified JSON on a
+/// single line
+const MAX_INLINE_CHARS: usize = 4000;
+
+/// Number of characters of the first line of a paste quoted in its reference
+const PREVIEW_CHARS: usize = 80;
+
+/// Edit mode saving long bracketed pastes, e.g. a stack trace, to a file and
+/// putting a short reference to it in the prompt instead. The model then reads
+/// the lines it needs from the file rather than getting all of them in the
+/// context.
+pub struct PasteAwareEditMode {
+    inner: Box<dyn EditMode>,
+    dir: PathBuf,
+}
+
+impl PasteAwareEditMode {
+    pub fn new(inner: Box<dyn EditMode>, dir: PathBuf) -> Self {
+        Self { inner, dir }
+    }
+
+    /// Text put in the prompt for the paste
+    fn paste(&self, text: &str) -> String {
+        let text = text.replace("\r\n", "\n").replace('\r', "\n");
+        if !is_large(&text) {
+            return text;
+        }
+
+        match self.save(&text) {
+            Ok(path) => paste_reference(&path, &text),
+            Err(error) => {
+                tracing::warn!(error = ?error, "Failed to save the paste");
+                text
+            }
+        }
+    }
+
+    fn save(&self, text: &str) -> std::io::Result<PathBuf> {
+        std::fs::create_dir_all(&self.dir)?;
+        let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S%3f");
+        let path = self.dir.join(format!("paste-{timestamp}.txt"));
+        std::fs::write(&path, text)?;
+        Ok(path)
+    }
+}
+
+impl EditMode for PasteAwareEditMode {
+    fn parse_event(&mut self, event: ReedlineRawEvent) -> ReedlineEvent {
+        match Event::from(event) {
+            Event::Paste(text) => {
+                ReedlineEvent::Edit(vec![EditCommand::InsertString(self.paste(&text))])
+            }
+            event => match ReedlineRawEvent::try_from(event) {
+                Ok(event) => self.inner.parse_event(event),
+                Err(_) => ReedlineEvent::None,
+            },
+        }
+    }
+
+    fn edit_mode(&self) -> PromptEditMode {
+        self.inner.edit_mode()
+    }
+}
+
+/// Whether the paste is too long to be put in the prompt
+fn is_large(text: &str) -> bool {
+    text.lines().count() > MAX_INLINE_LINES || text.chars().count() > MAX_INLINE_CHARS
+}
+
+/// Reference to a paste saved to `path`, with its size and first line
+fn paste_reference(path: &Path, text: &str) -> String {
+    let first_line = text
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or_default();
+    let mut preview = first_line.chars().take(PREVIEW_CHARS).collect::<String>();
+    if preview.len() < first_line.len() {
+        preview.push('…');
+    }
+
+    format!(
+        "[Pasted {} lines saved to {}, starting with `{preview}`. Read the line ranges needed from the file.]",
+        text.lines().count(),
+        path.display()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_is_large() {
+        let actual = [
+            is_large("thread 'main' panicked"),
+            is_large(&"at src/main.rs:10\n".repeat(41)),
+            is_large(&"a".repeat(4001)),
+        ];
+
+        let expected = [false, true, true];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_paste_reference() {
+        let fixture = "\nthread 'main' panicked at src/main.rs:10\nstack backtrace:\n";
+
+        let actual = paste_reference(Path::new("/project/.forge/pastes/paste-1.txt"), fixture);
+
+        let expected = "[Pasted 3 lines saved to /project/.forge/pastes/paste-1.txt, starting with `thread 'main' panicked at src/main.rs:10`. Read the line ranges needed from the file.]";
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,35 @@
+use std::path::Path;
+use std::process::Stdio;
+
+use anyhow::{bail, Context, Result};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+/// Runs a command in `cwd` with `input` on its standard input and returns its
+/// standard output
+pub async fn run(cwd: &Path, program: &str, args: &[&str], input: Option<&str>) -> Result<String> {
+    let mut child = Command::new(program)
+        .args(args)
+        .current_dir(cwd)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run {program}, is it installed?"))?;
+
+    let mut stdin = child.stdin.take();
+    if let (Some(stdin), Some(input)) = (stdin.as_mut(), input) {
+        stdin.write_all(input.as_bytes()).await?;
+    }
+    drop(stdin);
+
+    let output = child.wait_with_output().await?;
+    if !output.status.success() {
+        bail!(
+            "{program} {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
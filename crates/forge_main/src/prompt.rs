@@ -6,6 +6,7 @@ use std::process::Command;
 use convert_case::{Case, Casing};
 use derive_setters::Setters;
 use forge_api::{AgentId, ModelId, Usage};
+use forge_display::{colors_enabled, theme};
 use forge_tracker::VERSION;
 use nu_ansi_term::{Color, Style};
 use reedline::{Prompt, PromptHistorySearchStatus};
@@ -21,14 +22,21 @@ pub struct ForgePrompt {
     pub usage: Option<Usage>,
     pub agent_id: AgentId,
     pub model: Option<ModelId>,
+    /// Whether the messages are answered in ask mode, shown instead of the
+    /// agent
+    pub ask: bool,
+    /// Status line rendered from the template of the workflow, replacing the
+    /// default one
+    pub status: Option<String>,
 }
 
 impl Prompt for ForgePrompt {
     fn render_prompt_left(&self) -> Cow<'_, str> {
         // Pre-compute styles to avoid repeated style creation
-        let mode_style = Style::new().fg(Color::White).bold();
-        let folder_style = Style::new().fg(Color::Cyan);
-        let branch_style = Style::new().fg(Color::LightGreen);
+        let theme = theme();
+        let mode_style = bold_style(theme.text);
+        let folder_style = style(theme.highlight);
+        let branch_style = style(theme.success);
 
         // Get current directory
         let current_dir = env::current_dir()
@@ -47,10 +55,14 @@ impl Prompt for ForgePrompt {
         let mut result = String::with_capacity(64); // Pre-allocate a reasonable size
 
         // Build the string step-by-step
+        let mode = match self.ask {
+            true => "ASK".to_string(),
+            false => self.agent_id.as_str().to_case(Case::UpperSnake),
+        };
         write!(
             result,
             "{} {}",
-            mode_style.paint(self.agent_id.as_str().to_case(Case::UpperSnake)),
+            mode_style.paint(mode),
             folder_style.paint(&current_dir)
         )
         .unwrap();
@@ -68,6 +80,11 @@ impl Prompt for ForgePrompt {
     }
 
     fn render_prompt_right(&self) -> Cow<'_, str> {
+        let style = bold_style(theme().muted);
+        if let Some(status) = self.status.as_ref() {
+            return Cow::Owned(style.paint(status).to_string());
+        }
+
         // Use a string buffer with pre-allocation to reduce allocations
         let mut result = String::with_capacity(32);
 
@@ -102,13 +119,7 @@ impl Prompt for ForgePrompt {
         write!(result, "]").unwrap();
 
         // Apply styling once at the end
-        Cow::Owned(
-            Style::new()
-                .bold()
-                .fg(Color::DarkGray)
-                .paint(&result)
-                .to_string(),
-        )
+        Cow::Owned(style.paint(&result).to_string())
     }
 
     fn render_prompt_indicator(&self, _prompt_mode: reedline::PromptEditMode) -> Cow<'_, str> {
@@ -146,8 +157,46 @@ impl Prompt for ForgePrompt {
     }
 }
 
+/// Style of the prompt in the color of the theme, or without color when the
+/// output isn't colored
+fn style(color: colored::Color) -> Style {
+    if !colors_enabled() {
+        return Style::new();
+    }
+
+    let color = match color {
+        colored::Color::Black => Color::Black,
+        colored::Color::Red => Color::Red,
+        colored::Color::Green => Color::Green,
+        colored::Color::Yellow => Color::Yellow,
+        colored::Color::Blue => Color::Blue,
+        colored::Color::Magenta => Color::Purple,
+        colored::Color::Cyan => Color::Cyan,
+        colored::Color::White => Color::White,
+        colored::Color::BrightBlack => Color::DarkGray,
+        colored::Color::BrightRed => Color::LightRed,
+        colored::Color::BrightGreen => Color::LightGreen,
+        colored::Color::BrightYellow => Color::LightYellow,
+        colored::Color::BrightBlue => Color::LightBlue,
+        colored::Color::BrightMagenta => Color::LightPurple,
+        colored::Color::BrightCyan => Color::LightCyan,
+        colored::Color::BrightWhite => Color::LightGray,
+        colored::Color::AnsiColor(value) => Color::Fixed(value),
+        colored::Color::TrueColor { r, g, b } => Color::Rgb(r, g, b),
+    };
+    Style::new().fg(color)
+}
+
+fn bold_style(color: colored::Color) -> Style {
+    if colors_enabled() {
+        style(color).bold()
+    } else {
+        Style::new()
+    }
+}
+
 /// Gets the current git branch name if available
-fn get_git_branch() -> Option<String> {
+pub fn get_git_branch() -> Option<String> {
     // First check if we're in a git repository
     let git_check = Command::new("git")
         .args(["rev-parse", "--is-inside-work-tree"])
@@ -174,6 +223,17 @@ fn get_git_branch() -> Option<String> {
     }
 }
 
+/// Whether the git repository of the current directory has uncommitted
+/// changes, `false` outside of a repository
+pub fn is_git_dirty() -> bool {
+    Command::new("git")
+        .args(["status", "--porcelain"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .is_some_and(|output| !output.stdout.is_empty())
+}
+
 #[cfg(test)]
 mod tests {
     use nu_ansi_term::Style;
@@ -183,7 +243,13 @@ mod tests {
 
     impl Default for ForgePrompt {
         fn default() -> Self {
-            ForgePrompt { usage: None, agent_id: AgentId::default(), model: None }
+            ForgePrompt {
+                usage: None,
+                agent_id: AgentId::default(),
+                model: None,
+                ask: false,
+                status: None,
+            }
         }
     }
 
@@ -198,6 +264,16 @@ mod tests {
         assert!(actual.contains(RIGHT_CHEVRON));
     }
 
+    #[test]
+    fn test_render_prompt_left_in_ask_mode() {
+        let prompt = ForgePrompt { ask: true, ..Default::default() };
+
+        let actual = prompt.render_prompt_left();
+
+        assert!(actual.contains("ASK"));
+        assert!(!actual.contains("FORGE"));
+    }
+
     #[test]
     fn test_render_prompt_left_with_custom_prompt() {
         // Set $PROMPT environment variable temporarily for this test
@@ -309,4 +385,15 @@ mod tests {
         assert!(actual.contains(&VERSION.to_string()));
         assert!(actual.contains("10"));
     }
+
+    #[test]
+    fn test_render_prompt_right_with_status() {
+        let mut prompt = ForgePrompt::default();
+        prompt.status("claude-3 25% main*".to_string());
+
+        let actual = console::strip_ansi_codes(&prompt.render_prompt_right()).to_string();
+
+        let expected = "claude-3 25% main*";
+        assert_eq!(actual, expected);
+    }
 }
@@ -0,0 +1,15 @@
+use forge_domain::{ReleaseNotes, RELEASE_NOTES_TAG};
+
+/// Task asking the agent to rewrite the changes of a release for its users
+pub fn release_notes_task(version: &str, notes: &ReleaseNotes) -> String {
+    format!(
+        "Draft the release notes of {version} from the changes below, grouped by type. Rewrite \
+         each change for the users of the project: tell what changed for them rather than how, \
+         merge the changes that belong together and leave out those that don't affect them, such \
+         as chores and tests. Keep the `###` sections that still have changes in the same order, \
+         along with the pull request numbers and the commit hashes. Read the code when a change \
+         is unclear. Answer with the Markdown of the notes only, without a heading for the \
+         version, within <{RELEASE_NOTES_TAG}> tags.\n\n{}",
+        notes.to_markdown()
+    )
+}
@@ -0,0 +1,98 @@
+use std::path::Path;
+
+use anyhow::Result;
+use forge_domain::FileDiff;
+
+use crate::process::run;
+
+/// Fetches the diff of a GitHub pull request with the GitHub CLI, or the
+/// changes of the current branch since it forked from `base`
+pub async fn fetch_diff(cwd: &Path, pr: Option<u64>, base: &str) -> Result<String> {
+    match pr {
+        Some(pr) => run(cwd, "gh", &["pr", "diff", &pr.to_string()], None).await,
+        None => {
+            let range = format!("{base}...HEAD");
+            run(
+                cwd,
+                "git",
+                &["--no-pager", "diff", "--no-color", &range],
+                None,
+            )
+            .await
+        }
+    }
+}
+
+/// Fetches the head of a GitHub pull request from the repository it was
+/// opened against and returns its commit, so that the files can be read as
+/// they are in the pull request
+pub async fn fetch_pr_head(cwd: &Path, pr: u64) -> Result<String> {
+    let url = run(
+        cwd,
+        "gh",
+        &["repo", "view", "--json", "url", "--jq", ".url"],
+        None,
+    )
+    .await?;
+    let head = format!("refs/pull/{pr}/head");
+    run(cwd, "git", &["fetch", "--quiet", url.trim(), &head], None).await?;
+    let commit = run(cwd, "git", &["rev-parse", "FETCH_HEAD"], None).await?;
+    Ok(commit.trim().to_string())
+}
+
+/// Submits a review, as built by `Review::to_github`, to a pull request of the
+/// repository of `cwd`
+pub async fn submit_review(cwd: &Path, pr: u64, review: &serde_json::Value) -> Result<()> {
+    let endpoint = format!("repos/{{owner}}/{{repo}}/pulls/{pr}/reviews");
+    let payload = serde_json::to_string(review)?;
+    run(
+        cwd,
+        "gh",
+        &["api", &endpoint, "--input", "-"],
+        Some(&payload),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Task given to the reviewer for a file, with its hunks numbered so that the
+/// comments can refer to lines of the new version
+pub fn review_task(file: &FileDiff) -> String {
+    let hunks = file
+        .hunks
+        .iter()
+        .map(|hunk| hunk.numbered())
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        "Review the changes made to {}:\n\n```diff\n{hunks}\n```",
+        file.path
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_review_task() {
+        let fixture = FileDiff::parse_all(concat!(
+            "diff --git a/src/lib.rs b/src/lib.rs\n",
+            "--- a/src/lib.rs\n",
+            "+++ b/src/lib.rs\n",
+            "@@ -1 +1 @@\n",
+            "-mod old;\n",
+            "+mod new;\n",
+        ));
+
+        let actual = review_task(&fixture[0]);
+
+        let expected = concat!(
+            "Review the changes made to src/lib.rs:\n\n",
+            "```diff\n@@ -1 +1 @@\n  -mod old;\n1 +mod new;\n```"
+        );
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,112 @@
+use anyhow::Context;
+use base64::prelude::{Engine, BASE64_STANDARD, BASE64_URL_SAFE_NO_PAD};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+
+/// Prefix of the uploaded payloads, identifying their format
+const PAYLOAD_PREFIX: &str = "forge-share-v1:";
+
+/// Encrypts the text with AES-256-GCM and a new random key, returning the
+/// payload to upload and the key, both encoded in base64
+pub fn encrypt(text: &str) -> anyhow::Result<(String, String)> {
+    let rng = SystemRandom::new();
+    let mut key = [0u8; 32];
+    let mut nonce = [0u8; NONCE_LEN];
+    rng.fill(&mut key)
+        .and_then(|_| rng.fill(&mut nonce))
+        .map_err(|_| anyhow::anyhow!("Failed to generate an encryption key"))?;
+
+    let mut data = text.as_bytes().to_vec();
+    cipher(&key)?
+        .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut data)
+        .map_err(|_| anyhow::anyhow!("Failed to encrypt the transcript"))?;
+
+    let payload = [nonce.as_slice(), &data].concat();
+    Ok((
+        format!("{PAYLOAD_PREFIX}{}", BASE64_STANDARD.encode(payload)),
+        BASE64_URL_SAFE_NO_PAD.encode(key),
+    ))
+}
+
+/// Decrypts a payload created by [`encrypt`] with its key
+pub fn decrypt(payload: &str, key: &str) -> anyhow::Result<String> {
+    let payload = payload
+        .trim()
+        .strip_prefix(PAYLOAD_PREFIX)
+        .context("The link doesn't point to a conversation shared by forge")?;
+    let payload = BASE64_STANDARD.decode(payload)?;
+    let key = BASE64_URL_SAFE_NO_PAD
+        .decode(key.trim())
+        .context("The key isn't valid")?;
+    if payload.len() < NONCE_LEN {
+        return Err(anyhow::anyhow!("The shared conversation is truncated"));
+    }
+
+    let (nonce, data) = payload.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce)
+        .map_err(|_| anyhow::anyhow!("The shared conversation is truncated"))?;
+    let mut data = data.to_vec();
+    let text = cipher(&key)?
+        .open_in_place(nonce, Aad::empty(), &mut data)
+        .map_err(|_| anyhow::anyhow!("Failed to decrypt the conversation, check the key"))?;
+    Ok(String::from_utf8(text.to_vec())?)
+}
+
+fn cipher(key: &[u8]) -> anyhow::Result<LessSafeKey> {
+    UnboundKey::new(&AES_256_GCM, key)
+        .map(LessSafeKey::new)
+        .map_err(|_| anyhow::anyhow!("The key isn't valid"))
+}
+
+/// Uploads the payload to the paste service and returns its link
+pub async fn upload(url: &str, payload: String) -> anyhow::Result<String> {
+    let link = reqwest::Client::new()
+        .post(url)
+        .body(payload)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .with_context(|| format!("Failed to upload the conversation to {url}"))?
+        .text()
+        .await?
+        .trim()
+        .to_string();
+    if link.is_empty() {
+        return Err(anyhow::anyhow!("{url} didn't reply with a link"));
+    }
+    Ok(link)
+}
+
+/// Downloads the payload of a shared conversation
+pub async fn download(link: &str) -> anyhow::Result<String> {
+    Ok(reqwest::get(link)
+        .await
+        .and_then(|response| response.error_for_status())
+        .with_context(|| format!("Failed to download {link}"))?
+        .text()
+        .await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_encrypt_decrypt() {
+        let fixture = "# Conversation\n\n## User\n\nWhy does the build fail?\n";
+
+        let (payload, key) = encrypt(fixture).unwrap();
+        let (_, other_key) = encrypt(fixture).unwrap();
+
+        let actual = (
+            payload.contains("build"),
+            decrypt(&payload, &key).unwrap(),
+            decrypt(&payload, &other_key).is_err(),
+        );
+
+        let expected = (false, fixture.to_string(), true);
+        assert_eq!(actual, expected);
+    }
+}
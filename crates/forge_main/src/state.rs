@@ -1,7 +1,8 @@
 use derive_setters::Setters;
-use forge_api::{AgentId, ConversationId, ModelId, Provider, Usage, Workflow};
+use forge_api::{AgentId, ConversationId, ModelId, Notifications, Provider, Usage, Workflow};
 
-use crate::prompt::ForgePrompt;
+use crate::prompt::{get_git_branch, is_git_dirty, ForgePrompt};
+use crate::status_line::StatusLine;
 
 //TODO: UIState and ForgePrompt seem like the same thing and can be merged
 /// State information for the UI
@@ -14,6 +15,20 @@ pub struct UIState {
     pub is_first: bool,
     pub model: Option<ModelId>,
     pub provider: Option<Provider>,
+    /// Whether the following messages are answered without tools
+    pub no_tools: bool,
+    /// Whether the following messages are answered in ask mode, with a single
+    /// response of the model
+    pub ask: bool,
+    /// Template of the status line configured in the workflow
+    pub status_line: Option<String>,
+    /// Maximum number of tokens of the context of the model, when known
+    pub context_length: Option<u64>,
+    /// Number of configured MCP servers
+    pub mcp_servers: usize,
+    /// Desktop notifications configured in the workflow, disabled when not
+    /// set
+    pub notifications: Option<Notifications>,
 }
 
 impl UIState {
@@ -41,16 +56,37 @@ impl UIState {
             model: workflow.model,
             operating_agent,
             provider: Default::default(),
+            no_tools: Default::default(),
+            ask: Default::default(),
+            status_line: workflow.status_line,
+            context_length: Default::default(),
+            mcp_servers: Default::default(),
+            notifications: workflow.notifications,
         }
     }
 }
 
 impl From<UIState> for ForgePrompt {
     fn from(state: UIState) -> Self {
+        let status = state.status_line.as_deref().map(|template| {
+            let uses_git = StatusLine::uses_git(template);
+            StatusLine {
+                model: state.model.clone(),
+                usage: state.usage.clone(),
+                context_length: state.context_length,
+                branch: uses_git.then(get_git_branch).flatten(),
+                dirty: uses_git && is_git_dirty(),
+                mcp_servers: state.mcp_servers,
+            }
+            .render(template)
+        });
+
         ForgePrompt {
             usage: Some(state.usage),
             model: state.model,
             agent_id: state.operating_agent,
+            ask: state.ask,
+            status,
         }
     }
 }
@@ -0,0 +1,151 @@
+use forge_api::{ModelId, Usage};
+use forge_tracker::VERSION;
+
+/// Values of the fields of the status line shown on the right of the prompt
+#[derive(Debug, Clone, Default)]
+pub struct StatusLine {
+    pub model: Option<ModelId>,
+    pub usage: Usage,
+    /// Maximum number of tokens of the context of the model, when known
+    pub context_length: Option<u64>,
+    pub branch: Option<String>,
+    /// Whether the working tree has uncommitted changes
+    pub dirty: bool,
+    /// Number of configured MCP servers
+    pub mcp_servers: usize,
+}
+
+impl StatusLine {
+    /// Whether the template shows the state of the git repository, which is
+    /// only looked up for the templates that need it
+    pub fn uses_git(template: &str) -> bool {
+        template.contains("{branch}") || template.contains("{dirty}")
+    }
+
+    /// Fills the fields of the template, e.g. `{model} {context}`. Unknown
+    /// fields are kept as written and the spaces left by empty fields are
+    /// removed.
+    pub fn render(&self, template: &str) -> String {
+        let mut result = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            result.push_str(&rest[..start]);
+            let field = &rest[start..];
+            match field
+                .find('}')
+                .and_then(|end| self.field(&field[1..end]).map(|value| (value, end)))
+            {
+                Some((value, end)) => {
+                    result.push_str(&value);
+                    rest = &field[end + 1..];
+                }
+                None => {
+                    result.push('{');
+                    rest = &field[1..];
+                }
+            }
+        }
+        result.push_str(rest);
+
+        result.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    fn field(&self, name: &str) -> Option<String> {
+        let value = match name {
+            "version" => VERSION.to_string(),
+            "model" => self
+                .model
+                .as_ref()
+                .map(|model| {
+                    model
+                        .as_str()
+                        .split('/')
+                        .next_back()
+                        .unwrap_or(model.as_str())
+                        .to_string()
+                })
+                .unwrap_or_default(),
+            "tokens" => {
+                if self.usage.estimated_tokens > self.usage.prompt_tokens {
+                    format!("~{}", self.usage.estimated_tokens)
+                } else {
+                    self.usage.prompt_tokens.to_string()
+                }
+            }
+            "context" => self
+                .context_length
+                .filter(|limit| *limit > 0)
+                .map(|limit| {
+                    let tokens = self.usage.prompt_tokens.max(self.usage.estimated_tokens);
+                    format!("{}%", tokens as u64 * 100 / limit)
+                })
+                .unwrap_or_default(),
+            "cost" => self
+                .usage
+                .cost
+                .map(|cost| format!("${cost:.2}"))
+                .unwrap_or_default(),
+            "branch" => self.branch.clone().unwrap_or_default(),
+            "dirty" => if self.dirty { "*" } else { "" }.to_string(),
+            "mcp" => self.mcp_servers.to_string(),
+            _ => return None,
+        };
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn fixture() -> StatusLine {
+        StatusLine {
+            model: Some(ModelId::new("anthropic/claude-3")),
+            usage: Usage {
+                prompt_tokens: 50_000,
+                cost: Some(0.1234),
+                ..Default::default()
+            },
+            context_length: Some(200_000),
+            branch: Some("main".to_string()),
+            dirty: true,
+            mcp_servers: 2,
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let actual = fixture().render("{model} {context} {cost} {branch}{dirty} mcp:{mcp}");
+        let expected = "claude-3 25% $0.12 main* mcp:2";
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_render_empty_fields() {
+        let fixture = StatusLine { usage: Usage::default(), context_length: None, ..fixture() };
+
+        let actual = fixture.render("{context}  {cost} | {tokens}");
+
+        let expected = "| 0";
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_render_unknown_field() {
+        let actual = fixture().render("{foo} {model} {");
+        let expected = "{foo} claude-3 {";
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_uses_git() {
+        let actual = (
+            StatusLine::uses_git("{model} {dirty}"),
+            StatusLine::uses_git("{model} {tokens}"),
+        );
+        let expected = (true, false);
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,93 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use forge_domain::Issue;
+
+use crate::process::run;
+
+/// Fetches an issue of the repository of `cwd` with the GitHub CLI
+pub async fn fetch_issue(cwd: &Path, issue: &str) -> Result<Issue> {
+    let output = run(
+        cwd,
+        "gh",
+        &["issue", "view", issue, "--json", "number,title,body,url"],
+        None,
+    )
+    .await?;
+    serde_json::from_str(&output).with_context(|| format!("Invalid issue: {issue}"))
+}
+
+/// Creates the branch of a task from the current commit and switches to it
+pub async fn create_branch(cwd: &Path, branch: &str) -> Result<()> {
+    run(cwd, "git", &["switch", "--create", branch], None).await?;
+    Ok(())
+}
+
+/// First message of the conversation of a task, with the issue and the files
+/// it refers to attached
+pub fn task_message(issue: &Issue, branch: &str, cwd: &Path) -> String {
+    let mut message = format!(
+        "We're working on issue #{} on the branch {branch}. Read the issue and the code it \
+         refers to, then propose a plan before changing anything.\n\n<issue number=\"{}\" \
+         url=\"{}\">\n# {}\n\n{}\n</issue>",
+        issue.number,
+        issue.number,
+        issue.url,
+        issue.title,
+        issue.body.trim()
+    );
+
+    // Symlinks are resolved so that a file of the repository can't attach one
+    // outside of it
+    let root = cwd.canonicalize().unwrap_or_else(|_| cwd.to_path_buf());
+    let references = issue
+        .code_references()
+        .into_iter()
+        .filter_map(|reference| {
+            let path = cwd.join(&reference.path).canonicalize().ok()?;
+            (path.starts_with(&root) && path.is_file()).then_some((reference, path))
+        })
+        .collect::<Vec<_>>();
+    if !references.is_empty() {
+        message.push_str("\n\nThe issue refers to:");
+    }
+    for (reference, path) in references {
+        message.push_str(&format!("\n- {reference} @[{}]", path.display()));
+    }
+
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_task_message_skips_files_outside_of_workspace() {
+        let outside = tempfile::tempdir().unwrap();
+        std::fs::write(outside.path().join("secret.rs"), "").unwrap();
+        let workspace = tempfile::tempdir().unwrap();
+        std::fs::create_dir(workspace.path().join("src")).unwrap();
+        std::fs::write(workspace.path().join("src/lib.rs"), "").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(outside.path(), workspace.path().join("linked")).unwrap();
+        let fixture = Issue {
+            number: 7,
+            title: "Leak".to_string(),
+            body: "See `src/lib.rs` and `linked/secret.rs`.".to_string(),
+            url: "https://github.com/acme/cache/issues/7".to_string(),
+        };
+
+        let message = task_message(&fixture, "7-leak", workspace.path());
+
+        let lib = workspace.path().canonicalize().unwrap().join("src/lib.rs");
+        let actual = (
+            message.ends_with(&format!("- src/lib.rs @[{}]", lib.display())),
+            message.contains("secret.rs @["),
+        );
+        let expected = (true, false);
+        assert_eq!(actual, expected);
+    }
+}
@@ -1,4 +1,15 @@
-use forge_api::ToolDefinition;
+use forge_api::{ToolCallFull, ToolDefinition};
+
+/// Arguments describing what a tool works on, the first one present is shown
+/// next to the tool while it runs
+const KEY_ARGUMENTS: [&str; 5] = ["command", "path", "url", "regex", "query"];
+
+/// Number of characters of the key argument shown while a tool runs
+const MAX_ARGUMENT_CHARS: usize = 60;
+
+/// Tools that aren't shown running, because they print their own output or
+/// complete at once
+const SILENT_TOOLS: [&str; 2] = ["forge_tool_process_shell", "forge_tool_attempt_completion"];
 
 /// Formats the list of tools for display in the shell UI, showing only the tool
 /// name as a blue bold heading with numbering for each tool.
@@ -25,3 +36,67 @@ pub fn format_tools(tools: &[ToolDefinition]) -> String {
 
     output
 }
+
+/// Message of the spinner shown while a tool runs, e.g. `fs_read
+/// /project/src/lib.rs`, or `None` for the tools shown otherwise
+pub fn tool_progress(call: &ToolCallFull) -> Option<String> {
+    if SILENT_TOOLS.contains(&call.name.as_str()) {
+        return None;
+    }
+
+    let name = call
+        .name
+        .as_str()
+        .strip_prefix("forge_tool_")
+        .unwrap_or(call.name.as_str());
+    let argument = KEY_ARGUMENTS
+        .iter()
+        .find_map(|key| call.arguments.get(key)?.as_str())
+        .map(|value| value.lines().next().unwrap_or_default().trim());
+
+    Some(match argument {
+        Some(argument) if !argument.is_empty() => {
+            let mut truncated = argument
+                .chars()
+                .take(MAX_ARGUMENT_CHARS)
+                .collect::<String>();
+            if truncated.len() < argument.len() {
+                truncated.push('…');
+            }
+            format!("{name} {truncated}")
+        }
+        _ => name.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use forge_api::ToolName;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_tool_progress() {
+        let fixture = [
+            ToolCallFull::new(ToolName::new("forge_tool_fs_read"))
+                .arguments(json!({"path": "/project/src/lib.rs"})),
+            ToolCallFull::new(ToolName::new("forge_tool_net_fetch"))
+                .arguments(json!({"url": format!("https://example.com/{}", "a".repeat(60))})),
+            ToolCallFull::new(ToolName::new("github_list_issues")).arguments(json!({})),
+            ToolCallFull::new(ToolName::new("forge_tool_process_shell"))
+                .arguments(json!({"command": "cargo test"})),
+        ];
+
+        let actual = fixture.iter().map(tool_progress).collect::<Vec<_>>();
+
+        let expected = vec![
+            Some("fs_read /project/src/lib.rs".to_string()),
+            Some(format!("net_fetch https://example.com/{}…", "a".repeat(40))),
+            Some("github_list_issues".to_string()),
+            None,
+        ];
+        assert_eq!(actual, expected);
+    }
+}
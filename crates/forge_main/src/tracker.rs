@@ -1,4 +1,8 @@
+use std::future::Future;
+
+use forge_api::{ChatResponse, Subscription, TurnEvent};
 use forge_tracker::{EventKind, ToolCallPayload};
+use tokio::runtime::{Handle, RuntimeFlavor};
 
 use crate::TRACKER;
 
@@ -8,14 +12,38 @@ fn dispatch(event: EventKind) {
     tokio::spawn(TRACKER.dispatch(event));
 }
 
+/// Runs the future to completion from synchronous code, e.g. the panic hook,
+/// whichever thread it's called on. Blocking in place is only possible on a
+/// multi-threaded runtime, elsewhere the future runs on a runtime of its own
+/// on another thread since blocking a current-thread runtime or nesting
+/// runtimes panics.
+pub fn block_on<F>(future: F)
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    match Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            tokio::task::block_in_place(|| handle.block_on(future))
+        }
+        _ => {
+            let _ = std::thread::spawn(move || {
+                tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .map(|runtime| runtime.block_on(future))
+            })
+            .join();
+        }
+    }
+}
+
 /// Dispatches an event blockingly
 /// This is useful for events that are not expected to be dispatched in the
 /// background
 fn dispatch_blocking(event: EventKind) {
-    tokio::task::block_in_place(|| {
-        tokio::runtime::Handle::current().block_on(TRACKER.dispatch(event))
-    })
-    .ok();
+    block_on(async move {
+        let _ = TRACKER.dispatch(event).await;
+    });
 }
 
 /// For error events with Debug formatting
@@ -50,3 +78,27 @@ pub fn set_model(model: String) {
 pub fn login(login: String) {
     tokio::spawn(TRACKER.login(login));
 }
+
+/// Tracks tool calls and retries from the turn events published by the API
+pub fn subscribe(mut subscription: Subscription<TurnEvent>) {
+    tokio::spawn(async move {
+        while let Some(event) = subscription.recv().await {
+            match event.response {
+                ChatResponse::ToolCallEnd(toolcall_result) => {
+                    // Only track toolcall name in case of success else track the error.
+                    let mut payload = ToolCallPayload::new(toolcall_result.name.to_string());
+                    if toolcall_result.is_error() {
+                        if let Some(cause) = toolcall_result.output.as_str() {
+                            payload = payload.with_cause(cause.to_string());
+                        }
+                    }
+                    tool_call(payload);
+                }
+                ChatResponse::RetryAttempt { cause, duration: _ } => {
+                    error_string(cause.into_string());
+                }
+                _ => {}
+            }
+        }
+    });
+}
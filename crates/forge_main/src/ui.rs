@@ -1,19 +1,29 @@
 use std::collections::BTreeMap;
 use std::fmt::Display;
+use std::path::Path;
 use std::sync::Arc;
+use std::time::Instant;
 
 use anyhow::{Context, Result};
 use colored::Colorize;
 use convert_case::{Case, Casing};
 use forge_api::{
     AgentId, AppConfig, ChatRequest, ChatResponse, Conversation, ConversationId, Event,
-    InterruptionReason, Model, ModelId, Workflow, API,
+    ForgeClient, InterruptionReason, Model, ModelId, Workflow, API,
+};
+use forge_display::{
+    colors_enabled, init_colors, set_theme, theme, MarkdownFormat, Theme, TitleFormat,
+};
+use forge_domain::{
+    extract_tag_content, prepend_release_notes, resolve_variable, transcript, Autonomy, Batch,
+    BatchReport, BatchStatus, CheckedCitation, CitationStatus, CommitMessage, DropTarget,
+    FileChangeKind, FileDiff, McpConfig, McpImport, McpServerConfig, McpSource, Pin, ProjectMemory,
+    Provider, ReleaseChange, ReleaseNotes, Review, ReviewComment, Scope, SnapshotRetention,
+    ThemeConfig, ThemeName, ToolChoice, PROJECT_MEMORY_TAG, RELEASE_NOTES_TAG,
 };
-use forge_display::{MarkdownFormat, TitleFormat};
-use forge_domain::{McpConfig, McpServerConfig, Provider, Scope};
 use forge_fs::ForgeFS;
 use forge_spinner::SpinnerManager;
-use forge_tracker::ToolCallPayload;
+use forge_tracker::VERSION;
 use inquire::error::InquireError;
 use inquire::ui::{RenderConfig, Styled};
 use inquire::Select;
@@ -22,13 +32,41 @@ use serde::Deserialize;
 use serde_json::Value;
 use tokio_stream::StreamExt;
 
-use crate::cli::{Cli, McpCommand, TopLevelCommand, Transport};
+use crate::batch::run_task;
+use crate::citation::CitationChecker;
+use crate::cli::{
+    BatchArgs, Cli, McpCommand, NewArgs, ReleaseNotesArgs, ReviewArgs, ReviewFormat, SharedArgs,
+    StatsCommand, TaskCommand, TaskStartArgs, TopLevelCommand, Transport, WorktreeCommand,
+};
+use crate::commit::{commit, commit_task};
+use crate::compare::side_by_side;
+use crate::completions::{generate_completions, generate_manpage};
+use crate::doctor::{
+    check_git, check_mcp_server, check_shell, check_writable, unknown_workflow_keys, Check,
+};
+use crate::fingerprint::fingerprint;
+use crate::history::{fuzzy_filter, parse_history};
 use crate::info::Info;
 use crate::input::Console;
-use crate::model::{Command, ForgeCommandManager};
+use crate::memory::memory_task;
+use crate::model::{
+    CheckpointAction, Command, EnvUpdate, ForgeCommandManager, HistoryAction, LanguageUpdate,
+    McpToggle, PinAction,
+};
+use crate::notification::notify;
+use crate::onboarding::{
+    append_env, configured_provider, detect_providers, is_ollama_running, render_config,
+    ApprovalPolicy, ProviderSetup,
+};
+use crate::release_notes::release_notes_task;
+use crate::review::{fetch_diff, fetch_pr_head, review_task, submit_review};
+use crate::share::{decrypt, download, encrypt, upload};
 use crate::state::UIState;
-use crate::update::on_update;
-use crate::{banner, tracker, TRACKER};
+use crate::task::{create_branch, fetch_issue, task_message};
+use crate::tools_display::tool_progress;
+use crate::update::{available_update, on_update, self_update};
+use crate::worktree::WorktreeManager;
+use crate::{banner, crash, tracker, TRACKER};
 
 // Event type constants moved to UI layer
 pub const EVENT_USER_TASK_INIT: &str = "user_task_init";
@@ -61,8 +99,20 @@ pub struct UI<A, F: Fn() -> A> {
     command: Arc<ForgeCommandManager>,
     cli: Cli,
     spinner: SpinnerManager,
-    #[allow(dead_code)] // The guard is kept alive by being held in the struct
-    _guard: forge_tracker::Guard,
+    /// Text put in the input of the next prompt, e.g. a previous prompt picked
+    /// from the history
+    draft: Option<String>,
+    /// Whether a notification was sent during the current turn, which then
+    /// doesn't notify its completion
+    notified: bool,
+    /// Whether the answer being received was printed as it was streamed, so
+    /// that it isn't printed again once complete
+    streamed: bool,
+    /// Streamed part of the answer not printed yet, printed a block at a time
+    /// so that it's rendered as markdown
+    pending: String,
+    // Keeps the logging system alive and allows reconfiguring it
+    log_guard: forge_tracker::Guard,
 }
 
 impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
@@ -82,18 +132,102 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
 
     // Handle creating a new conversation
     async fn on_new(&mut self) -> Result<()> {
+        self.remember_learnings().await;
+        self.end_conversation().await;
         self.api = Arc::new((self.new_api)());
+        tracker::subscribe(self.api.subscribe());
+        crash::subscribe(self.api.subscribe());
         self.init_state(false).await?;
-        banner::display()?;
+        banner::display(None)?;
         self.trace_user();
         Ok(())
     }
 
+    /// Cleans up after the current conversation. Failures are only logged as
+    /// they must not prevent leaving the conversation.
+    async fn end_conversation(&self) {
+        if let Some(conversation_id) = self.state.conversation_id.as_ref() {
+            if let Err(error) = self.api.end_conversation(conversation_id).await {
+                tracing::warn!(error = ?error, "Failed to clean up the conversation");
+            }
+        }
+    }
+
+    /// Offers to remember the learnings of the conversation before it ends.
+    /// Failures are only shown as they must not prevent leaving the
+    /// conversation.
+    async fn remember_learnings(&mut self) {
+        if let Err(error) = self.on_remember().await {
+            tracing::warn!(error = ?error, "Failed to remember the learnings");
+            let _ = self.spinner.stop(None);
+            eprintln!("{}", TitleFormat::error(format!("{error:?}")));
+        }
+    }
+
+    /// Distills the learnings of the conversation in a read-only fork kept in
+    /// memory and adds them to the memory of the project once the user
+    /// confirms them. Only runs when `remember_learnings` is enabled.
+    async fn on_remember(&mut self) -> Result<()> {
+        if self.state.is_first {
+            return Ok(());
+        }
+        let Some(conversation_id) = self.state.conversation_id else {
+            return Ok(());
+        };
+        if !self
+            .active_workflow()
+            .await?
+            .remember_learnings
+            .unwrap_or_default()
+        {
+            return Ok(());
+        }
+        let Some(conversation) = self.api.conversation(&conversation_id).await? else {
+            return Ok(());
+        };
+
+        let memory = self.api.project_memory().await?;
+        let event = self.create_task_event(Some(memory_task(&memory)), EVENT_USER_TASK_UPDATE)?;
+        let mut fork = conversation.fork(&conversation.main_model()?)?;
+        fork.ephemeral = true;
+        self.api.upsert_conversation(fork.clone()).await?;
+
+        self.spinner
+            .start(Some("Distilling the learnings of the conversation"))?;
+        let answer = collect_answer(self.api.as_ref(), ChatRequest::new(event, fork.id)).await;
+        self.spinner.stop(None)?;
+        let answer = answer?;
+        let learnings = ProjectMemory::parse(
+            extract_tag_content(&answer, PROJECT_MEMORY_TAG).unwrap_or_default(),
+        );
+        if learnings.is_empty() {
+            return Ok(());
+        }
+
+        self.writeln(&learnings)?;
+        let confirmed = inquire::Confirm::new("Remember these learnings in .forge/memory.md?")
+            .with_default(true)
+            .with_error_message("Invalid response!")
+            .prompt()
+            .unwrap_or_default();
+        if !confirmed {
+            return Ok(());
+        }
+
+        let added = self.api.remember(learnings).await?;
+        self.writeln(TitleFormat::action(format!(
+            "Remembered {} learning(s) in .forge/memory.md",
+            added.len()
+        )))
+    }
+
     async fn active_workflow(&self) -> Result<Workflow> {
         // Read the current workflow to validate the agent
         let workflow = self.api.read_workflow(self.cli.workflow.as_deref()).await?;
         let mut base_workflow = Workflow::default();
         base_workflow.merge(workflow.clone());
+        self.log_guard
+            .configure(&base_workflow.logging.clone().unwrap_or_default())?;
         Ok(base_workflow)
     }
 
@@ -123,6 +257,11 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
                 );
             })
             .await?;
+        self.api
+            .update_workspace_settings(|settings| {
+                settings.operating_agent = Some(agent.id.clone());
+            })
+            .await?;
 
         self.writeln(TitleFormat::action(format!(
             "Switched to agent {}",
@@ -145,9 +284,14 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
     }
 
     pub fn init(cli: Cli, f: F) -> Result<Self> {
+        init_colors();
+
         // Parse CLI arguments first to get flags
         let api = Arc::new(f());
+        tracker::subscribe(api.subscribe());
+        crash::subscribe(api.subscribe());
         let env = api.environment();
+        crash::init(&env);
         let command = Arc::new(ForgeCommandManager::default());
         Ok(Self {
             state: Default::default(),
@@ -157,14 +301,76 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
             cli,
             command,
             spinner: SpinnerManager::new(),
+            draft: None,
+            notified: false,
+            streamed: false,
+            pending: String::new(),
             markdown: MarkdownFormat::new(),
-            _guard: forge_tracker::init_tracing(env.log_path(), TRACKER.clone())?,
+            log_guard: forge_tracker::init_tracing(env.log_path(), TRACKER.clone())?,
         })
     }
 
-    async fn prompt(&self) -> Result<Command> {
-        // Prompt the user for input
-        self.console.prompt(self.state.clone().into()).await
+    async fn prompt(&mut self) -> Result<Command> {
+        if self.state.status_line.is_some() {
+            self.update_status_line().await;
+        }
+
+        // Prompt the user for input, showing the reminders coming due meanwhile
+        let reminders = self.spawn_reminders();
+        let command = self
+            .console
+            .prompt(self.state.clone().into(), self.draft.take())
+            .await;
+        if let Some(reminders) = reminders {
+            reminders.abort();
+        }
+        command
+    }
+
+    /// Shows the reminders of the conversation above the prompt as they come
+    /// due while the user is idle. They are marked shown so that the next
+    /// request only posts them into the conversation.
+    fn spawn_reminders(&self) -> Option<tokio::task::JoinHandle<()>> {
+        let conversation_id = self.state.conversation_id.clone()?;
+        let api = self.api.clone();
+        let printer = self.console.printer();
+        Some(tokio::spawn(async move {
+            loop {
+                let Ok(Some(mut conversation)) = api.conversation(&conversation_id).await else {
+                    return;
+                };
+                let Some(due) = conversation
+                    .reminders
+                    .iter()
+                    .filter(|reminder| !reminder.shown)
+                    .map(|reminder| reminder.due)
+                    .min()
+                else {
+                    return;
+                };
+                let delay = (due - chrono::Utc::now()).to_std().unwrap_or_default();
+                tokio::time::sleep(delay).await;
+
+                let now = chrono::Utc::now();
+                for reminder in conversation
+                    .reminders
+                    .iter_mut()
+                    .filter(|reminder| !reminder.shown && reminder.due <= now)
+                {
+                    reminder.shown = true;
+                    let text = TitleFormat::action("Reminder")
+                        .sub_title(&reminder.message)
+                        .to_string();
+                    if let Err(error) = printer.print(text) {
+                        tracing::warn!(error = %error, "Failed to show a reminder");
+                    }
+                }
+                if let Err(error) = api.upsert_conversation(conversation).await {
+                    tracing::warn!(error = ?error, "Failed to mark the reminders shown");
+                    return;
+                }
+            }
+        }))
     }
 
     pub async fn run(&mut self) {
@@ -173,6 +379,13 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
             Err(error) => {
                 tracing::error!(error = ?error);
                 eprintln!("{}", TitleFormat::error(format!("{error:?}")));
+                if let Some(dir) = crash::write_report(&format!("{error:?}")) {
+                    eprintln!(
+                        "{}",
+                        TitleFormat::info(format!("Crash report written to {}", dir.display()))
+                    );
+                    crash::submit_report(&dir).await;
+                }
             }
         }
     }
@@ -190,21 +403,66 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
         // Handle direct prompt if provided
         let prompt = self.cli.prompt.clone();
         if let Some(prompt) = prompt {
-            self.on_message(Some(prompt)).await?;
-            return Ok(());
+            let result = self.on_message(Some(prompt)).await;
+            self.end_conversation().await;
+            return result;
         }
 
         // Display the banner in dimmed colors since we're in interactive mode
-        banner::display()?;
+        let workflow = self
+            .api
+            .read_merged(self.cli.workflow.as_deref())
+            .await
+            .unwrap_or_default();
+        banner::display(available_update(workflow.updates.as_ref()).await.as_deref())?;
+        if !self.confirm_workspace_size().await? {
+            return Ok(());
+        }
         self.init_state(true).await?;
         self.trace_user();
 
         // Get initial input from file or prompt
-        let mut command = match &self.cli.command {
+        let command = match &self.cli.command {
             Some(path) => self.console.upload(path).await?,
             None => self.prompt().await?,
         };
 
+        self.interact(command).await
+    }
+
+    /// Warns before a gigantic workspace is walked, e.g. a home directory forge
+    /// was started in by accident, and asks whether to continue. The workspace
+    /// is then walked in sparse mode, listing only its top level.
+    async fn confirm_workspace_size(&mut self) -> Result<bool> {
+        let size = self.api.workspace_size().await?;
+        if !size.is_large() {
+            return Ok(true);
+        }
+
+        let cwd = self.api.environment().cwd;
+        self.writeln(TitleFormat::error(format!(
+            "{} contains at least {} files and directories, it's walked in sparse mode",
+            cwd.display(),
+            size.entries
+        )))?;
+        let confirmed = inquire::Confirm::new("Continue with its top level only?")
+            .with_default(false)
+            .with_error_message("Invalid response!")
+            .prompt()
+            .unwrap_or_default();
+        if confirmed {
+            self.console.set_sparse(true);
+        } else {
+            self.writeln(TitleFormat::info(format!(
+                "Nothing was done, start forge in a project directory instead of {}",
+                cwd.display()
+            )))?;
+        }
+        Ok(confirmed)
+    }
+
+    /// Runs the commands of the user, starting with `command`, until they exit
+    async fn interact(&mut self, mut command: Command) -> Result<()> {
         loop {
             tokio::select! {
                 _ = tokio::signal::ctrl_c() => {
@@ -212,7 +470,11 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
                 }
                 result = self.on_command(command) => {
                     match result {
-                        Ok(exit) => if exit {return Ok(())},
+                        Ok(exit) => if exit {
+                            self.remember_learnings().await;
+                            self.end_conversation().await;
+                            return Ok(())
+                        },
                         Err(error) => {
                             if let Some(conversation_id) = self.state.conversation_id.as_ref() {
                                 if let Some(conversation) = self.api.conversation(conversation_id).await.ok().flatten() {
@@ -237,6 +499,60 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
 
     async fn handle_subcommands(&mut self, subcommand: TopLevelCommand) -> anyhow::Result<()> {
         match subcommand {
+            TopLevelCommand::Init => self.on_init().await?,
+            TopLevelCommand::New(args) => self.on_scaffold(args).await?,
+            TopLevelCommand::Doctor => self.on_doctor().await?,
+            TopLevelCommand::Shared(args) => self.on_shared(args).await?,
+            TopLevelCommand::Completions(args) => {
+                generate_completions(args.shell, &mut std::io::stdout());
+            }
+            TopLevelCommand::Manpage => {
+                generate_manpage(&mut std::io::stdout())?;
+            }
+            TopLevelCommand::Update => {
+                self.spinner.start(Some("Updating"))?;
+                let updated = self_update(self.api.clone()).await;
+                self.spinner.stop(None)?;
+                match updated? {
+                    Some(version) => self.writeln(TitleFormat::action(format!(
+                        "Updated forge to {version}, restart to use it"
+                    )))?,
+                    None => self.writeln(TitleFormat::info("forge is already up to date"))?,
+                }
+            }
+            TopLevelCommand::Usage(args) => {
+                let ledger = self.api.usage().await?;
+                let env = self.api.environment();
+                let today = chrono::Utc::now().date_naive();
+                let since = today - chrono::Days::new(args.days.saturating_sub(1));
+                let workspace = (!args.all).then_some(env.cwd.as_path());
+                let days = ledger.daily(workspace, since);
+                if days.is_empty() {
+                    self.writeln(TitleFormat::info("No usage recorded yet"))?;
+                } else {
+                    self.writeln(Info::from(days.as_slice()))?;
+                }
+                if env.quota.is_enabled() {
+                    self.writeln(Info::from(&env.quota))?;
+                }
+            }
+            TopLevelCommand::Stats(group) => match group.command {
+                StatsCommand::Tools => {
+                    let ledger = self.api.tool_stats().await?;
+                    if ledger.tools.is_empty() {
+                        self.writeln(TitleFormat::info("No tool calls recorded yet"))?;
+                    } else {
+                        self.writeln(Info::from(&ledger))?;
+                    }
+                }
+            },
+            TopLevelCommand::Review(args) => self.on_review(args).await?,
+            TopLevelCommand::ReleaseNotes(args) => self.on_release_notes(args).await?,
+            TopLevelCommand::Task(group) => match group.command {
+                TaskCommand::Start(args) => self.on_task_start(args).await?,
+            },
+            TopLevelCommand::Batch(args) => self.on_batch(args).await?,
+            TopLevelCommand::Worktree(group) => self.on_worktree(group.command).await?,
             TopLevelCommand::Mcp(mcp_command) => match mcp_command.command {
                 McpCommand::Add(add) => {
                     let name = add.name;
@@ -248,6 +564,7 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
                             add.args.clone(),
                             Some(parse_env(add.env.clone())),
                         ),
+                        Transport::Http => McpServerConfig::new_http(add.command_or_url.clone()),
                         Transport::Sse => McpServerConfig::new_sse(add.command_or_url.clone()),
                     };
                     // Command/URL already set in the constructor
@@ -309,6 +626,54 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
                         add_json.name
                     )))?;
                 }
+                McpCommand::Import(import) => {
+                    let source: McpSource = import.from.into();
+                    let path = match import.path {
+                        Some(path) => path,
+                        None => source
+                            .config_paths(&self.api.environment())
+                            .into_iter()
+                            .find(|path| path.exists())
+                            .ok_or_else(|| {
+                                anyhow::anyhow!("No {source} MCP configuration found, use --path")
+                            })?,
+                    };
+                    let content = tokio::fs::read_to_string(&path)
+                        .await
+                        .with_context(|| format!("Failed to read {}", path.display()))?;
+                    let env = self.api.environment();
+                    let imported = McpImport::parse(source, &content, |name| {
+                        resolve_variable(name, &env, |key| std::env::var(key).ok())
+                    })?;
+                    let scope: Scope = import.scope.into();
+                    let mut added = Vec::new();
+                    let mut conflicts = Vec::new();
+                    self.update_mcp_config(&scope, |config| {
+                        for (name, server) in imported.config.mcp_servers {
+                            if config.mcp_servers.contains_key(&name) {
+                                conflicts.push(name);
+                            } else {
+                                config.mcp_servers.insert(name.clone(), server);
+                                added.push(name);
+                            }
+                        }
+                    })
+                    .await?;
+
+                    self.writeln(TitleFormat::info(format!(
+                        "Imported {} MCP server(s) from {}",
+                        added.len(),
+                        path.display()
+                    )))?;
+                    for name in conflicts {
+                        self.writeln(TitleFormat::error(format!(
+                            "{name}: a server with this name is already configured, skipped. Remove it with `forge mcp remove {name}` to import it"
+                        )))?;
+                    }
+                    for unsupported in imported.unsupported {
+                        self.writeln(TitleFormat::error(unsupported))?;
+                    }
+                }
             },
         }
         Ok(())
@@ -324,6 +689,23 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
                 self.spinner.start(Some("Dumping"))?;
                 self.on_dump(format).await?;
             }
+            Command::Context { diff: false } => {
+                self.spinner.start(Some("Inspecting"))?;
+                self.on_context().await?;
+            }
+            Command::Context { diff: true } => {
+                self.on_context_diff().await?;
+            }
+            Command::History { action: Some(HistoryAction::List { query }) } => {
+                self.on_history_list(query).await?;
+            }
+            Command::History { action } => {
+                let restore = match action {
+                    Some(HistoryAction::Restore { turn }) => Some(turn),
+                    _ => None,
+                };
+                self.on_history(restore).await?;
+            }
             Command::New => {
                 self.on_new().await?;
             }
@@ -372,6 +754,11 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
                 self.spinner.start(None)?;
                 self.on_custom_event(event.into()).await?;
             }
+            Command::McpPrompt { name, arguments } => {
+                self.spinner.start(Some("Loading prompt"))?;
+                let content = self.api.get_mcp_prompt(&name, arguments).await?;
+                self.on_message(Some(content)).await?;
+            }
             Command::Model => {
                 self.on_model_selection().await?;
             }
@@ -436,6 +823,73 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
                         .unwrap_or_default(),
                 );
             }
+            Command::Settings { clear } => {
+                self.on_settings(clear).await?;
+            }
+            Command::Env { update } => {
+                self.on_env(update).await?;
+            }
+            Command::Compare { models, prompt } => {
+                self.on_compare(models, prompt).await?;
+            }
+            Command::Commit => {
+                self.on_commit().await?;
+            }
+            Command::Checkpoint { action } => {
+                self.on_checkpoint(action).await?;
+            }
+            Command::Pin { action } => {
+                self.on_pin(action).await?;
+            }
+            Command::Mcp { toggle } => {
+                self.on_mcp_toggle(toggle).await?;
+            }
+            Command::Lang { update } => {
+                self.on_lang(update).await?;
+            }
+            Command::Drop { target } => {
+                self.on_drop(target).await?;
+            }
+            Command::Unpin { position } => {
+                let conversation_id = self.init_conversation().await?;
+                let pin = self.api.unpin(&conversation_id, position).await?;
+                self.writeln(TitleFormat::action("Unpinned").sub_title(pin.to_string()))?;
+            }
+            Command::Share { strip_files } => {
+                self.spinner.start(Some("Sharing"))?;
+                self.on_share(strip_files).await?;
+            }
+            Command::Ask { question: Some(question) } => {
+                let ask = self.state.ask;
+                self.state.ask = true;
+                let result = self.on_message(Some(question)).await;
+                self.state.ask = ask;
+                result?;
+            }
+            Command::Ask { question: None } => {
+                self.state.ask = !self.state.ask;
+                let status = if self.state.ask {
+                    "Ask mode, the messages are answered without tools (Alt+A to switch back)"
+                } else {
+                    "Agent mode"
+                };
+                self.writeln(TitleFormat::action(status))?;
+            }
+            Command::NoTools => {
+                self.state.no_tools = !self.state.no_tools;
+                let status = if self.state.no_tools {
+                    "Tools disabled, the agent will answer without using them"
+                } else {
+                    "Tools enabled"
+                };
+                self.writeln(TitleFormat::action(status))?;
+            }
+            Command::Debug => {
+                let log_path = self.log_guard.debug()?.display().to_string();
+                self.writeln(TitleFormat::info(format!(
+                    "Debug logging enabled for this session, writing to {log_path}"
+                )))?;
+            }
             Command::Logout => {
                 self.spinner.start(Some("Logging out"))?;
                 self.api.logout().await?;
@@ -448,109 +902,900 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
 
         Ok(false)
     }
-    async fn on_compaction(&mut self) -> Result<(), anyhow::Error> {
+    async fn on_settings(&mut self, clear: bool) -> Result<()> {
+        if clear {
+            self.api
+                .update_workspace_settings(|settings| *settings = Default::default())
+                .await?;
+            self.writeln(TitleFormat::info("Workspace settings cleared"))?;
+            return Ok(());
+        }
+
+        let settings = self.api.workspace_settings().await?;
+        if settings.is_empty() {
+            self.writeln(TitleFormat::info(
+                "No settings remembered for this workspace",
+            ))?;
+        } else {
+            self.writeln(Info::from(&settings))?;
+        }
+        Ok(())
+    }
+
+    async fn on_env(&mut self, update: Option<EnvUpdate>) -> Result<()> {
         let conversation_id = self.init_conversation().await?;
-        let compaction_result = self.api.compact_conversation(&conversation_id).await?;
-        let token_reduction = compaction_result.token_reduction_percentage();
-        let message_reduction = compaction_result.message_reduction_percentage();
-        let content = TitleFormat::action(format!("Context size reduced by {token_reduction:.1}% (tokens), {message_reduction:.1}% (messages)"));
-        self.writeln(content)?;
+        let Some(mut conversation) = self.api.conversation(&conversation_id).await? else {
+            return Err(anyhow::anyhow!(
+                "Conversation: {conversation_id} was not found"
+            ));
+        };
+
+        match update {
+            Some(EnvUpdate::Set { key, value }) => {
+                conversation.env.insert(key.clone(), value);
+                self.api.upsert_conversation(conversation).await?;
+                self.writeln(TitleFormat::action(format!(
+                    "{key} set for the shell commands of this conversation"
+                )))?;
+            }
+            Some(EnvUpdate::Unset { key }) => {
+                if conversation.env.remove(&key).is_some() {
+                    self.api.upsert_conversation(conversation).await?;
+                    self.writeln(TitleFormat::action(format!("{key} unset")))?;
+                } else {
+                    self.writeln(TitleFormat::info(format!("{key} is not set")))?;
+                }
+            }
+            None if conversation.env.is_empty() => {
+                self.writeln(TitleFormat::info(
+                    "No environment variables set for this conversation",
+                ))?;
+            }
+            None => {
+                let info = conversation.env.iter().fold(
+                    Info::new().add_title("Environment"),
+                    |info, (key, value)| info.add_key_value(key, value),
+                );
+                self.writeln(info)?;
+            }
+        }
         Ok(())
     }
 
-    /// Select a model from the available models
-    /// Returns Some(ModelId) if a model was selected, or None if selection was
-    /// canceled
-    async fn select_model(&mut self) -> Result<Option<ModelId>> {
-        // Fetch available models
-        let models = self
-            .get_models()
-            .await?
-            .into_iter()
-            .map(CliModel)
-            .collect::<Vec<_>>();
+    /// Reviews each file changed by the diff of a branch or a pull request in
+    /// its own conversation with the reviewer, and outputs the comments
+    async fn on_review(&mut self, args: ReviewArgs) -> Result<()> {
+        let cwd = self.api.environment().cwd;
+        let workflow = self.init_state(false).await?;
 
-        // Create a custom render config with the specified icons
-        let render_config = RenderConfig::default()
-            .with_scroll_up_prefix(Styled::new("⇡"))
-            .with_scroll_down_prefix(Styled::new("⇣"))
-            .with_highlighted_option_prefix(Styled::new("➤"));
+        self.spinner.start(Some("Fetching the diff"))?;
+        let diff = fetch_diff(&cwd, args.pr, &args.base).await;
+        self.spinner.stop(None)?;
+        let files = FileDiff::parse_all(&diff?);
+        if files.is_empty() {
+            return self.writeln(TitleFormat::info("No changes to review"));
+        }
 
-        // Find the index of the current model
-        let starting_cursor = self
-            .state
-            .model
-            .as_ref()
-            .and_then(|current| models.iter().position(|m| &m.0.id == current))
-            .unwrap_or(0);
+        // The files of a pull request are read from a worktree of its head, as
+        // the workspace may be on another branch
+        let comments = match args.pr {
+            Some(pr) => {
+                self.spinner.start(Some("Checking out the pull request"))?;
+                let manager = WorktreeManager::new(&cwd);
+                let checkout = match fetch_pr_head(&cwd, pr).await {
+                    Ok(head) => manager.checkout(&format!("review-{pr}"), &head).await,
+                    Err(error) => Err(error),
+                };
+                self.spinner.stop(None)?;
+                let worktree = checkout?;
+
+                let client = ForgeClient::builder()
+                    .restricted(self.cli.restricted)
+                    .workspace(worktree.path.clone())
+                    .build();
+                let comments = self.review_files(client.api(), &workflow, &files).await;
+                manager.remove(&worktree).await?;
+                comments?
+            }
+            None => {
+                let api = self.api.clone();
+                self.review_files(api.as_ref(), &workflow, &files).await?
+            }
+        };
 
-        // Use inquire to select a model, with the current model pre-selected
-        match Select::new("Select a model:", models)
-            .with_help_message(
-                "Type a model name or use arrow keys to navigate and Enter to select",
-            )
-            .with_render_config(render_config)
-            .with_starting_cursor(starting_cursor)
-            .prompt()
-        {
-            Ok(model) => Ok(Some(model.0.id)),
-            Err(InquireError::OperationCanceled | InquireError::OperationInterrupted) => {
-                // Return None if selection was canceled
-                Ok(None)
+        let review = Review::new(comments);
+        if args.submit {
+            let pr = args
+                .pr
+                .context("A pull request is required to submit a review")?;
+            self.spinner.start(Some("Submitting"))?;
+            let submitted = submit_review(&cwd, pr, &review.to_github(&files)).await;
+            self.spinner.stop(None)?;
+            submitted?;
+            return self.writeln(TitleFormat::action(format!(
+                "Submitted {} comments to pull request #{pr}",
+                review.comments.len()
+            )));
+        }
+
+        let content = match args.format {
+            ReviewFormat::Markdown => review.to_markdown(),
+            ReviewFormat::Github => serde_json::to_string_pretty(&review.to_github(&files))?,
+        };
+        match args.output {
+            Some(path) => {
+                ForgeFS::write(&path, content).await?;
+                self.writeln(TitleFormat::action(format!(
+                    "Review written to {}",
+                    path.display()
+                )))
             }
-            Err(err) => Err(err.into()),
+            None => self.writeln(content),
         }
     }
 
-    // Helper method to handle model selection and update the conversation
-    async fn on_model_selection(&mut self) -> Result<()> {
-        // Select a model
-        let model_option = self.select_model().await?;
-
-        // If no model was selected (user canceled), return early
-        let model = match model_option {
-            Some(model) => model,
-            None => return Ok(()),
-        };
+    /// Reviews each file in its own conversation with the reviewer. The
+    /// comments on a file are skipped when the answer of the reviewer can't
+    /// be read.
+    async fn review_files<B: API>(
+        &mut self,
+        api: &B,
+        workflow: &Workflow,
+        files: &[FileDiff],
+    ) -> Result<Vec<ReviewComment>> {
+        let mut comments = Vec::new();
+        for file in files {
+            self.spinner
+                .start(Some(&format!("Reviewing {}", file.path)))?;
+            let conversation = api.init_conversation(workflow.clone()).await?;
+            let event = Event::new(
+                format!("{}/{EVENT_USER_TASK_INIT}", AgentId::REVIEWER),
+                Some(review_task(file)),
+            );
+            let chat = ChatRequest::new(event, conversation.id);
+            let answer = collect_answer(api, chat).await;
+            self.spinner.stop(None)?;
 
-        self.api
-            .update_workflow(self.cli.workflow.as_deref(), |workflow| {
-                workflow.model = Some(model.clone());
-            })
-            .await?;
+            match ReviewComment::parse_all(&answer?) {
+                Ok(file_comments) => comments.extend(file_comments),
+                Err(error) => self.writeln(TitleFormat::error(format!(
+                    "Skipped the comments on {}: {error}",
+                    file.path
+                )))?,
+            }
+        }
+        Ok(comments)
+    }
 
-        // Get the conversation to update
-        let conversation_id = self.init_conversation().await?;
+    /// Runs the tasks of a batch autonomously, in their own worktrees when they
+    /// run in parallel, and reports their outcome. The changes of the tasks
+    /// run in worktrees are committed to their branches. Exits with a non-zero
+    /// status when a task wasn't completed.
+    async fn on_batch(&mut self, args: BatchArgs) -> Result<()> {
+        let cwd = self.api.environment().cwd;
+        let batch = Batch::parse(&ForgeFS::read_utf8(&args.file).await?)
+            .with_context(|| format!("Invalid batch: {}", args.file.display()))?;
+        let workflow = self.init_state(false).await?;
+        let parallel = args.parallel.max(1);
+        let semaphore = Arc::new(tokio::sync::Semaphore::new(parallel));
+        let manager = WorktreeManager::new(&cwd);
+        if parallel > 1 {
+            for (index, task) in batch.tasks.iter().enumerate() {
+                manager.ensure_available(&task.slug(index)).await?;
+            }
+        }
 
-        if let Some(mut conversation) = self.api.conversation(&conversation_id).await? {
-            // Update the model in the conversation
-            conversation.set_model(&model)?;
+        let mut running = tokio::task::JoinSet::new();
+        let mut worktrees = Vec::new();
+        for (index, task) in batch.tasks.into_iter().enumerate() {
+            let slug = task.slug(index);
+            let worktree = if parallel > 1 {
+                Some(manager.create(&slug).await?)
+            } else {
+                None
+            };
+            let workspace = worktree.as_ref().map(|worktree| worktree.path.clone());
+            worktrees.push(worktree);
+
+            let permit = semaphore.clone().acquire_owned().await?;
+            let restricted = self.cli.restricted;
+            let workflow = workflow.clone();
+            self.writeln(TitleFormat::action(format!("Started {slug}")))?;
+            running.spawn(async move {
+                let result = run_task(restricted, workflow, task, slug, workspace).await;
+                drop(permit);
+                (index, result)
+            });
+        }
 
-            // Upsert the updated conversation
-            self.api.upsert_conversation(conversation).await?;
+        let mut results = Vec::new();
+        while let Some(finished) = running.join_next().await {
+            let (index, result) = finished?;
+            self.writeln(TitleFormat::info(format!(
+                "Finished {}: {}",
+                result.name,
+                result.status.as_str()
+            )))?;
+            results.push((index, result));
+        }
+        results.sort_by_key(|(index, _)| *index);
+
+        for (index, result) in results.iter_mut() {
+            let Some(worktree) = worktrees[*index].as_ref() else {
+                continue;
+            };
+            // A task whose changes can't be committed is reported as failed,
+            // without stopping the others from being committed
+            let message = format!("forge batch: {}", result.name);
+            if let Err(error) = manager.commit(worktree, &message).await {
+                result.status = BatchStatus::Failed;
+                result.reason = Some(format!("{error:#}"));
+                continue;
+            }
+            result.branch = worktree.branch.clone();
+
+            if args.merge && result.status == BatchStatus::Succeeded {
+                match manager.merge(worktree).await {
+                    Ok(()) => {
+                        self.writeln(TitleFormat::action(format!("Merged {}", result.name)))?;
+                        if let Err(error) = manager.remove(worktree).await {
+                            self.writeln(TitleFormat::error(format!("{error:#}")))?;
+                        }
+                    }
+                    Err(error) => self.writeln(TitleFormat::error(format!("{error:#}")))?,
+                }
+            }
+        }
 
-            // Update the UI state with the new model
-            self.update_model(model.clone());
+        let report = BatchReport {
+            tasks: results.into_iter().map(|(_, result)| result).collect(),
+        };
 
-            self.writeln(TitleFormat::action(format!("Switched to model: {model}")))?;
+        let summary = self.markdown.render(&report.to_markdown());
+        self.writeln(summary)?;
+        if let Some(path) = args.output {
+            ForgeFS::write(&path, serde_json::to_string_pretty(&report)?).await?;
+            self.writeln(TitleFormat::action(format!(
+                "Report written to {}",
+                path.display()
+            )))?;
         }
 
+        if !report.is_success() {
+            self.writeln(TitleFormat::error(format!(
+                "{} of {} task(s) not completed",
+                report.count(BatchStatus::Interrupted) + report.count(BatchStatus::Failed),
+                report.tasks.len()
+            )))?;
+            std::process::exit(1);
+        }
         Ok(())
     }
 
-    // Handle dispatching events from the CLI
-    async fn handle_dispatch(&mut self, json: String) -> Result<()> {
-        // Initialize the conversation
-        let conversation_id = self.init_conversation().await?;
-
-        // Parse the JSON to determine the event name and value
-        let event: PartialEvent = serde_json::from_str(&json)?;
+    /// Sets up the provider when none is configured, checks the connection
+    /// and writes the chosen default model and approval policy to a commented
+    /// forge.yaml in the current directory
+    async fn on_init(&mut self) -> Result<()> {
+        let cwd = self.api.environment().cwd;
+        let path = cwd.join("forge.yaml");
+        if path.exists()
+            && !inquire::Confirm::new("forge.yaml already exists, overwrite it?")
+                .with_default(false)
+                .prompt()?
+        {
+            return Ok(());
+        }
 
-        // Create the chat request with the event
-        let chat = ChatRequest::new(event.into(), conversation_id);
+        self.spinner.start(Some("Detecting providers"))?;
+        let logged_in = self.api.app_config().await?.key_info.is_some();
+        let providers = detect_providers(|name| std::env::var(name).ok());
+        let ollama = is_ollama_running().await;
+        self.spinner.stop(None)?;
 
-        self.on_chat(chat).await
-    }
+        let mut info = Info::new().add_title("Providers");
+        if logged_in {
+            info = info.add_key_value("Forge", "logged in");
+        }
+        for (env, name) in &providers {
+            info = info.add_key_value(name, env);
+        }
+        if ollama {
+            info = info.add_key_value("Ollama", "running locally");
+        }
+        self.writeln(info)?;
+
+        let provider = if !logged_in && providers.is_empty() {
+            self.setup_provider(&cwd, ollama).await?
+        } else {
+            None
+        };
+
+        self.spinner.start(Some("Checking the connection"))?;
+        let models = match provider {
+            Some(provider) => {
+                let client = ForgeClient::builder()
+                    .workspace(cwd.clone())
+                    .provider(provider)
+                    .build();
+                client.api().models().await
+            }
+            None => self.api.models().await,
+        };
+        self.spinner.stop(None)?;
+        let models = models.context("Failed to reach the provider, check its API key and URL")?;
+        self.writeln(TitleFormat::action(format!(
+            "Connected, {} models available",
+            models.len()
+        )))?;
+
+        let current = self
+            .api
+            .read_merged(None)
+            .await
+            .ok()
+            .and_then(|workflow| workflow.model);
+        let ids = models.into_iter().map(|model| model.id).collect::<Vec<_>>();
+        let cursor = ids
+            .iter()
+            .position(|id| Some(id) == current.as_ref())
+            .unwrap_or_default();
+        let model = Select::new("Default model", ids)
+            .with_starting_cursor(cursor)
+            .prompt()?;
+        let policy = Select::new("Approval policy", ApprovalPolicy::ALL.to_vec()).prompt()?;
+
+        ForgeFS::write(&path, render_config(&model, policy, &cwd)).await?;
+        self.writeln(TitleFormat::action(format!("Created {}", path.display())))
+    }
+
+    /// Configures a provider, either by logging in to Forge or by saving the
+    /// environment variables of another one to the `.env` of the directory.
+    /// Returns the provider saved to the `.env`, which the running process
+    /// doesn't see.
+    async fn setup_provider(&mut self, cwd: &Path, ollama: bool) -> Result<Option<Provider>> {
+        let setup = Select::new(
+            "No provider is configured, how do you want to connect?",
+            ProviderSetup::options(ollama),
+        )
+        .prompt()?;
+        let vars = match setup {
+            ProviderSetup::Login => return self.login().await.map(|_| None),
+            ProviderSetup::Ollama => ProviderSetup::ollama_env(),
+            ProviderSetup::Key { env, name } => {
+                let key = inquire::Password::new(&format!("{name} API key"))
+                    .without_confirmation()
+                    .prompt()?;
+                vec![(env, key)]
+            }
+        };
+
+        let env_path = cwd.join(".env");
+        append_env(&env_path, &vars)?;
+        self.writeln(TitleFormat::info(format!(
+            "Saved {} to {}, keep it out of version control",
+            vars.iter()
+                .map(|(key, _)| *key)
+                .collect::<Vec<_>>()
+                .join(", "),
+            env_path.display()
+        )))?;
+        Ok(configured_provider(&vars))
+    }
+
+    /// Checks the environment forge runs in and prints the fix of each
+    /// problem found. Exits with a non-zero status when a check failed.
+    async fn on_doctor(&mut self) -> Result<()> {
+        let env = self.api.environment();
+        self.spinner.start(Some("Checking the environment"))?;
+        let mut checks = vec![self.check_provider().await];
+
+        match self.api.read_mcp_config().await {
+            Ok(config) => {
+                for (name, server) in &config.mcp_servers {
+                    checks.push(check_mcp_server(name, server).await);
+                }
+            }
+            Err(error) => checks.push(Check::failed(
+                "MCP servers",
+                format!("{error:#}"),
+                format!(
+                    "Fix the JSON of {} or {}",
+                    env.mcp_user_config().display(),
+                    env.mcp_local_config().display()
+                ),
+            )),
+        }
+
+        checks.push(check_git().await);
+        checks.push(check_shell(&env.shell, self.cli.restricted));
+        checks.push(check_writable(&env.base_path));
+        checks.push(self.check_workflow(&env.cwd).await);
+        self.spinner.stop(None)?;
+
+        for check in &checks {
+            match check {
+                Check::Passed { name, detail } => {
+                    self.writeln(TitleFormat::action(format!("{name}: {detail}")))?
+                }
+                Check::Failed { name, problem, fix } => {
+                    self.writeln(TitleFormat::error(format!("{name}: {problem}")))?;
+                    self.writeln(format!("  {} {fix}", "Fix:".bold()))?;
+                }
+            }
+        }
+
+        let failed = checks.iter().filter(|check| check.is_failed()).count();
+        if failed > 0 {
+            self.writeln(TitleFormat::error(format!(
+                "{failed} of {} check(s) failed",
+                checks.len()
+            )))?;
+            std::process::exit(1);
+        }
+        Ok(())
+    }
+
+    /// Checks that a provider is configured and accepts its API key
+    async fn check_provider(&self) -> Check {
+        let name = "Provider";
+        let provider = match self.api.provider().await {
+            Ok(provider) => provider,
+            Err(_) => {
+                return Check::failed(
+                    name,
+                    "No provider is configured",
+                    "Run `forge init`, or set FORGE_KEY, OPENROUTER_API_KEY, REQUESTY_API_KEY, XAI_API_KEY, OPENAI_API_KEY or ANTHROPIC_API_KEY",
+                )
+            }
+        };
+        let url = provider.to_base_url();
+        match self.api.models().await {
+            Ok(models) => Check::passed(name, format!("{url}, {} models", models.len())),
+            Err(error) => Check::failed(
+                name,
+                format!("{url} is unreachable or rejected the API key: {error:#}"),
+                "Check the network connection and the API key, or run `forge init` to set up another provider",
+            ),
+        }
+    }
+
+    /// Checks that forge.yaml, when there is one, parses and only has keys
+    /// this version of forge knows
+    async fn check_workflow(&self, cwd: &Path) -> Check {
+        let name = "forge.yaml";
+        let Some(path) = cwd
+            .ancestors()
+            .map(|dir| dir.join("forge.yaml"))
+            .find(|path| path.is_file())
+        else {
+            return Check::passed(name, "not found, using the defaults");
+        };
+
+        let keys = match ForgeFS::read_utf8(&path).await {
+            Ok(content) => unknown_workflow_keys(&content),
+            Err(error) => Err(error),
+        };
+        match keys {
+            Ok(keys) if keys.is_empty() => Check::passed(name, path.display().to_string()),
+            Ok(keys) => Check::failed(
+                name,
+                format!(
+                    "{} has settings unknown to forge {VERSION}: {}",
+                    path.display(),
+                    keys.join(", ")
+                ),
+                "Fix the misspelled keys or run `forge update` to get the version they were written for",
+            ),
+            Err(error) => Check::failed(
+                name,
+                format!("{} is invalid: {error:#}", path.display()),
+                "Fix the YAML, the schema referenced at the top of the file documents all the settings",
+            ),
+        }
+    }
+
+    /// Creates the files of a project template, asking for the values of the
+    /// variables that weren't given. Lists the templates when none is given.
+    async fn on_scaffold(&mut self, args: NewArgs) -> Result<()> {
+        let workflow = self.api.read_merged(self.cli.workflow.as_deref()).await?;
+        let templates = workflow.scaffolds.unwrap_or_default();
+        let Some(name) = args.template else {
+            if templates.is_empty() {
+                return self.writeln(TitleFormat::info(
+                    "No project templates are defined in forge.yaml",
+                ));
+            }
+            let mut info = Info::new().add_title("Templates");
+            for template in &templates {
+                info = info.add_key_value(
+                    template.signature(),
+                    template.description.as_deref().unwrap_or_default(),
+                );
+            }
+            return self.writeln(info);
+        };
+        let template = templates
+            .iter()
+            .find(|template| template.name == name)
+            .with_context(|| {
+                format!("Unknown template {name}, run `forge new` to list the templates")
+            })?;
+
+        let mut values = parse_env(args.vars);
+        for variable in &template.variables {
+            if values.contains_key(&variable.name) {
+                continue;
+            }
+            let mut prompt = inquire::Text::new(&variable.name);
+            if let Some(description) = &variable.description {
+                prompt = prompt.with_help_message(description);
+            }
+            if let Some(default) = &variable.default {
+                prompt = prompt.with_default(default);
+            }
+            values.insert(variable.name.clone(), prompt.prompt()?);
+        }
+
+        let root = args.path.unwrap_or(self.api.environment().cwd);
+        let files = template.render(&values)?;
+        if let Some(file) = files.iter().find(|file| root.join(&file.path).exists()) {
+            return Err(anyhow::anyhow!(
+                "{} already exists, nothing was created",
+                root.join(&file.path).display()
+            ));
+        }
+        for file in &files {
+            let path = root.join(&file.path);
+            if let Some(parent) = path.parent() {
+                ForgeFS::create_dir_all(parent).await?;
+            }
+            ForgeFS::write(&path, &file.content).await?;
+            self.writeln(TitleFormat::action(format!("Created {}", path.display())))?;
+        }
+        Ok(())
+    }
+
+    /// Lists, merges or removes the worktrees created for the agents
+    async fn on_worktree(&mut self, command: WorktreeCommand) -> Result<()> {
+        let manager = WorktreeManager::new(self.api.environment().cwd);
+        match command {
+            WorktreeCommand::List => {
+                let worktrees = manager.list().await?;
+                if worktrees.is_empty() {
+                    return self.writeln(TitleFormat::info("No worktrees"));
+                }
+                let mut info = Info::new().add_title("Worktrees");
+                for worktree in worktrees {
+                    let name = worktree.name().unwrap_or_default().to_string();
+                    info = info.add_key_value(name, worktree.path.display());
+                }
+                self.writeln(info)
+            }
+            WorktreeCommand::Merge(args) => {
+                let worktree = manager.find(&args.name).await?;
+                manager.merge(&worktree).await?;
+                manager.remove(&worktree).await?;
+                self.writeln(TitleFormat::action(format!("Merged {}", args.name)))
+            }
+            WorktreeCommand::Remove(args) => {
+                let worktree = manager.find(&args.name).await?;
+                manager.remove(&worktree).await?;
+                self.writeln(TitleFormat::action(format!("Removed {}", args.name)))
+            }
+        }
+    }
+
+    /// Creates the branch of an issue, remembers the issue it was started from
+    /// and continues in interactive mode with the issue as the first message
+    async fn on_task_start(&mut self, args: TaskStartArgs) -> Result<()> {
+        let cwd = self.api.environment().cwd;
+        self.spinner.start(Some("Fetching the issue"))?;
+        let issue = fetch_issue(&cwd, &args.issue).await;
+        self.spinner.stop(None)?;
+        let issue = issue?;
+
+        let branch = issue.branch_name();
+        create_branch(&cwd, &branch).await?;
+        self.api
+            .update_workspace_settings(|settings| settings.start_task(&branch, &issue))
+            .await?;
+        self.writeln(TitleFormat::action(format!(
+            "Started #{} {} on {branch}",
+            issue.number, issue.title
+        )))?;
+
+        self.init_state(true).await?;
+        self.trace_user();
+        self.interact(Command::Message(task_message(&issue, &branch, &cwd)))
+            .await
+    }
+
+    /// Drafts the release notes of the commits between two refs in a read-only
+    /// conversation and adds them to the changelog
+    async fn on_release_notes(&mut self, args: ReleaseNotesArgs) -> Result<()> {
+        let workflow = self.init_state(false).await?;
+        let range = format!("{}..{}", args.from, args.to);
+        let changes = self
+            .api
+            .git_log(&range)
+            .await?
+            .iter()
+            .rev()
+            .map(|commit| ReleaseChange::new(&commit.hash, &commit.summary))
+            .collect();
+        let notes = ReleaseNotes::new(changes);
+        if notes.is_empty() {
+            return self.writeln(TitleFormat::info(format!("No commits in {range}")));
+        }
+
+        let conversation = self.api.init_conversation(workflow).await?;
+        let fork = conversation.fork(&conversation.main_model()?)?;
+        self.api.upsert_conversation(fork.clone()).await?;
+        let task = release_notes_task(&args.version, &notes);
+        let event = self.create_task_event(Some(task), EVENT_USER_TASK_INIT)?;
+
+        self.spinner.start(Some("Drafting the release notes"))?;
+        let answer = collect_answer(self.api.as_ref(), ChatRequest::new(event, fork.id)).await;
+        self.spinner.stop(None)?;
+        let answer = answer?;
+        let draft = extract_tag_content(&answer, RELEASE_NOTES_TAG).unwrap_or(answer.trim());
+        let today = chrono::Local::now().date_naive();
+        let release = format!("## {} - {today}\n\n{draft}", args.version);
+        if args.dry_run {
+            return self.writeln(release);
+        }
+
+        let path = self.api.environment().cwd.join(&args.output);
+        let changelog = if path.exists() {
+            ForgeFS::read_utf8(&path).await?
+        } else {
+            String::new()
+        };
+        self.api
+            .write_file(&path, prepend_release_notes(&changelog, &release))
+            .await?;
+        self.writeln(TitleFormat::action(format!(
+            "Added the release notes of {} to {}",
+            args.version,
+            args.output.display()
+        )))
+    }
+
+    async fn on_compare(&mut self, models: [String; 2], prompt: String) -> Result<()> {
+        let conversation_id = self.init_conversation().await?;
+        let Some(mut conversation) = self.api.conversation(&conversation_id).await? else {
+            return Err(anyhow::anyhow!(
+                "Conversation: {conversation_id} was not found"
+            ));
+        };
+
+        let event_name = if self.state.is_first {
+            EVENT_USER_TASK_INIT
+        } else {
+            EVENT_USER_TASK_UPDATE
+        };
+        let event = self.create_task_event(Some(prompt), event_name)?;
+
+        // Each model answers in a read-only fork so that neither changes the workspace
+        let models = models.map(ModelId::new);
+        let mut forks = Vec::with_capacity(models.len());
+        for model in models.iter() {
+            let fork = conversation.fork(model)?;
+            self.api.upsert_conversation(fork.clone()).await?;
+            forks.push(fork);
+        }
+
+        self.spinner.start(Some("Comparing"))?;
+        let (answer_a, answer_b) = tokio::join!(
+            collect_answer(
+                self.api.as_ref(),
+                ChatRequest::new(event.clone(), forks[0].id)
+            ),
+            collect_answer(self.api.as_ref(), ChatRequest::new(event, forks[1].id)),
+        );
+        self.spinner.stop(None)?;
+
+        let columns = [
+            (models[0].to_string(), answer_a?),
+            (models[1].to_string(), answer_b?),
+        ];
+        let (_, width) = console::Term::stdout().size();
+        self.writeln(side_by_side(&columns, width as usize))?;
+
+        let options = models
+            .iter()
+            .map(ModelId::to_string)
+            .chain(["Neither".to_string()])
+            .collect::<Vec<_>>();
+        let choice = match Select::new("Continue with:", options).prompt() {
+            Ok(choice) => choice,
+            Err(InquireError::OperationCanceled | InquireError::OperationInterrupted) => {
+                return Ok(());
+            }
+            Err(err) => return Err(err.into()),
+        };
+        let Some(index) = models.iter().position(|model| model.to_string() == choice) else {
+            return Ok(());
+        };
+
+        let fork_id = forks[index].id;
+        let Some(fork) = self.api.conversation(&fork_id).await? else {
+            return Err(anyhow::anyhow!("Conversation: {fork_id} was not found"));
+        };
+        conversation.adopt(&fork)?;
+        self.api.upsert_conversation(conversation).await?;
+        self.state.is_first = false;
+        self.update_model(models[index].clone());
+        self.writeln(TitleFormat::action(format!(
+            "Continuing with the answer of {}",
+            models[index]
+        )))?;
+        Ok(())
+    }
+
+    async fn on_commit(&mut self) -> Result<()> {
+        let conversation_id = self.init_conversation().await?;
+        let Some(conversation) = self.api.conversation(&conversation_id).await? else {
+            return Err(anyhow::anyhow!(
+                "Conversation: {conversation_id} was not found"
+            ));
+        };
+
+        let event_name = if self.state.is_first {
+            EVENT_USER_TASK_INIT
+        } else {
+            EVENT_USER_TASK_UPDATE
+        };
+        let task = commit_task(conversation.response_language.as_deref());
+        let event = self.create_task_event(Some(task), event_name)?;
+
+        // The message is written in a read-only fork to keep the conversation as is
+        let fork = conversation.fork(&conversation.main_model()?)?;
+        self.api.upsert_conversation(fork.clone()).await?;
+
+        self.spinner.start(Some("Writing the commit message"))?;
+        let answer = collect_answer(self.api.as_ref(), ChatRequest::new(event, fork.id)).await;
+        self.spinner.stop(None)?;
+        let message = CommitMessage::parse(&answer?)?;
+
+        self.writeln(&message)?;
+        let confirmed = inquire::Confirm::new("Commit the staged changes with this message?")
+            .with_default(true)
+            .with_error_message("Invalid response!")
+            .prompt()
+            .unwrap_or_default();
+        if !confirmed {
+            return Ok(());
+        }
+
+        let summary = commit(&self.api.environment().cwd, &message).await?;
+        self.writeln(TitleFormat::action(summary))
+    }
+
+    async fn on_compaction(&mut self) -> Result<(), anyhow::Error> {
+        let conversation_id = self.init_conversation().await?;
+        let compaction_result = self.api.compact_conversation(&conversation_id).await?;
+        let token_reduction = compaction_result.token_reduction_percentage();
+        let message_reduction = compaction_result.message_reduction_percentage();
+        let content = TitleFormat::action(format!("Context size reduced by {token_reduction:.1}% (tokens), {message_reduction:.1}% (messages)"));
+        self.writeln(content)?;
+        Ok(())
+    }
+
+    /// Select a model from the available models
+    /// Returns Some(ModelId) if a model was selected, or None if selection was
+    /// canceled
+    async fn select_model(&mut self) -> Result<Option<ModelId>> {
+        // Fetch available models
+        let models = self
+            .get_models()
+            .await?
+            .into_iter()
+            .map(CliModel)
+            .collect::<Vec<_>>();
+
+        // Create a custom render config with the specified icons
+        let render_config = RenderConfig::default()
+            .with_scroll_up_prefix(Styled::new("⇡"))
+            .with_scroll_down_prefix(Styled::new("⇣"))
+            .with_highlighted_option_prefix(Styled::new("➤"));
+
+        // Find the index of the current model
+        let starting_cursor = self
+            .state
+            .model
+            .as_ref()
+            .and_then(|current| models.iter().position(|m| &m.0.id == current))
+            .unwrap_or(0);
+
+        // Use inquire to select a model, with the current model pre-selected
+        match Select::new("Select a model:", models)
+            .with_help_message(
+                "Type a model name or use arrow keys to navigate and Enter to select",
+            )
+            .with_render_config(render_config)
+            .with_starting_cursor(starting_cursor)
+            .prompt()
+        {
+            Ok(model) => Ok(Some(model.0.id)),
+            Err(InquireError::OperationCanceled | InquireError::OperationInterrupted) => {
+                // Return None if selection was canceled
+                Ok(None)
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    // Helper method to handle model selection and update the conversation
+    async fn on_model_selection(&mut self) -> Result<()> {
+        // Select a model
+        let model_option = self.select_model().await?;
+
+        // If no model was selected (user canceled), return early
+        let model = match model_option {
+            Some(model) => model,
+            None => return Ok(()),
+        };
+
+        self.api
+            .update_workflow(self.cli.workflow.as_deref(), |workflow| {
+                workflow.model = Some(model.clone());
+            })
+            .await?;
+        self.api
+            .update_workspace_settings(|settings| {
+                settings.model = Some(model.clone());
+            })
+            .await?;
+
+        // Get the conversation to update
+        let conversation_id = self.init_conversation().await?;
+
+        if let Some(mut conversation) = self.api.conversation(&conversation_id).await? {
+            // Update the model in the conversation
+            conversation.set_model(&model)?;
+
+            // Upsert the updated conversation
+            self.api.upsert_conversation(conversation).await?;
+
+            // Update the UI state with the new model
+            self.update_model(model.clone());
+
+            self.writeln(TitleFormat::action(format!("Switched to model: {model}")))?;
+        }
+
+        Ok(())
+    }
+
+    // Handle dispatching events from the CLI
+    async fn handle_dispatch(&mut self, json: String) -> Result<()> {
+        // Initialize the conversation
+        let conversation_id = self.init_conversation().await?;
+
+        // Parse the JSON to determine the event name and value
+        let event: PartialEvent = serde_json::from_str(&json)?;
+
+        // Create the chat request with the event
+        let chat = ChatRequest::new(event.into(), conversation_id);
+
+        self.on_chat(chat).await
+    }
+
+    /// Conversation given with `--conversation`, read from the JSON file at
+    /// `source` or found in the storage when it's the ID of a stored one
+    async fn cli_conversation(&self, source: &Path) -> Result<Conversation> {
+        if !source.is_file() {
+            if let Ok(id) = ConversationId::parse(source.display()) {
+                return self
+                    .api
+                    .conversation(&id)
+                    .await?
+                    .with_context(|| format!("Conversation {id} not found"));
+            }
+        }
+        serde_json::from_str(ForgeFS::read_utf8(source.as_os_str()).await?.as_str())
+            .context("Failed to parse Conversation")
+    }
 
     async fn init_conversation(&mut self) -> Result<ConversationId> {
         match self.state.conversation_id {
@@ -561,20 +1806,25 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
                 // Select a model if workflow doesn't have one
                 let workflow = self.init_state(false).await?;
                 // We need to try and get the conversation ID first before fetching the model
-                let id = if let Some(ref path) = self.cli.conversation {
-                    let conversation: Conversation =
-                        serde_json::from_str(ForgeFS::read_utf8(path.as_os_str()).await?.as_str())
-                            .context("Failed to parse Conversation")?;
+                let id = if let Some(ref source) = self.cli.conversation {
+                    let conversation = self.cli_conversation(source).await?;
 
                     let conversation_id = conversation.id;
                     self.state.conversation_id = Some(conversation_id);
                     self.update_model(conversation.main_model()?);
+                    let conversation = self.with_autonomy(conversation, &workflow);
+                    let conversation = self.with_seed(conversation, &workflow).await?;
                     self.api.upsert_conversation(conversation).await?;
                     conversation_id
                 } else {
-                    let conversation = self.api.init_conversation(workflow).await?;
+                    let conversation = self.api.init_conversation(workflow.clone()).await?;
                     self.state.conversation_id = Some(conversation.id);
                     self.update_model(conversation.main_model()?);
+                    if self.cli.autonomous || self.cli.seed.is_some() {
+                        let conversation = self.with_autonomy(conversation.clone(), &workflow);
+                        let conversation = self.with_seed(conversation, &workflow).await?;
+                        self.api.upsert_conversation(conversation).await?;
+                    }
                     conversation.id
                 };
 
@@ -583,11 +1833,104 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
         }
     }
 
+    /// Lets the agents of the conversation run without interactive approval
+    /// when started with `--autonomous`
+    fn with_autonomy(&self, mut conversation: Conversation, workflow: &Workflow) -> Conversation {
+        if self.cli.autonomous {
+            conversation.autonomy = Some(workflow.autonomy.clone().unwrap_or_default());
+        }
+        conversation
+    }
+
+    /// Makes the conversation reproducible when started with `--seed`,
+    /// recording the fingerprint of the environment. A conversation recorded
+    /// in another environment reports what differs.
+    async fn with_seed(
+        &mut self,
+        mut conversation: Conversation,
+        workflow: &Workflow,
+    ) -> Result<Conversation> {
+        let Some(seed) = self.cli.seed else {
+            return Ok(conversation);
+        };
+        let env = self.api.environment();
+        let current = fingerprint(
+            env.version(),
+            &conversation.main_model()?,
+            workflow,
+            &env.cwd,
+        )
+        .await;
+        if let Some(recorded) = &conversation.fingerprint {
+            for (field, recorded, current) in recorded.divergence(&current) {
+                self.writeln(TitleFormat::error(format!(
+                    "The {field} differs from the recorded run: {recorded}, now {current}"
+                )))?;
+            }
+        }
+        if let Some(recorded) = conversation.seed.filter(|recorded| *recorded != seed) {
+            self.writeln(TitleFormat::error(format!(
+                "The seed differs from the recorded run: {recorded}, now {seed}"
+            )))?;
+        }
+
+        conversation.seed = Some(seed);
+        conversation.fingerprint.get_or_insert(current);
+        Ok(conversation)
+    }
+
+    /// Purges the snapshots exceeding the retention limits right away and then
+    /// periodically for the rest of the session.
+    fn spawn_snapshot_purge(&self, retention: SnapshotRetention) {
+        let api = self.api.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(retention.purge_interval());
+            loop {
+                interval.tick().await;
+                match api.purge_snapshots(&retention).await {
+                    Ok(removed) => tracing::debug!(removed, "Purged snapshots"),
+                    Err(error) => tracing::warn!(error = ?error, "Failed to purge snapshots"),
+                }
+            }
+        });
+    }
+
+    /// Registers the commands of the MCP prompts and resources once the servers
+    /// answered, without holding back the prompt. Commands of the servers that
+    /// can't be reached are left out.
+    fn spawn_mcp_commands(&self) {
+        let api = self.api.clone();
+        let command = self.command.clone();
+        tokio::spawn(async move {
+            let (prompts, resources) = tokio::join!(api.mcp_prompts(), api.mcp_resources());
+            match prompts {
+                Ok(prompts) => command.register_mcp_prompts(prompts),
+                Err(error) => tracing::warn!(error = ?error, "Failed to list the MCP prompts"),
+            }
+            match resources {
+                Ok(resources) => command.register_mcp_resources(resources),
+                Err(error) => tracing::warn!(error = ?error, "Failed to list the MCP resources"),
+            }
+        });
+    }
+
     /// Initialize the state of the UI
     async fn init_state(&mut self, first: bool) -> Result<Workflow> {
         let provider = self.init_provider().await?;
         let mut workflow = self.api.read_workflow(self.cli.workflow.as_deref()).await?;
-        if workflow.model.is_none() {
+
+        // Restore the choices remembered for this workspace
+        let settings = self.api.workspace_settings().await.unwrap_or_default();
+        if let Some(model) = settings.model {
+            workflow.model = Some(model);
+        }
+        if let Some(agent) = settings.operating_agent {
+            workflow
+                .variables
+                .insert("operating_agent".to_string(), Value::from(agent.as_str()));
+        }
+
+        if workflow.model.is_none() && self.cli.model.is_none() {
             workflow.model = Some(
                 self.select_model()
                     .await?
@@ -596,16 +1939,33 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
         }
         let mut base_workflow = Workflow::default();
         base_workflow.merge(workflow.clone());
+        let retention = base_workflow.snapshots.clone().unwrap_or_default();
+        retention.validate()?;
         if first {
             // only call on_update if this is the first initialization
             on_update(self.api.clone(), base_workflow.updates.as_ref()).await;
+            self.spawn_snapshot_purge(retention);
         }
         self.api
             .write_workflow(self.cli.workflow.as_deref(), &workflow)
             .await?;
 
+        // The model passed on the command line only applies to this session
+        if let Some(model) = self.cli.model.as_ref() {
+            workflow.model = Some(ModelId::new(model));
+            base_workflow.model = Some(ModelId::new(model));
+        }
+
+        let theme = base_workflow.theme.clone().unwrap_or_default();
+        set_theme(build_theme(&theme)?);
+        self.markdown = MarkdownFormat::new();
         self.command.register_all(&base_workflow);
-        self.state = UIState::new(base_workflow).provider(provider);
+        self.spawn_mcp_commands();
+        self.console
+            .set_edit_mode(base_workflow.edit_mode.unwrap_or_default());
+        self.state = UIState::new(base_workflow)
+            .provider(provider)
+            .ask(self.cli.ask);
 
         Ok(workflow)
     }
@@ -647,8 +2007,9 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
     async fn on_message(&mut self, content: Option<String>) -> Result<()> {
         let conversation_id = self.init_conversation().await?;
 
-        // Create a ChatRequest with the appropriate event type
-        let event = if self.state.is_first {
+        // Create a ChatRequest with the appropriate event type. Questions in ask
+        // mode don't start the task of the agent.
+        let event = if self.state.is_first && !self.state.ask {
             self.state.is_first = false;
             self.create_task_event(content, EVENT_USER_TASK_INIT)?
         } else {
@@ -656,29 +2017,435 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
         };
 
         // Create the chat request with the event
-        let chat = ChatRequest::new(event, conversation_id);
+        let chat = ChatRequest::new(event, conversation_id).ask(self.state.ask);
 
         self.on_chat(chat).await
     }
 
     async fn on_chat(&mut self, chat: ChatRequest) -> Result<()> {
+        let chat = if self.state.no_tools {
+            chat.tool_choice(ToolChoice::None)
+        } else {
+            chat
+        };
         let mut stream = self.api.chat(chat).await?;
+        let started_at = Instant::now();
+        self.notified = false;
+        self.reset_streamed();
+
+        let result = async {
+            while let Some(message) = stream.next().await {
+                self.handle_chat_response(message?).await?;
+            }
+            Ok::<_, anyhow::Error>(())
+        }
+        .await;
+        // An interrupted answer doesn't leave its stream to the next chat
+        self.reset_streamed();
+        self.spinner.stop(None)?;
+        result?;
+
+        let elapsed = started_at.elapsed();
+        if !self.notified
+            && self
+                .state
+                .notifications
+                .as_ref()
+                .is_some_and(|notifications| notifications.is_turn_notified(elapsed))
+        {
+            notify(
+                "Forge is done",
+                format!("The task completed in {}s", elapsed.as_secs()),
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Notifies that an agent waits for the user, when enabled
+    fn notify_input(&mut self, body: impl Into<String>) {
+        if self
+            .state
+            .notifications
+            .as_ref()
+            .is_some_and(|notifications| notifications.is_input_notified())
+        {
+            notify("Forge needs your input", body);
+            self.notified = true;
+        }
+    }
+
+    /// Writes the request the provider would receive for the conversation to
+    /// a JSON file and shows how its tokens are spent
+    async fn on_context(&mut self) -> Result<()> {
+        let conversation_id = self.init_conversation().await?;
+        let Some(inspection) = self.api.inspect_context(&conversation_id).await? else {
+            self.writeln(TitleFormat::info(
+                "No context yet, send a message to build one",
+            ))?;
+            return Ok(());
+        };
+
+        let timestamp = chrono::Local::now().format("%Y-%m-%d_%H-%M-%S");
+        let path = format!("{timestamp}-context.json");
+        let content = serde_json::to_string_pretty(&inspection.payload)?;
+        tokio::fs::write(path.as_str(), content).await?;
+
+        self.writeln(
+            TitleFormat::action("Provider request dump created".to_string())
+                .sub_title(path.to_string()),
+        )?;
+        self.writeln(Info::from(&inspection))?;
+        Ok(())
+    }
+
+    async fn on_context_diff(&mut self) -> Result<()> {
+        let conversation_id = self.init_conversation().await?;
+        let diff = self
+            .api
+            .conversation(&conversation_id)
+            .await?
+            .and_then(|conversation| conversation.context_diff());
+        match diff {
+            Some(diff) => self.writeln(Info::from(&diff))?,
+            None => self.writeln(TitleFormat::info(
+                "No context diff yet, it needs two requests to the provider",
+            ))?,
+        }
+        Ok(())
+    }
+
+    async fn on_checkpoint(&mut self, action: Option<CheckpointAction>) -> Result<()> {
+        let conversation_id = self.init_conversation().await?;
+
+        match action {
+            Some(CheckpointAction::Save { name }) => {
+                self.spinner.start(Some("Saving"))?;
+                let checkpoint = self.api.save_checkpoint(&conversation_id, &name).await?;
+                self.spinner.stop(None)?;
+                self.writeln(
+                    TitleFormat::action(format!("Checkpoint {name} saved"))
+                        .sub_title(format!("{} file(s) captured", checkpoint.files.len())),
+                )?;
+            }
+            Some(CheckpointAction::Restore { name }) => {
+                self.spinner.start(Some("Restoring"))?;
+                let restore = self.api.restore_checkpoint(&conversation_id, &name).await?;
+                self.spinner.stop(None)?;
+                self.writeln(
+                    TitleFormat::action(format!("Checkpoint {name} restored")).sub_title(format!(
+                        "{} file(s) restored, {} removed",
+                        restore.restored.len(),
+                        restore.removed.len()
+                    )),
+                )?;
+                for path in restore.unrecoverable {
+                    self.writeln(TitleFormat::error(format!("No snapshot to restore {path}")))?;
+                }
+            }
+            None => {
+                let checkpoints = self
+                    .api
+                    .conversation(&conversation_id)
+                    .await?
+                    .map(|conversation| conversation.checkpoints)
+                    .unwrap_or_default();
+                if checkpoints.is_empty() {
+                    self.writeln(TitleFormat::info(
+                        "No checkpoint yet, use /checkpoint save <name> to save one",
+                    ))?;
+                } else {
+                    self.writeln(Info::from(checkpoints.as_slice()))?;
+                }
+            }
+        }
+        Ok(())
+    }
 
-        while let Some(message) = stream.next().await {
-            match message {
-                Ok(message) => self.handle_chat_response(message).await?,
-                Err(err) => {
-                    self.spinner.stop(None)?;
-                    return Err(err);
+    async fn on_mcp_toggle(&mut self, toggle: Option<McpToggle>) -> Result<()> {
+        let conversation_id = self.init_conversation().await?;
+        let Some(mut conversation) = self.api.conversation(&conversation_id).await? else {
+            return Err(anyhow::anyhow!(
+                "Conversation: {conversation_id} was not found"
+            ));
+        };
+        let config = self.api.read_mcp_config().await?;
+
+        let (server, enabled) = match toggle {
+            Some(McpToggle::Enable { server }) => (server, true),
+            Some(McpToggle::Disable { server }) => (server, false),
+            None if config.is_empty() => {
+                self.writeln(TitleFormat::info("No MCP servers configured"))?;
+                return Ok(());
+            }
+            None => {
+                let mut info = Info::new().add_title("MCP Servers");
+                for server in config.keys() {
+                    let status = if conversation.disabled_mcp_servers.contains(server) {
+                        "disabled in this conversation"
+                    } else {
+                        "enabled"
+                    };
+                    info = info.add_key_value(server, status);
                 }
+                self.writeln(info)?;
+                return Ok(());
             }
+        };
+
+        if !config.contains_key(&server) {
+            return Err(anyhow::anyhow!("MCP server not found: {server}"));
         }
+        let status = if enabled { "enabled" } else { "disabled" };
+        if conversation.set_mcp_server_enabled(&server, enabled) {
+            self.api.upsert_conversation(conversation).await?;
+            self.writeln(TitleFormat::action(format!(
+                "MCP server {server} {status} in this conversation"
+            )))?;
+        } else {
+            self.writeln(TitleFormat::info(format!(
+                "MCP server {server} is already {status}"
+            )))?;
+        }
+        Ok(())
+    }
 
-        self.spinner.stop(None)?;
+    async fn on_lang(&mut self, update: Option<LanguageUpdate>) -> Result<()> {
+        let conversation_id = self.init_conversation().await?;
+        let Some(mut conversation) = self.api.conversation(&conversation_id).await? else {
+            return Err(anyhow::anyhow!(
+                "Conversation: {conversation_id} was not found"
+            ));
+        };
+
+        match update {
+            Some(LanguageUpdate::Set { language }) => {
+                conversation.response_language = Some(language.clone());
+                self.api.upsert_conversation(conversation).await?;
+                self.writeln(TitleFormat::action(format!(
+                    "The agents now answer in {language}"
+                )))?;
+            }
+            Some(LanguageUpdate::Reset) => {
+                let workflow = self.api.read_merged(self.cli.workflow.as_deref()).await?;
+                conversation.response_language = workflow.response_language;
+                let language = conversation.response_language.clone();
+                self.api.upsert_conversation(conversation).await?;
+                self.writeln(TitleFormat::action(format!(
+                    "The agents now answer in {}",
+                    language.as_deref().unwrap_or("the language of the user")
+                )))?;
+            }
+            None => {
+                let language = conversation
+                    .response_language
+                    .unwrap_or_else(|| "the language of the user".to_string());
+                self.writeln(TitleFormat::info(format!(
+                    "The agents answer in {language}"
+                )))?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn on_drop(&mut self, target: Option<DropTarget>) -> Result<()> {
+        let conversation_id = self.init_conversation().await?;
+        let Some(target) = target else {
+            let context = self
+                .api
+                .conversation(&conversation_id)
+                .await?
+                .and_then(|conversation| conversation.context);
+            match context {
+                Some(context) => self.writeln(Info::from(&context))?,
+                None => self.writeln(TitleFormat::info(
+                    "No context yet, send a message to build one",
+                ))?,
+            }
+            return Ok(());
+        };
+
+        let dropped = self.api.drop_context(&conversation_id, &target).await?;
+        let tokens = dropped
+            .iter()
+            .map(|dropped| dropped.summary.tokens)
+            .sum::<usize>();
+        self.writeln(
+            TitleFormat::action(format!("Dropped {} message(s)", dropped.len()))
+                .sub_title(format!("~{tokens} tokens freed")),
+        )?;
+        Ok(())
+    }
+
+    async fn on_pin(&mut self, action: Option<PinAction>) -> Result<()> {
+        let conversation_id = self.init_conversation().await?;
+        let conversation = self.api.conversation(&conversation_id).await?;
+
+        let pin = match action {
+            Some(PinAction::Text { text }) => Pin::Text { text },
+            Some(PinAction::File { path }) => Pin::File { path },
+            Some(PinAction::Last) => {
+                match conversation.and_then(|conversation| conversation.last_prompt()) {
+                    Some(text) => Pin::Text { text },
+                    None => {
+                        self.writeln(TitleFormat::info("No prompt to pin yet"))?;
+                        return Ok(());
+                    }
+                }
+            }
+            None => {
+                let pins = conversation
+                    .map(|conversation| conversation.pins)
+                    .unwrap_or_default();
+                if pins.is_empty() {
+                    self.writeln(TitleFormat::info(
+                        "Nothing pinned yet, use /pin <text> or /pin file <path> to pin context",
+                    ))?;
+                } else {
+                    self.writeln(Info::from(pins.as_slice()))?;
+                }
+                return Ok(());
+            }
+        };
+
+        let title = if self.api.pin(&conversation_id, pin.clone()).await? {
+            "Pinned"
+        } else {
+            "Already pinned"
+        };
+        self.writeln(TitleFormat::action(title).sub_title(pin.to_string()))?;
+        Ok(())
+    }
+
+    /// Lets the user pick a previous prompt matching the query, and puts it in
+    /// the input of the next prompt
+    async fn on_history_list(&mut self, query: String) -> Result<()> {
+        let path = self.api.environment().history_path();
+        let content = match tokio::fs::read_to_string(&path).await {
+            Ok(content) => content,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(error) => return Err(error.into()),
+        };
+        let entries = parse_history(&content);
+        let matches = fuzzy_filter(&entries, &query);
+        if matches.is_empty() {
+            self.writeln(TitleFormat::info("No previous prompt matches the query"))?;
+            return Ok(());
+        }
 
+        // Multi-line prompts are listed on a single line
+        let options = matches
+            .iter()
+            .map(|entry| entry.replace('\n', " ↵ "))
+            .collect::<Vec<_>>();
+        match Select::new("Reuse a previous prompt:", options)
+            .with_help_message("Type to filter, Enter to edit the prompt before sending it")
+            .raw_prompt()
+        {
+            Ok(choice) => self.draft = Some(matches[choice.index].clone()),
+            Err(InquireError::OperationCanceled | InquireError::OperationInterrupted) => {}
+            Err(error) => return Err(error.into()),
+        }
         Ok(())
     }
 
+    async fn on_history(&mut self, restore: Option<usize>) -> Result<()> {
+        let conversation_id = self.init_conversation().await?;
+
+        if let Some(turn) = restore {
+            self.spinner.start(Some("Restoring"))?;
+            let plan = self.api.restore_turn(&conversation_id, turn - 1).await?;
+            self.spinner.stop(None)?;
+            self.writeln(
+                TitleFormat::action(format!("Workspace restored to before turn {turn}")).sub_title(
+                    format!(
+                        "{} file(s) restored, {} removed",
+                        plan.restore.len(),
+                        plan.remove.len()
+                    ),
+                ),
+            )?;
+            for path in plan.unrecoverable {
+                self.writeln(TitleFormat::error(format!("No snapshot to restore {path}")))?;
+            }
+            return Ok(());
+        }
+
+        let turns = self
+            .api
+            .conversation(&conversation_id)
+            .await?
+            .map(|conversation| conversation.turns)
+            .unwrap_or_default();
+        if turns.is_empty() {
+            self.writeln(TitleFormat::info("No turn has changed the workspace yet"))?;
+        } else {
+            self.writeln(Info::from(turns.as_slice()))?;
+        }
+        Ok(())
+    }
+
+    /// Uploads the transcript of the conversation, with its secrets redacted,
+    /// encrypted with a new key and prints its link and key
+    async fn on_share(&mut self, strip_files: bool) -> Result<()> {
+        let conversation_id = self
+            .state
+            .conversation_id
+            .context("No conversation initiated yet")?;
+        let conversation = self
+            .api
+            .conversation(&conversation_id)
+            .await?
+            .with_context(|| format!("Conversation: {conversation_id} was not found"))?;
+        let sharing = self
+            .api
+            .read_merged(self.cli.workflow.as_deref())
+            .await?
+            .sharing
+            .unwrap_or_default();
+
+        let secrets = std::env::vars()
+            .chain(conversation.env.clone())
+            .filter(|(name, _)| crash::is_secret(name))
+            .map(|(_, value)| value)
+            .collect::<Vec<_>>();
+        let strip_files = strip_files || sharing.strip_files();
+        let transcript = transcript(&conversation, strip_files, &secrets);
+        let (payload, key) = encrypt(&transcript)?;
+        let link = upload(sharing.url(), payload).await?;
+        self.spinner.stop(None)?;
+
+        let info = Info::new()
+            .add_title("Shared conversation")
+            .add_key_value("Link", &link)
+            .add_key_value("Key", &key)
+            .add_key_value("Files", if strip_files { "removed" } else { "included" })
+            .add_key_value("Open", format!("forge shared {link} {key}"));
+        self.writeln(info)?;
+        self.writeln(TitleFormat::info(
+            "Only the people given the key can read it, send the key separately from the link",
+        ))
+    }
+
+    /// Prints or saves a conversation shared with `/share`
+    async fn on_shared(&mut self, args: SharedArgs) -> Result<()> {
+        self.spinner.start(Some("Downloading"))?;
+        let payload = download(&args.link).await;
+        self.spinner.stop(None)?;
+        let transcript = decrypt(&payload?, &args.key)?;
+
+        match args.output {
+            Some(path) => {
+                ForgeFS::write(&path, transcript).await?;
+                self.writeln(TitleFormat::action(format!("Saved {}", path.display())))
+            }
+            None => {
+                let rendered = self.markdown.render(&transcript);
+                self.writeln(rendered)
+            }
+        }
+    }
+
     /// Modified version of handle_dump that supports HTML format
     async fn on_dump(&mut self, format: Option<String>) -> Result<()> {
         if let Some(conversation_id) = self.state.conversation_id {
@@ -686,9 +2453,28 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
             if let Some(conversation) = conversation {
                 let timestamp = chrono::Local::now().format("%Y-%m-%d_%H-%M-%S");
                 if let Some(format) = format {
+                    if format == "timeline" {
+                        // Export the timeline as JSON and as an HTML chart
+                        let timeline = conversation.timeline();
+                        let json_path = format!("{timestamp}-timeline.json");
+                        let html_path = format!("{timestamp}-timeline.html");
+                        let json = serde_json::to_string_pretty(&timeline)?;
+                        tokio::fs::write(json_path.as_str(), json).await?;
+                        tokio::fs::write(html_path.as_str(), timeline.to_html()).await?;
+
+                        self.writeln(
+                            TitleFormat::action("Conversation timeline created".to_string())
+                                .sub_title(format!("{json_path}, {html_path}")),
+                        )?;
+
+                        open::that(html_path.as_str()).ok();
+
+                        return Ok(());
+                    }
                     if format == "html" {
                         // Export as HTML
-                        let html_content = conversation.to_html();
+                        let citations = self.check_citations(&conversation).await;
+                        let html_content = conversation.to_html(&citations);
                         let path = format!("{timestamp}-dump.html");
                         tokio::fs::write(path.as_str(), html_content).await?;
 
@@ -702,9 +2488,13 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
                         return Ok(());
                     }
                 } else {
-                    // Default: Export as JSON
+                    // Default: Export as JSON, with the citations of the answers as checked
+                    // against the workspace
                     let path = format!("{timestamp}-dump.json");
-                    let content = serde_json::to_string_pretty(&conversation)?;
+                    let citations = self.check_citations(&conversation).await;
+                    let mut content = serde_json::to_value(&conversation)?;
+                    content["citations"] = serde_json::to_value(&citations)?;
+                    let content = serde_json::to_string_pretty(&content)?;
                     tokio::fs::write(path.as_str(), content).await?;
 
                     self.writeln(
@@ -725,41 +2515,93 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
         Ok(())
     }
 
+    /// Citations of the answers of the conversation checked against the
+    /// workspace
+    async fn check_citations(&self, conversation: &Conversation) -> Vec<CheckedCitation> {
+        let mut checker = CitationChecker::new(self.api.environment().cwd);
+        let mut citations = Vec::new();
+        for citation in conversation.citations() {
+            citations.push(checker.check(&citation).await);
+        }
+        citations
+    }
+
+    /// Writes the rendered answer with its citations of the workspace
+    /// clickable, warning about the ones designating lines that don't exist
+    async fn write_answer(&mut self, answer: &str) -> Result<()> {
+        let rendered = self.markdown.render(answer);
+        let (rendered, invalid) = CitationChecker::new(self.api.environment().cwd)
+            .link(answer, rendered, colors_enabled())
+            .await;
+        self.writeln(rendered)?;
+
+        for checked in invalid {
+            let problem = match checked.status {
+                CitationStatus::MissingLines { lines } => format!("the file has {lines} lines"),
+                _ => "the file doesn't exist".to_string(),
+            };
+            self.writeln(TitleFormat::error(format!(
+                "Unverified citation {}: {problem}",
+                checked.citation
+            )))?;
+        }
+        Ok(())
+    }
+
+    fn reset_streamed(&mut self) {
+        self.streamed = false;
+        self.pending.clear();
+    }
+
     async fn handle_chat_response(&mut self, message: ChatResponse) -> Result<()> {
         match message {
-            ChatResponse::Text { mut text, is_complete, is_md } => {
+            ChatResponse::Text { text, is_complete: false, .. } => {
+                self.spinner.stop(None)?;
+                self.streamed = true;
+                self.pending.push_str(&text);
+                if let Some(end) = block_end(&self.pending) {
+                    let block = self.pending.drain(..end).collect::<String>();
+                    if !block.trim().is_empty() {
+                        self.write_answer(&block).await?;
+                    }
+                }
+            }
+            ChatResponse::Text { text, .. } if self.streamed => {
+                tracing::info!(message = %text, "Agent Response");
+                let rest = std::mem::take(&mut self.pending);
+                if !rest.trim().is_empty() {
+                    self.write_answer(&rest).await?;
+                }
+                self.streamed = false;
+            }
+            ChatResponse::Text { text, is_complete, is_md } => {
                 if is_complete && !text.trim().is_empty() {
                     if is_md {
                         tracing::info!(message = %text, "Agent Response");
-                        text = self.markdown.render(&text);
+                        self.write_answer(&text).await?;
+                    } else {
+                        self.writeln(text)?;
                     }
-
-                    self.writeln(text)?;
                 }
             }
             ChatResponse::Summary { content } => {
                 if !content.trim().is_empty() {
                     tracing::info!(message = %content, "Agent Completion Response");
-                    let rendered = self.markdown.render(&content);
-                    self.writeln(rendered)?;
+                    self.write_answer(&content).await?;
                 }
             }
-            ChatResponse::ToolCallStart(_) => {
+            ChatResponse::ToolCallStart(call) => {
                 self.spinner.stop(None)?;
+                if let Some(question) = Autonomy::decision_required(std::slice::from_ref(&call)) {
+                    self.notify_input(question);
+                } else if let Some(progress) = tool_progress(&call) {
+                    self.spinner.start(Some(&progress))?;
+                }
             }
-            ChatResponse::ToolCallEnd(toolcall_result) => {
-                // Only track toolcall name in case of success else track the error.
-                let payload = if toolcall_result.is_error() {
-                    let mut r = ToolCallPayload::new(toolcall_result.name.to_string());
-                    if let Some(cause) = toolcall_result.output.as_str() {
-                        r = r.with_cause(cause.to_string());
-                    }
-                    r
-                } else {
-                    ToolCallPayload::new(toolcall_result.name.to_string())
-                };
-                tracker::tool_call(payload);
-
+            ChatResponse::ToolProgress { output, .. } => {
+                self.writeln(output.trim_end_matches('\n'))?;
+            }
+            ChatResponse::ToolCallEnd(_) => {
                 self.spinner.start(None)?;
                 if !self.cli.verbose {
                     return Ok(());
@@ -775,7 +2617,6 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
             ChatResponse::RetryAttempt { cause, duration: _ } => {
                 self.spinner.start(Some("Retrying"))?;
                 self.writeln(TitleFormat::error(cause.as_str()))?;
-                tracker::error_string(cause.into_string());
             }
             ChatResponse::Interrupt { reason } => {
                 self.spinner.stop(None)?;
@@ -787,16 +2628,91 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
                     InterruptionReason::MaxToolFailurePerTurnLimitReached { limit } => {
                         format!("Maximum tool failure limit ({limit}) reached for this turn")
                     }
+                    // An autonomous agent doesn't wait for the user to continue
+                    InterruptionReason::AutonomyBudgetExhausted { elapsed, requests } => {
+                        let title = format!(
+                            "Autonomous budget spent after {requests} request(s) in {}m",
+                            elapsed.as_secs() / 60
+                        );
+                        if self
+                            .state
+                            .notifications
+                            .as_ref()
+                            .is_some_and(|notifications| notifications.is_budget_notified())
+                        {
+                            notify("Forge stopped", title.as_str());
+                            self.notified = true;
+                        }
+                        self.writeln(TitleFormat::action(title))?;
+                        return Ok(());
+                    }
+                    InterruptionReason::DecisionRequired { question } => {
+                        // The user answers the question in the next prompt
+                        self.notify_input(question.as_str());
+                        let question = self.markdown.render(&question);
+                        self.writeln(TitleFormat::action("Decision required"))?;
+                        self.writeln(question)?;
+                        return Ok(());
+                    }
                 };
 
+                self.notify_input(title.as_str());
                 self.writeln(TitleFormat::action(title))?;
                 self.should_continue().await?;
             }
+            ChatResponse::Blocked { reason } => {
+                self.spinner.stop(None)?;
+                self.writeln(TitleFormat::error(format!("Response blocked: {reason}")))?;
+            }
             ChatResponse::Reasoning { content } => {
                 if !content.trim().is_empty() {
                     self.writeln(content.dimmed())?;
                 }
             }
+            ChatResponse::ProgressReport(report) => {
+                self.spinner.stop(None)?;
+                self.writeln(TitleFormat::info(format!(
+                    "Progress after {} request(s) in {}m, confidence {:.0}%",
+                    report.requests,
+                    report.elapsed.as_secs() / 60,
+                    report.confidence * 100.0
+                )))?;
+                for task in report.done.iter() {
+                    self.writeln(format!(" {} {task}", "✓".color(theme().success)))?;
+                }
+                for task in report.next.iter() {
+                    self.writeln(format!(" {} {task}", "·".dimmed()))?;
+                }
+                self.spinner.start(None)?;
+            }
+            ChatResponse::ChangeSummary(changes) => {
+                self.spinner.stop(None)?;
+                self.writeln(TitleFormat::info(format!(
+                    "{} file(s) changed, {} insertion(s)(+), {} deletion(s)(-)",
+                    changes.files.len(),
+                    changes.insertions(),
+                    changes.deletions()
+                )))?;
+                let theme = theme();
+                for file in changes.files.iter() {
+                    let kind = match file.kind {
+                        FileChangeKind::Added => "A",
+                        FileChangeKind::Modified => "M",
+                        FileChangeKind::Deleted => "D",
+                    };
+                    self.writeln(format!(
+                        " {kind} {} {} {}",
+                        file.path,
+                        format!("+{}", file.insertions).color(theme.success),
+                        format!("-{}", file.deletions).color(theme.error)
+                    ))?;
+                }
+                if !changes.commands.is_empty() {
+                    self.writeln(
+                        format!(" {} command(s) executed", changes.commands.len()).dimmed(),
+                    )?;
+                }
+            }
         }
         Ok(())
     }
@@ -826,6 +2742,24 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
         self.state.model = Some(model);
     }
 
+    /// Refreshes the context length of the model and the number of MCP
+    /// servers shown in the status line
+    async fn update_status_line(&mut self) {
+        let models = self.api.models().await.unwrap_or_default();
+        self.state.context_length = self.state.model.as_ref().and_then(|current| {
+            models
+                .iter()
+                .find(|model| &model.id == current)
+                .and_then(|model| model.context_length)
+        });
+        self.state.mcp_servers = self
+            .api
+            .read_mcp_config()
+            .await
+            .map(|config| config.len())
+            .unwrap_or_default();
+    }
+
     async fn on_custom_event(&mut self, event: Event) -> Result<()> {
         let conversation_id = self.init_conversation().await?;
         let chat = ChatRequest::new(event, conversation_id);
@@ -852,6 +2786,22 @@ impl<A: API + 'static, F: Fn() -> A> UI<A, F> {
     }
 }
 
+/// End of the complete blocks of a streamed answer, after the last blank line
+/// outside of a code block, `None` while no block is complete
+fn block_end(text: &str) -> Option<usize> {
+    text.match_indices("\n\n")
+        .map(|(start, separator)| start + separator.len())
+        .filter(|end| {
+            text[..*end]
+                .lines()
+                .filter(|line| line.trim_start().starts_with("```"))
+                .count()
+                % 2
+                == 0
+        })
+        .next_back()
+}
+
 fn parse_env(env: Vec<String>) -> BTreeMap<String, String> {
     env.into_iter()
         .filter_map(|s| {
@@ -865,6 +2815,41 @@ fn parse_env(env: Vec<String>) -> BTreeMap<String, String> {
         .collect()
 }
 
+/// Theme of the output configured in the workflow
+fn build_theme(config: &ThemeConfig) -> Result<Theme> {
+    let mut theme = match config.name.unwrap_or_default() {
+        ThemeName::Dark => Theme::dark(),
+        ThemeName::Light => Theme::light(),
+        ThemeName::Solarized => Theme::solarized(),
+    };
+    for (role, color) in config.colors.iter().flatten() {
+        theme
+            .set_color(role, color)
+            .map_err(|error| anyhow::anyhow!("Invalid theme: {error}"))?;
+    }
+    Ok(theme)
+}
+
+/// Runs a chat without rendering it and returns its final answer
+async fn collect_answer<A: API>(api: &A, chat: ChatRequest) -> Result<String> {
+    let mut stream = api.chat(chat).await?;
+    let mut answer = String::new();
+    while let Some(message) = stream.next().await {
+        match message? {
+            ChatResponse::Text { text, is_complete: true, .. }
+            | ChatResponse::Summary { content: text }
+                if !text.trim().is_empty() =>
+            {
+                answer = text;
+            }
+            ChatResponse::Blocked { reason } => answer = format!("Response blocked: {reason}"),
+            _ => {}
+        }
+    }
+
+    Ok(answer)
+}
+
 struct CliModel(Model);
 
 impl Display for CliModel {
@@ -923,6 +2908,53 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_block_end() {
+        let fixture = [
+            "First paragraph",
+            "First paragraph\n\nSecond",
+            "Code:\n```\nfn a() {}\n\nfn b",
+            "Code:\n```\nfn a() {}\n```\n\nAfter",
+        ];
+
+        let actual = fixture.map(block_end);
+
+        let expected = [None, Some(17), None, Some(25)];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_build_theme() {
+        let fixture = ThemeConfig {
+            name: Some(ThemeName::Light),
+            colors: Some(BTreeMap::from([(
+                "insert".to_string(),
+                "#859900".to_string(),
+            )])),
+        };
+
+        let actual = build_theme(&fixture).unwrap();
+
+        let mut expected = Theme::light();
+        expected.insert = colored::Color::TrueColor { r: 0x85, g: 0x99, b: 0x00 };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_build_theme_unknown_color() {
+        let fixture = ThemeConfig {
+            name: None,
+            colors: Some(BTreeMap::from([(
+                "insert".to_string(),
+                "mauve".to_string(),
+            )])),
+        };
+
+        let actual = build_theme(&fixture);
+
+        assert!(actual.is_err());
+    }
+
     #[test]
     fn test_cli_model_display_with_context_and_tools() {
         let fixture = create_model_fixture("gpt-4", Some(128000), Some(true));
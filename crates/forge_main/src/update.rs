@@ -1,13 +1,43 @@
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
+use anyhow::{bail, Context, Result};
 use colored::Colorize;
 use forge_api::{Update, API};
 use forge_tracker::VERSION;
+use sha2::{Digest, Sha256};
 use update_informer::{registry, Check, Version};
 
 /// Package name for forge on npm.
 const FORGE_NPM_PACKAGE: &str = "forgecode";
 
+/// GitHub repository publishing the release binaries of the npm versions
+const FORGE_GITHUB_REPO: &str = "antinomyhq/forge";
+
+/// Interval between update checks for the banner notice.
+const NOTICE_INTERVAL: Duration = Duration::from_secs(60 * 60 * 24);
+
+/// Latest version published on npm when it's newer than the running one, as
+/// compared by semver. Development builds are never updated. The registry is
+/// queried off the async runtime since the check blocks on HTTP.
+async fn latest_version(interval: Duration) -> Option<Version> {
+    if VERSION.contains("dev") || VERSION == "0.1.0" {
+        return None;
+    }
+    tokio::task::spawn_blocking(move || {
+        update_informer::new(registry::Npm, FORGE_NPM_PACKAGE, VERSION)
+            .interval(interval)
+            .check_version()
+            .ok()
+            .flatten()
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
 /// Runs npm update in the background, failing silently
 async fn execute_update_command(api: Arc<impl API>) {
     // Spawn a new task that won't block the main application
@@ -63,26 +93,217 @@ pub async fn on_update(api: Arc<impl API>, update: Option<&Update>) {
     let frequency = update.frequency.unwrap_or_default();
     let auto_update = update.auto_update.unwrap_or_default();
 
-    // Check if version is development version, in which case we skip the update
-    // check
-    if VERSION.contains("dev") || VERSION == "0.1.0" {
-        // Skip update for development version 0.1.0
-        return;
-    }
-
-    let informer =
-        update_informer::new(registry::Npm, FORGE_NPM_PACKAGE, VERSION).interval(frequency.into());
-
-    if let Some(version) = informer.check_version().ok().flatten() {
+    if let Some(version) = latest_version(frequency.into()).await {
         if auto_update || confirm_update(version).await {
             execute_update_command(api).await;
         }
     }
 }
 
+/// Returns the latest released version if it is newer than the running one
+/// and update notices are enabled.
+pub async fn available_update(update: Option<&Update>) -> Option<String> {
+    if update.and_then(|update| update.notify) == Some(false) {
+        return None;
+    }
+    latest_version(NOTICE_INTERVAL)
+        .await
+        .map(|version| version.to_string())
+}
+
 /// Sends an event to the tracker when an update fails
 async fn send_update_failure_event(error_msg: &str) -> anyhow::Result<()> {
     tracing::error!(error = error_msg, "Update failed");
     // Always return Ok since we want to fail silently
     Ok(())
 }
+
+/// Name of the release asset containing the binary for the running platform,
+/// as published by the release matrix of forge_ci.
+fn platform_asset_name() -> Result<String> {
+    let arch = std::env::consts::ARCH;
+    let target = match std::env::consts::OS {
+        "linux" if cfg!(target_env = "musl") => format!("{arch}-unknown-linux-musl"),
+        "linux" => format!("{arch}-unknown-linux-gnu"),
+        "macos" => format!("{arch}-apple-darwin"),
+        "windows" => format!("{arch}-pc-windows-msvc.exe"),
+        os => bail!("Self-update is not supported on {os}"),
+    };
+    Ok(format!("forge-{target}"))
+}
+
+/// Verifies `content` against a checksum file in the `sha256sum` format
+/// (`<hex digest>  <file name>`).
+fn verify_checksum(content: &[u8], checksum_file: &str) -> Result<()> {
+    let expected = checksum_file
+        .split_whitespace()
+        .next()
+        .context("Checksum file is empty")?
+        .to_lowercase();
+    let actual = format!("{:x}", Sha256::digest(content));
+    if actual != expected {
+        bail!("Checksum mismatch: expected {expected}, got {actual}");
+    }
+    Ok(())
+}
+
+/// Atomically replaces the executable at `target` with `content`. The current
+/// executable is kept as a backup until the new one is in place and restored
+/// if the replacement fails.
+fn replace_executable(target: &Path, content: &[u8]) -> Result<()> {
+    let dir = target
+        .parent()
+        .context("Executable has no parent directory")?;
+
+    // Write next to the target so that the final rename stays on one filesystem
+    let mut staged = tempfile::NamedTempFile::new_in(dir)
+        .with_context(|| format!("Failed to stage update in {}", dir.display()))?;
+    staged.write_all(content)?;
+    staged.flush()?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let permissions = std::fs::metadata(target)?.permissions().mode();
+        std::fs::set_permissions(
+            staged.path(),
+            std::fs::Permissions::from_mode(permissions | 0o755),
+        )?;
+    }
+
+    let backup = backup_path(target);
+    std::fs::rename(target, &backup).context("Failed to back up the current executable")?;
+
+    if let Err(error) = staged.persist(target) {
+        // Roll back to the previous executable
+        std::fs::rename(&backup, target)
+            .context("Failed to restore the previous executable after a failed update")?;
+        return Err(error.error).context("Failed to install the new executable");
+    }
+
+    // Windows does not allow deleting a running executable, so the backup may
+    // outlive this process there.
+    let _ = std::fs::remove_file(&backup);
+    Ok(())
+}
+
+fn backup_path(target: &Path) -> PathBuf {
+    let mut name = target.file_name().unwrap_or_default().to_os_string();
+    name.push(".old");
+    target.with_file_name(name)
+}
+
+async fn download(client: &reqwest::Client, url: &str) -> Result<Vec<u8>> {
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .with_context(|| format!("Failed to download {url}"))?
+        .error_for_status()?;
+    Ok(response.bytes().await?.to_vec())
+}
+
+/// Whether the executable was installed by npm, which then has to update it
+/// so that its package stays consistent
+fn is_npm_managed(executable: &Path) -> bool {
+    executable
+        .components()
+        .any(|component| component == Component::Normal("node_modules".as_ref()))
+}
+
+/// Updates forge to the latest version published on npm: through npm when it
+/// installed forge, otherwise by downloading the release binary of that
+/// version for the running platform, verifying its checksum and replacing the
+/// current executable. Returns the installed version or `None` if forge is
+/// already up to date.
+pub async fn self_update(api: Arc<impl API>) -> Result<Option<String>> {
+    let Some(version) = latest_version(Duration::ZERO).await else {
+        return Ok(None);
+    };
+    let version = version.to_string();
+    let target = std::env::current_exe()?;
+    let target = target.canonicalize().unwrap_or(target);
+
+    if is_npm_managed(&target) {
+        let status = api
+            .execute_shell_command_raw(&format!("npm update -g {FORGE_NPM_PACKAGE} --force"))
+            .await?;
+        if !status.success() {
+            bail!("npm failed to update {FORGE_NPM_PACKAGE}: {status}");
+        }
+        return Ok(Some(version));
+    }
+
+    let client = reqwest::Client::builder()
+        .user_agent(format!("forge/{VERSION}"))
+        .build()?;
+    let release = format!("https://github.com/{FORGE_GITHUB_REPO}/releases/download/v{version}");
+    let asset_name = platform_asset_name()?;
+    let binary = download(&client, &format!("{release}/{asset_name}")).await?;
+    let checksum = download(&client, &format!("{release}/{asset_name}.sha256")).await?;
+    verify_checksum(&binary, &String::from_utf8_lossy(&checksum))?;
+
+    replace_executable(&target, &binary)?;
+
+    Ok(Some(version))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_verify_checksum_matches() {
+        let fixture = b"forge";
+        let checksum = format!(
+            "{:x}  forge-x86_64-unknown-linux-gnu",
+            Sha256::digest(fixture)
+        );
+
+        let actual = verify_checksum(fixture, &checksum).is_ok();
+
+        assert!(actual);
+    }
+
+    #[test]
+    fn test_verify_checksum_mismatch() {
+        let fixture = b"forge";
+        let checksum = format!("{:x}", Sha256::digest(b"tampered"));
+
+        let actual = verify_checksum(fixture, &checksum).is_err();
+
+        assert!(actual);
+    }
+
+    #[test]
+    fn test_replace_executable() {
+        let dir = tempfile::tempdir().unwrap();
+        let fixture = dir.path().join("forge");
+        std::fs::write(&fixture, b"old").unwrap();
+
+        replace_executable(&fixture, b"new").unwrap();
+
+        let actual = std::fs::read(&fixture).unwrap();
+        let expected = b"new".to_vec();
+        assert_eq!(actual, expected);
+        assert!(!backup_path(&fixture).exists());
+    }
+
+    #[test]
+    fn test_is_npm_managed() {
+        let fixture = [
+            "/usr/local/lib/node_modules/forgecode/bin/forge",
+            "/home/user/.local/bin/forge",
+        ];
+
+        let actual = fixture
+            .into_iter()
+            .map(|path| is_npm_managed(Path::new(path)))
+            .collect::<Vec<_>>();
+
+        let expected = vec![true, false];
+        assert_eq!(actual, expected);
+    }
+}
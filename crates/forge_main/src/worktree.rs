@@ -0,0 +1,181 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use forge_domain::Worktree;
+
+use crate::process::run;
+
+/// Creates the worktrees the agents work in, so that agents running in
+/// parallel don't write over each other in the workspace, and brings their
+/// branches back into the workspace.
+pub struct WorktreeManager {
+    repo: PathBuf,
+}
+
+impl WorktreeManager {
+    pub fn new(repo: impl Into<PathBuf>) -> Self {
+        Self { repo: repo.into() }
+    }
+
+    /// Directory of the worktrees of the repository, in the temporary
+    /// directory of the system
+    fn root(&self) -> PathBuf {
+        let mut hasher = DefaultHasher::new();
+        self.repo.hash(&mut hasher);
+        let repo = self
+            .repo
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        std::env::temp_dir()
+            .join("forge-worktrees")
+            .join(format!("{repo}-{:x}", hasher.finish()))
+    }
+
+    /// Fails when the branch a worktree would be created on already exists,
+    /// e.g. left by an earlier batch that wasn't merged
+    pub async fn ensure_available(&self, name: &str) -> Result<()> {
+        let branch = Worktree::branch_name(name);
+        let reference = format!("refs/heads/{branch}");
+        let exists = run(
+            &self.repo,
+            "git",
+            &["rev-parse", "--verify", "--quiet", &reference],
+            None,
+        )
+        .await
+        .is_ok();
+        anyhow::ensure!(
+            !exists,
+            "The branch {branch} already exists, merge or remove it with `forge worktree merge \
+             {name}` or `forge worktree remove {name}` first"
+        );
+        Ok(())
+    }
+
+    /// Creates a worktree on a new branch from the current commit, with the
+    /// changes of the workspace that aren't committed yet applied to it
+    pub async fn create(&self, name: &str) -> Result<Worktree> {
+        // Records the uncommitted changes without touching the workspace
+        let stash = run(&self.repo, "git", &["stash", "create"], None).await?;
+
+        let path = self.root().join(name);
+        let branch = Worktree::branch_name(name);
+        let worktree = path.display().to_string();
+        run(
+            &self.repo,
+            "git",
+            &["worktree", "add", "-b", &branch, &worktree, "HEAD"],
+            None,
+        )
+        .await?;
+        // The branch is based on HEAD rather than on the stash, whose commits
+        // would otherwise end up in the history when the branch is merged
+        if !stash.trim().is_empty() {
+            run(&path, "git", &["stash", "apply", stash.trim()], None).await?;
+        }
+        let head = run(&path, "git", &["rev-parse", "HEAD"], None).await?;
+
+        Ok(Worktree { path, head: head.trim().to_string(), branch: Some(branch) })
+    }
+
+    /// Creates a worktree with `revision` checked out, without a branch, e.g.
+    /// to read a pull request without touching the workspace
+    pub async fn checkout(&self, name: &str, revision: &str) -> Result<Worktree> {
+        let path = self.root().join(name);
+        let worktree = path.display().to_string();
+        run(
+            &self.repo,
+            "git",
+            &["worktree", "add", "--detach", &worktree, revision],
+            None,
+        )
+        .await?;
+
+        Ok(Worktree { path, head: revision.to_string(), branch: None })
+    }
+
+    /// Worktrees created for the agents
+    pub async fn list(&self) -> Result<Vec<Worktree>> {
+        let porcelain = run(
+            &self.repo,
+            "git",
+            &["worktree", "list", "--porcelain"],
+            None,
+        )
+        .await?;
+        Ok(Worktree::parse_all(&porcelain)
+            .into_iter()
+            .filter(|worktree| worktree.name().is_some())
+            .collect())
+    }
+
+    pub async fn find(&self, name: &str) -> Result<Worktree> {
+        self.list()
+            .await?
+            .into_iter()
+            .find(|worktree| worktree.name() == Some(name))
+            .with_context(|| format!("No worktree named {name}"))
+    }
+
+    /// Commits the changes made in a worktree to its branch. Returns whether
+    /// there was anything to commit.
+    pub async fn commit(&self, worktree: &Worktree, message: &str) -> Result<bool> {
+        let status = run(&worktree.path, "git", &["status", "--porcelain"], None).await?;
+        if status.trim().is_empty() {
+            return Ok(false);
+        }
+
+        run(&worktree.path, "git", &["add", "--all"], None).await?;
+        run(
+            &worktree.path,
+            "git",
+            &["commit", "--file", "-"],
+            Some(message),
+        )
+        .await?;
+        Ok(true)
+    }
+
+    /// Merges the branch of a worktree into the current branch of the
+    /// workspace. A conflicting merge is aborted, leaving the workspace as it
+    /// was.
+    pub async fn merge(&self, worktree: &Worktree) -> Result<()> {
+        let branch = worktree
+            .branch
+            .as_deref()
+            .context("The worktree has no branch")?;
+        let merged = run(
+            &self.repo,
+            "git",
+            &["merge", "--no-ff", "--no-edit", branch],
+            None,
+        )
+        .await;
+        if merged.is_err() {
+            run(&self.repo, "git", &["merge", "--abort"], None)
+                .await
+                .ok();
+        }
+        merged.with_context(|| format!("Failed to merge {branch}"))?;
+        Ok(())
+    }
+
+    /// Removes a worktree along with its branch
+    pub async fn remove(&self, worktree: &Worktree) -> Result<()> {
+        let path = worktree.path.display().to_string();
+        run(
+            &self.repo,
+            "git",
+            &["worktree", "remove", "--force", &path],
+            None,
+        )
+        .await?;
+        if let Some(branch) = worktree.branch.as_deref() {
+            run(&self.repo, "git", &["branch", "-D", branch], None).await?;
+        }
+        Ok(())
+    }
+}
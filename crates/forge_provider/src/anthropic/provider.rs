@@ -13,7 +13,7 @@ use super::request::Request;
 use super::response::{EventData, ListModelResponse};
 use crate::anthropic::transforms::ReasoningTransform;
 use crate::error::Error;
-use crate::utils::format_http_context;
+use crate::utils::{format_http_context, json_body};
 
 #[derive(Clone, Builder)]
 pub struct Anthropic {
@@ -21,6 +21,9 @@ pub struct Anthropic {
     api_key: String,
     base_url: Url,
     anthropic_version: String,
+    /// Compresses the chat requests with gzip
+    #[builder(default)]
+    compress_requests: bool,
 }
 
 impl Anthropic {
@@ -61,29 +64,38 @@ impl Anthropic {
 }
 
 impl Anthropic {
-    pub async fn chat(
-        &self,
-        model: &ModelId,
-        context: Context,
-    ) -> ResultStream<ChatCompletionMessage, anyhow::Error> {
+    fn request(&self, model: &ModelId, context: Context) -> anyhow::Result<Request> {
         let max_tokens = context.max_tokens.unwrap_or(4000);
         // transform the context to match the request format
         let context = ReasoningTransform.transform(context);
 
-        let request = Request::try_from(context)?
+        Ok(Request::try_from(context)?
             .model(model.as_str().to_string())
             .stream(true)
-            .max_tokens(max_tokens as u64);
+            .max_tokens(max_tokens as u64))
+    }
+
+    /// Returns the request body that would be sent for the given context
+    pub fn payload(&self, model: &ModelId, context: Context) -> anyhow::Result<serde_json::Value> {
+        Ok(serde_json::to_value(self.request(model, context)?)?)
+    }
+
+    pub async fn chat(
+        &self,
+        model: &ModelId,
+        context: Context,
+    ) -> ResultStream<ChatCompletionMessage, anyhow::Error> {
+        let request = self.request(model, context)?;
 
         let url = self.url("/messages")?;
         debug!(url = %url, model = %model, "Connecting Upstream");
-        let es = self
-            .client
-            .post(url.clone())
-            .headers(self.headers())
-            .json(&request)
-            .eventsource()
-            .with_context(|| format_http_context(None, "POST", &url))?;
+        let es = json_body(
+            self.client.post(url.clone()).headers(self.headers()),
+            &request,
+            self.compress_requests,
+        )?
+        .eventsource()
+        .with_context(|| format_http_context(None, "POST", &url))?;
 
         let stream = es
             .take_while(|message| !matches!(message, Err(reqwest_eventsource::Error::StreamEnded)))
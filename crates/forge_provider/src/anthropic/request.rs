@@ -273,6 +273,7 @@ pub enum Role {
 #[derive(Serialize)]
 #[serde(rename_all = "snake_case", tag = "type")]
 pub enum ToolChoice {
+    None,
     Auto {
         #[serde(skip_serializing_if = "Option::is_none")]
         disable_parallel_tool_use: Option<bool>,
@@ -301,9 +302,7 @@ impl From<forge_app::domain::ToolChoice> for ToolChoice {
             forge_app::domain::ToolChoice::Required => {
                 ToolChoice::Any { disable_parallel_tool_use: None }
             }
-            forge_app::domain::ToolChoice::None => {
-                ToolChoice::Auto { disable_parallel_tool_use: None }
-            }
+            forge_app::domain::ToolChoice::None => ToolChoice::None,
         }
     }
 }
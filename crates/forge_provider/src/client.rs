@@ -2,10 +2,12 @@
 
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::{Context as _, Result};
 use forge_app::domain::{
-    ChatCompletionMessage, Context, HttpConfig, Model, ModelId, Provider, ResultStream, RetryConfig,
+    ChatCompletionMessage, Context, HttpConfig, Model, ModelId, Provider, ResultStream,
+    RetryConfig, StreamStall,
 };
 use reqwest::redirect::Policy;
 use tokio::sync::RwLock;
@@ -14,10 +16,12 @@ use tokio_stream::StreamExt;
 use crate::anthropic::Anthropic;
 use crate::forge_provider::ForgeProvider;
 use crate::retry::into_retry;
+use crate::stall::detect_stalls;
 
 #[derive(Clone)]
 pub struct Client {
     retry_config: Arc<RetryConfig>,
+    stream_stall: StreamStall,
     inner: Arc<InnerClient>,
     models_cache: Arc<RwLock<HashMap<ModelId, Model>>>,
 }
@@ -34,17 +38,25 @@ impl Client {
         version: impl ToString,
         timeout_config: &HttpConfig,
     ) -> Result<Self> {
-        let client = reqwest::Client::builder()
-            .connect_timeout(std::time::Duration::from_secs(
-                timeout_config.connect_timeout,
-            ))
-            .read_timeout(std::time::Duration::from_secs(timeout_config.read_timeout))
-            .pool_idle_timeout(std::time::Duration::from_secs(
-                timeout_config.pool_idle_timeout,
-            ))
+        // Zero turns the keep-alives off
+        let seconds = |seconds: u64| (seconds > 0).then(|| Duration::from_secs(seconds));
+        let builder = reqwest::Client::builder()
+            .connect_timeout(Duration::from_secs(timeout_config.connect_timeout))
+            .read_timeout(Duration::from_secs(timeout_config.read_timeout))
+            .pool_idle_timeout(Duration::from_secs(timeout_config.pool_idle_timeout))
             .pool_max_idle_per_host(timeout_config.pool_max_idle_per_host)
-            .redirect(Policy::limited(timeout_config.max_redirects))
-            .build()?;
+            .tcp_keepalive(seconds(timeout_config.tcp_keepalive))
+            .redirect(Policy::limited(timeout_config.max_redirects));
+        let builder = if timeout_config.http2 {
+            builder
+                .http2_adaptive_window(true)
+                .http2_keep_alive_interval(seconds(timeout_config.http2_keep_alive_interval))
+                .http2_keep_alive_while_idle(true)
+        } else {
+            builder.http1_only()
+        };
+        let client = builder.build()?;
+        let stream_stall = timeout_config.stream_stall(&provider);
 
         let inner = match &provider {
             Provider::OpenAI { url, .. } => InnerClient::OpenAICompat(
@@ -52,6 +64,7 @@ impl Client {
                     .client(client)
                     .provider(provider.clone())
                     .version(version.to_string())
+                    .compress_requests(timeout_config.compress_requests)
                     .build()
                     .with_context(|| format!("Failed to initialize: {url}"))?,
             ),
@@ -62,6 +75,7 @@ impl Client {
                     .api_key(key.to_string())
                     .base_url(url.clone())
                     .anthropic_version("2023-06-01".to_string())
+                    .compress_requests(timeout_config.compress_requests)
                     .build()
                     .with_context(|| {
                         format!("Failed to initialize Anthropic client with URL: {url}")
@@ -72,6 +86,7 @@ impl Client {
         Ok(Self {
             inner: Arc::new(inner),
             retry_config,
+            stream_stall,
             models_cache: Arc::new(RwLock::new(HashMap::new())),
         })
     }
@@ -113,7 +128,10 @@ impl Client {
 
         let this = self.clone();
         Ok(Box::pin(
-            chat_stream.map(move |item| this.clone().retry(item)),
+            detect_stalls(chat_stream, self.stream_stall, |seconds| {
+                ChatCompletionMessage::default().stalled(seconds)
+            })
+            .map(move |item| this.clone().retry(item)),
         ))
     }
 
@@ -121,6 +139,15 @@ impl Client {
         self.refresh_models().await
     }
 
+    /// Returns the exact request body that would be sent to the provider for
+    /// the given context
+    pub fn payload(&self, model: &ModelId, context: Context) -> anyhow::Result<serde_json::Value> {
+        match self.inner.as_ref() {
+            InnerClient::OpenAICompat(provider) => provider.payload(model, context),
+            InnerClient::Anthropic(provider) => provider.payload(model, context),
+        }
+    }
+
     pub async fn model(&self, model: &ModelId) -> anyhow::Result<Option<Model>> {
         // First, check if the model is in the cache
         {
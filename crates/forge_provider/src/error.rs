@@ -24,6 +24,9 @@ pub enum Error {
 
     #[error("Invalid Status Code: {0}")]
     InvalidStatusCode(u16),
+
+    #[error("The provider sent nothing for {0} seconds")]
+    StreamStalled(u64),
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
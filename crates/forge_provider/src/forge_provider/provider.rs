@@ -14,13 +14,16 @@ use super::request::Request;
 use super::response::Response;
 use crate::error::Error;
 use crate::forge_provider::transformers::{ProviderPipeline, Transformer};
-use crate::utils::{format_http_context, sanitize_headers};
+use crate::utils::{format_http_context, json_body, sanitize_headers};
 
 #[derive(Clone, Builder)]
 pub struct ForgeProvider {
     client: Client,
     provider: Provider,
     version: String,
+    /// Compresses the chat requests with gzip
+    #[builder(default)]
+    compress_requests: bool,
 }
 
 impl ForgeProvider {
@@ -67,22 +70,22 @@ impl ForgeProvider {
             "HTTP-Referer",
             HeaderValue::from_static("https://github.com/antinomyhq/forge"),
         );
-        headers.insert(
-            reqwest::header::CONNECTION,
-            HeaderValue::from_static("keep-alive"),
-        );
         debug!(headers = ?sanitize_headers(&headers), "Request Headers");
         headers
     }
 
+    fn request(&self, model: &ModelId, context: ChatContext) -> Request {
+        let request = Request::from(context).model(model.clone()).stream(true);
+        let mut pipeline = ProviderPipeline::new(&self.provider);
+        pipeline.transform(request)
+    }
+
     async fn inner_chat(
         &self,
         model: &ModelId,
         context: ChatContext,
     ) -> ResultStream<ChatCompletionMessage, anyhow::Error> {
-        let mut request = Request::from(context).model(model.clone()).stream(true);
-        let mut pipeline = ProviderPipeline::new(&self.provider);
-        request = pipeline.transform(request);
+        let request = self.request(model, context);
 
         let url = self.url("chat/completions")?;
         let headers = self.headers();
@@ -96,13 +99,13 @@ impl ForgeProvider {
             "Connecting Upstream"
         );
 
-        let es = self
-            .client
-            .post(url.clone())
-            .headers(headers)
-            .json(&request)
-            .eventsource()
-            .with_context(|| format_http_context(None, "POST", &url))?;
+        let es = json_body(
+            self.client.post(url.clone()).headers(headers),
+            &request,
+            self.compress_requests,
+        )?
+        .eventsource()
+        .with_context(|| format_http_context(None, "POST", &url))?;
 
         let stream = es
             .take_while(|message| !matches!(message, Err(reqwest_eventsource::Error::StreamEnded)))
@@ -230,6 +233,11 @@ impl ForgeProvider {
     pub async fn models(&self) -> Result<Vec<forge_app::domain::Model>> {
         self.inner_models().await
     }
+
+    /// Returns the request body that would be sent for the given context
+    pub fn payload(&self, model: &ModelId, context: ChatContext) -> Result<serde_json::Value> {
+        Ok(serde_json::to_value(self.request(model, context))?)
+    }
 }
 
 impl From<Model> for forge_app::domain::Model {
@@ -264,7 +264,7 @@ impl From<Context> for Request {
             max_tokens: context.max_tokens.map(|t| t as u32),
             temperature: context.temperature.map(|t| t.value()),
             tool_choice: context.tool_choice.map(|tc| tc.into()),
-            seed: Default::default(),
+            seed: context.seed,
             top_p: context.top_p.map(|t| t.value()),
             top_k: context.top_k.map(|t| t.value()),
             frequency_penalty: Default::default(),
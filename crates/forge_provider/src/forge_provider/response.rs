@@ -166,16 +166,22 @@ impl TryFrom<Response> for ChatCompletionMessage {
 
                             if let Some(tool_calls) = &message.tool_calls {
                                 for tool_call in tool_calls {
+                                    let name = tool_call
+                                        .function
+                                        .name
+                                        .clone()
+                                        .ok_or(Error::ToolCallMissingName)?;
+                                    // Malformed arguments are reported back to the model, as
+                                    // when streaming
+                                    let arguments = ToolCallFull::parse_call_arguments(
+                                        &name,
+                                        tool_call.id.as_ref(),
+                                        &tool_call.function.arguments,
+                                    )?;
                                     resp = resp.add_tool_call(ToolCallFull {
                                         call_id: tool_call.id.clone(),
-                                        name: tool_call
-                                            .function
-                                            .name
-                                            .clone()
-                                            .ok_or(Error::ToolCallMissingName)?,
-                                        arguments: serde_json::from_str(
-                                            &tool_call.function.arguments,
-                                        )?,
+                                        name,
+                                        arguments,
                                     });
                                 }
                             }
@@ -238,6 +244,7 @@ impl TryFrom<Response> for ChatCompletionMessage {
 mod tests {
     use anyhow::Context;
     use forge_app::domain::ChatCompletionMessage;
+    use pretty_assertions::assert_eq;
 
     use super::*;
 
@@ -256,6 +263,21 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_non_streaming_malformed_arguments() {
+        let event = r#"{"id":"gen-1","model":"openai/gpt-4o-mini","object":"chat.completion","created":1739949430,"choices":[{"index":0,"message":{"role":"assistant","content":"","tool_calls":[{"id":"call_1","type":"function","function":{"name":"forge_tool_fs_read","arguments":"{\"path\": "}}]},"finish_reason":"tool_calls"}]}"#;
+        let response = serde_json::from_str::<Response>(event).unwrap();
+
+        let actual = ChatCompletionMessage::try_from(response).unwrap_err();
+
+        let call = match actual.downcast_ref::<forge_app::domain::Error>() {
+            Some(forge_app::domain::Error::MalformedToolCall(call)) => call.clone(),
+            _ => panic!("Expected a malformed tool call, got {actual}"),
+        };
+        assert_eq!(call.name, ToolName::new("forge_tool_fs_read"));
+        assert_eq!(call.arguments, r#"{"path": "#);
+    }
+
     #[test]
     fn test_open_ai_response_event() {
         let event = "{\"id\":\"chatcmpl-B2YVxGR9TaLBrEcFMVCv2B4IcNe4g\",\"object\":\"chat.completion.chunk\",\"created\":1739949029,\"model\":\"gpt-4o-mini-2024-07-18\",\"service_tier\":\"default\",\"system_fingerprint\":\"fp_00428b782a\",\"choices\":[{\"index\":0,\"delta\":{\"role\":\"assistant\",\"content\":null,\"tool_calls\":[{\"index\":0,\"id\":\"call_fmuXMsHhKD5eM2k0CvgNed53\",\"type\":\"function\",\"function\":{\"name\":\"forge_tool_process_shell\",\"arguments\":\"\"}}],\"refusal\":null},\"logprobs\":null,\"finish_reason\":null}]}";
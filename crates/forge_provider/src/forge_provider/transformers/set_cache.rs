@@ -94,6 +94,7 @@ mod tests {
             top_p: None,
             top_k: None,
             reasoning: None,
+            seed: None,
         };
 
         let request = Request::from(context);
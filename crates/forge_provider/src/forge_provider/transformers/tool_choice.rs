@@ -3,6 +3,7 @@ use forge_app::domain::Transformer;
 use crate::forge_provider::request::Request;
 use crate::forge_provider::tool_choice::ToolChoice;
 
+/// Sets the tool choice of requests that don't specify one
 pub struct SetToolChoice {
     choice: ToolChoice,
 }
@@ -17,7 +18,9 @@ impl Transformer for SetToolChoice {
     type Value = Request;
 
     fn transform(&mut self, mut request: Self::Value) -> Self::Value {
-        request.tool_choice = Some(self.choice.clone());
+        request
+            .tool_choice
+            .get_or_insert_with(|| self.choice.clone());
         request
     }
 }
@@ -39,4 +42,15 @@ mod tests {
 
         assert_eq!(transformed.tool_choice, Some(ToolChoice::Auto));
     }
+
+    #[test]
+    fn test_keeps_requested_tool_choice() {
+        let context = Context::default().tool_choice(forge_app::domain::ToolChoice::None);
+        let request = Request::from(context).model(ModelId::new("google/gemini-pro"));
+
+        let mut transformer = SetToolChoice::new(ToolChoice::Auto);
+        let transformed = transformer.transform(request);
+
+        assert_eq!(transformed.tool_choice, Some(ToolChoice::None));
+    }
 }
@@ -5,6 +5,7 @@ mod forge_provider;
 #[cfg(test)]
 mod mock_server;
 mod retry;
+mod stall;
 
 mod utils;
 
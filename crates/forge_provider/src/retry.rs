@@ -18,6 +18,7 @@ pub fn into_retry(error: anyhow::Error, retry_config: &RetryConfig) -> anyhow::E
         || is_req_transport_error(&error)
         || is_event_transport_error(&error)
         || is_empty_error(&error)
+        || is_stream_stall(&error)
     {
         return DomainError::Retryable(error).into();
     }
@@ -95,6 +96,12 @@ fn is_empty_error(error: &anyhow::Error) -> bool {
     })
 }
 
+fn is_stream_stall(error: &anyhow::Error) -> bool {
+    error
+        .downcast_ref::<Error>()
+        .is_some_and(|error| matches!(error, Error::StreamStalled(_)))
+}
+
 fn is_req_transport_error(error: &anyhow::Error) -> bool {
     error
         .downcast_ref::<reqwest::Error>()
@@ -185,6 +192,16 @@ mod tests {
         assert!(is_retryable(actual));
     }
 
+    #[test]
+    fn test_into_retry_with_stream_stall() {
+        let retry_config = RetryConfig::default().retry_status_codes(vec![]);
+        let error = anyhow::Error::from(Error::StreamStalled(300));
+
+        let actual = into_retry(error, &retry_config);
+
+        assert!(is_retryable(actual));
+    }
+
     // Note: Testing with real reqwest::Error and reqwest_eventsource::Error
     // instances is challenging in unit tests as they're designed to be created
     // internally by their respective libraries during real HTTP operations.
@@ -0,0 +1,159 @@
+use forge_app::domain::StreamStall;
+use tokio::time::Instant;
+use tokio_stream::{Stream, StreamExt};
+
+use crate::error::Error;
+
+/// Watches the time between the chunks of a response. Once `warn_after`
+/// passes without a chunk, the response yields the notice `warning` makes of
+/// the seconds waited, and it ends with a retryable error once `abort_after`
+/// does, dropping the connection.
+pub fn detect_stalls<S, A, W>(
+    stream: S,
+    stall: StreamStall,
+    warning: W,
+) -> impl Stream<Item = anyhow::Result<A>>
+where
+    S: Stream<Item = anyhow::Result<A>> + Unpin,
+    W: Fn(u64) -> A,
+{
+    // The time the chunk is waited for since, kept when the stall was warned
+    // about so that the wait goes on until the response is aborted
+    futures::stream::unfold(Some((stream, None, warning)), move |state| async move {
+        let (mut stream, warned_since, warning) = state?;
+        let waiting_since = warned_since.unwrap_or_else(Instant::now);
+        match next_chunk(&mut stream, &stall, waiting_since, warned_since.is_some()).await {
+            Ok(Next::Chunk(item)) => item.map(|item| (item, Some((stream, None, warning)))),
+            Ok(Next::Stalled(seconds)) => {
+                let notice = warning(seconds);
+                Some((Ok(notice), Some((stream, Some(waiting_since), warning))))
+            }
+            Err(error) => Some((Err(error), None)),
+        }
+    })
+}
+
+/// What waiting for the next chunk of a response ended with
+enum Next<T> {
+    Chunk(Option<T>),
+    /// Nothing was sent for the seconds
+    Stalled(u64),
+}
+
+async fn next_chunk<S, A>(
+    stream: &mut S,
+    stall: &StreamStall,
+    waiting_since: Instant,
+    warned: bool,
+) -> anyhow::Result<Next<S::Item>>
+where
+    S: Stream<Item = anyhow::Result<A>> + Unpin,
+{
+    let next = stream.next();
+    tokio::pin!(next);
+
+    if let Some(warn_after) = stall.warn_after().filter(|_| !warned) {
+        match tokio::time::timeout(warn_after, &mut next).await {
+            Ok(item) => return Ok(Next::Chunk(item)),
+            Err(_) => {
+                tracing::warn!(
+                    seconds = warn_after.as_secs(),
+                    "The provider hasn't sent anything for a while"
+                );
+                return Ok(Next::Stalled(warn_after.as_secs()));
+            }
+        }
+    }
+
+    match stall.abort_after() {
+        Some(abort_after) => {
+            let left = abort_after.saturating_sub(waiting_since.elapsed());
+            tokio::time::timeout(left, next)
+                .await
+                .map(Next::Chunk)
+                .map_err(|_| Error::StreamStalled(abort_after.as_secs()).into())
+        }
+        None => Ok(Next::Chunk(next.await)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    /// Chunks sent after the delays, in seconds
+    fn chunks(delays: Vec<u64>) -> impl Stream<Item = anyhow::Result<u64>> + Unpin {
+        Box::pin(futures::stream::unfold(
+            delays.into_iter(),
+            |mut delays| async move {
+                let delay = delays.next()?;
+                tokio::time::sleep(Duration::from_secs(delay)).await;
+                Some((Ok(delay), delays))
+            },
+        ))
+    }
+
+    /// Notice of a stall, told apart from the chunks by its size
+    fn warning(seconds: u64) -> u64 {
+        seconds * 1000
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_stream_without_stall() {
+        let fixture = chunks(vec![1, 20, 2]);
+
+        let actual = detect_stalls(
+            fixture,
+            StreamStall { warn_after: 30, abort_after: 60 },
+            warning,
+        )
+        .map(|item| item.unwrap())
+        .collect::<Vec<_>>()
+        .await;
+
+        let expected = vec![1, 20, 2];
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_stream_stall_warns() {
+        let fixture = chunks(vec![1, 40, 2]);
+
+        let actual = detect_stalls(
+            fixture,
+            StreamStall { warn_after: 30, abort_after: 60 },
+            warning,
+        )
+        .map(|item| item.unwrap())
+        .collect::<Vec<_>>()
+        .await;
+
+        let expected = vec![1, 30_000, 40, 2];
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_stream_stall_aborts() {
+        let fixture = chunks(vec![1, 90, 2]);
+
+        let actual = detect_stalls(
+            fixture,
+            StreamStall { warn_after: 30, abort_after: 60 },
+            warning,
+        )
+        .map(|item| item.map_err(|error| error.to_string()))
+        .collect::<Vec<_>>()
+        .await;
+
+        let expected = vec![
+            Ok(1),
+            Ok(30_000),
+            Err("The provider sent nothing for 60 seconds".to_string()),
+        ];
+        assert_eq!(actual, expected);
+    }
+}
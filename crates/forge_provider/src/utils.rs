@@ -1,5 +1,10 @@
-use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
-use reqwest::StatusCode;
+use std::io::Write;
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_ENCODING, CONTENT_TYPE};
+use reqwest::{RequestBuilder, StatusCode};
+use serde::Serialize;
 
 /// Helper function to format HTTP request/response context for logging and
 /// error reporting
@@ -15,6 +20,24 @@ pub(crate) fn format_http_context<U: AsRef<str>>(
     }
 }
 
+/// Sets the body of the request to the JSON of `body`, compressed with gzip
+/// when `compress` is set
+pub(crate) fn json_body<T: Serialize>(
+    request: RequestBuilder,
+    body: &T,
+    compress: bool,
+) -> anyhow::Result<RequestBuilder> {
+    if !compress {
+        return Ok(request.json(body));
+    }
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+    encoder.write_all(&serde_json::to_vec(body)?)?;
+    Ok(request
+        .header(CONTENT_TYPE, "application/json")
+        .header(CONTENT_ENCODING, "gzip")
+        .body(encoder.finish()?))
+}
+
 /// Sanitizes headers for logging by redacting sensitive values
 pub fn sanitize_headers(headers: &HeaderMap) -> HeaderMap {
     let sensitive_headers = [AUTHORIZATION.as_str()];
@@ -34,10 +57,36 @@ pub fn sanitize_headers(headers: &HeaderMap) -> HeaderMap {
 
 #[cfg(test)]
 mod tests {
+    use std::io::Read;
+
+    use flate2::read::GzDecoder;
     use reqwest::header::HeaderValue;
+    use serde_json::json;
 
     use super::*;
 
+    #[test]
+    fn test_compressed_json_body() {
+        let fixture = json!({"model": "claude", "messages": [{"role": "user", "content": "hi"}]});
+
+        let request = json_body(
+            reqwest::Client::new().post("https://api.example.com/v1/chat"),
+            &fixture,
+            true,
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+
+        let mut actual = String::new();
+        GzDecoder::new(request.body().unwrap().as_bytes().unwrap())
+            .read_to_string(&mut actual)
+            .unwrap();
+        let expected = fixture.to_string();
+        assert_eq!(actual, expected);
+        assert_eq!(request.headers()[CONTENT_ENCODING], "gzip");
+    }
+
     #[test]
     fn test_sanitize_headers_for_logging() {
         let mut headers = HeaderMap::new();
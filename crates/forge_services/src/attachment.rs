@@ -2,37 +2,67 @@ use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use forge_app::domain::{Attachment, AttachmentContent, Image};
-use forge_app::AttachmentService;
+use forge_app::domain::{
+    parse_mcp_resource_reference, Attachment, AttachmentContent, ConversationId, Image,
+};
+use forge_app::{AttachmentService, McpService};
 
 use crate::{EnvironmentInfra, FileReaderInfra};
 
 #[derive(Clone)]
-pub struct ForgeChatRequest<F> {
+pub struct ForgeChatRequest<F, M> {
     infra: Arc<F>,
+    mcp_service: Arc<M>,
 }
 
-impl<F: FileReaderInfra + EnvironmentInfra> ForgeChatRequest<F> {
-    pub fn new(infra: Arc<F>) -> Self {
-        Self { infra }
+impl<F: FileReaderInfra + EnvironmentInfra, M: McpService> ForgeChatRequest<F, M> {
+    pub fn new(infra: Arc<F>, mcp_service: Arc<M>) -> Self {
+        Self { infra, mcp_service }
     }
 
     async fn prepare_attachments<T: AsRef<Path>>(
         &self,
+        conversation_id: &ConversationId,
         paths: HashSet<T>,
     ) -> anyhow::Result<Vec<Attachment>> {
         futures::future::join_all(
             paths
                 .into_iter()
                 .map(|v| v.as_ref().to_path_buf())
-                .map(|v| self.populate_attachments(v)),
+                .map(|v| self.populate_attachments(conversation_id, v)),
         )
         .await
         .into_iter()
         .collect::<anyhow::Result<Vec<_>>>()
+        .map(|attachments| attachments.into_iter().flatten().collect())
     }
 
-    async fn populate_attachments(&self, mut path: PathBuf) -> anyhow::Result<Attachment> {
+    async fn populate_attachments(
+        &self,
+        conversation_id: &ConversationId,
+        mut path: PathBuf,
+    ) -> anyhow::Result<Vec<Attachment>> {
+        let reference = path.to_string_lossy().to_string();
+        if let Some((server, uri)) = parse_mcp_resource_reference(&reference) {
+            let contents = self.mcp_service.read_resource(server, uri).await?;
+            // Following the changes is a bonus that servers may not support
+            let subscribed = self
+                .mcp_service
+                .subscribe_resource(conversation_id, server, uri)
+                .await;
+            if let Err(error) = subscribed {
+                tracing::debug!(
+                    resource = %reference,
+                    error = %error,
+                    "MCP resource isn't followed"
+                );
+            }
+            return Ok(contents
+                .into_iter()
+                .map(|content| Attachment { content, path: reference.clone() })
+                .collect());
+        }
+
         let extension = path.extension().map(|v| v.to_string_lossy().to_string());
 
         if !path.is_absolute() {
@@ -55,31 +85,45 @@ impl<F: FileReaderInfra + EnvironmentInfra> ForgeChatRequest<F> {
             None => AttachmentContent::FileContent(self.infra.read_utf8(&path).await?),
         };
 
-        Ok(Attachment { content, path: path.to_string_lossy().to_string() })
+        Ok(vec![Attachment {
+            content,
+            path: path.to_string_lossy().to_string(),
+        }])
     }
 }
 
 #[async_trait::async_trait]
-impl<F: FileReaderInfra + EnvironmentInfra> AttachmentService for ForgeChatRequest<F> {
-    async fn attachments(&self, url: &str) -> anyhow::Result<Vec<Attachment>> {
-        self.prepare_attachments(Attachment::parse_all(url)).await
+impl<F: FileReaderInfra + EnvironmentInfra, M: McpService> AttachmentService
+    for ForgeChatRequest<F, M>
+{
+    async fn attachments(
+        &self,
+        conversation_id: &ConversationId,
+        url: &str,
+    ) -> anyhow::Result<Vec<Attachment>> {
+        self.prepare_attachments(conversation_id, Attachment::parse_all(url))
+            .await
     }
 }
 
 #[cfg(test)]
 pub mod tests {
-    use std::collections::HashMap;
+    use std::collections::{BTreeMap, HashMap};
     use std::path::{Path, PathBuf};
     use std::sync::{Arc, Mutex};
+    use std::time::SystemTime;
 
     use base64::Engine;
     use bytes::Bytes;
     use forge_app::domain::{
-        AttachmentContent, CommandOutput, Environment, ToolDefinition, ToolName, ToolOutput,
+        mcp_resource_reference, Attachment, AttachmentContent, CommandOutput, ConversationId,
+        Environment, McpNotification, McpPrompt, McpResource, McpResourceUpdate, McpSampling,
+        OutputRing, ToolCallFull, ToolDefinition, ToolName, ToolOutput,
     };
-    use forge_app::AttachmentService;
-    use forge_snaps::Snapshot;
+    use forge_app::{AttachmentService, McpService};
+    use forge_snaps::{RetentionPolicy, Snapshot, SnapshotId};
     use serde_json::Value;
+    use tokio::sync::{broadcast, mpsc};
     use url::Url;
 
     use crate::attachment::ForgeChatRequest;
@@ -111,6 +155,13 @@ pub mod tests {
                 max_read_size: 0,
                 http: Default::default(),
                 max_file_size: 10_000_000,
+                max_write_size: 10_000_000,
+                external_paths: vec![],
+                resource_limits: Default::default(),
+                text_coalesce_ms: None,
+                response_cache: false,
+                quota: Default::default(),
+                storage: Default::default(),
                 forge_api_url: Url::parse("http://forgecode.dev/api").unwrap(),
             }
         }
@@ -118,6 +169,10 @@ pub mod tests {
         fn get_env_var(&self, _key: &str) -> Option<String> {
             None
         }
+
+        fn get_env_vars(&self) -> Vec<(String, String)> {
+            Vec::new()
+        }
     }
 
     impl MockFileService {
@@ -175,6 +230,11 @@ pub mod tests {
             }
         }
 
+        async fn read_from(&self, path: &Path, offset: u64) -> anyhow::Result<Vec<u8>> {
+            let content = self.read(path).await?;
+            Ok(content.get(offset as usize..).unwrap_or_default().to_vec())
+        }
+
         async fn range_read_utf8(
             &self,
             path: &Path,
@@ -201,13 +261,24 @@ pub mod tests {
 
     #[async_trait::async_trait]
     impl FileRemoverInfra for MockFileService {
-        async fn remove(&self, path: &Path) -> anyhow::Result<()> {
+        async fn remove(&self, path: &Path, _permanent: bool) -> anyhow::Result<()> {
             if !self.exists(path).await? {
                 return Err(anyhow::anyhow!("File not found: {:?}", path));
             }
             self.files.lock().unwrap().retain(|(p, _)| p != path);
             Ok(())
         }
+
+        async fn remove_dir(&self, path: &Path, _permanent: bool) -> anyhow::Result<Vec<PathBuf>> {
+            let mut files = self.files.lock().unwrap();
+            let removed = files
+                .iter()
+                .filter(|(p, _)| p.starts_with(path))
+                .map(|(p, _)| p.clone())
+                .collect();
+            files.retain(|(p, _)| !p.starts_with(path));
+            Ok(removed)
+        }
     }
 
     #[async_trait::async_trait]
@@ -240,6 +311,17 @@ pub mod tests {
             Ok(())
         }
 
+        async fn append(&self, path: &Path, contents: Bytes) -> anyhow::Result<()> {
+            let mut files = self.files.lock().unwrap();
+            match files.iter_mut().find(|v| v.0 == path) {
+                Some((_, existing)) => {
+                    *existing = [existing.as_ref(), contents.as_ref()].concat().into()
+                }
+                None => files.push((path.to_path_buf(), contents)),
+            }
+            Ok(())
+        }
+
         async fn write_temp(&self, _: &str, _: &str, content: &str) -> anyhow::Result<PathBuf> {
             let temp_dir = crate::utils::TempDir::new().unwrap();
             let path = temp_dir.path();
@@ -248,6 +330,10 @@ pub mod tests {
 
             Ok(path)
         }
+
+        async fn lock(&self, _path: &Path) -> anyhow::Result<forge_fs::FileLock> {
+            unimplemented!()
+        }
     }
 
     #[derive(Debug)]
@@ -262,6 +348,22 @@ pub mod tests {
         async fn undo_snapshot(&self, _: &Path) -> anyhow::Result<()> {
             unimplemented!()
         }
+
+        async fn list_snapshots(&self, _: &Path) -> anyhow::Result<Vec<Snapshot>> {
+            unimplemented!()
+        }
+
+        async fn restore_snapshot(&self, _: &Path, _: &SnapshotId) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+
+        async fn apply_snapshot(&self, _: &Path, _: &SnapshotId) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+
+        async fn purge_snapshots(&self, _: &RetentionPolicy) -> anyhow::Result<usize> {
+            unimplemented!()
+        }
     }
 
     #[async_trait::async_trait]
@@ -288,6 +390,14 @@ pub mod tests {
                 Err(anyhow::anyhow!("File not found: {}", path.display()))
             }
         }
+
+        async fn modified(&self, _path: &Path) -> anyhow::Result<SystemTime> {
+            unimplemented!()
+        }
+
+        async fn list_dir(&self, _path: &Path) -> anyhow::Result<Vec<String>> {
+            unimplemented!()
+        }
     }
 
     #[async_trait::async_trait]
@@ -299,6 +409,38 @@ pub mod tests {
         async fn call(&self, _: &ToolName, _: Value) -> anyhow::Result<ToolOutput> {
             Ok(ToolOutput::default())
         }
+
+        async fn list_prompts(&self) -> anyhow::Result<Vec<McpPrompt>> {
+            Ok(vec![])
+        }
+
+        async fn get_prompt(&self, _: &str, _: BTreeMap<String, String>) -> anyhow::Result<String> {
+            Ok(String::new())
+        }
+
+        async fn list_resources(&self) -> anyhow::Result<Vec<McpResource>> {
+            Ok(vec![])
+        }
+
+        async fn read_resource(&self, _: &str) -> anyhow::Result<Vec<AttachmentContent>> {
+            Ok(vec![])
+        }
+
+        async fn subscribe_resource(&self, _: &str) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn unsubscribe_resource(&self, _: &str) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        fn subscribe(&self) -> broadcast::Receiver<McpNotification> {
+            broadcast::channel(1).1
+        }
+
+        async fn next_sampling(&self) -> Option<McpSampling> {
+            None
+        }
     }
 
     #[async_trait::async_trait]
@@ -319,6 +461,7 @@ pub mod tests {
             &self,
             command: String,
             working_dir: PathBuf,
+            _env: BTreeMap<String, String>,
         ) -> anyhow::Result<CommandOutput> {
             // For test purposes, we'll create outputs that match what the shell tests
             // expect Check for common command patterns
@@ -432,6 +575,27 @@ pub mod tests {
             })
         }
 
+        async fn execute_command_with_progress(
+            &self,
+            command: String,
+            working_dir: PathBuf,
+            env: BTreeMap<String, String>,
+            progress: OutputRing,
+        ) -> anyhow::Result<CommandOutput> {
+            let output = self.execute_command(command, working_dir, env).await?;
+            progress.push(&output.stdout);
+            Ok(output)
+        }
+
+        async fn execute_program(
+            &self,
+            _program: &str,
+            _args: Vec<String>,
+            _working_dir: PathBuf,
+        ) -> anyhow::Result<CommandOutput> {
+            unimplemented!()
+        }
+
         async fn execute_command_raw(&self, _: &str) -> anyhow::Result<std::process::ExitStatus> {
             unimplemented!()
         }
@@ -477,6 +641,7 @@ pub mod tests {
     pub struct MockCompositeService {
         file_service: Arc<MockFileService>,
         env_service: Arc<MockEnvironmentInfra>,
+        subscriptions: Mutex<Vec<String>>,
     }
 
     impl MockCompositeService {
@@ -484,6 +649,7 @@ pub mod tests {
             Self {
                 file_service: Arc::new(MockFileService::new()),
                 env_service: Arc::new(MockEnvironmentInfra {}),
+                subscriptions: Default::default(),
             }
         }
 
@@ -502,6 +668,10 @@ pub mod tests {
             self.file_service.read(path).await
         }
 
+        async fn read_from(&self, path: &Path, offset: u64) -> anyhow::Result<Vec<u8>> {
+            self.file_service.read_from(path, offset).await
+        }
+
         async fn range_read_utf8(
             &self,
             path: &Path,
@@ -523,19 +693,88 @@ pub mod tests {
         fn get_env_var(&self, _key: &str) -> Option<String> {
             None
         }
+
+        fn get_env_vars(&self) -> Vec<(String, String)> {
+            Vec::new()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl McpService for MockCompositeService {
+        async fn list(&self) -> anyhow::Result<Vec<ToolDefinition>> {
+            Ok(vec![])
+        }
+
+        async fn call(
+            &self,
+            _: ToolCallFull,
+            _: mpsc::UnboundedSender<McpSampling>,
+        ) -> anyhow::Result<ToolOutput> {
+            Ok(ToolOutput::default())
+        }
+
+        async fn list_prompts(&self) -> anyhow::Result<Vec<McpPrompt>> {
+            Ok(vec![])
+        }
+
+        async fn get_prompt(&self, _: &str, _: BTreeMap<String, String>) -> anyhow::Result<String> {
+            Ok(String::new())
+        }
+
+        async fn list_resources(&self) -> anyhow::Result<Vec<McpResource>> {
+            Ok(vec![])
+        }
+
+        async fn read_resource(
+            &self,
+            server: &str,
+            uri: &str,
+        ) -> anyhow::Result<Vec<AttachmentContent>> {
+            match (server, uri) {
+                ("docs", "docs://guide/setup") => Ok(vec![AttachmentContent::FileContent(
+                    "Run forge login first".to_string(),
+                )]),
+                _ => Err(anyhow::anyhow!("Resource not found: {uri}")),
+            }
+        }
+
+        async fn subscribe_resource(
+            &self,
+            _: &ConversationId,
+            server: &str,
+            uri: &str,
+        ) -> anyhow::Result<()> {
+            self.subscriptions
+                .lock()
+                .unwrap()
+                .push(mcp_resource_reference(server, uri));
+            Ok(())
+        }
+
+        async fn unsubscribe_resources(&self, _: &ConversationId) -> anyhow::Result<()> {
+            self.subscriptions.lock().unwrap().clear();
+            Ok(())
+        }
+
+        async fn take_resource_updates(&self, _: &ConversationId) -> Vec<McpResourceUpdate> {
+            vec![]
+        }
     }
 
     #[tokio::test]
     async fn test_add_url_with_text_file() {
         // Setup
         let infra = Arc::new(MockCompositeService::new());
-        let chat_request = ForgeChatRequest::new(infra.clone());
+        let chat_request = ForgeChatRequest::new(infra.clone(), infra.clone());
 
         // Test with a text file path in chat message
         let url = "@[/test/file1.txt]".to_string();
 
         // Execute
-        let attachments = chat_request.attachments(&url).await.unwrap();
+        let attachments = chat_request
+            .attachments(&ConversationId::default(), &url)
+            .await
+            .unwrap();
 
         // Assert
         // Text files should be included in the attachments
@@ -551,13 +790,16 @@ pub mod tests {
     async fn test_add_url_with_image() {
         // Setup
         let infra = Arc::new(MockCompositeService::new());
-        let chat_request = ForgeChatRequest::new(infra.clone());
+        let chat_request = ForgeChatRequest::new(infra.clone(), infra.clone());
 
         // Test with an image file
         let url = "@[/test/image.png]".to_string();
 
         // Execute
-        let attachments = chat_request.attachments(&url).await.unwrap();
+        let attachments = chat_request
+            .attachments(&ConversationId::default(), &url)
+            .await
+            .unwrap();
 
         // Assert
         assert_eq!(attachments.len(), 1);
@@ -578,13 +820,16 @@ pub mod tests {
     async fn test_add_url_with_jpg_image_with_spaces() {
         // Setup
         let infra = Arc::new(MockCompositeService::new());
-        let chat_request = ForgeChatRequest::new(infra.clone());
+        let chat_request = ForgeChatRequest::new(infra.clone(), infra.clone());
 
         // Test with an image file that has spaces in the path
         let url = "@[/test/image with spaces.jpg]".to_string();
 
         // Execute
-        let attachments = chat_request.attachments(&url).await.unwrap();
+        let attachments = chat_request
+            .attachments(&ConversationId::default(), &url)
+            .await
+            .unwrap();
 
         // Assert
         assert_eq!(attachments.len(), 1);
@@ -611,13 +856,16 @@ pub mod tests {
             "This is another text file".to_string(),
         );
 
-        let chat_request = ForgeChatRequest::new(infra.clone());
+        let chat_request = ForgeChatRequest::new(infra.clone(), infra.clone());
 
         // Test with multiple files mentioned
         let url = "@[/test/file1.txt] @[/test/file2.txt] @[/test/image.png]".to_string();
 
         // Execute
-        let attachments = chat_request.attachments(&url).await.unwrap();
+        let attachments = chat_request
+            .attachments(&ConversationId::default(), &url)
+            .await
+            .unwrap();
 
         // Assert
         // All files should be included in the attachments
@@ -643,13 +891,15 @@ pub mod tests {
     async fn test_add_url_with_nonexistent_file() {
         // Setup
         let infra = Arc::new(MockCompositeService::new());
-        let chat_request = ForgeChatRequest::new(infra.clone());
+        let chat_request = ForgeChatRequest::new(infra.clone(), infra.clone());
 
         // Test with a file that doesn't exist
         let url = "@[/test/nonexistent.txt]".to_string();
 
         // Execute - Let's handle the error properly
-        let result = chat_request.attachments(&url).await;
+        let result = chat_request
+            .attachments(&ConversationId::default(), &url)
+            .await;
 
         // Assert - we expect an error for nonexistent files
         assert!(result.is_err());
@@ -660,13 +910,16 @@ pub mod tests {
     async fn test_add_url_empty() {
         // Setup
         let infra = Arc::new(MockCompositeService::new());
-        let chat_request = ForgeChatRequest::new(infra.clone());
+        let chat_request = ForgeChatRequest::new(infra.clone(), infra.clone());
 
         // Test with an empty message
         let url = "".to_string();
 
         // Execute
-        let attachments = chat_request.attachments(&url).await.unwrap();
+        let attachments = chat_request
+            .attachments(&ConversationId::default(), &url)
+            .await
+            .unwrap();
 
         // Assert - no attachments
         assert_eq!(attachments.len(), 0);
@@ -683,13 +936,16 @@ pub mod tests {
             "Some content".to_string(),
         );
 
-        let chat_request = ForgeChatRequest::new(infra.clone());
+        let chat_request = ForgeChatRequest::new(infra.clone(), infra.clone());
 
         // Test with the file
         let url = "@[/test/unknown.xyz]".to_string();
 
         // Execute
-        let attachments = chat_request.attachments(&url).await.unwrap();
+        let attachments = chat_request
+            .attachments(&ConversationId::default(), &url)
+            .await
+            .unwrap();
 
         // Assert - should be treated as text
         assert_eq!(attachments.len(), 1);
@@ -699,4 +955,39 @@ pub mod tests {
         // Check that the content contains our original text and has range information
         assert!(attachment.content.contains("Some content"));
     }
+
+    #[tokio::test]
+    async fn test_add_url_with_mcp_resource() {
+        let infra = Arc::new(MockCompositeService::new());
+        let chat_request = ForgeChatRequest::new(infra.clone(), infra.clone());
+
+        let url = "How do I start? See @mcp:docs/docs://guide/setup".to_string();
+        let actual = chat_request
+            .attachments(&ConversationId::default(), &url)
+            .await
+            .unwrap();
+
+        let expected = vec![Attachment {
+            content: AttachmentContent::FileContent("Run forge login first".to_string()),
+            path: "mcp:docs/docs://guide/setup".to_string(),
+        }];
+        assert_eq!(actual, expected);
+        let actual = infra.subscriptions.lock().unwrap().clone();
+        let expected = vec!["mcp:docs/docs://guide/setup".to_string()];
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_add_url_with_unknown_mcp_resource() {
+        let infra = Arc::new(MockCompositeService::new());
+        let chat_request = ForgeChatRequest::new(infra.clone(), infra.clone());
+
+        let url = "@[mcp:docs/docs://missing]".to_string();
+        let actual = chat_request
+            .attachments(&ConversationId::default(), &url)
+            .await
+            .is_err();
+
+        assert!(actual);
+    }
 }
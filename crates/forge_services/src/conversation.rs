@@ -2,30 +2,74 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use anyhow::{Context as AnyhowContext, Result};
+use bytes::Bytes;
 use forge_app::domain::{Conversation, ConversationId, Workflow};
 use forge_app::{ConversationService, McpService};
 use tokio::sync::Mutex;
 
+use crate::StorageInfra;
+
+/// Prefix of the storage keys of the conversations
+const PREFIX: &str = "conversations/";
+
 /// Service for managing conversations, including creation, retrieval, and
-/// updates
+/// updates. The conversations created or upserted are kept in the storage
+/// too, so that they can be found again once the session is over.
 #[derive(Clone)]
-pub struct ForgeConversationService<M> {
+pub struct ForgeConversationService<M, F> {
     workflows: Arc<Mutex<HashMap<ConversationId, Conversation>>>,
     mcp_service: Arc<M>,
+    infra: Arc<F>,
 }
 
-impl<M: McpService> ForgeConversationService<M> {
+impl<M: McpService, F: StorageInfra> ForgeConversationService<M, F> {
     /// Creates a new ForgeConversationService with the provided MCP service
-    pub fn new(mcp_service: Arc<M>) -> Self {
-        Self { workflows: Arc::new(Mutex::new(HashMap::new())), mcp_service }
+    pub fn new(mcp_service: Arc<M>, infra: Arc<F>) -> Self {
+        Self {
+            workflows: Arc::new(Mutex::new(HashMap::new())),
+            mcp_service,
+            infra,
+        }
+    }
+
+    fn key(id: &ConversationId) -> String {
+        format!("{PREFIX}{id}.json")
+    }
+
+    /// Stores the conversation unless it's ephemeral. Failing to do so doesn't
+    /// stop the conversation, it only isn't kept once the session is over.
+    async fn persist(&self, conversation: &Conversation) {
+        if conversation.ephemeral {
+            return;
+        }
+        let result = match serde_json::to_vec(conversation) {
+            Ok(content) => {
+                self.infra
+                    .put_object(&Self::key(&conversation.id), Bytes::from(content))
+                    .await
+            }
+            Err(error) => Err(error.into()),
+        };
+        if let Err(error) = result {
+            tracing::warn!(%error, id = %conversation.id, "Failed to store the conversation");
+        }
+    }
+
+    async fn load(&self, id: &ConversationId) -> Result<Option<Conversation>> {
+        let Some(content) = self.infra.get_object(&Self::key(id)).await? else {
+            return Ok(None);
+        };
+        let conversation: Conversation = serde_json::from_slice(&content)
+            .with_context(|| format!("Failed to parse the stored conversation {id}"))?;
+        Ok(Some(conversation))
     }
 }
 
 #[async_trait::async_trait]
-impl<M: McpService> ConversationService for ForgeConversationService<M> {
-    async fn update<F, T>(&self, id: &ConversationId, f: F) -> Result<T>
+impl<M: McpService, F: StorageInfra> ConversationService for ForgeConversationService<M, F> {
+    async fn update<U, T>(&self, id: &ConversationId, f: U) -> Result<T>
     where
-        F: FnOnce(&mut Conversation) -> T + Send,
+        U: FnOnce(&mut Conversation) -> T + Send,
     {
         let mut workflows = self.workflows.lock().await;
         let conversation = workflows.get_mut(id).context("Conversation not found")?;
@@ -33,10 +77,21 @@ impl<M: McpService> ConversationService for ForgeConversationService<M> {
     }
 
     async fn find(&self, id: &ConversationId) -> Result<Option<Conversation>> {
-        Ok(self.workflows.lock().await.get(id).cloned())
+        if let Some(conversation) = self.workflows.lock().await.get(id).cloned() {
+            return Ok(Some(conversation));
+        }
+        let conversation = self.load(id).await?;
+        if let Some(conversation) = &conversation {
+            self.workflows
+                .lock()
+                .await
+                .insert(conversation.id, conversation.clone());
+        }
+        Ok(conversation)
     }
 
     async fn upsert(&self, conversation: Conversation) -> Result<()> {
+        self.persist(&conversation).await;
         self.workflows
             .lock()
             .await
@@ -56,7 +111,44 @@ impl<M: McpService> ConversationService for ForgeConversationService<M> {
                 .map(|a| a.name)
                 .collect(),
         );
+        self.persist(&conversation).await;
         self.workflows.lock().await.insert(id, conversation.clone());
         Ok(conversation)
     }
+
+    async fn list_conversations(&self) -> Result<Vec<ConversationId>> {
+        let keys = self.infra.list_objects(PREFIX).await?;
+        Ok(keys.iter().filter_map(|key| conversation_id(key)).collect())
+    }
+}
+
+/// ID of the conversation stored at the key, `None` for other objects
+fn conversation_id(key: &str) -> Option<ConversationId> {
+    let id = key.strip_prefix(PREFIX)?.strip_suffix(".json")?;
+    ConversationId::parse(id).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_conversation_id() {
+        let id = ConversationId::generate();
+        let fixture = [
+            format!("conversations/{id}.json"),
+            "conversations/notes.json".to_string(),
+            format!("checkpoints/{id}.json"),
+        ];
+
+        let actual = fixture
+            .iter()
+            .map(|key| conversation_id(key))
+            .collect::<Vec<_>>();
+
+        let expected = vec![Some(id), None, None];
+        assert_eq!(actual, expected);
+    }
 }
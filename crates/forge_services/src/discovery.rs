@@ -1,22 +1,29 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use anyhow::Result;
 use forge_app::domain::File;
 use forge_app::{FileDiscoveryService, Walker};
+use tokio::sync::RwLock;
 
-use crate::{EnvironmentInfra, WalkerInfra};
+use crate::tool_services::syn;
+use crate::{repo_map, EnvironmentInfra, FileReaderInfra, WalkerInfra};
 
 pub struct ForgeDiscoveryService<F> {
     service: Arc<F>,
+    /// Entries counted in the session by directory and limit, so that a
+    /// gigantic workspace is scanned once rather than on every message
+    entries: RwLock<HashMap<(PathBuf, usize), usize>>,
 }
 
 impl<F> ForgeDiscoveryService<F> {
     pub fn new(service: Arc<F>) -> Self {
-        Self { service }
+        Self { service, entries: Default::default() }
     }
 }
 
-impl<F: EnvironmentInfra + WalkerInfra> ForgeDiscoveryService<F> {
+impl<F: EnvironmentInfra + WalkerInfra + FileReaderInfra> ForgeDiscoveryService<F> {
     async fn discover_with_config(&self, config: Walker) -> Result<Vec<File>> {
         let files = self.service.walk(config).await?;
         Ok(files
@@ -24,13 +31,49 @@ impl<F: EnvironmentInfra + WalkerInfra> ForgeDiscoveryService<F> {
             .map(|file| File { path: file.path.clone(), is_dir: file.is_dir() })
             .collect())
     }
+
+    async fn rank(&self, files: Vec<String>) -> Result<Vec<String>> {
+        let cwd = self.service.get_environment().cwd;
+        let files = futures::future::join_all(files.into_iter().map(|path| {
+            let absolute = cwd.join(&path);
+            async move {
+                // Directories and unreadable files have no imports but still get ranked
+                if path.ends_with('/') {
+                    return (path, Vec::new());
+                }
+                let imports = match self.service.read_utf8(&absolute).await {
+                    Ok(content) => syn::imports(&path, &content),
+                    Err(_) => Vec::new(),
+                };
+                (path, imports)
+            }
+        }))
+        .await;
+
+        Ok(repo_map::rank(files))
+    }
 }
 
 #[async_trait::async_trait]
-impl<F: EnvironmentInfra + WalkerInfra + Send + Sync> FileDiscoveryService
+impl<F: EnvironmentInfra + WalkerInfra + FileReaderInfra + Send + Sync> FileDiscoveryService
     for ForgeDiscoveryService<F>
 {
     async fn collect_files(&self, config: Walker) -> Result<Vec<File>> {
         self.discover_with_config(config).await
     }
+
+    async fn count_entries(&self, cwd: PathBuf, limit: usize) -> Result<usize> {
+        let key = (cwd, limit);
+        if let Some(entries) = self.entries.read().await.get(&key) {
+            return Ok(*entries);
+        }
+
+        let entries = self.service.count_entries(key.0.clone(), limit).await?;
+        self.entries.write().await.insert(key, entries);
+        Ok(entries)
+    }
+
+    async fn rank_files(&self, files: Vec<String>) -> Result<Vec<String>> {
+        self.rank(files).await
+    }
 }
@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use forge_app::domain::Provider;
 use forge_app::Services;
 
 use crate::app_config::ForgeConfigService;
@@ -8,19 +9,28 @@ use crate::auth::ForgeAuthService;
 use crate::conversation::ForgeConversationService;
 use crate::discovery::ForgeDiscoveryService;
 use crate::env::ForgeEnvironmentService;
+use crate::journal::ForgeJournalService;
 use crate::mcp::{ForgeMcpManager, ForgeMcpService};
+use crate::moderation::ForgeModerationService;
+use crate::path_resolver::ForgePathResolver;
+use crate::project_memory::ForgeProjectMemoryService;
 use crate::provider::ForgeProviderService;
 use crate::provider_registry::ForgeProviderRegistry;
+use crate::resource_governor::ForgeResourceGovernor;
 use crate::template::ForgeTemplateService;
 use crate::tool_services::{
-    ForgeFetch, ForgeFollowup, ForgeFsCreate, ForgeFsPatch, ForgeFsRead, ForgeFsRemove,
-    ForgeFsSearch, ForgeFsUndo, ForgeShell,
+    ForgeArchive, ForgeDiscoverTasks, ForgeFetch, ForgeFollowup, ForgeFsCheckpoint, ForgeFsCreate,
+    ForgeFsMeta, ForgeFsPatch, ForgeFsRead, ForgeFsRemove, ForgeFsSearch, ForgeFsSnapshot,
+    ForgeFsUndo, ForgeFsWriteDraft, ForgeGit, ForgeImageDiff, ForgeLint, ForgeProcessEnv,
+    ForgeShell, ForgeToolchainInfo,
 };
+use crate::tool_stats::ForgeToolStatsService;
 use crate::workflow::ForgeWorkflowService;
+use crate::workspace_settings::ForgeWorkspaceSettingsService;
 use crate::{
     CommandInfra, EnvironmentInfra, FileDirectoryInfra, FileInfoInfra, FileReaderInfra,
-    FileRemoverInfra, FileWriterInfra, HttpInfra, McpServerInfra, SnapshotInfra, UserInfra,
-    WalkerInfra,
+    FileRemoverInfra, FileWriterInfra, HttpInfra, McpServerInfra, SnapshotInfra, StorageInfra,
+    UserInfra, WalkerInfra,
 };
 
 type McpService<F> = ForgeMcpService<ForgeMcpManager<F>, F, <F as McpServerInfra>::Client>;
@@ -34,20 +44,33 @@ type AuthService<F> = ForgeAuthService<F>;
 ///   environment, file reading, vector indexing, and embedding.
 #[derive(Clone)]
 pub struct ForgeServices<F: McpServerInfra + WalkerInfra> {
-    chat_service: Arc<ForgeProviderService>,
-    conversation_service: Arc<ForgeConversationService<McpService<F>>>,
+    chat_service: Arc<ForgeProviderService<F>>,
+    conversation_service: Arc<ForgeConversationService<McpService<F>, F>>,
     template_service: Arc<ForgeTemplateService<F>>,
-    attachment_service: Arc<ForgeChatRequest<F>>,
+    attachment_service: Arc<ForgeChatRequest<F, McpService<F>>>,
     workflow_service: Arc<ForgeWorkflowService<F>>,
     discovery_service: Arc<ForgeDiscoveryService<F>>,
     mcp_manager: Arc<ForgeMcpManager<F>>,
     file_create_service: Arc<ForgeFsCreate<F>>,
+    file_write_draft_service: Arc<ForgeFsWriteDraft<F>>,
     file_read_service: Arc<ForgeFsRead<F>>,
     file_search_service: Arc<ForgeFsSearch<F>>,
     file_remove_service: Arc<ForgeFsRemove<F>>,
     file_patch_service: Arc<ForgeFsPatch<F>>,
     file_undo_service: Arc<ForgeFsUndo<F>>,
+    file_meta_service: Arc<ForgeFsMeta<F>>,
+    path_resolver_service: Arc<ForgePathResolver<F>>,
+    file_snapshot_service: Arc<ForgeFsSnapshot<F>>,
+    file_checkpoint_service: Arc<ForgeFsCheckpoint<F>>,
     shell_service: Arc<ForgeShell<F>>,
+    git_service: Arc<ForgeGit<F>>,
+    lint_service: Arc<ForgeLint<F>>,
+    task_discovery_service: Arc<ForgeDiscoverTasks<F>>,
+    process_env_service: Arc<ForgeProcessEnv<F>>,
+    toolchain_service: Arc<ForgeToolchainInfo<F>>,
+    archive_service: Arc<ForgeArchive<F>>,
+    image_diff_service: Arc<ForgeImageDiff<F>>,
+    resource_governor_service: Arc<ForgeResourceGovernor>,
     fetch_service: Arc<ForgeFetch>,
     followup_service: Arc<ForgeFollowup<F>>,
     mcp_service: Arc<McpService<F>>,
@@ -55,6 +78,11 @@ pub struct ForgeServices<F: McpServerInfra + WalkerInfra> {
     config_service: Arc<ForgeConfigService<F>>,
     auth_service: Arc<AuthService<F>>,
     provider_service: Arc<ForgeProviderRegistry<F>>,
+    workspace_settings_service: Arc<ForgeWorkspaceSettingsService<F>>,
+    project_memory_service: Arc<ForgeProjectMemoryService<F>>,
+    journal_service: Arc<ForgeJournalService<F>>,
+    tool_stats_service: Arc<ForgeToolStatsService<F>>,
+    moderation_service: Arc<ForgeModerationService<F>>,
 }
 
 impl<
@@ -64,6 +92,7 @@ impl<
             + FileInfoInfra
             + FileReaderInfra
             + HttpInfra
+            + StorageInfra
             + WalkerInfra,
     > ForgeServices<F>
 {
@@ -71,24 +100,47 @@ impl<
         let mcp_manager = Arc::new(ForgeMcpManager::new(infra.clone()));
         let mcp_service = Arc::new(ForgeMcpService::new(mcp_manager.clone(), infra.clone()));
         let template_service = Arc::new(ForgeTemplateService::new(infra.clone()));
-        let attachment_service = Arc::new(ForgeChatRequest::new(infra.clone()));
+        let attachment_service =
+            Arc::new(ForgeChatRequest::new(infra.clone(), mcp_service.clone()));
 
         let workflow_service = Arc::new(ForgeWorkflowService::new(infra.clone()));
         let suggestion_service = Arc::new(ForgeDiscoveryService::new(infra.clone()));
-        let conversation_service = Arc::new(ForgeConversationService::new(mcp_service.clone()));
+        let conversation_service = Arc::new(ForgeConversationService::new(
+            mcp_service.clone(),
+            infra.clone(),
+        ));
         let config_service = Arc::new(ForgeConfigService::new(infra.clone()));
         let auth_service = Arc::new(ForgeAuthService::new(infra.clone()));
         let chat_service = Arc::new(ForgeProviderService::new(infra.clone()));
         let file_create_service = Arc::new(ForgeFsCreate::new(infra.clone()));
+        let file_write_draft_service = Arc::new(ForgeFsWriteDraft::new(infra.clone()));
         let file_read_service = Arc::new(ForgeFsRead::new(infra.clone()));
         let file_search_service = Arc::new(ForgeFsSearch::new(infra.clone()));
         let file_remove_service = Arc::new(ForgeFsRemove::new(infra.clone()));
         let file_patch_service = Arc::new(ForgeFsPatch::new(infra.clone()));
         let file_undo_service = Arc::new(ForgeFsUndo::new(infra.clone()));
+        let file_meta_service = Arc::new(ForgeFsMeta::new(infra.clone()));
+        let path_resolver_service = Arc::new(ForgePathResolver::new(infra.clone()));
+        let file_snapshot_service = Arc::new(ForgeFsSnapshot::new(infra.clone()));
+        let file_checkpoint_service = Arc::new(ForgeFsCheckpoint::new(infra.clone()));
         let shell_service = Arc::new(ForgeShell::new(infra.clone()));
+        let git_service = Arc::new(ForgeGit::new(infra.clone()));
+        let lint_service = Arc::new(ForgeLint::new(infra.clone()));
+        let task_discovery_service = Arc::new(ForgeDiscoverTasks::new(infra.clone()));
+        let process_env_service = Arc::new(ForgeProcessEnv::new(infra.clone()));
+        let toolchain_service = Arc::new(ForgeToolchainInfo::new(infra.clone()));
+        let archive_service = Arc::new(ForgeArchive::new(infra.clone()));
+        let image_diff_service = Arc::new(ForgeImageDiff::new(infra.clone()));
+        let resource_governor_service = Arc::new(ForgeResourceGovernor::new(infra.clone()));
         let fetch_service = Arc::new(ForgeFetch::new());
         let followup_service = Arc::new(ForgeFollowup::new(infra.clone()));
         let provider_service = Arc::new(ForgeProviderRegistry::new(infra.clone()));
+        let workspace_settings_service =
+            Arc::new(ForgeWorkspaceSettingsService::new(infra.clone()));
+        let project_memory_service = Arc::new(ForgeProjectMemoryService::new(infra.clone()));
+        let journal_service = Arc::new(ForgeJournalService::new(infra.clone()));
+        let tool_stats_service = Arc::new(ForgeToolStatsService::new(infra.clone()));
+        let moderation_service = Arc::new(ForgeModerationService::new(infra.clone()));
         let env_service = Arc::new(ForgeEnvironmentService::new(infra));
         Self {
             conversation_service,
@@ -98,12 +150,25 @@ impl<
             discovery_service: suggestion_service,
             mcp_manager,
             file_create_service,
+            file_write_draft_service,
             file_read_service,
             file_search_service,
             file_remove_service,
             file_patch_service,
             file_undo_service,
+            file_meta_service,
+            path_resolver_service,
+            file_snapshot_service,
+            file_checkpoint_service,
             shell_service,
+            git_service,
+            lint_service,
+            task_discovery_service,
+            process_env_service,
+            toolchain_service,
+            archive_service,
+            image_diff_service,
+            resource_governor_service,
             fetch_service,
             followup_service,
             mcp_service,
@@ -112,8 +177,20 @@ impl<
             auth_service,
             chat_service,
             provider_service,
+            workspace_settings_service,
+            project_memory_service,
+            journal_service,
+            tool_stats_service,
+            moderation_service,
         }
     }
+
+    /// Pins the provider used for all requests, bypassing detection from the
+    /// app config and environment variables.
+    pub fn provider(mut self, provider: Provider) -> Self {
+        self.provider_service = Arc::new(self.provider_service.with_provider(provider));
+        self
+    }
 }
 
 impl<
@@ -128,31 +205,50 @@ impl<
             + FileDirectoryInfra
             + EnvironmentInfra
             + HttpInfra
+            + StorageInfra
             + WalkerInfra
             + Clone,
     > Services for ForgeServices<F>
 {
-    type ProviderService = ForgeProviderService;
-    type ConversationService = ForgeConversationService<McpService<F>>;
+    type ProviderService = ForgeProviderService<F>;
+    type ConversationService = ForgeConversationService<McpService<F>, F>;
     type TemplateService = ForgeTemplateService<F>;
-    type AttachmentService = ForgeChatRequest<F>;
+    type AttachmentService = ForgeChatRequest<F, McpService<F>>;
     type EnvironmentService = ForgeEnvironmentService<F>;
     type WorkflowService = ForgeWorkflowService<F>;
     type FileDiscoveryService = ForgeDiscoveryService<F>;
     type McpConfigManager = ForgeMcpManager<F>;
     type FsCreateService = ForgeFsCreate<F>;
+    type FsWriteDraftService = ForgeFsWriteDraft<F>;
     type FsPatchService = ForgeFsPatch<F>;
     type FsReadService = ForgeFsRead<F>;
     type FsRemoveService = ForgeFsRemove<F>;
     type FsSearchService = ForgeFsSearch<F>;
     type FollowUpService = ForgeFollowup<F>;
     type FsUndoService = ForgeFsUndo<F>;
+    type FsMetaService = ForgeFsMeta<F>;
+    type PathResolverService = ForgePathResolver<F>;
+    type FsSnapshotService = ForgeFsSnapshot<F>;
+    type FsCheckpointService = ForgeFsCheckpoint<F>;
     type NetFetchService = ForgeFetch;
     type ShellService = ForgeShell<F>;
+    type GitService = ForgeGit<F>;
+    type LintService = ForgeLint<F>;
+    type TaskDiscoveryService = ForgeDiscoverTasks<F>;
+    type ProcessEnvService = ForgeProcessEnv<F>;
+    type ToolchainService = ForgeToolchainInfo<F>;
+    type ArchiveService = ForgeArchive<F>;
+    type ImageDiffService = ForgeImageDiff<F>;
+    type ResourceGovernorService = ForgeResourceGovernor;
     type McpService = McpService<F>;
     type AppConfigService = ForgeConfigService<F>;
     type AuthService = AuthService<F>;
     type ProviderRegistry = ForgeProviderRegistry<F>;
+    type WorkspaceSettingsService = ForgeWorkspaceSettingsService<F>;
+    type ProjectMemoryService = ForgeProjectMemoryService<F>;
+    type JournalService = ForgeJournalService<F>;
+    type ToolStatsService = ForgeToolStatsService<F>;
+    type ModerationService = ForgeModerationService<F>;
 
     fn provider_service(&self) -> &Self::ProviderService {
         &self.chat_service
@@ -190,6 +286,10 @@ impl<
         &self.file_create_service
     }
 
+    fn fs_write_draft_service(&self) -> &Self::FsWriteDraftService {
+        &self.file_write_draft_service
+    }
+
     fn fs_patch_service(&self) -> &Self::FsPatchService {
         &self.file_patch_service
     }
@@ -214,6 +314,22 @@ impl<
         &self.file_undo_service
     }
 
+    fn fs_meta_service(&self) -> &Self::FsMetaService {
+        &self.file_meta_service
+    }
+
+    fn path_resolver_service(&self) -> &Self::PathResolverService {
+        &self.path_resolver_service
+    }
+
+    fn fs_snapshot_service(&self) -> &Self::FsSnapshotService {
+        &self.file_snapshot_service
+    }
+
+    fn fs_checkpoint_service(&self) -> &Self::FsCheckpointService {
+        &self.file_checkpoint_service
+    }
+
     fn net_fetch_service(&self) -> &Self::NetFetchService {
         &self.fetch_service
     }
@@ -222,6 +338,38 @@ impl<
         &self.shell_service
     }
 
+    fn git_service(&self) -> &Self::GitService {
+        &self.git_service
+    }
+
+    fn lint_service(&self) -> &Self::LintService {
+        &self.lint_service
+    }
+
+    fn task_discovery_service(&self) -> &Self::TaskDiscoveryService {
+        &self.task_discovery_service
+    }
+
+    fn process_env_service(&self) -> &Self::ProcessEnvService {
+        &self.process_env_service
+    }
+
+    fn toolchain_service(&self) -> &Self::ToolchainService {
+        &self.toolchain_service
+    }
+
+    fn archive_service(&self) -> &Self::ArchiveService {
+        &self.archive_service
+    }
+
+    fn image_diff_service(&self) -> &Self::ImageDiffService {
+        &self.image_diff_service
+    }
+
+    fn resource_governor_service(&self) -> &Self::ResourceGovernorService {
+        &self.resource_governor_service
+    }
+
     fn mcp_service(&self) -> &Self::McpService {
         &self.mcp_service
     }
@@ -237,4 +385,24 @@ impl<
     fn provider_registry(&self) -> &Self::ProviderRegistry {
         &self.provider_service
     }
+
+    fn workspace_settings_service(&self) -> &Self::WorkspaceSettingsService {
+        &self.workspace_settings_service
+    }
+
+    fn project_memory_service(&self) -> &Self::ProjectMemoryService {
+        &self.project_memory_service
+    }
+
+    fn journal_service(&self) -> &Self::JournalService {
+        &self.journal_service
+    }
+
+    fn tool_stats_service(&self) -> &Self::ToolStatsService {
+        &self.tool_stats_service
+    }
+
+    fn moderation_service(&self) -> &Self::ModerationService {
+        &self.moderation_service
+    }
 }
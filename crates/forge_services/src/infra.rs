@@ -1,18 +1,25 @@
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 use anyhow::Result;
 use bytes::Bytes;
 use forge_app::domain::{
-    CommandOutput, Environment, McpServerConfig, ToolDefinition, ToolName, ToolOutput,
+    AttachmentContent, CommandOutput, Environment, McpNotification, McpPrompt, McpResource,
+    McpSampling, McpServerConfig, OutputRing, ToolDefinition, ToolName, ToolOutput,
 };
 use forge_app::{WalkedFile, Walker};
-use forge_snaps::Snapshot;
+use forge_snaps::{RetentionPolicy, Snapshot, SnapshotId};
 use reqwest::header::HeaderMap;
 use reqwest::Response;
+use tokio::sync::broadcast;
 
 pub trait EnvironmentInfra: Send + Sync {
     fn get_environment(&self) -> Environment;
     fn get_env_var(&self, key: &str) -> Option<String>;
+    /// Variables of the environment of the process, without those whose name
+    /// or value isn't valid unicode
+    fn get_env_vars(&self) -> Vec<(String, String)>;
 }
 
 /// Repository for accessing system environment information
@@ -31,6 +38,10 @@ pub trait FileReaderInfra: Send + Sync {
     /// Returns the file content as raw bytes.
     async fn read(&self, path: &Path) -> anyhow::Result<Vec<u8>>;
 
+    /// Reads the bytes of a file that follow `offset`, without reading the
+    /// content before it.
+    async fn read_from(&self, path: &Path, offset: u64) -> anyhow::Result<Vec<u8>>;
+
     /// Reads a specific line range from a file at the specified path.
     /// Returns the file content within the range as a UTF-8 string along with
     /// metadata.
@@ -63,6 +74,10 @@ pub trait FileWriterInfra: Send + Sync {
         capture_snapshot: bool,
     ) -> anyhow::Result<()>;
 
+    /// Appends content to the end of a file, creating it along with its
+    /// parent directories if it doesn't exist.
+    async fn append(&self, path: &Path, contents: Bytes) -> anyhow::Result<()>;
+
     /// Writes content to a temporary file with the given prefix and extension,
     /// and returns its path. The file will be kept (not deleted) after
     /// creation.
@@ -72,12 +87,23 @@ pub trait FileWriterInfra: Send + Sync {
     /// * `ext` - File extension (e.g. ".txt", ".md")
     /// * `content` - Content to write to the file
     async fn write_temp(&self, prefix: &str, ext: &str, content: &str) -> anyhow::Result<PathBuf>;
+
+    /// Takes the lock of a file shared with the other processes, held until
+    /// the returned guard is dropped, to update the file without losing their
+    /// updates.
+    async fn lock(&self, path: &Path) -> anyhow::Result<forge_fs::FileLock>;
 }
 
 #[async_trait::async_trait]
 pub trait FileRemoverInfra: Send + Sync {
-    /// Removes a file at the specified path.
-    async fn remove(&self, path: &Path) -> anyhow::Result<()>;
+    /// Removes a file at the specified path, moving it to the trash unless
+    /// `permanent` is set.
+    async fn remove(&self, path: &Path, permanent: bool) -> anyhow::Result<()>;
+
+    /// Recursively removes a directory at the specified path, moving it to the
+    /// trash unless `permanent` is set. The files that aren't ignored are
+    /// snapshotted first, and their paths are returned.
+    async fn remove_dir(&self, path: &Path, permanent: bool) -> anyhow::Result<Vec<PathBuf>>;
 }
 
 #[async_trait::async_trait]
@@ -85,6 +111,9 @@ pub trait FileInfoInfra: Send + Sync {
     async fn is_file(&self, path: &Path) -> anyhow::Result<bool>;
     async fn exists(&self, path: &Path) -> anyhow::Result<bool>;
     async fn file_size(&self, path: &Path) -> anyhow::Result<u64>;
+    async fn modified(&self, path: &Path) -> anyhow::Result<SystemTime>;
+    /// Names of the entries of the directory, in no particular order
+    async fn list_dir(&self, path: &Path) -> anyhow::Result<Vec<String>>;
 }
 
 #[async_trait::async_trait]
@@ -100,16 +129,51 @@ pub trait SnapshotInfra: Send + Sync {
 
     /// Restores the most recent snapshot for the given file path
     async fn undo_snapshot(&self, file_path: &Path) -> Result<()>;
+
+    /// Lists the snapshots of the given file path, oldest first
+    async fn list_snapshots(&self, file_path: &Path) -> Result<Vec<Snapshot>>;
+
+    /// Restores the given snapshot, discarding it and the newer snapshots of
+    /// the file path
+    async fn restore_snapshot(&self, file_path: &Path, id: &SnapshotId) -> Result<()>;
+
+    /// Writes the content captured by the given snapshot back to the file
+    /// path, keeping the snapshots
+    async fn apply_snapshot(&self, file_path: &Path, id: &SnapshotId) -> Result<()>;
+
+    /// Removes the snapshots exceeding the policy and returns their number
+    async fn purge_snapshots(&self, policy: &RetentionPolicy) -> Result<usize>;
 }
 
 /// Service for executing shell commands
 #[async_trait::async_trait]
 pub trait CommandInfra: Send + Sync {
-    /// Executes a shell command and returns the output
+    /// Executes a shell command with the given environment variables set on
+    /// top of the inherited ones and returns the output
     async fn execute_command(
         &self,
         command: String,
         working_dir: PathBuf,
+        env: BTreeMap<String, String>,
+    ) -> anyhow::Result<CommandOutput>;
+
+    /// Executes a shell command like `execute_command`, pushing its output to
+    /// the ring as it's produced instead of printing it
+    async fn execute_command_with_progress(
+        &self,
+        command: String,
+        working_dir: PathBuf,
+        env: BTreeMap<String, String>,
+        progress: OutputRing,
+    ) -> anyhow::Result<CommandOutput>;
+
+    /// Runs a program with its arguments passed as-is rather than through
+    /// the shell, and returns its captured output
+    async fn execute_program(
+        &self,
+        program: &str,
+        args: Vec<String>,
+        working_dir: PathBuf,
     ) -> anyhow::Result<CommandOutput>;
 
     /// execute the shell command on present stdio.
@@ -147,6 +211,28 @@ pub trait McpClientInfra: Clone + Send + Sync + 'static {
         tool_name: &ToolName,
         input: serde_json::Value,
     ) -> anyhow::Result<ToolOutput>;
+    /// Prompts the server offers, none when it doesn't support prompts
+    async fn list_prompts(&self) -> anyhow::Result<Vec<McpPrompt>>;
+    /// Text of the messages of the prompt with its arguments filled in
+    async fn get_prompt(
+        &self,
+        name: &str,
+        arguments: BTreeMap<String, String>,
+    ) -> anyhow::Result<String>;
+    /// Resources the server offers, none when it doesn't support resources
+    async fn list_resources(&self) -> anyhow::Result<Vec<McpResource>>;
+    /// Contents of the resource
+    async fn read_resource(&self, uri: &str) -> anyhow::Result<Vec<AttachmentContent>>;
+    /// Asks the server to notify the changes of the resource, failing when
+    /// it doesn't support subscriptions
+    async fn subscribe_resource(&self, uri: &str) -> anyhow::Result<()>;
+    /// Stops the notifications of the changes of the resource
+    async fn unsubscribe_resource(&self, uri: &str) -> anyhow::Result<()>;
+    /// Receives the notifications the server sends from now on
+    fn subscribe(&self) -> broadcast::Receiver<McpNotification>;
+    /// Waits for the next completion the server asks for, `None` once the
+    /// client is dropped
+    async fn next_sampling(&self) -> Option<McpSampling>;
 }
 
 #[async_trait::async_trait]
@@ -160,6 +246,10 @@ pub trait WalkerInfra: Send + Sync {
     /// Walks the filesystem starting from the given directory with the
     /// specified configuration
     async fn walk(&self, config: Walker) -> anyhow::Result<Vec<WalkedFile>>;
+
+    /// Counts the entries under `cwd`, ignored files excluded, stopping at
+    /// `limit`
+    async fn count_entries(&self, cwd: PathBuf, limit: usize) -> anyhow::Result<usize>;
 }
 
 // TODO: rename me, add Infra suffix
@@ -169,3 +259,19 @@ pub trait HttpInfra: Send + Sync + 'static {
     async fn post(&self, url: &str, body: Bytes) -> anyhow::Result<Response>;
     async fn delete(&self, url: &str) -> anyhow::Result<Response>;
 }
+
+/// Store of the data outliving the session, e.g. the conversations, as blobs
+/// addressed by keys like `conversations/<id>.json`
+#[async_trait::async_trait]
+pub trait StorageInfra: Send + Sync {
+    async fn put_object(&self, key: &str, content: Bytes) -> anyhow::Result<()>;
+
+    /// Content stored at the key, `None` when there is none
+    async fn get_object(&self, key: &str) -> anyhow::Result<Option<Bytes>>;
+
+    /// Keys below the directory-like prefix, e.g. `conversations/`
+    async fn list_objects(&self, prefix: &str) -> anyhow::Result<Vec<String>>;
+
+    /// Removes the content stored at the key, if any
+    async fn delete_object(&self, key: &str) -> anyhow::Result<()>;
+}
@@ -0,0 +1,115 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use forge_app::domain::{JournalEntry, JournalPage};
+use forge_app::JournalService;
+
+use crate::{EnvironmentInfra, FileInfoInfra, FileReaderInfra, FileWriterInfra};
+
+/// Keeps the journal of the workspace as a JSONL file that external tools can
+/// tail, independently of the UI.
+pub struct ForgeJournalService<I> {
+    infra: Arc<I>,
+}
+
+impl<I> ForgeJournalService<I> {
+    pub fn new(infra: Arc<I>) -> Self {
+        Self { infra }
+    }
+}
+
+#[async_trait::async_trait]
+impl<I: FileReaderInfra + FileWriterInfra + FileInfoInfra + EnvironmentInfra> JournalService
+    for ForgeJournalService<I>
+{
+    async fn record_journal(&self, entries: Vec<JournalEntry>) -> anyhow::Result<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+
+        let mut lines = String::new();
+        for entry in entries {
+            lines.push_str(&serde_json::to_string(&entry)?);
+            lines.push('\n');
+        }
+
+        let path = self.infra.get_environment().journal_path();
+        self.infra.append(&path, Bytes::from(lines)).await
+    }
+
+    async fn read_journal(&self, offset: u64) -> anyhow::Result<JournalPage> {
+        let path = self.infra.get_environment().journal_path();
+        if !self.infra.exists(&path).await? {
+            return Ok(JournalPage { entries: Vec::new(), offset: 0 });
+        }
+
+        // A journal shorter than the offset was recreated, so it's read from the
+        // start
+        let size = self.infra.file_size(&path).await?;
+        let offset = if offset > size { 0 } else { offset };
+        let content = self.infra.read_from(&path, offset).await?;
+        Ok(read_page(&content, offset))
+    }
+}
+
+/// Parses the complete lines of the content read from `offset`. A line that
+/// is still being written is left for the next read.
+fn read_page(content: &[u8], offset: u64) -> JournalPage {
+    let end = content
+        .iter()
+        .rposition(|byte| *byte == b'\n')
+        .map_or(0, |index| index + 1);
+
+    let entries = content[..end]
+        .split(|byte| *byte == b'\n')
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| match serde_json::from_slice(line) {
+            Ok(entry) => Some(entry),
+            Err(error) => {
+                tracing::warn!(error = %error, "Invalid journal entry");
+                None
+            }
+        })
+        .collect();
+
+    JournalPage { entries, offset: offset + end as u64 }
+}
+
+#[cfg(test)]
+mod tests {
+    use forge_app::domain::{ConversationId, JournalEvent};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn line(event: JournalEvent) -> (JournalEntry, String) {
+        let entry = JournalEntry::new(ConversationId::generate(), event);
+        let line = format!("{}\n", serde_json::to_string(&entry).unwrap());
+        (entry, line)
+    }
+
+    #[test]
+    fn test_read_page_from_offset() {
+        let (_, first) = line(JournalEvent::ConversationStarted);
+        let (second, second_line) = line(JournalEvent::ConversationEnded);
+
+        let actual = read_page(second_line.as_bytes(), first.len() as u64);
+
+        let expected = JournalPage {
+            entries: vec![second],
+            offset: (first.len() + second_line.len()) as u64,
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_read_page_leaves_partial_line() {
+        let (first, first_line) = line(JournalEvent::ConversationStarted);
+        let fixture = format!("{first_line}{{\"timestamp\":");
+
+        let actual = read_page(fixture.as_bytes(), 0);
+
+        let expected = JournalPage { entries: vec![first], offset: first_line.len() as u64 };
+        assert_eq!(actual, expected);
+    }
+}
@@ -7,13 +7,23 @@ mod discovery;
 mod env;
 mod forge_services;
 mod infra;
+mod journal;
 mod mcp;
+mod moderation;
+mod path_resolver;
+mod project_memory;
 mod provider;
 mod provider_registry;
+mod repo_map;
+mod resource_governor;
+mod response_cache;
 mod template;
 mod tool_services;
+mod tool_stats;
+mod usage;
 mod utils;
 mod workflow;
+mod workspace_settings;
 
 pub use clipper::*;
 pub use discovery::*;
@@ -53,6 +53,8 @@ impl<I: McpServerInfra + FileReaderInfra + FileInfoInfra + EnvironmentInfra + Fi
             }
         }
 
+        config.validate()?;
+
         Ok(config)
     }
 
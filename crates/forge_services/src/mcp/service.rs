@@ -1,21 +1,48 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::hash::{DefaultHasher, Hash, Hasher};
 use std::sync::Arc;
 
 use anyhow::Context;
 use forge_app::domain::{
-    McpConfig, McpServerConfig, ToolCallFull, ToolDefinition, ToolName, ToolOutput,
+    is_mcp_tool_of, mcp_prompt_name, mcp_server_of, mcp_tool_name, AttachmentContent,
+    ConversationId, McpConfig, McpNotification, McpPrompt, McpResource, McpResourceUpdate,
+    McpSampling, McpServerConfig, McpToolFilter, ToolCallFull, ToolDefinition, ToolName,
+    ToolOutput,
 };
 use forge_app::{McpConfigManager, McpService};
-use tokio::sync::{Mutex, RwLock};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{broadcast, mpsc, Mutex, RwLock};
+use tokio::task::JoinHandle;
 
 use crate::mcp::tool::McpExecutor;
 use crate::{McpClientInfra, McpServerInfra};
 
+type ToolMap<C> = Arc<RwLock<HashMap<ToolName, ToolHolder<McpExecutor<C>>>>>;
+
+/// Resources followed for changes, by server and URI, for each conversation
+type Subscriptions = Arc<RwLock<HashMap<ConversationId, BTreeSet<(String, String)>>>>;
+
+/// Contents of the subscribed resources that changed, the latest one of each
+/// resource, for each conversation
+type ResourceUpdates = Arc<Mutex<HashMap<ConversationId, Vec<McpResourceUpdate>>>>;
+
+/// Receivers of the sampling requests of each server, one per call of its
+/// tools running
+type SamplingCalls = Arc<Mutex<HashMap<String, Vec<mpsc::UnboundedSender<McpSampling>>>>>;
+
 #[derive(Clone)]
 pub struct ForgeMcpService<M, I, C> {
-    tools: Arc<RwLock<HashMap<ToolName, ToolHolder<McpExecutor<C>>>>>,
+    tools: ToolMap<C>,
+    /// Clients of the connected servers by name
+    clients: Arc<RwLock<HashMap<String, Arc<C>>>>,
     previous_config_hash: Arc<Mutex<u64>>,
+    /// Tasks reacting to the notifications of the connected servers
+    watchers: Arc<Mutex<Vec<JoinHandle<()>>>>,
+    /// Calls answering the sampling requests of the connected servers
+    sampling_calls: SamplingCalls,
+    /// Subscribed again when their server reconnects
+    subscriptions: Subscriptions,
+    resource_updates: ResourceUpdates,
     manager: Arc<M>,
     infra: Arc<I>,
 }
@@ -34,7 +61,12 @@ where
     pub fn new(manager: Arc<M>, infra: Arc<I>) -> Self {
         Self {
             tools: Default::default(),
+            clients: Default::default(),
             previous_config_hash: Arc::new(Mutex::new(0)),
+            watchers: Default::default(),
+            sampling_calls: Default::default(),
+            subscriptions: Default::default(),
+            resource_updates: Default::default(),
             manager,
             infra,
         }
@@ -49,31 +81,134 @@ where
         *self.previous_config_hash.lock().await != Self::hash(config)
     }
 
-    async fn insert_clients(&self, server_name: &str, client: Arc<C>) -> anyhow::Result<()> {
-        let tools = client.list().await?;
+    async fn connect(&self, server_name: &str, config: McpServerConfig) -> anyhow::Result<()> {
+        let filter = config.tool_filter().clone();
+        let client = self.infra.connect(config).await?;
+        let client = Arc::new(C::from(client));
+        // Subscribed first so that no change made while listing is missed
+        let notifications = client.subscribe();
+        insert_clients(&self.tools, server_name, &filter, client.clone()).await?;
+        self.clients
+            .write()
+            .await
+            .insert(server_name.to_string(), client.clone());
+        self.watch(server_name, filter, client.clone(), notifications)
+            .await;
+        self.forward_sampling(server_name, client.clone()).await;
+        self.resubscribe(server_name, &client).await;
 
-        let mut tool_map = self.tools.write().await;
+        Ok(())
+    }
 
-        for mut tool in tools.into_iter() {
-            let server = McpExecutor::new(tool.name.clone(), client.clone())?;
-            // Generate a unique name for the tool
-            let tool_name = ToolName::new(format!("mcp_{server_name}_tool_{}", tool.name));
-            tool.name = tool_name.clone();
-            tool_map.insert(
-                tool_name,
-                ToolHolder { definition: tool, executable: server },
-            );
+    /// Subscribes the new connection to the resources of the server that
+    /// were followed before it reconnected
+    async fn resubscribe(&self, server_name: &str, client: &C) {
+        let subscriptions = self.subscriptions.read().await;
+        let uris = subscriptions
+            .values()
+            .flatten()
+            .filter(|(server, _)| server == server_name)
+            .map(|(_, uri)| uri)
+            .collect::<BTreeSet<_>>();
+        for uri in uris {
+            if let Err(error) = client.subscribe_resource(uri).await {
+                tracing::warn!(
+                    server = %server_name,
+                    uri = %uri,
+                    error = %error,
+                    "Failed to subscribe to the MCP resource again"
+                );
+            }
         }
-
-        Ok(())
     }
 
-    async fn connect(&self, server_name: &str, config: McpServerConfig) -> anyhow::Result<()> {
-        let client = self.infra.connect(config).await?;
-        let client = Arc::new(C::from(client));
-        self.insert_clients(server_name, client).await?;
+    /// Forwards the sampling requests of the server to a call of its tools
+    /// running. The ones sent outside of a call are declined, as nothing the
+    /// user asked for needs them.
+    async fn forward_sampling(&self, server_name: &str, client: Arc<C>) {
+        let sampling_calls = self.sampling_calls.clone();
+        let server_name = server_name.to_string();
+        let forwarder = tokio::spawn(async move {
+            while let Some(mut request) = client.next_sampling().await {
+                request.request.server = server_name.clone();
+                let calls = sampling_calls.lock().await;
+                let call = calls
+                    .get(&server_name)
+                    .and_then(|calls| calls.iter().find(|call| !call.is_closed()));
+                let request = match call {
+                    Some(call) => match call.send(request) {
+                        Ok(()) => continue,
+                        Err(mpsc::error::SendError(request)) => request,
+                    },
+                    None => request,
+                };
+                let _ = request.reply.send(Err(anyhow::anyhow!(
+                    "The MCP server {server_name} can only ask for completions while one of its \
+                     tools is called"
+                )));
+            }
+        });
+        self.watchers.lock().await.push(forwarder);
+    }
 
-        Ok(())
+    /// Lists the tools of the server again whenever it notifies that they
+    /// changed, so that the agent calls the tools the server has now, and
+    /// reads the subscribed resources that changed
+    async fn watch(
+        &self,
+        server_name: &str,
+        filter: McpToolFilter,
+        client: Arc<C>,
+        mut notifications: broadcast::Receiver<McpNotification>,
+    ) {
+        let tools = self.tools.clone();
+        let subscriptions = self.subscriptions.clone();
+        let resource_updates = self.resource_updates.clone();
+        let server_name = server_name.to_string();
+        let watcher = tokio::spawn(async move {
+            loop {
+                match notifications.recv().await {
+                    // A notification lost while lagging may have been a change
+                    Ok(McpNotification::ToolListChanged) | Err(RecvError::Lagged(_)) => {
+                        let listed =
+                            insert_clients(&tools, &server_name, &filter, client.clone()).await;
+                        if let Err(error) = listed {
+                            tracing::warn!(
+                                server = %server_name,
+                                error = %error,
+                                "Failed to list the changed MCP tools"
+                            );
+                        }
+                    }
+                    Ok(McpNotification::ResourceUpdated { uri }) => {
+                        let subscription = (server_name.clone(), uri);
+                        let conversations = subscriptions
+                            .read()
+                            .await
+                            .iter()
+                            .filter(|(_, subscribed)| subscribed.contains(&subscription))
+                            .map(|(conversation_id, _)| conversation_id.clone())
+                            .collect::<Vec<_>>();
+                        if !conversations.is_empty() {
+                            let (_, uri) = subscription;
+                            read_resource_update(
+                                &client,
+                                &server_name,
+                                uri,
+                                &conversations,
+                                &resource_updates,
+                            )
+                            .await;
+                        }
+                    }
+                    Ok(notification) => {
+                        tracing::debug!(server = %server_name, ?notification, "MCP notification");
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+        self.watchers.lock().await.push(watcher);
     }
 
     async fn init_mcp(&self) -> anyhow::Result<()> {
@@ -115,16 +250,254 @@ where
             .collect())
     }
     async fn clear_tools(&self) {
+        for watcher in self.watchers.lock().await.drain(..) {
+            watcher.abort();
+        }
+        self.clients.write().await.clear();
         self.tools.write().await.clear()
     }
 
-    async fn call(&self, call: ToolCallFull) -> anyhow::Result<ToolOutput> {
+    async fn call(
+        &self,
+        call: ToolCallFull,
+        sampling: mpsc::UnboundedSender<McpSampling>,
+    ) -> anyhow::Result<ToolOutput> {
         let lock = self.tools.read().await;
 
         let tool = lock.get(&call.name).context("Tool not found")?;
 
-        tool.executable.call_tool(call.arguments).await
+        // The server's sampling requests are answered while the call runs
+        let server = mcp_server_of(&call.name).unwrap_or_default().to_string();
+        self.sampling_calls
+            .lock()
+            .await
+            .entry(server.clone())
+            .or_default()
+            .push(sampling.clone());
+        let output = tool.executable.call_tool(call.arguments).await;
+        if let Some(calls) = self.sampling_calls.lock().await.get_mut(&server) {
+            calls.retain(|call| !call.same_channel(&sampling));
+        }
+        output
+    }
+
+    async fn list_prompts(&self) -> anyhow::Result<Vec<McpPrompt>> {
+        self.init_mcp().await?;
+        let clients = self.clients.read().await.clone();
+        let listed = futures::future::join_all(
+            clients
+                .iter()
+                .map(|(server, client)| async move { (server, client.list_prompts().await) }),
+        )
+        .await;
+
+        let mut prompts = Vec::new();
+        for (server, listed) in listed {
+            match listed {
+                Ok(listed) => prompts.extend(listed.into_iter().map(|mut prompt| {
+                    prompt.name = mcp_prompt_name(server, &prompt.name);
+                    prompt
+                })),
+                // A server failing to list its prompts doesn't hide the others
+                Err(error) => tracing::warn!(
+                    server = %server,
+                    error = %error,
+                    "Failed to list the MCP prompts"
+                ),
+            }
+        }
+        prompts.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(prompts)
+    }
+
+    async fn get_prompt(
+        &self,
+        name: &str,
+        arguments: BTreeMap<String, String>,
+    ) -> anyhow::Result<String> {
+        self.init_mcp().await?;
+        let (server, prompt) = name.split_once(':').context("Prompt not found")?;
+        let client = self
+            .clients
+            .read()
+            .await
+            .get(server)
+            .cloned()
+            .context("Prompt not found")?;
+        client.get_prompt(prompt, arguments).await
+    }
+
+    async fn list_resources(&self) -> anyhow::Result<Vec<McpResource>> {
+        self.init_mcp().await?;
+        let clients = self.clients.read().await.clone();
+        let listed = futures::future::join_all(
+            clients
+                .iter()
+                .map(|(server, client)| async move { (server, client.list_resources().await) }),
+        )
+        .await;
+
+        let mut resources = Vec::new();
+        for (server, listed) in listed {
+            match listed {
+                Ok(listed) => resources.extend(listed.into_iter().map(|mut resource| {
+                    resource.server = server.clone();
+                    resource
+                })),
+                // A server failing to list its resources doesn't hide the others
+                Err(error) => tracing::warn!(
+                    server = %server,
+                    error = %error,
+                    "Failed to list the MCP resources"
+                ),
+            }
+        }
+        resources.sort_by_key(McpResource::reference);
+        Ok(resources)
+    }
+
+    async fn read_resource(
+        &self,
+        server: &str,
+        uri: &str,
+    ) -> anyhow::Result<Vec<AttachmentContent>> {
+        self.init_mcp().await?;
+        let client = self
+            .clients
+            .read()
+            .await
+            .get(server)
+            .cloned()
+            .context(format!("MCP server {server} not found"))?;
+        client.read_resource(uri).await
+    }
+
+    async fn subscribe_resource(
+        &self,
+        conversation_id: &ConversationId,
+        server: &str,
+        uri: &str,
+    ) -> anyhow::Result<()> {
+        self.init_mcp().await?;
+        let client = self
+            .clients
+            .read()
+            .await
+            .get(server)
+            .cloned()
+            .context(format!("MCP server {server} not found"))?;
+        client.subscribe_resource(uri).await?;
+        self.subscriptions
+            .write()
+            .await
+            .entry(conversation_id.clone())
+            .or_default()
+            .insert((server.to_string(), uri.to_string()));
+        Ok(())
+    }
+
+    async fn unsubscribe_resources(&self, conversation_id: &ConversationId) -> anyhow::Result<()> {
+        // The resources other conversations still follow stay subscribed
+        let unsubscribed = {
+            let mut subscriptions = self.subscriptions.write().await;
+            let removed = subscriptions.remove(conversation_id).unwrap_or_default();
+            removed
+                .into_iter()
+                .filter(|subscription| {
+                    !subscriptions
+                        .values()
+                        .any(|subscribed| subscribed.contains(subscription))
+                })
+                .collect::<Vec<_>>()
+        };
+        self.resource_updates.lock().await.remove(conversation_id);
+        let clients = self.clients.read().await.clone();
+        for (server, uri) in unsubscribed {
+            // The servers removed since forgot the subscription with the connection
+            let Some(client) = clients.get(&server) else {
+                continue;
+            };
+            if let Err(error) = client.unsubscribe_resource(&uri).await {
+                tracing::warn!(
+                    server = %server,
+                    uri = %uri,
+                    error = %error,
+                    "Failed to unsubscribe from the MCP resource"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    async fn take_resource_updates(
+        &self,
+        conversation_id: &ConversationId,
+    ) -> Vec<McpResourceUpdate> {
+        self.resource_updates
+            .lock()
+            .await
+            .remove(conversation_id)
+            .unwrap_or_default()
+    }
+}
+
+/// Queues the contents of the changed resource for the conversations following
+/// it, replacing the ones of the resource that weren't taken yet
+async fn read_resource_update<C: McpClientInfra>(
+    client: &C,
+    server_name: &str,
+    uri: String,
+    conversations: &[ConversationId],
+    resource_updates: &Mutex<HashMap<ConversationId, Vec<McpResourceUpdate>>>,
+) {
+    match client.read_resource(&uri).await {
+        Ok(contents) => {
+            let update = McpResourceUpdate { server: server_name.to_string(), uri, contents };
+            let mut resource_updates = resource_updates.lock().await;
+            for conversation_id in conversations {
+                let updates = resource_updates.entry(conversation_id.clone()).or_default();
+                updates.retain(|pending| pending.reference() != update.reference());
+                updates.push(update.clone());
+            }
+        }
+        Err(error) => tracing::warn!(
+            server = %server_name,
+            uri = %uri,
+            error = %error,
+            "Failed to read the changed MCP resource"
+        ),
+    }
+}
+
+/// Replaces the tools of the server with the ones it lists, leaving out the
+/// ones its configuration doesn't offer to the model
+async fn insert_clients<C: McpClientInfra>(
+    tools: &ToolMap<C>,
+    server_name: &str,
+    filter: &McpToolFilter,
+    client: Arc<C>,
+) -> anyhow::Result<()> {
+    let listed = client
+        .list()
+        .await?
+        .into_iter()
+        .filter(|tool| filter.allows(tool.name.as_str()));
+
+    let mut tool_map = tools.write().await;
+    tool_map.retain(|name, _| !is_mcp_tool_of(name, server_name));
+
+    for mut tool in listed {
+        let server = McpExecutor::new(tool.name.clone(), client.clone())?;
+        // Generate a unique name for the tool
+        let tool_name = mcp_tool_name(server_name, tool.name.as_str());
+        tool.name = tool_name.clone();
+        tool_map.insert(
+            tool_name,
+            ToolHolder { definition: tool, executable: server },
+        );
     }
+
+    Ok(())
 }
 
 #[async_trait::async_trait]
@@ -137,7 +510,55 @@ where
         self.list().await
     }
 
-    async fn call(&self, call: ToolCallFull) -> anyhow::Result<ToolOutput> {
-        self.call(call).await
+    async fn call(
+        &self,
+        call: ToolCallFull,
+        sampling: mpsc::UnboundedSender<McpSampling>,
+    ) -> anyhow::Result<ToolOutput> {
+        self.call(call, sampling).await
+    }
+
+    async fn list_prompts(&self) -> anyhow::Result<Vec<McpPrompt>> {
+        self.list_prompts().await
+    }
+
+    async fn get_prompt(
+        &self,
+        name: &str,
+        arguments: BTreeMap<String, String>,
+    ) -> anyhow::Result<String> {
+        self.get_prompt(name, arguments).await
+    }
+
+    async fn list_resources(&self) -> anyhow::Result<Vec<McpResource>> {
+        self.list_resources().await
+    }
+
+    async fn read_resource(
+        &self,
+        server: &str,
+        uri: &str,
+    ) -> anyhow::Result<Vec<AttachmentContent>> {
+        self.read_resource(server, uri).await
+    }
+
+    async fn subscribe_resource(
+        &self,
+        conversation_id: &ConversationId,
+        server: &str,
+        uri: &str,
+    ) -> anyhow::Result<()> {
+        self.subscribe_resource(conversation_id, server, uri).await
+    }
+
+    async fn unsubscribe_resources(&self, conversation_id: &ConversationId) -> anyhow::Result<()> {
+        self.unsubscribe_resources(conversation_id).await
+    }
+
+    async fn take_resource_updates(
+        &self,
+        conversation_id: &ConversationId,
+    ) -> Vec<McpResourceUpdate> {
+        self.take_resource_updates(conversation_id).await
     }
 }
@@ -0,0 +1,37 @@
+use std::sync::Arc;
+
+use anyhow::bail;
+use bytes::Bytes;
+use forge_app::domain::{ModerationRequest, ModerationVerdict};
+use forge_app::ModerationService;
+
+use crate::HttpInfra;
+
+/// Asks a moderation service of the deployment for verdicts on the responses
+/// of the models
+pub struct ForgeModerationService<I> {
+    infra: Arc<I>,
+}
+
+impl<I> ForgeModerationService<I> {
+    pub fn new(infra: Arc<I>) -> Self {
+        Self { infra }
+    }
+}
+
+#[async_trait::async_trait]
+impl<I: HttpInfra> ModerationService for ForgeModerationService<I> {
+    async fn moderate(
+        &self,
+        endpoint: &str,
+        request: &ModerationRequest<'_>,
+    ) -> anyhow::Result<ModerationVerdict> {
+        let body = Bytes::from(serde_json::to_vec(request)?);
+        let response = self.infra.post(endpoint, body).await?;
+        if !response.status().is_success() {
+            bail!("Moderation service responded with {}", response.status())
+        }
+
+        Ok(serde_json::from_slice(&response.bytes().await?)?)
+    }
+}
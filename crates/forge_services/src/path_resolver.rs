@@ -0,0 +1,361 @@
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::bail;
+use forge_app::PathResolverService;
+
+use crate::{EnvironmentInfra, FileInfoInfra};
+
+/// Maximum number of similar paths suggested for a missing path
+const MAX_SIMILAR_PATHS: usize = 3;
+
+/// Resolves the paths supplied to the tools against the workspace, so that
+/// every tool accepts the same forms of paths and reports the missing ones
+/// with the same suggestions.
+pub struct ForgePathResolver<F> {
+    infra: Arc<F>,
+}
+
+impl<F> ForgePathResolver<F> {
+    pub fn new(infra: Arc<F>) -> Self {
+        Self { infra }
+    }
+}
+
+#[async_trait::async_trait]
+impl<F: EnvironmentInfra + FileInfoInfra> PathResolverService for ForgePathResolver<F> {
+    async fn resolve_path(&self, path: &str) -> anyhow::Result<PathBuf> {
+        let env = self.infra.get_environment();
+        let resolved = resolve(path, &env.cwd, env.home.as_deref())?;
+
+        // Outputs of the tools too large to be returned are written to the
+        // scratch directory of the conversation to be read back
+        let mut roots = vec![env.cwd.clone(), env.scratch_root()];
+        roots.extend(env.external_paths);
+        if !is_accessible(&resolved, &roots).await? {
+            bail!(
+                "Path {path} is outside of the workspace {}, symbolic links included. Directories outside of the workspace can be allowed with FORGE_EXTERNAL_PATHS",
+                env.cwd.display()
+            )
+        }
+        Ok(resolved)
+    }
+
+    async fn similar_paths(&self, path: &Path) -> anyhow::Result<Vec<PathBuf>> {
+        // The first missing component is the one that is likely misspelled
+        let mut missing = path;
+        let dir = loop {
+            let Some(parent) = missing.parent() else {
+                return Ok(Vec::new());
+            };
+            if self.infra.exists(parent).await? {
+                break parent;
+            }
+            missing = parent;
+        };
+        let Some(name) = missing.file_name().and_then(|name| name.to_str()) else {
+            return Ok(Vec::new());
+        };
+        let rest = path.strip_prefix(missing)?;
+
+        let names = self.infra.list_dir(dir).await?;
+        let mut similar = Vec::new();
+        for name in closest_names(name, &names) {
+            let candidate = dir.join(name);
+            // Keep the rest of the path when it exists under the candidate
+            let full = candidate.join(rest);
+            if rest.as_os_str().is_empty() || !self.infra.exists(&full).await? {
+                similar.push(candidate);
+            } else {
+                similar.push(full);
+            }
+        }
+        Ok(similar)
+    }
+}
+
+/// Makes `path` absolute: `~` is the home directory and relative paths are
+/// resolved against the workspace `cwd`.
+fn resolve(path: &str, cwd: &Path, home: Option<&Path>) -> anyhow::Result<PathBuf> {
+    let trimmed = path.trim();
+    if trimmed.is_empty() {
+        bail!(
+            "Path is empty. Provide the path of a file or directory of the workspace {}",
+            cwd.display()
+        )
+    }
+
+    let joined = match (trimmed.strip_prefix('~'), home) {
+        (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with('/') => {
+            home.join(rest.trim_start_matches('/'))
+        }
+        _ => cwd.join(trimmed),
+    };
+    Ok(normalize(&joined))
+}
+
+/// Whether `path` is in one of the `roots` once the symbolic links of both
+/// are followed
+async fn is_accessible(path: &Path, roots: &[PathBuf]) -> anyhow::Result<bool> {
+    let real = real_path(path).await?;
+    for root in roots {
+        let root = real_path(root).await.unwrap_or_else(|_| root.clone());
+        if real.starts_with(root) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Follows the symbolic links of an absolute path. The components that don't
+/// exist yet are appended to their nearest existing ancestor, so that a file
+/// created under a link is checked where it would actually be written.
+async fn real_path(path: &Path) -> anyhow::Result<PathBuf> {
+    let mut missing = Vec::new();
+    let mut current = path;
+    loop {
+        if let Ok(real) = tokio::fs::canonicalize(current).await {
+            let real = missing
+                .into_iter()
+                .rev()
+                .fold(real, |real, name| real.join(name));
+            return Ok(real);
+        }
+        // A link to a missing target would be followed when writing to it
+        if tokio::fs::symlink_metadata(current).await.is_ok() {
+            bail!(
+                "Path {} is a symbolic link whose target can't be resolved",
+                current.display()
+            )
+        }
+        let (Some(parent), Some(name)) = (current.parent(), current.file_name()) else {
+            return Ok(path.to_path_buf());
+        };
+        missing.push(name);
+        current = parent;
+    }
+}
+
+/// Removes the `.` and `..` components without touching the file system, so
+/// that paths that don't exist yet can be resolved
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// Names close to `name` ignoring case, the closest first
+fn closest_names<'a>(name: &str, names: &'a [String]) -> Vec<&'a str> {
+    let name = name.to_lowercase();
+    let max_distance = (name.chars().count() / 3).max(1);
+    let mut closest = names
+        .iter()
+        .map(|candidate| {
+            let distance = distance(&name, &candidate.to_lowercase());
+            (distance, candidate.as_str())
+        })
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect::<Vec<_>>();
+    closest.sort();
+    closest
+        .into_iter()
+        .take(MAX_SIMILAR_PATHS)
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+/// Edit distance between two strings, swapping two adjacent characters
+/// counting as a single edit
+fn distance(a: &str, b: &str) -> usize {
+    let (a, b) = (a.chars().collect::<Vec<_>>(), b.chars().collect::<Vec<_>>());
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1)
+                .min(distances[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+    distances[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_resolve() {
+        let cwd = Path::new("/home/user/project");
+        let home = Some(Path::new("/home/user"));
+        let fixture = [
+            "src/main.rs",
+            "./src/../Cargo.toml",
+            "/etc/hosts",
+            "~/.gitconfig",
+            "/home/user/project/src/./lib.rs",
+            "../other/notes.txt",
+        ];
+
+        let actual = fixture
+            .iter()
+            .map(|path| resolve(path, cwd, home).unwrap())
+            .collect::<Vec<_>>();
+
+        let expected = [
+            "/home/user/project/src/main.rs",
+            "/home/user/project/Cargo.toml",
+            "/etc/hosts",
+            "/home/user/.gitconfig",
+            "/home/user/project/src/lib.rs",
+            "/home/user/other/notes.txt",
+        ]
+        .map(PathBuf::from)
+        .to_vec();
+        assert_eq!(actual, expected);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_is_accessible() {
+        let workspace = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        let root = workspace.path();
+        std::os::unix::fs::symlink(outside.path(), root.join("link")).unwrap();
+        std::os::unix::fs::symlink(outside.path().join("gone"), root.join("dangling")).unwrap();
+        let roots = [root.to_path_buf()];
+
+        let actual = (
+            is_accessible(&root.join("src/new.rs"), &roots)
+                .await
+                .unwrap(),
+            is_accessible(&root.join("link/new/file.txt"), &roots)
+                .await
+                .unwrap(),
+            is_accessible(&root.join("dangling"), &roots).await.is_err(),
+        );
+
+        let expected = (true, false, true);
+        assert_eq!(actual, expected);
+    }
+
+    /// A file system holding the paths, with their parent directories
+    struct MockInfra {
+        paths: Vec<PathBuf>,
+    }
+
+    impl EnvironmentInfra for MockInfra {
+        fn get_environment(&self) -> forge_app::domain::Environment {
+            unimplemented!()
+        }
+
+        fn get_env_var(&self, _key: &str) -> Option<String> {
+            None
+        }
+
+        fn get_env_vars(&self) -> Vec<(String, String)> {
+            Vec::new()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl FileInfoInfra for MockInfra {
+        async fn is_file(&self, path: &Path) -> anyhow::Result<bool> {
+            Ok(self.paths.iter().any(|file| file == path))
+        }
+
+        async fn exists(&self, path: &Path) -> anyhow::Result<bool> {
+            Ok(self.paths.iter().any(|file| file.starts_with(path)))
+        }
+
+        async fn file_size(&self, _path: &Path) -> anyhow::Result<u64> {
+            unimplemented!()
+        }
+
+        async fn modified(&self, _path: &Path) -> anyhow::Result<std::time::SystemTime> {
+            unimplemented!()
+        }
+
+        async fn list_dir(&self, path: &Path) -> anyhow::Result<Vec<String>> {
+            let mut names = self
+                .paths
+                .iter()
+                .filter_map(|file| file.strip_prefix(path).ok()?.components().next())
+                .map(|name| name.as_os_str().to_string_lossy().to_string())
+                .collect::<Vec<_>>();
+            names.dedup();
+            Ok(names)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_similar_paths() {
+        let fixture = ForgePathResolver::new(Arc::new(MockInfra {
+            paths: [
+                "/project/src/main.rs",
+                "/project/Cargo.toml",
+                "/project/tests/cli.rs",
+            ]
+            .map(PathBuf::from)
+            .to_vec(),
+        }));
+
+        let actual = (
+            fixture
+                .similar_paths(Path::new("/project/scr/main.rs"))
+                .await
+                .unwrap(),
+            fixture
+                .similar_paths(Path::new("/project/cargo.toml"))
+                .await
+                .unwrap(),
+            fixture
+                .similar_paths(Path::new("/project/docs/guide.md"))
+                .await
+                .unwrap(),
+        );
+
+        let expected = (
+            vec![PathBuf::from("/project/src/main.rs")],
+            vec![PathBuf::from("/project/Cargo.toml")],
+            vec![],
+        );
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_closest_names() {
+        let fixture = ["src", "Cargo.toml", "README.md", "tests", "scripts"]
+            .map(String::from)
+            .to_vec();
+
+        let actual = (
+            closest_names("scr", &fixture),
+            closest_names("cargo.toml", &fixture),
+            closest_names("docs", &fixture),
+        );
+
+        let expected = (vec!["src"], vec!["Cargo.toml"], vec![]);
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,40 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use forge_app::domain::ProjectMemory;
+use forge_app::ProjectMemoryService;
+
+use crate::{EnvironmentInfra, FileInfoInfra, FileReaderInfra, FileWriterInfra};
+
+pub struct ForgeProjectMemoryService<I> {
+    infra: Arc<I>,
+}
+
+impl<I: FileReaderInfra + FileWriterInfra + FileInfoInfra + EnvironmentInfra>
+    ForgeProjectMemoryService<I>
+{
+    pub fn new(infra: Arc<I>) -> Self {
+        Self { infra }
+    }
+}
+
+#[async_trait::async_trait]
+impl<I: FileReaderInfra + FileWriterInfra + FileInfoInfra + EnvironmentInfra> ProjectMemoryService
+    for ForgeProjectMemoryService<I>
+{
+    async fn read_project_memory(&self) -> anyhow::Result<ProjectMemory> {
+        let path = self.infra.get_environment().project_memory();
+        if !self.infra.exists(&path).await? {
+            return Ok(ProjectMemory::default());
+        }
+        let content = self.infra.read_utf8(&path).await?;
+        Ok(ProjectMemory::parse(&content))
+    }
+
+    async fn write_project_memory(&self, memory: &ProjectMemory) -> anyhow::Result<()> {
+        let path = self.infra.get_environment().project_memory();
+        self.infra
+            .write(&path, Bytes::from(memory.to_string()), false)
+            .await
+    }
+}
@@ -3,34 +3,50 @@ use std::sync::Arc;
 use anyhow::{Context, Result};
 use forge_app::domain::{
     ChatCompletionMessage, Context as ChatContext, HttpConfig, Model, ModelId, Provider,
-    ResultStream, RetryConfig,
+    ResultStream, RetryConfig, UsageLedger,
 };
 use forge_app::ProviderService;
 use forge_provider::Client;
 use tokio::sync::Mutex;
 
-use crate::EnvironmentInfra;
+use crate::response_cache::ResponseCache;
+use crate::usage::UsageAccounting;
+use crate::{EnvironmentInfra, FileInfoInfra, FileReaderInfra, FileWriterInfra};
 
 #[derive(Clone)]
-pub struct ForgeProviderService {
+pub struct ForgeProviderService<F> {
     retry_config: Arc<RetryConfig>,
     cached_client: Arc<Mutex<Option<Client>>>,
     cached_models: Arc<Mutex<Option<Vec<Model>>>>,
     version: String,
     timeout_config: HttpConfig,
+    /// Cache of the responses, when enabled
+    response_cache: Option<Arc<ResponseCache<F>>>,
+    usage: Arc<UsageAccounting<F>>,
 }
 
-impl ForgeProviderService {
-    pub fn new<I: EnvironmentInfra>(infra: Arc<I>) -> Self {
+impl<F: EnvironmentInfra> ForgeProviderService<F> {
+    pub fn new(infra: Arc<F>) -> Self {
         let env = infra.get_environment();
         let version = env.version();
-        let retry_config = Arc::new(env.retry_config);
+        let retry_config = Arc::new(env.retry_config.clone());
+        let response_cache = env
+            .response_cache
+            .then(|| Arc::new(ResponseCache::new(infra.clone(), env.response_cache_path())));
+        let usage = Arc::new(UsageAccounting::new(
+            infra,
+            env.usage_path(),
+            env.quota.clone(),
+            env.cwd.clone(),
+        ));
         Self {
             retry_config,
             cached_client: Arc::new(Mutex::new(None)),
             cached_models: Arc::new(Mutex::new(None)),
             version,
             timeout_config: env.http,
+            response_cache,
+            usage,
         }
     }
 
@@ -57,19 +73,38 @@ impl ForgeProviderService {
 }
 
 #[async_trait::async_trait]
-impl ProviderService for ForgeProviderService {
+impl<F: EnvironmentInfra + FileReaderInfra + FileWriterInfra + FileInfoInfra + 'static>
+    ProviderService for ForgeProviderService<F>
+{
     async fn chat(
         &self,
         model: &ModelId,
         request: ChatContext,
         provider: Provider,
     ) -> ResultStream<ChatCompletionMessage, anyhow::Error> {
+        let account = UsageAccounting::<F>::account(provider.key());
+        self.usage.check(&account).await?;
         let client = self.client(provider).await?;
 
-        client
+        let Some(cache) = self.response_cache.as_ref() else {
+            let stream = client
+                .chat(model, request)
+                .await
+                .with_context(|| format!("Failed to chat with model: {model}"))?;
+            return Ok(self.usage.record(&account, stream));
+        };
+
+        let key = ResponseCache::<F>::key(model, &client.payload(model, request.clone())?);
+        if let Some(messages) = cache.get(&key).await {
+            tracing::debug!(model = %model, key = %key, "Replaying cached response");
+            return Ok(Box::pin(tokio_stream::iter(messages.into_iter().map(Ok))));
+        }
+
+        let stream = client
             .chat(model, request)
             .await
-            .with_context(|| format!("Failed to chat with model: {model}"))
+            .with_context(|| format!("Failed to chat with model: {model}"))?;
+        Ok(cache.record(&key, self.usage.record(&account, stream)))
     }
 
     async fn models(&self, provider: Provider) -> Result<Vec<Model>> {
@@ -93,4 +128,18 @@ impl ProviderService for ForgeProviderService {
 
         Ok(models)
     }
+
+    async fn payload(
+        &self,
+        model: &ModelId,
+        request: ChatContext,
+        provider: Provider,
+    ) -> Result<serde_json::Value> {
+        let client = self.client(provider).await?;
+        client.payload(model, request)
+    }
+
+    async fn usage(&self) -> Result<UsageLedger> {
+        self.usage.read().await
+    }
 }
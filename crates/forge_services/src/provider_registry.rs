@@ -21,6 +21,15 @@ impl<F: EnvironmentInfra> ForgeProviderRegistry<F> {
         Self { infra, cache: Arc::new(Default::default()) }
     }
 
+    /// Returns a registry that always resolves to the given provider instead
+    /// of detecting it from the app config or environment.
+    pub fn with_provider(&self, provider: Provider) -> Self {
+        Self {
+            infra: self.infra.clone(),
+            cache: Arc::new(RwLock::new(Some(provider))),
+        }
+    }
+
     fn provider_url(&self) -> Option<ProviderUrl> {
         if let Some(url) = self.infra.get_env_var("OPENAI_URL") {
             return Some(ProviderUrl::OpenAI(url));
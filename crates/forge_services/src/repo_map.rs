@@ -0,0 +1,479 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, HashMap};
+
+/// Probability of following an import rather than jumping to a random file
+const DAMPING: f64 = 0.85;
+const ITERATIONS: usize = 30;
+
+/// Orders files by their PageRank over the graph of imports between them, so
+/// that the files the rest of the project depends on come first. Files with
+/// the same rank are ordered by path.
+///
+/// `files` pairs the path of each file, relative to the project root, with the
+/// modules it imports as returned by [`crate::tool_services::syn::imports`].
+/// Imports that don't resolve to one of the files, like third-party packages,
+/// are ignored.
+pub fn rank(files: Vec<(String, Vec<String>)>) -> Vec<String> {
+    let index = Index::new(files.iter().map(|(path, _)| path.as_str()));
+    let edges = files
+        .iter()
+        .enumerate()
+        .map(|(file, (path, imports))| {
+            let mut targets = imports
+                .iter()
+                .flat_map(|import| index.resolve(path, import))
+                .collect::<BTreeSet<_>>();
+            targets.remove(&file);
+            targets.into_iter().collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut ranked = files
+        .into_iter()
+        .map(|(path, _)| path)
+        .zip(pagerank(&edges))
+        .collect::<Vec<_>>();
+    ranked.sort_by(|(a, a_score), (b, b_score)| b_score.total_cmp(a_score).then_with(|| a.cmp(b)));
+    ranked.into_iter().map(|(path, _)| path).collect()
+}
+
+fn pagerank(edges: &[Vec<usize>]) -> Vec<f64> {
+    let size = edges.len() as f64;
+    let mut scores = vec![1.0 / size; edges.len()];
+
+    for _ in 0..ITERATIONS {
+        // Files without imports spread their score over every file
+        let dangling = edges
+            .iter()
+            .zip(&scores)
+            .filter(|(targets, _)| targets.is_empty())
+            .map(|(_, score)| score)
+            .sum::<f64>();
+
+        let mut next = vec![(1.0 - DAMPING + DAMPING * dangling) / size; edges.len()];
+        for (targets, score) in edges.iter().zip(&scores) {
+            if targets.is_empty() {
+                continue;
+            }
+            let share = DAMPING * score / targets.len() as f64;
+            for target in targets {
+                next[*target] += share;
+            }
+        }
+        scores = next;
+    }
+
+    scores
+}
+
+/// Where an import may point to, relative to the project root
+enum Target {
+    /// The module at exactly this path
+    Module(String),
+    /// The module whose path ends with this one
+    ModuleSuffix(String),
+    /// The files of the directory whose path ends with this one
+    PackageSuffix(String),
+}
+
+struct Index {
+    modules: Lookup,
+    packages: Lookup,
+}
+
+impl Index {
+    fn new<'a>(paths: impl Iterator<Item = &'a str>) -> Self {
+        let mut modules = Lookup::default();
+        let mut packages = Lookup::default();
+        for (file, path) in paths.enumerate() {
+            if path.ends_with('/') {
+                continue;
+            }
+            modules.insert(module(path), file);
+            if !parent(path).is_empty() {
+                packages.insert(parent(path).to_string(), file);
+            }
+        }
+        Self { modules, packages }
+    }
+
+    /// Files imported by `import` in the file at `from`. Each path an import
+    /// expands to lists its targets from the most to the least specific, and
+    /// resolves to the first one that exists.
+    fn resolve(&self, from: &str, import: &str) -> Vec<usize> {
+        targets(from, import)
+            .into_iter()
+            .filter_map(|targets| {
+                targets.into_iter().find_map(|target| match target {
+                    Target::Module(key) => self.modules.get(&key),
+                    Target::ModuleSuffix(suffix) => self.modules.find(&suffix, from),
+                    Target::PackageSuffix(suffix) => self.packages.find(&suffix, from),
+                })
+            })
+            .flatten()
+            .copied()
+            .collect()
+    }
+}
+
+/// Files by key, with the keys also indexed by their last segment to look
+/// them up by suffix
+#[derive(Default)]
+struct Lookup {
+    files: HashMap<String, Vec<usize>>,
+    keys: HashMap<String, Vec<String>>,
+}
+
+impl Lookup {
+    fn insert(&mut self, key: String, file: usize) {
+        let files = self.files.entry(key.clone()).or_default();
+        if files.is_empty() {
+            let name = key.rsplit('/').next().unwrap_or_default().to_string();
+            self.keys.entry(name).or_default().push(key);
+        }
+        files.push(file);
+    }
+
+    fn get(&self, key: &str) -> Option<&[usize]> {
+        self.files.get(key).map(Vec::as_slice)
+    }
+
+    /// Files of the key that ends with `suffix`, preferring the one closest to
+    /// `from` when there are several
+    fn find(&self, suffix: &str, from: &str) -> Option<&[usize]> {
+        let name = suffix.rsplit('/').next()?;
+        let key = self
+            .keys
+            .get(name)?
+            .iter()
+            .filter(|key| *key == suffix || key.ends_with(&format!("/{suffix}")))
+            .max_by_key(|key| (common_prefix(key, from), Reverse(key.as_str())))?;
+        self.get(key)
+    }
+}
+
+fn common_prefix(a: &str, b: &str) -> usize {
+    a.split('/')
+        .zip(b.split('/'))
+        .take_while(|(a, b)| a == b)
+        .count()
+}
+
+/// Module path of a file, without its extension or the file name a language
+/// uses for the module of a directory, e.g. `src/utils` for
+/// `src/utils/mod.rs`
+fn module(path: &str) -> String {
+    let (stem, ext) = match path.rsplit_once('.') {
+        Some((stem, ext)) if !ext.contains('/') => (stem, ext),
+        _ => (path, ""),
+    };
+    let names: &[&str] = match ext {
+        "rs" => &["mod", "lib", "main"],
+        "py" => &["__init__"],
+        "js" | "jsx" | "ts" | "tsx" | "mjs" | "cjs" => &["index"],
+        _ => &[],
+    };
+
+    let stem = format!("/{stem}");
+    let stem = names
+        .iter()
+        .find_map(|name| stem.strip_suffix(&format!("/{name}")))
+        .unwrap_or(&stem);
+    stem.trim_start_matches('/').to_string()
+}
+
+fn parent(path: &str) -> &str {
+    path.rsplit_once('/')
+        .map(|(dir, _)| dir)
+        .unwrap_or_default()
+}
+
+/// Joins `segments` to `base`, resolving `.` and `..`
+fn join(base: &str, segments: &[&str]) -> String {
+    let mut path = base
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>();
+    for segment in segments {
+        match *segment {
+            "" | "." => {}
+            ".." => {
+                path.pop();
+            }
+            segment => path.push(segment),
+        }
+    }
+    path.join("/")
+}
+
+fn targets(from: &str, import: &str) -> Vec<Vec<Target>> {
+    match from.rsplit_once('.').map(|(_, ext)| ext) {
+        Some("rs") => rust(from, import),
+        Some("py") => python(from, import),
+        Some("js" | "jsx" | "ts" | "tsx" | "mjs" | "cjs") => javascript(from, import),
+        Some("go") => go(import),
+        Some("java") => java(import),
+        _ => Vec::new(),
+    }
+}
+
+fn rust(from: &str, import: &str) -> Vec<Vec<Target>> {
+    // Directory of the module defined by `from`
+    let module_dir = match from.rsplit('/').next() {
+        Some("mod.rs" | "lib.rs" | "main.rs") => parent(from).to_string(),
+        _ => module(from),
+    };
+    let dirs = parent(from).split('/').collect::<Vec<_>>();
+    let crate_root = match dirs.iter().rposition(|dir| *dir == "src") {
+        Some(src) => dirs[..=src].join("/"),
+        None => parent(from).to_string(),
+    };
+
+    expand(import)
+        .into_iter()
+        .filter_map(|path| {
+            let segments = path.split("::").map(str::trim).collect::<Vec<_>>();
+            let supers = segments.iter().take_while(|s| **s == "super").count();
+            let (base, rest) = match segments.as_slice() {
+                [] => return None,
+                ["crate", rest @ ..] => (crate_root.clone(), rest),
+                ["self", rest @ ..] => (module_dir.clone(), rest),
+                _ if supers > 0 => (
+                    join(&module_dir, &[".."].repeat(supers)),
+                    &segments[supers..],
+                ),
+                [name, rest @ ..] => {
+                    // Another crate of the workspace, in a directory named after it
+                    let names = BTreeSet::from([name.to_string(), name.replace('_', "-")]);
+                    let targets = names
+                        .into_iter()
+                        .flat_map(|name| {
+                            prefixes(rest).map(move |rest| {
+                                Target::ModuleSuffix(join(&format!("{name}/src"), rest))
+                            })
+                        })
+                        .collect();
+                    return Some(targets);
+                }
+            };
+            Some(
+                prefixes(rest)
+                    .map(|rest| Target::Module(join(&base, rest)))
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+/// Prefixes of `segments`, from the longest to the empty one
+fn prefixes<'a>(segments: &'a [&'a str]) -> impl Iterator<Item = &'a [&'a str]> {
+    (0..=segments.len()).rev().map(move |len| &segments[..len])
+}
+
+/// Expands the groups of a Rust use tree into one path per imported item, e.g.
+/// `a::{b, c::{d, self}}` into `a::b`, `a::c::d` and `a::c`
+fn expand(tree: &str) -> Vec<String> {
+    let tree = tree.trim();
+    let Some(open) = tree.find('{') else {
+        let path = tree.split(" as ").next().unwrap_or_default().trim();
+        let path = path.trim_end_matches("::*").trim_end_matches("::self");
+        return vec![path.to_string()];
+    };
+
+    let prefix = &tree[..open];
+    let mut depth = 0;
+    let mut start = open + 1;
+    let mut paths = Vec::new();
+    for (i, c) in tree.char_indices().skip(open) {
+        match c {
+            '{' => depth += 1,
+            '}' | ',' if depth == 1 => {
+                let item = tree[start..i].trim();
+                if !item.is_empty() {
+                    paths.extend(expand(&format!("{prefix}{item}")));
+                }
+                start = i + 1;
+                if c == '}' {
+                    break;
+                }
+            }
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    paths
+}
+
+fn python(from: &str, import: &str) -> Vec<Vec<Target>> {
+    let dots = import.chars().take_while(|c| *c == '.').count();
+    let segments = import[dots..]
+        .split('.')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>();
+
+    let targets = if dots > 0 {
+        let base = join(parent(from), &[".."].repeat(dots - 1));
+        prefixes(&segments)
+            .map(|segments| Target::Module(join(&base, segments)))
+            .collect()
+    } else {
+        (1..=segments.len())
+            .rev()
+            .map(|len| Target::ModuleSuffix(segments[..len].join("/")))
+            .collect()
+    };
+    vec![targets]
+}
+
+fn javascript(from: &str, import: &str) -> Vec<Vec<Target>> {
+    // Only relative imports point to files of the project
+    if !import.starts_with('.') {
+        return Vec::new();
+    }
+    let path = join(parent(from), &import.split('/').collect::<Vec<_>>());
+    vec![vec![Target::Module(module(&path))]]
+}
+
+fn go(import: &str) -> Vec<Vec<Target>> {
+    // The module path prefixes the directory of the package in the project
+    let segments = import.split('/').collect::<Vec<_>>();
+    let targets = (0..segments.len())
+        .map(|start| Target::PackageSuffix(segments[start..].join("/")))
+        .collect();
+    vec![targets]
+}
+
+fn java(import: &str) -> Vec<Vec<Target>> {
+    if let Some(package) = import.strip_suffix(".*") {
+        return vec![vec![Target::PackageSuffix(package.replace('.', "/"))]];
+    }
+    let segments = import.split('.').collect::<Vec<_>>();
+    let targets = (1..=segments.len())
+        .rev()
+        .map(|len| Target::ModuleSuffix(segments[..len].join("/")))
+        .collect();
+    vec![targets]
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn fixture(files: &[(&str, &[&str])]) -> Vec<(String, Vec<String>)> {
+        files
+            .iter()
+            .map(|(path, imports)| {
+                (
+                    path.to_string(),
+                    imports.iter().map(|i| i.to_string()).collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_rank_rust_crate() {
+        let fixture = fixture(&[
+            ("Cargo.toml", &[]),
+            ("src/app.rs", &["crate::{domain::Agent, utils::*}"]),
+            ("src/domain/agent.rs", &["super::Model"]),
+            ("src/domain/mod.rs", &["self::agent", "self::model"]),
+            ("src/domain/model.rs", &[]),
+            ("src/main.rs", &["self::app", "self::domain", "self::utils"]),
+            ("src/utils.rs", &["crate::domain::model::Model"]),
+        ]);
+
+        let actual = rank(fixture);
+
+        let expected = vec![
+            "src/domain/model.rs",
+            "src/domain/mod.rs",
+            "src/domain/agent.rs",
+            "src/utils.rs",
+            "src/app.rs",
+            "Cargo.toml",
+            "src/main.rs",
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_rank_without_imports_is_alphabetical() {
+        let fixture = fixture(&[("b.md", &[]), ("a/", &[]), ("a/c.txt", &[])]);
+
+        let actual = rank(fixture);
+
+        let expected = vec!["a/", "a/c.txt", "b.md"];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_rank_ignores_unresolved_imports() {
+        let fixture = fixture(&[
+            ("app/main.py", &["os.path", ".models", "app.views"]),
+            ("app/models.py", &[]),
+            ("app/views.py", &["numpy", ".models"]),
+            ("web/index.ts", &["react", "../app/models"]),
+        ]);
+
+        let actual = rank(fixture);
+
+        let expected = vec![
+            "app/models.py",
+            "app/views.py",
+            "app/main.py",
+            "web/index.ts",
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_resolve_workspace_crate() {
+        let index = Index::new(
+            [
+                "crates/forge_app/src/app.rs",
+                "crates/forge_domain/src/lib.rs",
+            ]
+            .into_iter(),
+        );
+
+        let actual = index.resolve("crates/forge_app/src/app.rs", "forge_domain::Agent");
+
+        let expected = vec![1];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_resolve_go_package() {
+        let index = Index::new(["cmd/main.go", "pkg/util/a.go", "pkg/util/b.go"].into_iter());
+
+        let actual = index.resolve("cmd/main.go", "github.com/acme/tool/pkg/util");
+
+        let expected = vec![1, 2];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_expand_use_tree() {
+        let actual = expand("a::{b, c::{d, self}, e as f}");
+        let expected = vec!["a::b", "a::c::d", "a::c", "a::e"];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_module() {
+        let actual = [
+            "src/utils/mod.rs",
+            "src/lib.rs",
+            "pkg/__init__.py",
+            "web/index.ts",
+            "index.ts",
+            "README",
+        ]
+        .map(module);
+        let expected = ["src/utils", "src", "pkg", "web", "", "README"];
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,25 @@
+use std::sync::Arc;
+
+use forge_app::domain::ResourceGovernor;
+use forge_app::ResourceGovernorService;
+
+use crate::EnvironmentInfra;
+
+/// Holds the governor built from the limits of the environment, so that every
+/// conversation of the process shares the same semaphores.
+pub struct ForgeResourceGovernor {
+    governor: ResourceGovernor,
+}
+
+impl ForgeResourceGovernor {
+    pub fn new<F: EnvironmentInfra>(infra: Arc<F>) -> Self {
+        let limits = infra.get_environment().resource_limits;
+        Self { governor: ResourceGovernor::new(&limits) }
+    }
+}
+
+impl ResourceGovernorService for ForgeResourceGovernor {
+    fn governor(&self) -> ResourceGovernor {
+        self.governor.clone()
+    }
+}
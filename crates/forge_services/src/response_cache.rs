@@ -0,0 +1,139 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use forge_app::domain::{BoxStream, ChatCompletionMessage, ModelId};
+use futures::StreamExt;
+use sha2::{Digest, Sha256};
+
+use crate::{FileInfoInfra, FileReaderInfra, FileWriterInfra};
+
+/// Stores provider responses on disk so that identical requests, like
+/// scripted runs with a temperature of 0, are replayed instead of being sent
+/// to the provider again.
+pub struct ResponseCache<F> {
+    infra: Arc<F>,
+    path: PathBuf,
+}
+
+impl<F> ResponseCache<F> {
+    pub fn new(infra: Arc<F>, path: PathBuf) -> Self {
+        Self { infra, path }
+    }
+
+    /// Key of a request, derived from the model and the exact payload sent to
+    /// the provider, which includes the seed when one is set.
+    pub fn key(model: &ModelId, payload: &serde_json::Value) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(model.as_str().as_bytes());
+        hasher.update([0]);
+        hasher.update(payload.to_string().as_bytes());
+        format!("{:x}", hasher.finalize())
+    }
+
+    fn entry(&self, key: &str) -> PathBuf {
+        self.path.join(format!("{key}.json"))
+    }
+}
+
+impl<F: FileReaderInfra + FileWriterInfra + FileInfoInfra + 'static> ResponseCache<F> {
+    /// Returns the messages of the cached response, if there is one
+    pub async fn get(&self, key: &str) -> Option<Vec<ChatCompletionMessage>> {
+        let path = self.entry(key);
+        if !self.infra.exists(&path).await.ok()? {
+            return None;
+        }
+
+        let content = self.infra.read(&path).await.ok()?;
+        match serde_json::from_slice(&content) {
+            Ok(messages) => Some(messages),
+            Err(error) => {
+                tracing::warn!(path = %path.display(), error = %error, "Invalid cached response");
+                None
+            }
+        }
+    }
+
+    /// Passes the messages of `stream` through and caches them once the
+    /// response is complete. Responses that fail or aren't read to the end
+    /// are not cached.
+    pub fn record(
+        &self,
+        key: &str,
+        stream: BoxStream<ChatCompletionMessage, anyhow::Error>,
+    ) -> BoxStream<ChatCompletionMessage, anyhow::Error> {
+        let infra = self.infra.clone();
+        let path = Some(self.entry(key));
+
+        Box::pin(futures::stream::unfold(
+            (stream, Vec::new(), path),
+            move |(mut stream, mut messages, path)| {
+                let infra = infra.clone();
+                async move {
+                    match stream.next().await {
+                        Some(Ok(message)) => {
+                            messages.push(message.clone());
+                            Some((Ok(message), (stream, messages, path)))
+                        }
+                        Some(Err(error)) => Some((Err(error), (stream, messages, None))),
+                        None => {
+                            if let Some(path) = path {
+                                if let Err(error) = save(infra.as_ref(), &path, &messages).await {
+                                    tracing::warn!(error = ?error, "Failed to cache response");
+                                }
+                            }
+                            None
+                        }
+                    }
+                }
+            },
+        ))
+    }
+}
+
+async fn save<F: FileWriterInfra>(
+    infra: &F,
+    path: &std::path::Path,
+    messages: &[ChatCompletionMessage],
+) -> anyhow::Result<()> {
+    let content = serde_json::to_vec(messages)?;
+    infra.write(path, content.into(), false).await
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+
+    type Cache = ResponseCache<()>;
+
+    #[test]
+    fn test_key_is_stable() {
+        let model = ModelId::new("anthropic/claude-3.5-sonnet");
+        let payload = json!({"messages": [{"role": "user", "content": "hi"}], "temperature": 0});
+
+        let actual = Cache::key(&model, &payload);
+        let expected = Cache::key(
+            &model,
+            &json!({"temperature": 0, "messages": [{"content": "hi", "role": "user"}]}),
+        );
+
+        assert_eq!(actual, expected);
+        assert_eq!(actual.len(), 64);
+    }
+
+    #[test]
+    fn test_key_depends_on_model_and_seed() {
+        let model = ModelId::new("openai/gpt-4o");
+        let payload = json!({"messages": [], "seed": 1});
+
+        let actual = [
+            Cache::key(&ModelId::new("openai/gpt-4o-mini"), &payload),
+            Cache::key(&model, &json!({"messages": [], "seed": 2})),
+        ];
+
+        let expected = Cache::key(&model, &payload);
+        assert!(actual.iter().all(|key| *key != expected));
+    }
+}
@@ -0,0 +1,536 @@
+use std::collections::HashSet;
+use std::io::{Cursor, Read};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use bytes::Bytes;
+use flate2::read::GzDecoder;
+use forge_app::domain::{
+    archive_entry_path, ArchiveEntry, ArchiveEntryKind, ArchiveExtraction, ArchiveFormat, Error,
+    MAX_ARCHIVE_ENTRIES, MAX_ARCHIVE_SIZE, MAX_EXTRACTED_SIZE,
+};
+use forge_app::ArchiveService;
+
+use crate::{
+    FileDirectoryInfra, FileInfoInfra, FileReaderInfra, FileRemoverInfra, FileWriterInfra,
+};
+
+/// Lists and extracts zip and tar archives. Archives are decoded in memory and
+/// only written once all their entries were checked, so that a refused archive
+/// leaves nothing behind. An extraction failing while it's written is removed.
+pub struct ForgeArchive<F>(Arc<F>);
+
+impl<F> ForgeArchive<F> {
+    pub fn new(infra: Arc<F>) -> Self {
+        Self(infra)
+    }
+}
+
+/// Entries of an archive, with the content of its files when it was read
+struct Decoded {
+    format: ArchiveFormat,
+    entries: Vec<(ArchiveEntry, Vec<u8>)>,
+}
+
+/// Bytes of the extracted files still allowed
+struct Budget(u64);
+
+impl Budget {
+    /// Reads the content of an entry, failing once the archive is larger than
+    /// allowed whatever the sizes written in its headers
+    fn read(&mut self, reader: impl Read) -> anyhow::Result<Vec<u8>> {
+        let mut content = Vec::new();
+        reader.take(self.0 + 1).read_to_end(&mut content)?;
+        self.0 = self.0.checked_sub(content.len() as u64).ok_or_else(|| {
+            Error::ArchiveTooLarge(format!(
+                "more than {} MiB once extracted",
+                MAX_EXTRACTED_SIZE >> 20
+            ))
+        })?;
+        Ok(content)
+    }
+}
+
+/// File or directory written by an extraction, with the content of the files
+type Extracted = (PathBuf, ArchiveEntryKind, Vec<u8>);
+
+/// Refuses the archives with a path that is both a file and a directory, e.g. a
+/// file `bin` next to a file `bin/forge`, which can't be fully extracted
+fn check_conflicts(writes: &[Extracted], destination: &Path) -> anyhow::Result<()> {
+    let files = writes
+        .iter()
+        .filter(|(_, kind, _)| *kind == ArchiveEntryKind::File)
+        .map(|(target, _, _)| target.as_path())
+        .collect::<HashSet<_>>();
+    for (target, kind, _) in writes {
+        let dir = match kind {
+            ArchiveEntryKind::Directory => Some(target.as_path()),
+            _ => target.parent(),
+        };
+        let conflict = dir
+            .into_iter()
+            .flat_map(Path::ancestors)
+            .take_while(|dir| *dir != destination)
+            .find(|dir| files.contains(dir));
+        if let Some(conflict) = conflict {
+            let entry = conflict.strip_prefix(destination).unwrap_or(conflict);
+            return Err(Error::ConflictingArchiveEntry(entry.display().to_string()).into());
+        }
+    }
+    Ok(())
+}
+
+fn check_entries(count: usize) -> anyhow::Result<()> {
+    if count > MAX_ARCHIVE_ENTRIES {
+        return Err(
+            Error::ArchiveTooLarge(format!("more than {MAX_ARCHIVE_ENTRIES} entries")).into(),
+        );
+    }
+    Ok(())
+}
+
+/// Decodes the archive, reading the content of its files only when
+/// `with_content` is set
+fn decode(path: &str, bytes: &[u8], with_content: bool) -> anyhow::Result<Decoded> {
+    let format =
+        ArchiveFormat::detect(bytes).ok_or_else(|| Error::UnsupportedArchive(path.to_string()))?;
+    let mut budget = Budget(MAX_EXTRACTED_SIZE);
+    let mut entries = Vec::new();
+    match format {
+        ArchiveFormat::Zip => {
+            let mut archive = zip::ZipArchive::new(Cursor::new(bytes))?;
+            check_entries(archive.len())?;
+            for index in 0..archive.len() {
+                let mut file = archive.by_index(index)?;
+                let kind = if file.is_dir() {
+                    ArchiveEntryKind::Directory
+                } else if file.is_symlink() {
+                    ArchiveEntryKind::Link
+                } else {
+                    ArchiveEntryKind::File
+                };
+                let entry = ArchiveEntry { path: file.name().to_string(), kind, size: file.size() };
+                let content = match with_content && kind == ArchiveEntryKind::File {
+                    true => budget.read(&mut file)?,
+                    false => Vec::new(),
+                };
+                entries.push((entry, content));
+            }
+        }
+        ArchiveFormat::Tar | ArchiveFormat::TarGz => {
+            let reader: Box<dyn Read + '_> = match format {
+                ArchiveFormat::TarGz => Box::new(GzDecoder::new(bytes)),
+                _ => Box::new(bytes),
+            };
+            let mut archive = tar::Archive::new(reader);
+            for entry in archive.entries()? {
+                let mut entry = entry?;
+                let kind = match entry.header().entry_type() {
+                    tar::EntryType::Regular | tar::EntryType::Continuous => ArchiveEntryKind::File,
+                    tar::EntryType::Directory => ArchiveEntryKind::Directory,
+                    tar::EntryType::Symlink | tar::EntryType::Link => ArchiveEntryKind::Link,
+                    // Devices and pipes have no place in a release artifact
+                    _ => continue,
+                };
+                let path = entry.path()?.to_string_lossy().to_string();
+                let entry_info = ArchiveEntry { path, kind, size: entry.size() };
+                let content = match with_content && kind == ArchiveEntryKind::File {
+                    true => budget.read(&mut entry)?,
+                    false => Vec::new(),
+                };
+                entries.push((entry_info, content));
+                check_entries(entries.len())?;
+            }
+        }
+    }
+    Ok(Decoded { format, entries })
+}
+
+impl<F: FileReaderInfra + FileInfoInfra> ForgeArchive<F> {
+    async fn decode(&self, path: &Path, with_content: bool) -> anyhow::Result<Decoded> {
+        if self.0.file_size(path).await? > MAX_ARCHIVE_SIZE {
+            return Err(Error::ArchiveTooLarge(format!(
+                "the archive is larger than {} MiB",
+                MAX_ARCHIVE_SIZE >> 20
+            ))
+            .into());
+        }
+        let bytes = self.0.read(path).await?;
+        let name = path.display().to_string();
+        tokio::task::spawn_blocking(move || decode(&name, &bytes, with_content)).await?
+    }
+}
+
+impl<F: FileWriterInfra + FileDirectoryInfra> ForgeArchive<F> {
+    async fn write_entries(
+        &self,
+        writes: Vec<Extracted>,
+        extraction: &mut ArchiveExtraction,
+    ) -> anyhow::Result<()> {
+        for (target, kind, content) in writes {
+            if kind == ArchiveEntryKind::Directory {
+                self.0.create_dirs(&target).await?;
+                continue;
+            }
+            if let Some(parent) = target.parent() {
+                self.0.create_dirs(parent).await?;
+            }
+            extraction.files += 1;
+            extraction.size += content.len() as u64;
+            self.0.write(&target, Bytes::from(content), false).await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl<
+        F: FileReaderInfra + FileWriterInfra + FileInfoInfra + FileDirectoryInfra + FileRemoverInfra,
+    > ArchiveService for ForgeArchive<F>
+{
+    async fn list_archive(&self, path: &Path) -> anyhow::Result<Vec<ArchiveEntry>> {
+        let decoded = self.decode(path, false).await?;
+        Ok(decoded
+            .entries
+            .into_iter()
+            .map(|(entry, _)| entry)
+            .collect())
+    }
+
+    async fn extract_archive(
+        &self,
+        path: &Path,
+        destination: &Path,
+    ) -> anyhow::Result<ArchiveExtraction> {
+        let decoded = self.decode(path, true).await?;
+
+        let mut writes = Vec::new();
+        let mut skipped = Vec::new();
+        for (entry, content) in decoded.entries {
+            if entry.kind == ArchiveEntryKind::Link {
+                skipped.push(entry.path);
+                continue;
+            }
+            let relative = archive_entry_path(&entry.path)
+                .ok_or_else(|| Error::UnsafeArchiveEntry(entry.path.clone()))?;
+            writes.push((destination.join(relative), entry.kind, content));
+        }
+        check_conflicts(&writes, destination)?;
+
+        let mut extraction = ArchiveExtraction {
+            format: decoded.format,
+            destination: destination.to_path_buf(),
+            files: 0,
+            size: 0,
+            skipped,
+        };
+        let existed = self.0.exists(destination).await?;
+        self.0.create_dirs(destination).await?;
+        if let Err(error) = self.write_entries(writes, &mut extraction).await {
+            // Only a destination created here is removed, an existing one may
+            // hold other files
+            if !existed {
+                if let Err(error) = self.0.remove_dir(destination, true).await {
+                    tracing::warn!(error = ?error, "Failed to remove a partial extraction");
+                }
+            }
+            return Err(error);
+        }
+        Ok(extraction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::io::Write;
+    use std::path::PathBuf;
+    use std::sync::Mutex;
+    use std::time::SystemTime;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    /// File system holding the archive and the files written
+    #[derive(Default)]
+    struct MockInfra {
+        files: Mutex<BTreeMap<PathBuf, Vec<u8>>>,
+    }
+
+    impl MockInfra {
+        fn with_archive(archive: Vec<u8>) -> Arc<Self> {
+            let infra = Self::default();
+            infra
+                .files
+                .lock()
+                .unwrap()
+                .insert(PathBuf::from("/downloads/release"), archive);
+            Arc::new(infra)
+        }
+
+        fn written(&self) -> Vec<(String, String)> {
+            self.files
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(path, _)| path.starts_with("/scratch"))
+                .map(|(path, content)| {
+                    (
+                        path.display().to_string(),
+                        String::from_utf8_lossy(content).to_string(),
+                    )
+                })
+                .collect()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl FileReaderInfra for MockInfra {
+        async fn read_utf8(&self, path: &Path) -> anyhow::Result<String> {
+            Ok(String::from_utf8(self.read(path).await?)?)
+        }
+
+        async fn read(&self, path: &Path) -> anyhow::Result<Vec<u8>> {
+            self.files
+                .lock()
+                .unwrap()
+                .get(path)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("No such file"))
+        }
+
+        async fn read_from(&self, path: &Path, offset: u64) -> anyhow::Result<Vec<u8>> {
+            let content = self.read(path).await?;
+            Ok(content.get(offset as usize..).unwrap_or_default().to_vec())
+        }
+
+        async fn range_read_utf8(
+            &self,
+            _path: &Path,
+            _start_line: u64,
+            _end_line: u64,
+        ) -> anyhow::Result<(String, forge_fs::FileInfo)> {
+            unimplemented!()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl FileWriterInfra for MockInfra {
+        async fn write(
+            &self,
+            path: &Path,
+            contents: Bytes,
+            _capture_snapshot: bool,
+        ) -> anyhow::Result<()> {
+            self.files
+                .lock()
+                .unwrap()
+                .insert(path.to_path_buf(), contents.to_vec());
+            Ok(())
+        }
+
+        async fn append(&self, _path: &Path, _contents: Bytes) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+
+        async fn write_temp(&self, _: &str, _: &str, _: &str) -> anyhow::Result<PathBuf> {
+            unimplemented!()
+        }
+
+        async fn lock(&self, _path: &Path) -> anyhow::Result<forge_fs::FileLock> {
+            unimplemented!()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl FileInfoInfra for MockInfra {
+        async fn is_file(&self, path: &Path) -> anyhow::Result<bool> {
+            self.exists(path).await
+        }
+
+        async fn exists(&self, path: &Path) -> anyhow::Result<bool> {
+            Ok(self.files.lock().unwrap().contains_key(path))
+        }
+
+        async fn file_size(&self, path: &Path) -> anyhow::Result<u64> {
+            Ok(self.read(path).await?.len() as u64)
+        }
+
+        async fn modified(&self, _path: &Path) -> anyhow::Result<SystemTime> {
+            Ok(SystemTime::UNIX_EPOCH)
+        }
+
+        async fn list_dir(&self, _path: &Path) -> anyhow::Result<Vec<String>> {
+            unimplemented!()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl FileRemoverInfra for MockInfra {
+        async fn remove(&self, _path: &Path, _permanent: bool) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+
+        async fn remove_dir(&self, path: &Path, _permanent: bool) -> anyhow::Result<Vec<PathBuf>> {
+            self.files
+                .lock()
+                .unwrap()
+                .retain(|file, _| !file.starts_with(path));
+            Ok(Vec::new())
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl FileDirectoryInfra for MockInfra {
+        async fn create_dirs(&self, _path: &Path) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn zip(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default();
+        writer.add_directory("bin/", options).unwrap();
+        for (name, content) in files {
+            writer.start_file(*name, options).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn tar_gz() -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        let content = b"#!/bin/sh\necho forge\n";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o755);
+        builder
+            .append_data(&mut header, "forge/bin/forge", &content[..])
+            .unwrap();
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Symlink);
+        header.set_size(0);
+        builder
+            .append_link(&mut header, "forge/latest", "/etc/passwd")
+            .unwrap();
+        let tar = builder.into_inner().unwrap();
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        encoder.write_all(&tar).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_list_zip() {
+        let fixture = ForgeArchive::new(MockInfra::with_archive(zip(&[("bin/forge", "binary")])));
+
+        let actual = fixture
+            .list_archive(Path::new("/downloads/release"))
+            .await
+            .unwrap();
+
+        let expected = vec![
+            ArchiveEntry {
+                path: "bin/".to_string(),
+                kind: ArchiveEntryKind::Directory,
+                size: 0,
+            },
+            ArchiveEntry {
+                path: "bin/forge".to_string(),
+                kind: ArchiveEntryKind::File,
+                size: 6,
+            },
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_extract_tar_gz_skips_links() {
+        let infra = MockInfra::with_archive(tar_gz());
+        let fixture = ForgeArchive::new(infra.clone());
+
+        let actual = fixture
+            .extract_archive(
+                Path::new("/downloads/release"),
+                Path::new("/scratch/release"),
+            )
+            .await
+            .unwrap();
+
+        let expected = ArchiveExtraction {
+            format: ArchiveFormat::TarGz,
+            destination: PathBuf::from("/scratch/release"),
+            files: 1,
+            size: 21,
+            skipped: vec!["forge/latest".to_string()],
+        };
+        assert_eq!(actual, expected);
+        assert_eq!(
+            infra.written(),
+            vec![(
+                "/scratch/release/forge/bin/forge".to_string(),
+                "#!/bin/sh\necho forge\n".to_string()
+            )]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_extract_refuses_path_traversal() {
+        let infra = MockInfra::with_archive(zip(&[
+            ("README.md", "read me"),
+            ("../../.bashrc", "rm -rf ~"),
+        ]));
+        let fixture = ForgeArchive::new(infra.clone());
+
+        let actual = fixture
+            .extract_archive(
+                Path::new("/downloads/release"),
+                Path::new("/scratch/release"),
+            )
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            actual.to_string(),
+            "Archive entry ../../.bashrc would be extracted outside of the destination"
+        );
+        assert_eq!(infra.written(), vec![]);
+    }
+
+    #[tokio::test]
+    async fn test_extract_refuses_conflicting_entries() {
+        // The archive holds a directory bin/ as well
+        let infra = MockInfra::with_archive(zip(&[("README.md", "read me"), ("bin", "binary")]));
+        let fixture = ForgeArchive::new(infra.clone());
+
+        let actual = fixture
+            .extract_archive(
+                Path::new("/downloads/release"),
+                Path::new("/scratch/release"),
+            )
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            actual.to_string(),
+            "Archive entry bin is both a file and a directory"
+        );
+        assert_eq!(infra.written(), vec![]);
+    }
+
+    #[tokio::test]
+    async fn test_list_refuses_other_files() {
+        let fixture = ForgeArchive::new(MockInfra::with_archive(b"not an archive".to_vec()));
+
+        let actual = fixture
+            .list_archive(Path::new("/downloads/release"))
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            actual.to_string(),
+            "/downloads/release is not a zip, tar or tar.gz archive"
+        );
+    }
+}
@@ -0,0 +1,96 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use forge_app::domain::{
+    cargo_workspace_targets, parse_cargo_metadata, parse_justfile, parse_makefile,
+    parse_package_json, BuildTool, Runnable,
+};
+use forge_app::TaskDiscoveryService;
+
+use crate::utils::assert_absolute_path;
+use crate::{CommandInfra, FileInfoInfra, FileReaderInfra};
+
+/// Lock files of the package managers running the scripts of package.json,
+/// npm being used when there's none
+const LOCK_FILES: [(&str, &str); 4] = [
+    ("pnpm-lock.yaml", "pnpm"),
+    ("yarn.lock", "yarn"),
+    ("bun.lock", "bun"),
+    ("bun.lockb", "bun"),
+];
+
+/// Reads the manifests of the build tools of a project to list the targets
+/// it defines.
+pub struct ForgeDiscoverTasks<I> {
+    infra: Arc<I>,
+}
+
+impl<I> ForgeDiscoverTasks<I> {
+    pub fn new(infra: Arc<I>) -> Self {
+        Self { infra }
+    }
+}
+
+impl<I: CommandInfra + FileInfoInfra + FileReaderInfra> ForgeDiscoverTasks<I> {
+    /// Content of the manifest of the tool in the directory, if any
+    async fn read_manifest(&self, dir: &Path, tool: BuildTool) -> anyhow::Result<Option<String>> {
+        for manifest in tool.manifests() {
+            let path = dir.join(manifest);
+            if self.infra.is_file(&path).await? {
+                return Ok(Some(self.infra.read_utf8(&path).await?));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn package_manager(&self, dir: &Path) -> anyhow::Result<&'static str> {
+        for (lock_file, manager) in LOCK_FILES {
+            if self.infra.is_file(&dir.join(lock_file)).await? {
+                return Ok(manager);
+            }
+        }
+        Ok("npm")
+    }
+
+    /// Packages, binaries and examples of the workspace, as cargo resolves
+    /// them. None are listed when cargo isn't installed or fails.
+    async fn cargo_packages(&self, dir: &Path) -> Vec<Runnable> {
+        let args = ["metadata", "--no-deps", "--format-version", "1"]
+            .map(String::from)
+            .to_vec();
+        match self
+            .infra
+            .execute_program("cargo", args, dir.to_path_buf())
+            .await
+        {
+            Ok(output) if output.success() => parse_cargo_metadata(&output.stdout),
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<I: CommandInfra + FileInfoInfra + FileReaderInfra> TaskDiscoveryService
+    for ForgeDiscoverTasks<I>
+{
+    async fn discover_tasks(&self, path: PathBuf) -> anyhow::Result<Vec<Runnable>> {
+        assert_absolute_path(&path)?;
+
+        let mut runnables = Vec::new();
+        if self.read_manifest(&path, BuildTool::Cargo).await?.is_some() {
+            runnables.extend(cargo_workspace_targets());
+            runnables.extend(self.cargo_packages(&path).await);
+        }
+        if let Some(content) = self.read_manifest(&path, BuildTool::Npm).await? {
+            let manager = self.package_manager(&path).await?;
+            runnables.extend(parse_package_json(&content, manager));
+        }
+        if let Some(content) = self.read_manifest(&path, BuildTool::Make).await? {
+            runnables.extend(parse_makefile(&content));
+        }
+        if let Some(content) = self.read_manifest(&path, BuildTool::Just).await? {
+            runnables.extend(parse_justfile(&content));
+        }
+        Ok(runnables)
+    }
+}
@@ -0,0 +1,80 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::Context;
+use bytes::Bytes;
+use forge_app::domain::FileState;
+use forge_app::FsCheckpointService;
+use forge_snaps::SnapshotId;
+
+use super::fs_snapshot::to_ref;
+use crate::utils::assert_absolute_path;
+use crate::{FileInfoInfra, FileRemoverInfra, FileWriterInfra, SnapshotInfra};
+
+/// Captures the files for the checkpoints of a conversation as snapshots, and
+/// writes them back when a checkpoint is restored.
+pub struct ForgeFsCheckpoint<F>(Arc<F>);
+
+impl<F> ForgeFsCheckpoint<F> {
+    pub fn new(infra: Arc<F>) -> Self {
+        Self(infra)
+    }
+}
+
+#[async_trait::async_trait]
+impl<F: FileWriterInfra + FileInfoInfra + FileRemoverInfra + SnapshotInfra> FsCheckpointService
+    for ForgeFsCheckpoint<F>
+{
+    async fn capture_files(&self, paths: &[String]) -> anyhow::Result<Vec<FileState>> {
+        let mut files = Vec::with_capacity(paths.len());
+        for path in paths {
+            assert_absolute_path(Path::new(path))?;
+            let snapshot = if self.0.is_file(Path::new(path)).await? {
+                let snapshot = self
+                    .0
+                    .create_snapshot(Path::new(path))
+                    .await
+                    .with_context(|| format!("Failed to capture {path}"))?;
+                Some(to_ref(snapshot))
+            } else {
+                None
+            };
+            files.push(FileState { path: path.clone(), snapshot, content: None });
+        }
+        Ok(files)
+    }
+
+    async fn restore_files(&self, files: &[FileState]) -> anyhow::Result<()> {
+        for file in files {
+            let path = Path::new(&file.path);
+            assert_absolute_path(path)?;
+            match (&file.snapshot, &file.content) {
+                (Some(snapshot), _) => {
+                    let id = SnapshotId::parse(&snapshot.id)
+                        .with_context(|| format!("Invalid snapshot id {}", snapshot.id))?;
+                    if self.0.exists(path).await? {
+                        self.0.create_snapshot(path).await?;
+                    }
+                    self.0
+                        .apply_snapshot(path, &id)
+                        .await
+                        .with_context(|| format!("Failed to restore {}", file.path))?;
+                }
+                (None, Some(content)) => {
+                    self.0
+                        .write(path, Bytes::from(content.clone()), true)
+                        .await
+                        .with_context(|| format!("Failed to restore {}", file.path))?;
+                }
+                (None, None) if self.0.exists(path).await? => {
+                    self.0
+                        .remove(path, false)
+                        .await
+                        .with_context(|| format!("Failed to remove {}", file.path))?;
+                }
+                (None, None) => {}
+            }
+        }
+        Ok(())
+    }
+}
@@ -4,9 +4,13 @@ use std::sync::Arc;
 use anyhow::Context;
 use bytes::Bytes;
 use forge_app::{FsCreateOutput, FsCreateService};
+use forge_fs::TextEncoding;
 
-use crate::utils::assert_absolute_path;
-use crate::{tool_services, FileDirectoryInfra, FileInfoInfra, FileReaderInfra, FileWriterInfra};
+use crate::utils::{assert_absolute_path, assert_write_size};
+use crate::{
+    tool_services, EnvironmentInfra, FileDirectoryInfra, FileInfoInfra, FileReaderInfra,
+    FileWriterInfra,
+};
 
 /// Use it to create a new file at a specified path with the provided content.
 /// Always provide absolute paths for file locations. The tool
@@ -23,18 +27,28 @@ impl<F> ForgeFsCreate<F> {
 }
 
 #[async_trait::async_trait]
-impl<F: FileDirectoryInfra + FileInfoInfra + FileReaderInfra + FileWriterInfra + Send + Sync>
-    FsCreateService for ForgeFsCreate<F>
+impl<
+        F: FileDirectoryInfra
+            + FileInfoInfra
+            + FileReaderInfra
+            + FileWriterInfra
+            + EnvironmentInfra
+            + Send
+            + Sync,
+    > FsCreateService for ForgeFsCreate<F>
 {
     async fn create(
         &self,
         path: String,
         content: String,
         overwrite: bool,
+        allow_large: bool,
         capture_snapshot: bool,
     ) -> anyhow::Result<FsCreateOutput> {
         let path = Path::new(&path);
         assert_absolute_path(path)?;
+        let env = self.0.get_environment();
+        assert_write_size(path, content.len(), env.max_write_size, allow_large)?;
         // Validate file content if it's a supported language file
         let syntax_warning = tool_services::syn::validate(path, &content);
         if let Some(parent) = Path::new(&path).parent() {
@@ -57,16 +71,29 @@ impl<F: FileDirectoryInfra + FileInfoInfra + FileReaderInfra + FileWriterInfra +
             .with_context(|| format!("File already exists at {}", path.display()));
         }
 
-        // record the file content before they're modified
-        let old_content = if file_exists && overwrite {
-            Some(self.0.read_utf8(path).await?)
+        // record the file content before they're modified, and the encoding to
+        // write the new content in
+        let (old_content, encoding) = if file_exists && overwrite {
+            let bytes = self.0.read(path).await?;
+            let encoding = TextEncoding::detect(&bytes);
+            (Some(encoding.decode(&bytes)), encoding)
         } else {
-            None
+            (None, TextEncoding::UTF_8)
+        };
+
+        // Keep the line endings of the file being replaced
+        let content = match old_content.as_deref() {
+            Some(old_content) => match_line_endings(content, old_content),
+            None => content,
         };
 
         // Write file only after validation passes and directories are created
         self.0
-            .write(path, Bytes::from(content), capture_snapshot)
+            .write(
+                path,
+                Bytes::from(encoding.encode(&content)),
+                capture_snapshot,
+            )
             .await?;
 
         Ok(FsCreateOutput {
@@ -76,3 +103,36 @@ impl<F: FileDirectoryInfra + FileInfoInfra + FileReaderInfra + FileWriterInfra +
         })
     }
 }
+
+/// Converts the line endings of `content` to CRLF when `original` uses them,
+/// since models almost always produce LF line endings.
+fn match_line_endings(content: String, original: &str) -> String {
+    if original.contains("\r\n") {
+        content.replace("\r\n", "\n").replace('\n', "\r\n")
+    } else {
+        content
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_match_line_endings_crlf() {
+        let fixture = "fn main() {\n    run();\r\n}\n".to_string();
+        let actual = match_line_endings(fixture, "fn main() {\r\n}\r\n");
+        let expected = "fn main() {\r\n    run();\r\n}\r\n";
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_match_line_endings_lf() {
+        let fixture = "fn main() {\n}\n".to_string();
+        let actual = match_line_endings(fixture.clone(), "fn main() {}\n");
+        let expected = fixture;
+        assert_eq!(actual, expected);
+    }
+}
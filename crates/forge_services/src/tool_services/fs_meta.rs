@@ -0,0 +1,198 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use forge_app::domain::{FileStamp, FsChange, FS_WAIT_POLL_INTERVAL};
+use forge_app::FsMetaService;
+use tokio::time::Instant;
+
+use crate::FileInfoInfra;
+
+/// Entries of a watched directory checked for changes, so that watching a
+/// large tree stays cheap
+const MAX_LISTED_ENTRIES: usize = 10_000;
+
+/// Answers questions about the file system without reading any content, such
+/// as whether a path exists.
+pub struct ForgeFsMeta<F>(Arc<F>);
+
+impl<F> ForgeFsMeta<F> {
+    pub fn new(infra: Arc<F>) -> Self {
+        Self(infra)
+    }
+}
+
+impl<F: FileInfoInfra> ForgeFsMeta<F> {
+    /// State of the path, `None` when nothing can be read at it, e.g. while
+    /// an editor replaces the file
+    async fn stamp(&self, path: &Path) -> Option<FileStamp> {
+        if !self.0.exists(path).await.unwrap_or(false) {
+            return None;
+        }
+        let modified = self.0.modified(path).await.ok()?;
+        let size = self.0.file_size(path).await.ok()?;
+        let listing = if self.0.is_file(path).await.unwrap_or(true) {
+            0
+        } else {
+            self.listing(path).await
+        };
+        Some(FileStamp { modified, size, listing })
+    }
+
+    /// Hash of the entries nested in the directory with their modification
+    /// time and size
+    async fn listing(&self, dir: &Path) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        let mut pending = vec![dir.to_path_buf()];
+        let mut listed = 0;
+        while let Some(dir) = pending.pop() {
+            let Ok(mut names) = self.0.list_dir(&dir).await else {
+                continue;
+            };
+            names.sort();
+            for name in names {
+                listed += 1;
+                if listed > MAX_LISTED_ENTRIES {
+                    return hasher.finish();
+                }
+                let path = dir.join(name);
+                let modified = self.0.modified(&path).await.ok();
+                let size = self.0.file_size(&path).await.ok();
+                (&path, modified, size).hash(&mut hasher);
+                if !self.0.is_file(&path).await.unwrap_or(true) {
+                    pending.push(path);
+                }
+            }
+        }
+        hasher.finish()
+    }
+}
+
+#[async_trait::async_trait]
+impl<F: FileInfoInfra> FsMetaService for ForgeFsMeta<F> {
+    async fn exists(&self, path: &Path) -> anyhow::Result<bool> {
+        self.0.exists(path).await
+    }
+
+    async fn is_file(&self, path: &Path) -> anyhow::Result<bool> {
+        self.0.is_file(path).await
+    }
+
+    async fn wait_for_change(
+        &self,
+        path: &Path,
+        timeout: Duration,
+    ) -> anyhow::Result<Option<FsChange>> {
+        let deadline = Instant::now() + timeout;
+        let before = self.stamp(path).await;
+        loop {
+            tokio::time::sleep(FS_WAIT_POLL_INTERVAL).await;
+            if let Some(change) = FsChange::between(before, self.stamp(path).await) {
+                return Ok(Some(change));
+            }
+            if Instant::now() >= deadline {
+                return Ok(None);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::time::SystemTime;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    /// Files rewritten once after the number of checks, in a `/project/src`
+    /// directory
+    struct MockInfra {
+        checks: AtomicU64,
+        rewritten_after: u64,
+    }
+
+    #[async_trait::async_trait]
+    impl FileInfoInfra for MockInfra {
+        async fn is_file(&self, path: &Path) -> anyhow::Result<bool> {
+            Ok(path.extension().is_some())
+        }
+
+        async fn exists(&self, _path: &Path) -> anyhow::Result<bool> {
+            Ok(true)
+        }
+
+        async fn file_size(&self, _path: &Path) -> anyhow::Result<u64> {
+            Ok(10)
+        }
+
+        async fn modified(&self, path: &Path) -> anyhow::Result<SystemTime> {
+            // Directories aren't modified by the changes of their files
+            if path.extension().is_none() {
+                return Ok(SystemTime::UNIX_EPOCH);
+            }
+            let checks = self.checks.fetch_add(1, Ordering::SeqCst);
+            let secs = u64::from(checks > self.rewritten_after);
+            Ok(SystemTime::UNIX_EPOCH + Duration::from_secs(secs))
+        }
+
+        async fn list_dir(&self, path: &Path) -> anyhow::Result<Vec<String>> {
+            match path.to_str() {
+                Some("/project") => Ok(vec!["src".to_string()]),
+                Some("/project/src") => Ok(vec!["lib.rs".to_string()]),
+                _ => Ok(vec![]),
+            }
+        }
+    }
+
+    fn service(rewritten_after: u64) -> ForgeFsMeta<MockInfra> {
+        ForgeFsMeta::new(Arc::new(MockInfra {
+            checks: AtomicU64::new(0),
+            rewritten_after,
+        }))
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_wait_for_change_reports_modification() {
+        let fixture = service(3);
+
+        let actual = fixture
+            .wait_for_change(Path::new("/project/out.log"), Duration::from_secs(60))
+            .await
+            .unwrap();
+
+        let expected = Some(FsChange::Modified);
+        assert_eq!(actual, expected);
+        assert_eq!(fixture.0.checks.load(Ordering::SeqCst), 5);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_wait_for_change_reports_nested_modification() {
+        let fixture = service(3);
+
+        let actual = fixture
+            .wait_for_change(Path::new("/project"), Duration::from_secs(60))
+            .await
+            .unwrap();
+
+        let expected = Some(FsChange::Modified);
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_wait_for_change_times_out() {
+        let fixture = service(u64::MAX);
+        let start = Instant::now();
+
+        let actual = fixture
+            .wait_for_change(Path::new("/project/out.log"), Duration::from_secs(5))
+            .await
+            .unwrap();
+
+        assert_eq!(actual, None);
+        assert_eq!(start.elapsed(), Duration::from_secs(5));
+    }
+}
@@ -4,12 +4,13 @@ use std::sync::Arc;
 use bytes::Bytes;
 use forge_app::domain::PatchOperation;
 use forge_app::{FsPatchService, PatchOutput};
+use forge_fs::TextEncoding;
 use thiserror::Error;
 use tokio::fs;
 
 // No longer using dissimilar for fuzzy matching
-use crate::utils::assert_absolute_path;
-use crate::{tool_services, FileWriterInfra};
+use crate::utils::{assert_absolute_path, assert_write_size};
+use crate::{tool_services, EnvironmentInfra, FileWriterInfra};
 
 /// A match found in the source text. Represents a range in the source text that
 /// can be used for extraction or replacement operations. Stores the position
@@ -200,30 +201,36 @@ impl<F> ForgeFsPatch<F> {
 }
 
 #[async_trait::async_trait]
-impl<F: FileWriterInfra> FsPatchService for ForgeFsPatch<F> {
+impl<F: FileWriterInfra + EnvironmentInfra> FsPatchService for ForgeFsPatch<F> {
     async fn patch(
         &self,
         input_path: String,
         search: Option<String>,
         operation: PatchOperation,
         content: String,
+        allow_large: bool,
     ) -> anyhow::Result<PatchOutput> {
         let path = Path::new(&input_path);
         assert_absolute_path(path)?;
 
-        // Read the original content once
+        // Read the original content once, keeping its encoding for the write
         // TODO: use forge_fs
-        let mut current_content = fs::read_to_string(path)
-            .await
-            .map_err(Error::FileOperation)?;
+        let bytes = fs::read(path).await.map_err(Error::FileOperation)?;
+        let encoding = TextEncoding::detect(&bytes);
+        let mut current_content = encoding.decode(&bytes);
         // Save the old content before modification for diff generation
         let old_content = current_content.clone();
         // Apply the replacement
         current_content = apply_replacement(current_content, search, &operation, &content)?;
 
+        // Only the content the patch adds counts, large files stay patchable
+        let env = self.0.get_environment();
+        let growth = current_content.len().saturating_sub(old_content.len());
+        assert_write_size(path, growth, env.max_write_size, allow_large)?;
+
         // Write final content to file after all patches are applied
         self.0
-            .write(path, Bytes::from(current_content.clone()), true)
+            .write(path, Bytes::from(encoding.encode(&current_content)), true)
             .await?;
 
         Ok(PatchOutput {
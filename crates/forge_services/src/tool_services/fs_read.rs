@@ -111,6 +111,7 @@ impl<F: FileInfoInfra + EnvironmentInfra + InfraFsReadService> FsReadService for
             start_line: file_info.start_line,
             end_line: file_info.end_line,
             total_lines: file_info.total_lines,
+            encoding: (!file_info.encoding.is_utf8()).then(|| file_info.encoding.to_string()),
         })
     }
 }
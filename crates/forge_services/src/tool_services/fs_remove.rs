@@ -1,14 +1,14 @@
 use std::path::Path;
 use std::sync::Arc;
 
-use forge_app::{FsRemoveOutput, FsRemoveService};
+use forge_app::{FsRemoveDirOutput, FsRemoveOutput, FsRemoveService};
 
 use crate::utils::assert_absolute_path;
 use crate::FileRemoverInfra;
 
-/// Request to remove a file at the specified path. Use this when you need to
-/// delete an existing file. The path must be absolute. This operation cannot
-/// be undone, so use it carefully.
+/// Removes files and directories at the specified paths, moving them to the
+/// trash unless the removal is permanent. Removed files are snapshotted first
+/// so that they can be undone.
 pub struct ForgeFsRemove<T>(Arc<T>);
 
 impl<T> ForgeFsRemove<T> {
@@ -19,12 +19,31 @@ impl<T> ForgeFsRemove<T> {
 
 #[async_trait::async_trait]
 impl<F: FileRemoverInfra> FsRemoveService for ForgeFsRemove<F> {
-    async fn remove(&self, input_path: String) -> anyhow::Result<FsRemoveOutput> {
+    async fn remove(&self, input_path: String, permanent: bool) -> anyhow::Result<FsRemoveOutput> {
         let path = Path::new(&input_path);
         assert_absolute_path(path)?;
 
-        self.0.remove(path).await?;
+        self.0.remove(path, permanent).await?;
 
         Ok(FsRemoveOutput {})
     }
+
+    async fn remove_dir(
+        &self,
+        input_path: String,
+        permanent: bool,
+    ) -> anyhow::Result<FsRemoveDirOutput> {
+        let path = Path::new(&input_path);
+        assert_absolute_path(path)?;
+
+        let snapshotted = self.0.remove_dir(path, permanent).await?;
+
+        Ok(FsRemoveDirOutput {
+            snapshotted: snapshotted
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect(),
+            trashed: !permanent,
+        })
+    }
 }
@@ -83,6 +83,28 @@ impl<W: WalkerInfra> FsSearchService for ForgeFsSearch<W> {
         input_path: String,
         input_regex: Option<String>,
         file_pattern: Option<String>,
+    ) -> anyhow::Result<Option<SearchResult>> {
+        self.search_in(input_path, input_regex, file_pattern, Walker::unlimited())
+            .await
+    }
+
+    async fn search_walk(
+        &self,
+        walker: Walker,
+        regex: String,
+    ) -> anyhow::Result<Option<SearchResult>> {
+        let path = walker.cwd.to_string_lossy().to_string();
+        self.search_in(path, Some(regex), None, walker).await
+    }
+}
+
+impl<W: WalkerInfra> ForgeFsSearch<W> {
+    async fn search_in(
+        &self,
+        input_path: String,
+        input_regex: Option<String>,
+        file_pattern: Option<String>,
+        walker: Walker,
     ) -> anyhow::Result<Option<SearchResult>> {
         let helper = FSSearchHelper {
             path: &input_path,
@@ -103,7 +125,7 @@ impl<W: WalkerInfra> FsSearchService for ForgeFsSearch<W> {
             }
             None => None,
         };
-        let paths = self.retrieve_file_paths(path).await?;
+        let paths = self.retrieve_file_paths(path, walker).await?;
 
         let mut matches = Vec::new();
 
@@ -157,17 +179,19 @@ impl<W: WalkerInfra> FsSearchService for ForgeFsSearch<W> {
 
         Ok(Some(SearchResult { matches }))
     }
-}
 
-impl<W: WalkerInfra> ForgeFsSearch<W> {
-    async fn retrieve_file_paths(&self, dir: &Path) -> anyhow::Result<Vec<std::path::PathBuf>> {
+    async fn retrieve_file_paths(
+        &self,
+        dir: &Path,
+        walker: Walker,
+    ) -> anyhow::Result<Vec<std::path::PathBuf>> {
         let metadata = tokio::fs::metadata(dir).await?;
         if metadata.is_dir() {
             // note: Paths needs mutable to avoid flaky tests.
             #[allow(unused_mut)]
             let mut paths = self
                 .walker
-                .walk(Walker::unlimited().cwd(dir.to_path_buf()))
+                .walk(walker.cwd(dir.to_path_buf()))
                 .await
                 .with_context(|| format!("Failed to walk directory '{}'", dir.display()))?
                 .into_iter()
@@ -188,6 +212,7 @@ impl<W: WalkerInfra> ForgeFsSearch<W> {
 
 #[cfg(test)]
 mod test {
+    use std::path::PathBuf;
     use std::sync::Arc;
 
     use forge_app::{WalkedFile, Walker};
@@ -221,6 +246,11 @@ mod test {
             }
             Ok(files)
         }
+
+        async fn count_entries(&self, cwd: PathBuf, limit: usize) -> anyhow::Result<usize> {
+            let files = self.walk(Walker::unlimited().cwd(cwd)).await?;
+            Ok(files.len().min(limit))
+        }
     }
 
     async fn create_simple_test_directory() -> anyhow::Result<TempDir> {
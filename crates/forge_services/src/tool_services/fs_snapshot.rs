@@ -0,0 +1,53 @@
+use std::path::Path;
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use forge_app::domain::{SnapshotRef, SnapshotRetention};
+use forge_app::FsSnapshotService;
+use forge_snaps::{RetentionPolicy, Snapshot, SnapshotId};
+
+use crate::SnapshotInfra;
+
+/// Gives access to the copies of the files taken before they were modified.
+pub struct ForgeFsSnapshot<F>(Arc<F>);
+
+impl<F> ForgeFsSnapshot<F> {
+    pub fn new(infra: Arc<F>) -> Self {
+        Self(infra)
+    }
+}
+
+pub(crate) fn to_ref(snapshot: Snapshot) -> SnapshotRef {
+    SnapshotRef {
+        id: snapshot.id.to_string(),
+        path: snapshot.path,
+        created_at: DateTime::<Utc>::from(UNIX_EPOCH + snapshot.timestamp),
+    }
+}
+
+#[async_trait::async_trait]
+impl<F: SnapshotInfra> FsSnapshotService for ForgeFsSnapshot<F> {
+    async fn list_snapshots(&self, path: &Path) -> anyhow::Result<Vec<SnapshotRef>> {
+        let snapshots = self.0.list_snapshots(path).await?;
+        Ok(snapshots.into_iter().map(to_ref).collect())
+    }
+
+    async fn restore_snapshot(&self, snapshot: &SnapshotRef) -> anyhow::Result<()> {
+        let id = SnapshotId::parse(&snapshot.id)
+            .with_context(|| format!("Invalid snapshot id {}", snapshot.id))?;
+        self.0
+            .restore_snapshot(Path::new(&snapshot.path), &id)
+            .await
+    }
+
+    async fn purge_snapshots(&self, retention: &SnapshotRetention) -> anyhow::Result<usize> {
+        let policy = RetentionPolicy {
+            max_per_file: retention.max_snapshots_per_file,
+            max_age: retention.max_age(),
+            max_total_size: retention.max_disk_usage_bytes(),
+        };
+        self.0.purge_snapshots(&policy).await
+    }
+}
@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::Context;
+use forge_app::{FsWriteAppendOutput, FsWriteDraft, FsWriteDraftService};
+use tokio::sync::Mutex;
+
+use crate::utils::assert_absolute_path;
+use crate::FileInfoInfra;
+
+/// Assembles files that are generated in several parts. The parts are kept in
+/// memory until the draft is written with the create service, so that a file
+/// is never left half written.
+pub struct ForgeFsWriteDraft<F> {
+    infra: Arc<F>,
+    drafts: Arc<Mutex<HashMap<PathBuf, FsWriteDraft>>>,
+}
+
+impl<F> ForgeFsWriteDraft<F> {
+    pub fn new(infra: Arc<F>) -> Self {
+        Self { infra, drafts: Default::default() }
+    }
+}
+
+fn not_started(path: &Path) -> anyhow::Error {
+    anyhow::anyhow!(
+        "No file is being written at {}. Start one with forge_tool_fs_write_begin.",
+        path.display()
+    )
+}
+
+#[async_trait::async_trait]
+impl<F: FileInfoInfra> FsWriteDraftService for ForgeFsWriteDraft<F> {
+    async fn begin_draft(
+        &self,
+        path: String,
+        overwrite: bool,
+        allow_large: bool,
+    ) -> anyhow::Result<()> {
+        let path = PathBuf::from(path);
+        assert_absolute_path(&path)?;
+
+        // Fail before the parts are generated rather than at the commit
+        if !overwrite && self.infra.is_file(&path).await? {
+            return Err(anyhow::anyhow!(
+                "Cannot overwrite existing file: overwrite flag not set.",
+            ))
+            .with_context(|| format!("File already exists at {}", path.display()));
+        }
+
+        let draft = FsWriteDraft { overwrite, allow_large, ..Default::default() };
+        self.drafts.lock().await.insert(path, draft);
+        Ok(())
+    }
+
+    async fn append_draft(
+        &self,
+        path: String,
+        content: String,
+    ) -> anyhow::Result<FsWriteAppendOutput> {
+        let path = PathBuf::from(path);
+        let mut drafts = self.drafts.lock().await;
+        let draft = drafts.get_mut(&path).ok_or_else(|| not_started(&path))?;
+
+        draft.content.push_str(&content);
+        draft.parts += 1;
+
+        Ok(FsWriteAppendOutput {
+            parts: draft.parts,
+            total_lines: draft.content.lines().count(),
+        })
+    }
+
+    async fn get_draft(&self, path: String) -> anyhow::Result<FsWriteDraft> {
+        let path = PathBuf::from(path);
+        let drafts = self.drafts.lock().await;
+        drafts.get(&path).cloned().ok_or_else(|| not_started(&path))
+    }
+
+    async fn discard_draft(&self, path: String) -> anyhow::Result<()> {
+        self.drafts.lock().await.remove(Path::new(&path));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::SystemTime;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    struct MockInfra {
+        files: Vec<PathBuf>,
+    }
+
+    #[async_trait::async_trait]
+    impl FileInfoInfra for MockInfra {
+        async fn is_file(&self, path: &Path) -> anyhow::Result<bool> {
+            Ok(self.files.iter().any(|file| file == path))
+        }
+
+        async fn exists(&self, path: &Path) -> anyhow::Result<bool> {
+            self.is_file(path).await
+        }
+
+        async fn file_size(&self, _path: &Path) -> anyhow::Result<u64> {
+            Ok(0)
+        }
+
+        async fn modified(&self, _path: &Path) -> anyhow::Result<SystemTime> {
+            Ok(SystemTime::UNIX_EPOCH)
+        }
+
+        async fn list_dir(&self, _path: &Path) -> anyhow::Result<Vec<String>> {
+            unimplemented!()
+        }
+    }
+
+    fn service() -> ForgeFsWriteDraft<MockInfra> {
+        ForgeFsWriteDraft::new(Arc::new(MockInfra {
+            files: vec!["/project/main.rs".into()],
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_parts_are_assembled_in_order() {
+        let fixture = service();
+        fixture
+            .begin_draft("/project/data.json".to_string(), false, true)
+            .await
+            .unwrap();
+        fixture
+            .append_draft("/project/data.json".to_string(), "[\n  1,\n".to_string())
+            .await
+            .unwrap();
+        let output = fixture
+            .append_draft("/project/data.json".to_string(), "  2\n]\n".to_string())
+            .await
+            .unwrap();
+
+        let actual = fixture
+            .get_draft("/project/data.json".to_string())
+            .await
+            .unwrap();
+
+        assert_eq!(actual.content, "[\n  1,\n  2\n]\n");
+        assert_eq!(actual.parts, 2);
+        assert!(actual.allow_large);
+        assert_eq!(output.total_lines, 4);
+    }
+
+    #[tokio::test]
+    async fn test_begin_discards_earlier_parts() {
+        let fixture = service();
+        let path = "/project/lib.rs".to_string();
+        fixture
+            .begin_draft(path.clone(), false, false)
+            .await
+            .unwrap();
+        fixture
+            .append_draft(path.clone(), "fn a() {}".to_string())
+            .await
+            .unwrap();
+        fixture
+            .begin_draft(path.clone(), false, false)
+            .await
+            .unwrap();
+
+        let actual = fixture.get_draft(path).await.unwrap().content;
+
+        let expected = "";
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_begin_existing_file_without_overwrite() {
+        let fixture = service();
+
+        let actual = fixture
+            .begin_draft("/project/main.rs".to_string(), false, false)
+            .await;
+
+        assert!(actual.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_append_without_begin() {
+        let fixture = service();
+
+        let actual = fixture
+            .append_draft("/project/main.rs".to_string(), "fn main() {}".to_string())
+            .await;
+
+        assert!(actual.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_discard_draft() {
+        let fixture = service();
+        let path = "/project/main.rs".to_string();
+        fixture
+            .begin_draft(path.clone(), true, false)
+            .await
+            .unwrap();
+        fixture.discard_draft(path.clone()).await.unwrap();
+
+        let actual = fixture.get_draft(path).await;
+
+        assert!(actual.is_err());
+    }
+}
@@ -0,0 +1,322 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::bail;
+use chrono::{DateTime, FixedOffset, Offset, Utc};
+use forge_app::{BlameHunk, GitBlameOutput, GitCommit, GitService};
+
+use crate::tool_services::resolve_range;
+use crate::utils::assert_absolute_path;
+use crate::{CommandInfra, EnvironmentInfra};
+
+/// Number of commits listed when the tool doesn't ask for a number
+const DEFAULT_MAX_COUNT: u64 = 50;
+
+/// Format of the commits listed by `git log`, each starting with a record
+/// separator and with its fields separated by unit separators
+const LOG_FORMAT: &str = "%x1e%H%x1f%an%x1f%aI%x1f%s";
+const RECORD_SEPARATOR: char = '\u{1e}';
+const FIELD_SEPARATOR: char = '\u{1f}';
+
+/// Reads the history of the workspace with git and returns it as structured
+/// entries rather than raw output.
+pub struct ForgeGit<I> {
+    infra: Arc<I>,
+}
+
+impl<I> ForgeGit<I> {
+    pub fn new(infra: Arc<I>) -> Self {
+        Self { infra }
+    }
+}
+
+impl<I: CommandInfra + EnvironmentInfra> ForgeGit<I> {
+    async fn git(&self, args: Vec<String>) -> anyhow::Result<String> {
+        let subcommand = args[0].clone();
+        let args = std::iter::once("--no-pager".to_string())
+            .chain(args)
+            .collect();
+        let cwd = self.infra.get_environment().cwd;
+        let output = self.infra.execute_program("git", args, cwd).await?;
+
+        if !output.success() {
+            bail!("git {subcommand} failed: {}", output.stderr.trim())
+        }
+        Ok(output.stdout)
+    }
+}
+
+#[async_trait::async_trait]
+impl<I: CommandInfra + EnvironmentInfra> GitService for ForgeGit<I> {
+    async fn log(
+        &self,
+        path: Option<String>,
+        range: Option<String>,
+        max_count: Option<u64>,
+    ) -> anyhow::Result<Vec<GitCommit>> {
+        let mut args = vec![
+            "log".to_string(),
+            "--no-color".to_string(),
+            format!("--max-count={}", max_count.unwrap_or(DEFAULT_MAX_COUNT)),
+            format!("--format={LOG_FORMAT}"),
+        ];
+        if let Some(range) = range {
+            // Keep the range from being read as an option of git
+            if range.starts_with('-') {
+                bail!("Invalid revision range: {range}")
+            }
+            args.push(range);
+        }
+        args.push("--".to_string());
+        if let Some(path) = path {
+            assert_absolute_path(Path::new(&path))?;
+            args.push(path);
+        }
+
+        Ok(parse_log(&self.git(args).await?))
+    }
+
+    async fn blame(
+        &self,
+        path: String,
+        start_line: Option<u64>,
+        end_line: Option<u64>,
+    ) -> anyhow::Result<GitBlameOutput> {
+        assert_absolute_path(Path::new(&path))?;
+        let max_read_size = self.infra.get_environment().max_read_size;
+        let (start_line, end_line) = resolve_range(start_line, end_line, max_read_size);
+
+        // The whole file is blamed because git rejects ranges past its end
+        let args = vec![
+            "blame".to_string(),
+            "--line-porcelain".to_string(),
+            "--".to_string(),
+            path,
+        ];
+        let lines = parse_blame(&self.git(args).await?);
+        let total_lines = lines.len() as u64;
+
+        Ok(GitBlameOutput { hunks: group_blame(lines, start_line, end_line), total_lines })
+    }
+
+    async fn staged_diff(&self) -> anyhow::Result<String> {
+        let args = [
+            "diff",
+            "--cached",
+            "--no-color",
+            "--no-ext-diff",
+            "--stat",
+            "--patch",
+        ];
+        let diff = self.git(args.map(String::from).to_vec()).await?;
+        if diff.trim().is_empty() {
+            bail!("No changes are staged, stage them with git add first")
+        }
+        Ok(diff)
+    }
+}
+
+fn parse_log(output: &str) -> Vec<GitCommit> {
+    output
+        .split(RECORD_SEPARATOR)
+        .filter_map(|record| {
+            let mut fields = record.trim_end().splitn(4, FIELD_SEPARATOR);
+            Some(GitCommit {
+                hash: fields.next()?.to_string(),
+                author: fields.next()?.to_string(),
+                date: fields.next()?.to_string(),
+                summary: fields.next()?.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// A line of the output of `git blame --line-porcelain`
+struct BlameLine {
+    commit: GitCommit,
+    line: u64,
+    content: String,
+}
+
+fn parse_blame(output: &str) -> Vec<BlameLine> {
+    let mut lines = Vec::new();
+    let mut header: Option<(String, u64)> = None;
+    let mut author = String::new();
+    let mut time = 0;
+    let mut timezone = String::new();
+    let mut summary = String::new();
+
+    for line in output.lines() {
+        if let Some(content) = line.strip_prefix('\t') {
+            if let Some((hash, line)) = header.take() {
+                let commit = GitCommit {
+                    hash,
+                    author: std::mem::take(&mut author),
+                    date: format_date(time, &timezone),
+                    summary: std::mem::take(&mut summary),
+                };
+                lines.push(BlameLine { commit, line, content: content.to_string() });
+            }
+        } else if header.is_none() {
+            let mut parts = line.split(' ');
+            let hash = parts.next().unwrap_or_default().to_string();
+            let line = parts
+                .nth(1)
+                .and_then(|line| line.parse().ok())
+                .unwrap_or_default();
+            header = Some((hash, line));
+        } else if let Some((key, value)) = line.split_once(' ') {
+            match key {
+                "author" => author = value.to_string(),
+                "author-time" => time = value.parse().unwrap_or_default(),
+                "author-tz" => timezone = value.to_string(),
+                "summary" => summary = value.to_string(),
+                _ => {}
+            }
+        }
+    }
+
+    lines
+}
+
+/// Formats the time of a commit in the timezone of its author, like `%aI` does
+/// for `git log`
+fn format_date(time: i64, timezone: &str) -> String {
+    let offset = timezone
+        .get(1..)
+        .and_then(|digits| digits.parse::<i32>().ok())
+        .map(|digits| digits / 100 * 3600 + digits % 100 * 60)
+        .map(|seconds| {
+            if timezone.starts_with('-') {
+                -seconds
+            } else {
+                seconds
+            }
+        })
+        .and_then(FixedOffset::east_opt)
+        .unwrap_or(Utc.fix());
+
+    DateTime::from_timestamp(time, 0)
+        .map(|date| date.with_timezone(&offset).to_rfc3339())
+        .unwrap_or_default()
+}
+
+/// Groups the consecutive lines within the range that were last changed by
+/// the same commit
+fn group_blame(lines: Vec<BlameLine>, start_line: u64, end_line: u64) -> Vec<BlameHunk> {
+    let mut hunks: Vec<BlameHunk> = Vec::new();
+    for line in lines
+        .into_iter()
+        .filter(|line| (start_line..=end_line).contains(&line.line))
+    {
+        match hunks.last_mut() {
+            Some(hunk) if hunk.commit.hash == line.commit.hash => {
+                hunk.end_line = line.line;
+                hunk.content.push('\n');
+                hunk.content.push_str(&line.content);
+            }
+            _ => hunks.push(BlameHunk {
+                commit: line.commit,
+                start_line: line.line,
+                end_line: line.line,
+                content: line.content,
+            }),
+        }
+    }
+
+    hunks
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn commit(hash: &str, summary: &str) -> GitCommit {
+        GitCommit {
+            hash: hash.to_string(),
+            author: "Jane Doe".to_string(),
+            date: "2025-06-02T10:00:00+02:00".to_string(),
+            summary: summary.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_log() {
+        let fixture = [
+            [
+                "aaa",
+                "Jane Doe",
+                "2025-06-02T10:00:00+02:00",
+                "Add the cache",
+            ],
+            [
+                "bbb",
+                "Jane Doe",
+                "2025-06-02T10:00:00+02:00",
+                "Fix: keys are case-insensitive",
+            ],
+        ]
+        .map(|fields| format!("{RECORD_SEPARATOR}{}\n", fields.join("\u{1f}")))
+        .concat();
+
+        let actual = parse_log(&fixture);
+
+        let expected = vec![
+            commit("aaa", "Add the cache"),
+            commit("bbb", "Fix: keys are case-insensitive"),
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_parse_and_group_blame() {
+        let fixture = [
+            "aaa 1 1 2",
+            "author Jane Doe",
+            "author-mail <jane@example.com>",
+            "author-time 1748851200",
+            "author-tz +0200",
+            "summary Add the cache",
+            "filename src/cache.rs",
+            "\tuse std::collections::HashMap;",
+            "aaa 2 2",
+            "author Jane Doe",
+            "author-mail <jane@example.com>",
+            "author-time 1748851200",
+            "author-tz +0200",
+            "summary Add the cache",
+            "filename src/cache.rs",
+            "\t",
+            "bbb 5 3 1",
+            "author Jane Doe",
+            "author-mail <jane@example.com>",
+            "author-time 1748851200",
+            "author-tz +0200",
+            "summary Fix: keys are case-insensitive",
+            "previous aaa src/cache.rs",
+            "filename src/cache.rs",
+            "\tpub struct Cache;",
+        ]
+        .join("\n");
+
+        let actual = group_blame(parse_blame(&fixture), 1, 2000);
+
+        let expected = vec![
+            BlameHunk {
+                commit: commit("aaa", "Add the cache"),
+                start_line: 1,
+                end_line: 2,
+                content: "use std::collections::HashMap;\n".to_string(),
+            },
+            BlameHunk {
+                commit: commit("bbb", "Fix: keys are case-insensitive"),
+                start_line: 3,
+                end_line: 3,
+                content: "pub struct Cache;".to_string(),
+            },
+        ];
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,228 @@
+use std::io::Cursor;
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::Context;
+use forge_app::domain::{
+    diff_images, Bitmap, Error, Image, ImageDiffOutput, MAX_IMAGE_DIFF_PIXELS, MAX_IMAGE_DIFF_SIZE,
+};
+use forge_app::ImageDiffService;
+use image::{ImageFormat, ImageReader, RgbaImage};
+
+use crate::{FileInfoInfra, FileReaderInfra};
+
+/// Compares PNG, JPEG and WebP images. They're decoded and the highlighted
+/// differences encoded as a PNG off the async runtime.
+pub struct ForgeImageDiff<F>(Arc<F>);
+
+impl<F> ForgeImageDiff<F> {
+    pub fn new(infra: Arc<F>) -> Self {
+        Self(infra)
+    }
+}
+
+/// Decodes the image, refusing the ones with more pixels than allowed before
+/// allocating them
+fn decode(name: &str, bytes: &[u8]) -> anyhow::Result<Bitmap> {
+    let unsupported = || Error::UnsupportedImage(name.to_string());
+    let (width, height) = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()?
+        .into_dimensions()
+        .map_err(|_| unsupported())?;
+    if width as u64 * height as u64 > MAX_IMAGE_DIFF_PIXELS {
+        return Err(Error::ImageTooLarge(format!("{name} ({width}x{height} pixels)")).into());
+    }
+    let image = image::load_from_memory(bytes)
+        .map_err(|_| unsupported())?
+        .to_rgba8();
+    Ok(Bitmap {
+        width: image.width(),
+        height: image.height(),
+        pixels: image.into_raw(),
+    })
+}
+
+fn encode(bitmap: Bitmap) -> anyhow::Result<Image> {
+    let image = RgbaImage::from_raw(bitmap.width, bitmap.height, bitmap.pixels)
+        .context("The highlighted image doesn't match its size")?;
+    let mut png = Vec::new();
+    image.write_to(&mut Cursor::new(&mut png), ImageFormat::Png)?;
+    Ok(Image::new_bytes(png, "image/png"))
+}
+
+impl<F: FileReaderInfra + FileInfoInfra> ForgeImageDiff<F> {
+    async fn read(&self, path: &Path) -> anyhow::Result<(String, Vec<u8>)> {
+        let name = path.display().to_string();
+        if self.0.file_size(path).await? > MAX_IMAGE_DIFF_SIZE {
+            return Err(Error::ImageTooLarge(format!(
+                "{name} (more than {} MiB)",
+                MAX_IMAGE_DIFF_SIZE >> 20
+            ))
+            .into());
+        }
+        Ok((name, self.0.read(path).await?))
+    }
+}
+
+#[async_trait::async_trait]
+impl<F: FileReaderInfra + FileInfoInfra> ImageDiffService for ForgeImageDiff<F> {
+    async fn diff_images(
+        &self,
+        before: &Path,
+        after: &Path,
+        tolerance: u8,
+    ) -> anyhow::Result<ImageDiffOutput> {
+        let (before_name, before) = self.read(before).await?;
+        let (after_name, after) = self.read(after).await?;
+        tokio::task::spawn_blocking(move || {
+            let before = decode(&before_name, &before)?;
+            let after = decode(&after_name, &after)?;
+            let (comparison, highlighted) = diff_images(&before, &after, tolerance);
+            Ok(ImageDiffOutput { comparison, diff: encode(highlighted)? })
+        })
+        .await?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+    use std::time::SystemTime;
+
+    use forge_app::domain::{DiffRegion, ImageComparison};
+    use image::Rgba;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    /// File system holding the images
+    #[derive(Default)]
+    struct MockInfra {
+        files: BTreeMap<PathBuf, Vec<u8>>,
+    }
+
+    impl MockInfra {
+        fn with_files(files: Vec<(&str, Vec<u8>)>) -> Arc<Self> {
+            let files = files
+                .into_iter()
+                .map(|(path, content)| (PathBuf::from(path), content))
+                .collect();
+            Arc::new(Self { files })
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl FileReaderInfra for MockInfra {
+        async fn read_utf8(&self, path: &Path) -> anyhow::Result<String> {
+            Ok(String::from_utf8(self.read(path).await?)?)
+        }
+
+        async fn read(&self, path: &Path) -> anyhow::Result<Vec<u8>> {
+            self.files
+                .get(path)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("No such file"))
+        }
+
+        async fn read_from(&self, path: &Path, offset: u64) -> anyhow::Result<Vec<u8>> {
+            let content = self.read(path).await?;
+            Ok(content.get(offset as usize..).unwrap_or_default().to_vec())
+        }
+
+        async fn range_read_utf8(
+            &self,
+            _path: &Path,
+            _start_line: u64,
+            _end_line: u64,
+        ) -> anyhow::Result<(String, forge_fs::FileInfo)> {
+            unimplemented!()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl FileInfoInfra for MockInfra {
+        async fn is_file(&self, path: &Path) -> anyhow::Result<bool> {
+            self.exists(path).await
+        }
+
+        async fn exists(&self, path: &Path) -> anyhow::Result<bool> {
+            Ok(self.files.contains_key(path))
+        }
+
+        async fn file_size(&self, path: &Path) -> anyhow::Result<u64> {
+            Ok(self.read(path).await?.len() as u64)
+        }
+
+        async fn modified(&self, _path: &Path) -> anyhow::Result<SystemTime> {
+            Ok(SystemTime::UNIX_EPOCH)
+        }
+
+        async fn list_dir(&self, _path: &Path) -> anyhow::Result<Vec<String>> {
+            unimplemented!()
+        }
+    }
+
+    /// PNG of a white image with a black square at `(x, y)`
+    fn screenshot(width: u32, height: u32, square: Option<(u32, u32)>) -> Vec<u8> {
+        let image = RgbaImage::from_fn(width, height, |x, y| match square {
+            Some((left, top)) if (left..left + 2).contains(&x) && (top..top + 2).contains(&y) => {
+                Rgba([0, 0, 0, 255])
+            }
+            _ => Rgba([255, 255, 255, 255]),
+        });
+        let mut png = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+        png
+    }
+
+    #[tokio::test]
+    async fn test_diff_images() {
+        let fixture = ForgeImageDiff::new(MockInfra::with_files(vec![
+            ("/shots/before.png", screenshot(10, 5, None)),
+            ("/shots/after.png", screenshot(10, 5, Some((6, 1)))),
+        ]));
+
+        let actual = fixture
+            .diff_images(
+                Path::new("/shots/before.png"),
+                Path::new("/shots/after.png"),
+                16,
+            )
+            .await
+            .unwrap();
+
+        let expected = ImageComparison {
+            before: (10, 5),
+            after: (10, 5),
+            changed_pixels: 4,
+            total_pixels: 50,
+            region: Some(DiffRegion { x: 6, y: 1, width: 2, height: 2 }),
+        };
+        assert_eq!(actual.comparison, expected);
+        assert_eq!(actual.diff.mime_type(), "image/png");
+    }
+
+    #[tokio::test]
+    async fn test_diff_unsupported_image() {
+        let fixture = ForgeImageDiff::new(MockInfra::with_files(vec![
+            ("/shots/before.png", screenshot(4, 4, None)),
+            ("/shots/notes.txt", b"not an image".to_vec()),
+        ]));
+
+        let actual = fixture
+            .diff_images(
+                Path::new("/shots/before.png"),
+                Path::new("/shots/notes.txt"),
+                16,
+            )
+            .await
+            .unwrap_err()
+            .to_string();
+
+        let expected = "/shots/notes.txt is not a PNG, JPEG or WebP image";
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,130 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::bail;
+use bytes::Bytes;
+use forge_app::domain::{apply_edits, Diagnostic, Linter, TextEdit};
+use forge_app::{LintFix, LintOutput, LintService};
+
+use crate::utils::assert_absolute_path;
+use crate::{CommandInfra, FileInfoInfra, FileReaderInfra, FileWriterInfra};
+
+/// Runs the linters configured in the projects of the files and applies the
+/// fixes they mark as safe through the same writes as the patches, so that
+/// they can be undone.
+pub struct ForgeLint<I> {
+    infra: Arc<I>,
+}
+
+impl<I> ForgeLint<I> {
+    pub fn new(infra: Arc<I>) -> Self {
+        Self { infra }
+    }
+}
+
+impl<I: CommandInfra + FileInfoInfra> ForgeLint<I> {
+    /// Directory the linter of the file is run from, `None` when the file
+    /// isn't in a project configured for it. Cargo reports the paths relative
+    /// to the workspace, so clippy is run from the outermost manifest.
+    async fn root(&self, linter: Linter, path: &Path) -> anyhow::Result<Option<PathBuf>> {
+        let mut roots = Vec::new();
+        for dir in path.ancestors().skip(1) {
+            for marker in linter.markers() {
+                if self.infra.is_file(&dir.join(marker)).await? {
+                    roots.push(dir.to_path_buf());
+                    break;
+                }
+            }
+        }
+        Ok(match linter {
+            Linter::Clippy => roots.pop(),
+            Linter::Eslint => roots.into_iter().next(),
+        })
+    }
+
+    /// Runs each linter from its root and returns the diagnostics of the
+    /// files it was run for
+    async fn run(
+        &self,
+        projects: &BTreeMap<(Linter, PathBuf), Vec<String>>,
+    ) -> anyhow::Result<Vec<Diagnostic>> {
+        let mut diagnostics = Vec::new();
+        for ((linter, root), paths) in projects {
+            let mut args = linter.command(paths);
+            let program = args.remove(0);
+            let output = self
+                .infra
+                .execute_program(&program, args, root.clone())
+                .await?;
+
+            // Linters exit with an error when they report errors, only a run
+            // without any output failed
+            if !output.success() && output.stdout.trim().is_empty() {
+                bail!("{linter} failed: {}", output.stderr.trim())
+            }
+            diagnostics.extend(
+                linter
+                    .parse(&output.stdout, root)
+                    .into_iter()
+                    .filter(|diagnostic| paths.contains(&diagnostic.path)),
+            );
+        }
+        // Clippy reports the problems of a file once for each target including it
+        diagnostics.sort_by(|a, b| (&a.path, a.line, a.column).cmp(&(&b.path, b.line, b.column)));
+        diagnostics.dedup();
+        Ok(diagnostics)
+    }
+}
+
+#[async_trait::async_trait]
+impl<I: CommandInfra + FileInfoInfra + FileReaderInfra + FileWriterInfra> LintService
+    for ForgeLint<I>
+{
+    async fn lint(&self, paths: Vec<String>, fix: bool) -> anyhow::Result<LintOutput> {
+        let mut projects = BTreeMap::<_, Vec<String>>::new();
+        let mut skipped = Vec::new();
+        for path in paths {
+            assert_absolute_path(Path::new(&path))?;
+            let Some(linter) = Linter::for_path(Path::new(&path)) else {
+                skipped.push(path);
+                continue;
+            };
+            match self.root(linter, Path::new(&path)).await? {
+                Some(root) => projects.entry((linter, root)).or_default().push(path),
+                None => skipped.push(path),
+            }
+        }
+
+        let diagnostics = self.run(&projects).await?;
+        if !fix {
+            return Ok(LintOutput { diagnostics, fixes: Vec::new(), skipped });
+        }
+
+        let mut edits = BTreeMap::<_, Vec<TextEdit>>::new();
+        for diagnostic in &diagnostics {
+            edits
+                .entry(diagnostic.path.clone())
+                .or_default()
+                .extend(diagnostic.fixes.iter().cloned());
+        }
+        let mut fixes = Vec::new();
+        for (path, edits) in edits.into_iter().filter(|(_, edits)| !edits.is_empty()) {
+            let before = self.infra.read_utf8(Path::new(&path)).await?;
+            let (after, applied) = apply_edits(&before, &edits);
+            if applied == 0 {
+                continue;
+            }
+            self.infra
+                .write(Path::new(&path), Bytes::from(after.clone()), true)
+                .await?;
+            fixes.push(LintFix { path, applied, before, after });
+        }
+        if fixes.is_empty() {
+            return Ok(LintOutput { diagnostics, fixes, skipped });
+        }
+
+        let diagnostics = self.run(&projects).await?;
+        Ok(LintOutput { diagnostics, fixes, skipped })
+    }
+}
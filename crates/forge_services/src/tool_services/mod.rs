@@ -1,20 +1,42 @@
+mod archive;
+mod discover_tasks;
 mod fetch;
 mod followup;
+mod fs_checkpoint;
 mod fs_create;
+mod fs_meta;
 mod fs_patch;
 mod fs_read;
 mod fs_remove;
 mod fs_search;
+mod fs_snapshot;
 mod fs_undo;
+mod fs_write_draft;
+mod git;
+mod image_diff;
+mod lint;
+mod process_env;
 mod shell;
-mod syn;
+pub(crate) mod syn;
+mod toolchain_info;
 
+pub use archive::*;
+pub use discover_tasks::*;
 pub use fetch::*;
 pub use followup::*;
+pub use fs_checkpoint::*;
 pub use fs_create::*;
+pub use fs_meta::*;
 pub use fs_patch::*;
 pub use fs_read::*;
 pub use fs_remove::*;
 pub use fs_search::*;
+pub use fs_snapshot::*;
 pub use fs_undo::*;
+pub use fs_write_draft::*;
+pub use git::*;
+pub use image_diff::*;
+pub use lint::*;
+pub use process_env::*;
 pub use shell::*;
+pub use toolchain_info::*;
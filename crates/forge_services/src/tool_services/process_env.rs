@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use forge_app::domain::EnvVar;
+use forge_app::ProcessEnvService;
+
+use crate::EnvironmentInfra;
+
+/// Exposes the environment variables of the process with the values that
+/// could hold secrets redacted.
+pub struct ForgeProcessEnv<I> {
+    infra: Arc<I>,
+}
+
+impl<I> ForgeProcessEnv<I> {
+    pub fn new(infra: Arc<I>) -> Self {
+        Self { infra }
+    }
+}
+
+#[async_trait::async_trait]
+impl<I: EnvironmentInfra> ProcessEnvService for ForgeProcessEnv<I> {
+    async fn process_env(&self, name: Option<String>) -> anyhow::Result<Vec<EnvVar>> {
+        let mut vars = self
+            .infra
+            .get_env_vars()
+            .into_iter()
+            .filter(|(key, _)| name.as_ref().is_none_or(|name| name == key))
+            .map(|(key, value)| EnvVar::redacted(key, &value))
+            .collect::<Vec<_>>();
+        vars.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(vars)
+    }
+}
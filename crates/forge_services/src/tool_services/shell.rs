@@ -1,8 +1,9 @@
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 
 use anyhow::bail;
-use forge_app::domain::Environment;
+use forge_app::domain::{Environment, OutputRing};
 use forge_app::{ShellOutput, ShellService};
 use strip_ansi_escapes::strip;
 
@@ -47,16 +48,25 @@ impl<I: CommandInfra + EnvironmentInfra> ShellService for ForgeShell<I> {
         command: String,
         cwd: PathBuf,
         keep_ansi: bool,
+        env: BTreeMap<String, String>,
+        progress: Option<OutputRing>,
     ) -> anyhow::Result<ShellOutput> {
         Self::validate_command(&command)?;
 
-        let mut output = self.infra.execute_command(command, cwd).await?;
+        let mut output = match progress {
+            Some(progress) => {
+                self.infra
+                    .execute_command_with_progress(command, cwd, env, progress)
+                    .await?
+            }
+            None => self.infra.execute_command(command, cwd, env).await?,
+        };
 
         if !keep_ansi {
             output.stdout = strip_ansi(output.stdout);
             output.stderr = strip_ansi(output.stderr);
         }
 
-        Ok(ShellOutput { output, shell: self.env.shell.clone() })
+        Ok(ShellOutput { output, shell: self.env.shell.clone(), attempts: 1 })
     }
 }
@@ -0,0 +1,130 @@
+use std::path::Path;
+
+use tree_sitter::{Node, Parser};
+
+use super::validate::extension;
+
+/// Extracts the modules imported by a source file, as written in the import
+/// statements of its language, e.g. `crate::fs::{read, write}` for Rust or
+/// `./utils` for JavaScript. Rust `mod` declarations without a body are
+/// returned as `self::<name>`.
+///
+/// Files in unsupported languages or that can't be parsed have no imports.
+pub fn imports(path: impl AsRef<Path>, content: &str) -> Vec<String> {
+    let Some(ext) = path.as_ref().extension().and_then(|ext| ext.to_str()) else {
+        return Vec::new();
+    };
+    let Some(language) = extension(ext) else {
+        return Vec::new();
+    };
+
+    let mut parser = Parser::new();
+    if parser.set_language(&language).is_err() {
+        return Vec::new();
+    }
+    let Some(tree) = parser.parse(content, None) else {
+        return Vec::new();
+    };
+
+    let mut imports = Vec::new();
+    collect(tree.root_node(), content.as_bytes(), &mut imports);
+    imports
+}
+
+fn collect(node: Node, source: &[u8], imports: &mut Vec<String>) {
+    let text = |node: Node| node.utf8_text(source).unwrap_or_default().to_string();
+    let unquote = |text: String| {
+        text.trim_matches(|c| matches!(c, '"' | '\'' | '`'))
+            .to_string()
+    };
+
+    match node.kind() {
+        // Rust
+        "use_declaration" => imports.extend(node.child_by_field_name("argument").map(text)),
+        "mod_item" if node.child_by_field_name("body").is_none() => {
+            let name = node.child_by_field_name("name");
+            imports.extend(name.map(|name| format!("self::{}", text(name))))
+        }
+        // Python
+        "import_statement" if node.child_by_field_name("source").is_none() => {
+            let mut cursor = node.walk();
+            for name in node.children_by_field_name("name", &mut cursor) {
+                let name = name.child_by_field_name("name").unwrap_or(name);
+                imports.push(text(name));
+            }
+        }
+        "import_from_statement" => {
+            imports.extend(node.child_by_field_name("module_name").map(text))
+        }
+        // JavaScript and TypeScript
+        "import_statement" | "export_statement" => {
+            imports.extend(node.child_by_field_name("source").map(text).map(unquote))
+        }
+        // Go
+        "import_spec" => imports.extend(node.child_by_field_name("path").map(text).map(unquote)),
+        // Java
+        "import_declaration" => {
+            let declaration = text(node);
+            let module = declaration
+                .trim_start_matches("import")
+                .trim()
+                .trim_start_matches("static")
+                .trim_end_matches(';')
+                .trim();
+            imports.push(module.to_string());
+        }
+        _ => {
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                collect(child, source, imports);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_rust_imports() {
+        let fixture = r#"
+use std::sync::Arc;
+use crate::{fs::read, utils};
+mod service;
+mod tests {}
+"#;
+        let actual = imports("src/lib.rs", fixture);
+        let expected = vec![
+            "std::sync::Arc",
+            "crate::{fs::read, utils}",
+            "self::service",
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_python_imports() {
+        let fixture = "import os.path\nimport numpy as np\nfrom .models import User\n";
+        let actual = imports("app/views.py", fixture);
+        let expected = vec!["os.path", "numpy", ".models"];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_typescript_imports() {
+        let fixture = "import { a } from './a';\nexport * from \"../b\";\nconst c = 1;\n";
+        let actual = imports("src/index.ts", fixture);
+        let expected = vec!["./a", "../b"];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_unsupported_language() {
+        let actual = imports("README.md", "# import this");
+        let expected: Vec<String> = vec![];
+        assert_eq!(actual, expected);
+    }
+}
@@ -1,3 +1,5 @@
+mod imports;
 mod validate;
 
+pub use imports::imports;
 pub use validate::validate;
@@ -0,0 +1,61 @@
+use std::sync::Arc;
+
+use forge_app::domain::{Toolchain, TOOLCHAIN_PROBES};
+use forge_app::ToolchainService;
+use tokio::sync::Mutex;
+
+use crate::{CommandInfra, EnvironmentInfra};
+
+/// Reads the versions of the toolchains installed on the PATH, once per
+/// session since installing a tool while the agent runs is rare.
+pub struct ForgeToolchainInfo<I> {
+    infra: Arc<I>,
+    cached_toolchains: Arc<Mutex<Option<Vec<Toolchain>>>>,
+}
+
+impl<I> ForgeToolchainInfo<I> {
+    pub fn new(infra: Arc<I>) -> Self {
+        Self { infra, cached_toolchains: Arc::new(Mutex::new(None)) }
+    }
+}
+
+impl<I: CommandInfra + EnvironmentInfra> ForgeToolchainInfo<I> {
+    /// Runs the program with the arguments printing its version, `None`
+    /// when it isn't installed
+    async fn probe(&self, name: &str, program: &str, args: &[&str]) -> Option<Toolchain> {
+        let cwd = self.infra.get_environment().cwd;
+        let args = args.iter().map(|arg| arg.to_string()).collect();
+        let output = self.infra.execute_program(program, args, cwd).await.ok()?;
+        if output.exit_code != Some(0) {
+            return None;
+        }
+        // Java prints its version on stderr
+        let printed = match output.stdout.trim().is_empty() {
+            true => &output.stderr,
+            false => &output.stdout,
+        };
+        Some(Toolchain::parse(name, printed))
+    }
+}
+
+#[async_trait::async_trait]
+impl<I: CommandInfra + EnvironmentInfra> ToolchainService for ForgeToolchainInfo<I> {
+    async fn toolchains(&self, refresh: bool) -> anyhow::Result<Vec<Toolchain>> {
+        let mut cached_toolchains = self.cached_toolchains.lock().await;
+        if let Some(toolchains) = cached_toolchains.as_ref().filter(|_| !refresh) {
+            return Ok(toolchains.clone());
+        }
+
+        let toolchains = futures::future::join_all(
+            TOOLCHAIN_PROBES
+                .iter()
+                .map(|(name, program, args)| self.probe(name, program, args)),
+        )
+        .await
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+        *cached_toolchains = Some(toolchains.clone());
+        Ok(toolchains)
+    }
+}
@@ -0,0 +1,50 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use bytes::Bytes;
+use forge_app::domain::{ToolCallSample, ToolStatsLedger};
+use forge_app::ToolStatsService;
+use tokio::sync::Mutex;
+
+use crate::{EnvironmentInfra, FileInfoInfra, FileReaderInfra, FileWriterInfra};
+
+/// Keeps the statistics of the tool calls in a JSON file shared by all
+/// workspaces
+pub struct ForgeToolStatsService<I> {
+    infra: Arc<I>,
+    /// Serializes the updates of the statistics within the process
+    lock: Mutex<()>,
+}
+
+impl<I> ForgeToolStatsService<I> {
+    pub fn new(infra: Arc<I>) -> Self {
+        Self { infra, lock: Mutex::new(()) }
+    }
+}
+
+#[async_trait::async_trait]
+impl<I: FileReaderInfra + FileWriterInfra + FileInfoInfra + EnvironmentInfra> ToolStatsService
+    for ForgeToolStatsService<I>
+{
+    async fn record_tool_stats(&self, sample: ToolCallSample) -> anyhow::Result<()> {
+        let _guard = self.lock.lock().await;
+        let path = self.infra.get_environment().tool_stats_path();
+        // The statistics are shared by the processes of every workspace
+        let _file = self.infra.lock(&path).await?;
+        let mut ledger = self.tool_stats().await?;
+        ledger.record(&sample);
+        let content = Bytes::from(serde_json::to_vec_pretty(&ledger)?);
+        self.infra.write(&path, content, false).await
+    }
+
+    async fn tool_stats(&self) -> anyhow::Result<ToolStatsLedger> {
+        let path = self.infra.get_environment().tool_stats_path();
+        if !self.infra.exists(&path).await? {
+            return Ok(ToolStatsLedger::default());
+        }
+
+        let content = self.infra.read(&path).await?;
+        serde_json::from_slice(&content)
+            .with_context(|| format!("Invalid tool statistics: {}", path.display()))
+    }
+}
@@ -0,0 +1,174 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::Context;
+use bytes::Bytes;
+use chrono::Utc;
+use forge_app::domain::{BoxStream, ChatCompletionMessage, Usage, UsageLedger, UsageQuota};
+use futures::StreamExt;
+use sha2::{Digest, Sha256};
+use tokio::sync::Mutex;
+
+use crate::{FileInfoInfra, FileReaderInfra, FileWriterInfra};
+
+/// Keeps the ledger of the usage of the providers and enforces the daily
+/// quota of the deployment
+pub struct UsageAccounting<F> {
+    infra: Arc<F>,
+    path: PathBuf,
+    quota: UsageQuota,
+    workspace: PathBuf,
+    /// Serializes the updates of the ledger within the process
+    lock: Mutex<()>,
+}
+
+impl<F> UsageAccounting<F> {
+    pub fn new(infra: Arc<F>, path: PathBuf, quota: UsageQuota, workspace: PathBuf) -> Self {
+        Self { infra, path, quota, workspace, lock: Mutex::new(()) }
+    }
+
+    /// Fingerprint identifying an API key in the ledger without storing it
+    pub fn account(key: Option<&str>) -> String {
+        let Some(key) = key else {
+            return "anonymous".to_string();
+        };
+        let digest = format!("{:x}", Sha256::digest(key.as_bytes()));
+        digest[..12].to_string()
+    }
+}
+
+impl<F: FileReaderInfra + FileWriterInfra + FileInfoInfra + 'static> UsageAccounting<F> {
+    pub async fn read(&self) -> anyhow::Result<UsageLedger> {
+        if !self.infra.exists(&self.path).await? {
+            return Ok(UsageLedger::default());
+        }
+
+        let content = self.infra.read(&self.path).await?;
+        serde_json::from_slice(&content)
+            .with_context(|| format!("Invalid usage ledger: {}", self.path.display()))
+    }
+
+    /// Fails with `QuotaExceeded` when the account already reached a limit of
+    /// the quota today
+    pub async fn check(&self, account: &str) -> anyhow::Result<()> {
+        if !self.quota.is_enabled() {
+            return Ok(());
+        }
+
+        let today = Utc::now().date_naive();
+        self.read()
+            .await?
+            .check(&self.quota, today, &self.workspace, account)?;
+        Ok(())
+    }
+
+    async fn add(&self, account: &str, usage: &Usage) -> anyhow::Result<()> {
+        let _guard = self.lock.lock().await;
+        // The ledger is shared by the processes of every workspace
+        let _file = self.infra.lock(&self.path).await?;
+        let mut ledger = self.read().await?;
+        ledger.record(Utc::now().date_naive(), &self.workspace, account, usage);
+        let content = Bytes::from(serde_json::to_vec_pretty(&ledger)?);
+        self.infra.write(&self.path, content, false).await
+    }
+
+    /// Passes the messages of `stream` through and adds the usage of the
+    /// response to the ledger once it ends, or once it's dropped when the
+    /// response is interrupted
+    pub fn record(
+        self: &Arc<Self>,
+        account: &str,
+        stream: BoxStream<ChatCompletionMessage, anyhow::Error>,
+    ) -> BoxStream<ChatCompletionMessage, anyhow::Error> {
+        let pending = PendingUsage {
+            accounting: self.clone(),
+            account: account.to_string(),
+            usage: None,
+        };
+
+        Box::pin(futures::stream::unfold(
+            (stream, Some(pending)),
+            |(mut stream, mut pending)| async move {
+                match stream.next().await {
+                    Some(message) => {
+                        if let (
+                            Ok(ChatCompletionMessage { usage: Some(latest), .. }),
+                            Some(pending),
+                        ) = (&message, pending.as_mut())
+                        {
+                            pending.usage = Some(latest.clone());
+                        }
+                        Some((message, (stream, pending)))
+                    }
+                    None => {
+                        if let Some(pending) = pending {
+                            pending.finish().await;
+                        }
+                        None
+                    }
+                }
+            },
+        ))
+    }
+}
+
+/// Usage of a response that is still streamed
+struct PendingUsage<F: FileReaderInfra + FileWriterInfra + FileInfoInfra + 'static> {
+    accounting: Arc<UsageAccounting<F>>,
+    account: String,
+    usage: Option<Usage>,
+}
+
+impl<F: FileReaderInfra + FileWriterInfra + FileInfoInfra + 'static> PendingUsage<F> {
+    async fn finish(mut self) {
+        if let Some(usage) = self.usage.take() {
+            if let Err(error) = self.accounting.add(&self.account, &usage).await {
+                tracing::warn!(error = ?error, "Failed to record the usage");
+            }
+        }
+    }
+}
+
+impl<F: FileReaderInfra + FileWriterInfra + FileInfoInfra + 'static> Drop for PendingUsage<F> {
+    /// Records the usage of an interrupted response in the background, as
+    /// the tokens were billed anyway
+    fn drop(&mut self) {
+        let Some(usage) = self.usage.take() else {
+            return;
+        };
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+
+        let accounting = self.accounting.clone();
+        let account = std::mem::take(&mut self.account);
+        runtime.spawn(async move {
+            if let Err(error) = accounting.add(&account, &usage).await {
+                tracing::warn!(error = ?error, "Failed to record the usage");
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    type Accounting = UsageAccounting<()>;
+
+    #[test]
+    fn test_account_hides_the_key() {
+        let actual = [
+            Accounting::account(Some("sk-secret")),
+            Accounting::account(Some("sk-secret")),
+            Accounting::account(None),
+        ];
+
+        assert_eq!(actual[0], actual[1]);
+        assert_eq!(actual[0].len(), 12);
+        assert!(!actual[0].contains("secret"));
+        assert_eq!(actual[2], "anonymous");
+    }
+}
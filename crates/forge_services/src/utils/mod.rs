@@ -1,4 +1,5 @@
 mod path;
+mod write_size;
 
 #[cfg(test)]
 mod extensions;
@@ -11,3 +12,4 @@ pub use extensions::*;
 pub use path::*;
 #[cfg(test)]
 pub use temp_dir::*;
+pub use write_size::*;
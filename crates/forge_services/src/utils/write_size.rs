@@ -0,0 +1,57 @@
+use std::path::Path;
+
+use anyhow::bail;
+
+/// Ensures that content written to a file doesn't exceed the write size
+/// limit, unless the tool call explicitly allows large content
+///
+/// # Arguments
+/// * `path` - The path of the file being written
+/// * `size` - The size in bytes of the content to write
+/// * `max_write_size` - Maximum allowed size in bytes
+/// * `allow_large` - Whether the limit was explicitly overridden
+///
+/// # Returns
+/// * `Ok(())` if the content is within the limit or the limit was overridden
+/// * `Err(anyhow::Error)` with instructions to override the limit otherwise
+pub fn assert_write_size(
+    path: &Path,
+    size: usize,
+    max_write_size: u64,
+    allow_large: bool,
+) -> anyhow::Result<()> {
+    if allow_large || size as u64 <= max_write_size {
+        return Ok(());
+    }
+
+    bail!(
+        "Refusing to write {size} bytes to {}: content exceeds the maximum write size of {max_write_size} bytes. Reduce the content, or set allow_large to true if a file this large is intended.",
+        path.display()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_size_within_limit() {
+        let actual = assert_write_size(Path::new("/src/main.rs"), 1024, 1024, false);
+        assert!(actual.is_ok());
+    }
+
+    #[test]
+    fn test_write_size_above_limit() {
+        let actual = assert_write_size(Path::new("/src/main.rs"), 1025, 1024, false);
+        assert!(actual
+            .unwrap_err()
+            .to_string()
+            .contains("exceeds the maximum write size of 1024 bytes"));
+    }
+
+    #[test]
+    fn test_write_size_above_limit_allowed() {
+        let actual = assert_write_size(Path::new("/src/main.rs"), 1025, 1024, true);
+        assert!(actual.is_ok());
+    }
+}
@@ -0,0 +1,44 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use forge_app::domain::WorkspaceSettings;
+use forge_app::WorkspaceSettingsService;
+
+use crate::{EnvironmentInfra, FileInfoInfra, FileReaderInfra, FileWriterInfra};
+
+pub struct ForgeWorkspaceSettingsService<I> {
+    infra: Arc<I>,
+}
+
+impl<I: FileReaderInfra + FileWriterInfra + FileInfoInfra + EnvironmentInfra>
+    ForgeWorkspaceSettingsService<I>
+{
+    pub fn new(infra: Arc<I>) -> Self {
+        Self { infra }
+    }
+}
+
+#[async_trait::async_trait]
+impl<I: FileReaderInfra + FileWriterInfra + FileInfoInfra + EnvironmentInfra>
+    WorkspaceSettingsService for ForgeWorkspaceSettingsService<I>
+{
+    async fn read_workspace_settings(&self) -> anyhow::Result<WorkspaceSettings> {
+        let path = self.infra.get_environment().workspace_settings();
+        if !self.infra.exists(&path).await? {
+            return Ok(WorkspaceSettings::default());
+        }
+        let content = self.infra.read(&path).await?;
+        Ok(serde_json::from_slice(&content)?)
+    }
+
+    async fn write_workspace_settings(&self, settings: &WorkspaceSettings) -> anyhow::Result<()> {
+        let path = self.infra.get_environment().workspace_settings();
+        self.infra
+            .write(
+                &path,
+                Bytes::from(serde_json::to_vec_pretty(settings)?),
+                false,
+            )
+            .await
+    }
+}
@@ -1,7 +1,11 @@
 // Export the modules
+mod metadata;
+mod retention;
 mod service;
 mod snapshot;
 
 // Re-export the SnapshotInfo struct and SnapshotId
+pub use metadata::FileMetadata;
+pub use retention::RetentionPolicy;
 pub use service::*;
 pub use snapshot::{Snapshot, SnapshotId};
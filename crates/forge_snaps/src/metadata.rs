@@ -0,0 +1,154 @@
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use forge_fs::ForgeFS;
+use serde::{Deserialize, Serialize};
+
+/// File attributes that aren't part of the content, stored next to a snapshot
+/// so that restoring it doesn't lose them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileMetadata {
+    /// Unix permission bits of the file, including the execute bits
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<u32>,
+
+    /// Target of the path when it was a symbolic link
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symlink: Option<PathBuf>,
+}
+
+impl FileMetadata {
+    /// Reads the attributes of `path` without following it if it's a symlink
+    pub async fn capture(path: &Path) -> Self {
+        let symlink = tokio::fs::read_link(path).await.ok();
+        let mode = tokio::fs::metadata(path)
+            .await
+            .ok()
+            .and_then(|metadata| mode(&metadata));
+
+        Self { mode, symlink }
+    }
+
+    /// Path of the metadata stored for the snapshot at `snapshot_path`
+    pub fn path(snapshot_path: &Path) -> PathBuf {
+        let mut path = OsString::from(snapshot_path.as_os_str());
+        path.push(".meta");
+        PathBuf::from(path)
+    }
+
+    /// Loads the metadata of a snapshot. Snapshots taken before metadata was
+    /// recorded have none, in which case the defaults are returned.
+    pub async fn load(snapshot_path: &Path) -> Result<Self> {
+        let path = Self::path(snapshot_path);
+        if !ForgeFS::exists(&path) {
+            return Ok(Self::default());
+        }
+
+        let content = ForgeFS::read(&path).await?;
+        serde_json::from_slice(&content)
+            .with_context(|| format!("Failed to parse snapshot metadata {}", path.display()))
+    }
+
+    pub async fn save(&self, snapshot_path: &Path) -> Result<()> {
+        if *self == Self::default() {
+            return Ok(());
+        }
+
+        ForgeFS::write(Self::path(snapshot_path), serde_json::to_vec(self)?).await
+    }
+
+    /// Writes `content` to `path`, recreating the symlink if it was removed
+    /// and restoring the permissions of the file.
+    pub async fn restore(&self, path: &Path, content: Vec<u8>) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            ForgeFS::create_dir_all(parent).await?;
+        }
+
+        // An existing link is written through, so only a removed link needs to
+        // be created again
+        if let Some(target) = self.symlink.as_ref() {
+            if tokio::fs::symlink_metadata(path).await.is_err() {
+                symlink(target, path).await?;
+            }
+        }
+
+        ForgeFS::write(path, content).await?;
+
+        if let Some(mode) = self.mode {
+            set_mode(path, mode).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Removes a snapshot along with its metadata
+pub async fn remove_snapshot(snapshot_path: &Path) -> Result<()> {
+    ForgeFS::remove_file(snapshot_path).await?;
+    let metadata_path = FileMetadata::path(snapshot_path);
+    if ForgeFS::exists(&metadata_path) {
+        ForgeFS::remove_file(metadata_path).await?;
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn mode(metadata: &std::fs::Metadata) -> Option<u32> {
+    use std::os::unix::fs::PermissionsExt;
+    Some(metadata.permissions().mode())
+}
+
+#[cfg(not(unix))]
+fn mode(_metadata: &std::fs::Metadata) -> Option<u32> {
+    None
+}
+
+#[cfg(unix)]
+async fn set_mode(path: &Path, mode: u32) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    tokio::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))
+        .await
+        .with_context(|| format!("Failed to set permissions of {}", path.display()))
+}
+
+#[cfg(not(unix))]
+async fn set_mode(_path: &Path, _mode: u32) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(unix)]
+async fn symlink(target: &Path, path: &Path) -> Result<()> {
+    tokio::fs::symlink(target, path)
+        .await
+        .with_context(|| format!("Failed to create symlink {}", path.display()))
+}
+
+#[cfg(windows)]
+async fn symlink(target: &Path, path: &Path) -> Result<()> {
+    tokio::fs::symlink_file(target, path)
+        .await
+        .with_context(|| format!("Failed to create symlink {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metadata_path() {
+        let fixture = Path::new("/snaps/abc/2025-01-01_12-00-00-000000000_id.snap");
+        let actual = FileMetadata::path(fixture);
+        let expected = PathBuf::from("/snaps/abc/2025-01-01_12-00-00-000000000_id.snap.meta");
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_load_without_metadata() {
+        let actual = FileMetadata::load(Path::new("/this/path/does/not/exist.snap"))
+            .await
+            .unwrap();
+        let expected = FileMetadata::default();
+        assert_eq!(actual, expected);
+    }
+}
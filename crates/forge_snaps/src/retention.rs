@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+/// Limits applied to the stored snapshots when they are purged
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    /// Maximum number of snapshots kept for each file
+    pub max_per_file: Option<usize>,
+    /// Maximum age of a snapshot
+    pub max_age: Option<Duration>,
+    /// Maximum size of all the snapshots together, in bytes
+    pub max_total_size: Option<u64>,
+}
+
+/// A stored snapshot considered for eviction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct StoredSnapshot {
+    pub path: PathBuf,
+    /// Directory holding all the snapshots of the same file
+    pub group: PathBuf,
+    pub created_at: SystemTime,
+    pub size: u64,
+}
+
+impl RetentionPolicy {
+    /// Returns the snapshots to remove so that the remaining ones satisfy the
+    /// policy. The oldest snapshots are evicted first.
+    pub(crate) fn evict(
+        &self,
+        mut snapshots: Vec<StoredSnapshot>,
+        now: SystemTime,
+    ) -> Vec<PathBuf> {
+        let mut evicted = Vec::new();
+
+        // Newest first, so that the snapshots beyond a limit are the oldest
+        snapshots.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+        if let Some(max_age) = self.max_age {
+            let (keep, expired): (Vec<_>, Vec<_>) = snapshots.into_iter().partition(|snapshot| {
+                now.duration_since(snapshot.created_at).unwrap_or_default() <= max_age
+            });
+            snapshots = keep;
+            evicted.extend(expired.into_iter().map(|snapshot| snapshot.path));
+        }
+
+        if let Some(max_per_file) = self.max_per_file {
+            let mut counts = HashMap::new();
+            let (keep, excess): (Vec<_>, Vec<_>) = snapshots.into_iter().partition(|snapshot| {
+                let count = counts.entry(snapshot.group.clone()).or_insert(0);
+                *count += 1;
+                *count <= max_per_file
+            });
+            snapshots = keep;
+            evicted.extend(excess.into_iter().map(|snapshot| snapshot.path));
+        }
+
+        if let Some(max_total_size) = self.max_total_size {
+            let mut total = snapshots.iter().map(|snapshot| snapshot.size).sum::<u64>();
+            while total > max_total_size {
+                let Some(oldest) = snapshots.pop() else {
+                    break;
+                };
+                total -= oldest.size;
+                evicted.push(oldest.path);
+            }
+        }
+
+        evicted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    fn snapshot(
+        name: &str,
+        group: &str,
+        age_days: u32,
+        size: u64,
+        now: SystemTime,
+    ) -> StoredSnapshot {
+        StoredSnapshot {
+            path: PathBuf::from(name),
+            group: PathBuf::from(group),
+            created_at: now - DAY * age_days,
+            size,
+        }
+    }
+
+    fn fixture(now: SystemTime) -> Vec<StoredSnapshot> {
+        vec![
+            snapshot("a1", "a", 1, 10, now),
+            snapshot("a2", "a", 2, 10, now),
+            snapshot("a3", "a", 3, 10, now),
+            snapshot("b1", "b", 4, 10, now),
+            snapshot("b2", "b", 40, 10, now),
+        ]
+    }
+
+    #[test]
+    fn test_evict_by_age_and_count() {
+        let now = SystemTime::now();
+        let policy = RetentionPolicy {
+            max_per_file: Some(2),
+            max_age: Some(DAY * 30),
+            max_total_size: None,
+        };
+
+        let actual = policy.evict(fixture(now), now);
+
+        let expected = vec![PathBuf::from("b2"), PathBuf::from("a3")];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_evict_by_total_size() {
+        let now = SystemTime::now();
+        let policy = RetentionPolicy { max_total_size: Some(25), ..Default::default() };
+
+        let actual = policy.evict(fixture(now), now);
+
+        let expected = vec![
+            PathBuf::from("b2"),
+            PathBuf::from("b1"),
+            PathBuf::from("a3"),
+        ];
+        assert_eq!(actual, expected);
+    }
+}
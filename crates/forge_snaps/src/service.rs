@@ -1,9 +1,12 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 use anyhow::{Context, Result};
 use forge_fs::ForgeFS;
 
-use crate::snapshot::Snapshot;
+use crate::metadata::{remove_snapshot, FileMetadata};
+use crate::retention::{RetentionPolicy, StoredSnapshot};
+use crate::snapshot::{Snapshot, SnapshotId};
 
 /// Implementation of the SnapshotService
 #[derive(Debug)]
@@ -73,14 +76,122 @@ impl SnapshotService {
             .context(format!("No valid snapshots found for {path:?}"))?;
 
         // Restore the content
-        let content = ForgeFS::read(&snapshot_path).await?;
-        ForgeFS::write(&path, content).await?;
+        Self::restore_file(&snapshot_path, &path).await?;
 
         // Remove the used snapshot
-        ForgeFS::remove_file(&snapshot_path).await?;
+        remove_snapshot(&snapshot_path).await?;
 
         Ok(())
     }
+
+    /// Writes the content of the snapshot at `snapshot_path` back to `path`
+    /// along with the permissions and the symlink it was taken from
+    async fn restore_file(snapshot_path: &Path, path: &Path) -> Result<()> {
+        let content = ForgeFS::read(snapshot_path).await?;
+        FileMetadata::load(snapshot_path)
+            .await?
+            .restore(path, content)
+            .await
+    }
+
+    /// Lists the snapshots of `path`, oldest first
+    pub async fn list_snapshots(&self, path: PathBuf) -> Result<Vec<Snapshot>> {
+        let snapshot = Snapshot::create(path)?;
+        let snapshot_dir = self.snapshots_directory.join(snapshot.path_hash());
+        if !ForgeFS::exists(&snapshot_dir) {
+            return Ok(Vec::new());
+        }
+
+        let mut snapshots = Vec::new();
+        let mut dir = ForgeFS::read_dir(&snapshot_dir).await?;
+        while let Some(entry) = dir.next_entry().await? {
+            let filename = entry.file_name().to_string_lossy().to_string();
+            if let Some(found) = Snapshot::from_file_name(snapshot.path.clone(), &filename) {
+                snapshots.push(found);
+            }
+        }
+        snapshots.sort_by_key(|snapshot| snapshot.timestamp);
+
+        Ok(snapshots)
+    }
+
+    /// Restores `path` to the content captured by the snapshot `id`. The
+    /// snapshot and all the newer snapshots of `path` are removed, just like
+    /// undoing them one after another would.
+    pub async fn restore_snapshot(&self, path: PathBuf, id: &SnapshotId) -> Result<()> {
+        let snapshots = self.list_snapshots(path.clone()).await?;
+        let position = snapshots
+            .iter()
+            .position(|snapshot| snapshot.id == *id)
+            .context(format!("Snapshot {id} not found for {path:?}"))?;
+
+        // Restore the content
+        let snapshot_path =
+            snapshots[position].snapshot_path(Some(self.snapshots_directory.clone()));
+        Self::restore_file(&snapshot_path, &path).await?;
+
+        // Remove the used snapshot and the ones taken after it
+        for snapshot in &snapshots[position..] {
+            remove_snapshot(&snapshot.snapshot_path(Some(self.snapshots_directory.clone())))
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the content captured by the snapshot `id` back to `path`,
+    /// keeping the snapshots so that it can be written back again
+    pub async fn apply_snapshot(&self, path: PathBuf, id: &SnapshotId) -> Result<()> {
+        let snapshot = self
+            .list_snapshots(path.clone())
+            .await?
+            .into_iter()
+            .find(|snapshot| snapshot.id == *id)
+            .context(format!("Snapshot {id} not found for {path:?}"))?;
+
+        let snapshot_path = snapshot.snapshot_path(Some(self.snapshots_directory.clone()));
+        Self::restore_file(&snapshot_path, &path).await
+    }
+
+    /// Removes the snapshots of all files that exceed the retention policy.
+    /// Returns the number of removed snapshots.
+    pub async fn purge(&self, policy: &RetentionPolicy) -> Result<usize> {
+        if !ForgeFS::exists(&self.snapshots_directory) {
+            return Ok(0);
+        }
+
+        let mut snapshots = Vec::new();
+        let mut groups = ForgeFS::read_dir(&self.snapshots_directory).await?;
+        while let Some(group) = groups.next_entry().await? {
+            if !group.file_type().await?.is_dir() {
+                continue;
+            }
+
+            let mut entries = ForgeFS::read_dir(group.path()).await?;
+            while let Some(entry) = entries.next_entry().await? {
+                if !entry.file_name().to_string_lossy().ends_with(".snap") {
+                    continue;
+                }
+
+                // Snapshots are never modified, so their modification time is
+                // the time they were taken at
+                let metadata = entry.metadata().await?;
+                snapshots.push(StoredSnapshot {
+                    path: entry.path(),
+                    group: group.path(),
+                    created_at: metadata.modified()?,
+                    size: metadata.len(),
+                });
+            }
+        }
+
+        let evicted = policy.evict(snapshots, SystemTime::now());
+        for path in evicted.iter() {
+            remove_snapshot(path).await?;
+        }
+
+        Ok(evicted.len())
+    }
 }
 
 #[cfg(test)]
@@ -229,6 +340,154 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_restore_snapshot() -> Result<()> {
+        // Arrange
+        let ctx = TestContext::new().await?;
+
+        // Act
+        ctx.write_content("Initial content").await?;
+        let first = ctx.create_snapshot().await?;
+
+        ctx.write_content("Second content").await?;
+        ctx.create_snapshot().await?;
+
+        ctx.write_content("Final content").await?;
+        ctx.service
+            .restore_snapshot(ctx.test_file.clone(), &first.id)
+            .await?;
+
+        // Assert
+        assert_eq!(ctx.read_content().await?, "Initial content");
+        assert!(ctx
+            .service
+            .list_snapshots(ctx.test_file.clone())
+            .await?
+            .is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_purge_keeps_newest_snapshots() -> Result<()> {
+        // Arrange
+        let ctx = TestContext::new().await?;
+        let policy = RetentionPolicy { max_per_file: Some(1), ..Default::default() };
+
+        // Act
+        ctx.write_content("Initial content").await?;
+        ctx.create_snapshot().await?;
+        ctx.write_content("Second content").await?;
+        ctx.create_snapshot().await?;
+        ctx.write_content("Final content").await?;
+        let removed = ctx.service.purge(&policy).await?;
+        ctx.undo_snapshot().await?;
+
+        // Assert
+        assert_eq!(removed, 1);
+        assert_eq!(ctx.read_content().await?, "Second content");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_snapshots() -> Result<()> {
+        // Arrange
+        let ctx = TestContext::new().await?;
+
+        // Act
+        ctx.write_content("Initial content").await?;
+        let first = ctx.create_snapshot().await?;
+        ctx.write_content("Second content").await?;
+        let second = ctx.create_snapshot().await?;
+
+        // Assert
+        let actual = ctx
+            .service
+            .list_snapshots(ctx.test_file.clone())
+            .await?
+            .into_iter()
+            .map(|snapshot| snapshot.id)
+            .collect::<Vec<_>>();
+        assert_eq!(actual, vec![first.id, second.id]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_apply_snapshot_keeps_it() -> Result<()> {
+        // Arrange
+        let ctx = TestContext::new().await?;
+        ctx.write_content("Checkpoint content").await?;
+        let snapshot = ctx.create_snapshot().await?;
+
+        // Act
+        ctx.write_content("Later content").await?;
+        ctx.service
+            .apply_snapshot(ctx.test_file.clone(), &snapshot.id)
+            .await?;
+        let first = ctx.read_content().await?;
+        ctx.write_content("Even later content").await?;
+        ctx.service
+            .apply_snapshot(ctx.test_file.clone(), &snapshot.id)
+            .await?;
+
+        // Assert
+        assert_eq!(first, "Checkpoint content");
+        assert_eq!(ctx.read_content().await?, "Checkpoint content");
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_undo_snapshot_restores_permissions() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        // Arrange
+        let ctx = TestContext::new().await?;
+        ctx.write_content("#!/bin/sh").await?;
+        let executable = std::fs::Permissions::from_mode(0o755);
+        tokio::fs::set_permissions(&ctx.test_file, executable).await?;
+
+        // Act
+        ctx.create_snapshot().await?;
+        ForgeFS::remove_file(&ctx.test_file).await?;
+        ctx.undo_snapshot().await?;
+
+        // Assert
+        let actual = tokio::fs::metadata(&ctx.test_file)
+            .await?
+            .permissions()
+            .mode()
+            & 0o777;
+        assert_eq!(actual, 0o755);
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_undo_snapshot_recreates_symlink() -> Result<()> {
+        // Arrange
+        let ctx = TestContext::new().await?;
+        let link = ctx.test_file.with_file_name("link.txt");
+        ctx.write_content("Initial content").await?;
+        tokio::fs::symlink(&ctx.test_file, &link).await?;
+
+        // Act
+        ctx.service.create_snapshot(link.clone()).await?;
+        ForgeFS::remove_file(&link).await?;
+        ctx.write_content("Modified content").await?;
+        ctx.service.undo_snapshot(link.clone()).await?;
+
+        // Assert
+        assert_eq!(tokio::fs::read_link(&link).await?, ctx.test_file);
+        assert_eq!(ctx.read_content().await?, "Initial content");
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_multiple_snapshots_undo_twice() -> Result<()> {
         // Arrange
@@ -1,12 +1,14 @@
 use std::fmt::{Display, Formatter};
 use std::hash::Hasher;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use forge_fs::ForgeFS;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::metadata::FileMetadata;
+
 /// A newtype for snapshot IDs, internally using UUID
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct SnapshotId(Uuid);
@@ -46,6 +48,9 @@ impl From<Uuid> for SnapshotId {
     }
 }
 
+/// Format of the creation time in the snapshot filenames
+const TIME_FORMAT: &str = "%Y-%m-%d_%H-%M-%S-%9f";
+
 /// Represents information about a file snapshot
 ///
 /// Contains details about when the snapshot was created,
@@ -64,7 +69,13 @@ pub struct Snapshot {
 
 impl Snapshot {
     pub fn create(path: PathBuf) -> anyhow::Result<Self> {
-        let path = match path.canonicalize() {
+        // Only the parent is resolved so that a symlink is snapshotted as itself
+        // rather than as its target
+        let resolved = match (path.parent(), path.file_name()) {
+            (Some(parent), Some(name)) => parent.canonicalize().map(|parent| parent.join(name)),
+            _ => path.canonicalize(),
+        };
+        let path = match resolved {
             Ok(p) => p,
             Err(_) => {
                 if path.is_absolute() {
@@ -83,6 +94,17 @@ impl Snapshot {
         })
     }
 
+    /// Recovers the snapshot of `path` stored under `file_name`. Returns `None`
+    /// if the name wasn't produced by [`Snapshot::snapshot_path`].
+    pub fn from_file_name(path: String, file_name: &str) -> Option<Self> {
+        let (time, id) = file_name.strip_suffix(".snap")?.rsplit_once('_')?;
+        let time = chrono::NaiveDateTime::parse_from_str(time, TIME_FORMAT).ok()?;
+        let nanos = time.and_utc().timestamp_nanos_opt()?;
+        let timestamp = Duration::from_nanos(nanos.try_into().ok()?);
+
+        Some(Self { id: SnapshotId::parse(id)?, timestamp, path })
+    }
+
     /// Create a hash of a file path for storage
     pub fn path_hash(&self) -> String {
         let mut hasher = fnv_rs::Fnv64::default();
@@ -96,10 +118,10 @@ impl Snapshot {
         let datetime = UNIX_EPOCH + self.timestamp;
         // Format: YYYY-MM-DD_HH-MM-SS-nnnnnnnnn (including nanoseconds)
         let formatted_time = chrono::DateTime::<chrono::Utc>::from(datetime)
-            .format("%Y-%m-%d_%H-%M-%S-%9f")
+            .format(TIME_FORMAT)
             .to_string();
 
-        let filename = format!("{formatted_time}.snap");
+        let filename = format!("{formatted_time}_{}.snap", self.id);
         let path = PathBuf::from(self.path_hash()).join(PathBuf::from(filename));
         if let Some(cwd) = cwd {
             cwd.join(path)
@@ -111,7 +133,11 @@ impl Snapshot {
     pub async fn save(&self, path: Option<PathBuf>) -> anyhow::Result<()> {
         let content = ForgeFS::read(&self.path).await?;
         let path = self.snapshot_path(path);
-        ForgeFS::write(path, content).await?;
+        ForgeFS::write(&path, content).await?;
+        FileMetadata::capture(Path::new(&self.path))
+            .await
+            .save(&path)
+            .await?;
         Ok(())
     }
 }
@@ -140,6 +166,28 @@ mod tests {
         assert!(snapshot.is_err());
     }
 
+    #[test]
+    fn test_from_file_name() {
+        let fixture = Snapshot::create(PathBuf::from("/project/src/main.rs")).unwrap();
+        let file_name = fixture.snapshot_path(None);
+        let file_name = file_name.file_name().unwrap().to_string_lossy();
+
+        let actual = Snapshot::from_file_name(fixture.path.clone(), &file_name).unwrap();
+
+        assert_eq!(actual.id, fixture.id);
+        assert_eq!(actual.timestamp, fixture.timestamp);
+        assert_eq!(actual.path, fixture.path);
+    }
+
+    #[test]
+    fn test_from_file_name_without_id() {
+        let actual = Snapshot::from_file_name(
+            "/project/src/main.rs".to_string(),
+            "2025-01-01_12-00-00-000000000.snap",
+        );
+        assert!(actual.is_none());
+    }
+
     #[cfg(windows)]
     #[test]
     fn test_create_with_nonexistent_absolute_windows_path() {
@@ -22,6 +22,11 @@ impl SpinnerManager {
 
     /// Start the spinner with a message
     pub fn start(&mut self, message: Option<&str>) -> Result<()> {
+        self.start_at(message, Instant::now())
+    }
+
+    /// Start the spinner with a message and the time counted from `start_time`
+    fn start_at(&mut self, message: Option<&str>, start_time: Instant) -> Result<()> {
         self.stop(None)?;
 
         let words = [
@@ -45,7 +50,7 @@ impl SpinnerManager {
         self.message = Some(word.to_string());
 
         // Initialize the start time for the timer
-        self.start_time = Some(Instant::now());
+        self.start_time = Some(start_time);
 
         // Create the spinner with a better style that respects terminal width
         let pb = ProgressBar::new_spinner();
@@ -65,8 +70,9 @@ impl SpinnerManager {
 
         // Set the initial message
         let message = format!(
-            "{} 0s · {}",
+            "{} {}s · {}",
             word.green().bold(),
+            start_time.elapsed().as_secs(),
             "Ctrl+C to interrupt".white().dimmed()
         );
         pb.set_message(message);
@@ -132,12 +138,15 @@ impl SpinnerManager {
         Ok(())
     }
 
+    /// Prints a line above the spinner, which keeps counting from the same
+    /// start time
     pub fn write_ln(&mut self, message: impl ToString) -> Result<()> {
         let is_running = self.spinner.is_some();
         let prev_message = self.message.clone();
+        let start_time = self.start_time;
         self.stop(Some(message.to_string()))?;
-        if is_running {
-            self.start(prev_message.as_deref())?
+        if let (true, Some(start_time)) = (is_running, start_time) {
+            self.start_at(prev_message.as_deref(), start_time)?
         }
 
         Ok(())
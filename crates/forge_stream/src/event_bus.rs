@@ -0,0 +1,131 @@
+use std::sync::Arc;
+
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::broadcast::{self, Sender};
+
+const DEFAULT_CAPACITY: usize = 1024;
+
+type Filter<T> = Arc<dyn Fn(&T) -> bool + Send + Sync>;
+
+/// A broadcast channel that fans out every published event to all of its
+/// subscribers, allowing multiple consumers to observe the same events
+/// concurrently.
+pub struct EventBus<T> {
+    sender: Sender<T>,
+}
+
+impl<T: Clone> EventBus<T> {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    /// Publishes an event to all current subscribers and returns the number
+    /// of subscribers that will receive it.
+    pub fn publish(&self, event: T) -> usize {
+        self.sender.send(event).unwrap_or_default()
+    }
+
+    /// Creates a subscription that receives every event published after this
+    /// call.
+    pub fn subscribe(&self) -> Subscription<T> {
+        Subscription { receiver: self.sender.subscribe(), filter: None }
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+}
+
+impl<T: Clone> Default for EventBus<T> {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl<T> Clone for EventBus<T> {
+    fn clone(&self) -> Self {
+        Self { sender: self.sender.clone() }
+    }
+}
+
+/// A receiver for events published on an [`EventBus`], optionally narrowed
+/// down to the events a consumer is interested in.
+pub struct Subscription<T> {
+    receiver: broadcast::Receiver<T>,
+    filter: Option<Filter<T>>,
+}
+
+impl<T: Clone> Subscription<T> {
+    /// Only yields events for which the predicate returns true.
+    pub fn filter(mut self, predicate: impl Fn(&T) -> bool + Send + Sync + 'static) -> Self {
+        self.filter = Some(Arc::new(predicate));
+        self
+    }
+
+    /// Waits for the next matching event. Returns `None` once the bus has been
+    /// dropped. Slow subscribers skip the events they missed instead of
+    /// blocking publishers.
+    pub async fn recv(&mut self) -> Option<T> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(event) => {
+                    if self.filter.as_ref().is_none_or(|filter| filter(&event)) {
+                        return Some(event);
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "Event subscriber lagged behind");
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_all_subscribers_receive_events() {
+        let bus = EventBus::default();
+        let mut first = bus.subscribe();
+        let mut second = bus.subscribe();
+
+        let actual = bus.publish(1);
+
+        assert_eq!(actual, 2);
+        assert_eq!(first.recv().await, Some(1));
+        assert_eq!(second.recv().await, Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_filtered_subscription() {
+        let bus = EventBus::default();
+        let mut even = bus.subscribe().filter(|value: &i32| value % 2 == 0);
+
+        bus.publish(1);
+        bus.publish(2);
+        bus.publish(3);
+        bus.publish(4);
+        drop(bus);
+
+        let mut actual = vec![];
+        while let Some(value) = even.recv().await {
+            actual.push(value);
+        }
+
+        let expected = vec![2, 4];
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn test_publish_without_subscribers() {
+        let bus = EventBus::<i32>::default();
+        let actual = bus.publish(1);
+        assert_eq!(actual, 0);
+    }
+}
@@ -1,3 +1,5 @@
+mod event_bus;
 mod mpsc_stream;
 
+pub use event_bus::*;
 pub use mpsc_stream::*;
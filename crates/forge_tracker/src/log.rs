@@ -1,59 +1,193 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
 
+use forge_domain::{LogFormat, LogRotation, Logging};
+use serde::de::DeserializeOwned;
 use tracing::debug;
-use tracing_appender::non_blocking::{self, WorkerGuard};
-use tracing_subscriber::{self};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::{Layered, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{reload, EnvFilter, Layer, Registry};
 
 use crate::can_track::can_track;
 use crate::Tracker;
 
+/// Filter used by the debug mode to capture everything forge does.
+const DEBUG_FILTER: &str = "forge=trace";
+
+type FilteredRegistry = Layered<reload::Layer<EnvFilter, Registry>, Registry>;
+type OutputLayer = Box<dyn Layer<FilteredRegistry> + Send + Sync>;
+
 pub fn init_tracing(log_path: PathBuf, tracker: Tracker) -> anyhow::Result<Guard> {
-    debug!(path = %log_path.display(), "Initializing logging system in JSON format");
+    debug!(path = %log_path.display(), "Initializing logging system");
+
+    let state = LogState {
+        logging: with_env_overrides(Logging::default()),
+        debug: false,
+    };
+    let (output, worker, filter) = prepare_output(&log_path, &tracker, &state)?;
+    let (filter, filter_handle) = reload::Layer::new(filter);
+    let (output, output_handle) = reload::Layer::new(output);
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(output)
+        .try_init()?;
+
+    debug!("Logging system initialized successfully");
+    Ok(Guard {
+        log_path,
+        tracker,
+        filter: filter_handle,
+        output: output_handle,
+        worker: Mutex::new(worker),
+        state: Mutex::new(state),
+    })
+}
+
+/// Logging settings currently in effect.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct LogState {
+    logging: Logging,
+    debug: bool,
+}
+
+impl LogState {
+    /// Whether records are sent to PostHog rather than written to disk. The
+    /// debug mode always writes to disk so that the records can be inspected.
+    fn tracking(&self) -> bool {
+        can_track() && !self.debug
+    }
+
+    fn level(&self) -> &str {
+        if self.debug {
+            DEBUG_FILTER
+        } else if let Some(level) = self.logging.level.as_deref() {
+            level
+        } else if self.tracking() {
+            "forge=info"
+        } else {
+            "forge=debug"
+        }
+    }
+}
+
+/// Applies the `FORGE_LOG*` environment variables on top of the configured
+/// logging. Values from the environment take precedence.
+fn with_env_overrides(mut logging: Logging) -> Logging {
+    if let Ok(level) = std::env::var("FORGE_LOG") {
+        logging.level = Some(level);
+    }
+    if let Some(format) = env_value("FORGE_LOG_FORMAT") {
+        logging.format = Some(format);
+    }
+    if let Some(rotation) = env_value("FORGE_LOG_ROTATION") {
+        logging.rotation = Some(rotation);
+    }
+    if let Some(max_files) = std::env::var("FORGE_LOG_MAX_FILES")
+        .ok()
+        .and_then(|value| value.parse().ok())
+    {
+        logging.max_files = Some(max_files);
+    }
+    logging
+}
+
+fn env_value<T: DeserializeOwned>(name: &str) -> Option<T> {
+    let value = std::env::var(name).ok()?;
+    serde_json::from_value(serde_json::Value::String(value.to_lowercase())).ok()
+}
+
+fn prepare_output(
+    log_path: &Path,
+    tracker: &Tracker,
+    state: &LogState,
+) -> anyhow::Result<(OutputLayer, WorkerGuard, EnvFilter)> {
+    let filter = EnvFilter::try_new(state.level())?;
 
     // If tracking is enabled, use PostHog for logging; otherwise, use a rolling
     // file appender.
-    let (writer, guard, level) = prepare_writer(log_path, tracker);
+    let (writer, guard) = if state.tracking() {
+        tracing_appender::non_blocking(PostHogWriter::new(tracker.clone()))
+    } else {
+        tracing_appender::non_blocking(file_appender(log_path, &state.logging)?)
+    };
 
-    tracing_subscriber::fmt()
-        .json()
-        .with_env_filter(tracing_subscriber::EnvFilter::try_from_env("FORGE_LOG").unwrap_or(level))
+    let layer = tracing_subscriber::fmt::layer()
         .with_timer(tracing_subscriber::fmt::time::uptime())
         .with_thread_ids(false)
         .with_target(false)
         .with_file(true)
         .with_line_number(true)
-        .with_writer(writer)
-        .init();
+        .with_writer(writer);
+    let layer = match state.logging.format.unwrap_or_default() {
+        LogFormat::Json => layer.json().boxed(),
+        LogFormat::Pretty => layer.pretty().with_ansi(false).boxed(),
+    };
 
-    debug!("JSON logging system initialized successfully");
-    Ok(Guard(guard))
+    Ok((layer, guard, filter))
 }
 
-fn prepare_writer(
+fn file_appender(log_path: &Path, logging: &Logging) -> anyhow::Result<RollingFileAppender> {
+    let rotation = match logging.rotation.unwrap_or_default() {
+        LogRotation::Hourly => Rotation::HOURLY,
+        LogRotation::Daily => Rotation::DAILY,
+        LogRotation::Never => Rotation::NEVER,
+    };
+    let mut builder = RollingFileAppender::builder()
+        .rotation(rotation)
+        .filename_prefix("forge.log");
+    if let Some(max_files) = logging.max_files {
+        builder = builder.max_log_files(max_files);
+    }
+    Ok(builder.build(log_path)?)
+}
+
+/// Keeps the logging system alive and allows reconfiguring it at runtime.
+pub struct Guard {
     log_path: PathBuf,
     tracker: Tracker,
-) -> (
-    non_blocking::NonBlocking,
-    WorkerGuard,
-    tracing_subscriber::EnvFilter,
-) {
-    let ((non_blocking, guard), env) = if can_track() {
-        let append = PostHogWriter::new(tracker);
-        (
-            tracing_appender::non_blocking(append),
-            tracing_subscriber::EnvFilter::new("forge=info"),
-        )
-    } else {
-        let append = tracing_appender::rolling::daily(log_path, "forge.log");
-        (
-            tracing_appender::non_blocking(append),
-            tracing_subscriber::EnvFilter::new("forge=debug"),
-        )
-    };
-    (non_blocking, guard, env)
+    filter: reload::Handle<EnvFilter, Registry>,
+    output: reload::Handle<OutputLayer, FilteredRegistry>,
+    worker: Mutex<WorkerGuard>,
+    state: Mutex<LogState>,
 }
 
-pub struct Guard(#[allow(dead_code)] WorkerGuard);
+impl Guard {
+    /// Applies the logging configuration of the workflow. The `FORGE_LOG*`
+    /// environment variables take precedence over it.
+    pub fn configure(&self, logging: &Logging) -> anyhow::Result<()> {
+        let logging = with_env_overrides(logging.clone());
+        self.update(|state| state.logging = logging)
+    }
+
+    /// Raises the verbosity to trace for the rest of the session and writes the
+    /// records to disk. Returns the directory containing the logs.
+    pub fn debug(&self) -> anyhow::Result<&Path> {
+        self.update(|state| state.debug = true)?;
+        Ok(&self.log_path)
+    }
+
+    fn update(&self, f: impl FnOnce(&mut LogState)) -> anyhow::Result<()> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut updated = state.clone();
+        f(&mut updated);
+        if updated == *state {
+            return Ok(());
+        }
+
+        let (output, worker, filter) = prepare_output(&self.log_path, &self.tracker, &updated)?;
+        self.output.reload(output)?;
+        self.filter.reload(filter)?;
+        // Dropping the previous worker flushes the records it still buffers
+        *self.worker.lock().unwrap_or_else(PoisonError::into_inner) = worker;
+        *state = updated;
+
+        debug!(level = state.level(), "Logging system reconfigured");
+        Ok(())
+    }
+}
 
 struct PostHogWriter {
     tracker: Tracker,
@@ -84,3 +218,43 @@ impl std::io::Write for PostHogWriter {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_level_from_logging() {
+        let fixture = LogState {
+            logging: Logging {
+                level: Some("forge_provider=trace".to_string()),
+                ..Default::default()
+            },
+            debug: false,
+        };
+        let actual = fixture.level();
+        let expected = "forge_provider=trace";
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_debug_overrides_level() {
+        let fixture = LogState {
+            logging: Logging { level: Some("forge=warn".to_string()), ..Default::default() },
+            debug: true,
+        };
+        let actual = (fixture.level(), fixture.tracking());
+        let expected = (DEBUG_FILTER, false);
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_env_value_is_case_insensitive() {
+        std::env::set_var("FORGE_TEST_LOG_FORMAT", "Pretty");
+        let actual: Option<LogFormat> = env_value("FORGE_TEST_LOG_FORMAT");
+        let expected = Some(LogFormat::Pretty);
+        assert_eq!(actual, expected);
+    }
+}
@@ -1,5 +1,5 @@
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
 use derive_setters::Setters;
@@ -49,6 +49,10 @@ const DEFAULT_MAX_TOTAL_SIZE: u64 = 10 * 1024 * 1024; // 10MB
 const DEFAULT_MAX_DEPTH: usize = 5;
 const DEFAULT_MAX_BREADTH: usize = 10;
 
+/// Directories holding the scratch directories of the conversations and the
+/// workspace journal, which are never part of the project
+const GENERATED_DIRS: [&str; 2] = [".forge/tmp", ".forge/journal"];
+
 impl Walker {
     /// Creates a new Walker instance with all settings set to conservative
     /// values.
@@ -88,6 +92,10 @@ impl Walker {
             .context("Failed to spawn blocking task")?
     }
 
+    fn is_generated_dir(path: &Path) -> bool {
+        GENERATED_DIRS.iter().any(|dir| path.ends_with(dir))
+    }
+
     fn is_likely_binary(path: &std::path::Path) -> bool {
         if let Some(extension) = path.extension() {
             let ext = extension.to_string_lossy().to_lowercase();
@@ -104,6 +112,26 @@ impl Walker {
         }
     }
 
+    /// Counts the entries under the base directory, ignored files excluded,
+    /// stopping at `limit`. It reads no metadata, so it's cheap enough to run
+    /// before deciding how to walk a directory.
+    pub async fn count(&self, limit: usize) -> Result<usize> {
+        let walker = self.clone();
+        spawn_blocking(move || walker.count_blocking(limit))
+            .await
+            .context("Failed to spawn blocking task")
+    }
+
+    fn count_blocking(&self, limit: usize) -> usize {
+        WalkBuilder::new(&self.cwd)
+            .standard_filters(true)
+            .filter_entry(|entry| !Self::is_generated_dir(entry.path()))
+            .build()
+            .flatten()
+            .take(limit)
+            .count()
+    }
+
     /// Blocking function to scan filesystem. Use this when you already have
     /// a runtime or want to avoid spawning a new one.
     pub fn get_blocking(&self) -> Result<Vec<File>> {
@@ -116,6 +144,7 @@ impl Walker {
         let walk = WalkBuilder::new(&self.cwd)
             .standard_filters(true) // use standard ignore filters.
             .max_depth(Some(self.max_depth))
+            .filter_entry(|entry| !Self::is_generated_dir(entry.path()))
             // TODO: use build_parallel() for better performance
             .build();
 
@@ -405,6 +434,32 @@ mod tests {
         assert!(dir.path.ends_with('/'));
     }
 
+    #[tokio::test]
+    async fn test_walker_skips_scratch_dir() {
+        let fixture = fixtures::Fixture::default();
+        fixture.add_file(".forge/state.json", "{}").unwrap();
+        fixture
+            .add_file(".forge/tmp/abc/stdout.txt", "output")
+            .unwrap();
+        fixture
+            .add_file(".forge/journal/events.jsonl", "{}")
+            .unwrap();
+
+        let actual = Walker::max_all()
+            .cwd(fixture.as_path().join(".forge"))
+            .get()
+            .await
+            .unwrap();
+
+        let expected = vec!["state.json"];
+        let actual: Vec<_> = actual
+            .iter()
+            .filter(|f| !f.is_dir())
+            .map(|f| f.path.as_str())
+            .collect();
+        assert_eq!(actual, expected);
+    }
+
     #[tokio::test]
     async fn test_walker_respects_ignore_file() {
         let fixture = fixtures::Fixture::default();
@@ -455,4 +510,19 @@ mod tests {
             "Walker should exclude files listed in .ignore file"
         );
     }
+
+    #[tokio::test]
+    async fn test_walker_count_stops_at_limit() {
+        let (fixture, _) = fixtures::create_file_collection(10, "file").unwrap();
+        let walker = Walker::max_all().cwd(fixture.path().to_path_buf());
+
+        let actual = (
+            walker.count(5).await.unwrap(),
+            walker.count(100).await.unwrap(),
+        );
+
+        // The base directory and the directory of the files are counted too
+        let expected = (5, 12);
+        assert_eq!(actual, expected);
+    }
 }